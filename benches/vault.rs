@@ -0,0 +1,12 @@
+//! `cargo bench`: the `wardwell bench` suite against a generated vault, printed as JSON.
+//! `WARDWELL_BENCH_FILES` sets the vault size (default 10,000 files).
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let files = std::env::var("WARDWELL_BENCH_FILES").ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(wardwell::install::bench::DEFAULT_FILES);
+    let report = wardwell::install::bench::run_suite(files)?;
+    let regressions = wardwell::install::bench::check(&report, None, 0.0);
+    println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "report": report, "over_budget": regressions }))?);
+    Ok(())
+}
@@ -1,3 +1,5 @@
+pub mod portable;
 pub mod resolver;
 
+pub use portable::*;
 pub use resolver::*;
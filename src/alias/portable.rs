@@ -0,0 +1,71 @@
+use crate::alias::resolver::{expand_home, glob_base};
+use crate::domain::model::Domain;
+use std::path::Path;
+
+/// A reference to `path` that means the same thing on every machine the vault syncs to,
+/// in the syntax `AliasResolver` reads back. Tried in order: a vault-relative path; the
+/// longest domain alias containing it, as `{alias:name}/rest`; a domain's root, as
+/// `{domain:name}/rest`; the home directory, as `~/rest`. Anything else, and any path
+/// that is already relative, is returned unchanged.
+pub fn portable_path(path: &str, vault_root: &Path, domains: &[Domain]) -> String {
+    let full = Path::new(path);
+    if !full.is_absolute() {
+        return path.to_string();
+    }
+    if let Ok(rel) = full.strip_prefix(vault_root) {
+        return rel.to_string_lossy().to_string();
+    }
+
+    let aliases = domains.iter()
+        .flat_map(|d| d.aliases.iter().map(|(name, target)| (format!("{{alias:{name}}}"), expand_home(target))));
+    let roots = domains.iter()
+        .flat_map(|d| d.paths.iter().map(move |g| (format!("{{domain:{}}}", d.name.as_str()), expand_home(&glob_base(g.as_str())))));
+    let best = |candidates: Vec<(String, std::path::PathBuf)>| {
+        candidates.into_iter()
+            .filter(|(_, base)| base.is_absolute() && full.starts_with(base))
+            .max_by_key(|(_, base)| base.components().count())
+    };
+    let home = std::env::var("HOME").ok().map(|h| ("~".to_string(), std::path::PathBuf::from(h)));
+
+    let found = best(aliases.collect())
+        .or_else(|| best(roots.collect()))
+        .or_else(|| best(home.into_iter().collect()));
+    match found {
+        Some((reference, base)) => match full.strip_prefix(&base) {
+            Ok(rest) if !rest.as_os_str().is_empty() => format!("{reference}/{}", rest.to_string_lossy()),
+            _ => reference,
+        },
+        None => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::config::types::{DomainName, PathGlob};
+
+    #[test]
+    fn absolute_paths_become_stable_references() {
+        let domains = vec![Domain {
+            name: DomainName::new("work").unwrap(),
+            paths: vec![PathGlob::new("/srv/work/*").unwrap()],
+            aliases: [("api".to_string(), "/srv/work/api".to_string())].into_iter().collect(),
+            can_read: Vec::new(),
+            seed_template: None,
+        }];
+        let vault = Path::new("/vault");
+        let portable = |p: &str| portable_path(p, vault, &domains);
+
+        assert_eq!(portable("/vault/work/api/INDEX.md"), "work/api/INDEX.md");
+        assert_eq!(portable("/srv/work/api/src/main.rs"), "{alias:api}/src/main.rs");
+        assert_eq!(portable("/srv/work/api"), "{alias:api}");
+        assert_eq!(portable("/srv/work/web"), "{domain:work}/web");
+        assert_eq!(portable("/opt/elsewhere"), "/opt/elsewhere");
+        assert_eq!(portable("work/api/INDEX.md"), "work/api/INDEX.md");
+
+        let resolver = crate::alias::AliasResolver::new(&domains[0].aliases, "work", &["/srv/work/*".to_string()]);
+        assert_eq!(resolver.resolve("{alias:api}/src/main.rs").unwrap(), Path::new("/srv/work/api/src/main.rs"));
+        assert_eq!(resolver.resolve("{domain:work}/web").unwrap(), Path::new("/srv/work/web"));
+    }
+}
@@ -154,7 +154,7 @@ impl AliasResolver {
 }
 
 /// Expand `~` prefix to home directory.
-fn expand_home(path: &str) -> PathBuf {
+pub(crate) fn expand_home(path: &str) -> PathBuf {
     if let Some(rest) = path.strip_prefix("~/")
         && let Ok(home) = std::env::var("HOME")
     {
@@ -164,7 +164,7 @@ fn expand_home(path: &str) -> PathBuf {
 }
 
 /// Extract the base directory from a glob pattern (everything before the first `*`).
-fn glob_base(glob: &str) -> String {
+pub(crate) fn glob_base(glob: &str) -> String {
     let expanded = if let Some(rest) = glob.strip_prefix("~/") {
         if let Ok(home) = std::env::var("HOME") {
             format!("{home}/{rest}")
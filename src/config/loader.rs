@@ -2,7 +2,7 @@ use crate::config::types::{ConfigError, DomainName, PathGlob};
 use crate::domain::model::Domain;
 use crate::domain::registry::DomainRegistry;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 /// Top-level wardwell configuration.
@@ -10,7 +10,10 @@ use std::path::{Path, PathBuf};
 pub struct WardwellConfig {
     pub vault_path: PathBuf,
     pub registry: DomainRegistry,
-    pub session_sources: Vec<PathBuf>,
+    pub session_sources: Vec<SessionSource>,
+    /// Working-directory globs whose sessions are never indexed, summarized, or read. Also
+    /// copied into every `SessionSource`.
+    pub session_exclude: Vec<PathGlob>,
     pub exclude: Vec<String>,
     pub ai: AiConfig,
     /// Whether the stop hook prompts for session logging. Defaults to true.
@@ -21,6 +24,388 @@ pub struct WardwellConfig {
     pub kanban_queries: HashMap<String, String>,
     /// Prefix mappings for kanban item display (prefix → label).
     pub kanban_prefixes: HashMap<String, String>,
+    /// Scheduled vault backup. None = backups disabled.
+    pub backup: Option<BackupConfig>,
+    /// How long session summaries and sessions.db rows are kept. None = forever.
+    pub retention: Option<RetentionConfig>,
+    /// Which MCP tools the server registers.
+    pub tools_enabled: ToolsEnabled,
+    /// When search/read should warn that the index lags behind the vault.
+    pub stale_index: StaleIndexConfig,
+    /// Seconds within which an identical history/lesson/list append is skipped. 0 disables dedup.
+    pub dedup_window_seconds: u64,
+    /// Minimum level of log notifications pushed to MCP clients. Clients may change it.
+    pub log_level: rmcp::model::LoggingLevel,
+    /// Vault-specific status names (lowercased) → the canonical status they count as.
+    pub statuses: BTreeMap<String, crate::vault::types::Status>,
+    /// Read tuning for vaults on network mounts.
+    pub remote_vault: RemoteVaultConfig,
+    /// When JSONL appends are flushed to disk.
+    pub durability: DurabilityConfig,
+    /// How new project folder names spell non-ASCII letters.
+    pub slug_transliteration: crate::vault::project::Transliteration,
+    /// Whether indexing, the watcher, and project listings enter symlinks in the vault.
+    pub symlinks: crate::vault::symlinks::SymlinkPolicy,
+    /// How orchestrate orders its queue.
+    pub ranking: RankingConfig,
+    /// Tool responses larger than this many bytes are cut down to fit. 0 = no limit.
+    pub max_response_bytes: usize,
+    /// Status check-ins on stale projects in inject output. None = no heartbeat.
+    pub heartbeat: Option<HeartbeatConfig>,
+    /// Scheduled digests in `<domain>/_digests/`. None = no digests.
+    pub digest: Option<DigestConfig>,
+    /// Section order for current_state.md on sync; INDEX.md `state_sections` overrides it per
+    /// project. Empty = the built-in order.
+    pub state_sections: Vec<String>,
+    /// Days either side of the date that `sessions_around` covers.
+    pub sessions_around_days: u32,
+    /// Days a desktop history entry counts as a pending intent for the Stop hook. 0 = no limit.
+    pub intent_ttl_days: u32,
+    /// Count tool calls, search hits, syncs, and call latencies in ~/.wardwell/metrics.db (local only).
+    pub metrics: bool,
+    /// Tool calls slower than this many milliseconds are logged with their (sanitized)
+    /// parameters. 0 = off.
+    pub slow_action_ms: u64,
+    /// Estimated tokens `wardwell inject` may print for a domain before it shortens the
+    /// project listing. 0 = no limit.
+    pub inject_max_tokens: usize,
+    /// What sync and decide do to a project not read or searched this session.
+    pub write_policy: WritePolicy,
+    /// Words that mark a history entry as blocked in `patterns` when it has no recorded
+    /// blockers, waiting-on items, or blocked status. Lowercased.
+    pub blocked_terms: Vec<String>,
+    /// Serve without wardwell_write and wardwell_clipboard, and refuse kanban changes.
+    /// `wardwell serve --read-only` turns it on for one run.
+    pub read_only: bool,
+    /// Who the HTTP transport answers.
+    pub http: HttpConfig,
+}
+
+/// A directory of Claude Code session transcripts (`~/.claude/projects/`), optionally limited
+/// to sessions from some domains or working directories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSource {
+    pub path: PathBuf,
+    /// Only sessions whose project resolves to one of these domains. Empty = any.
+    pub domains: Vec<String>,
+    /// Only sessions run in a directory under one of these globs. Empty = any.
+    pub paths: Vec<PathGlob>,
+    /// Never sessions run under one of these globs (`session_exclude`), whatever the filters above say.
+    pub exclude: Vec<PathGlob>,
+}
+
+impl SessionSource {
+    /// A source that admits every session.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), domains: Vec::new(), paths: Vec::new(), exclude: Vec::new() }
+    }
+
+    /// Whether a session run in `project_path`, in `domain` if it resolved to one, belongs to this source.
+    pub fn admits(&self, project_path: &str, domain: Option<&str>) -> bool {
+        if self.excludes(project_path) {
+            return false;
+        }
+        let path_ok = self.paths.is_empty() || self.paths.iter().any(|g| g.matches(Path::new(project_path)));
+        let domain_ok = self.domains.is_empty() || domain.is_some_and(|d| self.domains.iter().any(|s| s == d));
+        path_ok && domain_ok
+    }
+
+    /// Whether `project_path`, as decoded from its transcript folder name, is under an
+    /// `exclude` glob. Decoding turns every `-` into `/`, so each glob is also matched in the
+    /// folder's encoded form, which is exact. Folders whose names extend a glob's
+    /// (`nda-client-v2` for `~/Code/nda-client`) are excluded too; for a denylist that's the
+    /// safe side.
+    pub fn excludes(&self, project_path: &str) -> bool {
+        let folder = crate::daemon::indexer::encode_project_dir(project_path);
+        self.exclude.iter().any(|glob| {
+            let encoded: String = glob.expand().to_string_lossy().chars()
+                .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '*' | '?' | '[' | ']') { c } else { '-' })
+                .collect();
+            let encoded = encoded.trim_end_matches('-');
+            glob.matches(Path::new(project_path))
+                || [encoded.to_string(), format!("{encoded}-*")].iter()
+                    .any(|p| glob::Pattern::new(p).is_ok_and(|p| p.matches(&folder)))
+        })
+    }
+}
+
+/// Stale-index detection settings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaleIndexConfig {
+    /// Minutes a vault file may be newer than the last index event before warning. Defaults to 10.
+    #[serde(default = "default_stale_threshold")]
+    pub threshold_minutes: u64,
+    /// Rebuild the FTS index in place when staleness is detected. Defaults to false.
+    #[serde(default)]
+    pub self_heal: bool,
+}
+
+impl Default for StaleIndexConfig {
+    fn default() -> Self {
+        Self { threshold_minutes: default_stale_threshold(), self_heal: false }
+    }
+}
+
+/// When an appended history, lesson, or list line is fsynced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsyncPolicy {
+    /// After every append.
+    #[default]
+    Always,
+    /// At most once per `interval_seconds`, covering every append since the last sync.
+    Periodic,
+    /// Never; the OS writes the data back on its own schedule.
+    Never,
+}
+
+/// Durability of JSONL appends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct DurabilityConfig {
+    /// Defaults to `always`.
+    #[serde(default)]
+    pub fsync: FsyncPolicy,
+    /// Seconds between syncs with `fsync: periodic`. Defaults to 5.
+    #[serde(default = "default_fsync_interval")]
+    pub interval_seconds: u64,
+}
+
+impl Default for DurabilityConfig {
+    fn default() -> Self {
+        Self { fsync: FsyncPolicy::Always, interval_seconds: default_fsync_interval() }
+    }
+}
+
+fn default_fsync_interval() -> u64 {
+    5
+}
+
+/// What a sync or decide does to a project that wasn't read or searched this session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WritePolicy {
+    /// Write it, with a warning in the response.
+    #[default]
+    Warn,
+    /// Refuse it, naming the file to read first.
+    RequireRead,
+}
+
+/// Settings for a vault on a network mount (SSH, rclone, SMB).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteVaultConfig {
+    /// Take project state from the index instead of stat-ing and reading each file, and read
+    /// history files in parallel with a deadline. Defaults to false.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Milliseconds a batch of reads may take before the remaining files are skipped. Defaults to 5000.
+    #[serde(default = "default_read_timeout")]
+    pub read_timeout_ms: u64,
+    /// Files read at once. Defaults to 8.
+    #[serde(default = "default_read_concurrency")]
+    pub read_concurrency: usize,
+}
+
+impl Default for RemoteVaultConfig {
+    fn default() -> Self {
+        Self { enabled: false, read_timeout_ms: default_read_timeout(), read_concurrency: default_read_concurrency() }
+    }
+}
+
+impl RemoteVaultConfig {
+    /// How to read batches of vault files: sequential and unbounded unless enabled.
+    pub fn batch(&self) -> crate::vault::batch::BatchRead {
+        if !self.enabled {
+            return crate::vault::batch::BatchRead::default();
+        }
+        crate::vault::batch::BatchRead {
+            concurrency: self.read_concurrency.max(1),
+            timeout: Some(std::time::Duration::from_millis(self.read_timeout_ms)),
+        }
+    }
+}
+
+/// How orchestrate orders its queue. Without either setting, neglected projects come first.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RankingConfig {
+    /// Score each project as a weighted sum of its features, highest first.
+    #[serde(default)]
+    pub weights: Option<RankingWeights>,
+    /// Shell command given the queue as JSON on stdin that prints the order. Takes precedence
+    /// over `weights`, which apply if it fails. `{secret:NAME}` references are resolved per run.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Milliseconds the command may run. Defaults to 2000.
+    #[serde(default = "default_ranking_timeout")]
+    pub timeout_ms: u64,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self { weights: None, command: None, timeout_ms: default_ranking_timeout() }
+    }
+}
+
+/// Weight per orchestrate feature. Unset weights are 0; unknown names are a config error.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RankingWeights {
+    #[serde(default)]
+    pub neglected: f64,
+    #[serde(default)]
+    pub overdue_ratio: f64,
+    #[serde(default)]
+    pub days_since_update: f64,
+    #[serde(default)]
+    pub days_since_last: f64,
+    #[serde(default)]
+    pub priority: f64,
+    #[serde(default)]
+    pub due_urgency: f64,
+}
+
+/// How often inject asks about a domain's stalest active projects.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartbeatConfig {
+    /// `weekly` (default) or `daily`.
+    #[serde(default)]
+    pub cadence: HeartbeatCadence,
+    /// Day a weekly heartbeat falls on: the first session on or after it gets one. Defaults to Monday.
+    #[serde(default = "default_heartbeat_weekday")]
+    pub weekday: chrono::Weekday,
+    /// Projects to ask about. Defaults to 3.
+    #[serde(default = "default_heartbeat_projects")]
+    pub projects: usize,
+}
+
+/// When the daemon writes digests, and for which domains.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DigestConfig {
+    /// Cron-style `minute hour day month weekday`. Defaults to 07:00 every day.
+    #[serde(default)]
+    pub schedule: crate::daemon::digest::Schedule,
+    /// Domains to write digests for. Empty (the default) = every domain with projects.
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Per-domain schedule, sections, and folder. A domain listed here gets digests even
+    /// when `domains` leaves it out.
+    #[serde(default)]
+    pub subscriptions: BTreeMap<String, DigestSubscription>,
+}
+
+/// One domain's departures from the digest defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct DigestSubscription {
+    /// Replaces `digest.schedule` for this domain.
+    #[serde(default)]
+    pub schedule: Option<crate::daemon::digest::Schedule>,
+    /// Sections to write, e.g. `[up_next, blocked]`. Empty (the default) = all.
+    #[serde(default)]
+    pub sections: Vec<crate::daemon::digest::Section>,
+    /// Folder under the domain's `_digests` to write to, so it is never taken for a project.
+    /// Defaults to `_digests` itself.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl DigestConfig {
+    /// Whether `domain` gets digests at all.
+    pub fn covers(&self, domain: &str) -> bool {
+        self.domains.is_empty() || self.domains.iter().any(|d| d == domain) || self.subscriptions.contains_key(domain)
+    }
+
+    /// `domain`'s digest settings: its subscription over the defaults.
+    pub fn subscription(&self, domain: &str) -> crate::daemon::digest::Subscription {
+        let sub = self.subscriptions.get(domain).cloned().unwrap_or_default();
+        crate::daemon::digest::Subscription {
+            domain: domain.to_string(),
+            schedule: sub.schedule.unwrap_or_else(|| self.schedule.clone()),
+            sections: sub.sections,
+            dir: digest_dir(sub.path.as_deref()),
+        }
+    }
+}
+
+/// The folder a subscription's `path` writes to: under `_digests`, whether or not it
+/// already starts with it.
+fn digest_dir(path: Option<&str>) -> String {
+    use crate::daemon::digest::DIGEST_DIR;
+    let path = path.unwrap_or_default().trim_matches('/');
+    let rest = path.strip_prefix(DIGEST_DIR).filter(|r| r.is_empty() || r.starts_with('/')).map_or(path, |r| r.trim_start_matches('/'));
+    if rest.is_empty() { DIGEST_DIR.to_string() } else { format!("{DIGEST_DIR}/{rest}") }
+}
+
+/// Whether a subscription's `path` names a folder inside its domain: relative, with no `..`.
+fn valid_digest_path(path: &str) -> bool {
+    let path = Path::new(path.trim_matches('/'));
+    !path.as_os_str().is_empty() && path.components().all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeartbeatCadence {
+    Daily,
+    #[default]
+    Weekly,
+}
+
+/// Per-tool opt-out for the MCP server. Disabled tools are never registered.
+/// Kanban is controlled separately by `kanban.enabled`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolsEnabled {
+    #[serde(default = "default_true")]
+    pub search: bool,
+    #[serde(default = "default_true")]
+    pub write: bool,
+    #[serde(default = "default_true")]
+    pub clipboard: bool,
+}
+
+impl Default for ToolsEnabled {
+    fn default() -> Self {
+        Self { search: true, write: true, clipboard: true }
+    }
+}
+
+/// Vault backup configuration (e.g. a restic or borg invocation).
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    /// Shell command template. `{vault}` and `{config_dir}` are replaced with quoted paths;
+    /// `{secret:NAME}` references are resolved when the backup runs.
+    pub command: String,
+    /// Hours between scheduled backups. Defaults to 24.
+    pub interval_hours: u64,
+}
+
+/// Retention for what the summarizer keeps outside the vault.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RetentionConfig {
+    /// Summaries (and cached resume documents) last written more than this many days ago
+    /// expire. None = kept.
+    #[serde(default)]
+    pub summaries_days: Option<u32>,
+    /// Sessions whose last message is more than this many days old leave sessions.db.
+    /// None = kept.
+    #[serde(default)]
+    pub sessions_days: Option<u32>,
+    /// Move expired summaries to `summaries/archive/` instead of deleting them.
+    #[serde(default)]
+    pub archive: bool,
+}
+
+/// Access to `wardwell serve --transport http`. Loopback needs none of it; binding any
+/// other address needs a token.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HttpConfig {
+    /// Clients must send `Authorization: Bearer <token>`. Usually a `{secret:NAME}` reference.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// `Origin` values accepted besides loopback ones, e.g. `https://claude.ai`.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Host names accepted besides loopback ones and IP addresses, e.g. `studio.lan`.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
 }
 
 /// AI configuration for session summarization.
@@ -28,12 +413,81 @@ pub struct WardwellConfig {
 pub struct AiConfig {
     /// Model for summarization. Defaults to "haiku".
     pub summarize_model: String,
+    /// Sessions scoring below this signal are skipped rather than summarized. Defaults to 3,
+    /// which three short user messages reach on their own.
+    pub summarize_min_signal: f64,
+    /// Recent unsummarized sessions of the current project summarized when a session starts,
+    /// so `context` and `resume` hit the cache. Defaults to 2; 0 turns pre-warm off.
+    pub prewarm_sessions: usize,
+    /// Sessions the daemon summarizes per five-minute cycle, so a first run works through a
+    /// large backlog gradually. Defaults to 20; 0 = no limit.
+    pub max_summaries_per_cycle: usize,
+    /// Which end of the backlog the daemon starts from. Defaults to newest first.
+    pub summarize_order: SummarizeOrder,
+    /// Backend that writes summaries. Defaults to the claude CLI.
+    pub provider: AiProvider,
+    /// Endpoint for the HTTP providers. None uses the provider's usual default.
+    pub base_url: Option<String>,
+    /// Bearer token for `openai`. Usually a `{secret:NAME}` reference.
+    pub api_key: Option<String>,
+    /// Sessions summarized at once. Defaults to 1.
+    pub concurrency: usize,
+    /// Provider calls started per minute, across all workers; 0 = no limit. Defaults to the
+    /// provider's `default_requests_per_minute`.
+    pub requests_per_minute: u32,
+    /// Also summarize as soon as a session transcript changes and settles, not only on the
+    /// five-minute cycle. Defaults to false; new sessions are indexed right away either way.
+    pub summarize_on_change: bool,
+}
+
+/// Where summary prompts are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiProvider {
+    /// `claude -p`, using the Claude Code login.
+    #[default]
+    ClaudeCli,
+    /// Any OpenAI-compatible `/chat/completions` API.
+    Openai,
+    /// A local Ollama server.
+    Ollama,
+}
+
+impl AiProvider {
+    /// Call rate used unless `ai.requests_per_minute` is set: gentle on the shared Claude Code
+    /// login, higher for a metered API, unlimited for a local model.
+    pub fn default_requests_per_minute(self) -> u32 {
+        match self {
+            AiProvider::ClaudeCli => 30,
+            AiProvider::Openai => 60,
+            AiProvider::Ollama => 0,
+        }
+    }
+}
+
+/// Order the daemon summarizes pending sessions in, after those in domains with active projects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummarizeOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
 }
 
 impl Default for AiConfig {
     fn default() -> Self {
         Self {
             summarize_model: "haiku".to_string(),
+            summarize_min_signal: 3.0,
+            prewarm_sessions: 2,
+            max_summaries_per_cycle: 20,
+            summarize_order: SummarizeOrder::default(),
+            provider: AiProvider::default(),
+            base_url: None,
+            api_key: None,
+            concurrency: 1,
+            requests_per_minute: AiProvider::default().default_requests_per_minute(),
+            summarize_on_change: false,
         }
     }
 }
@@ -49,7 +503,9 @@ struct RawConfig {
     #[allow(dead_code)]
     sources: Vec<String>,
     #[serde(default)]
-    session_sources: Vec<String>,
+    session_sources: Vec<RawSessionSource>,
+    #[serde(default)]
+    session_exclude: Vec<String>,
     /// Ignored — kept for backwards compatibility with old configs.
     #[serde(default)]
     #[allow(dead_code)]
@@ -66,12 +522,119 @@ struct RawConfig {
     stop_hook: bool,
     #[serde(default)]
     kanban: Option<RawKanbanConfig>,
+    #[serde(default)]
+    backup: Option<RawBackupConfig>,
+    #[serde(default)]
+    retention: Option<RetentionConfig>,
+    #[serde(default)]
+    tools_enabled: ToolsEnabled,
+    #[serde(default)]
+    stale_index: StaleIndexConfig,
+    #[serde(default = "default_dedup_window")]
+    dedup_window_seconds: u64,
+    #[serde(default = "default_log_level")]
+    log_level: rmcp::model::LoggingLevel,
+    #[serde(default)]
+    statuses: BTreeMap<String, crate::vault::types::Status>,
+    #[serde(default)]
+    remote_vault: RemoteVaultConfig,
+    #[serde(default)]
+    durability: DurabilityConfig,
+    #[serde(default)]
+    slug_transliteration: crate::vault::project::Transliteration,
+    #[serde(default)]
+    symlinks: crate::vault::symlinks::SymlinkPolicy,
+    #[serde(default)]
+    ranking: RankingConfig,
+    #[serde(default = "default_max_response_bytes")]
+    max_response_bytes: usize,
+    #[serde(default)]
+    heartbeat: Option<HeartbeatConfig>,
+    #[serde(default)]
+    digest: Option<DigestConfig>,
+    #[serde(default)]
+    state_sections: Vec<String>,
+    #[serde(default = "default_sessions_around_days")]
+    sessions_around_days: u32,
+    #[serde(default = "default_intent_ttl_days")]
+    intent_ttl_days: u32,
+    #[serde(default)]
+    metrics: bool,
+    #[serde(default = "default_slow_action_ms")]
+    slow_action_ms: u64,
+    #[serde(default)]
+    inject_max_tokens: usize,
+    #[serde(default)]
+    write_policy: WritePolicy,
+    #[serde(default = "default_blocked_terms")]
+    blocked_terms: Vec<String>,
+    #[serde(default)]
+    read_only: bool,
+    #[serde(default)]
+    http: HttpConfig,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_backup_interval() -> u64 {
+    24
+}
+
+fn default_stale_threshold() -> u64 {
+    10
+}
+
+fn default_ranking_timeout() -> u64 {
+    2000
+}
+
+fn default_dedup_window() -> u64 {
+    300
+}
+
+/// About 25k tokens, the default MCP output limit in Claude Code.
+fn default_max_response_bytes() -> usize {
+    80_000
+}
+
+fn default_sessions_around_days() -> u32 {
+    3
+}
+
+fn default_intent_ttl_days() -> u32 {
+    14
+}
+
+fn default_slow_action_ms() -> u64 {
+    2000
+}
+
+pub fn default_blocked_terms() -> Vec<String> {
+    ["blocked", "waiting", "stuck", "blocker"].iter().map(|t| t.to_string()).collect()
+}
+
+fn default_heartbeat_weekday() -> chrono::Weekday {
+    chrono::Weekday::Mon
+}
+
+fn default_heartbeat_projects() -> usize {
+    3
+}
+
+fn default_read_timeout() -> u64 {
+    5000
+}
+
+fn default_read_concurrency() -> usize {
+    8
+}
+
+fn default_log_level() -> rmcp::model::LoggingLevel {
+    rmcp::model::LoggingLevel::Info
+}
+
 #[derive(Debug, Deserialize)]
 struct RawDomainEntry {
     paths: Vec<String>,
@@ -91,9 +654,40 @@ struct RawKanbanConfig {
     prefixes: HashMap<String, String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct RawBackupConfig {
+    command: String,
+    #[serde(default = "default_backup_interval")]
+    interval_hours: u64,
+}
+
+/// A `session_sources` entry: a bare path, or a path with `domains:` and `paths:` filters.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawSessionSource {
+    Path(String),
+    Scoped {
+        path: String,
+        #[serde(default)]
+        domains: Vec<String>,
+        #[serde(default)]
+        paths: Vec<String>,
+    },
+}
+
 #[derive(Debug, Deserialize)]
 struct RawAiConfig {
     summarize_model: Option<String>,
+    summarize_min_signal: Option<f64>,
+    prewarm_sessions: Option<usize>,
+    max_summaries_per_cycle: Option<usize>,
+    summarize_order: Option<SummarizeOrder>,
+    provider: Option<AiProvider>,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    concurrency: Option<usize>,
+    requests_per_minute: Option<u32>,
+    summarize_on_change: Option<bool>,
     /// Ignored — kept for backwards compatibility with old configs.
     #[serde(default)]
     #[allow(dead_code)]
@@ -126,33 +720,43 @@ pub fn load(path: Option<&Path>) -> Result<WardwellConfig, ConfigError> {
         vault_registry
     } else if !raw.domains.is_empty() {
         // Fall back to config domains (migration path)
-        let mut config_domains = Vec::new();
-        for (name, entry) in &raw.domains {
-            let domain_name = DomainName::new(name)?;
-            let mut paths = Vec::new();
-            for p in &entry.paths {
-                paths.push(PathGlob::new(p)?);
-            }
-            config_domains.push(Domain {
-                name: domain_name,
-                paths,
-                aliases: entry.aliases.clone(),
-                can_read: entry.can_read.clone(),
-            });
-        }
+        let config_domains = config_domains(&raw.domains)?;
         DomainRegistry::from_domains(config_domains)
     } else {
         DomainRegistry::empty()
     };
 
-    let session_sources = raw.session_sources.iter().map(|s| expand_tilde(s)).collect();
+    let session_exclude: Vec<PathGlob> = raw.session_exclude.iter().map(|p| PathGlob::new(p)).collect::<Result<_, _>>()?;
+    let mut session_sources = Vec::new();
+    for source in &raw.session_sources {
+        session_sources.push(match source {
+            RawSessionSource::Path(path) => SessionSource { exclude: session_exclude.clone(), ..SessionSource::new(expand_tilde(path)) },
+            RawSessionSource::Scoped { path, domains, paths } => SessionSource {
+                path: expand_tilde(path),
+                domains: domains.clone(),
+                paths: paths.iter().map(|p| PathGlob::new(p)).collect::<Result<_, _>>()?,
+                exclude: session_exclude.clone(),
+            },
+        });
+    }
     let exclude = raw.exclude;
 
     let ai = match raw.ai {
         Some(raw_ai) => {
             let defaults = AiConfig::default();
+            let provider = raw_ai.provider.unwrap_or(defaults.provider);
             AiConfig {
                 summarize_model: raw_ai.summarize_model.unwrap_or(defaults.summarize_model),
+                summarize_min_signal: raw_ai.summarize_min_signal.unwrap_or(defaults.summarize_min_signal),
+                prewarm_sessions: raw_ai.prewarm_sessions.unwrap_or(defaults.prewarm_sessions),
+                max_summaries_per_cycle: raw_ai.max_summaries_per_cycle.unwrap_or(defaults.max_summaries_per_cycle),
+                summarize_order: raw_ai.summarize_order.unwrap_or(defaults.summarize_order),
+                provider,
+                base_url: raw_ai.base_url.filter(|u| !u.trim().is_empty()),
+                api_key: raw_ai.api_key.filter(|k| !k.trim().is_empty()),
+                concurrency: raw_ai.concurrency.unwrap_or(defaults.concurrency).max(1),
+                requests_per_minute: raw_ai.requests_per_minute.unwrap_or_else(|| provider.default_requests_per_minute()),
+                summarize_on_change: raw_ai.summarize_on_change.unwrap_or(defaults.summarize_on_change),
             }
         }
         None => AiConfig::default(),
@@ -167,15 +771,87 @@ pub fn load(path: Option<&Path>) -> Result<WardwellConfig, ConfigError> {
         vault_path,
         registry,
         session_sources,
+        session_exclude,
         exclude,
         ai,
         stop_hook: raw.stop_hook,
         kanban_enabled,
         kanban_queries,
         kanban_prefixes,
+        backup: raw.backup.map(|b| BackupConfig {
+            command: b.command,
+            interval_hours: b.interval_hours.max(1),
+        }),
+        retention: raw.retention.filter(|r| r.summaries_days.is_some() || r.sessions_days.is_some()),
+        tools_enabled: raw.tools_enabled,
+        stale_index: raw.stale_index,
+        dedup_window_seconds: raw.dedup_window_seconds,
+        log_level: raw.log_level,
+        statuses: raw.statuses.into_iter().map(|(name, status)| (name.trim().to_lowercase(), status)).collect(),
+        remote_vault: raw.remote_vault,
+        durability: raw.durability,
+        slug_transliteration: raw.slug_transliteration,
+        symlinks: raw.symlinks,
+        ranking: raw.ranking,
+        max_response_bytes: raw.max_response_bytes,
+        heartbeat: raw.heartbeat,
+        digest: match raw.digest {
+            Some(digest) => {
+                if let Some((domain, path)) = digest.subscriptions.iter()
+                    .find_map(|(d, sub)| sub.path.as_ref().filter(|p| !valid_digest_path(p)).map(|p| (d, p)))
+                {
+                    return Err(ConfigError::InvalidDigestPath { domain: domain.clone(), path: path.clone() });
+                }
+                Some(digest)
+            }
+            None => None,
+        },
+        state_sections: raw.state_sections,
+        sessions_around_days: raw.sessions_around_days,
+        intent_ttl_days: raw.intent_ttl_days,
+        metrics: raw.metrics,
+        slow_action_ms: raw.slow_action_ms,
+        inject_max_tokens: raw.inject_max_tokens,
+        write_policy: raw.write_policy,
+        blocked_terms: raw.blocked_terms.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()).collect(),
+        read_only: raw.read_only,
+        http: HttpConfig { token: raw.http.token.filter(|t| !t.trim().is_empty()), ..raw.http },
     })
 }
 
+/// The domains a legacy config.yml declares under `domains:`, by name. Current configs keep
+/// domains in the vault and have none.
+pub fn legacy_domains(contents: &str) -> Result<Vec<Domain>, ConfigError> {
+    #[derive(Deserialize)]
+    struct Legacy {
+        #[serde(default)]
+        domains: HashMap<String, RawDomainEntry>,
+    }
+    let legacy: Legacy = serde_yaml::from_str(contents)?;
+    let mut domains = config_domains(&legacy.domains)?;
+    domains.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+    Ok(domains)
+}
+
+fn config_domains(entries: &HashMap<String, RawDomainEntry>) -> Result<Vec<Domain>, ConfigError> {
+    let mut domains = Vec::new();
+    for (name, entry) in entries {
+        let domain_name = DomainName::new(name)?;
+        let mut paths = Vec::new();
+        for p in &entry.paths {
+            paths.push(PathGlob::new(p)?);
+        }
+        domains.push(Domain {
+            name: domain_name,
+            paths,
+            aliases: entry.aliases.clone(),
+            can_read: entry.can_read.clone(),
+            seed_template: None,
+        });
+    }
+    Ok(domains)
+}
+
 /// Resolve the wardwell config directory. Defaults to ~/.wardwell.
 pub fn config_dir() -> PathBuf {
     if let Ok(dir) = std::env::var("WARDWELL_CONFIG_DIR") {
@@ -253,6 +929,52 @@ session_sources: []
         assert_eq!(config.registry.all().len(), 0);
     }
 
+    #[test]
+    fn load_scoped_session_sources() {
+        let yaml = r#"
+vault_path: /tmp/vault
+session_sources:
+  - /tmp/sessions/
+  - path: /tmp/work-sessions/
+    domains: [work]
+    paths: ["/tmp/code/*"]
+"#;
+        let f = write_config(yaml).unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert_eq!(config.session_sources.len(), 2);
+        assert_eq!(config.session_sources[0], SessionSource::new("/tmp/sessions/"));
+        let scoped = &config.session_sources[1];
+        assert!(scoped.admits("/tmp/code/api", Some("work")));
+        assert!(!scoped.admits("/tmp/code/api", Some("personal")));
+        assert!(!scoped.admits("/tmp/code/api", None));
+        assert!(!scoped.admits("/tmp/elsewhere/api", Some("work")));
+        assert!(config.session_sources[0].admits("/anywhere", None));
+    }
+
+    #[test]
+    fn load_session_exclude() {
+        let yaml = r#"
+vault_path: /tmp/vault
+session_sources:
+  - /tmp/sessions/
+  - path: /tmp/work-sessions/
+    domains: [work]
+session_exclude: ["/tmp/code/nda-client", "/tmp/secret/*"]
+"#;
+        let f = write_config(yaml).unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert_eq!(config.session_exclude.len(), 2);
+        let source = &config.session_sources[0];
+        assert_eq!(source.exclude, config.session_exclude);
+        assert_eq!(config.session_sources[1].exclude, config.session_exclude);
+        // Decoded from -tmp-code-nda-client, the path reads /tmp/code/nda/client
+        assert!(!source.admits("/tmp/code/nda/client", None));
+        assert!(!source.admits("/tmp/code/nda/client/web", None));
+        assert!(!source.admits("/tmp/secret/repo", None));
+        assert!(source.admits("/tmp/code/api", None));
+        assert!(source.admits("/tmp/code/nda", None));
+    }
+
     #[test]
     fn load_config_with_can_read() {
         let yaml = r#"
@@ -355,4 +1077,341 @@ kanban:
         assert_eq!(config.kanban_prefixes.get("P-").unwrap(), "project");
         assert_eq!(config.kanban_prefixes.get("T-").unwrap(), "task");
     }
+
+    #[test]
+    fn load_backup_config_defaults_interval() {
+        let yaml = r#"
+vault_path: /tmp/vault
+backup:
+  command: "restic backup {vault}"
+"#;
+        let f = write_config(yaml).unwrap();
+        let config = load(Some(f.path())).unwrap();
+        let backup = config.backup.unwrap();
+        assert_eq!(backup.command, "restic backup {vault}");
+        assert_eq!(backup.interval_hours, 24);
+    }
+
+    #[test]
+    fn load_digest_schedule() {
+        let f = write_config("vault_path: /tmp/vault\ndigest: {}\n").unwrap();
+        let digest = load(Some(f.path())).unwrap().digest.unwrap();
+        assert_eq!(digest.schedule, crate::daemon::digest::Schedule::default());
+        assert!(digest.domains.is_empty());
+
+        let f = write_config("vault_path: /tmp/vault\ndigest:\n  schedule: \"0 8 * * 1\"\n  domains: [work]\n").unwrap();
+        let digest = load(Some(f.path())).unwrap().digest.unwrap();
+        assert_eq!(digest.schedule, crate::daemon::digest::Schedule::parse("0 8 * * 1").unwrap());
+        assert_eq!(digest.domains, vec!["work"]);
+
+        let f = write_config("vault_path: /tmp/vault\ndigest:\n  schedule: \"every morning\"\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+
+        let f = write_config("vault_path: /tmp/vault\ndigest:\n  domains: [work]\n  subscriptions:\n    personal:\n      schedule: \"0 8 * * 1\"\n      sections: [up_next, what_happened]\n      path: _weekly/\n").unwrap();
+        let digest = load(Some(f.path())).unwrap().digest.unwrap();
+        assert!(digest.covers("work") && digest.covers("personal") && !digest.covers("side"));
+        let personal = digest.subscription("personal");
+        assert_eq!(personal.schedule, crate::daemon::digest::Schedule::parse("0 8 * * 1").unwrap());
+        assert_eq!(personal.sections, [crate::daemon::digest::Section::UpNext, crate::daemon::digest::Section::WhatHappened]);
+        assert_eq!(personal.dir, "_digests/_weekly");
+        assert_eq!((digest_dir(Some("_digests/weekly/")), digest_dir(Some("_digests")), digest_dir(Some("_digestsx"))), ("_digests/weekly".to_string(), "_digests".to_string(), "_digests/_digestsx".to_string()));
+        let work = digest.subscription("work");
+        assert_eq!((work.schedule, work.dir.as_str()), (crate::daemon::digest::Schedule::default(), "_digests"));
+
+        let f = write_config("vault_path: /tmp/vault\ndigest:\n  subscriptions:\n    work:\n      path: ../elsewhere\n").unwrap();
+        assert!(matches!(load(Some(f.path())), Err(ConfigError::InvalidDigestPath { .. })));
+    }
+
+    #[test]
+    fn load_without_backup_disables_it() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert!(config.backup.is_none());
+    }
+
+    #[test]
+    fn load_tools_enabled_partial() {
+        let yaml = r#"
+vault_path: /tmp/vault
+tools_enabled:
+  clipboard: false
+"#;
+        let f = write_config(yaml).unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert!(config.tools_enabled.search);
+        assert!(config.tools_enabled.write);
+        assert!(!config.tools_enabled.clipboard);
+    }
+
+    #[test]
+    fn load_dedup_window() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().dedup_window_seconds, 300);
+
+        let f = write_config("vault_path: /tmp/vault\ndedup_window_seconds: 0\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().dedup_window_seconds, 0);
+    }
+
+    #[test]
+    fn load_max_response_bytes() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().max_response_bytes, 80_000);
+
+        let f = write_config("vault_path: /tmp/vault\nmax_response_bytes: 0\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().max_response_bytes, 0);
+    }
+
+    #[test]
+    fn load_heartbeat() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert!(load(Some(f.path())).unwrap().heartbeat.is_none());
+
+        let f = write_config("vault_path: /tmp/vault\nheartbeat: {}\n").unwrap();
+        let heartbeat = load(Some(f.path())).unwrap().heartbeat.unwrap();
+        assert_eq!((heartbeat.cadence, heartbeat.weekday, heartbeat.projects), (HeartbeatCadence::Weekly, chrono::Weekday::Mon, 3));
+
+        let f = write_config("vault_path: /tmp/vault\nheartbeat:\n  weekday: friday\n  projects: 2\n").unwrap();
+        let heartbeat = load(Some(f.path())).unwrap().heartbeat.unwrap();
+        assert_eq!((heartbeat.weekday, heartbeat.projects), (chrono::Weekday::Fri, 2));
+    }
+
+    #[test]
+    fn load_state_sections() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert!(load(Some(f.path())).unwrap().state_sections.is_empty());
+
+        let f = write_config("vault_path: /tmp/vault\nstate_sections: [Focus, Links, Next Action]\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().state_sections, vec!["Focus", "Links", "Next Action"]);
+    }
+
+    #[test]
+    fn load_intent_ttl_days() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().intent_ttl_days, 14);
+
+        let f = write_config("vault_path: /tmp/vault\nintent_ttl_days: 0\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().intent_ttl_days, 0);
+    }
+
+    #[test]
+    fn load_sessions_around_days() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().sessions_around_days, 3);
+
+        let f = write_config("vault_path: /tmp/vault\nsessions_around_days: 7\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().sessions_around_days, 7);
+    }
+
+    #[test]
+    fn load_slow_action_ms() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().slow_action_ms, 2000);
+
+        let f = write_config("vault_path: /tmp/vault\nslow_action_ms: 0\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().slow_action_ms, 0);
+    }
+
+    #[test]
+    fn load_inject_max_tokens() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().inject_max_tokens, 0);
+
+        let f = write_config("vault_path: /tmp/vault\ninject_max_tokens: 1500\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().inject_max_tokens, 1500);
+    }
+
+    #[test]
+    fn load_write_policy() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().write_policy, WritePolicy::Warn);
+
+        let f = write_config("vault_path: /tmp/vault\nwrite_policy: require_read\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().write_policy, WritePolicy::RequireRead);
+    }
+
+    #[test]
+    fn load_metrics_is_opt_in() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert!(!load(Some(f.path())).unwrap().metrics);
+
+        let f = write_config("vault_path: /tmp/vault\nmetrics: true\n").unwrap();
+        assert!(load(Some(f.path())).unwrap().metrics);
+    }
+
+    #[test]
+    fn load_blocked_terms() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().blocked_terms, default_blocked_terms());
+
+        let f = write_config("vault_path: /tmp/vault\nblocked_terms: [Bloqueado, esperando, \"待ち\"]\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().blocked_terms, vec!["bloqueado", "esperando", "待ち"]);
+    }
+
+    #[test]
+    fn load_log_level() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().log_level, rmcp::model::LoggingLevel::Info);
+
+        let f = write_config("vault_path: /tmp/vault\nlog_level: warning\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().log_level, rmcp::model::LoggingLevel::Warning);
+
+        let f = write_config("vault_path: /tmp/vault\nlog_level: loud\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+    }
+
+    #[test]
+    fn load_extra_statuses() {
+        let f = write_config("vault_path: /tmp/vault\nstatuses:\n  Review: active\n  waiting_on_client: blocked\n").unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert_eq!(config.statuses.get("review"), Some(&crate::vault::types::Status::Active));
+        assert_eq!(config.statuses.get("waiting_on_client"), Some(&crate::vault::types::Status::Blocked));
+
+        let f = write_config("vault_path: /tmp/vault\nstatuses:\n  review: sideways\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+    }
+
+    #[test]
+    fn load_remote_vault() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert!(!config.remote_vault.enabled);
+        assert_eq!(config.remote_vault.batch(), crate::vault::batch::BatchRead::default());
+
+        let f = write_config("vault_path: /tmp/vault\nremote_vault:\n  enabled: true\n  read_timeout_ms: 2000\n").unwrap();
+        let batch = load(Some(f.path())).unwrap().remote_vault.batch();
+        assert_eq!(batch.concurrency, 8);
+        assert_eq!(batch.timeout, Some(std::time::Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn load_durability() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().durability, DurabilityConfig::default());
+
+        let f = write_config("vault_path: /tmp/vault\ndurability:\n  fsync: periodic\n  interval_seconds: 30\n").unwrap();
+        let durability = load(Some(f.path())).unwrap().durability;
+        assert_eq!(durability.fsync, FsyncPolicy::Periodic);
+        assert_eq!(durability.interval_seconds, 30);
+    }
+
+    #[test]
+    fn load_slug_transliteration() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().slug_transliteration, crate::vault::project::Transliteration::Ascii);
+        let f = write_config("vault_path: /tmp/vault\nslug_transliteration: german\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().slug_transliteration, crate::vault::project::Transliteration::German);
+        let f = write_config("vault_path: /tmp/vault\nslug_transliteration: klingon\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+    }
+
+    #[test]
+    fn load_symlink_policy() {
+        use crate::vault::symlinks::SymlinkPolicy;
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().symlinks, SymlinkPolicy::Follow);
+        let f = write_config("vault_path: /tmp/vault\nsymlinks: skip\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().symlinks, SymlinkPolicy::Skip);
+    }
+
+    #[test]
+    fn load_http_access() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().http, HttpConfig::default());
+        let f = write_config("vault_path: /tmp/vault\nhttp:\n  token: \" \"\n  allowed_origins: [https://claude.ai]\n").unwrap();
+        let http = load(Some(f.path())).unwrap().http;
+        assert_eq!((http.token, http.allowed_origins), (None, vec!["https://claude.ai".to_string()]));
+    }
+
+    #[test]
+    fn load_ranking() {
+        let f = write_config("vault_path: /tmp/vault\nranking:\n  weights:\n    priority: 2\n    due_urgency: 10\n").unwrap();
+        let ranking = load(Some(f.path())).unwrap().ranking;
+        assert_eq!(ranking.weights, Some(RankingWeights { priority: 2.0, due_urgency: 10.0, ..Default::default() }));
+        assert_eq!((ranking.command, ranking.timeout_ms), (None, 2000));
+
+        let f = write_config("vault_path: /tmp/vault\nranking:\n  weights:\n    priorty: 2\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+    }
+
+    #[test]
+    fn load_summarize_min_signal() {
+        let f = write_config("vault_path: /tmp/vault\nai:\n  summarize_min_signal: 1.5\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.summarize_model.as_str(), ai.summarize_min_signal), ("haiku", 1.5));
+
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().ai.summarize_min_signal, 3.0);
+    }
+
+    #[test]
+    fn load_summarize_on_change() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        assert!(!load(Some(f.path())).unwrap().ai.summarize_on_change);
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  summarize_on_change: true\n").unwrap();
+        assert!(load(Some(f.path())).unwrap().ai.summarize_on_change);
+    }
+
+    #[test]
+    fn load_prewarm_sessions() {
+        let f = write_config("vault_path: /tmp/vault\nai:\n  summarize_model: sonnet\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().ai.prewarm_sessions, 2);
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  prewarm_sessions: 0\n").unwrap();
+        assert_eq!(load(Some(f.path())).unwrap().ai.prewarm_sessions, 0);
+    }
+
+    #[test]
+    fn load_summarizer_batching() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.max_summaries_per_cycle, ai.summarize_order), (20, SummarizeOrder::NewestFirst));
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  max_summaries_per_cycle: 5\n  summarize_order: oldest_first\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.max_summaries_per_cycle, ai.summarize_order), (5, SummarizeOrder::OldestFirst));
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  summarize_order: random\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+    }
+
+    #[test]
+    fn load_summarizer_concurrency() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.concurrency, ai.requests_per_minute), (1, 30));
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  provider: ollama\n  concurrency: 0\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.concurrency, ai.requests_per_minute), (1, 0));
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  provider: openai\n  concurrency: 8\n  requests_per_minute: 120\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.concurrency, ai.requests_per_minute), (8, 120));
+    }
+
+    #[test]
+    fn load_ai_provider() {
+        let f = write_config("vault_path: /tmp/vault\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.provider, ai.base_url), (AiProvider::ClaudeCli, None));
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  provider: ollama\n  base_url: http://gpu-box:11434\n  summarize_model: llama3.1\n").unwrap();
+        let ai = load(Some(f.path())).unwrap().ai;
+        assert_eq!((ai.provider, ai.base_url.as_deref()), (AiProvider::Ollama, Some("http://gpu-box:11434")));
+
+        let f = write_config("vault_path: /tmp/vault\nai:\n  provider: bard\n").unwrap();
+        assert!(load(Some(f.path())).is_err());
+    }
+
+    #[test]
+    fn secret_references_load_unresolved() {
+        // A missing secret mustn't stop hooks and tools that never use it
+        let f = write_config(
+            "vault_path: /tmp/vault\nbackup:\n  command: \"restic -p {secret:wardwell_test_missing} backup {vault}\"\nai:\n  api_key: \"{secret:wardwell_test_missing}\"\n",
+        ).unwrap();
+        let config = load(Some(f.path())).unwrap();
+        assert_eq!(config.backup.unwrap().command, "restic -p {secret:wardwell_test_missing} backup {vault}");
+        assert_eq!(config.ai.api_key.as_deref(), Some("{secret:wardwell_test_missing}"));
+    }
 }
@@ -1,5 +1,6 @@
 pub mod types;
 pub mod loader;
+pub mod secrets;
 
 pub use types::*;
 pub use loader::*;
@@ -0,0 +1,175 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Keychain service name all wardwell secrets are stored under.
+const SERVICE: &str = "wardwell";
+
+/// Errors from the OS secret store.
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("invalid secret name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+
+    #[error("secret '{0}' not found (set it with `wardwell secret set {0}`)")]
+    NotFound(String),
+
+    #[error("secret store unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("secret store command failed: {0}")]
+    Failed(String),
+}
+
+/// Store a secret in the macOS keychain (`security`) or libsecret (`secret-tool`).
+pub fn set(name: &str, value: &str) -> Result<(), SecretError> {
+    validate_name(name)?;
+    if cfg!(target_os = "macos") {
+        // `security -i` reads the command from stdin, keeping the value out of `ps`.
+        // -U updates an existing item instead of failing
+        if value.contains(['\n', '\r']) {
+            return Err(SecretError::Failed("secret values can't contain line breaks".to_string()));
+        }
+        let quoted = value.replace('\\', "\\\\").replace('"', "\\\"");
+        let line = format!("add-generic-password -U -s {SERVICE} -a {name} -w \"{quoted}\"\n");
+        run(Command::new("security").arg("-i"), Some(&line))?;
+    } else {
+        let label = format!("wardwell: {name}");
+        run(
+            Command::new("secret-tool").args(["store", "--label", &label, "service", SERVICE, "account", name]),
+            Some(value),
+        )?;
+    }
+    Ok(())
+}
+
+/// Look up a secret. `WARDWELL_SECRET_<NAME>` (uppercased, `-` → `_`) overrides the
+/// OS store, for headless machines and CI.
+pub fn get(name: &str) -> Result<String, SecretError> {
+    validate_name(name)?;
+    if let Ok(value) = std::env::var(env_var_name(name)) {
+        return Ok(value);
+    }
+    let output = if cfg!(target_os = "macos") {
+        run(Command::new("security").args(["find-generic-password", "-s", SERVICE, "-a", name, "-w"]), None)
+    } else {
+        run(Command::new("secret-tool").args(["lookup", "service", SERVICE, "account", name]), None)
+    };
+    match output {
+        Ok(value) if !value.is_empty() => Ok(value),
+        Ok(_) | Err(SecretError::Failed(_)) => Err(SecretError::NotFound(name.to_string())),
+        Err(e) => Err(e),
+    }
+}
+
+/// Remove a secret from the OS store.
+pub fn delete(name: &str) -> Result<(), SecretError> {
+    validate_name(name)?;
+    if cfg!(target_os = "macos") {
+        run(Command::new("security").args(["delete-generic-password", "-s", SERVICE, "-a", name]), None)?;
+    } else {
+        run(Command::new("secret-tool").args(["clear", "service", SERVICE, "account", name]), None)?;
+    }
+    Ok(())
+}
+
+/// Replace every `{secret:NAME}` in a config value with the stored secret. Called where the
+/// value is used, so a missing secret only fails the feature that needs it.
+pub fn resolve(input: &str) -> Result<String, SecretError> {
+    if !has_refs(input) {
+        return Ok(input.to_string());
+    }
+    resolve_refs(input, &get)
+}
+
+/// Replace every `{secret:NAME}` in `input` using `lookup`.
+pub fn resolve_refs(
+    input: &str,
+    lookup: &dyn Fn(&str) -> Result<String, SecretError>,
+) -> Result<String, SecretError> {
+    const OPEN: &str = "{secret:";
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find(OPEN) {
+        let after = &rest[start + OPEN.len()..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&lookup(&after[..end])?);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Whether a string contains a `{secret:...}` reference.
+pub fn has_refs(input: &str) -> bool {
+    input.contains("{secret:")
+}
+
+fn env_var_name(name: &str) -> String {
+    format!("WARDWELL_SECRET_{}", name.to_uppercase().replace('-', "_"))
+}
+
+fn validate_name(name: &str) -> Result<(), SecretError> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(SecretError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Run a secret-store command, optionally feeding `stdin`. Returns trimmed stdout.
+fn run(cmd: &mut Command, stdin: Option<&str>) -> Result<String, SecretError> {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let mut child = cmd
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SecretError::Unavailable(format!("`{program}` could not be run: {e}")))?;
+    if let Some(input) = stdin
+        && let Some(mut pipe) = child.stdin.take()
+    {
+        pipe.write_all(input.as_bytes()).map_err(|e| SecretError::Failed(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| SecretError::Failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(SecretError::Failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches(['\n', '\r']).to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn fake_lookup(name: &str) -> Result<String, SecretError> {
+        match name {
+            "api_key" => Ok("sk-123".to_string()),
+            other => Err(SecretError::NotFound(other.to_string())),
+        }
+    }
+
+    #[test]
+    fn resolve_refs_substitutes_all_references() {
+        let out = resolve_refs("Bearer {secret:api_key} / {secret:api_key}", &fake_lookup).unwrap();
+        assert_eq!(out, "Bearer sk-123 / sk-123");
+        assert_eq!(resolve_refs("no refs here", &fake_lookup).unwrap(), "no refs here");
+    }
+
+    #[test]
+    fn resolve_refs_propagates_missing_secret() {
+        let err = resolve_refs("{secret:missing}", &fake_lookup).unwrap_err();
+        assert!(matches!(err, SecretError::NotFound(ref n) if n == "missing"));
+    }
+
+    #[test]
+    fn names_are_validated() {
+        assert!(validate_name("anthropic_api_key").is_ok());
+        assert!(validate_name("slack-webhook").is_ok());
+        assert!(validate_name("a b").is_err());
+        assert!(validate_name("").is_err());
+        assert_eq!(env_var_name("slack-webhook"), "WARDWELL_SECRET_SLACK_WEBHOOK");
+    }
+}
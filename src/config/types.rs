@@ -166,6 +166,9 @@ pub enum ConfigError {
 
     #[error("empty domain configuration")]
     EmptyConfig,
+
+    #[error("digest path '{path}' for domain '{domain}' must be a folder inside the domain")]
+    InvalidDigestPath { domain: String, path: String },
 }
 
 fn dirs_home() -> Option<PathBuf> {
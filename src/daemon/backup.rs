@@ -0,0 +1,199 @@
+use crate::config::loader::BackupConfig;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// Errors from running a backup.
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("backup IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("backup command exited with {status}: {stderr}")]
+    Failed { status: String, stderr: String },
+
+    #[error("backup command secret: {0}")]
+    Secret(#[from] crate::config::secrets::SecretError),
+
+    #[error("another backup is already running")]
+    Busy,
+}
+
+/// File under the config dir holding the RFC 3339 timestamp of the last successful backup.
+const STATE_FILE: &str = "last_backup";
+
+/// Held while a backup runs, so servers sharing a config dir never back up at once.
+const LOCK_FILE: &str = "backup.lock";
+
+/// A backup running longer than this is taken as abandoned and its lock cleared.
+const LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+
+/// Consecutive failed backups and when the last one ended, so every server backs off alike.
+const FAILURE_FILE: &str = "backup_failures";
+
+/// Wait after a first failure before trying again. Doubles with each failure after it, up
+/// to the backup interval.
+const FIRST_RETRY_MINUTES: i64 = 5;
+
+/// Expand `{vault}` and `{config_dir}` in a backup command template.
+/// Substituted paths are single-quoted for the shell.
+pub fn render_command(template: &str, vault_path: &Path, config_dir: &Path) -> String {
+    template
+        .replace("{vault}", &shell_quote(&vault_path.to_string_lossy()))
+        .replace("{config_dir}", &shell_quote(&config_dir.to_string_lossy()))
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Run the configured backup command via `sh -c` and record the success time, or the
+/// failure that `is_due` backs off from. Fails with `Busy` while another backup runs.
+pub fn run(config: &BackupConfig, vault_path: &Path, config_dir: &Path) -> Result<DateTime<Utc>, BackupError> {
+    std::fs::create_dir_all(config_dir)?;
+    let lock = config_dir.join(LOCK_FILE);
+    if !super::take_lock(&lock, LOCK_TTL) {
+        return Err(BackupError::Busy);
+    }
+    let result = backup(config, vault_path, config_dir);
+    let _ = std::fs::remove_file(&lock);
+    match result {
+        Ok(_) => {
+            let _ = std::fs::remove_file(config_dir.join(FAILURE_FILE));
+        }
+        Err(_) => {
+            let failures = failures(config_dir).map_or(0, |(n, _)| n) + 1;
+            let _ = std::fs::write(config_dir.join(FAILURE_FILE), format!("{failures} {}", Utc::now().to_rfc3339()));
+        }
+    }
+    result
+}
+
+fn backup(config: &BackupConfig, vault_path: &Path, config_dir: &Path) -> Result<DateTime<Utc>, BackupError> {
+    let command = render_command(&crate::config::secrets::resolve(&config.command)?, vault_path, config_dir);
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .stdin(std::process::Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(BackupError::Failed {
+            status: output.status.to_string(),
+            stderr: stderr.trim().chars().take(500).collect(),
+        });
+    }
+
+    let now = Utc::now();
+    std::fs::write(config_dir.join(STATE_FILE), now.to_rfc3339())?;
+    Ok(now)
+}
+
+/// When the last successful backup finished, if one was ever recorded.
+pub fn last_success(config_dir: &Path) -> Option<DateTime<Utc>> {
+    let raw = std::fs::read_to_string(config_dir.join(STATE_FILE)).ok()?;
+    DateTime::parse_from_rfc3339(raw.trim()).ok().map(|d| d.with_timezone(&Utc))
+}
+
+/// Consecutive failed backups since the last success, and when the latest ended.
+fn failures(config_dir: &Path) -> Option<(u32, DateTime<Utc>)> {
+    let raw = std::fs::read_to_string(config_dir.join(FAILURE_FILE)).ok()?;
+    let (count, at) = raw.trim().split_once(' ')?;
+    Some((count.parse().ok()?, DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc)))
+}
+
+/// Whether a backup should run now, given the configured interval. After a failure the
+/// next attempt waits `FIRST_RETRY_MINUTES`, doubling per further failure, up to the interval.
+pub fn is_due(config: &BackupConfig, config_dir: &Path) -> bool {
+    let interval = chrono::Duration::hours(config.interval_hours as i64);
+    if let Some((count, at)) = failures(config_dir) {
+        let wait = chrono::Duration::minutes(FIRST_RETRY_MINUTES << count.saturating_sub(1).min(16)).min(interval);
+        if Utc::now() - at < wait {
+            return false;
+        }
+    }
+    match last_success(config_dir) {
+        Some(last) => Utc::now() - last >= interval,
+        None => true,
+    }
+}
+
+/// Run backups on the configured schedule, backing off after failures. Checks every five minutes.
+pub async fn run_backup_loop(config: BackupConfig, vault_path: std::path::PathBuf, config_dir: std::path::PathBuf) {
+    loop {
+        if is_due(&config, &config_dir) {
+            let cfg = config.clone();
+            let vault = vault_path.clone();
+            let dir = config_dir.clone();
+            let result = tokio::task::spawn_blocking(move || run(&cfg, &vault, &dir)).await;
+            match result {
+                Ok(Ok(_)) => eprintln!("wardwell: backup completed"),
+                // Another server sharing the config dir is on it
+                Ok(Err(BackupError::Busy)) => {}
+                Ok(Err(e)) => eprintln!("wardwell: backup failed: {e}"),
+                Err(e) => eprintln!("wardwell: backup task panicked: {e}"),
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn render_command_quotes_paths() {
+        let cmd = render_command(
+            "restic -r /backups backup {vault} --exclude {config_dir}",
+            Path::new("/home/me/My Vault"),
+            Path::new("/home/me/.wardwell"),
+        );
+        assert_eq!(cmd, "restic -r /backups backup '/home/me/My Vault' --exclude '/home/me/.wardwell'");
+    }
+
+    #[test]
+    fn successful_run_records_timestamp() {
+        let tmp = tempdir().unwrap();
+        let config = BackupConfig { command: "true".to_string(), interval_hours: 24 };
+        assert!(is_due(&config, tmp.path()));
+
+        run(&config, tmp.path(), tmp.path()).unwrap();
+        assert!(last_success(tmp.path()).is_some());
+        assert!(!is_due(&config, tmp.path()));
+    }
+
+    #[test]
+    fn failed_run_leaves_no_timestamp() {
+        let tmp = tempdir().unwrap();
+        let config = BackupConfig { command: "echo nope >&2; exit 3".to_string(), interval_hours: 24 };
+        let err = run(&config, tmp.path(), tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+        assert!(last_success(tmp.path()).is_none());
+    }
+
+    #[test]
+    fn failures_back_off_and_runs_are_exclusive() {
+        let tmp = tempdir().unwrap();
+        let failing = BackupConfig { command: "exit 1".to_string(), interval_hours: 24 };
+        run(&failing, tmp.path(), tmp.path()).unwrap_err();
+        assert_eq!(failures(tmp.path()).unwrap().0, 1);
+        assert!(!is_due(&failing, tmp.path()), "retries wait after a failure");
+
+        // Second failure, recorded long enough ago for the doubled wait to have passed
+        let earlier = Utc::now() - chrono::Duration::minutes(11);
+        std::fs::write(tmp.path().join(FAILURE_FILE), format!("2 {}", earlier.to_rfc3339())).unwrap();
+        assert!(is_due(&failing, tmp.path()));
+
+        std::fs::write(tmp.path().join(LOCK_FILE), "").unwrap();
+        let ok = BackupConfig { command: "true".to_string(), interval_hours: 24 };
+        assert!(matches!(run(&ok, tmp.path(), tmp.path()), Err(BackupError::Busy)));
+        std::fs::remove_file(tmp.path().join(LOCK_FILE)).unwrap();
+
+        run(&ok, tmp.path(), tmp.path()).unwrap();
+        assert!(failures(tmp.path()).is_none());
+        assert!(!tmp.path().join(LOCK_FILE).exists());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::config::loader::DurabilityConfig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The queue of decisions proposed from session summaries, in the project folder. Nothing in
+/// it reaches decisions.md until the `approve` write action takes it.
+pub const QUEUE: &str = "pending-decisions.jsonl";
+const SCHEMA: &str = "pending-decisions";
+/// Longest title taken from a decision's first sentence.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// A decision waiting for approval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Proposed {
+    pub id: String,
+    /// The day the summary was written, `YYYY-MM-DD`.
+    pub date: String,
+    pub session_id: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// The decisions in a summary's `## Decisions` section as (title, body): one per bullet, or
+/// one per paragraph when the section has no bullets. A bold lead-in is the title, otherwise
+/// the first sentence is.
+pub fn extract(summary: &str) -> Vec<(String, String)> {
+    let section: Vec<&str> = summary.lines()
+        .skip_while(|l| l.trim() != "## Decisions")
+        .skip(1)
+        .take_while(|l| !l.starts_with("# ") && !l.starts_with("## "))
+        .collect();
+    let bullet = |l: &str| l.strip_prefix("- ").or_else(|| l.strip_prefix("* ")).map(str::to_string);
+    let bulleted = section.iter().any(|l| bullet(l).is_some());
+
+    let mut items: Vec<String> = Vec::new();
+    let mut open = false;
+    for line in &section {
+        let text = line.trim();
+        if let Some(item) = bullet(line) {
+            items.push(item.trim().to_string());
+            open = true;
+        } else if text.is_empty() {
+            open = false;
+        } else if open && let Some(last) = items.last_mut() {
+            last.push(' ');
+            last.push_str(text);
+        } else if !bulleted {
+            items.push(text.to_string());
+            open = true;
+        }
+    }
+    items.into_iter()
+        .filter(|i| !i.to_lowercase().starts_with("none"))
+        .map(|body| (title(&body), body))
+        .collect()
+}
+
+fn title(item: &str) -> String {
+    if let Some(rest) = item.strip_prefix("**")
+        && let Some((bold, _)) = rest.split_once("**")
+    {
+        return bold.trim().trim_end_matches([':', '.']).to_string();
+    }
+    let sentence = item.split_once(". ").map_or(item, |(s, _)| s).trim_end_matches('.');
+    if sentence.chars().count() <= MAX_TITLE_CHARS {
+        return sentence.to_string();
+    }
+    let cut: String = sentence.chars().take(MAX_TITLE_CHARS).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    format!("{cut}…")
+}
+
+/// The proposals queued in `project_dir`, oldest first.
+pub fn pending(project_dir: &Path) -> Vec<Proposed> {
+    std::fs::read_to_string(project_dir.join(QUEUE)).unwrap_or_default()
+        .lines()
+        .filter(|l| !l.starts_with("{\"_schema\""))
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// Queue the decisions in `summary` for the project at `project_dir`. Ones already queued
+/// from the same session, or already in decisions.md under the same title, are left out.
+/// Returns how many were queued.
+pub fn propose(project_dir: &Path, session_id: &str, date: &str, summary: &str) -> std::io::Result<usize> {
+    let found = extract(summary);
+    if found.is_empty() || !project_dir.is_dir() {
+        return Ok(0);
+    }
+    let queued = pending(project_dir);
+    let recorded = std::fs::read_to_string(project_dir.join("decisions.md")).unwrap_or_default();
+    let mut added = 0;
+    for (title, body) in found {
+        if queued.iter().any(|p| p.session_id == session_id && p.title == title) || recorded.contains(&format!(" — {title}\n")) {
+            continue;
+        }
+        let entry = Proposed {
+            id: crate::vault::entry_id::new_id(),
+            date: date.to_string(),
+            session_id: session_id.to_string(),
+            title,
+            body,
+        };
+        let line = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+        crate::vault::jsonl::append(&project_dir.join(QUEUE), SCHEMA, &line, &DurabilityConfig::default())?;
+        added += 1;
+    }
+    Ok(added)
+}
+
+/// The queue in `project_dir` without the proposals in `ids`. The file is removed once the
+/// queue is empty. It is locked against `propose` while it's rewritten.
+pub fn remove(project_dir: &Path, ids: &[String]) -> std::io::Result<()> {
+    let path = project_dir.join(QUEUE);
+    if !path.exists() {
+        return Ok(());
+    }
+    crate::vault::jsonl::rewrite(&path, |current| {
+        let kept: Vec<Proposed> = crate::vault::jsonl::parse_entries::<Proposed>(current).into_iter()
+            .filter(|p| !ids.iter().any(|id| id.eq_ignore_ascii_case(&p.id)))
+            .collect();
+        if kept.is_empty() {
+            return Ok(None);
+        }
+        let mut content = format!("{{\"_schema\": \"{SCHEMA}\", \"_version\": \"1.0\"}}\n");
+        for p in &kept {
+            content.push_str(&serde_json::to_string(p).map_err(std::io::Error::other)?);
+            content.push('\n');
+        }
+        Ok(Some(content))
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_one_decision_per_bullet_or_paragraph() {
+        let summary = "## Summary\nDid things.\n\n## Decisions\n- **Client-level retry**: chose it over application-level retry because\n  the transport already knows which calls are idempotent.\n- Went with SQLite instead of Postgres. One file is easier to back up.\n\n## Patterns\n- Tests first\n";
+        assert_eq!(extract(summary), [
+            ("Client-level retry".to_string(), "**Client-level retry**: chose it over application-level retry because the transport already knows which calls are idempotent.".to_string()),
+            ("Went with SQLite instead of Postgres".to_string(), "Went with SQLite instead of Postgres. One file is easier to back up.".to_string()),
+        ]);
+
+        let paragraphs = "## Decisions\nKept the queue per project.\nIt follows the project on rename.\n\nDropped the global queue.\n";
+        let titles: Vec<_> = extract(paragraphs).into_iter().map(|(t, _)| t).collect();
+        assert_eq!(titles, ["Kept the queue per project", "Dropped the global queue"]);
+        assert!(extract("## Patterns\n- Tests first\n").is_empty());
+        assert!(extract("## Decisions\nNone worth keeping.\n").is_empty());
+    }
+
+    #[test]
+    fn proposals_queue_once_and_leave_when_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let summary = "## Decisions\n- Chose SQLite.\n- Chose zstd.\n";
+        std::fs::write(tmp.path().join("decisions.md"), "# api Decisions\n\n## 2026-03-01 — Chose zstd\n\nEarlier.\n\n---\n\n").unwrap();
+
+        assert_eq!(propose(tmp.path(), "s1", "2026-03-02", summary).unwrap(), 1);
+        assert_eq!(propose(tmp.path(), "s1", "2026-03-02", summary).unwrap(), 0);
+        let queued = pending(tmp.path());
+        assert_eq!(queued.len(), 1);
+        assert_eq!((queued[0].title.as_str(), queued[0].date.as_str()), ("Chose SQLite", "2026-03-02"));
+
+        // A proposal queued while a removal is waiting survives it, whichever goes first
+        let held = crate::vault::jsonl::open_locked(&tmp.path().join(QUEUE)).unwrap();
+        let (dir, id) = (tmp.path().to_path_buf(), queued[0].id.clone());
+        let removing = std::thread::spawn(move || remove(&dir, &[id]));
+        let dir = tmp.path().to_path_buf();
+        let proposing = std::thread::spawn(move || propose(&dir, "s2", "2026-03-03", "## Decisions\n- Chose Rust.\n"));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        drop(held);
+        removing.join().unwrap().unwrap();
+        assert_eq!(proposing.join().unwrap().unwrap(), 1);
+        let left: Vec<_> = pending(tmp.path()).into_iter().map(|p| p.title).collect();
+        assert_eq!(left, ["Chose Rust"]);
+
+        remove(tmp.path(), &[pending(tmp.path())[0].id.to_lowercase()]).unwrap();
+        assert!(!tmp.path().join(QUEUE).exists());
+        assert_eq!(propose(&tmp.path().join("missing"), "s1", "2026-03-02", summary).unwrap(), 0);
+    }
+}
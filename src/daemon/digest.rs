@@ -0,0 +1,417 @@
+use crate::mcp::metrics::MetricsReport;
+use crate::vault::template::{self, Vars};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, Timelike, Utc};
+use std::path::{Path, PathBuf};
+
+/// Folder under each domain that digests are written to, one `YYYY-MM-DD.md` per run day.
+pub const DIGEST_DIR: &str = "_digests";
+
+/// File under the config dir holding the RFC 3339 timestamp of the last digest run, from
+/// before runs were kept per domain. Domains with no run of their own count from it.
+const STATE_FILE: &str = "last_digest";
+
+/// File under the config dir mapping each domain to its last digest run (RFC 3339).
+const RUNS_FILE: &str = "digest_runs.json";
+
+/// Entries listed per digest section; the rest are counted.
+const MAX_ITEMS: usize = 10;
+
+/// A cron-style schedule: `minute hour day-of-month month day-of-week`, each field `*`, a
+/// number, a range `a-b`, a list `a,b`, or a step `*/n` / `a-b/n`. Days of the week run
+/// 0–6 from Sunday (7 is Sunday too). `@daily` and `@weekly` are midnight, and Sunday midnight.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether day-of-month and day-of-week were both restricted: cron then fires on either.
+    either_day: bool,
+}
+
+impl Default for Schedule {
+    /// 07:00 every day.
+    fn default() -> Self {
+        Schedule { minutes: 1, hours: 1 << 7, days: 0xFFFF_FFFE, months: 0x1FFE, weekdays: 0x7F, either_day: false }
+    }
+}
+
+impl TryFrom<String> for Schedule {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Schedule::parse(&s)
+    }
+}
+
+impl Schedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("schedule '{expr}' needs 5 fields (minute hour day month weekday), got {}", fields.len()));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Schedule {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            either_day: day != "*" && weekday != "*",
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days & (1 << date.day()) != 0;
+        let dow = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.either_day { dom || dow } else { dom && dow }
+    }
+
+    /// Days between the schedule's next two fires after `after`, at least 1: 7 for a weekly
+    /// schedule, 1 for a daily one.
+    pub fn period_days(&self, after: NaiveDateTime) -> i64 {
+        self.next_after(after)
+            .and_then(|next| Some((self.next_after(next)? - next).num_days()))
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// The first minute after `after` the schedule fires on, within the next year.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let limit = after + chrono::Duration::days(366);
+        while t <= limit {
+            if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) != 0 && self.minutes & (1 << t.minute()) != 0 {
+                return Some(t);
+            }
+            t += chrono::Duration::minutes(1);
+        }
+        None
+    }
+}
+
+/// A section of a digest, for picking which ones a domain's digest carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    UpNext,
+    Blocked,
+    WhatHappened,
+    StaleThreads,
+    RecurringBlockers,
+    Usage,
+}
+
+/// One domain's digest: when it is written, what it carries, and where it goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscription {
+    pub domain: String,
+    pub schedule: Schedule,
+    /// Empty = every section.
+    pub sections: Vec<Section>,
+    /// Folder inside the domain the digests go to.
+    pub dir: String,
+}
+
+/// One schedule field as a bitmask of the values it allows.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(|| format!("bad step in '{part}'"))?),
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (number(a, min, max)?, number(b, min, max)?)
+        } else {
+            let n = number(range, min, max)?;
+            (n, if step > 1 { max } else { n })
+        };
+        if lo > hi {
+            return Err(format!("range '{range}' runs backwards"));
+        }
+        for n in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << n;
+        }
+    }
+    Ok(mask)
+}
+
+fn number(s: &str, min: u32, max: u32) -> Result<u32, String> {
+    s.parse::<u32>().ok()
+        .filter(|n| (min..=max).contains(n))
+        .ok_or_else(|| format!("'{s}' is not a number from {min} to {max}"))
+}
+
+/// When `domain`'s last digest run finished, if one was ever recorded.
+pub fn last_run(config_dir: &Path, domain: &str) -> Option<DateTime<Utc>> {
+    let parse = |raw: &str| DateTime::parse_from_rfc3339(raw.trim()).ok().map(|d| d.with_timezone(&Utc));
+    runs(config_dir).get(domain).and_then(|raw| parse(raw))
+        .or_else(|| parse(&std::fs::read_to_string(config_dir.join(STATE_FILE)).ok()?))
+}
+
+fn runs(config_dir: &Path) -> std::collections::BTreeMap<String, String> {
+    std::fs::read_to_string(config_dir.join(RUNS_FILE)).ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn record_run(config_dir: &Path, domain: &str, at: DateTime<Utc>) -> std::io::Result<()> {
+    std::fs::create_dir_all(config_dir)?;
+    let mut runs = runs(config_dir);
+    runs.insert(domain.to_string(), at.to_rfc3339());
+    std::fs::write(config_dir.join(RUNS_FILE), serde_json::to_string_pretty(&runs)?)
+}
+
+/// The newest digest in a domain's digest folder `dir`: its date and path.
+pub fn latest(domain_dir: &Path, dir: &str) -> Option<(NaiveDate, PathBuf)> {
+    std::fs::read_dir(domain_dir.join(dir)).ok()?
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let date = NaiveDate::parse_from_str(name.strip_suffix(".md")?, "%Y-%m-%d").ok()?;
+            Some((date, e.path()))
+        })
+        .max_by_key(|(date, _)| *date)
+}
+
+/// Write `<domain>/<dir>/<date>.md`, replacing an earlier digest from the same day.
+pub fn write(domain_dir: &Path, dir: &str, date: NaiveDate, content: &str) -> std::io::Result<PathBuf> {
+    let dir = domain_dir.join(dir);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{date}.md"));
+    let tmp = path.with_extension("md.tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+/// What one domain's digest is built from: orchestrate, retrospective, and patterns output
+/// for the domain, and usage counters when metrics are on.
+pub struct DigestInput<'a> {
+    pub domain: &'a str,
+    pub date: NaiveDate,
+    pub since: NaiveDate,
+    pub orchestrate: &'a serde_json::Value,
+    pub retrospective: &'a serde_json::Value,
+    pub patterns: &'a serde_json::Value,
+    pub usage: Option<&'a MetricsReport>,
+    /// Sections to render; empty = all of them.
+    pub sections: &'a [Section],
+}
+
+/// Layout of a digest note, rendered with `template::render`.
+const DIGEST_TEMPLATE: &str = "---\ntype: machine\ndomain: {{domain}}\nupdated: {{date}}\ngenerated: wardwell\n---\n\n\
+# {{domain}} digest, {{date}}\n\nCovers {{since}} to {{date}}.\n{{sections}}";
+
+/// Render a digest as markdown. Sections with nothing to say, or not asked for, are left out.
+pub fn render(input: &DigestInput) -> String {
+    let DigestInput { domain, date, since, .. } = *input;
+    let mut out = String::new();
+    let text = |v: &serde_json::Value, key: &str| v[key].as_str().unwrap_or_default().to_string();
+
+    let mut section = |kind: Section, title: &str, lines: Vec<String>| {
+        if lines.is_empty() || !(input.sections.is_empty() || input.sections.contains(&kind)) {
+            return;
+        }
+        out.push_str(&format!("\n## {title}\n"));
+        let more = lines.len().saturating_sub(MAX_ITEMS);
+        for line in lines.into_iter().take(MAX_ITEMS) {
+            out.push_str(&format!("- {line}\n"));
+        }
+        if more > 0 {
+            out.push_str(&format!("- …and {more} more\n"));
+        }
+    };
+
+    let list = |v: &serde_json::Value, key: &str| v[key].as_array().cloned().unwrap_or_default();
+    section(Section::UpNext, "Up next", list(input.orchestrate, "queue").iter().map(|e| {
+        let next = text(e, "next_action");
+        let quiet = if e["effort"]["neglected"].as_bool() == Some(true) { " — gone quiet" } else { "" };
+        format!("**{}**: {}{quiet}", text(e, "project"), if next.is_empty() { text(e, "focus") } else { next })
+    }).collect());
+    section(Section::Blocked, "Blocked", list(input.orchestrate, "blocked").iter()
+        .map(|e| format!("**{}**: {}", text(e, "project"), text(e, "focus")))
+        .collect());
+
+    let mut touched: Vec<String> = list(input.retrospective, "per_project").iter().map(|e| {
+        let titles: Vec<String> = list(e, "titles").iter().filter_map(|t| t.as_str().map(String::from)).take(3).collect();
+        format!("**{}** ({} entr{}, {}): {}", text(e, "project"), e["entries"].as_u64().unwrap_or(0),
+            if e["entries"].as_u64() == Some(1) { "y" } else { "ies" }, text(e, "status_flow"), titles.join("; "))
+    }).collect();
+    touched.sort();
+    section(Section::WhatHappened, "What happened", touched);
+
+    let mut stale: Vec<(i64, String)> = list(input.patterns, "stale_threads").iter()
+        .map(|e| (e["days_stale"].as_i64().unwrap_or(0), format!("**{}**: last entry {} ({} days)", text(e, "project"), text(e, "last_entry"), e["days_stale"].as_i64().unwrap_or(0))))
+        .collect();
+    stale.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    section(Section::StaleThreads, "Stale threads", stale.into_iter().map(|(_, line)| line).collect());
+    section(Section::RecurringBlockers, "Recurring blockers", list(input.patterns, "recurring_blockers").iter()
+        .map(|e| format!("**{}**: blocked in {} entries", text(e, "project"), e["count"].as_u64().unwrap_or(0)))
+        .collect());
+
+    if let Some(usage) = input.usage {
+        let mut lines = vec![format!("{} tool calls", usage.tool_calls)];
+        let mut actions: Vec<(&String, &i64)> = usage.calls_by_action.iter().collect();
+        actions.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        if !actions.is_empty() {
+            lines.push(format!("Top actions: {}", actions.iter().take(5).map(|(a, n)| format!("{a} ({n})")).collect::<Vec<_>>().join(", ")));
+        }
+        for (action, counts) in &usage.searches {
+            if let Some(rate) = counts.hit_rate() {
+                lines.push(format!("{action} found results {:.0}% of the time ({} of {})", rate * 100.0, counts.hits, counts.hits + counts.misses));
+            }
+        }
+        let syncs: i64 = usage.syncs_by_project.iter().filter(|(p, _)| p.starts_with(&format!("{domain}/"))).map(|(_, n)| n).sum();
+        lines.push(format!("{syncs} sync{} in {domain}", if syncs == 1 { "" } else { "s" }));
+        section(Section::Usage, "Usage", lines);
+    }
+    let vars: Vars = [("domain", domain.to_string()), ("date", date.to_string()), ("since", since.to_string()), ("sections", out)]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+    template::render(DIGEST_TEMPLATE, &vars)
+}
+
+/// Every minute, run `generate` for each subscription whose schedule has fired since that
+/// domain's last digest. A fire missed while the machine slept runs at the next check. A
+/// domain without a recorded run waits for its next scheduled time. `subscriptions` is asked
+/// again each minute, so domains added to the vault are picked up. `generate` returns the
+/// written digest's path, or an error, for logging.
+pub async fn run_digest_loop<S, F>(config_dir: PathBuf, subscriptions: S, generate: F)
+where
+    S: Fn() -> Vec<Subscription> + Clone + Send + 'static,
+    F: Fn(&Subscription) -> Result<PathBuf, String> + Clone + Send + 'static,
+{
+    let started = Utc::now();
+    loop {
+        let (subscriptions, generate, config_dir) = (subscriptions.clone(), generate.clone(), config_dir.clone());
+        let tick = move || {
+            for sub in subscriptions() {
+                let since = last_run(&config_dir, &sub.domain).unwrap_or(started);
+                let due = sub.schedule.next_after(since.with_timezone(&Local).naive_local())
+                    .is_some_and(|next| next <= Local::now().naive_local());
+                if !due {
+                    continue;
+                }
+                match generate(&sub) {
+                    Ok(path) => eprintln!("wardwell: digest written to {}", path.display()),
+                    Err(e) => eprintln!("wardwell: digest failed: {e}"),
+                }
+                if let Err(e) = record_run(&config_dir, &sub.domain, Utc::now()) {
+                    eprintln!("wardwell: could not record digest run: {e}");
+                }
+            }
+        };
+        if let Err(e) = tokio::task::spawn_blocking(tick).await {
+            eprintln!("wardwell: digest task panicked: {e}");
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn schedules_find_their_next_minute() {
+        let daily = Schedule::parse("0 7 * * *").unwrap();
+        assert_eq!(daily, Schedule::default());
+        assert_eq!(daily.next_after(at("2026-03-02 06:59")), Some(at("2026-03-02 07:00")));
+        assert_eq!(daily.next_after(at("2026-03-02 07:00")), Some(at("2026-03-03 07:00")));
+
+        // Mondays at 08:30; 2026-03-02 is a Monday
+        let weekly = Schedule::parse("30 8 * * 1").unwrap();
+        assert_eq!(weekly.next_after(at("2026-03-02 09:00")), Some(at("2026-03-09 08:30")));
+        assert_eq!(Schedule::parse("@weekly").unwrap().next_after(at("2026-03-02 09:00")), Some(at("2026-03-08 00:00")));
+
+        let steps = Schedule::parse("*/20 9-17 * * 1-5").unwrap();
+        assert_eq!(steps.next_after(at("2026-03-06 17:45")), Some(at("2026-03-09 09:00")));
+
+        assert!(Schedule::parse("0 7 * *").is_err());
+        assert!(Schedule::parse("0 24 * * *").is_err());
+        assert!(Schedule::parse("0 7 * * 5-1").is_err());
+    }
+
+    #[test]
+    fn render_covers_each_source_and_latest_finds_the_newest() {
+        let orchestrate = serde_json::json!({
+            "queue": [{"domain": "work", "project": "api", "focus": "Auth", "next_action": "Ship the login page", "effort": {"neglected": true}}],
+            "blocked": [{"domain": "work", "project": "billing", "focus": "Waiting on finance"}],
+        });
+        let retrospective = serde_json::json!({
+            "per_project": [{"project": "work/api", "entries": 2, "status_flow": "active", "titles": ["Login form", "Session tokens"]}],
+        });
+        let patterns = serde_json::json!({
+            "stale_threads": [{"project": "work/docs", "last_entry": "2026-02-01", "days_stale": 29}],
+        });
+        let usage = MetricsReport { tool_calls: 12, ..Default::default() };
+        let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let input = DigestInput {
+            domain: "work", date, since: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            orchestrate: &orchestrate, retrospective: &retrospective, patterns: &patterns, usage: Some(&usage),
+            sections: &[],
+        };
+        let md = render(&input);
+        assert!(md.contains("# work digest, 2026-03-02"), "{md}");
+        assert!(md.contains("- **api**: Ship the login page — gone quiet\n"), "{md}");
+        assert!(md.contains("## Blocked\n- **billing**: Waiting on finance\n"), "{md}");
+        assert!(md.contains("- **work/api** (2 entries, active): Login form; Session tokens\n"), "{md}");
+        assert!(md.contains("- **work/docs**: last entry 2026-02-01 (29 days)\n"), "{md}");
+        assert!(md.contains("## Usage\n- 12 tool calls\n"), "{md}");
+        assert!(!md.contains("## Recurring blockers"), "{md}");
+
+        let picked = render(&DigestInput { sections: &[Section::Blocked, Section::Usage], ..input });
+        assert!(picked.contains("## Blocked") && picked.contains("## Usage"), "{picked}");
+        assert!(!picked.contains("## Up next") && !picked.contains("## What happened"), "{picked}");
+
+        let tmp = tempfile::tempdir().unwrap();
+        write(tmp.path(), DIGEST_DIR, date, &md).unwrap();
+        write(tmp.path(), DIGEST_DIR, NaiveDate::from_ymd_opt(2026, 2, 23).unwrap(), "older").unwrap();
+        let (newest, path) = latest(tmp.path(), DIGEST_DIR).unwrap();
+        assert_eq!(newest, date);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), md);
+        assert!(latest(tmp.path(), "_weekly").is_none());
+    }
+
+    #[test]
+    fn runs_are_kept_per_domain_after_the_shared_one() {
+        let tmp = tempfile::tempdir().unwrap();
+        let shared = Utc::now() - chrono::Duration::days(3);
+        std::fs::write(tmp.path().join(STATE_FILE), shared.to_rfc3339()).unwrap();
+        let now = Utc::now();
+        record_run(tmp.path(), "work", now).unwrap();
+
+        assert_eq!(last_run(tmp.path(), "work").map(|t| t.timestamp()), Some(now.timestamp()));
+        assert_eq!(last_run(tmp.path(), "personal").map(|t| t.timestamp()), Some(shared.timestamp()));
+
+        assert_eq!(Schedule::parse("0 8 * * 1").unwrap().period_days(at("2026-03-02 06:00")), 7);
+        assert_eq!(Schedule::default().period_days(at("2026-03-02 06:00")), 1);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::daemon::indexer::ConversationMessage;
+use serde::Serialize;
+
+/// Longest quote returned in a citation, in characters.
+const MAX_QUOTE_CHARS: usize = 160;
+
+/// Minimum shared keywords between a point and a sentence for it to count as support.
+const MIN_OVERLAP: usize = 2;
+
+/// A verbatim transcript quote backing one line of a summary.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Citation {
+    /// The summary line this supports.
+    pub point: String,
+    /// Zero-based message index, as used by the `excerpt` action.
+    pub message_index: usize,
+    pub role: String,
+    pub quote: String,
+}
+
+/// One message returned by the `excerpt` action.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcerptMessage {
+    pub index: usize,
+    pub role: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Pick up to `max` summary lines and, for each, the transcript sentence sharing the most
+/// keywords with it. Purely lexical — no model call — so quotes are always verbatim.
+pub fn cite(summary: &str, conversation: &[ConversationMessage], max: usize) -> Vec<Citation> {
+    let sentences: Vec<(usize, &str, Vec<String>)> = conversation.iter().enumerate()
+        .flat_map(|(i, m)| split_sentences(&m.text).into_iter().map(move |s| (i, s)))
+        .map(|(i, s)| (i, s, keywords(s)))
+        .collect();
+
+    let mut scored: Vec<(usize, Citation)> = Vec::new();
+    for point in summary_points(summary) {
+        let point_words = keywords(&point);
+        if point_words.len() < MIN_OVERLAP {
+            continue;
+        }
+        let best = sentences.iter()
+            .map(|(i, s, words)| (words.iter().filter(|w| point_words.contains(w)).count(), *i, *s))
+            .filter(|(score, _, _)| *score >= MIN_OVERLAP)
+            // Highest overlap wins; earliest message breaks ties
+            .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        if let Some((score, index, sentence)) = best {
+            let quote = truncate(sentence.trim(), MAX_QUOTE_CHARS);
+            if scored.iter().any(|(_, c)| c.message_index == index && c.quote == quote) {
+                continue;
+            }
+            scored.push((score, Citation {
+                point,
+                message_index: index,
+                role: conversation.get(index).map(|m| m.role.clone()).unwrap_or_default(),
+                quote,
+            }));
+        }
+    }
+
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    let mut citations: Vec<Citation> = scored.into_iter().take(max).map(|(_, c)| c).collect();
+    citations.sort_by_key(|c| c.message_index);
+    citations
+}
+
+/// Messages `start..=end` (clamped to the conversation), each capped at `max_chars`.
+pub fn message_range(
+    conversation: &[ConversationMessage],
+    start: usize,
+    end: usize,
+    max_chars: usize,
+) -> Vec<ExcerptMessage> {
+    let end = end.min(conversation.len().saturating_sub(1));
+    if conversation.is_empty() || start > end {
+        return Vec::new();
+    }
+    conversation[start..=end].iter().enumerate()
+        .map(|(offset, m)| {
+            let truncated = m.text.chars().count() > max_chars;
+            ExcerptMessage {
+                index: start + offset,
+                role: m.role.clone(),
+                text: if truncated { truncate(&m.text, max_chars) } else { m.text.clone() },
+                truncated,
+            }
+        })
+        .collect()
+}
+
+/// Content lines of a summary: bullets and prose, without headings or markup.
+fn summary_points(summary: &str) -> Vec<String> {
+    let mut points: Vec<String> = Vec::new();
+    for line in summary.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("---") {
+            continue;
+        }
+        let text = line
+            .trim_start_matches(['-', '*', '+'])
+            .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == ')')
+            .trim()
+            .replace("**", "");
+        if !text.is_empty() && !points.contains(&text) {
+            points.push(text);
+        }
+    }
+    points
+}
+
+fn split_sentences(text: &str) -> Vec<&str> {
+    text.split(['\n', '.', '!', '?'])
+        .map(str::trim)
+        .filter(|s| s.len() >= 12)
+        .collect()
+}
+
+fn keywords(text: &str) -> Vec<String> {
+    const STOPWORDS: &[&str] = &[
+        "this", "that", "with", "from", "have", "were", "will", "what", "when", "which",
+        "them", "then", "they", "their", "there", "into", "also", "been", "about", "would",
+        "should", "could", "just", "some", "more", "than", "only", "over", "after", "before",
+    ];
+    let mut words: Vec<String> = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        let w = word.to_lowercase();
+        if w.chars().count() > 3 && !STOPWORDS.contains(&w.as_str()) && !words.contains(&w) {
+            words.push(w);
+        }
+    }
+    words
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, text: &str) -> ConversationMessage {
+        ConversationMessage { role: role.to_string(), text: text.to_string() }
+    }
+
+    #[test]
+    fn cite_finds_supporting_sentences() {
+        let conversation = vec![
+            msg("user", "Can you look at why the webhook retries keep failing?"),
+            msg("assistant", "The webhook retries fail because the signing secret rotated. I updated the secret in staging."),
+            msg("user", "Great, now add a regression test for signature validation."),
+        ];
+        let summary = "## Summary\n- Webhook retries failed because the signing secret rotated\n- Added a regression test for signature validation\n";
+        let citations = cite(summary, &conversation, 5);
+
+        assert_eq!(citations.len(), 2);
+        assert_eq!(citations[0].message_index, 1);
+        assert_eq!(citations[0].role, "assistant");
+        assert!(citations[0].quote.contains("signing secret rotated"));
+        assert_eq!(citations[1].message_index, 2);
+    }
+
+    #[test]
+    fn cite_skips_unsupported_points_and_respects_max() {
+        let conversation = vec![msg("user", "Deploy the billing service to production tonight")];
+        let summary = "- Deploy the billing service to production\n- Weather was nice in Lisbon apparently\n";
+        assert_eq!(cite(summary, &conversation, 5).len(), 1);
+        assert!(cite(summary, &conversation, 0).is_empty());
+    }
+
+    #[test]
+    fn message_range_clamps_and_truncates() {
+        let conversation = vec![msg("user", "first message"), msg("assistant", &"x".repeat(50))];
+        let range = message_range(&conversation, 0, 99, 10);
+        assert_eq!(range.len(), 2);
+        assert_eq!(range[1].index, 1);
+        assert!(range[1].truncated);
+        assert_eq!(range[1].text.chars().count(), 10);
+        assert!(message_range(&conversation, 5, 9, 10).is_empty());
+    }
+}
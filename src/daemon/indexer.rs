@@ -1,7 +1,8 @@
 use rusqlite::Connection;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::io::BufRead;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::{Mutex, MutexGuard};
 
 /// Errors from session indexing.
@@ -80,6 +81,7 @@ impl SessionStore {
                 indexed_at TEXT NOT NULL
             );"
         )?;
+        migrate(&conn)?;
 
         Ok(Self { conn: Mutex::new(conn) })
     }
@@ -103,6 +105,7 @@ impl SessionStore {
                 indexed_at TEXT NOT NULL
             );"
         )?;
+        migrate(&conn)?;
         Ok(Self { conn: Mutex::new(conn) })
     }
 
@@ -124,6 +127,19 @@ impl SessionStore {
         if existing_hash.as_deref() == Some(meta.file_hash.as_str()) {
             return Ok(false);
         }
+        // Retention pruned it; only new activity in the transcript brings it back
+        let pruned_hash: Option<String> = conn.query_row(
+            "SELECT file_hash FROM pruned_sessions WHERE session_id = ?1",
+            rusqlite::params![meta.session_id],
+            |row| row.get(0),
+        ).ok();
+        match pruned_hash {
+            Some(hash) if hash == meta.file_hash => return Ok(false),
+            Some(_) => {
+                conn.execute("DELETE FROM pruned_sessions WHERE session_id = ?1", rusqlite::params![meta.session_id])?;
+            }
+            None => {}
+        }
 
         let indexed_at = chrono::Utc::now().to_rfc3339();
         conn.execute(
@@ -149,11 +165,14 @@ impl SessionStore {
         let conn = self.lock()?;
         let mut stmt = conn.prepare(
             "SELECT session_id, project_dir, project_path, domain, user_message_count, file_size
-             FROM sessions WHERE summarized = 0
+             FROM sessions
+             WHERE summarized = 0
+               AND (summary_retry_after IS NULL OR summary_retry_after <= ?1)
              ORDER BY last_message_at DESC"
         )?;
 
-        let rows = stmt.query_map([], |row| {
+        let now = chrono::Utc::now().to_rfc3339();
+        let rows = stmt.query_map(rusqlite::params![now], |row| {
             Ok(UnsummarizedSession {
                 session_id: row.get(0)?,
                 project_dir: row.get(1)?,
@@ -175,19 +194,311 @@ impl SessionStore {
     pub fn mark_summarized(&self, session_id: &str) -> Result<(), SessionError> {
         let conn = self.lock()?;
         conn.execute(
-            "UPDATE sessions SET summarized = 1 WHERE session_id = ?1",
+            "UPDATE sessions SET summarized = 1, skip_reason = NULL WHERE session_id = ?1",
             rusqlite::params![session_id],
         )?;
         Ok(())
     }
 
+    /// Mark a session done without a summary, recording why. Cleared if the transcript grows.
+    pub fn mark_skipped(&self, session_id: &str, reason: &str) -> Result<(), SessionError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "UPDATE sessions SET summarized = 1, skip_reason = ?1 WHERE session_id = ?2",
+            rusqlite::params![reason, session_id],
+        )?;
+        Ok(())
+    }
+
+    /// The most recent sessions and where each stands with the summarizer, newest first.
+    /// With `skipped_only`, just those the summarizer passed over.
+    pub fn session_statuses(&self, limit: usize, skipped_only: bool) -> Result<Vec<SessionStatus>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT session_id, project_path, domain, last_message_at, user_message_count,
+                    summarized, skip_reason, summary_attempts, summary_retry_after
+             FROM sessions
+             WHERE (?1 = 0 OR skip_reason IS NOT NULL)
+             ORDER BY last_message_at DESC
+             LIMIT ?2"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![i64::from(skipped_only), limit as i64], |row| {
+            Ok(SessionStatus {
+                session_id: row.get(0)?,
+                project_path: row.get(1)?,
+                domain: row.get(2)?,
+                last_message_at: row.get(3)?,
+                user_message_count: row.get(4)?,
+                summarized: row.get::<_, i64>(5)? != 0,
+                skip_reason: row.get(6)?,
+                summary_attempts: row.get(7)?,
+                retry_after: row.get(8)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Record a failed summary attempt and push the next retry out with exponential backoff:
+    /// `base * 2^(attempts-1)`, capped at `max`. Returns the new attempt count.
+    pub fn record_summary_failure(
+        &self,
+        session_id: &str,
+        base: std::time::Duration,
+        max: std::time::Duration,
+    ) -> Result<i64, SessionError> {
+        let conn = self.lock()?;
+        let attempts: i64 = conn.query_row(
+            "SELECT summary_attempts FROM sessions WHERE session_id = ?1",
+            rusqlite::params![session_id],
+            |row| row.get(0),
+        ).unwrap_or(0) + 1;
+        let delay = retry_delay(attempts, base, max);
+        let retry_after = chrono::Utc::now()
+            + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX);
+        conn.execute(
+            "UPDATE sessions SET summary_attempts = ?1, summary_retry_after = ?2 WHERE session_id = ?3",
+            rusqlite::params![attempts, retry_after.to_rfc3339(), session_id],
+        )?;
+        Ok(attempts)
+    }
+
+    /// Number of unsummarized sessions currently waiting out a retry backoff.
+    pub fn backoff_count(&self) -> Result<i64, SessionError> {
+        let conn = self.lock()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE summarized = 0 AND summary_retry_after > ?1",
+            rusqlite::params![now],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Read a daemon state value (e.g. summarizer circuit breaker fields).
+    pub fn get_state(&self, key: &str) -> Result<Option<String>, SessionError> {
+        let conn = self.lock()?;
+        let value: Option<String> = conn.query_row(
+            "SELECT value FROM daemon_state WHERE key = ?1",
+            rusqlite::params![key],
+            |row| row.get(0),
+        ).ok();
+        Ok(value)
+    }
+
+    /// Write a daemon state value.
+    pub fn set_state(&self, key: &str, value: &str) -> Result<(), SessionError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO daemon_state (key, value) VALUES (?1, ?2)",
+            rusqlite::params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Sessions matching `filter`, summarized or not, newest first.
+    pub fn sessions_matching(&self, filter: &SessionFilter) -> Result<Vec<UnsummarizedSession>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT session_id, project_dir, project_path, domain, user_message_count, file_size
+             FROM sessions
+             WHERE (?1 IS NULL OR summarized = 0)
+               AND (?2 IS NULL OR domain = ?2)
+               AND (?3 IS NULL OR last_message_at >= ?3)
+             ORDER BY last_message_at DESC"
+        )?;
+        let pending_only: Option<i64> = (!filter.include_summarized).then_some(1);
+        let rows = stmt.query_map(rusqlite::params![pending_only, filter.domain, filter.since], |row| {
+            Ok(UnsummarizedSession {
+                session_id: row.get(0)?,
+                project_dir: row.get(1)?,
+                project_path: row.get(2)?,
+                domain: row.get(3)?,
+                user_message_count: row.get(4)?,
+                file_size: row.get(5)?,
+            })
+        })?;
+
+        let mut results = Vec::new();
+        for r in rows.flatten() {
+            if filter.matches_project(&r.project_path) {
+                results.push(r);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Every indexed session with whether it has been summarized, oldest first.
+    pub fn all_sessions(&self) -> Result<Vec<(SessionMeta, bool)>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT session_id, project_dir, project_path, domain, message_count, user_message_count,
+                    assistant_message_count, first_message_at, last_message_at, file_size, file_hash, summarized
+             FROM sessions
+             ORDER BY first_message_at"
+        )?;
+        let rows = stmt.query_map([], meta_row)?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Sessions active at some point between `from` and `until` (ISO timestamps or dates,
+    /// `until` exclusive), with whether each has been summarized, oldest first.
+    pub fn sessions_between(&self, from: &str, until: &str) -> Result<Vec<(SessionMeta, bool)>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT session_id, project_dir, project_path, domain, message_count, user_message_count,
+                    assistant_message_count, first_message_at, last_message_at, file_size, file_hash, summarized
+             FROM sessions
+             WHERE first_message_at IS NOT NULL AND first_message_at < ?2
+               AND COALESCE(last_message_at, first_message_at) >= ?1
+             ORDER BY first_message_at"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![from, until], meta_row)?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Sessions with known start and end times that ended on or after `since`, oldest first.
+    pub fn session_spans(&self, since: &str) -> Result<Vec<SessionSpan>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT project_path, domain, first_message_at, last_message_at
+             FROM sessions
+             WHERE first_message_at IS NOT NULL AND last_message_at >= ?1
+             ORDER BY first_message_at"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok(SessionSpan {
+                project_path: row.get(0)?,
+                domain: row.get(1)?,
+                first_message_at: row.get(2)?,
+                last_message_at: row.get(3)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+
+    /// Mark specific sessions unsummarized and clear their retry backoff.
+    pub fn reset_sessions(&self, session_ids: &[String]) -> Result<usize, SessionError> {
+        let conn = self.lock()?;
+        let mut count = 0;
+        for id in session_ids {
+            count += conn.execute(
+                "UPDATE sessions SET summarized = 0, skip_reason = NULL, summary_attempts = 0, summary_retry_after = NULL
+                 WHERE session_id = ?1",
+                rusqlite::params![id],
+            )?;
+        }
+        Ok(count)
+    }
+
     /// Reset all sessions to unsummarized state.
     pub fn reset_summarized(&self) -> Result<usize, SessionError> {
         let conn = self.lock()?;
-        let count = conn.execute("UPDATE sessions SET summarized = 0 WHERE summarized = 1", [])?;
+        let count = conn.execute("UPDATE sessions SET summarized = 0, skip_reason = NULL WHERE summarized = 1", [])?;
         Ok(count)
     }
 
+    /// Record the working directory a session started in, as its SessionStart hook reported
+    /// it. Indexing prefers this to the path decoded from the transcript folder's name, which
+    /// can't tell a `-` in a directory name from a `/`.
+    pub fn link_session(&self, session_id: &str, cwd: &str, source: Option<&str>, model: Option<&str>) -> Result<(), SessionError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO session_links (session_id, cwd, source, model, linked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![session_id, cwd, source, model, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Working directories recorded by `link_session`, by session id.
+    pub fn session_links(&self) -> Result<HashMap<String, String>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT session_id, cwd FROM session_links")?;
+        let links = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(links)
+    }
+
+    /// Forget every session recorded from a transcript folder. Returns how many there were.
+    pub fn remove_project_dir(&self, project_dir: &str) -> Result<usize, SessionError> {
+        let conn = self.lock()?;
+        let count = conn.execute("DELETE FROM sessions WHERE project_dir = ?1", rusqlite::params![project_dir])?;
+        Ok(count)
+    }
+
+    /// The ID of every session in the index.
+    pub fn session_ids(&self) -> Result<HashSet<String>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT session_id FROM sessions")?;
+        let ids = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
+    /// Sessions whose last message, or indexing when they have none, is before `cutoff`
+    /// (an RFC 3339 date or timestamp).
+    pub fn sessions_before(&self, cutoff: &str) -> Result<Vec<String>, SessionError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT session_id FROM sessions WHERE COALESCE(last_message_at, indexed_at) < ?1 ORDER BY session_id")?;
+        let ids = stmt.query_map(rusqlite::params![cutoff], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
+    /// Forget `session_ids` and their links, keeping each transcript's hash so an unchanged
+    /// transcript isn't indexed (and summarized) again. Returns how many were removed.
+    pub fn prune_sessions(&self, session_ids: &[String]) -> Result<usize, SessionError> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let mut removed = 0;
+        for id in session_ids {
+            tx.execute(
+                "INSERT OR REPLACE INTO pruned_sessions (session_id, file_hash, pruned_at)
+                 SELECT session_id, file_hash, ?2 FROM sessions WHERE session_id = ?1",
+                rusqlite::params![id, now],
+            )?;
+            removed += tx.execute("DELETE FROM sessions WHERE session_id = ?1", rusqlite::params![id])?;
+            tx.execute("DELETE FROM session_links WHERE session_id = ?1", rusqlite::params![id])?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Rebuild sessions.db without its free pages and fold the WAL back in.
+    pub fn vacuum(&self) -> Result<(), SessionError> {
+        let conn = self.lock()?;
+        conn.execute_batch("VACUUM")?;
+        // The checkpoint reports its progress as a row
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    /// How many sessions the summarizer has finished, skipped, and still has to do.
+    pub fn summary_counts(&self) -> Result<SummaryCounts, SessionError> {
+        let conn = self.lock()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let counts = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(summarized = 1 AND skip_reason IS NULL), 0),
+                    COALESCE(SUM(skip_reason IS NOT NULL), 0),
+                    COALESCE(SUM(summarized = 0), 0),
+                    COALESCE(SUM(summarized = 0 AND summary_retry_after > ?1), 0)
+             FROM sessions",
+            rusqlite::params![now],
+            |row| Ok(SummaryCounts {
+                total: row.get(0)?,
+                summarized: row.get(1)?,
+                skipped: row.get(2)?,
+                unsummarized: row.get(3)?,
+                backing_off: row.get(4)?,
+            }),
+        )?;
+        Ok(counts)
+    }
+
     /// Get total session count.
     pub fn count(&self) -> Result<i64, SessionError> {
         let conn = self.lock()?;
@@ -196,7 +507,92 @@ impl SessionStore {
     }
 }
 
-#[derive(Debug)]
+/// Add columns and tables introduced after the original sessions schema.
+fn migrate(conn: &Connection) -> Result<(), SessionError> {
+    let mut stmt = conn.prepare("PRAGMA table_info(sessions)")?;
+    let columns: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .collect();
+    drop(stmt);
+
+    if !columns.iter().any(|c| c == "summary_attempts") {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN summary_attempts INTEGER NOT NULL DEFAULT 0;")?;
+    }
+    if !columns.iter().any(|c| c == "summary_retry_after") {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN summary_retry_after TEXT;")?;
+    }
+    if !columns.iter().any(|c| c == "skip_reason") {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN skip_reason TEXT;")?;
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS daemon_state (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS session_links (
+            session_id TEXT PRIMARY KEY,
+            cwd TEXT NOT NULL,
+            source TEXT,
+            model TEXT,
+            linked_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS pruned_sessions (
+            session_id TEXT PRIMARY KEY,
+            file_hash TEXT NOT NULL,
+            pruned_at TEXT NOT NULL
+        );"
+    )?;
+    Ok(())
+}
+
+/// Backoff before retry number `attempts + 1`: `base * 2^(attempts-1)`, capped at `max`.
+pub fn retry_delay(attempts: i64, base: std::time::Duration, max: std::time::Duration) -> std::time::Duration {
+    let exp = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    base.saturating_mul(2u32.saturating_pow(exp)).min(max)
+}
+
+/// A `sessions` row selected with every column plus `summarized`, in schema order.
+fn meta_row(row: &rusqlite::Row) -> rusqlite::Result<(SessionMeta, bool)> {
+    Ok((SessionMeta {
+        session_id: row.get(0)?,
+        project_dir: row.get(1)?,
+        project_path: row.get(2)?,
+        domain: row.get(3)?,
+        message_count: row.get(4)?,
+        user_message_count: row.get(5)?,
+        assistant_message_count: row.get(6)?,
+        first_message_at: row.get(7)?,
+        last_message_at: row.get(8)?,
+        file_size: row.get(9)?,
+        file_hash: row.get(10)?,
+    }, row.get::<_, i64>(11)? != 0))
+}
+
+/// Which sessions a bulk summarize run covers.
+#[derive(Debug, Clone, Default)]
+pub struct SessionFilter {
+    pub domain: Option<String>,
+    /// Matched against the last component of the session's project path, slugified.
+    pub project: Option<String>,
+    /// Sessions whose last message is on or after this date (YYYY-MM-DD).
+    pub since: Option<String>,
+    /// Include sessions that already have a summary.
+    pub include_summarized: bool,
+}
+
+impl SessionFilter {
+    fn matches_project(&self, project_path: &str) -> bool {
+        let Some(ref want) = self.project else {
+            return true;
+        };
+        let name = project_path.trim_end_matches('/').rsplit('/').next().unwrap_or(project_path);
+        crate::vault::project::slugify(name) == crate::vault::project::slugify(want)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct UnsummarizedSession {
     pub session_id: String,
     pub project_dir: String,
@@ -206,6 +602,43 @@ pub struct UnsummarizedSession {
     pub file_size: i64,
 }
 
+/// A session's summarizer state, for `wardwell sessions`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionStatus {
+    pub session_id: String,
+    pub project_path: String,
+    pub domain: Option<String>,
+    pub last_message_at: Option<String>,
+    pub user_message_count: i64,
+    /// Summarized or skipped; either way the summarizer is done with it.
+    pub summarized: bool,
+    /// Why the summarizer passed over the session, when it did.
+    pub skip_reason: Option<String>,
+    pub summary_attempts: i64,
+    /// Failed summaries wait until this time before the next try.
+    pub retry_after: Option<String>,
+}
+
+/// Session totals by summarizer state, for `wardwell status`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct SummaryCounts {
+    pub total: i64,
+    pub summarized: i64,
+    pub skipped: i64,
+    pub unsummarized: i64,
+    /// Unsummarized sessions waiting out a retry backoff.
+    pub backing_off: i64,
+}
+
+/// When a session ran, for effort metrics.
+#[derive(Debug)]
+pub struct SessionSpan {
+    pub project_path: String,
+    pub domain: Option<String>,
+    pub first_message_at: String,
+    pub last_message_at: String,
+}
+
 /// Stats from an indexing run.
 #[derive(Debug, Default)]
 pub struct IndexStats {
@@ -213,22 +646,29 @@ pub struct IndexStats {
     pub indexed: usize,
     pub skipped: usize,
     pub errors: usize,
+    /// Sessions dropped from the store because their folder is now under `session_exclude`.
+    pub excluded: usize,
 }
 
-/// Walk all session sources and index session metadata.
+/// daemon_state key holding when `index_sessions` last finished.
+pub const STATE_LAST_INDEXED: &str = "sessions_last_indexed_at";
+
+/// Walk all session sources and index session metadata. Sessions a scoped source doesn't
+/// admit are left out, and ones under `session_exclude` are removed if indexed before.
 pub fn index_sessions(
-    session_sources: &[PathBuf],
+    session_sources: &[crate::config::loader::SessionSource],
     store: &SessionStore,
     domains: &[crate::domain::model::Domain],
 ) -> Result<IndexStats, SessionError> {
     let mut stats = IndexStats::default();
+    let links = store.session_links()?;
 
     for source in session_sources {
-        if !source.exists() {
+        if !source.path.exists() {
             continue;
         }
 
-        let entries = match std::fs::read_dir(source) {
+        let entries = match std::fs::read_dir(&source.path) {
             Ok(e) => e,
             Err(_) => continue,
         };
@@ -242,8 +682,16 @@ pub fn index_sessions(
             let project_dir_name = entry.file_name().to_string_lossy().to_string();
             let project_path = decode_project_dir(&project_dir_name);
 
+            if source.excludes(&project_path) {
+                stats.excluded += store.remove_project_dir(&project_dir_name)?;
+                continue;
+            }
+
             // Resolve domain from project path
             let domain = resolve_domain(&project_path, domains);
+            if !source.admits(&project_path, domain.as_deref()) {
+                continue;
+            }
 
             // Find all .jsonl files in this project dir
             let jsonl_entries = match std::fs::read_dir(&project_dir_path) {
@@ -256,31 +704,107 @@ pub fn index_sessions(
                 if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
                     continue;
                 }
-
-                let session_id = path
-                    .file_stem()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .unwrap_or_default();
-
-                stats.scanned += 1;
-
-                match extract_session_meta(&path, &session_id, &project_dir_name, &project_path, &domain) {
-                    Ok(meta) => {
-                        match store.upsert(&meta) {
-                            Ok(true) => stats.indexed += 1,
-                            Ok(false) => stats.skipped += 1,
-                            Err(_) => stats.errors += 1,
-                        }
-                    }
-                    Err(_) => stats.errors += 1,
-                }
+                index_session_file(&path, &project_dir_name, &project_path, &domain, &links, store, domains, &mut stats);
             }
         }
     }
 
+    store.set_state(STATE_LAST_INDEXED, &chrono::Utc::now().to_rfc3339())?;
     Ok(stats)
 }
 
+/// Index just the transcripts at `paths`, as `index_sessions` would: the ones the session
+/// watcher reported, without walking every source again. Paths that are gone, aren't
+/// `<source>/<project dir>/<id>.jsonl`, or that their source doesn't admit are passed over;
+/// the next full run removes excluded sessions.
+pub fn index_session_paths(
+    paths: &[std::path::PathBuf],
+    session_sources: &[crate::config::loader::SessionSource],
+    store: &SessionStore,
+    domains: &[crate::domain::model::Domain],
+) -> Result<IndexStats, SessionError> {
+    let mut stats = IndexStats::default();
+    let links = store.session_links()?;
+    let roots: Vec<(std::path::PathBuf, &crate::config::loader::SessionSource)> = session_sources.iter()
+        .map(|source| (source.path.canonicalize().unwrap_or_else(|_| source.path.clone()), source))
+        .collect();
+
+    for path in paths {
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") || !path.is_file() {
+            continue;
+        }
+        let Some(project_dir) = path.parent() else { continue };
+        let Some(root) = project_dir.parent() else { continue };
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let Some((_, source)) = roots.iter().find(|(r, _)| *r == root) else { continue };
+
+        let project_dir_name = project_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let project_path = decode_project_dir(&project_dir_name);
+        if source.excludes(&project_path) {
+            continue;
+        }
+        let domain = resolve_domain(&project_path, domains);
+        if !source.admits(&project_path, domain.as_deref()) {
+            continue;
+        }
+        index_session_file(path, &project_dir_name, &project_path, &domain, &links, store, domains, &mut stats);
+    }
+
+    Ok(stats)
+}
+
+/// Index one transcript into `store`, counting the outcome in `stats`. `project_path` and
+/// `domain` come from the folder name, and give way to the hook's cwd when `links` has it.
+#[allow(clippy::too_many_arguments)]
+fn index_session_file(
+    path: &Path,
+    project_dir_name: &str,
+    project_path: &str,
+    domain: &Option<String>,
+    links: &HashMap<String, String>,
+    store: &SessionStore,
+    domains: &[crate::domain::model::Domain],
+    stats: &mut IndexStats,
+) {
+    let session_id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    stats.scanned += 1;
+
+    // The hook's cwd is exact where the folder name is not
+    let linked = links.get(&session_id);
+    let project_path = linked.map_or(project_path, String::as_str);
+    let domain = linked.and_then(|cwd| resolve_domain(cwd, domains)).or_else(|| domain.clone());
+
+    match extract_session_meta(path, &session_id, project_dir_name, project_path, &domain) {
+        Ok(meta) => {
+            match store.upsert(&meta) {
+                Ok(true) => stats.indexed += 1,
+                Ok(false) => stats.skipped += 1,
+                Err(_) => stats.errors += 1,
+            }
+        }
+        Err(_) => stats.errors += 1,
+    }
+}
+
+/// Transcript folders, across all sources, that `session_exclude` keeps out. Folder names
+/// rather than decoded paths, since decoding can't tell `-` from `/`.
+pub fn excluded_project_dirs(session_sources: &[crate::config::loader::SessionSource]) -> Vec<String> {
+    let mut excluded: Vec<String> = session_sources.iter()
+        .filter(|source| !source.exclude.is_empty())
+        .flat_map(|source| std::fs::read_dir(&source.path).into_iter().flatten().flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| source.excludes(&decode_project_dir(name))))
+        .collect();
+    excluded.sort();
+    excluded.dedup();
+    excluded
+}
+
 /// Decode a claude project directory name back to a path.
 /// `-Users-jack-Code-wardwell` → `/Users/jack/Code/wardwell`
 pub fn decode_project_dir(dir_name: &str) -> String {
@@ -291,8 +815,14 @@ pub fn decode_project_dir(dir_name: &str) -> String {
     }
 }
 
+/// How Claude Code names the transcript folder for a working directory: every character
+/// but ASCII letters and digits becomes `-`. Unlike `decode_project_dir`, this is exact.
+pub fn encode_project_dir(path: &str) -> String {
+    path.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect()
+}
+
 /// Resolve which domain a project path belongs to.
-fn resolve_domain(project_path: &str, domains: &[crate::domain::model::Domain]) -> Option<String> {
+pub(crate) fn resolve_domain(project_path: &str, domains: &[crate::domain::model::Domain]) -> Option<String> {
     let path = Path::new(project_path);
     for domain in domains {
         for glob_pat in &domain.paths {
@@ -424,6 +954,34 @@ pub fn extract_conversation(path: &Path) -> Result<Vec<ConversationMessage>, Ses
     Ok(messages)
 }
 
+/// Distinct files a session changed through Edit, MultiEdit, Write, or NotebookEdit tool calls.
+pub fn files_edited(path: &Path) -> Result<usize, SessionError> {
+    let file = std::fs::File::open(path)?;
+    let mut edited = std::collections::HashSet::new();
+    for line in std::io::BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(msg) = serde_json::from_str::<RawMessage>(&line) else {
+            continue;
+        };
+        if msg.r#type != "assistant" {
+            continue;
+        }
+        let Some(serde_json::Value::Array(items)) = msg.message.and_then(|m| m.content) else {
+            continue;
+        };
+        for item in items {
+            let is_edit = item.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+                && matches!(item.get("name").and_then(|n| n.as_str()), Some("Edit" | "MultiEdit" | "Write" | "NotebookEdit"));
+            let target = item.get("input")
+                .and_then(|i| i.get("file_path").or_else(|| i.get("notebook_path")))
+                .and_then(|p| p.as_str());
+            if is_edit && let Some(target) = target {
+                edited.insert(target.to_string());
+            }
+        }
+    }
+    Ok(edited.len())
+}
+
 #[derive(Debug, Clone)]
 pub struct ConversationMessage {
     pub role: String,
@@ -479,6 +1037,11 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_project_dir_replaces_punctuation() {
+        assert_eq!(encode_project_dir("/Users/jack/Code/my-app.v2"), "-Users-jack-Code-my-app-v2");
+    }
+
     #[test]
     fn session_store_open_in_memory() {
         let store = SessionStore::open_in_memory();
@@ -510,6 +1073,32 @@ mod tests {
         assert_eq!(count.ok(), Some(1));
     }
 
+    #[test]
+    fn sessions_between_returns_overlapping_sessions() {
+        let store = SessionStore::open_in_memory().unwrap();
+        let session = |id: &str, first: &str, last: &str| SessionMeta {
+            session_id: id.to_string(),
+            project_dir: "-Users-test".to_string(),
+            project_path: "/Users/test".to_string(),
+            domain: None,
+            message_count: 4,
+            user_message_count: 2,
+            assistant_message_count: 2,
+            first_message_at: Some(first.to_string()),
+            last_message_at: Some(last.to_string()),
+            file_size: 100,
+            file_hash: id.to_string(),
+        };
+        store.upsert(&session("before", "2026-03-01T10:00:00Z", "2026-03-01T11:00:00Z")).unwrap();
+        store.upsert(&session("spans-start", "2026-03-04T23:00:00Z", "2026-03-05T01:00:00Z")).unwrap();
+        store.upsert(&session("inside", "2026-03-06T09:00:00Z", "2026-03-06T10:00:00Z")).unwrap();
+        store.upsert(&session("after", "2026-03-08T09:00:00Z", "2026-03-08T10:00:00Z")).unwrap();
+
+        let ids: Vec<String> = store.sessions_between("2026-03-05", "2026-03-08").unwrap()
+            .into_iter().map(|(m, _)| m.session_id).collect();
+        assert_eq!(ids, vec!["spans-start", "inside"]);
+    }
+
     #[test]
     fn session_store_upsert_skips_unchanged() {
         let store = SessionStore::open_in_memory().unwrap();
@@ -559,6 +1148,216 @@ mod tests {
         assert_eq!(unsumm.len(), 0);
     }
 
+    #[test]
+    fn summary_failure_backs_off_session() {
+        let store = SessionStore::open_in_memory().unwrap();
+        let meta = SessionMeta {
+            session_id: "flaky-1".to_string(),
+            project_dir: "-Users-test".to_string(),
+            project_path: "/Users/test".to_string(),
+            domain: None,
+            message_count: 20,
+            user_message_count: 10,
+            assistant_message_count: 10,
+            first_message_at: None,
+            last_message_at: None,
+            file_size: 2048,
+            file_hash: "2048:22222".to_string(),
+        };
+        store.upsert(&meta).unwrap();
+
+        let minute = std::time::Duration::from_secs(60);
+        assert_eq!(store.record_summary_failure("flaky-1", minute, minute * 60).unwrap(), 1);
+        assert!(store.unsummarized().unwrap().is_empty(), "session should wait out its backoff");
+        assert_eq!(store.backoff_count().unwrap(), 1);
+        assert_eq!(store.record_summary_failure("flaky-1", minute, minute * 60).unwrap(), 2);
+
+        // A changed transcript resets the backoff
+        let changed = SessionMeta { file_hash: "4096:33333".to_string(), ..meta };
+        store.upsert(&changed).unwrap();
+        assert_eq!(store.unsummarized().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn sessions_matching_filters_and_resets() {
+        let store = SessionStore::open_in_memory().unwrap();
+        for (id, path, domain, last) in [
+            ("a", "/Users/test/Code/foo", "work", "2026-01-05T10:00:00Z"),
+            ("b", "/Users/test/Code/foo", "work", "2025-12-20T10:00:00Z"),
+            ("c", "/Users/test/Code/bar", "work", "2026-02-01T10:00:00Z"),
+            ("d", "/Users/test/Code/Foo", "personal", "2026-02-01T10:00:00Z"),
+        ] {
+            store.upsert(&SessionMeta {
+                session_id: id.to_string(),
+                project_dir: path.replace('/', "-"),
+                project_path: path.to_string(),
+                domain: Some(domain.to_string()),
+                message_count: 20,
+                user_message_count: 10,
+                assistant_message_count: 10,
+                first_message_at: Some(last.to_string()),
+                last_message_at: Some(last.to_string()),
+                file_size: 2048,
+                file_hash: format!("2048:{id}"),
+            }).unwrap();
+            store.mark_summarized(id).unwrap();
+        }
+
+        let mut filter = SessionFilter {
+            domain: Some("work".to_string()),
+            project: Some("foo".to_string()),
+            since: Some("2026-01-01".to_string()),
+            include_summarized: false,
+        };
+        assert!(store.sessions_matching(&filter).unwrap().is_empty(), "all are summarized");
+
+        filter.include_summarized = true;
+        let ids: Vec<String> = store.sessions_matching(&filter).unwrap().into_iter().map(|s| s.session_id).collect();
+        assert_eq!(ids, vec!["a"]);
+
+        filter.domain = None;
+        filter.since = None;
+        let ids: Vec<String> = store.sessions_matching(&filter).unwrap().into_iter().map(|s| s.session_id).collect();
+        assert_eq!(ids, vec!["d", "a", "b"]);
+
+        assert_eq!(store.reset_sessions(&ids).unwrap(), 3);
+        assert_eq!(store.unsummarized().unwrap().len(), 3);
+
+        let all = store.all_sessions().unwrap();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].0.session_id, "b");
+        let still_summarized: Vec<&str> = all.iter().filter(|(_, done)| *done).map(|(m, _)| m.session_id.as_str()).collect();
+        assert_eq!(still_summarized, vec!["c"]);
+
+        let spans = store.session_spans("2026-01-01").unwrap();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].project_path, "/Users/test/Code/foo");
+    }
+
+    #[test]
+    fn scoped_session_sources_admit_matching_projects() {
+        use crate::config::loader::SessionSource;
+        use crate::config::types::{DomainName, PathGlob};
+        let tmp = tempfile::tempdir().unwrap();
+        for (dir, id) in [("-Users-test-Work-api", "w1"), ("-Users-test-Home-notes", "h1")] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+            std::fs::write(tmp.path().join(dir).join(format!("{id}.jsonl")), "{\"type\":\"user\"}\n").unwrap();
+        }
+        let domains = vec![crate::domain::model::Domain {
+            name: DomainName::new("work").unwrap(),
+            paths: vec![PathGlob::new("/Users/test/Work/*").unwrap()],
+            aliases: std::collections::HashMap::new(),
+            can_read: Vec::new(),
+            seed_template: None,
+        }];
+        let scoped = vec![SessionSource { domains: vec!["work".to_string()], ..SessionSource::new(tmp.path()) }];
+
+        let store = SessionStore::open_in_memory().unwrap();
+        let stats = index_sessions(&scoped, &store, &domains).unwrap();
+        assert_eq!(stats.scanned, 1);
+        let ids: Vec<String> = store.all_sessions().unwrap().into_iter().map(|(m, _)| m.session_id).collect();
+        assert_eq!(ids, vec!["w1"]);
+
+        let find = |id: &str, sources: &[SessionSource]| crate::daemon::summarizer::find_session_file_by_id(id, sources, &domains);
+        assert!(find("w1", &scoped).is_some());
+        assert!(find("h1", &scoped).is_none());
+        let by_path = vec![SessionSource { paths: vec![PathGlob::new("/Users/test/Home/*").unwrap()], ..SessionSource::new(tmp.path()) }];
+        assert!(find("h1", &by_path).is_some());
+        assert!(find("h1", &[SessionSource::new(tmp.path())]).is_some());
+    }
+
+    #[test]
+    fn changed_paths_index_only_those_sessions() {
+        use crate::config::loader::SessionSource;
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("-Users-test-Code-api");
+        std::fs::create_dir_all(&dir).unwrap();
+        for id in ["s1", "s2"] {
+            std::fs::write(dir.join(format!("{id}.jsonl")), "{\"type\":\"user\"}\n").unwrap();
+        }
+        let store = SessionStore::open_in_memory().unwrap();
+        let sources = [SessionSource::new(tmp.path())];
+
+        let outside = tmp.path().join("s3.jsonl");
+        std::fs::write(&outside, "{\"type\":\"user\"}\n").unwrap();
+        let stats = index_session_paths(&[dir.join("s1.jsonl"), dir.join("gone.jsonl"), outside], &sources, &store, &[]).unwrap();
+        assert_eq!((stats.scanned, stats.indexed), (1, 1));
+        let ids: Vec<String> = store.all_sessions().unwrap().into_iter().map(|(m, _)| m.session_id).collect();
+        assert_eq!(ids, vec!["s1"]);
+        assert_eq!(store.all_sessions().unwrap()[0].0.project_path, "/Users/test/Code/api");
+    }
+
+    #[test]
+    fn excluded_sessions_are_pruned_and_never_found() {
+        use crate::config::loader::SessionSource;
+        use crate::config::types::PathGlob;
+        let tmp = tempfile::tempdir().unwrap();
+        for (dir, id) in [("-Users-test-Code-nda-client", "n1"), ("-Users-test-Code-api", "a1")] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+            std::fs::write(tmp.path().join(dir).join(format!("{id}.jsonl")), "{\"type\":\"user\"}\n").unwrap();
+        }
+        let store = SessionStore::open_in_memory().unwrap();
+        let open = [SessionSource::new(tmp.path())];
+        index_sessions(&open, &store, &[]).unwrap();
+        assert_eq!(store.count().unwrap(), 2);
+
+        let guarded = [SessionSource { exclude: vec![PathGlob::new("/Users/test/Code/nda-client").unwrap()], ..SessionSource::new(tmp.path()) }];
+        let stats = index_sessions(&guarded, &store, &[]).unwrap();
+        assert_eq!(stats.excluded, 1);
+        let ids: Vec<String> = store.all_sessions().unwrap().into_iter().map(|(m, _)| m.session_id).collect();
+        assert_eq!(ids, vec!["a1"]);
+        assert!(crate::daemon::summarizer::find_session_file_by_id("n1", &guarded, &[]).is_none());
+        assert!(crate::daemon::summarizer::find_session_file_by_id("a1", &guarded, &[]).is_some());
+        assert_eq!(excluded_project_dirs(&guarded), vec!["-Users-test-Code-nda-client"]);
+    }
+
+    #[test]
+    fn linked_sessions_index_under_the_hook_cwd() {
+        use crate::config::loader::SessionSource;
+        use crate::config::types::{DomainName, PathGlob};
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("-Users-test-Work-my-app");
+        std::fs::create_dir_all(&dir).unwrap();
+        for id in ["linked", "plain"] {
+            std::fs::write(dir.join(format!("{id}.jsonl")), "{\"type\":\"user\"}\n").unwrap();
+        }
+        let domains = vec![crate::domain::model::Domain {
+            name: DomainName::new("work").unwrap(),
+            paths: vec![PathGlob::new("/Users/test/Work/my-app").unwrap()],
+            aliases: std::collections::HashMap::new(),
+            can_read: Vec::new(),
+            seed_template: None,
+        }];
+        let store = SessionStore::open_in_memory().unwrap();
+        store.link_session("linked", "/Users/test/Work/my-app", Some("startup"), Some("sonnet")).unwrap();
+        index_sessions(&[SessionSource::new(tmp.path())], &store, &domains).unwrap();
+
+        let mut sessions: Vec<SessionMeta> = store.all_sessions().unwrap().into_iter().map(|(m, _)| m).collect();
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(sessions[0].project_path, "/Users/test/Work/my-app");
+        assert_eq!(sessions[0].domain.as_deref(), Some("work"));
+        assert_eq!(sessions[1].project_path, "/Users/test/Work/my/app");
+        assert_eq!(sessions[1].domain, None);
+    }
+
+    #[test]
+    fn retry_delay_doubles_and_caps() {
+        let base = std::time::Duration::from_secs(300);
+        let max = std::time::Duration::from_secs(3600);
+        assert_eq!(retry_delay(1, base, max), base);
+        assert_eq!(retry_delay(2, base, max), base * 2);
+        assert_eq!(retry_delay(3, base, max), base * 4);
+        assert_eq!(retry_delay(10, base, max), max);
+    }
+
+    #[test]
+    fn daemon_state_roundtrip() {
+        let store = SessionStore::open_in_memory().unwrap();
+        assert!(store.get_state("k").unwrap().is_none());
+        store.set_state("k", "v").unwrap();
+        assert_eq!(store.get_state("k").unwrap().as_deref(), Some("v"));
+    }
+
     #[test]
     fn content_value_to_text_string() {
         let val = serde_json::json!("hello world");
@@ -0,0 +1,278 @@
+use crate::vault::symlinks::SymlinkPolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Report file under the config dir, rewritten by every check.
+const REPORT_FILE: &str = "links_report.json";
+
+/// Seconds curl waits on a single URL.
+const URL_TIMEOUT_SECS: &str = "10";
+
+/// Hours between background related-path checks.
+const CHECK_INTERVAL_HOURS: u64 = 6;
+
+/// What kind of reference a broken link is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// A `related:` frontmatter entry pointing at a vault file.
+    Related,
+    /// An http(s) URL in a note body or `related:` entry.
+    Url,
+}
+
+/// One reference that didn't resolve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    /// Vault-relative path of the note holding the reference.
+    pub file: String,
+    pub target: String,
+    pub kind: LinkKind,
+    pub reason: String,
+}
+
+/// Outcome of one links check.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinkReport {
+    pub checked_at: String,
+    pub files: usize,
+    pub related_checked: usize,
+    /// Unique URLs checked. Zero when URL checks were skipped.
+    pub urls_checked: usize,
+    pub urls_skipped: bool,
+    pub broken: Vec<BrokenLink>,
+}
+
+impl LinkReport {
+    pub fn broken_count(&self, kind: LinkKind) -> usize {
+        self.broken.iter().filter(|b| b.kind == kind).count()
+    }
+}
+
+/// Checks one URL, returning Err(reason) when it's dead.
+pub type UrlCheck<'a> = &'a dyn Fn(&str) -> Result<(), String>;
+
+/// Check every note's `related:` entries, and its URLs when `check_url` is given.
+/// Each unique URL is checked once.
+pub fn check_vault(vault_root: &Path, exclude: &[String], symlinks: SymlinkPolicy, check_url: Option<UrlCheck>) -> LinkReport {
+    let notes: Vec<crate::vault::types::VaultFile> = crate::vault::reader::walk_vault_filtered(vault_root, exclude, symlinks)
+        .into_iter()
+        .flatten()
+        .filter(|vf| vf.path.extension().is_some_and(|e| e == "md"))
+        .collect();
+
+    // Lowercased file names and stems, for Obsidian-style bare references
+    let mut names: HashSet<String> = HashSet::new();
+    for vf in &notes {
+        if let Some(name) = vf.path.file_name().and_then(|n| n.to_str()) {
+            names.insert(name.to_lowercase());
+        }
+        if let Some(stem) = vf.path.file_stem().and_then(|n| n.to_str()) {
+            names.insert(stem.to_lowercase());
+        }
+    }
+
+    let mut report = LinkReport {
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        files: notes.len(),
+        urls_skipped: check_url.is_none(),
+        ..Default::default()
+    };
+    let mut urls: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for vf in &notes {
+        let rel = vf.path.strip_prefix(vault_root).unwrap_or(&vf.path).to_string_lossy().to_string();
+        for entry in &vf.frontmatter.related {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            if is_url(entry) {
+                urls.entry(entry.to_string()).or_default().push(rel.clone());
+                continue;
+            }
+            report.related_checked += 1;
+            if !related_resolves(entry, &vf.path, vault_root, &names) {
+                report.broken.push(BrokenLink {
+                    file: rel.clone(),
+                    target: entry.to_string(),
+                    kind: LinkKind::Related,
+                    reason: "no such file in the vault".to_string(),
+                });
+            }
+        }
+        for url in extract_urls(&vf.body) {
+            urls.entry(url).or_default().push(rel.clone());
+        }
+    }
+
+    if let Some(check) = check_url {
+        report.urls_checked = urls.len();
+        for (url, files) in &urls {
+            if let Err(reason) = check(url) {
+                let mut files = files.clone();
+                files.dedup();
+                for file in files {
+                    report.broken.push(BrokenLink { file, target: url.clone(), kind: LinkKind::Url, reason: reason.clone() });
+                }
+            }
+        }
+    }
+
+    report.broken.sort_by(|a, b| a.file.cmp(&b.file).then(a.target.cmp(&b.target)));
+    report
+}
+
+/// Whether a `related:` entry names an existing vault file. Accepts paths relative to the
+/// note or the vault root, with or without `.md`, `[[wikilinks]]`, and bare file names.
+fn related_resolves(entry: &str, note: &Path, vault_root: &Path, names: &HashSet<String>) -> bool {
+    let target = crate::vault::links::clean_target(entry);
+    if target.is_empty() {
+        return true;
+    }
+
+    let note_dir = note.parent().unwrap_or(vault_root);
+    let mut candidates: Vec<PathBuf> = vec![note_dir.join(target), vault_root.join(target)];
+    if Path::new(target).extension().is_none() {
+        candidates.push(note_dir.join(format!("{target}.md")));
+        candidates.push(vault_root.join(format!("{target}.md")));
+    }
+    if candidates.iter().any(|c| c.exists()) {
+        return true;
+    }
+    !target.contains('/') && names.contains(&target.to_lowercase())
+}
+
+fn is_url(s: &str) -> bool {
+    s.starts_with("http://") || s.starts_with("https://")
+}
+
+/// http(s) URLs in a note body, in order of appearance, without trailing punctuation.
+pub fn extract_urls(body: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("http://").into_iter().chain(rest.find("https://")).min() {
+        let tail = &rest[start..];
+        let end = tail.find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '`' | ')' | ']'))
+            .unwrap_or(tail.len());
+        let url = tail[..end].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        if url.len() > "https://".len() && !found.iter().any(|u| u == url) {
+            found.push(url.to_string());
+        }
+        rest = &tail[end.max(1)..];
+    }
+    found
+}
+
+/// HEAD-check a URL with curl, following redirects. Auth walls, rate limits and servers
+/// that refuse HEAD count as alive — only missing pages and unreachable hosts are dead.
+pub fn curl_head(url: &str) -> Result<(), String> {
+    let output = std::process::Command::new("curl")
+        .args(["-sS", "-o", "/dev/null", "-I", "-L", "--max-time", URL_TIMEOUT_SECS, "-w", "%{http_code}", url])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("could not run curl: {e}"))?;
+    let code: u16 = String::from_utf8_lossy(&output.stdout).trim().parse().unwrap_or(0);
+    match code {
+        0 => Err(String::from_utf8_lossy(&output.stderr).trim().trim_start_matches("curl: ").to_string()),
+        401 | 403 | 405 | 429 | 501 => Ok(()),
+        c if c >= 400 => Err(format!("HTTP {c}")),
+        _ => Ok(()),
+    }
+}
+
+pub fn report_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(REPORT_FILE)
+}
+
+pub fn write_report(config_dir: &Path, report: &LinkReport) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(config_dir)?;
+    std::fs::write(report_path(config_dir), serde_json::to_string_pretty(report).unwrap_or_default())
+}
+
+/// The last written report, if any.
+pub fn read_report(config_dir: &Path) -> Option<LinkReport> {
+    let raw = std::fs::read_to_string(report_path(config_dir)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Re-check related paths every few hours. URLs are only checked by `wardwell links check --urls`,
+/// so the background pass never touches the network; their last results are carried over.
+pub async fn run_links_loop(vault_root: PathBuf, exclude: Vec<String>, symlinks: SymlinkPolicy, config_dir: PathBuf) {
+    loop {
+        let root = vault_root.clone();
+        let excl = exclude.clone();
+        let dir = config_dir.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut report = check_vault(&root, &excl, symlinks, None);
+            if let Some(previous) = read_report(&dir).filter(|p| !p.urls_skipped) {
+                report.urls_checked = previous.urls_checked;
+                report.urls_skipped = false;
+                report.broken.extend(previous.broken.into_iter().filter(|b| b.kind == LinkKind::Url));
+            }
+            write_report(&dir, &report).map(|()| report.broken_count(LinkKind::Related))
+        }).await;
+        match result {
+            Ok(Ok(n)) if n > 0 => eprintln!("wardwell: {n} broken related link(s), see `wardwell links check`"),
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("wardwell: failed to write links report: {e}"),
+            Err(e) => eprintln!("wardwell: links task panicked: {e}"),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_HOURS * 3600)).await;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn note(vault: &Path, rel: &str, content: &str) {
+        let path = vault.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn flags_dead_related_paths() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "work/api/auth.md", "# Auth\n");
+        note(tmp.path(), "work/api/INDEX.md", "---\ntype: project\nrelated: [auth.md, work/api/auth, \"[[auth]]\", gone.md, work/old/plan.md]\n---\n# API\n");
+
+        let report = check_vault(tmp.path(), &[], SymlinkPolicy::Follow, None);
+        assert_eq!(report.files, 2);
+        assert_eq!(report.related_checked, 5);
+        assert!(report.urls_skipped);
+        let targets: Vec<&str> = report.broken.iter().map(|b| b.target.as_str()).collect();
+        assert_eq!(targets, vec!["gone.md", "work/old/plan.md"]);
+        assert!(report.broken.iter().all(|b| b.file == "work/api/INDEX.md" && b.kind == LinkKind::Related));
+    }
+
+    #[test]
+    fn checks_each_url_once() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "a.md", "See <https://example.com/dead>, and https://example.com/ok.\n");
+        note(tmp.path(), "b.md", "---\ntype: reference\nrelated: [\"https://example.com/dead\"]\n---\nagain https://example.com/dead\n");
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let check = |url: &str| {
+            calls.borrow_mut().push(url.to_string());
+            if url.ends_with("dead") { Err("HTTP 404".to_string()) } else { Ok(()) }
+        };
+        let report = check_vault(tmp.path(), &[], SymlinkPolicy::Follow, Some(&check));
+        assert_eq!(calls.borrow().len(), 2);
+        assert_eq!(report.urls_checked, 2);
+        assert_eq!(report.broken_count(LinkKind::Url), 2);
+        assert_eq!(report.broken[0].file, "a.md");
+        assert_eq!(report.broken[1].file, "b.md");
+        assert_eq!(report.broken[0].reason, "HTTP 404");
+    }
+
+    #[test]
+    fn extracts_urls_without_trailing_punctuation() {
+        let urls = extract_urls("[docs](https://docs.rs/serde). Also http://x.io/a?b=1, and `https://y.dev`");
+        assert_eq!(urls, vec!["https://docs.rs/serde", "http://x.io/a?b=1", "https://y.dev"]);
+    }
+}
@@ -1,3 +1,23 @@
+pub mod backup;
 pub mod watcher;
 pub mod indexer;
 pub mod summarizer;
+pub mod rollup;
+pub mod excerpt;
+pub mod links;
+pub mod prewarm;
+pub mod digest;
+pub mod decisions;
+pub mod retention;
+
+/// Create the lock file at `lock`, clearing one older than `ttl` as left by a run that died.
+/// One process at a time gets it; the holder removes it when done.
+pub(crate) fn take_lock(lock: &std::path::Path, ttl: std::time::Duration) -> bool {
+    let stale = std::fs::metadata(lock)
+        .and_then(|m| m.modified())
+        .is_ok_and(|t| t.elapsed().is_ok_and(|age| age > ttl));
+    if stale {
+        let _ = std::fs::remove_file(lock);
+    }
+    std::fs::OpenOptions::new().write(true).create_new(true).open(lock).is_ok()
+}
@@ -0,0 +1,139 @@
+use crate::config::loader::{SessionSource, WardwellConfig};
+use crate::daemon::indexer::{SessionStore, UnsummarizedSession};
+use crate::daemon::summarizer::{self, SummaryError};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Sessions written to this recently may still be running (a resumed or parallel session),
+/// so pre-warm leaves them for the daemon.
+const MIN_IDLE: Duration = Duration::from_secs(5 * 60);
+
+/// A pre-warm run younger than this holds the lock; an older lock is taken as abandoned.
+const LOCK_TTL: Duration = Duration::from_secs(10 * 60);
+
+const LOCK_FILE: &str = "prewarm.lock";
+
+/// What a pre-warm run did.
+#[derive(Debug, Default)]
+pub struct PrewarmStats {
+    pub summarized: usize,
+    pub skipped: usize,
+    /// A resume document was cached for the most recent session.
+    pub resume_cached: bool,
+    /// Another pre-warm was already running, or the summarizer breaker is open.
+    pub busy: bool,
+}
+
+/// The `limit` most recent unsummarized sessions run in `cwd`, skipping any touched within
+/// `MIN_IDLE` of `now`. `sessions` comes newest first, as `unsummarized` returns it.
+pub fn pick(sessions: Vec<UnsummarizedSession>, cwd: &Path, limit: usize, session_sources: &[SessionSource], now: SystemTime) -> Vec<UnsummarizedSession> {
+    let project_dir = crate::daemon::indexer::encode_project_dir(&cwd.to_string_lossy());
+    sessions.into_iter()
+        .filter(|s| s.project_dir == project_dir)
+        .filter(|s| {
+            summarizer::find_session_file(s, session_sources)
+                .and_then(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+                .is_some_and(|modified| now.duration_since(modified).is_ok_and(|idle| idle >= MIN_IDLE))
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Summarize the most recent sessions for `cwd` now, and cache a resume document for the
+/// latest, so `context` and `resume` answer from cache later in the session. Runs from the
+/// SessionStart hook in a detached process; one run at a time.
+pub async fn run(config: &WardwellConfig, config_dir: &Path, cwd: &Path) -> Result<PrewarmStats, SummaryError> {
+    let mut stats = PrewarmStats::default();
+    let limit = config.ai.prewarm_sessions;
+    if limit == 0 {
+        return Ok(stats);
+    }
+    let lock = config_dir.join(LOCK_FILE);
+    if !super::take_lock(&lock, LOCK_TTL) {
+        stats.busy = true;
+        return Ok(stats);
+    }
+    let result = warm(config, config_dir, cwd, limit, &mut stats).await;
+    let _ = std::fs::remove_file(&lock);
+    result.map(|_| stats)
+}
+
+async fn warm(config: &WardwellConfig, config_dir: &Path, cwd: &Path, limit: usize, stats: &mut PrewarmStats) -> Result<(), SummaryError> {
+    let store = SessionStore::open(&config_dir.join("sessions.db"))?;
+    if summarizer::breaker_state(&store)?.is_open() {
+        stats.busy = true;
+        return Ok(());
+    }
+    crate::daemon::indexer::index_sessions(&config.session_sources, &store, config.registry.all())?;
+    let sessions = pick(store.unsummarized()?, cwd, limit, &config.session_sources, SystemTime::now());
+    let Some(latest) = sessions.first() else {
+        return Ok(());
+    };
+
+    let summaries_dir = config_dir.join("summaries");
+    let provider: std::sync::Arc<dyn summarizer::SummaryProvider> = summarizer::provider(&config.ai, None).into();
+    let run = summarizer::summarize_sessions(&store, &sessions, &config.session_sources, &summaries_dir, &config.vault_path, provider.clone(), &config.ai, false, false).await?;
+    stats.summarized = run.summarized;
+    stats.skipped = run.skipped;
+
+    if let Some(path) = summarizer::find_session_file(latest, &config.session_sources)
+        && let Ok(conversation) = crate::daemon::indexer::extract_conversation(&path)
+        && !conversation.is_empty()
+    {
+        stats.resume_cached = summarizer::resume_document(&latest.session_id, &path, &latest.project_path, &conversation, &summaries_dir, provider.as_ref()).await.is_ok();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn picks_recent_idle_sessions_for_cwd() {
+        let tmp = tempdir().unwrap();
+        let sources = tmp.path().join("projects");
+        let session = |id: &str, project_path: &str| {
+            let dir = crate::daemon::indexer::encode_project_dir(project_path);
+            std::fs::create_dir_all(sources.join(&dir)).unwrap();
+            std::fs::write(sources.join(&dir).join(format!("{id}.jsonl")), "{}\n").unwrap();
+            UnsummarizedSession {
+                session_id: id.to_string(),
+                project_dir: dir,
+                project_path: project_path.to_string(),
+                domain: None,
+                user_message_count: 5,
+                file_size: 3,
+            }
+        };
+        let sessions = vec![
+            session("newest", "/code/api"),
+            session("other", "/code/site"),
+            session("v2", "/code/api-v2"),
+            session("oldest", "/code/api"),
+            session("gone", "/code/api"),
+        ];
+        std::fs::remove_file(sources.join("-code-api/gone.jsonl")).unwrap();
+        let sources = vec![SessionSource::new(&sources)];
+
+        let later = SystemTime::now() + Duration::from_secs(3600);
+        let ids = |limit: usize| -> Vec<String> {
+            pick(sessions.clone(), Path::new("/code/api"), limit, &sources, later).into_iter().map(|s| s.session_id).collect()
+        };
+        assert_eq!(ids(5), vec!["newest", "oldest"]);
+        assert_eq!(ids(1), vec!["newest"]);
+        assert!(pick(sessions, Path::new("/code/api"), 2, &sources, SystemTime::now()).is_empty(), "just-written sessions may still be running");
+    }
+
+    #[test]
+    fn lock_is_exclusive() {
+        let tmp = tempdir().unwrap();
+        let lock = tmp.path().join(LOCK_FILE);
+        assert!(crate::daemon::take_lock(&lock, LOCK_TTL));
+        assert!(!crate::daemon::take_lock(&lock, LOCK_TTL));
+        std::fs::remove_file(&lock).unwrap();
+        assert!(crate::daemon::take_lock(&lock, LOCK_TTL));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::config::loader::RetentionConfig;
+use crate::daemon::indexer::{SessionError, SessionStore};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Where `retention.archive` moves expired summaries, inside the summaries directory.
+/// Nothing in it expires.
+pub const ARCHIVE_DIR: &str = "archive";
+/// How often the daemon applies retention.
+const INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// What `wardwell prune` did, or would do in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PruneReport {
+    /// Expired summaries and cached resume documents, relative to the summaries directory.
+    pub summaries: Vec<String>,
+    pub summary_bytes: u64,
+    /// Whether they went to `summaries/archive/` rather than being deleted.
+    pub archived: bool,
+    /// Sessions dropped from sessions.db.
+    pub sessions: Vec<String>,
+    pub dry_run: bool,
+}
+
+impl PruneReport {
+    pub fn is_empty(&self) -> bool {
+        self.summaries.is_empty() && self.sessions.is_empty()
+    }
+}
+
+/// Expire summaries last written more than `summaries_days` before `now`, and session rows
+/// whose last message is more than `sessions_days` before it. Pruned sessions are
+/// remembered, so their transcripts are only indexed again once they change. A dry run
+/// finds what has expired and touches nothing.
+pub fn prune(
+    config: &RetentionConfig,
+    summaries_dir: &Path,
+    sessions: &SessionStore,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<PruneReport, SessionError> {
+    let mut report = PruneReport { archived: config.archive, dry_run, ..PruneReport::default() };
+
+    if let Some(days) = config.summaries_days {
+        let cutoff = now - chrono::Duration::days(i64::from(days));
+        for path in summary_files(summaries_dir) {
+            let Ok(meta) = std::fs::metadata(&path) else { continue };
+            let written: DateTime<Utc> = match meta.modified() {
+                Ok(t) => t.into(),
+                Err(_) => continue,
+            };
+            if written >= cutoff {
+                continue;
+            }
+            let rel = path.strip_prefix(summaries_dir).unwrap_or(&path).to_path_buf();
+            report.summary_bytes += meta.len();
+            if !dry_run {
+                if config.archive {
+                    let dest = summaries_dir.join(ARCHIVE_DIR).join(&rel);
+                    if let Some(parent) = dest.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::rename(&path, dest)?;
+                } else {
+                    std::fs::remove_file(&path)?;
+                }
+            }
+            report.summaries.push(rel.to_string_lossy().to_string());
+        }
+        report.summaries.sort();
+    }
+
+    if let Some(days) = config.sessions_days {
+        let cutoff = (now - chrono::Duration::days(i64::from(days))).format("%Y-%m-%dT%H:%M:%S").to_string();
+        report.sessions = sessions.sessions_before(&cutoff)?;
+        if !dry_run {
+            sessions.prune_sessions(&report.sessions)?;
+        }
+    }
+    Ok(report)
+}
+
+/// Summaries and cached resume documents; the archive is left alone.
+fn summary_files(summaries_dir: &Path) -> Vec<PathBuf> {
+    [summaries_dir.to_path_buf(), summaries_dir.join("resume")].iter()
+        .flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten())
+        .map(|entry| entry.path())
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+/// Apply retention at startup and then once a day.
+pub async fn run_retention_loop(config: RetentionConfig, config_dir: PathBuf) {
+    loop {
+        let cfg = config.clone();
+        let dir = config_dir.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let sessions = SessionStore::open(&dir.join("sessions.db"))?;
+            prune(&cfg, &dir.join("summaries"), &sessions, Utc::now(), false)
+        }).await;
+        match result {
+            Ok(Ok(report)) if !report.is_empty() => eprintln!(
+                "wardwell: retention {} {} summaries, pruned {} sessions",
+                if report.archived { "archived" } else { "deleted" }, report.summaries.len(), report.sessions.len(),
+            ),
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => eprintln!("wardwell: retention failed: {e}"),
+            Err(e) => eprintln!("wardwell: retention task panicked: {e}"),
+        }
+        tokio::time::sleep(INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::daemon::indexer::SessionMeta;
+
+    fn session(id: &str, last_message_at: &str) -> SessionMeta {
+        SessionMeta {
+            session_id: id.to_string(),
+            project_dir: "-work-api".to_string(),
+            project_path: "/work/api".to_string(),
+            domain: Some("work".to_string()),
+            message_count: 4,
+            user_message_count: 2,
+            assistant_message_count: 2,
+            first_message_at: Some(last_message_at.to_string()),
+            last_message_at: Some(last_message_at.to_string()),
+            file_size: 100,
+            file_hash: format!("hash-{id}"),
+        }
+    }
+
+    #[test]
+    fn expired_summaries_and_sessions_are_pruned_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        let summaries = tmp.path().join("summaries");
+        std::fs::create_dir_all(summaries.join("resume")).unwrap();
+        for name in ["old.md", "resume/old.md", "new.md"] {
+            std::fs::write(summaries.join(name), "summary").unwrap();
+        }
+        let store = SessionStore::open_in_memory().unwrap();
+        store.upsert(&session("old", "2026-01-02T09:00:00.000Z")).unwrap();
+        store.upsert(&session("new", "2026-03-01T09:00:00.000Z")).unwrap();
+
+        // Summaries age by mtime, so judge them from 45 days ahead; sessions age by their last message
+        let now = Utc::now() + chrono::Duration::days(45);
+        std::fs::File::options().write(true).open(summaries.join("new.md")).unwrap()
+            .set_modified((now - chrono::Duration::days(5)).into()).unwrap();
+        let config = RetentionConfig { summaries_days: Some(30), sessions_days: None, archive: true };
+        let dry = prune(&config, &summaries, &store, now, true).unwrap();
+        assert_eq!(dry.summaries, ["old.md", "resume/old.md"]);
+        assert_eq!(dry.summary_bytes, 14);
+        assert!(summaries.join("old.md").exists());
+
+        prune(&config, &summaries, &store, now, false).unwrap();
+        assert!(summaries.join("archive/resume/old.md").exists() && !summaries.join("old.md").exists());
+        assert!(summaries.join("new.md").exists());
+
+        let config = RetentionConfig { summaries_days: None, sessions_days: Some(30), archive: false };
+        let march: DateTime<Utc> = "2026-03-10T00:00:00Z".parse().unwrap();
+        assert_eq!(prune(&config, &summaries, &store, march, false).unwrap().sessions, ["old"]);
+        assert!(!store.session_ids().unwrap().contains("old"));
+        // The unchanged transcript stays pruned; one with new activity comes back
+        assert!(!store.upsert(&session("old", "2026-01-02T09:00:00.000Z")).unwrap());
+        let mut resumed = session("old", "2026-03-09T09:00:00.000Z");
+        resumed.file_hash = "hash-old-2".to_string();
+        assert!(store.upsert(&resumed).unwrap());
+    }
+}
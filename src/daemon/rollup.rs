@@ -0,0 +1,263 @@
+use crate::vault::template::{self, Vars};
+use crate::vault::types::Status;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Frontmatter marker identifying a domain `current_state.md` that wardwell owns.
+/// Files without it were written by hand and are never overwritten.
+const GENERATED_MARKER: &str = "wardwell";
+
+/// Maximum blockers listed in a rollup.
+const MAX_BLOCKERS: usize = 10;
+
+/// Layout of a domain rollup, rendered with `template::render`. Empty sections drop out.
+const ROLLUP_TEMPLATE: &str = "---\ntype: domain\ndomain: {{domain}}\nupdated: {{updated}}\ngenerated: {{generated}}\n---\n\n\
+<!-- Auto-generated by wardwell from project current_state.md files. Edits are overwritten; \
+delete the `generated` line to take this file over by hand. -->\n\n# {{domain}}\n\n\
+{{summary}}\n\n## Projects\n{{projects}}\n{{blockers}}\n{{next_actions}}\n";
+
+/// One project's state, as read from its current_state.md.
+#[derive(Debug, Clone)]
+struct ProjectState {
+    name: String,
+    status: Status,
+    focus: String,
+    next_action: String,
+    blockers: Vec<String>,
+}
+
+/// Render the rollup for a domain directory. None if no project has a current_state.md.
+/// `statuses` are the vault's extra states from config.yml.
+pub fn render_domain_rollup(domain_dir: &Path, statuses: &BTreeMap<String, Status>) -> Option<String> {
+    let domain = domain_dir.file_name()?.to_str()?;
+    let mut projects = read_project_states(domain_dir, statuses);
+    if projects.is_empty() {
+        return None;
+    }
+    projects.sort_by(|a, b| status_rank(a.status).cmp(&status_rank(b.status)).then(a.name.cmp(&b.name)));
+
+    let mut counts: Vec<(Status, usize)> = Vec::new();
+    for p in &projects {
+        match counts.iter_mut().find(|(s, _)| *s == p.status) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((p.status, 1)),
+        }
+    }
+    let summary: Vec<String> = counts.iter().map(|(s, n)| format!("{n} {s}")).collect();
+
+    let project_lines: Vec<String> = projects.iter().map(|p| {
+        let focus = if p.focus.is_empty() { "—" } else { p.focus.as_str() };
+        format!("- **{}** ({}): {focus}", p.name, p.status)
+    }).collect();
+
+    let blockers: Vec<String> = projects.iter()
+        .flat_map(|p| p.blockers.iter().map(move |b| format!("- {}: {b}", p.name)))
+        .take(MAX_BLOCKERS)
+        .collect();
+
+    let next: Vec<String> = projects.iter()
+        .filter(|p| !p.next_action.is_empty() && p.status.is_open())
+        .map(|p| format!("- {}: {}", p.name, p.next_action))
+        .collect();
+
+    let section = |title: &str, lines: &[String]| if lines.is_empty() { String::new() } else { format!("\n## {title}\n{}", lines.join("\n")) };
+    let vars: Vars = [
+        ("domain", domain.to_string()),
+        ("updated", chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()),
+        ("generated", GENERATED_MARKER.to_string()),
+        ("summary", format!("{} projects: {}", projects.len(), summary.join(", "))),
+        ("projects", project_lines.join("\n")),
+        ("blockers", section("Blockers", &blockers)),
+        ("next_actions", section("Next Actions", &next)),
+    ].into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+    Some(template::render(ROLLUP_TEMPLATE, &vars))
+}
+
+/// Regenerate `<domain>/current_state.md`. Returns true if the file was written.
+/// Hand-written domain files (no `generated: wardwell`) are left alone, and unchanged
+/// rollups are not rewritten so the watcher doesn't churn.
+pub fn write_domain_rollup(domain_dir: &Path, statuses: &BTreeMap<String, Status>) -> Result<bool, std::io::Error> {
+    let Some(content) = render_domain_rollup(domain_dir, statuses) else {
+        return Ok(false);
+    };
+    let path = domain_dir.join("current_state.md");
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        if !is_generated(&existing) {
+            return Ok(false);
+        }
+        if without_timestamp(&existing) == without_timestamp(&content) {
+            return Ok(false);
+        }
+    }
+    std::fs::write(&path, content)?;
+    Ok(true)
+}
+
+/// Regenerate rollups for every domain in the vault. Returns the number written.
+pub fn refresh_all(vault_root: &Path, exclude: &[String], statuses: &BTreeMap<String, Status>) -> usize {
+    let Ok(entries) = std::fs::read_dir(vault_root) else {
+        return 0;
+    };
+    let mut written = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_dir() || name.starts_with('.') || exclude.contains(&name) {
+            continue;
+        }
+        match write_domain_rollup(&entry.path(), statuses) {
+            Ok(true) => written += 1,
+            Ok(false) => {}
+            Err(e) => eprintln!("wardwell: failed to write rollup for {name}: {e}"),
+        }
+    }
+    written
+}
+
+/// Whether a domain current_state.md was generated by wardwell.
+pub fn is_generated(content: &str) -> bool {
+    crate::vault::frontmatter::frontmatter_yaml(content)
+        .and_then(|y| serde_yaml::from_str::<serde_yaml::Value>(y).ok())
+        .and_then(|v| v.get("generated").and_then(|g| g.as_str()).map(|g| g == GENERATED_MARKER))
+        .unwrap_or(false)
+}
+
+/// Regenerate domain rollups every five minutes.
+pub async fn run_rollup_loop(vault_root: std::path::PathBuf, exclude: Vec<String>, statuses: BTreeMap<String, Status>) {
+    loop {
+        let root = vault_root.clone();
+        let excl = exclude.clone();
+        let states = statuses.clone();
+        match tokio::task::spawn_blocking(move || refresh_all(&root, &excl, &states)).await {
+            Ok(n) if n > 0 => eprintln!("wardwell: refreshed {n} domain rollup(s)"),
+            Ok(_) => {}
+            Err(e) => eprintln!("wardwell: rollup task panicked: {e}"),
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+    }
+}
+
+fn read_project_states(domain_dir: &Path, statuses: &BTreeMap<String, Status>) -> Vec<ProjectState> {
+    let Ok(entries) = std::fs::read_dir(domain_dir) else {
+        return Vec::new();
+    };
+    let mut projects = Vec::new();
+    for entry in entries.flatten() {
+        let state_path = entry.path().join("current_state.md");
+        if !state_path.is_file() {
+            continue;
+        }
+        let Ok(vf) = crate::vault::reader::read_file_with(&state_path, statuses) else {
+            continue;
+        };
+        let blockers = crate::mcp::server::extract_section(&vf.body, "Blockers")
+            .lines()
+            .map(|l| l.trim().trim_start_matches("- ").trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+        projects.push(ProjectState {
+            name: entry.file_name().to_string_lossy().to_string(),
+            status: vf.frontmatter.status.unwrap_or(Status::Active),
+            focus: first_line(&crate::mcp::server::extract_section(&vf.body, "Focus")),
+            next_action: first_line(&crate::mcp::server::extract_section(&vf.body, "Next Action")),
+            blockers,
+        });
+    }
+    projects
+}
+
+fn first_line(s: &str) -> String {
+    s.lines().next().unwrap_or("").trim().to_string()
+}
+
+/// Blocked projects first, then active, then everything that's wound down.
+pub(crate) fn status_rank(status: Status) -> u8 {
+    match status {
+        Status::Blocked => 0,
+        Status::Active => 1,
+        Status::Paused => 2,
+        _ => 3,
+    }
+}
+
+fn without_timestamp(content: &str) -> String {
+    content.lines().filter(|l| !l.starts_with("updated: ")).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_state(domain_dir: &Path, project: &str, status: &str, focus: &str, next: &str, blockers: &[&str]) {
+        let dir = domain_dir.join(project);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut content = format!("---\nstatus: {status}\ntype: project\n---\n\n# {project}\n\n## Focus\n{focus}\n\n## Next Action\n{next}\n");
+        if !blockers.is_empty() {
+            content.push_str("\n## Blockers\n");
+            for b in blockers {
+                content.push_str(&format!("- {b}\n"));
+            }
+        }
+        std::fs::write(dir.join("current_state.md"), content).unwrap();
+    }
+
+    #[test]
+    fn rollup_summarizes_projects() {
+        let tmp = tempdir().unwrap();
+        let work = tmp.path().join("work");
+        write_state(&work, "api", "active", "Auth flow", "Write tests", &[]);
+        write_state(&work, "billing", "blocked", "Invoices", "Ping finance", &["Waiting on Stripe keys"]);
+        write_state(&work, "old-site", "completed", "Sunset", "Archive repo", &[]);
+
+        let rollup = render_domain_rollup(&work, &BTreeMap::new()).unwrap();
+        assert!(rollup.contains("generated: wardwell"));
+        assert!(rollup.contains("3 projects: 1 blocked, 1 active, 1 completed"));
+        assert!(rollup.find("**billing**").unwrap() < rollup.find("**api**").unwrap());
+        assert!(rollup.contains("- billing: Waiting on Stripe keys"));
+        assert!(rollup.contains("- api: Write tests"));
+        assert!(!rollup.contains("Archive repo"));
+    }
+
+    #[test]
+    fn rollup_ranks_configured_states_by_what_they_mean() {
+        let tmp = tempdir().unwrap();
+        let work = tmp.path().join("work");
+        write_state(&work, "api", "active", "Auth flow", "Write tests", &[]);
+        write_state(&work, "billing", "waiting_on_client", "Invoices", "Ping finance", &[]);
+        write_state(&work, "docs", "paused", "Guides", "Pick up later", &[]);
+        let statuses = BTreeMap::from([("waiting_on_client".to_string(), Status::Blocked)]);
+
+        let rollup = render_domain_rollup(&work, &statuses).unwrap();
+        assert!(rollup.contains("3 projects: 1 blocked, 1 active, 1 paused"), "{rollup}");
+        assert!(rollup.find("**billing**").unwrap() < rollup.find("**api**").unwrap());
+        // Paused work isn't open, so its next action stays out of the list
+        assert!(rollup.contains("- billing: Ping finance"));
+        assert!(!rollup.contains("Pick up later"));
+    }
+
+    #[test]
+    fn write_skips_hand_written_and_unchanged() {
+        let tmp = tempdir().unwrap();
+        let work = tmp.path().join("work");
+        write_state(&work, "api", "active", "Auth flow", "Write tests", &[]);
+
+        assert!(write_domain_rollup(&work, &BTreeMap::new()).unwrap());
+        assert!(!write_domain_rollup(&work, &BTreeMap::new()).unwrap());
+
+        std::fs::write(work.join("current_state.md"), "# work\n\nMy own notes\n").unwrap();
+        assert!(!write_domain_rollup(&work, &BTreeMap::new()).unwrap());
+        assert_eq!(std::fs::read_to_string(work.join("current_state.md")).unwrap(), "# work\n\nMy own notes\n");
+    }
+
+    #[test]
+    fn refresh_all_ignores_domains_without_projects() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("domains")).unwrap();
+        std::fs::write(tmp.path().join("domains/work.md"), "---\ntype: domain\n---\n").unwrap();
+        write_state(&tmp.path().join("work"), "api", "active", "Auth", "Tests", &[]);
+
+        assert_eq!(refresh_all(tmp.path(), &[], &BTreeMap::new()), 1);
+        assert!(!tmp.path().join("domains/current_state.md").exists());
+    }
+}
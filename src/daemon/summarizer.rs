@@ -1,5 +1,9 @@
+use crate::config::loader::{AiConfig, AiProvider, SessionSource, SummarizeOrder};
 use crate::daemon::indexer::{ConversationMessage, SessionStore, UnsummarizedSession};
+use crate::vault::project::ProjectMatch;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Errors from session summarization.
 #[derive(Debug, thiserror::Error)]
@@ -7,6 +11,9 @@ pub enum SummaryError {
     #[error("claude CLI error: {0}")]
     Cli(String),
 
+    #[error("{provider} API error: {message}")]
+    Api { provider: &'static str, message: String },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -23,28 +30,187 @@ pub struct SummaryStats {
     pub summarized: usize,
     pub skipped: usize,
     pub errors: usize,
+    /// The run stopped (or never started) because the circuit breaker is open.
+    pub breaker_open: bool,
+    /// Pending sessions left for later cycles by `max_summaries_per_cycle`.
+    pub deferred: usize,
+    /// Decisions queued for approval in a project's pending-decisions.jsonl.
+    pub proposed: usize,
+}
+
+/// First retry delay for a session whose summary failed. Doubles per attempt.
+const RETRY_BASE: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+/// Longest delay between retries of a single session.
+const RETRY_MAX: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+/// Consecutive provider failures (across sessions) that open the circuit breaker.
+pub const BREAKER_THRESHOLD: i64 = 5;
+/// How long the breaker stays open before the summarizer tries the backend again.
+const BREAKER_COOLDOWN_MINUTES: i64 = 30;
+
+/// Transcripts larger than this are skipped rather than summarized.
+const MAX_SESSION_BYTES: u64 = 1_048_576;
+/// User-message characters worth one point of signal.
+const CHARS_PER_POINT: usize = 1000;
+/// Phrases that mark a session where something was decided. Each distinct one scores a point
+/// and a half.
+const DECISION_KEYWORDS: &[&str] = &[
+    "decide", "decision", "trade-off", "tradeoff", "architecture", "going with", "instead of",
+    "root cause", "let's use", "we should", "approach",
+];
+/// Typical summary length, for cost estimates.
+const ESTIMATED_OUTPUT_TOKENS: usize = 800;
+
+const STATE_CONSECUTIVE_FAILURES: &str = "summarizer_consecutive_failures";
+const STATE_BREAKER_OPEN_UNTIL: &str = "summarizer_breaker_open_until";
+/// daemon_state key holding when `summarize_pending` last ran a cycle.
+pub const STATE_LAST_RUN: &str = "summarizer_last_run_at";
+
+/// Summarizer circuit breaker state, persisted in sessions.db.
+#[derive(Debug, Clone, Default)]
+pub struct BreakerState {
+    pub consecutive_failures: i64,
+    pub open_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl BreakerState {
+    /// Whether the breaker is currently blocking summarization.
+    pub fn is_open(&self) -> bool {
+        self.open_until.is_some_and(|t| t > chrono::Utc::now())
+    }
+}
+
+/// Load the circuit breaker state.
+pub fn breaker_state(store: &SessionStore) -> Result<BreakerState, SummaryError> {
+    let consecutive_failures = store.get_state(STATE_CONSECUTIVE_FAILURES)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let open_until = store.get_state(STATE_BREAKER_OPEN_UNTIL)?
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+        .map(|d| d.with_timezone(&chrono::Utc));
+    Ok(BreakerState { consecutive_failures, open_until })
+}
+
+/// Count a backend failure. Opens the breaker once the threshold is reached.
+fn record_backend_failure(store: &SessionStore) -> Result<BreakerState, SummaryError> {
+    let mut state = breaker_state(store)?;
+    state.consecutive_failures += 1;
+    store.set_state(STATE_CONSECUTIVE_FAILURES, &state.consecutive_failures.to_string())?;
+    if state.consecutive_failures >= BREAKER_THRESHOLD {
+        let until = chrono::Utc::now() + chrono::Duration::minutes(BREAKER_COOLDOWN_MINUTES);
+        store.set_state(STATE_BREAKER_OPEN_UNTIL, &until.to_rfc3339())?;
+        state.open_until = Some(until);
+    }
+    Ok(state)
+}
+
+/// A backend call succeeded — close the breaker.
+fn record_backend_success(store: &SessionStore) -> Result<(), SummaryError> {
+    store.set_state(STATE_CONSECUTIVE_FAILURES, "0")?;
+    store.set_state(STATE_BREAKER_OPEN_UNTIL, "")?;
+    Ok(())
 }
 
-/// Summarize all unsummarized sessions using the claude CLI.
+/// Summarize one cycle's worth of unsummarized sessions with the configured provider: at most
+/// `ai.max_summaries_per_cycle`, picked by `plan_cycle`.
 pub async fn summarize_pending(
     session_store: &SessionStore,
-    session_sources: &[PathBuf],
+    session_sources: &[SessionSource],
     summaries_dir: &Path,
-    model: &str,
+    vault_root: &Path,
+    ai: &AiConfig,
+    active_domains: &HashSet<String>,
     verbose: bool,
 ) -> Result<SummaryStats, SummaryError> {
     let mut stats = SummaryStats::default();
+
+    let breaker = breaker_state(session_store)?;
+    if breaker.is_open() {
+        if verbose {
+            eprintln!("wardwell: summarizer circuit breaker open after {} consecutive failures — skipping run", breaker.consecutive_failures);
+        }
+        stats.breaker_open = true;
+        return Ok(stats);
+    }
+
     let unsummarized = session_store.unsummarized()?;
-    let total = unsummarized.len();
+    let pending = unsummarized.len();
+    let batch = plan_cycle(unsummarized, active_domains, ai.summarize_order, ai.max_summaries_per_cycle);
+    let deferred = pending - batch.len();
+    let provider = Arc::from(provider(ai, None));
+    let mut stats = summarize_sessions(session_store, &batch, session_sources, summaries_dir, vault_root, provider, ai, verbose, false).await?;
+    stats.deferred = deferred;
+    session_store.set_state(STATE_LAST_RUN, &chrono::Utc::now().to_rfc3339())?;
+    Ok(stats)
+}
+
+/// The sessions to summarize this cycle: those in `active_domains` first, each group in
+/// `order`, at most `max` (0 = all). `sessions` comes newest first, as `unsummarized` returns it.
+pub fn plan_cycle(mut sessions: Vec<UnsummarizedSession>, active_domains: &HashSet<String>, order: SummarizeOrder, max: usize) -> Vec<UnsummarizedSession> {
+    if order == SummarizeOrder::OldestFirst {
+        sessions.reverse();
+    }
+    // Stable, so each group keeps the chosen order
+    sessions.sort_by_key(|s| !s.domain.as_ref().is_some_and(|d| active_domains.contains(d)));
+    if max > 0 {
+        sessions.truncate(max);
+    }
+    sessions
+}
+
+/// Domains with at least one active project, judged by each project's current_state.md
+/// (no status counts as active, as everywhere else).
+pub fn active_domains(vault_root: &Path) -> HashSet<String> {
+    let mut domains = HashSet::new();
+    let Ok(entries) = std::fs::read_dir(vault_root) else {
+        return domains;
+    };
+    for domain_dir in entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let Some(name) = domain_dir.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        let active = std::fs::read_dir(&domain_dir).into_iter().flatten().flatten().any(|project| {
+            crate::vault::reader::read_file(&project.path().join("current_state.md"))
+                .is_ok_and(|vf| vf.frontmatter.status.is_none_or(|s| s == crate::vault::types::Status::Active))
+        });
+        if active {
+            domains.insert(name);
+        }
+    }
+    domains
+}
+
+/// Summarize the given sessions. With `overwrite`, existing summary files are regenerated
+/// (used by `wardwell summarize --redo`); otherwise sessions that have one are skipped.
+/// Up to `ai.concurrency` provider calls run at once as tokio tasks, started no faster than
+/// `ai.requests_per_minute`. A failed or timed-out call costs only its session, which is
+/// retried later; the batch stops early only when the circuit breaker opens.
+///
+/// Decisions in a summary are queued for approval in the vault project the session maps to.
+#[allow(clippy::too_many_arguments)]
+pub async fn summarize_sessions(
+    session_store: &SessionStore,
+    sessions: &[UnsummarizedSession],
+    session_sources: &[SessionSource],
+    summaries_dir: &Path,
+    vault_root: &Path,
+    provider: Arc<dyn SummaryProvider>,
+    ai: &AiConfig,
+    verbose: bool,
+    overwrite: bool,
+) -> Result<SummaryStats, SummaryError> {
+    let mut stats = SummaryStats::default();
+    let total = sessions.len();
 
     std::fs::create_dir_all(summaries_dir)?;
 
-    let mut cli_calls_in_batch: usize = 0;
+    let limit = RateLimit::shared(ai.requests_per_minute);
+    let mut calls: tokio::task::JoinSet<Result<String, SummaryError>> = tokio::task::JoinSet::new();
+    let mut in_flight: HashMap<tokio::task::Id, (&UnsummarizedSession, PathBuf)> = HashMap::new();
 
-    for (i, session) in unsummarized.iter().enumerate() {
+    for (i, session) in sessions.iter().enumerate() {
         // Idempotent: skip if summary file already exists
         let summary_path = summaries_dir.join(format!("{}.md", session.session_id));
-        if summary_path.exists() {
+        if crate::vault::compress::existing(&summary_path).is_some() && !overwrite {
             session_store.mark_summarized(&session.session_id)?;
             stats.skipped += 1;
             continue;
@@ -64,11 +230,12 @@ pub async fn summarize_pending(
         let file_size = std::fs::metadata(&jsonl_path)
             .map(|m| m.len())
             .unwrap_or(0);
-        if file_size > 1_048_576 {
+        if file_size > MAX_SESSION_BYTES {
             if verbose {
                 eprintln!("wardwell: skipping large session {} ({} bytes)", session.session_id, file_size);
             }
-            session_store.mark_summarized(&session.session_id)?;
+            let reason = format!("too large: {} KB transcript (limit {} KB)", file_size / 1024, MAX_SESSION_BYTES / 1024);
+            session_store.mark_skipped(&session.session_id, &reason)?;
             stats.skipped += 1;
             continue;
         }
@@ -77,15 +244,16 @@ pub async fn summarize_pending(
         let conversation = match crate::daemon::indexer::extract_conversation(&jsonl_path) {
             Ok(c) => c,
             Err(_) => {
+                session_store.record_summary_failure(&session.session_id, RETRY_BASE, RETRY_MAX)?;
                 stats.errors += 1;
                 continue;
             }
         };
 
-        // Skip very short sessions (< 3 user messages)
-        let user_msgs = conversation.iter().filter(|m| m.role == "user").count();
-        if user_msgs < 3 {
-            session_store.mark_summarized(&session.session_id)?;
+        // Skip sessions with too little in them to be worth a summary
+        let signal = SessionSignal::measure(&conversation, &jsonl_path);
+        if signal.score() < ai.summarize_min_signal {
+            session_store.mark_skipped(&session.session_id, &signal.skip_reason(ai.summarize_min_signal))?;
             stats.skipped += 1;
             continue;
         }
@@ -100,36 +268,235 @@ pub async fn summarize_pending(
             );
         }
 
-        // Rate limiting: pause after every 5 claude calls
-        if cli_calls_in_batch > 0 && cli_calls_in_batch.is_multiple_of(5) {
-            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        // A free worker first: record finished calls until one is
+        while calls.len() >= ai.concurrency.max(1) {
+            if finish_next(&mut calls, &mut in_flight, session_store, vault_root, &mut stats).await? {
+                return Ok(stats);
+            }
         }
+        let prompt = summary_prompt(&conversation, &session.project_path);
+        let (provider, limit) = (provider.clone(), limit.clone());
+        let task = calls.spawn(async move {
+            limit.wait().await;
+            provider.complete(&prompt).await
+        });
+        in_flight.insert(task.id(), (session, summary_path));
+    }
 
-        // Summarize via claude CLI
-        match call_claude(&conversation, &session.project_path, model).await {
-            Ok(summary) => {
-                let frontmatter = build_summary_frontmatter(session);
-                let content = format!("{frontmatter}\n{summary}");
-                std::fs::write(&summary_path, content)?;
-                session_store.mark_summarized(&session.session_id)?;
-                stats.summarized += 1;
-                cli_calls_in_batch += 1;
+    while !calls.is_empty() {
+        if finish_next(&mut calls, &mut in_flight, session_store, vault_root, &mut stats).await? {
+            break;
+        }
+    }
+    Ok(stats)
+}
+
+/// Wait for the next summary call to finish and record its outcome. True when a failure
+/// opened the circuit breaker: the calls still running are then cancelled, leaving their
+/// sessions pending.
+async fn finish_next(
+    calls: &mut tokio::task::JoinSet<Result<String, SummaryError>>,
+    in_flight: &mut HashMap<tokio::task::Id, (&UnsummarizedSession, PathBuf)>,
+    session_store: &SessionStore,
+    vault_root: &Path,
+    stats: &mut SummaryStats,
+) -> Result<bool, SummaryError> {
+    let (id, result) = match calls.join_next_with_id().await {
+        Some(Ok((id, result))) => (id, result),
+        Some(Err(e)) => (e.id(), Err(SummaryError::Cli(format!("summary task failed: {e}")))),
+        None => return Ok(false),
+    };
+    let Some((session, summary_path)) = in_flight.remove(&id) else {
+        return Ok(false);
+    };
+    match result {
+        Ok(summary) => {
+            let frontmatter = build_summary_frontmatter(session);
+            let content = format!("{frontmatter}\n{summary}");
+            std::fs::write(summary_path, content)?;
+            session_store.mark_summarized(&session.session_id)?;
+            record_backend_success(session_store)?;
+            stats.summarized += 1;
+
+            if let Some(project_dir) = session_project_dir(vault_root, session) {
+                let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+                match crate::daemon::decisions::propose(&project_dir, &session.session_id, &today, &summary) {
+                    Ok(n) => stats.proposed += n,
+                    Err(e) => eprintln!("wardwell: could not queue decisions from {}: {e}", session.session_id),
+                }
             }
-            Err(e) => {
-                eprintln!("wardwell: summary failed for {}: {e}", session.session_id);
-                stats.errors += 1;
-                cli_calls_in_batch += 1;
+        }
+        Err(e) => {
+            let attempts = session_store.record_summary_failure(&session.session_id, RETRY_BASE, RETRY_MAX)?;
+            eprintln!("wardwell: summary failed for {} (attempt {attempts}): {e}", session.session_id);
+            stats.errors += 1;
+
+            let breaker = record_backend_failure(session_store)?;
+            if breaker.is_open() {
+                eprintln!(
+                    "wardwell: {} consecutive summarizer failures — pausing summarization for {BREAKER_COOLDOWN_MINUTES}m",
+                    breaker.consecutive_failures,
+                );
+                stats.breaker_open = true;
+                calls.abort_all();
+                return Ok(true);
             }
         }
     }
+    Ok(false)
+}
+
+/// The vault project folder a session's working directory names, in the session's domain.
+fn session_project_dir(vault_root: &Path, session: &UnsummarizedSession) -> Option<PathBuf> {
+    let domain_dir = vault_root.join(session.domain.as_deref()?);
+    let name = session.project_path.trim_end_matches('/').rsplit('/').next()?;
+    match crate::vault::project::resolve_project(&domain_dir, name) {
+        ProjectMatch::Exact(p) | ProjectMatch::Slug(p) | ProjectMatch::Alias(p) => Some(domain_dir.join(p)),
+        _ => None,
+    }
+}
+
+/// Spaces out provider calls: each one starts at least `interval` after the one before,
+/// however many workers are waiting.
+pub struct RateLimit {
+    interval: std::time::Duration,
+    next: tokio::sync::Mutex<tokio::time::Instant>,
+}
+
+impl RateLimit {
+    /// `per_minute` calls a minute; 0 = no limit.
+    pub fn per_minute(per_minute: u32) -> Self {
+        let interval = match per_minute {
+            0 => std::time::Duration::ZERO,
+            n => std::time::Duration::from_secs(60) / n,
+        };
+        RateLimit { interval, next: tokio::sync::Mutex::new(tokio::time::Instant::now()) }
+    }
+
+    /// The process-wide limiter for `per_minute`, so back-to-back batches (the daemon's rounds,
+    /// prewarm) share one budget rather than each starting with a fresh one.
+    pub fn shared(per_minute: u32) -> Arc<RateLimit> {
+        static LIMITS: std::sync::OnceLock<std::sync::Mutex<HashMap<u32, Arc<RateLimit>>>> = std::sync::OnceLock::new();
+        let mut limits = LIMITS.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(limits.entry(per_minute).or_insert_with(|| Arc::new(RateLimit::per_minute(per_minute))))
+    }
+
+    /// Wait for this call's slot.
+    pub async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(tokio::time::Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Local estimate of what a summarize run will send, before any CLI call is made.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SummaryEstimate {
+    /// Sessions that would actually reach the model (not skipped as too short or too large).
+    pub sessions: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    /// At API list prices, when the model is recognized.
+    pub usd: Option<f64>,
+}
+
+/// Estimate tokens and cost for summarizing `sessions` with `model`, using the same
+/// payload and skip rules as the summarizer (~4 chars per token).
+pub fn estimate_cost(sessions: &[UnsummarizedSession], session_sources: &[SessionSource], model: &str, min_signal: f64) -> SummaryEstimate {
+    let mut estimate = SummaryEstimate::default();
+    for session in sessions {
+        let Some(path) = find_session_file(session, session_sources) else {
+            continue;
+        };
+        if std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0) > MAX_SESSION_BYTES {
+            continue;
+        }
+        let Ok(conversation) = crate::daemon::indexer::extract_conversation(&path) else {
+            continue;
+        };
+        if SessionSignal::measure(&conversation, &path).score() < min_signal {
+            continue;
+        }
+        let chars = SUMMARY_PROMPT.len() + session.project_path.len() + build_conversation_payload(&conversation).len();
+        estimate.sessions += 1;
+        estimate.input_tokens += chars / 4;
+        estimate.output_tokens += ESTIMATED_OUTPUT_TOKENS;
+    }
+    estimate.usd = price_per_mtok(model).map(|(input, output)| {
+        (estimate.input_tokens as f64 * input + estimate.output_tokens as f64 * output) / 1_000_000.0
+    });
+    estimate
+}
 
-    Ok(stats)
+/// How much a session has in it, for deciding whether it is worth summarizing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSignal {
+    pub user_messages: usize,
+    /// Characters across all user messages.
+    pub user_chars: usize,
+    /// Distinct `DECISION_KEYWORDS` found anywhere in the conversation.
+    pub decision_keywords: Vec<&'static str>,
+    pub files_edited: usize,
+}
+
+impl SessionSignal {
+    /// Measure a conversation. `transcript` is read again for edit tool calls.
+    pub fn measure(conversation: &[ConversationMessage], transcript: &Path) -> Self {
+        let user: Vec<&ConversationMessage> = conversation.iter().filter(|m| m.role == "user").collect();
+        let text = conversation.iter().map(|m| m.text.to_lowercase()).collect::<Vec<_>>().join("\n");
+        Self {
+            user_messages: user.len(),
+            user_chars: user.iter().map(|m| m.text.chars().count()).sum(),
+            decision_keywords: DECISION_KEYWORDS.iter().copied().filter(|k| text.contains(k)).collect(),
+            files_edited: crate::daemon::indexer::files_edited(transcript).unwrap_or(0),
+        }
+    }
+
+    /// One point per user message and per thousand characters they hold, a point and a half
+    /// per decision keyword, and half a point per edited file (up to six files).
+    pub fn score(&self) -> f64 {
+        self.user_messages as f64
+            + (self.user_chars / CHARS_PER_POINT) as f64
+            + 1.5 * self.decision_keywords.len() as f64
+            + 0.5 * self.files_edited.min(6) as f64
+    }
+
+    /// The reason recorded in sessions.db when the score falls short.
+    fn skip_reason(&self, min_signal: f64) -> String {
+        let keywords = if self.decision_keywords.is_empty() {
+            "no decision keywords".to_string()
+        } else {
+            format!("keywords: {}", self.decision_keywords.join(", "))
+        };
+        format!(
+            "low signal: score {:.1} < {min_signal} ({} user message(s), {} chars, {keywords}, {} file(s) edited)",
+            self.score(), self.user_messages, self.user_chars, self.files_edited,
+        )
+    }
+}
+
+/// API list prices in USD per million tokens (input, output), by model family.
+fn price_per_mtok(model: &str) -> Option<(f64, f64)> {
+    let model = model.to_ascii_lowercase();
+    if model.contains("haiku") {
+        Some((1.0, 5.0))
+    } else if model.contains("sonnet") {
+        Some((3.0, 15.0))
+    } else if model.contains("opus") {
+        Some((5.0, 25.0))
+    } else {
+        None
+    }
 }
 
 /// Find the JSONL file for a session across session sources.
-fn find_session_file(session: &UnsummarizedSession, session_sources: &[PathBuf]) -> Option<PathBuf> {
+pub(crate) fn find_session_file(session: &UnsummarizedSession, session_sources: &[SessionSource]) -> Option<PathBuf> {
     for source in session_sources {
-        let path = source
+        let path = source.path
             .join(&session.project_dir)
             .join(format!("{}.jsonl", session.session_id));
         if path.exists() {
@@ -139,15 +506,56 @@ fn find_session_file(session: &UnsummarizedSession, session_sources: &[PathBuf])
     None
 }
 
+/// Resume handoff for a session. One cached under `<summaries_dir>/resume/` is reused while
+/// it is newer than the transcript; otherwise a fresh one is generated and cached.
+pub async fn resume_document(
+    session_id: &str,
+    jsonl_path: &Path,
+    project_path: &str,
+    conversation: &[ConversationMessage],
+    summaries_dir: &Path,
+    provider: &dyn SummaryProvider,
+) -> Result<String, SummaryError> {
+    let cache = summaries_dir.join("resume").join(format!("{session_id}.md"));
+    if let Some(doc) = cached_resume(&cache, jsonl_path) {
+        return Ok(doc);
+    }
+    let payload = build_resume_payload(conversation);
+    let prompt = format!(
+        "{RESUME_PROMPT}\n\n---\n\nThis session was for the project at `{project_path}`.\n\n---\n\n{payload}",
+    );
+    let doc = provider.complete(&prompt).await?;
+    if let Some(dir) = cache.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&cache, &doc)?;
+    Ok(doc)
+}
+
+/// A cached resume document, unless the transcript changed after it was written.
+fn cached_resume(cache: &Path, transcript: &Path) -> Option<String> {
+    let written = std::fs::metadata(cache).and_then(|m| m.modified()).ok()?;
+    let changed = std::fs::metadata(transcript).and_then(|m| m.modified()).ok()?;
+    if written < changed {
+        return None;
+    }
+    std::fs::read_to_string(cache).ok().filter(|doc| !doc.trim().is_empty())
+}
+
 /// Find a session JSONL file by session ID across all session sources.
-/// Walks each source's subdirectories looking for `{session_id}.jsonl`.
-pub fn find_session_file_by_id(session_id: &str, session_sources: &[PathBuf]) -> Option<PathBuf> {
+/// Walks each source's subdirectories looking for `{session_id}.jsonl`, skipping project
+/// directories a scoped source doesn't admit (`domains` resolves their domain).
+pub fn find_session_file_by_id(
+    session_id: &str,
+    session_sources: &[SessionSource],
+    domains: &[crate::domain::model::Domain],
+) -> Option<PathBuf> {
     let filename = format!("{session_id}.jsonl");
     for source in session_sources {
-        if !source.exists() {
+        if !source.path.exists() {
             continue;
         }
-        let entries = match std::fs::read_dir(source) {
+        let entries = match std::fs::read_dir(&source.path) {
             Ok(e) => e,
             Err(_) => continue,
         };
@@ -157,7 +565,12 @@ pub fn find_session_file_by_id(session_id: &str, session_sources: &[PathBuf]) ->
                 continue;
             }
             let candidate = project_dir.join(&filename);
-            if candidate.exists() {
+            if !candidate.exists() {
+                continue;
+            }
+            let project_path = crate::daemon::indexer::decode_project_dir(&entry.file_name().to_string_lossy());
+            let domain = crate::daemon::indexer::resolve_domain(&project_path, domains);
+            if source.admits(&project_path, domain.as_deref()) {
                 return Some(candidate);
             }
         }
@@ -274,22 +687,193 @@ Architectural or design decisions made during the session that the next session
 
 Omit empty sections. Prioritize completeness over brevity — this is a handoff document, not a summary."#;
 
-/// Call the claude CLI to summarize a conversation.
-async fn call_claude(
-    conversation: &[ConversationMessage],
-    project_path: &str,
-    model: &str,
-) -> Result<String, SummaryError> {
+/// The prompt that asks for a conversation's summary.
+fn summary_prompt(conversation: &[ConversationMessage], project_path: &str) -> String {
     let condensed = build_conversation_payload(conversation);
-    let prompt = format!(
-        "{SUMMARY_PROMPT}\n\n---\n\nThis session was for the project at `{project_path}`.\n\n---\n\n{condensed}"
-    );
+    format!("{SUMMARY_PROMPT}\n\n---\n\nThis session was for the project at `{project_path}`.\n\n---\n\n{condensed}")
+}
+
+/// Seconds a single summary call may take, for every provider.
+const CALL_TIMEOUT_SECS: u64 = 120;
+
+type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// A backend that turns a prompt into text: summaries, resume documents, and rollups all go
+/// through one, chosen by `ai.provider`.
+pub trait SummaryProvider: Send + Sync {
+    /// Short name for logs and `wardwell doctor`.
+    fn name(&self) -> &'static str;
+    /// The model prompts are sent to.
+    fn model(&self) -> &str;
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String, SummaryError>>;
+}
+
+/// The provider `ai` selects, sending to `model` when given, else `ai.summarize_model`.
+pub fn provider(ai: &AiConfig, model: Option<&str>) -> Box<dyn SummaryProvider> {
+    let model = model.unwrap_or(&ai.summarize_model).to_string();
+    let base_url = |default: &str| ai.base_url.as_deref().unwrap_or(default).trim_end_matches('/').to_string();
+    match ai.provider {
+        AiProvider::ClaudeCli => Box::new(ClaudeCli { model }),
+        AiProvider::Openai => Box::new(OpenAiCompatible {
+            base_url: base_url(OpenAiCompatible::DEFAULT_URL),
+            api_key: ai.api_key.clone(),
+            model,
+        }),
+        AiProvider::Ollama => Box::new(Ollama { base_url: base_url(Ollama::DEFAULT_URL), model }),
+    }
+}
+
+/// `claude -p`, riding on the Claude Code login.
+pub struct ClaudeCli {
+    pub model: String,
+}
+
+impl SummaryProvider for ClaudeCli {
+    fn name(&self) -> &'static str {
+        "claude_cli"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String, SummaryError>> {
+        Box::pin(claude_cli_call(prompt, &self.model))
+    }
+}
+
+/// An OpenAI-compatible chat completions API: OpenAI itself, or a proxy or local server
+/// speaking the same protocol.
+pub struct OpenAiCompatible {
+    /// Up to and including the version, e.g. `https://api.openai.com/v1`.
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+}
+
+impl OpenAiCompatible {
+    pub const DEFAULT_URL: &str = "https://api.openai.com/v1";
+}
+
+impl SummaryProvider for OpenAiCompatible {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String, SummaryError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "model": self.model,
+                "messages": [{"role": "user", "content": prompt}],
+            });
+            let auth = match &self.api_key {
+                Some(key) => {
+                    let key = crate::config::secrets::resolve(key).map_err(|e| api_error(self.name(), e.to_string()))?;
+                    Some(format!("Authorization: Bearer {key}"))
+                }
+                None => None,
+            };
+            let url = format!("{}/chat/completions", self.base_url);
+            let response = post_json(self.name(), &url, auth.as_deref(), &body).await?;
+            response.pointer("/choices/0/message/content")
+                .and_then(|c| c.as_str())
+                .map(str::to_string)
+                .ok_or_else(|| api_error(self.name(), "response has no choices[0].message.content"))
+        })
+    }
+}
 
-    claude_cli_call(&prompt, model).await
+/// A local Ollama server's generate endpoint.
+pub struct Ollama {
+    pub base_url: String,
+    pub model: String,
+}
+
+impl Ollama {
+    pub const DEFAULT_URL: &str = "http://localhost:11434";
+}
+
+impl SummaryProvider for Ollama {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String, SummaryError>> {
+        Box::pin(async move {
+            let body = serde_json::json!({"model": self.model, "prompt": prompt, "stream": false});
+            let url = format!("{}/api/generate", self.base_url);
+            let response = post_json(self.name(), &url, None, &body).await?;
+            response.get("response")
+                .and_then(|c| c.as_str())
+                .map(str::to_string)
+                .ok_or_else(|| api_error(self.name(), "response has no 'response' field"))
+        })
+    }
+}
+
+fn api_error(provider: &'static str, message: impl Into<String>) -> SummaryError {
+    SummaryError::Api { provider, message: message.into() }
+}
+
+/// POST `body` as JSON with curl and parse the JSON reply. The request goes to curl as a
+/// config file on stdin, so neither the prompt nor the API key shows up in `ps`.
+async fn post_json(provider: &'static str, url: &str, header: Option<&str>, body: &serde_json::Value) -> Result<serde_json::Value, SummaryError> {
+    let mut config = vec![
+        format!("url = {}", curl_quote(url)),
+        "header = \"Content-Type: application/json\"".to_string(),
+        format!("data-binary = {}", curl_quote(&body.to_string())),
+        format!("max-time = {CALL_TIMEOUT_SECS}"),
+        "silent".to_string(),
+        "show-error".to_string(),
+        "write-out = \"\\n%{http_code}\"".to_string(),
+    ];
+    if let Some(header) = header {
+        config.push(format!("header = {}", curl_quote(header)));
+    }
+
+    let mut child = tokio::process::Command::new("curl")
+        .args(["--config", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| api_error(provider, format!("could not run curl: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use tokio::io::AsyncWriteExt;
+        stdin.write_all(config.join("\n").as_bytes()).await
+            .map_err(|e| api_error(provider, format!("failed to write to curl stdin: {e}")))?;
+    }
+    let output = child.wait_with_output().await
+        .map_err(|e| api_error(provider, format!("curl process error: {e}")))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (reply, code) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    match code.trim().parse::<u16>().unwrap_or(0) {
+        0 => Err(api_error(provider, String::from_utf8_lossy(&output.stderr).trim().trim_start_matches("curl: ").to_string())),
+        200..=299 => serde_json::from_str(reply).map_err(|e| api_error(provider, format!("failed to parse response: {e}"))),
+        status => {
+            let detail: String = reply.trim().chars().take(300).collect();
+            Err(api_error(provider, format!("HTTP {status} from {url}: {detail}")))
+        }
+    }
+}
+
+/// A curl config value: double-quoted, with backslashes and quotes escaped.
+fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
 }
 
 /// Execute a prompt via `claude -p` and return the text result.
-pub async fn claude_cli_call(prompt: &str, model: &str) -> Result<String, SummaryError> {
+async fn claude_cli_call(prompt: &str, model: &str) -> Result<String, SummaryError> {
     let output = tokio::process::Command::new("claude")
         .args([
             "-p",
@@ -318,7 +902,7 @@ pub async fn claude_cli_call(prompt: &str, model: &str) -> Result<String, Summar
     }
 
     let output = match tokio::time::timeout(
-        std::time::Duration::from_secs(120),
+        std::time::Duration::from_secs(CALL_TIMEOUT_SECS),
         child.wait_with_output(),
     ).await {
         Ok(result) => result.map_err(|e| SummaryError::Cli(format!("claude process error: {e}")))?,
@@ -350,6 +934,277 @@ pub async fn claude_cli_call(prompt: &str, model: &str) -> Result<String, Summar
 mod tests {
     use super::*;
 
+    #[test]
+    fn breaker_opens_after_threshold_and_closes_on_success() {
+        let store = SessionStore::open_in_memory().unwrap();
+        for _ in 0..BREAKER_THRESHOLD - 1 {
+            assert!(!record_backend_failure(&store).unwrap().is_open());
+        }
+        let state = record_backend_failure(&store).unwrap();
+        assert!(state.is_open());
+        assert!(breaker_state(&store).unwrap().is_open(), "breaker state is persisted");
+
+        record_backend_success(&store).unwrap();
+        let state = breaker_state(&store).unwrap();
+        assert!(!state.is_open());
+        assert_eq!(state.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn provider_follows_config() {
+        let mut ai = AiConfig::default();
+        let cli = provider(&ai, Some("opus"));
+        assert_eq!((cli.name(), cli.model()), ("claude_cli", "opus"));
+
+        ai.provider = AiProvider::Ollama;
+        ai.summarize_model = "llama3.1".to_string();
+        assert_eq!((provider(&ai, None).name(), provider(&ai, None).model()), ("ollama", "llama3.1"));
+        assert_eq!(curl_quote("say \"hi\"\\n"), "\"say \\\"hi\\\"\\\\n\"");
+    }
+
+    #[tokio::test]
+    async fn openai_provider_posts_the_prompt_and_reads_the_reply() {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Headers, then a body of Content-Length bytes
+            while !String::from_utf8_lossy(&request).split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                head.lines().find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|n| n.trim().parse::<usize>().unwrap_or(0)))
+                    .is_some_and(|len| body.len() >= len)
+            }) {
+                let n = conn.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let reply = r###"{"choices":[{"message":{"role":"assistant","content":"## Summary\nDone."}}]}"###;
+            write!(conn, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}", reply.len()).unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let provider = OpenAiCompatible {
+            base_url: format!("http://127.0.0.1:{port}/v1"),
+            api_key: Some("sk-test".to_string()),
+            model: "gpt-4o-mini".to_string(),
+        };
+        let text = provider.complete("Summarize \"this\"\nplease \\ now").await.unwrap();
+        assert_eq!(text, "## Summary\nDone.");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /v1/chat/completions"), "{request}");
+        assert!(request.contains("Authorization: Bearer sk-test"));
+        let body: serde_json::Value = serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(body["model"], "gpt-4o-mini");
+        assert_eq!(body["messages"][0]["content"], "Summarize \"this\"\nplease \\ now");
+    }
+
+    /// Answers after a pause, failing prompts that mention "boom", and tracks how many calls
+    /// overlapped.
+    struct SlowProvider {
+        running: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    impl SummaryProvider for SlowProvider {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn model(&self) -> &str {
+            "test"
+        }
+
+        fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String, SummaryError>> {
+            use std::sync::atomic::Ordering;
+            Box::pin(async move {
+                let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+                self.peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                self.running.fetch_sub(1, Ordering::SeqCst);
+                if prompt.contains("boom") { Err(api_error("slow", "timed out")) } else { Ok("## Summary\nDone.\n\n## Decisions\n- Kept retries in the client.".to_string()) }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn sessions_are_summarized_in_parallel_and_fail_one_at_a_time() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project_dir = tmp.path().join("-Users-test-foo");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let line = |role: &str, word: &str| format!("{{\"type\":\"{role}\",\"message\":{{\"content\":\"{}\"}}}}\n", format!("{word} ").repeat(200));
+        let ids = ["a", "b", "boom", "c", "d"];
+        for id in ids {
+            let transcript: String = (0..4).flat_map(|_| [line("user", id), line("assistant", "ok")]).collect();
+            std::fs::write(project_dir.join(format!("{id}.jsonl")), transcript).unwrap();
+        }
+        let sessions: Vec<UnsummarizedSession> = ids.iter().map(|id| UnsummarizedSession {
+            session_id: id.to_string(),
+            project_dir: "-Users-test-foo".to_string(),
+            project_path: "/Users/test/foo".to_string(),
+            domain: (*id == "a").then(|| "work".to_string()),
+            user_message_count: 4,
+            file_size: 0,
+        }).collect();
+        let sources = vec![SessionSource::new(tmp.path())];
+        let summaries = tmp.path().join("summaries");
+        let vault = tmp.path().join("vault");
+        std::fs::create_dir_all(vault.join("work/foo")).unwrap();
+        let store = SessionStore::open_in_memory().unwrap();
+        let slow = Arc::new(SlowProvider { running: Default::default(), peak: Default::default() });
+        let ai = AiConfig { concurrency: 3, requests_per_minute: 0, ..AiConfig::default() };
+
+        let stats = summarize_sessions(&store, &sessions, &sources, &summaries, &vault, slow.clone(), &ai, false, false).await.unwrap();
+        assert_eq!((stats.summarized, stats.errors, stats.breaker_open), (4, 1, false));
+        // Only the session mapped to a vault project queues its decision
+        assert_eq!(stats.proposed, 1);
+        let queued = crate::daemon::decisions::pending(&vault.join("work/foo"));
+        assert_eq!((queued.len(), queued[0].session_id.as_str()), (1, "a"));
+        assert_eq!(slow.peak.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert!(summaries.join("d.md").exists());
+        assert!(!summaries.join("boom.md").exists());
+    }
+
+    #[tokio::test]
+    async fn rate_limit_spaces_calls_across_workers() {
+        let limit = Arc::new(RateLimit::per_minute(1200));
+        let start = tokio::time::Instant::now();
+        let waits: Vec<_> = (0..3).map(|_| {
+            let limit = limit.clone();
+            tokio::spawn(async move { limit.wait().await })
+        }).collect();
+        for wait in waits {
+            wait.await.unwrap();
+        }
+        assert!(start.elapsed() >= std::time::Duration::from_millis(100), "{:?}", start.elapsed());
+
+        let unlimited = RateLimit::per_minute(0);
+        let start = tokio::time::Instant::now();
+        for _ in 0..10 {
+            unlimited.wait().await;
+        }
+        assert!(start.elapsed() < std::time::Duration::from_millis(50));
+
+        // Batches at the same rate share one limiter, so a new batch waits out the last one's calls
+        assert!(Arc::ptr_eq(&RateLimit::shared(1201), &RateLimit::shared(1201)));
+        assert!(!Arc::ptr_eq(&RateLimit::shared(1201), &RateLimit::shared(1202)));
+    }
+
+    #[test]
+    fn plan_cycle_puts_active_domains_first_and_caps_the_batch() {
+        let session = |id: &str, domain: Option<&str>| UnsummarizedSession {
+            session_id: id.to_string(),
+            project_dir: String::new(),
+            project_path: String::new(),
+            domain: domain.map(String::from),
+            user_message_count: 5,
+            file_size: 100,
+        };
+        // Newest first, as unsummarized() returns them
+        let sessions = vec![session("new-idle", Some("hobby")), session("new-work", Some("work")), session("mid", None), session("old-work", Some("work"))];
+        let active: HashSet<String> = ["work".to_string()].into();
+        let ids = |order, max| -> Vec<String> {
+            plan_cycle(sessions.clone(), &active, order, max).into_iter().map(|s| s.session_id).collect()
+        };
+        assert_eq!(ids(SummarizeOrder::NewestFirst, 0), vec!["new-work", "old-work", "new-idle", "mid"]);
+        assert_eq!(ids(SummarizeOrder::OldestFirst, 3), vec!["old-work", "new-work", "mid"]);
+        assert_eq!(ids(SummarizeOrder::NewestFirst, 1), vec!["new-work"]);
+    }
+
+    #[test]
+    fn active_domains_reads_project_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = |domain: &str, project: &str, status: &str| {
+            let dir = tmp.path().join(domain).join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("current_state.md"), format!("---\nstatus: {status}\n---\n# {project}\n")).unwrap();
+        };
+        state("work", "api", "completed");
+        state("work", "site", "active");
+        state("hobby", "game", "paused");
+        assert_eq!(active_domains(tmp.path()), ["work".to_string()].into());
+    }
+
+    #[test]
+    fn estimate_cost_skips_short_sessions_and_prices_models() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project_dir = tmp.path().join("-Users-test-foo");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let line = |role: &str| format!("{{\"type\":\"{role}\",\"message\":{{\"content\":\"{}\"}}}}\n", "word ".repeat(200));
+        let long: String = (0..4).flat_map(|_| [line("user"), line("assistant")]).collect();
+        std::fs::write(project_dir.join("long.jsonl"), long).unwrap();
+        std::fs::write(project_dir.join("short.jsonl"), line("user")).unwrap();
+
+        let session = |id: &str| UnsummarizedSession {
+            session_id: id.to_string(),
+            project_dir: "-Users-test-foo".to_string(),
+            project_path: "/Users/test/foo".to_string(),
+            domain: None,
+            user_message_count: 4,
+            file_size: 0,
+        };
+        let sessions = vec![session("long"), session("short"), session("missing")];
+        let sources = vec![SessionSource::new(tmp.path())];
+
+        let opus = estimate_cost(&sessions, &sources, "opus", 3.0);
+        assert_eq!(opus.sessions, 1);
+        assert!(opus.input_tokens > 1000);
+        assert_eq!(opus.output_tokens, ESTIMATED_OUTPUT_TOKENS);
+        let haiku = estimate_cost(&sessions, &sources, "haiku", 3.0);
+        assert!(opus.usd.unwrap() > haiku.usd.unwrap());
+        assert!(estimate_cost(&sessions, &sources, "some-local-model", 3.0).usd.is_none());
+    }
+
+    #[test]
+    fn signal_keeps_short_decisive_sessions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let transcript = tmp.path().join("s.jsonl");
+        std::fs::write(&transcript, concat!(
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/repo/src/a.rs"}},"#,
+            r#"{"type":"tool_use","name":"Write","input":{"file_path":"/repo/src/b.rs"}},{"type":"tool_use","name":"Read","input":{"file_path":"/repo/c.rs"}}]}}"#,
+            "\n",
+        )).unwrap();
+        let msg = |role: &str, text: &str| ConversationMessage { role: role.to_string(), text: text.to_string() };
+
+        let decisive = vec![
+            msg("user", "Should the queue live in sqlite or redis?"),
+            msg("assistant", "Going with sqlite instead of redis: one less service, and the trade-off is throughput."),
+        ];
+        let signal = SessionSignal::measure(&decisive, &transcript);
+        assert_eq!(signal.decision_keywords, vec!["trade-off", "going with", "instead of"]);
+        assert_eq!(signal.files_edited, 2);
+        assert_eq!(signal.score(), 1.0 + 4.5 + 1.0);
+
+        let trivial = vec![msg("user", "thanks"), msg("assistant", "You're welcome.")];
+        let signal = SessionSignal::measure(&trivial, &tmp.path().join("missing.jsonl"));
+        assert_eq!(signal.score(), 1.0);
+        assert_eq!(
+            signal.skip_reason(3.0),
+            "low signal: score 1.0 < 3 (1 user message(s), 6 chars, no decision keywords, 0 file(s) edited)",
+        );
+
+        let store = SessionStore::open_in_memory().unwrap();
+        store.upsert(&crate::daemon::indexer::SessionMeta {
+            session_id: "s".to_string(),
+            project_dir: "-repo".to_string(),
+            project_path: "/repo".to_string(),
+            domain: None,
+            message_count: 2,
+            user_message_count: 1,
+            assistant_message_count: 1,
+            first_message_at: None,
+            last_message_at: Some("2026-01-01T00:00:00Z".to_string()),
+            file_size: 10,
+            file_hash: "10:1".to_string(),
+        }).unwrap();
+        store.mark_skipped("s", &signal.skip_reason(3.0)).unwrap();
+        let statuses = store.session_statuses(10, true).unwrap();
+        assert!(statuses[0].summarized && statuses[0].skip_reason.as_deref().is_some_and(|r| r.starts_with("low signal")));
+        store.mark_summarized("s").unwrap();
+        assert!(store.session_statuses(10, true).unwrap().is_empty());
+    }
+
     #[test]
     fn build_conversation_payload_basic() {
         let msgs = vec![
@@ -1,18 +1,30 @@
 use crate::domain::registry::DomainRegistry;
 use crate::index::store::IndexStore;
+use crate::vault::symlinks::{Symlink, SymlinkPolicy, Verdict};
 use notify::{Event, EventKind, RecursiveMode, Watcher};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
 
 /// Watch the vault directory for file changes and update the index.
 /// If a registry is provided, changes under `vault/domains/` trigger a registry rebuild.
+/// Manual-edit history entries are appended with `durability`. Symlinked folders are
+/// watched per `symlinks`, as they were when the watcher started, and changes are indexed
+/// under the same paths a full index build uses.
 pub async fn watch_vault(
     vault_root: PathBuf,
     index: Arc<IndexStore>,
     registry: Option<Arc<RwLock<DomainRegistry>>>,
+    durability: crate::config::loader::DurabilityConfig,
+    symlinks: SymlinkPolicy,
+    statuses: std::collections::BTreeMap<String, crate::vault::types::Status>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let (tx, mut rx) = mpsc::channel::<PathBuf>(100);
+    let followed: Vec<Symlink> = crate::vault::symlinks::scan(&vault_root, &[], symlinks).into_iter()
+        .filter(|l| l.verdict == Verdict::Followed)
+        .collect();
+    let targets: Vec<PathBuf> = followed.iter().map(|l| l.target.clone()).collect();
 
     let vault_root_clone = vault_root.clone();
     std::thread::spawn(move || {
@@ -42,6 +54,12 @@ pub async fn watch_vault(
             eprintln!("wardwell: could not watch {}: {e}", vault_root_clone.display());
             return;
         }
+        // Not every platform's watcher descends into links on its own
+        for target in &targets {
+            if let Err(e) = watcher.watch(target, RecursiveMode::Recursive) {
+                eprintln!("wardwell: could not watch linked folder {}: {e}", target.display());
+            }
+        }
 
         // Block this thread forever to keep the watcher alive
         std::thread::park();
@@ -52,19 +70,25 @@ pub async fn watch_vault(
     // Process file change events
     let vault_root = vault_root.clone();
     while let Some(path) = rx.recv().await {
+        let Some(path) = crate::vault::symlinks::vault_path(&vault_root, &followed, &path) else {
+            continue;
+        };
         // Check if this is a domain file change → rebuild registry
         if path.starts_with(&domains_prefix)
             && let Some(ref reg) = registry
         {
-            let new_registry = DomainRegistry::from_vault(&vault_root);
             let mut write_guard = reg.write().await;
+            let new_registry = write_guard.reload(&vault_root);
+            for error in new_registry.errors() {
+                eprintln!("wardwell: {}: {}", error.file, error.problems.join("; "));
+            }
             *write_guard = new_registry;
             eprintln!("wardwell: domain registry rebuilt");
         }
 
         if path.exists() {
             // File created or modified — upsert
-            match crate::vault::reader::read_file(&path) {
+            match crate::vault::reader::read_file_with(&path, &statuses) {
                 Ok(vf) => {
                     let is_jsonl = path.extension().and_then(|e| e.to_str()) == Some("jsonl");
                     if is_jsonl {
@@ -81,8 +105,24 @@ pub async fn watch_vault(
                             Err(e) => eprintln!("wardwell: index error for {}: {e}", path.display()),
                         }
                     } else {
+                        let rel_path = path.strip_prefix(&vault_root)
+                            .unwrap_or(&path)
+                            .to_string_lossy()
+                            .to_string();
+                        let previous_body = if is_tracked_state_file(&rel_path) {
+                            index.get_body(&rel_path).ok().flatten()
+                        } else {
+                            None
+                        };
                         match index.upsert(&vf, &vault_root) {
-                            Ok(true) => eprintln!("wardwell: indexed {}", path.display()),
+                            Ok(true) => {
+                                eprintln!("wardwell: indexed {}", path.display());
+                                // A changed hash here means the file was edited outside wardwell_write,
+                                // which reindexes its own writes before the watcher sees them.
+                                if let Some(ref old) = previous_body {
+                                    record_manual_edit(&vault_root, &rel_path, old, &vf, &durability);
+                                }
+                            }
                             Ok(false) => {} // unchanged
                             Err(e) => eprintln!("wardwell: index error for {}: {e}", path.display()),
                         }
@@ -105,7 +145,158 @@ pub async fn watch_vault(
                 let _ = index.remove_watermark(&relative);
             }
         }
+
+        if let Err(e) = index.mark_indexed() {
+            eprintln!("wardwell: failed to record index event: {e}");
+        }
     }
 
     Ok(())
 }
+
+/// Watch the `session_sources` directories and send each session transcript (`.jsonl`)
+/// that is created or appended to. Sources that don't exist yet are skipped. When the
+/// receiver falls behind, further changes are dropped until the next full indexing run.
+pub fn watch_sessions(sources: &[crate::config::loader::SessionSource]) -> mpsc::Receiver<PathBuf> {
+    let (tx, rx) = mpsc::channel::<PathBuf>(100);
+    let roots: Vec<PathBuf> = sources.iter().map(|s| s.path.clone()).filter(|p| p.is_dir()).collect();
+    if roots.is_empty() {
+        return rx;
+    }
+    std::thread::spawn(move || {
+        let mut watcher = match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res
+                && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            {
+                for path in event.paths.into_iter().filter(|p| p.extension().is_some_and(|e| e == "jsonl")) {
+                    let _ = tx.try_send(path);
+                }
+            }
+        }) {
+            Ok(w) => w,
+            Err(e) => {
+                eprintln!("wardwell: session watcher failed to start: {e}");
+                return;
+            }
+        };
+        for root in &roots {
+            if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+                eprintln!("wardwell: could not watch {}: {e}", root.display());
+            }
+        }
+        std::thread::park();
+    });
+    rx
+}
+
+/// Collect changes from `rx` until none has arrived for `quiet`, or `limit` has passed since
+/// `first`, so a session being written to is indexed once per pause instead of per line.
+/// Returns each changed path once.
+pub async fn settle(first: PathBuf, rx: &mut mpsc::Receiver<PathBuf>, quiet: Duration, limit: Duration) -> Vec<PathBuf> {
+    let deadline = tokio::time::Instant::now() + limit;
+    let mut paths = vec![first];
+    loop {
+        let left = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if left.is_zero() {
+            return paths;
+        }
+        match tokio::time::timeout(quiet.min(left), rx.recv()).await {
+            Ok(Some(path)) => {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+            Ok(None) | Err(_) => return paths,
+        }
+    }
+}
+
+/// Project files whose hand edits are recorded in history: `domain/project/{current_state,INDEX}.md`.
+fn is_tracked_state_file(rel_path: &str) -> bool {
+    let parts: Vec<&str> = rel_path.split('/').collect();
+    parts.len() == 3
+        && parts[0] != "domains"
+        && (parts[2] == "current_state.md" || parts[2] == "INDEX.md")
+}
+
+/// Append a `source: manual` history entry describing which sections of a tracked file changed.
+fn record_manual_edit(
+    vault_root: &std::path::Path,
+    rel_path: &str,
+    old_body: &str,
+    vf: &crate::vault::types::VaultFile,
+    durability: &crate::config::loader::DurabilityConfig,
+) {
+    let sections = crate::vault::diff::changed_sections(old_body, &vf.body);
+    if sections.is_empty() {
+        return;
+    }
+    let Some((project_rel, file_name)) = rel_path.rsplit_once('/') else {
+        return;
+    };
+    let labels: Vec<&str> = sections
+        .iter()
+        .map(|s| if s.is_empty() { "(preamble)" } else { s.as_str() })
+        .collect();
+    let entry = crate::mcp::server::HistoryJsonlEntry {
+        id: crate::vault::entry_id::new_id(),
+        date: chrono::Utc::now().to_rfc3339(),
+        title: format!("Manual edit: {file_name}"),
+        status: vf.frontmatter.status.as_ref().map(|s| s.to_string()).unwrap_or_default(),
+        focus: crate::mcp::server::extract_section(&vf.body, "Focus"),
+        next_action: crate::mcp::server::extract_section(&vf.body, "Next Action"),
+        commit: String::new(),
+        body: format!("Sections changed in {file_name}: {}", labels.join(", ")),
+        source: "manual".to_string(),
+        blockers: crate::mcp::server::section_bullets(&vf.body, "Blockers"),
+        waiting_on: crate::mcp::server::section_bullets(&vf.body, "Waiting On"),
+    };
+    let json = match serde_json::to_string(&entry) {
+        Ok(j) => j,
+        Err(e) => {
+            eprintln!("wardwell: failed to serialize manual edit entry: {e}");
+            return;
+        }
+    };
+    let history_path = vault_root.join(project_rel).join("history.jsonl");
+    match crate::mcp::server::append_jsonl(&history_path, "history", &json, durability) {
+        Ok(()) => eprintln!("wardwell: recorded manual edit to {rel_path} ({})", labels.join(", ")),
+        Err(e) => eprintln!("wardwell: failed to record manual edit to {rel_path}: {e}"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracked_state_files() {
+        assert!(is_tracked_state_file("work/api/current_state.md"));
+        assert!(is_tracked_state_file("work/api/INDEX.md"));
+        assert!(!is_tracked_state_file("work/api/decisions.md"));
+        assert!(!is_tracked_state_file("work/api/docs/current_state.md"));
+        assert!(!is_tracked_state_file("domains/work/INDEX.md"));
+    }
+
+    #[tokio::test]
+    async fn settle_collects_a_burst_once_per_path() {
+        let (tx, mut rx) = mpsc::channel(10);
+        for name in ["b.jsonl", "a.jsonl", "b.jsonl"] {
+            tx.send(PathBuf::from(name)).await.unwrap();
+        }
+        let paths = settle(PathBuf::from("a.jsonl"), &mut rx, Duration::from_millis(50), Duration::from_secs(5)).await;
+        assert_eq!(paths, [PathBuf::from("a.jsonl"), PathBuf::from("b.jsonl")]);
+
+        // A steady stream is cut off at the limit
+        let feeder = tokio::spawn(async move {
+            while tx.send(PathBuf::from("c.jsonl")).await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        let started = std::time::Instant::now();
+        settle(PathBuf::from("c.jsonl"), &mut rx, Duration::from_millis(50), Duration::from_millis(200)).await;
+        assert!(started.elapsed() < Duration::from_secs(2));
+        feeder.abort();
+    }
+}
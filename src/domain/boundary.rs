@@ -80,6 +80,7 @@ mod tests {
                 .unwrap()],
             aliases: HashMap::new(),
             can_read: Vec::new(),
+            seed_template: None,
         };
 
         (dir, domain)
@@ -14,6 +14,9 @@ pub struct Domain {
     /// Omitted or empty = self-only access.
     #[serde(default)]
     pub can_read: Vec<String>,
+    /// Template under `~/.wardwell/templates/` that `wardwell seed` uses for new projects here.
+    #[serde(default)]
+    pub seed_template: Option<String>,
 }
 
 impl Domain {
@@ -24,7 +27,8 @@ impl Domain {
 
     /// Build a Domain from a vault file with `type: domain` and `confidence: confirmed`.
     /// Name comes from frontmatter `domain` field, or filename stem.
-    /// Paths from `## Paths` section, aliases from `## Aliases` section.
+    /// Paths from `## Paths` section, aliases from `## Aliases` section, the seed template
+    /// from `## Seed Template`.
     pub fn from_vault_file(vf: &VaultFile) -> Result<Domain, ConfigError> {
         // Must be type: domain
         if vf.frontmatter.file_type != VaultType::Domain {
@@ -53,6 +57,7 @@ impl Domain {
 
         let mut paths = Vec::new();
         let mut aliases = HashMap::new();
+        let mut seed_template = None;
 
         let mut current_section: Option<&str> = None;
 
@@ -63,6 +68,9 @@ impl Domain {
             } else if line.starts_with("## Aliases") {
                 current_section = Some("aliases");
                 continue;
+            } else if line.starts_with("## Seed Template") {
+                current_section = Some("seed_template");
+                continue;
             } else if line.starts_with("## ") {
                 current_section = None;
                 continue;
@@ -85,13 +93,57 @@ impl Domain {
                         aliases.insert(key.trim().to_string(), value.trim().to_string());
                     }
                 }
+                Some("seed_template") if seed_template.is_none() => {
+                    seed_template = Some(item.trim().to_string());
+                }
                 _ => {}
             }
         }
 
         let can_read = vf.frontmatter.can_read.clone();
 
-        Ok(Domain { name, paths, aliases, can_read })
+        Ok(Domain { name, paths, aliases, can_read, seed_template })
+    }
+
+    /// Everything `from_vault_file` would reject or quietly skip in a `type: domain` file,
+    /// each with the fix. Empty when the file loads exactly as written.
+    pub fn problems(vf: &VaultFile) -> Vec<String> {
+        let mut problems = Vec::new();
+        match &vf.frontmatter.confidence {
+            Some(Confidence::Confirmed) => {}
+            Some(other) => problems.push(format!("confidence is '{other}'; set `confidence: confirmed` to activate this domain")),
+            None => problems.push("confidence is missing or not one of inferred, proposed, confirmed; set `confidence: confirmed`".to_string()),
+        }
+        if let Some(name) = &vf.frontmatter.domain
+            && let Err(e) = DomainName::new(name)
+        {
+            problems.push(e.to_string());
+        }
+
+        let mut section = "";
+        let mut seed_templates = 0;
+        for line in vf.body.lines() {
+            if let Some(heading) = line.strip_prefix("## ") {
+                section = heading.trim();
+                continue;
+            }
+            let Some(item) = line.trim().strip_prefix("- ") else {
+                continue;
+            };
+            if section.starts_with("Paths") {
+                if let Err(e) = PathGlob::new(item) {
+                    problems.push(format!("## Paths: {e}"));
+                }
+            } else if section.starts_with("Aliases") && !item.contains(": ") {
+                problems.push(format!("## Aliases: '{item}' should be `- name: path`"));
+            } else if section.starts_with("Seed Template") {
+                seed_templates += 1;
+                if seed_templates == 2 {
+                    problems.push("## Seed Template: only the first template is used; keep one `- name`".to_string());
+                }
+            }
+        }
+        problems
     }
 }
 
@@ -109,6 +161,7 @@ mod tests {
             paths: vec![PathGlob::new("/tmp/test/*").unwrap()],
             aliases: HashMap::new(),
             can_read: Vec::new(),
+            seed_template: None,
         }
     }
 
@@ -139,7 +192,7 @@ mod tests {
                 tags: Vec::new(),
                 can_read: Vec::new(),
             },
-            body: "## Paths\n- ~/Code/myapp-*/*\n- ~/Code/mycompany/*\n\n## Aliases\n- repos: ~/Code\n- docs: ~/Documents/myapp\n".to_string(),
+            body: "## Paths\n- ~/Code/myapp-*/*\n- ~/Code/mycompany/*\n\n## Aliases\n- repos: ~/Code\n- docs: ~/Documents/myapp\n\n## Seed Template\n- client\n".to_string(),
         };
 
         let domain = Domain::from_vault_file(&vf);
@@ -149,6 +202,7 @@ mod tests {
         assert_eq!(domain.paths.len(), 2);
         assert_eq!(domain.aliases.get("repos").map(|s| s.as_str()), Some("~/Code"));
         assert_eq!(domain.aliases.get("docs").map(|s| s.as_str()), Some("~/Documents/myapp"));
+        assert_eq!(domain.seed_template.as_deref(), Some("client"));
     }
 
     #[test]
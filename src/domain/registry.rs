@@ -1,11 +1,38 @@
 use crate::domain::model::Domain;
 use crate::vault::types::{Confidence, VaultType};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::path::Path;
 
 /// Registry of active domains, loaded from vault files.
 #[derive(Debug, Clone)]
 pub struct DomainRegistry {
     domains: Vec<Domain>,
+    /// Domain files that failed validation on the last load.
+    errors: Vec<DomainFileError>,
+    /// Last valid definition from each domain file (vault-relative path), kept across reloads.
+    last_good: HashMap<String, Domain>,
+}
+
+/// A `domains/*.md` file that didn't load as written.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DomainFileError {
+    /// Vault-relative path, e.g. `domains/work.md`.
+    pub file: String,
+    pub problems: Vec<String>,
+    /// What the registry used instead, if anything.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<Fallback>,
+}
+
+/// The definition a registry keeps for a domain whose file has problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fallback {
+    /// The definition from before the file broke.
+    LastKnownGood,
+    /// The parts of the file that parse, e.g. every path but the bad glob.
+    ValidParts,
 }
 
 impl DomainRegistry {
@@ -13,46 +40,76 @@ impl DomainRegistry {
     /// Only loads files with `type: domain`, `confidence: confirmed`.
     /// Falls back to auto-discovering domains from top-level vault subdirectories.
     pub fn from_vault(vault_path: &Path) -> Self {
-        // Try explicit domain files first
-        let domains_dir = vault_path.join("domains");
-        if domains_dir.exists() {
-            let entries = match std::fs::read_dir(&domains_dir) {
-                Ok(e) => e,
-                Err(_) => return Self::auto_discover(vault_path),
-            };
+        Self::load(vault_path, &HashMap::new())
+    }
 
-            let mut domains = Vec::new();
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|e| e.to_str()) != Some("md") {
-                    continue;
-                }
+    /// Rebuild from the vault, as on a domain file change. A file that was valid before and
+    /// now has problems keeps its previous definition rather than dropping the domain.
+    pub fn reload(&self, vault_path: &Path) -> Self {
+        Self::load(vault_path, &self.last_good)
+    }
 
-                let vf = match crate::vault::reader::read_file(&path) {
-                    Ok(vf) => vf,
-                    Err(_) => continue,
-                };
+    fn load(vault_path: &Path, previous: &HashMap<String, Domain>) -> Self {
+        let domains_dir = vault_path.join("domains");
+        let mut domains = Vec::new();
+        let mut errors = Vec::new();
+        let mut last_good = HashMap::new();
+
+        let mut paths: Vec<_> = std::fs::read_dir(&domains_dir).into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let file = format!("domains/{}", path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+            let (problems, parsed, confirmed) = match crate::vault::reader::read_file(&path) {
+                // Other notes may live alongside domain files
+                Ok(vf) if vf.frontmatter.file_type != VaultType::Domain => continue,
+                Ok(vf) => (
+                    Domain::problems(&vf),
+                    Domain::from_vault_file(&vf).ok(),
+                    vf.frontmatter.confidence == Some(Confidence::Confirmed),
+                ),
+                Err(e) => (vec![e.to_string()], None, false),
+            };
+            if problems.is_empty()
+                && let Some(domain) = parsed
+            {
+                last_good.insert(file, domain.clone());
+                domains.push(domain);
+                continue;
+            }
 
-                if vf.frontmatter.file_type != VaultType::Domain {
-                    continue;
+            let fallback = match (previous.get(&file), parsed) {
+                (Some(good), _) => {
+                    last_good.insert(file.clone(), good.clone());
+                    domains.push(good.clone());
+                    Some(Fallback::LastKnownGood)
                 }
-
-                if vf.frontmatter.confidence != Some(Confidence::Confirmed) {
-                    continue;
+                // Unconfirmed domains were never active, so there is nothing to fall back to
+                (None, Some(partial)) if confirmed => {
+                    domains.push(partial);
+                    Some(Fallback::ValidParts)
                 }
+                _ => None,
+            };
+            errors.push(DomainFileError { file, problems, fallback });
+        }
 
-                if let Ok(domain) = Domain::from_vault_file(&vf) {
-                    domains.push(domain);
-                }
-            }
-
-            if !domains.is_empty() {
-                return Self { domains };
-            }
+        if domains.is_empty() {
+            // Fallback: auto-discover from top-level vault subdirectories
+            let mut discovered = Self::auto_discover(vault_path);
+            discovered.errors = errors;
+            return discovered;
         }
+        Self { domains, errors, last_good }
+    }
 
-        // Fallback: auto-discover from top-level vault subdirectories
-        Self::auto_discover(vault_path)
+    /// Domain files that failed validation on the last load or reload.
+    pub fn errors(&self) -> &[DomainFileError] {
+        &self.errors
     }
 
     /// Auto-discover domains from top-level vault subdirectories.
@@ -60,7 +117,7 @@ impl DomainRegistry {
     fn auto_discover(vault_path: &Path) -> Self {
         let entries = match std::fs::read_dir(vault_path) {
             Ok(e) => e,
-            Err(_) => return Self { domains: Vec::new() },
+            Err(_) => return Self::empty(),
         };
 
         let skip = ["domains", ".obsidian", ".trash", "archive", "templates"];
@@ -77,23 +134,24 @@ impl DomainRegistry {
                 domains.push(Domain {
                     name: domain_name,
                     paths: Vec::new(),
-                    aliases: std::collections::HashMap::new(),
+                    aliases: HashMap::new(),
                     can_read: Vec::new(),
+                    seed_template: None,
                 });
             }
         }
 
-        Self { domains }
+        Self::from_domains(domains)
     }
 
     /// Build a registry from pre-existing Domain structs (for config fallback / migration).
     pub fn from_domains(domains: Vec<Domain>) -> Self {
-        Self { domains }
+        Self { domains, errors: Vec::new(), last_good: HashMap::new() }
     }
 
     /// Build an empty registry.
     pub fn empty() -> Self {
-        Self { domains: Vec::new() }
+        Self::from_domains(Vec::new())
     }
 
     /// Resolve which domain a path belongs to.
@@ -132,7 +190,6 @@ impl DomainRegistry {
 mod tests {
     use super::*;
     use crate::config::types::{DomainName, PathGlob};
-    use std::collections::HashMap;
 
     fn make_domain(name: &str, path_glob: &str) -> Domain {
         Domain {
@@ -140,6 +197,7 @@ mod tests {
             paths: vec![PathGlob::new(path_glob).unwrap()],
             aliases: HashMap::new(),
             can_read: Vec::new(),
+            seed_template: None,
         }
     }
 
@@ -204,6 +262,43 @@ mod tests {
         assert!(reg.resolve(Path::new("/tmp/inferred/foo")).is_none());
     }
 
+    #[test]
+    fn broken_domain_files_are_reported_and_keep_last_good() {
+        let dir = tempfile::tempdir().unwrap();
+        let domains_dir = dir.path().join("domains");
+        std::fs::create_dir_all(&domains_dir).unwrap();
+        let write = |name: &str, confidence: &str, paths: &str| {
+            std::fs::write(
+                domains_dir.join(format!("{name}.md")),
+                format!("---\ntype: domain\ndomain: {name}\nconfidence: {confidence}\n---\n## Paths\n{paths}"),
+            ).unwrap();
+        };
+        write("work", "confirmed", "- /tmp/work/*\n");
+        write("home", "confirmed", "- /tmp/home/*\n- /tmp/[broken\n");
+        write("draft", "proposed", "- /tmp/draft/*\n");
+
+        let reg = DomainRegistry::from_vault(dir.path());
+        assert_eq!(reg.names(), vec!["home", "work"]);
+        let files: Vec<(&str, Option<Fallback>)> = reg.errors().iter().map(|e| (e.file.as_str(), e.fallback)).collect();
+        assert_eq!(files, vec![("domains/draft.md", None), ("domains/home.md", Some(Fallback::ValidParts))]);
+        assert!(reg.errors()[0].problems[0].contains("confidence: confirmed"));
+        assert!(reg.errors()[1].problems[0].contains("[broken"));
+
+        // Breaking a valid file keeps its previous definition until it's fixed
+        write("work", "confirmd", "- /tmp/elsewhere/*\n");
+        let reg = reg.reload(dir.path());
+        let work = reg.errors().iter().find(|e| e.file == "domains/work.md").unwrap();
+        assert_eq!(work.fallback, Some(Fallback::LastKnownGood));
+        assert!(reg.resolve(Path::new("/tmp/work/api")).is_some_and(|d| d.name.as_str() == "work"));
+        let reg = reg.reload(dir.path());
+        assert!(reg.resolve(Path::new("/tmp/work/api")).is_some(), "still kept on a second reload");
+
+        write("work", "confirmed", "- /tmp/elsewhere/*\n");
+        let reg = reg.reload(dir.path());
+        assert!(!reg.errors().iter().any(|e| e.file == "domains/work.md"));
+        assert!(reg.resolve(Path::new("/tmp/elsewhere/api")).is_some());
+    }
+
     #[test]
     fn find_by_name() {
         let reg = DomainRegistry::from_domains(vec![
@@ -1,9 +1,11 @@
 use crate::index::chunk::{chunk_file, chunk_jsonl};
-use crate::index::embed::Embedder;
+use crate::index::embeddings::EmbeddingProvider;
 use crate::index::store::{IndexError, IndexStore};
-use crate::vault::reader::walk_vault_filtered;
+use crate::vault::reader::walk_vault_with;
+use crate::vault::symlinks::SymlinkPolicy;
+use crate::vault::types::Status;
 use sha2::{Digest, Sha256};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 
 /// Stats from an index build.
@@ -26,19 +28,22 @@ impl IndexBuilder {
     pub fn full_build(
         store: &IndexStore,
         vault_root: &Path,
-        embedder: Option<&mut Embedder>,
+        embedder: Option<&mut dyn EmbeddingProvider>,
     ) -> Result<BuildStats, IndexError> {
-        Self::build_filtered(store, vault_root, &[], embedder)
+        Self::build_filtered(store, vault_root, &[], SymlinkPolicy::default(), &BTreeMap::new(), embedder)
     }
 
-    /// Incremental build with exclusion patterns.
+    /// Incremental build with exclusion patterns, entering symlinks per `symlinks` and
+    /// indexing the extra states in `statuses` as the ones they stand for.
     pub fn build_filtered(
         store: &IndexStore,
         vault_root: &Path,
         exclude: &[String],
-        mut embedder: Option<&mut Embedder>,
+        symlinks: SymlinkPolicy,
+        statuses: &BTreeMap<String, Status>,
+        mut embedder: Option<&mut dyn EmbeddingProvider>,
     ) -> Result<BuildStats, IndexError> {
-        let results = walk_vault_filtered(vault_root, exclude);
+        let results = walk_vault_with(vault_root, exclude, symlinks, statuses);
         let mut indexed = 0;
         let mut skipped = 0;
         let mut errors = 0;
@@ -133,6 +138,7 @@ impl IndexBuilder {
 
         // Remove stale entries (files that no longer exist on disk)
         let removed = store.remove_stale(&seen_paths)?;
+        store.mark_indexed()?;
 
         Ok(BuildStats { indexed, skipped, removed, errors, chunks_embedded, error_details })
     }
@@ -166,7 +172,7 @@ fn index_jsonl_incremental(
     vf: &crate::vault::types::VaultFile,
     rel_path: &str,
     vault_root: &Path,
-    embedder: &mut Option<&mut Embedder>,
+    embedder: &mut Option<&mut dyn EmbeddingProvider>,
     error_details: &mut Vec<String>,
 ) -> Result<usize, IndexError> {
     let watermark = store.get_watermark(rel_path)?;
@@ -315,10 +321,44 @@ mod tests {
 
         let store = IndexStore::in_memory().unwrap();
         let exclude = vec!["node_modules".to_string()];
-        let stats = IndexBuilder::build_filtered(&store, dir.path(), &exclude, None).unwrap();
+        let stats = IndexBuilder::build_filtered(&store, dir.path(), &exclude, SymlinkPolicy::Follow, &BTreeMap::new(), None).unwrap();
         assert_eq!(stats.indexed, 3); // node_modules/junk.md excluded
     }
 
+    #[test]
+    fn configured_statuses_are_indexed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("work/api")).unwrap();
+        std::fs::write(dir.path().join("work/api/current_state.md"), "---\ntype: project\nstatus: review\n---\nup for review\n").unwrap();
+
+        let store = IndexStore::in_memory().unwrap();
+        let statuses = BTreeMap::from([("review".to_string(), Status::Blocked)]);
+        IndexBuilder::build_filtered(&store, dir.path(), &[], SymlinkPolicy::Follow, &statuses, None).unwrap();
+        assert_eq!(store.get_frontmatter("work/api/current_state.md").unwrap().status, Some(Status::Blocked));
+    }
+
+    #[test]
+    fn symlinked_projects_are_indexed_once_under_their_link() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (vault, docs) = (tmp.path().join("vault"), tmp.path().join("repo/docs"));
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(vault.join("work/api/INDEX.md"), "---\ntype: reference\n---\napi\n").unwrap();
+        std::fs::write(docs.join("guide.md"), "---\ntype: reference\n---\nguide\n").unwrap();
+        std::os::unix::fs::symlink(&docs, vault.join("work/docs")).unwrap();
+        std::os::unix::fs::symlink(&docs, docs.join("loop")).unwrap();
+        std::os::unix::fs::symlink(vault.join("work/api"), vault.join("work/api-alias")).unwrap();
+
+        let store = IndexStore::in_memory().unwrap();
+        let stats = IndexBuilder::build_filtered(&store, &vault, &[], SymlinkPolicy::Follow, &BTreeMap::new(), None).unwrap();
+        assert_eq!((stats.indexed, stats.errors), (2, 0));
+        assert_eq!(store.paths_under("work").unwrap(), ["work/api/INDEX.md", "work/docs/guide.md"]);
+
+        let stats = IndexBuilder::build_filtered(&store, &vault, &[], SymlinkPolicy::Skip, &BTreeMap::new(), None).unwrap();
+        assert_eq!(stats.removed, 1);
+        assert_eq!(store.paths_under("work").unwrap(), ["work/api/INDEX.md"]);
+    }
+
     #[test]
     fn build_removes_stale_entries() {
         let dir = tempfile::tempdir().unwrap();
@@ -504,6 +544,42 @@ mod tests {
         assert_eq!(results.results[0].frontmatter.file_type, crate::vault::types::VaultType::History);
     }
 
+    #[test]
+    fn machine_files_left_out_of_default_search() {
+        use crate::index::fts::SearchQuery;
+        use crate::vault::types::VaultType;
+
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("work").join("myproject");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("lessons.jsonl"),
+            "{\"_schema\": \"lessons\"}\n{\"date\": \"2026-03-15\", \"title\": \"Flaky zeppelin deploy\"}\n",
+        ).unwrap();
+        std::fs::write(
+            project_dir.join("history.jsonl"),
+            "{\"_schema\": \"history\"}\n{\"date\": \"2026-03-15\", \"title\": \"Fixed zeppelin deploy\"}\n",
+        ).unwrap();
+        std::fs::write(
+            dir.path().join("work").join("current_state.md"),
+            "---\ntype: domain\ngenerated: wardwell\n---\n# work\nzeppelin rollup\n",
+        ).unwrap();
+
+        let store = IndexStore::in_memory().unwrap();
+        IndexBuilder::full_build(&store, dir.path(), None).unwrap();
+
+        let q = SearchQuery { query: "zeppelin".to_string(), limit: 10, ..Default::default() };
+        let results = store.search(&q).unwrap();
+        let paths: Vec<&str> = results.results.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["work/myproject/history.jsonl"]);
+        assert!(!results.facets.file_type.contains_key("machine"));
+
+        let q = SearchQuery { types: vec![VaultType::Machine], ..q };
+        let results = store.search(&q).unwrap();
+        assert_eq!(results.total, 2);
+        assert!(results.results.iter().all(|r| r.frontmatter.file_type == VaultType::Machine));
+    }
+
     #[test]
     fn history_domain_inferred_from_path() {
         use crate::index::fts::SearchQuery;
@@ -0,0 +1,188 @@
+use crate::index::store::{IndexError, IndexStore};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Words per shingle.
+const SHINGLE_WORDS: usize = 5;
+/// Bodies with fewer shingles than this are too short to call duplicates.
+const MIN_SHINGLES: usize = 10;
+/// MinHash signature length: `BANDS` bands of `ROWS` rows.
+const BANDS: usize = 32;
+const ROWS: usize = 4;
+
+/// Project scaffolding every project has one of. Near-identical copies are expected.
+const SKIPPED_FILES: &[&str] = &["current_state.md", "INDEX.md", "decisions.md"];
+
+/// Two notes whose bodies overlap by at least the requested threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicatePair {
+    pub a: String,
+    pub b: String,
+    /// Jaccard similarity of the two bodies' word shingles, 0.0–1.0.
+    pub similarity: f64,
+    pub same_project: bool,
+    /// Which note to fold into which, and how.
+    pub suggestion: String,
+}
+
+/// One indexed note's shingles and what the suggestion needs to know about it.
+struct Note {
+    path: String,
+    updated: Option<String>,
+    words: usize,
+    shingles: HashSet<u64>,
+}
+
+impl Note {
+    /// `domain/project`, the first two path components.
+    fn project(&self) -> &str {
+        let mut cut = self.path.match_indices('/').map(|(i, _)| i);
+        match (cut.next(), cut.next()) {
+            (Some(_), Some(end)) => &self.path[..end],
+            _ => "",
+        }
+    }
+}
+
+impl IndexStore {
+    /// Markdown notes that say nearly the same thing, most similar first. Candidates come from
+    /// MinHash banding over 5-word shingles, then are scored by exact Jaccard similarity, so
+    /// the scores are real but a pair just over the threshold can occasionally be missed.
+    /// Machine files, and project scaffolding (current_state.md, INDEX.md, decisions.md),
+    /// are left out. `domains` limits the notes compared; empty means all.
+    pub fn duplicates(&self, threshold: f64, domains: &[String]) -> Result<Vec<DuplicatePair>, IndexError> {
+        let notes = self.dedupe_notes(domains)?;
+
+        let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+        for (i, note) in notes.iter().enumerate() {
+            let signature = minhash(&note.shingles);
+            for (band, rows) in signature.chunks(ROWS).enumerate() {
+                buckets.entry((band, hash_of(rows))).or_default().push(i);
+            }
+        }
+        let mut candidates: HashSet<(usize, usize)> = HashSet::new();
+        for members in buckets.values().filter(|m| m.len() > 1) {
+            for (n, &x) in members.iter().enumerate() {
+                for &y in &members[n + 1..] {
+                    candidates.insert((x.min(y), x.max(y)));
+                }
+            }
+        }
+
+        let mut pairs: Vec<DuplicatePair> = candidates.into_iter()
+            .filter_map(|(x, y)| {
+                let (a, b) = (&notes[x], &notes[y]);
+                let shared = a.shingles.intersection(&b.shingles).count();
+                let similarity = shared as f64 / (a.shingles.len() + b.shingles.len() - shared) as f64;
+                (similarity >= threshold).then(|| pair(a, b, similarity))
+            })
+            .collect();
+        pairs.sort_by(|p, q| q.similarity.total_cmp(&p.similarity).then_with(|| (&p.a, &p.b).cmp(&(&q.a, &q.b))));
+        Ok(pairs)
+    }
+
+    fn dedupe_notes(&self, domains: &[String]) -> Result<Vec<Note>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT m.path, m.domain, m.updated, s.body
+             FROM vault_meta m JOIN vault_search s ON s.path = m.path
+             WHERE m.path LIKE '%.md' AND m.type != 'machine'
+             ORDER BY m.path"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, String>(3)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows.into_iter()
+            .filter(|(path, domain, _, _)| {
+                let name = path.rsplit('/').next().unwrap_or(path);
+                !SKIPPED_FILES.contains(&name)
+                    && (domains.is_empty() || domain.as_ref().is_some_and(|d| domains.contains(d)))
+            })
+            .filter_map(|(path, _, updated, body)| {
+                let words: Vec<String> = body.split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .map(str::to_lowercase)
+                    .collect();
+                let shingles: HashSet<u64> = words.windows(SHINGLE_WORDS).map(hash_of).collect();
+                (shingles.len() >= MIN_SHINGLES).then_some(Note { path, updated, words: words.len(), shingles })
+            })
+            .collect())
+    }
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// SplitMix64 finalizer, seeded per signature row.
+fn mix(x: u64, seed: u64) -> u64 {
+    let mut z = x ^ seed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn minhash(shingles: &HashSet<u64>) -> Vec<u64> {
+    (0..(BANDS * ROWS) as u64)
+        .map(|seed| shingles.iter().map(|&s| mix(s, seed + 1)).min().unwrap_or(u64::MAX))
+        .collect()
+}
+
+/// Keep the note updated last (or, undated, the longer one) and fold the other into it.
+fn pair(a: &Note, b: &Note, similarity: f64) -> DuplicatePair {
+    let same_project = !a.project().is_empty() && a.project() == b.project();
+    let keep_a = match (&a.updated, &b.updated) {
+        (Some(x), Some(y)) if x != y => x > y,
+        _ => a.words >= b.words,
+    };
+    let (keep, fold) = if keep_a { (a, b) } else { (b, a) };
+    let why = if keep.updated.is_some() && keep.updated != fold.updated { "updated more recently" } else { "longer" };
+    let suggestion = if same_project {
+        format!("Merge {} into {} ({why}), then delete it.", fold.path, keep.path)
+    } else {
+        format!("Merge {} into {} ({why}); leave a note in {} linking to it, or list it under related:.", fold.path, keep.path, fold.project())
+    };
+    DuplicatePair { a: a.path.clone(), b: b.path.clone(), similarity: (similarity * 100.0).round() / 100.0, same_project, suggestion }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use crate::index::builder::IndexBuilder;
+    use crate::index::store::IndexStore;
+
+    const RUNBOOK: &str = "When the payments queue backs up, first check the worker dashboard for stuck jobs. \
+        Restart the consumer pool with the deploy script, then watch the lag metric fall for ten minutes. \
+        If it keeps growing, page the on-call engineer and open an incident in the tracker.";
+
+    #[test]
+    fn near_copies_pair_up_and_distinct_notes_do_not() {
+        let tmp = tempfile::tempdir().unwrap();
+        let note = |rel: &str, updated: &str, body: &str| {
+            let path = tmp.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, format!("---\ntype: reference\nupdated: {updated}\n---\n{body}\n")).unwrap();
+        };
+        note("work/payments/runbook.md", "2026-01-10", RUNBOOK);
+        note("work/oncall/queue-runbook.md", "2026-03-02", &format!("{RUNBOOK} Afterwards, write the timeline."));
+        note("work/payments/unrelated.md", "2026-03-05", "Quarterly planning covers hiring, the roadmap for the mobile app, \
+            and a budget review with finance before the board meeting next month.");
+        note("work/payments/current_state.md", "2026-03-05", RUNBOOK);
+        let store = IndexStore::in_memory().unwrap();
+        IndexBuilder::full_build(&store, tmp.path(), None).unwrap();
+
+        let pairs = store.duplicates(0.8, &[]).unwrap();
+        assert_eq!(pairs.len(), 1, "{pairs:?}");
+        let pair = &pairs[0];
+        assert_eq!((pair.a.as_str(), pair.b.as_str()), ("work/oncall/queue-runbook.md", "work/payments/runbook.md"));
+        assert!(pair.similarity >= 0.8 && pair.similarity < 1.0, "{}", pair.similarity);
+        assert!(!pair.same_project);
+        assert!(pair.suggestion.starts_with("Merge work/payments/runbook.md into work/oncall/queue-runbook.md (updated more recently)"), "{}", pair.suggestion);
+
+        assert!(store.duplicates(0.8, &["personal".to_string()]).unwrap().is_empty());
+    }
+}
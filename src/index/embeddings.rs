@@ -0,0 +1,35 @@
+use crate::index::embed::Embedder;
+use crate::index::store::IndexError;
+
+/// Width of the vectors the chunk index stores. A provider has to return vectors this wide.
+pub const EMBEDDING_DIM: usize = 384;
+
+/// Turns text into vectors for the chunk index and for `vector` and `hybrid` search. The
+/// local BGE model (`embed::Embedder`) is the default; anything else that can embed text (an
+/// HTTP API, a test double) plugs in wherever the index or the server takes a provider.
+pub trait EmbeddingProvider: Send {
+    /// One vector per text, in input order.
+    fn embed_batch(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>, IndexError>;
+
+    /// The vector for a search query. Defaults to a batch of one.
+    fn embed_query(&mut self, query: &str) -> Result<Vec<f32>, IndexError> {
+        self.embed_batch(&[query.to_string()])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| IndexError::Embedding("No embedding returned".to_string()))
+    }
+}
+
+impl EmbeddingProvider for Embedder {
+    fn embed_batch(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>, IndexError> {
+        Embedder::embed_batch(self, texts)
+    }
+
+    fn embed_query(&mut self, query: &str) -> Result<Vec<f32>, IndexError> {
+        Embedder::embed_query(self, query)
+    }
+}
+
+/// The provider the server searches with: loaded in the background, so None until then (or
+/// when no model is available).
+pub type SharedProvider = std::sync::Arc<std::sync::Mutex<Option<Box<dyn EmbeddingProvider>>>>;
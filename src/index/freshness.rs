@@ -0,0 +1,153 @@
+use crate::index::store::{IndexError, IndexStore};
+use crate::vault::symlinks::{SymlinkPolicy, Walk};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+/// The index lags behind the vault by more than the configured threshold.
+#[derive(Debug, Clone)]
+pub struct Staleness {
+    /// Last successful index event. None if the index has never recorded one.
+    pub last_indexed: Option<DateTime<Utc>>,
+    /// Modification time of the newest indexable vault file.
+    pub newest_change: DateTime<Utc>,
+    /// Vault-relative path of that file.
+    pub newest_path: String,
+}
+
+impl Staleness {
+    /// One-line warning suitable for tool responses and doctor output.
+    pub fn message(&self) -> String {
+        match self.last_indexed {
+            Some(last) => {
+                let lag = (self.newest_change - last).num_minutes();
+                format!(
+                    "index may be stale: {} changed {lag}m after the last index update ({}). The file watcher may have stopped — run `wardwell reindex` or restart the server.",
+                    self.newest_path,
+                    last.format("%Y-%m-%d %H:%M UTC"),
+                )
+            }
+            None => format!(
+                "index may be stale: no index update has been recorded, but {} exists. Run `wardwell reindex`.",
+                self.newest_path,
+            ),
+        }
+    }
+}
+
+/// Newest modification time among .md/.jsonl files under `root`, skipping excluded names
+/// and entering symlinks per `symlinks`.
+pub fn newest_mtime(root: &Path, exclude: &[String], symlinks: SymlinkPolicy) -> Option<(DateTime<Utc>, PathBuf)> {
+    let mut walk = Walk::new(root, symlinks);
+    let mut newest: Option<(DateTime<Utc>, PathBuf)> = None;
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if exclude.iter().any(|e| e == name.as_ref()) {
+                continue;
+            }
+            if path.is_dir() {
+                if walk.enters(&path) {
+                    stack.push(path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "md" || ext == "jsonl")
+                && walk.reads(&path)
+                && let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified())
+            {
+                let modified: DateTime<Utc> = modified.into();
+                if newest.as_ref().is_none_or(|(n, _)| modified > *n) {
+                    newest = Some((modified, path));
+                }
+            }
+        }
+    }
+    newest
+}
+
+/// Compare the store's last index event against the newest vault mtime.
+/// Returns Some when a vault file changed more than `threshold` after the last index event.
+pub fn check(
+    store: &IndexStore,
+    vault_root: &Path,
+    exclude: &[String],
+    symlinks: SymlinkPolicy,
+    threshold: chrono::Duration,
+) -> Result<Option<Staleness>, IndexError> {
+    assess(store, vault_root, newest_mtime(vault_root, exclude, symlinks), threshold)
+}
+
+/// `check` against a newest vault change already found by `newest_mtime`, for callers that
+/// keep it between checks.
+pub fn assess(
+    store: &IndexStore,
+    vault_root: &Path,
+    newest: Option<(DateTime<Utc>, PathBuf)>,
+    threshold: chrono::Duration,
+) -> Result<Option<Staleness>, IndexError> {
+    let Some((newest_change, newest_path)) = newest else {
+        return Ok(None);
+    };
+    let last_indexed = store.last_indexed_at()?;
+    let stale = match last_indexed {
+        Some(last) => newest_change - last > threshold,
+        None => true,
+    };
+    if !stale {
+        return Ok(None);
+    }
+    Ok(Some(Staleness {
+        last_indexed,
+        newest_change,
+        newest_path: newest_path
+            .strip_prefix(vault_root)
+            .unwrap_or(&newest_path)
+            .to_string_lossy()
+            .to_string(),
+    }))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn newest_mtime_skips_excluded_and_other_extensions() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("work/api")).unwrap();
+        std::fs::create_dir_all(tmp.path().join(".obsidian")).unwrap();
+        std::fs::write(tmp.path().join("work/api/INDEX.md"), "# api").unwrap();
+        std::fs::write(tmp.path().join("work/api/notes.txt"), "ignored").unwrap();
+        std::fs::write(tmp.path().join(".obsidian/workspace.md"), "ignored").unwrap();
+
+        let (_, path) = newest_mtime(tmp.path(), &[".obsidian".to_string()], SymlinkPolicy::Follow).unwrap();
+        assert!(path.ends_with("work/api/INDEX.md"));
+    }
+
+    #[test]
+    fn check_reports_never_indexed_vault() {
+        let tmp = tempdir().unwrap();
+        std::fs::write(tmp.path().join("note.md"), "# note").unwrap();
+        let store = IndexStore::in_memory().unwrap();
+
+        let stale = check(&store, tmp.path(), &[], SymlinkPolicy::Follow, chrono::Duration::minutes(10)).unwrap().unwrap();
+        assert!(stale.last_indexed.is_none());
+        assert_eq!(stale.newest_path, "note.md");
+    }
+
+    #[test]
+    fn check_passes_after_mark_indexed() {
+        let tmp = tempdir().unwrap();
+        std::fs::write(tmp.path().join("note.md"), "# note").unwrap();
+        let store = IndexStore::in_memory().unwrap();
+        store.mark_indexed().unwrap();
+
+        assert!(check(&store, tmp.path(), &[], SymlinkPolicy::Follow, chrono::Duration::minutes(10)).unwrap().is_none());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::index::store::{IndexError, IndexStore};
 use crate::vault::types::{Confidence, Frontmatter, Status, VaultType};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Search query parameters.
 #[derive(Debug, Clone, Default)]
@@ -10,6 +11,13 @@ pub struct SearchQuery {
     pub domains: Option<Vec<String>>,
     pub types: Vec<VaultType>,
     pub status: Option<Status>,
+    /// Only documents carrying every one of these tags (case-insensitive). With an empty
+    /// `query`, lists the tagged documents, most recently updated first.
+    pub tags: Vec<String>,
+    /// Leave out documents containing any of these words or phrases.
+    pub exclude_terms: Vec<String>,
+    /// Leave out documents in these domains.
+    pub exclude_domains: Vec<String>,
     pub limit: usize,
 }
 
@@ -28,63 +36,60 @@ pub struct SearchResults {
     pub total: usize,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub suggestions: Vec<String>,
+    /// Match counts across all hits, not just the returned page.
+    #[serde(default, skip_serializing_if = "SearchFacets::is_empty")]
+    pub facets: SearchFacets,
 }
 
+/// Per-domain, per-type, and per-status counts of every document matching a query.
+/// Lets a caller offer "12 of 15 hits are in work" without another search.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFacets {
+    /// Distinct documents matching the query and filters.
+    pub total: usize,
+    pub domain: BTreeMap<String, usize>,
+    #[serde(rename = "type")]
+    pub file_type: BTreeMap<String, usize>,
+    pub status: BTreeMap<String, usize>,
+}
+
+impl SearchFacets {
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+}
+
+/// `text` as one FTS5 phrase, so operators and hyphens inside it match literally.
+fn fts_phrase(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Facet key for documents with no value in a column.
+const FACET_NONE: &str = "(none)";
+
 impl IndexStore {
     /// Full-text search the vault index.
     pub fn search(&self, q: &SearchQuery) -> Result<SearchResults, IndexError> {
         let limit = if q.limit == 0 { 5 } else { q.limit };
 
         // Build the FTS5 query with filters
-        let mut sql = String::from(
+        let (filters, params) = Self::search_filters(q);
+        let matching = !q.query.trim().is_empty();
+        let (snippet, order) = if matching {
+            ("snippet(vault_search, 7, '', '', '...', 40)", "rank")
+        } else {
+            ("IFNULL(m.summary, '')", "m.updated DESC")
+        };
+        let sql = format!(
             "SELECT m.path, m.type, m.domain, m.status, m.confidence, m.updated,
                     m.summary, m.related, m.tags,
-                    snippet(vault_search, 7, '', '', '...', 40) as snip
-             FROM vault_search s
-             JOIN vault_meta m ON s.path = m.path
-             WHERE vault_search MATCH ?1"
+                    {snippet} as snip
+             FROM {}
+             WHERE {filters}
+             ORDER BY {order} LIMIT {}",
+            Self::search_source(q),
+            limit * 3,
         );
-        let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-        // Quote the query to prevent FTS5 operator interpretation (e.g. hyphens as NOT)
-        let quoted_query = format!("\"{}\"", q.query.replace('"', "\"\""));
-        params.push(Box::new(quoted_query));
-
-        let mut param_idx = 2;
-
-        if let Some(ref domains) = q.domains {
-            if domains.len() == 1 {
-                sql.push_str(&format!(" AND m.domain = ?{param_idx}"));
-                params.push(Box::new(domains[0].clone()));
-                param_idx += 1;
-            } else if !domains.is_empty() {
-                let placeholders: Vec<String> = domains.iter().enumerate().map(|(i, _)| {
-                    format!("?{}", param_idx + i)
-                }).collect();
-                sql.push_str(&format!(" AND m.domain IN ({})", placeholders.join(", ")));
-                for d in domains {
-                    params.push(Box::new(d.clone()));
-                }
-                param_idx += domains.len();
-            }
-        }
-
-        if !q.types.is_empty() {
-            let placeholders: Vec<String> = q.types.iter().enumerate().map(|(i, _)| {
-                format!("?{}", param_idx + i)
-            }).collect();
-            sql.push_str(&format!(" AND m.type IN ({})", placeholders.join(", ")));
-            for t in &q.types {
-                params.push(Box::new(t.to_string()));
-            }
-            param_idx += q.types.len();
-        }
-
-        if let Some(ref status) = q.status {
-            sql.push_str(&format!(" AND m.status = ?{param_idx}"));
-            params.push(Box::new(status.to_string()));
-        }
-
-        sql.push_str(&format!(" ORDER BY rank LIMIT {}", limit * 3));
 
         // Scope the lock so it's dropped before fuzzy_suggestions
         let mut results = Vec::new();
@@ -135,11 +140,171 @@ impl IndexStore {
         let total = results.len();
 
         if results.is_empty() {
-            let suggestions = self.fuzzy_suggestions(&q.query)?;
-            return Ok(SearchResults { results, total: 0, suggestions });
+            let suggestions = if matching { self.fuzzy_suggestions(&q.query)? } else { Vec::new() };
+            return Ok(SearchResults { results, total: 0, suggestions, facets: SearchFacets::default() });
         }
 
-        Ok(SearchResults { results, total, suggestions: Vec::new() })
+        let facets = self.search_facets(q)?;
+        Ok(SearchResults { results, total, suggestions: Vec::new(), facets })
+    }
+
+    /// FROM clause for a search query: the FTS table when there's text to match, else just
+    /// the metadata (a tag-only listing).
+    fn search_source(q: &SearchQuery) -> &'static str {
+        if q.query.trim().is_empty() {
+            "vault_meta m"
+        } else {
+            "vault_search s JOIN vault_meta m ON s.path = m.path"
+        }
+    }
+
+    /// WHERE clause and bound params for a search query.
+    /// Shared by the hit query and the facet aggregate so both see the same documents.
+    fn search_filters(q: &SearchQuery) -> (String, Vec<Box<dyn rusqlite::types::ToSql>>) {
+        let mut filters = String::new();
+        let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+        let excluded: Vec<String> = q.exclude_terms.iter()
+            .filter(|t| !t.trim().is_empty())
+            .map(|t| fts_phrase(t.trim()))
+            .collect();
+        if q.query.trim().is_empty() {
+            filters.push_str("1 = 1");
+        } else {
+            // Quote the query to prevent FTS5 operator interpretation (e.g. hyphens as NOT)
+            let mut fts_query = fts_phrase(&q.query);
+            for term in &excluded {
+                fts_query.push_str(&format!(" NOT {term}"));
+            }
+            filters.push_str("vault_search MATCH ?1");
+            params.push(Box::new(fts_query));
+        }
+
+        let mut param_idx = params.len() + 1;
+
+        // A tag-only listing has no MATCH to hang NOT clauses on
+        if q.query.trim().is_empty() && !excluded.is_empty() {
+            filters.push_str(&format!(" AND m.path NOT IN (SELECT path FROM vault_search WHERE vault_search MATCH ?{param_idx})"));
+            params.push(Box::new(excluded.join(" OR ")));
+            param_idx += 1;
+        }
+
+        if let Some(ref domains) = q.domains {
+            if domains.len() == 1 {
+                filters.push_str(&format!(" AND m.domain = ?{param_idx}"));
+                params.push(Box::new(domains[0].clone()));
+                param_idx += 1;
+            } else if !domains.is_empty() {
+                let placeholders: Vec<String> = domains.iter().enumerate().map(|(i, _)| {
+                    format!("?{}", param_idx + i)
+                }).collect();
+                filters.push_str(&format!(" AND m.domain IN ({})", placeholders.join(", ")));
+                for d in domains {
+                    params.push(Box::new(d.clone()));
+                }
+                param_idx += domains.len();
+            }
+        }
+
+        if !q.exclude_domains.is_empty() {
+            let placeholders: Vec<String> = q.exclude_domains.iter().enumerate().map(|(i, _)| {
+                format!("?{}", param_idx + i)
+            }).collect();
+            filters.push_str(&format!(" AND IFNULL(m.domain, '') NOT IN ({})", placeholders.join(", ")));
+            for d in &q.exclude_domains {
+                params.push(Box::new(d.clone()));
+            }
+            param_idx += q.exclude_domains.len();
+        }
+
+        if !q.types.is_empty() {
+            let placeholders: Vec<String> = q.types.iter().enumerate().map(|(i, _)| {
+                format!("?{}", param_idx + i)
+            }).collect();
+            filters.push_str(&format!(" AND m.type IN ({})", placeholders.join(", ")));
+            for t in &q.types {
+                params.push(Box::new(t.to_string()));
+            }
+            param_idx += q.types.len();
+        } else {
+            // Machine files only turn up when asked for by type
+            filters.push_str(" AND IFNULL(m.type, '') != 'machine'");
+        }
+
+        if let Some(ref status) = q.status {
+            filters.push_str(&format!(" AND m.status = ?{param_idx}"));
+            params.push(Box::new(status.to_string()));
+            param_idx += 1;
+        }
+
+        // Tags are stored joined by ", "; padding both sides matches whole tags only
+        for tag in &q.tags {
+            filters.push_str(&format!(" AND instr(', ' || lower(IFNULL(m.tags, '')) || ', ', ?{param_idx}) > 0"));
+            params.push(Box::new(format!(", {}, ", tag.trim().to_lowercase())));
+            param_idx += 1;
+        }
+
+        (filters, params)
+    }
+
+    /// Count every document matching the query, grouped by domain, type, and status.
+    fn search_facets(&self, q: &SearchQuery) -> Result<SearchFacets, IndexError> {
+        let (filters, params) = Self::search_filters(q);
+        let sql = format!(
+            "SELECT m.domain, m.type, m.status, COUNT(DISTINCT m.path)
+             FROM {}
+             WHERE {filters}
+             GROUP BY m.domain, m.type, m.status",
+            Self::search_source(q),
+        );
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(param_refs.as_slice(), |row| {
+            let domain: Option<String> = row.get(0)?;
+            let file_type: Option<String> = row.get(1)?;
+            let status: Option<String> = row.get(2)?;
+            let count: i64 = row.get(3)?;
+            Ok((domain, file_type, status, count as usize))
+        })?;
+
+        let mut facets = SearchFacets::default();
+        for row in rows {
+            let (domain, file_type, status, count) = row?;
+            facets.total += count;
+            let key = |v: Option<String>| v.filter(|s| !s.is_empty()).unwrap_or_else(|| FACET_NONE.to_string());
+            *facets.domain.entry(key(domain)).or_default() += count;
+            *facets.file_type.entry(key(file_type)).or_default() += count;
+            *facets.status.entry(key(status)).or_default() += count;
+        }
+        Ok(facets)
+    }
+
+    /// How many indexed documents carry each tag, per domain. Tags are lowercased so `Rust`
+    /// and `rust` count together, as tag filters match them. Machine files are left out.
+    pub fn tag_counts(&self, domains: Option<&[String]>) -> Result<BTreeMap<String, BTreeMap<String, usize>>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT domain, tags FROM vault_meta
+             WHERE IFNULL(tags, '') != '' AND IFNULL(type, '') != 'machine'"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?)))?;
+
+        let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for row in rows {
+            let (domain, tags) = row?;
+            let domain = domain.filter(|d| !d.is_empty()).unwrap_or_else(|| FACET_NONE.to_string());
+            if domains.is_some_and(|allowed| !allowed.contains(&domain)) {
+                continue;
+            }
+            let mut seen = std::collections::BTreeSet::new();
+            for tag in tags.split(", ").map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()) {
+                if seen.insert(tag.clone()) {
+                    *counts.entry(tag).or_default().entry(domain.clone()).or_default() += 1;
+                }
+            }
+        }
+        Ok(counts)
     }
 
     fn fuzzy_suggestions(&self, query: &str) -> Result<Vec<String>, IndexError> {
@@ -176,19 +341,15 @@ pub fn parse_vault_type(s: &str) -> VaultType {
         "thread" => VaultType::Thread,
         "domain" => VaultType::Domain,
         "history" => VaultType::History,
+        "machine" => VaultType::Machine,
         "reference" => VaultType::Reference,
         _ => VaultType::Reference, // fallback
     }
 }
 
+/// Indexed statuses are normalized, so "done" and "completed" land in the same bucket.
 pub fn parse_status(s: &str) -> Option<Status> {
-    match s {
-        "active" => Some(Status::Active),
-        "resolved" => Some(Status::Resolved),
-        "abandoned" => Some(Status::Abandoned),
-        "superseded" => Some(Status::Superseded),
-        _ => None,
-    }
+    Status::parse(s)
 }
 
 pub fn parse_confidence(s: &str) -> Option<Confidence> {
@@ -253,6 +414,36 @@ mod tests {
         assert!(results.total > 0);
     }
 
+    #[test]
+    fn search_facets_count_all_matches_beyond_limit() {
+        let store = build_test_index();
+        let q = SearchQuery {
+            query: "auth".to_string(),
+            limit: 1,
+            ..Default::default()
+        };
+        let results = store.search(&q).unwrap();
+        assert_eq!(results.results.len(), 1);
+        let facets = &results.facets;
+        assert!(facets.total >= 2, "{facets:?}");
+        assert_eq!(facets.domain.get("myapp"), Some(&facets.total));
+        assert_eq!(facets.file_type.values().sum::<usize>(), facets.total);
+        assert_eq!(facets.status.values().sum::<usize>(), facets.total);
+    }
+
+    #[test]
+    fn search_facets_respect_filters() {
+        let store = build_test_index();
+        let q = SearchQuery {
+            query: "auth".to_string(),
+            types: vec![VaultType::Decision],
+            limit: 5,
+            ..Default::default()
+        };
+        let facets = store.search(&q).unwrap().facets;
+        assert_eq!(facets.file_type.keys().collect::<Vec<_>>(), vec!["decision"]);
+    }
+
     #[test]
     fn search_filter_by_domain() {
         let store = build_test_index();
@@ -341,6 +532,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tag_filters_match_whole_tags_with_or_without_a_query() {
+        let store = build_test_index();
+        let q = SearchQuery { query: "auth".to_string(), tags: vec!["SaaS".to_string()], limit: 5, ..Default::default() };
+        let results = store.search(&q).unwrap();
+        assert_eq!(results.total, 1);
+        assert_eq!(results.facets.total, 1);
+        assert!(results.results[0].frontmatter.tags.contains(&"saas".to_string()));
+
+        // No text: every document carrying all the tags
+        let q = SearchQuery { tags: vec!["rust".to_string()], limit: 5, ..Default::default() };
+        assert_eq!(store.search(&q).unwrap().total, 2);
+        let q = SearchQuery { tags: vec!["rust".to_string(), "mcp".to_string()], limit: 5, ..Default::default() };
+        assert_eq!(store.search(&q).unwrap().total, 1);
+        let q = SearchQuery { tags: vec!["ru".to_string()], limit: 5, ..Default::default() };
+        assert_eq!(store.search(&q).unwrap().total, 0);
+    }
+
+    #[test]
+    fn exclusions_drop_terms_and_domains_with_or_without_a_query() {
+        let store = build_test_index();
+        let paths = |q: &SearchQuery| store.search(q).unwrap().results.into_iter().map(|r| r.path).collect::<Vec<_>>();
+        let q = SearchQuery { query: "auth".to_string(), exclude_terms: vec!["JWT".to_string()], limit: 5, ..Default::default() };
+        let found = paths(&q);
+        assert!(!found.is_empty());
+        assert!(!found.contains(&"myapp/auth.md".to_string()), "{found:?}");
+        assert_eq!(store.search(&q).unwrap().facets.total, found.len());
+
+        let q = SearchQuery { tags: vec!["rust".to_string()], exclude_terms: vec!["clippy warnings".to_string()], limit: 5, ..Default::default() };
+        assert_eq!(paths(&q), ["wardwell.md"]);
+        let q = SearchQuery { tags: vec!["rust".to_string()], exclude_domains: vec!["wardwell".to_string()], limit: 5, ..Default::default() };
+        assert_eq!(paths(&q), ["insights/debugging.md"]);
+    }
+
+    #[test]
+    fn tag_counts_group_by_domain() {
+        let store = build_test_index();
+        let counts = store.tag_counts(None).unwrap();
+        assert_eq!(counts["auth"].get("myapp"), Some(&2));
+        assert_eq!(counts["rust"].values().sum::<usize>(), 2);
+        let only = vec!["wardwell".to_string()];
+        let counts = store.tag_counts(Some(&only)).unwrap();
+        assert!(!counts.contains_key("auth"));
+        assert_eq!(counts["mcp"].get("wardwell"), Some(&1));
+    }
+
     #[test]
     fn search_single_domain_in_vec() {
         let store = build_test_index();
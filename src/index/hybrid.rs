@@ -1,4 +1,4 @@
-use crate::index::embed::Embedder;
+use crate::index::embeddings::EmbeddingProvider;
 use crate::index::store::{IndexError, IndexStore};
 use crate::vault::types::Frontmatter;
 use serde::{Deserialize, Serialize};
@@ -24,6 +24,31 @@ pub struct HybridResults {
 
 const RRF_K: f64 = 60.0;
 
+/// How the `search` action ranks results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// FTS5 over whole files.
+    #[default]
+    Keyword,
+    /// Vector similarity over chunks only, for conceptual matches that share no words.
+    Vector,
+    /// FTS5 and vector results over chunks, fused with RRF.
+    Hybrid,
+}
+
+impl SearchMode {
+    /// Parse a `mode` parameter. None for an unknown name.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "keyword" | "fts" => Some(Self::Keyword),
+            "vector" => Some(Self::Vector),
+            // `semantic` predates the split and has always meant keyword + vector
+            "hybrid" | "semantic" => Some(Self::Hybrid),
+            _ => None,
+        }
+    }
+}
+
 /// Reciprocal Rank Fusion: score(doc) = sum(1 / (k + rank_i)) across all result sets.
 fn rrf_fuse(result_sets: &[Vec<String>]) -> Vec<(String, f64)> {
     let mut scores: HashMap<String, f64> = HashMap::new();
@@ -39,32 +64,40 @@ fn rrf_fuse(result_sets: &[Vec<String>]) -> Vec<(String, f64)> {
     sorted
 }
 
-/// Run hybrid search: FTS5 on chunk_search + KNN on chunk_vec, fuse with RRF.
-/// Returns chunk-level results with full text bodies and parent file frontmatter.
+/// Run chunk search: KNN on chunk_vec, plus FTS5 on chunk_search fused with RRF unless
+/// `mode` is `Vector`. Returns chunk-level results with full text bodies and parent file
+/// frontmatter.
 pub fn hybrid_search(
     store: &IndexStore,
-    embedder: &mut Embedder,
+    embedder: &mut dyn EmbeddingProvider,
     query: &str,
     limit: usize,
     domains: Option<&[String]>,
+    mode: SearchMode,
 ) -> Result<HybridResults, IndexError> {
     let fetch_count = limit * 3;
+    let mut result_sets = Vec::new();
 
     // 1. FTS5 search on chunk_search
-    let fts_results = store.chunk_fts_search(query, fetch_count, domains)?;
-    let fts_ids: Vec<String> = fts_results.into_iter().map(|(id, _)| id).collect();
+    if mode != SearchMode::Vector {
+        let fts_results = store.chunk_fts_search(query, fetch_count, domains)?;
+        result_sets.push(fts_results.into_iter().map(|(id, _)| id).collect());
+    }
 
     // 2. Embed query and KNN search on chunk_vec
     let query_vec = embedder.embed_query(query)?;
     let vec_results = store.vector_search(&query_vec, fetch_count, domains)?;
-    let vec_ids: Vec<String> = vec_results.into_iter().map(|(id, _)| id).collect();
+    result_sets.push(vec_results.into_iter().map(|(id, _)| id).collect());
 
-    // 3. RRF fusion
-    let fused = rrf_fuse(&[fts_ids, vec_ids]);
+    // 3. RRF fusion (a single set keeps its order)
+    let fused = rrf_fuse(&result_sets);
 
-    // 4. Fetch chunk bodies + parent frontmatter for top N
+    // 4. Fetch chunk bodies + parent frontmatter for top N, skipping machine files
     let mut chunks = Vec::new();
-    for (chunk_id, score) in fused.into_iter().take(limit) {
+    for (chunk_id, score) in fused {
+        if chunks.len() == limit {
+            break;
+        }
         let (path, chunk_index, heading, body) = match store.get_chunk(&chunk_id) {
             Ok(c) => c,
             Err(_) => continue, // chunk may have been removed
@@ -72,6 +105,9 @@ pub fn hybrid_search(
 
         // Get parent file frontmatter from vault_meta
         let frontmatter = store.get_frontmatter(&path).unwrap_or_default();
+        if frontmatter.file_type == crate::vault::types::VaultType::Machine {
+            continue;
+        }
 
         chunks.push(ChunkResult {
             path,
@@ -88,8 +124,49 @@ pub fn hybrid_search(
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
     use super::*;
+    use crate::index::embeddings::EMBEDDING_DIM;
+
+    /// Words that mean the same thing land on the same axis, so texts sharing no words can
+    /// still sit close together.
+    struct Concepts;
+
+    impl EmbeddingProvider for Concepts {
+        fn embed_batch(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>, IndexError> {
+            Ok(texts.iter().map(|text| {
+                let mut v = vec![0.0; EMBEDDING_DIM];
+                for word in text.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+                    let axis = match word {
+                        "auth" | "login" => 0,
+                        "flow" | "pipeline" => 1,
+                        other => 2 + other.bytes().map(usize::from).sum::<usize>() % (EMBEDDING_DIM - 2),
+                    };
+                    v[axis] += 1.0;
+                }
+                let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+                v.into_iter().map(|x| x / norm).collect()
+            }).collect())
+        }
+    }
+
+    #[test]
+    fn a_pluggable_provider_finds_matches_that_share_no_words() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("auth.md"), "---\ntype: reference\n---\n\n# Notes\n\nThe login pipeline retries twice.\n").unwrap();
+        std::fs::write(dir.join("billing.md"), "---\ntype: reference\n---\n\n# Notes\n\nInvoices go out monthly.\n").unwrap();
+        let store = IndexStore::in_memory().unwrap();
+        let stats = crate::index::builder::IndexBuilder::full_build(&store, tmp.path(), Some(&mut Concepts)).unwrap();
+        assert!(stats.chunks_embedded >= 2, "{stats:?}");
+
+        for mode in [SearchMode::Vector, SearchMode::Hybrid] {
+            let results = hybrid_search(&store, &mut Concepts, "auth flow", 1, None, mode).unwrap();
+            assert_eq!(results.chunks[0].path, "work/api/auth.md", "{mode:?}");
+        }
+    }
 
     #[test]
     fn rrf_fuse_combines_rankings() {
@@ -132,6 +209,15 @@ mod tests {
         assert!((scores[0] - scores[1]).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn search_mode_parses_known_names() {
+        assert_eq!(SearchMode::parse("keyword"), Some(SearchMode::Keyword));
+        assert_eq!(SearchMode::parse(" Vector"), Some(SearchMode::Vector));
+        assert_eq!(SearchMode::parse("hybrid"), Some(SearchMode::Hybrid));
+        assert_eq!(SearchMode::parse("semantic"), Some(SearchMode::Hybrid));
+        assert_eq!(SearchMode::parse("fuzzy"), None);
+    }
+
     #[test]
     fn rrf_fuse_empty_sets() {
         let fused = rrf_fuse(&[Vec::new(), Vec::new()]);
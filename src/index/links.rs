@@ -0,0 +1,192 @@
+use crate::index::store::{IndexError, IndexStore};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::Path;
+
+/// How one note refers to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// An entry in the note's `related:` frontmatter.
+    Related,
+    /// A `[[wikilink]]` in the note's body.
+    Wikilink,
+}
+
+impl LinkKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            LinkKind::Related => "related",
+            LinkKind::Wikilink => "wikilink",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        if s == "related" { LinkKind::Related } else { LinkKind::Wikilink }
+    }
+}
+
+/// The note at the other end of a link.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Link {
+    /// Vault-relative path, or the name as written when it matches no file.
+    pub path: String,
+    pub kind: LinkKind,
+    /// Whether `path` is an indexed file.
+    pub resolved: bool,
+    /// The line the link sits on in the linking note. None for `related:` entries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Replace the links recorded for `source` with the ones `vf` makes now. Targets are stored
+/// resolved to vault paths where a file exists at index time, else as written.
+pub(crate) fn write_links(conn: &Connection, source: &str, vf: &crate::vault::types::VaultFile, vault_root: &Path) -> Result<(), IndexError> {
+    conn.execute("DELETE FROM links WHERE source = ?1", rusqlite::params![source])?;
+    if vf.path.extension().is_none_or(|e| e != "md") {
+        return Ok(());
+    }
+
+    let note = Path::new(source);
+    let target = |raw: &str| {
+        crate::vault::links::resolve(raw, note, vault_root)
+            .unwrap_or_else(|| crate::vault::links::clean_target(raw).to_string())
+    };
+    let related = vf.frontmatter.related.iter()
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty() && !r.starts_with("http://") && !r.starts_with("https://"))
+        .map(|r| (target(r), LinkKind::Related, None));
+    let inline = crate::vault::links::wikilinks(&vf.body).into_iter()
+        .map(|(t, line)| (target(&t), LinkKind::Wikilink, Some(line)));
+
+    for (target, kind, snippet) in related.chain(inline) {
+        if target.is_empty() || target == source {
+            continue;
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO links (source, target, kind, snippet) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![source, target, kind.as_str(), snippet],
+        )?;
+    }
+    Ok(())
+}
+
+/// Lowercased file stem, the key bare `[[name]]` links are matched on.
+fn name_key(path: &str) -> String {
+    Path::new(path).file_stem().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+impl IndexStore {
+    /// Notes `path` links to, by kind then target. Bare names that weren't found next to the
+    /// note resolve to an indexed file with that name, if there is one.
+    pub fn outbound_links(&self, path: &str) -> Result<Vec<Link>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT l.target, l.kind, l.snippet, m.path, m.summary
+             FROM links l LEFT JOIN vault_meta m ON m.path = l.target
+             WHERE l.source = ?1
+             ORDER BY l.kind, l.target"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?, row.get::<_, Option<String>>(4)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+
+        let mut by_name: Option<Vec<(String, Option<String>)>> = None;
+        let mut links = Vec::new();
+        for (target, kind, snippet, indexed, summary) in rows {
+            let mut link = Link { path: target, kind: LinkKind::parse(&kind), resolved: indexed.is_some(), snippet, summary };
+            if !link.resolved && !link.path.contains('/') {
+                let names = match &mut by_name {
+                    Some(names) => names,
+                    None => {
+                        let mut stmt = conn.prepare("SELECT path, summary FROM vault_meta WHERE path LIKE '%.md' ORDER BY length(path), path")?;
+                        let all = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))?
+                            .collect::<Result<Vec<_>, _>>()?;
+                        by_name.insert(all)
+                    }
+                };
+                let key = name_key(&link.path);
+                if let Some((found, summary)) = names.iter().find(|(p, _)| name_key(p) == key) {
+                    link.path = found.clone();
+                    link.summary = summary.clone();
+                    link.resolved = true;
+                }
+            }
+            links.push(link);
+        }
+        Ok(links)
+    }
+
+    /// Notes that link to `path`, by path: by its full path, or by its bare name.
+    pub fn inbound_links(&self, path: &str) -> Result<Vec<Link>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT l.source, l.kind, l.snippet, m.summary
+             FROM links l LEFT JOIN vault_meta m ON m.path = l.source
+             WHERE l.target = ?1 OR (instr(l.target, '/') = 0 AND lower(l.target) IN (?2, ?2 || '.md'))
+             ORDER BY l.source, l.kind"
+        )?;
+        let links = stmt.query_map(rusqlite::params![path, name_key(path)], |row| {
+            Ok(Link {
+                path: row.get(0)?,
+                kind: LinkKind::parse(&row.get::<_, String>(1)?),
+                resolved: true,
+                snippet: row.get(2)?,
+                summary: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(links)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use crate::index::builder::IndexBuilder;
+    use crate::index::store::IndexStore;
+    use super::*;
+
+    #[test]
+    fn links_are_recorded_both_ways_and_follow_edits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let note = |rel: &str, content: &str| {
+            let path = tmp.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        note("work/api/INDEX.md", "---\ntype: project\nsummary: The API\nrelated: [auth.md, https://example.com]\n---\n# API\n\nLogin follows [[plan|the plan]] and [[missing]].\n");
+        note("work/api/auth.md", "---\ntype: reference\nsummary: Auth notes\n---\n# Auth\n");
+        note("personal/plan.md", "---\ntype: reference\n---\n# Plan\n\nBack to [[work/api/INDEX]].\n");
+        let store = IndexStore::in_memory().unwrap();
+        IndexBuilder::full_build(&store, tmp.path(), None).unwrap();
+
+        let out = store.outbound_links("work/api/INDEX.md").unwrap();
+        let ends: Vec<(&str, LinkKind, bool)> = out.iter().map(|l| (l.path.as_str(), l.kind, l.resolved)).collect();
+        assert_eq!(ends, vec![
+            ("work/api/auth.md", LinkKind::Related, true),
+            ("missing", LinkKind::Wikilink, false),
+            ("personal/plan.md", LinkKind::Wikilink, true),
+        ]);
+        assert_eq!(out[0].summary.as_deref(), Some("Auth notes"));
+        assert_eq!(out[2].snippet.as_deref(), Some("Login follows [[plan|the plan]] and [[missing]]."));
+
+        // By full path from personal/plan.md, by bare name from the API index
+        let into_plan = store.inbound_links("personal/plan.md").unwrap();
+        assert_eq!(into_plan.iter().map(|l| l.path.as_str()).collect::<Vec<_>>(), vec!["work/api/INDEX.md"]);
+        let into_index = store.inbound_links("work/api/INDEX.md").unwrap();
+        assert_eq!(into_index.len(), 1);
+        assert_eq!(into_index[0].path, "personal/plan.md");
+
+        // Dropping the link from the body drops it from the index
+        note("personal/plan.md", "---\ntype: reference\n---\n# Plan\n");
+        IndexBuilder::full_build(&store, tmp.path(), None).unwrap();
+        assert!(store.inbound_links("work/api/INDEX.md").unwrap().is_empty());
+        std::fs::remove_file(tmp.path().join("work/api/INDEX.md")).unwrap();
+        IndexBuilder::full_build(&store, tmp.path(), None).unwrap();
+        assert!(store.inbound_links("personal/plan.md").unwrap().is_empty());
+    }
+}
@@ -3,7 +3,11 @@ pub mod builder;
 pub mod fts;
 pub mod chunk;
 pub mod embed;
+pub mod embeddings;
 pub mod hybrid;
+pub mod freshness;
+pub mod links;
+pub mod dedupe;
 
 pub use store::*;
 pub use builder::*;
@@ -22,6 +22,20 @@ pub enum IndexError {
     LockPoisoned,
 }
 
+/// A project known to the index: a `domain/project/` folder with an indexed
+/// current_state.md or INDEX.md.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ProjectEntry {
+    pub domain: String,
+    pub project: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
 /// SQLite FTS5 index store. Thread-safe via Mutex.
 #[derive(Debug)]
 pub struct IndexStore {
@@ -127,6 +141,40 @@ impl IndexStore {
             );"
         )?;
 
+        // Key/value state (e.g. last successful index event)
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS index_state (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );"
+        )?;
+
+        // Links between notes, from related: frontmatter and [[wikilinks]]
+        let links_exist: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='links'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|c| c > 0)
+            .unwrap_or(false);
+
+        if !links_exist {
+            conn.execute_batch(
+                "CREATE TABLE links (
+                    source TEXT NOT NULL,
+                    target TEXT NOT NULL,
+                    kind TEXT NOT NULL,
+                    snippet TEXT,
+                    PRIMARY KEY (source, target, kind)
+                );
+                CREATE INDEX links_target ON links (target);"
+            )?;
+            // Files indexed before links were tracked have none recorded; forget their
+            // hashes so the next build re-reads them
+            conn.execute("UPDATE vault_meta SET body_hash = NULL", [])?;
+        }
+
         // sqlite-vec virtual table for embeddings (optional — server works without it)
         let vec_exists: bool = conn
             .query_row(
@@ -200,7 +248,21 @@ impl IndexStore {
                 path TEXT PRIMARY KEY,
                 line_count INTEGER NOT NULL,
                 indexed_at TEXT NOT NULL
-            );"
+            );
+
+            CREATE TABLE index_state (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
+            CREATE TABLE links (
+                source TEXT NOT NULL,
+                target TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                snippet TEXT,
+                PRIMARY KEY (source, target, kind)
+            );
+            CREATE INDEX links_target ON links (target);"
         )?;
 
         Ok(Self { conn: Mutex::new(conn) })
@@ -218,6 +280,7 @@ impl IndexStore {
         conn.execute("DELETE FROM chunk_search", [])?;
         conn.execute("DELETE FROM vault_chunks", [])?;
         conn.execute("DELETE FROM chunk_vec", [])?;
+        conn.execute("DELETE FROM links", [])?;
         Ok(())
     }
 
@@ -231,7 +294,9 @@ impl IndexStore {
             .to_string_lossy()
             .to_string();
 
-        let new_hash = crate::index::builder::compute_hash(&vf.body);
+        // Frontmatter is hashed too, so a tag or status edit alone still reaches the index
+        let fm_json = serde_json::to_string(&vf.frontmatter).unwrap_or_default();
+        let new_hash = crate::index::builder::compute_hash(&format!("{fm_json}\n{}", vf.body));
         let conn = self.lock()?;
 
         // Check if hash is unchanged
@@ -251,7 +316,11 @@ impl IndexStore {
 
         // Insert fresh
         let fm = &vf.frontmatter;
-        let file_type = fm.file_type.to_string();
+        let file_type = if crate::vault::app_files::is_machine(&abs_path, &vf.path) {
+            crate::vault::types::VaultType::Machine.to_string()
+        } else {
+            fm.file_type.to_string()
+        };
         // Infer domain from first path component if frontmatter doesn't specify one
         let domain = fm.domain.as_deref()
             .filter(|d| !d.is_empty())
@@ -277,6 +346,7 @@ impl IndexStore {
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             rusqlite::params![abs_path, file_type, domain, status, confidence, updated, summary, related, tags, new_hash, indexed_at],
         )?;
+        crate::index::links::write_links(&conn, &abs_path, vf, vault_root)?;
 
         Ok(true)
     }
@@ -548,6 +618,101 @@ impl IndexStore {
         ).map_err(IndexError::from)
     }
 
+    /// Get the indexed body for a file path. Returns None if the path is not indexed.
+    pub fn get_body(&self, path: &str) -> Result<Option<String>, IndexError> {
+        let conn = self.lock()?;
+        let body: Option<String> = conn.query_row(
+            "SELECT body FROM vault_search WHERE path = ?1",
+            rusqlite::params![path],
+            |row| row.get(0),
+        ).ok();
+        Ok(body)
+    }
+
+    /// When a file was last written to the index. None if the path is not indexed.
+    pub fn indexed_at(&self, path: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>, IndexError> {
+        let conn = self.lock()?;
+        let value: Option<String> = conn.query_row(
+            "SELECT indexed_at FROM vault_meta WHERE path = ?1",
+            rusqlite::params![path],
+            |row| row.get(0),
+        ).ok();
+        Ok(value
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+            .map(|d| d.with_timezone(&chrono::Utc)))
+    }
+
+    /// Projects in the index, sorted by domain then project. Status and updated come from
+    /// current_state.md, falling back to INDEX.md; summary prefers INDEX.md.
+    pub fn list_projects(&self, domain: Option<&str>) -> Result<Vec<ProjectEntry>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT path, status, updated, summary FROM vault_meta
+             WHERE path LIKE '%/current_state.md' OR path LIKE '%/INDEX.md'"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+
+        let non_empty = |v: Option<String>| v.filter(|s| !s.is_empty());
+        let mut projects: std::collections::BTreeMap<(String, String), ProjectEntry> = std::collections::BTreeMap::new();
+        for row in rows {
+            let (path, status, updated, summary) = row?;
+            let parts: Vec<&str> = path.split('/').collect();
+            let [d, project, file] = parts.as_slice() else {
+                continue;
+            };
+            if domain.is_some_and(|want| want != *d) {
+                continue;
+            }
+            let entry = projects.entry((d.to_string(), project.to_string())).or_insert_with(|| ProjectEntry {
+                domain: d.to_string(),
+                project: project.to_string(),
+                status: None,
+                updated: None,
+                summary: None,
+            });
+            let is_state = *file == "current_state.md";
+            if is_state || entry.status.is_none() {
+                entry.status = non_empty(status).or(entry.status.take());
+            }
+            if is_state || entry.updated.is_none() {
+                entry.updated = non_empty(updated).or(entry.updated.take());
+            }
+            if !is_state || entry.summary.is_none() {
+                entry.summary = non_empty(summary).or(entry.summary.take());
+            }
+        }
+        Ok(projects.into_values().collect())
+    }
+
+    /// Indexed paths under a vault-relative directory (all paths for ""), sorted.
+    /// Lets callers find files without listing directories on a slow mount.
+    pub fn paths_under(&self, dir: &str) -> Result<Vec<String>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT path FROM vault_meta WHERE ?1 = '' OR substr(path, 1, length(?1) + 1) = ?1 || '/' ORDER BY path"
+        )?;
+        let dir = dir.trim_end_matches('/');
+        let paths = stmt.query_map(rusqlite::params![dir], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(paths)
+    }
+
+    /// Indexed files per domain, by domain name. Files outside every domain count under "".
+    pub fn counts_by_domain(&self) -> Result<std::collections::BTreeMap<String, i64>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT COALESCE(domain, ''), COUNT(*) FROM vault_meta GROUP BY 1")?;
+        let counts = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(counts)
+    }
+
     /// Remove a file from the index by its path.
     pub fn remove(&self, path: &str) -> Result<(), IndexError> {
         // Remove chunks first (drops MutexGuard between calls)
@@ -555,6 +720,8 @@ impl IndexStore {
         let conn = self.lock()?;
         conn.execute("DELETE FROM vault_search WHERE path = ?1", rusqlite::params![path])?;
         conn.execute("DELETE FROM vault_meta WHERE path = ?1", rusqlite::params![path])?;
+        conn.execute("DELETE FROM links WHERE source = ?1", rusqlite::params![path])?;
+        conn.execute("DELETE FROM jsonl_watermark WHERE path = ?1", rusqlite::params![path])?;
         Ok(())
     }
 
@@ -588,12 +755,39 @@ impl IndexStore {
             conn.execute("DELETE FROM vault_chunks WHERE path = ?1", rusqlite::params![path])?;
             conn.execute("DELETE FROM vault_search WHERE path = ?1", rusqlite::params![path])?;
             conn.execute("DELETE FROM vault_meta WHERE path = ?1", rusqlite::params![path])?;
+            conn.execute("DELETE FROM links WHERE source = ?1", rusqlite::params![path])?;
             // Clean up watermark for JSONL files
             conn.execute("DELETE FROM jsonl_watermark WHERE path = ?1", rusqlite::params![path])?;
         }
         Ok(stale.len())
     }
 
+    /// Indexed paths whose file is gone, resolved against `vault_root` (paths outside the
+    /// vault are stored absolute). A compressed twin still counts as the file.
+    pub fn orphaned_paths(&self, vault_root: &Path) -> Result<Vec<String>, IndexError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT path FROM vault_meta ORDER BY path")?;
+        let paths: Vec<String> = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .filter(|path| crate::vault::compress::existing(&vault_root.join(path)).is_none())
+            .collect();
+        Ok(paths)
+    }
+
+    /// Merge the FTS indexes' segments, rebuild the database file without its free pages,
+    /// and fold the WAL back in so the file shrinks on disk.
+    pub fn optimize(&self) -> Result<(), IndexError> {
+        let conn = self.lock()?;
+        conn.execute_batch(
+            "INSERT INTO vault_search(vault_search) VALUES('optimize');
+             INSERT INTO chunk_search(chunk_search) VALUES('optimize');
+             VACUUM;"
+        )?;
+        // The checkpoint reports its progress as a row
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
     /// Get the watermark (last indexed line count) for a JSONL file.
     /// Returns 0 if no watermark exists.
     pub fn get_watermark(&self, path: &str) -> Result<usize, IndexError> {
@@ -618,6 +812,29 @@ impl IndexStore {
         Ok(())
     }
 
+    /// Record that an index event (build, watcher update, write) just succeeded.
+    pub fn mark_indexed(&self) -> Result<(), IndexError> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO index_state (key, value) VALUES ('last_indexed_at', ?1)",
+            rusqlite::params![chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// When the last successful index event happened. None if never recorded.
+    pub fn last_indexed_at(&self) -> Result<Option<chrono::DateTime<chrono::Utc>>, IndexError> {
+        let conn = self.lock()?;
+        let value: Option<String> = conn.query_row(
+            "SELECT value FROM index_state WHERE key = 'last_indexed_at'",
+            [],
+            |row| row.get(0),
+        ).ok();
+        Ok(value
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
+            .map(|d| d.with_timezone(&chrono::Utc)))
+    }
+
     /// Remove watermark for a JSONL file (used when the file is deleted).
     pub fn remove_watermark(&self, path: &str) -> Result<(), IndexError> {
         let conn = self.lock()?;
@@ -709,4 +926,44 @@ mod tests {
         ).unwrap_or(0);
         assert_eq!(count, 0);
     }
+
+    #[test]
+    fn list_projects_merges_state_and_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("work/api/current_state.md", "---\ntype: project\nstatus: active\nupdated: 2026-03-01\n---\nstate\n"),
+            ("work/api/INDEX.md", "---\ntype: project\nstatus: paused\nsummary: Public API\nupdated: 2026-01-01\n---\nindex\n"),
+            ("work/billing/INDEX.md", "---\ntype: project\nstatus: blocked\n---\nindex\n"),
+            ("personal/blog/current_state.md", "---\ntype: project\nstatus: completed\n---\nstate\n"),
+            ("work/api/notes/current_state.md", "---\ntype: project\n---\nnested\n"),
+        ];
+        let store = IndexStore::in_memory().unwrap();
+        for (rel, content) in files {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+            let vf = crate::vault::reader::read_file(&path).unwrap();
+            store.upsert(&vf, dir.path()).unwrap();
+        }
+
+        let all = store.list_projects(None).unwrap();
+        let names: Vec<(&str, &str)> = all.iter().map(|p| (p.domain.as_str(), p.project.as_str())).collect();
+        assert_eq!(names, vec![("personal", "blog"), ("work", "api"), ("work", "billing")]);
+
+        let api = &all[1];
+        assert_eq!(api.status.as_deref(), Some("active"));
+        assert_eq!(api.updated.as_deref(), Some("2026-03-01"));
+        assert_eq!(api.summary.as_deref(), Some("Public API"));
+        assert_eq!(all[2].status.as_deref(), Some("blocked"));
+
+        let work = store.list_projects(Some("work")).unwrap();
+        assert_eq!(work.len(), 2);
+        assert!(store.list_projects(Some("nope")).unwrap().is_empty());
+
+        assert_eq!(store.paths_under("work/api").unwrap(), vec![
+            "work/api/INDEX.md", "work/api/current_state.md", "work/api/notes/current_state.md",
+        ]);
+        assert!(store.paths_under("work/ap").unwrap().is_empty());
+        assert_eq!(store.paths_under("").unwrap().len(), 5);
+    }
 }
@@ -44,6 +44,14 @@ pub fn inject(path: &Path, content: &str) -> Result<(), InjectError> {
     Ok(())
 }
 
+/// The content between the wardwell markers, if the file has both.
+pub fn injected(path: &Path) -> Option<String> {
+    let existing = std::fs::read_to_string(path).ok()?;
+    let start = existing.find(START_MARKER)? + START_MARKER.len();
+    let end = existing[start..].find(END_MARKER)? + start;
+    Some(existing[start..end].trim_matches('\n').to_string())
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
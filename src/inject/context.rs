@@ -0,0 +1,203 @@
+use crate::vault::types::Status;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Inject budgets are counted at four bytes to a token, the summarizer's estimate.
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// One project in the domain listing, with its full and one-line forms.
+struct ProjectEntry {
+    rank: u8,
+    updated: Option<chrono::NaiveDate>,
+    name: String,
+    full: String,
+    short: String,
+}
+
+/// What `wardwell inject` prints for a domain: its `current_state.md` (a generated rollup
+/// without its frontmatter), or else its projects, blocked and active first and the most
+/// recently updated first within each.
+///
+/// With `max_tokens` (0 = no limit), output that would run past it is cut down: projects
+/// past the budget get one line each, then are only counted. A domain current_state.md that
+/// doesn't fit gives way to that listing, or is cut at a line when the domain has no projects.
+/// `statuses` are the vault's extra states from config.yml.
+pub fn domain_context(domain_dir: &Path, max_tokens: usize, statuses: &BTreeMap<String, Status>) -> String {
+    let budget = if max_tokens == 0 { usize::MAX } else { max_tokens.saturating_mul(BYTES_PER_TOKEN) };
+    let state = domain_dir.join("current_state.md");
+    if state.exists()
+        && let Ok(content) = std::fs::read_to_string(&state)
+    {
+        let content = if crate::daemon::rollup::is_generated(&content)
+            && let Ok((_, body)) = crate::vault::frontmatter::parse_frontmatter(&content)
+        {
+            body.trim_start().to_string()
+        } else {
+            content
+        };
+        if content.len() <= budget {
+            return content;
+        }
+        let projects = ranked_projects(domain_dir, statuses);
+        if projects.is_empty() {
+            let domain = domain_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
+            return cut(&content, budget, max_tokens, domain);
+        }
+        return listing(&projects, budget, max_tokens);
+    }
+    listing(&ranked_projects(domain_dir, statuses), budget, max_tokens)
+}
+
+/// A project's entry in the domain listing inject falls back to: status and focus, then the
+/// next action when there is one. None when `project_dir` has no readable current_state.md.
+fn project_entry(domain_dir: &Path, project_dir: &Path, statuses: &BTreeMap<String, Status>) -> Option<ProjectEntry> {
+    if !project_dir.is_dir() {
+        return None;
+    }
+    let state = project_dir.join("current_state.md");
+    if !state.exists() {
+        return None;
+    }
+    let vf = crate::vault::reader::read_file_with(&state, statuses).ok()?;
+    let domain = domain_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
+    let project = project_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
+    let status = vf.frontmatter.status.unwrap_or(Status::Active);
+    let focus = extract_section_simple(&vf.body, "Focus");
+    let next = extract_section_simple(&vf.body, "Next Action");
+    let mut full = format!("**{domain}/{project}** ({status}): {focus}\n");
+    if !next.is_empty() {
+        full.push_str(&format!("  Next: {next}\n"));
+    }
+    Some(ProjectEntry {
+        rank: crate::daemon::rollup::status_rank(status),
+        updated: vf.frontmatter.updated,
+        name: project.to_string(),
+        short: format!("**{domain}/{project}** ({status})\n"),
+        full,
+    })
+}
+
+fn ranked_projects(domain_dir: &Path, statuses: &BTreeMap<String, Status>) -> Vec<ProjectEntry> {
+    let mut projects: Vec<ProjectEntry> = std::fs::read_dir(domain_dir).into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| project_entry(domain_dir, &entry.path(), statuses))
+        .collect();
+    projects.sort_by(|a, b| a.rank.cmp(&b.rank).then(b.updated.cmp(&a.updated)).then(a.name.cmp(&b.name)));
+    projects
+}
+
+/// Full entries while they fit in `budget` bytes, then one-line entries, then a count of the rest.
+fn listing(projects: &[ProjectEntry], budget: usize, max_tokens: usize) -> String {
+    let mut out = String::new();
+    let (mut shortened, mut left_out) = (0, 0);
+    for p in projects {
+        if shortened == 0 && left_out == 0 && out.len() + p.full.len() <= budget {
+            out.push_str(&p.full);
+        } else if left_out == 0 && out.len() + p.short.len() <= budget {
+            out.push_str(&p.short);
+            shortened += 1;
+        } else {
+            left_out += 1;
+        }
+    }
+    if shortened + left_out > 0 {
+        let left_out = if left_out > 0 { format!(", {left_out} left out") } else { String::new() };
+        out.push_str(&format!(
+            "({shortened} project(s) shortened{left_out} to fit the {max_tokens}-token inject budget; `wardwell_search action: state` has the rest)\n"
+        ));
+    }
+    out
+}
+
+/// `content` cut at the last line that fits in `budget` bytes, with a note saying so.
+fn cut(content: &str, budget: usize, max_tokens: usize, domain: &str) -> String {
+    let mut end = budget.min(content.len());
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = content[..end].rfind('\n').map_or(0, |i| i + 1);
+    format!("{}(cut to fit the {max_tokens}-token inject budget; read {domain}/current_state.md for the rest)\n", &content[..end])
+}
+
+/// A line pointing at the domain's digest when the newest one in `dir` is from today or
+/// yesterday.
+pub fn digest_line(domain_dir: &Path, dir: &str, today: chrono::NaiveDate) -> Option<String> {
+    let (date, _) = crate::daemon::digest::latest(domain_dir, dir).filter(|(date, _)| (today - *date).num_days() <= 1)?;
+    let domain = domain_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
+    Some(format!("Fresh digest: {domain}/{dir}/{date}.md (`wardwell_search action: digest domain: {domain}`)\n"))
+}
+
+/// Simple section extractor for inject (no dependency on server module).
+pub(crate) fn extract_section_simple(body: &str, heading: &str) -> String {
+    let marker = format!("## {heading}");
+    let start = match body.find(&marker) {
+        Some(pos) => pos + marker.len(),
+        None => return String::new(),
+    };
+    let rest = body[start..].trim_start();
+    let end = rest.find("\n## ").unwrap_or(rest.len());
+    rest[..end].trim().to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_keeps_the_most_pressing_projects_whole() {
+        let tmp = tempfile::tempdir().unwrap();
+        let domain = tmp.path().join("work");
+        for (project, status, updated) in [("api", "active", "2026-03-01"), ("web", "active", "2026-03-05"), ("old", "completed", "2026-03-09"), ("ops", "blocked", "2026-01-01")] {
+            std::fs::create_dir_all(domain.join(project)).unwrap();
+            std::fs::write(
+                domain.join(project).join("current_state.md"),
+                format!("---\ntype: project\nstatus: {status}\nupdated: {updated}\n---\n## Focus\n{}\n\n## Next Action\nShip {project}\n", "detail ".repeat(20)),
+            ).unwrap();
+        }
+
+        let all = domain_context(&domain, 0, &BTreeMap::new());
+        let order: Vec<_> = all.lines().filter(|l| l.starts_with("**")).filter_map(|l| l.split(" (").next()).collect();
+        assert_eq!(order, ["**work/ops**", "**work/web**", "**work/api**", "**work/old**"]);
+
+        // A configured state ranks as the status it stands for
+        std::fs::write(domain.join("old/current_state.md"), "---\ntype: project\nstatus: on_call\nupdated: 2026-03-09\n---\n## Focus\nPager\n").unwrap();
+        let statuses = BTreeMap::from([("on_call".to_string(), Status::Blocked)]);
+        let ranked = domain_context(&domain, 0, &statuses);
+        assert!(ranked.starts_with("**work/old** (blocked): Pager\n"), "{ranked}");
+        std::fs::write(domain.join("old/current_state.md"), format!("---\ntype: project\nstatus: completed\nupdated: 2026-03-09\n---\n## Focus\n{}\n\n## Next Action\nShip old\n", "detail ".repeat(20))).unwrap();
+
+        // Two whole entries fit in 100 tokens; the third gets one line, the last is counted
+        let tight = domain_context(&domain, 100, &BTreeMap::new());
+        assert!(tight.contains("**work/web** (active): detail"), "{tight}");
+        assert!(tight.contains("**work/api** (active)\n"), "{tight}");
+        assert!(!tight.contains("work/old"), "{tight}");
+        assert!(tight.contains("1 project(s) shortened, 1 left out to fit the 100-token inject budget"), "{tight}");
+
+        // A hand-written domain file over budget gives way to the listing
+        std::fs::write(domain.join("current_state.md"), "# Work\n".repeat(200)).unwrap();
+        assert!(domain_context(&domain, 0, &BTreeMap::new()).starts_with("# Work\n# Work\n"));
+        assert!(domain_context(&domain, 100, &BTreeMap::new()).starts_with("**work/ops** (blocked): detail"));
+
+        let lone = tmp.path().join("notes");
+        std::fs::create_dir_all(&lone).unwrap();
+        std::fs::write(lone.join("current_state.md"), "line one\nline two\nline three\n").unwrap();
+        assert_eq!(domain_context(&lone, 4, &BTreeMap::new()), "line one\n(cut to fit the 4-token inject budget; read notes/current_state.md for the rest)\n");
+    }
+
+    #[test]
+    fn digest_line_only_for_a_recent_digest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let domain = tmp.path().join("work");
+        let day = |d: &str| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+        crate::daemon::digest::write(&domain, "_weekly", day("2026-03-02"), "digest").unwrap();
+
+        assert_eq!(
+            digest_line(&domain, "_weekly", day("2026-03-03")).as_deref(),
+            Some("Fresh digest: work/_weekly/2026-03-02.md (`wardwell_search action: digest domain: work`)\n")
+        );
+        assert!(digest_line(&domain, "_weekly", day("2026-03-04")).is_none());
+        assert!(digest_line(&domain, "_digests", day("2026-03-02")).is_none());
+    }
+}
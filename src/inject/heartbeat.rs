@@ -0,0 +1,154 @@
+use crate::config::loader::{HeartbeatCadence, HeartbeatConfig};
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// File under the config dir mapping each domain to the day its last heartbeat was shown.
+const STATE_FILE: &str = "heartbeat.json";
+
+/// An active project that has gone a while without a sync.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaleProject {
+    pub project: String,
+    /// `updated:` from current_state.md, or the file's modification day without one.
+    pub updated: NaiveDate,
+    pub focus: String,
+}
+
+/// Whether a heartbeat is due today, given the day one was last shown. Weekly heartbeats fall
+/// on the first session on or after the configured weekday; daily ones on each day's first.
+pub fn is_due(config: &HeartbeatConfig, last: Option<NaiveDate>, today: NaiveDate) -> bool {
+    let Some(last) = last else {
+        return config.cadence == HeartbeatCadence::Daily || today.weekday() == config.weekday;
+    };
+    match config.cadence {
+        HeartbeatCadence::Daily => last < today,
+        HeartbeatCadence::Weekly => {
+            let back = (today.weekday().num_days_from_monday() + 7 - config.weekday.num_days_from_monday()) % 7;
+            let latest = today - chrono::Duration::days(i64::from(back));
+            last < latest
+        }
+    }
+}
+
+/// The `limit` active projects in `domain_dir` updated longest ago, stalest first.
+/// Projects without a status count as active, as they do in inject.
+pub fn stalest(domain_dir: &Path, limit: usize) -> Vec<StaleProject> {
+    let Ok(entries) = std::fs::read_dir(domain_dir) else {
+        return Vec::new();
+    };
+    let mut projects: Vec<StaleProject> = entries.flatten()
+        .filter(|e| e.path().is_dir())
+        .filter(|e| !e.file_name().to_string_lossy().starts_with(['.', '_']))
+        .filter_map(|e| {
+            let state = e.path().join("current_state.md");
+            let vf = crate::vault::reader::read_file(&state).ok()?;
+            if vf.frontmatter.status.is_some_and(|s| s != crate::vault::types::Status::Active) {
+                return None;
+            }
+            let updated = vf.frontmatter.updated.or_else(|| {
+                let modified = std::fs::metadata(&state).and_then(|m| m.modified()).ok()?;
+                Some(chrono::DateTime::<chrono::Local>::from(modified).date_naive())
+            })?;
+            Some(StaleProject {
+                project: e.file_name().to_string_lossy().to_string(),
+                updated,
+                focus: crate::mcp::server::extract_section(&vf.body, "Focus"),
+            })
+        })
+        .collect();
+    projects.sort_by(|a, b| a.updated.cmp(&b.updated).then_with(|| a.project.cmp(&b.project)));
+    projects.truncate(limit);
+    projects
+}
+
+/// The heartbeat block appended to inject output.
+pub fn render(domain: &str, projects: &[StaleProject], today: NaiveDate) -> String {
+    let mut out = String::from(
+        "\n## Heartbeat\n\n\
+         These active projects have gone longest without a sync. Early in this session, ask the \
+         user whether each is still accurate, then sync it with wardwell_write (action: sync) \
+         or mark it paused.\n\n",
+    );
+    for p in projects {
+        let days = (today - p.updated).num_days();
+        out.push_str(&format!("- **{domain}/{}** — last updated {} ({days} days ago)", p.project, p.updated));
+        if !p.focus.is_empty() {
+            let focus = p.focus.lines().next().unwrap_or("");
+            out.push_str(&format!(": {focus}"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The heartbeat for `domain_dir` if one is due, recording it as shown. Nothing is recorded
+/// when the domain has no active projects, so a later session can still get one.
+pub fn take(config: &HeartbeatConfig, config_dir: &Path, domain_dir: &Path, today: NaiveDate) -> Option<String> {
+    let domain = domain_dir.file_name()?.to_string_lossy().to_string();
+    let path = config_dir.join(STATE_FILE);
+    let mut shown: BTreeMap<String, NaiveDate> = std::fs::read_to_string(&path).ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    if !is_due(config, shown.get(&domain).copied(), today) {
+        return None;
+    }
+    let projects = stalest(domain_dir, config.projects);
+    if projects.is_empty() {
+        return None;
+    }
+    shown.insert(domain.clone(), today);
+    if let Ok(json) = serde_json::to_string_pretty(&shown) {
+        let _ = std::fs::write(&path, json);
+    }
+    Some(render(&domain, &projects, today))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+    use tempfile::tempdir;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn weekly_heartbeat_is_due_once_per_week() {
+        let weekly = HeartbeatConfig { cadence: HeartbeatCadence::Weekly, weekday: Weekday::Mon, projects: 3 };
+        // 2026-03-02 is a Monday
+        assert!(is_due(&weekly, None, day("2026-03-02")));
+        assert!(!is_due(&weekly, None, day("2026-03-03")));
+        assert!(!is_due(&weekly, Some(day("2026-03-02")), day("2026-03-02")));
+        assert!(!is_due(&weekly, Some(day("2026-03-02")), day("2026-03-08")));
+        assert!(is_due(&weekly, Some(day("2026-03-02")), day("2026-03-09")));
+        // Missed Monday: the first session after it still gets one
+        assert!(is_due(&weekly, Some(day("2026-03-02")), day("2026-03-11")));
+
+        let daily = HeartbeatConfig { cadence: HeartbeatCadence::Daily, ..weekly };
+        assert!(is_due(&daily, None, day("2026-03-03")));
+        assert!(!is_due(&daily, Some(day("2026-03-03")), day("2026-03-03")));
+        assert!(is_due(&daily, Some(day("2026-03-03")), day("2026-03-04")));
+    }
+
+    #[test]
+    fn take_lists_stalest_active_projects_once() {
+        let tmp = tempdir().unwrap();
+        let domain_dir = tmp.path().join("work");
+        for (project, status, updated) in [("api", "active", "2026-02-01"), ("site", "active", "2026-01-10"), ("docs", "paused", "2025-12-01"), ("cli", "active", "2026-02-20")] {
+            std::fs::create_dir_all(domain_dir.join(project)).unwrap();
+            std::fs::write(
+                domain_dir.join(project).join("current_state.md"),
+                format!("---\ntype: project\nstatus: {status}\nupdated: {updated}\n---\n\n## Focus\n{project} work\n"),
+            ).unwrap();
+        }
+        let config = HeartbeatConfig { cadence: HeartbeatCadence::Weekly, weekday: Weekday::Mon, projects: 2 };
+        let block = take(&config, tmp.path(), &domain_dir, day("2026-03-02")).unwrap();
+        assert!(block.contains("- **work/site** — last updated 2026-01-10 (51 days ago): site work\n- **work/api**"), "{block}");
+        assert!(!block.contains("docs") && !block.contains("work/cli"));
+        assert!(take(&config, tmp.path(), &domain_dir, day("2026-03-02")).is_none());
+        assert!(take(&config, tmp.path(), &domain_dir, day("2026-03-09")).is_some());
+    }
+}
@@ -0,0 +1,89 @@
+use serde::Deserialize;
+use std::io::{IsTerminal, Read};
+use std::time::Duration;
+
+/// How long inject waits for the hook payload before carrying on without one.
+const STDIN_WAIT: Duration = Duration::from_millis(500);
+
+/// The JSON Claude Code writes to a SessionStart hook's stdin. Every field is optional:
+/// older clients send less, and `wardwell inject` run by hand sends nothing.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HookPayload {
+    pub session_id: Option<String>,
+    /// `startup`, `resume`, `clear` or `compact`.
+    pub source: Option<String>,
+    pub model: Option<ModelField>,
+    pub cwd: Option<String>,
+    /// Set when the session is a subagent's rather than the user's.
+    pub agent_id: Option<String>,
+    pub agent_type: Option<String>,
+}
+
+/// `model` arrives as a bare name or as `{"id": ..., "display_name": ...}`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ModelField {
+    Name(String),
+    Info { id: String },
+}
+
+impl HookPayload {
+    /// The payload in `raw`, or None when it is empty or isn't one.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        serde_json::from_str(raw).ok()
+    }
+
+    /// The payload on stdin, if one arrives shortly. A terminal is never read, and a pipe
+    /// that stays open is given up on rather than holding the session's start.
+    pub fn from_stdin() -> Option<Self> {
+        if std::io::stdin().is_terminal() {
+            return None;
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut raw = String::new();
+            let _ = std::io::stdin().read_to_string(&mut raw);
+            let _ = tx.send(raw);
+        });
+        Self::parse(&rx.recv_timeout(STDIN_WAIT).ok()?)
+    }
+
+    pub fn model(&self) -> Option<&str> {
+        match self.model.as_ref()? {
+            ModelField::Name(name) => Some(name),
+            ModelField::Info { id } => Some(id),
+        }
+    }
+
+    /// Subagents start with their own brief from the parent; vault context would only
+    /// crowd it.
+    pub fn is_subagent(&self) -> bool {
+        self.agent_id.is_some() || self.agent_type.is_some()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_partial_payloads_and_model_shapes() {
+        let payload = HookPayload::parse(r#"{"session_id":"abc","source":"resume","model":{"id":"claude-sonnet","display_name":"Sonnet"},"cwd":"/w/api","hook_event_name":"SessionStart"}"#).unwrap();
+        assert_eq!(payload.session_id.as_deref(), Some("abc"));
+        assert_eq!(payload.source.as_deref(), Some("resume"));
+        assert_eq!(payload.model(), Some("claude-sonnet"));
+        assert!(!payload.is_subagent());
+
+        let sub = HookPayload::parse(r#"{"session_id":"def","model":"haiku","agent_type":"Explore"}"#).unwrap();
+        assert_eq!(sub.model(), Some("haiku"));
+        assert!(sub.is_subagent());
+
+        assert_eq!(HookPayload::parse("  \n"), None);
+        assert_eq!(HookPayload::parse("/not/json"), None);
+    }
+}
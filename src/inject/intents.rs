@@ -0,0 +1,83 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+
+/// A desktop history entry no code session has acted on yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intent {
+    pub id: Option<String>,
+    pub date: String,
+    pub title: String,
+    pub next_action: String,
+}
+
+#[derive(Deserialize)]
+struct Line {
+    id: Option<String>,
+    #[serde(default)]
+    date: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    next_action: String,
+    #[serde(default)]
+    source: String,
+}
+
+/// The desktop intents queued in a project's history.jsonl: desktop entries after the last
+/// `code` or `dismissed` entry, oldest first. Entries older than `ttl_days` have lapsed and
+/// are left out; 0 keeps every one.
+pub fn pending(history: &str, ttl_days: u32, now: DateTime<Utc>) -> Vec<Intent> {
+    let mut queue = Vec::new();
+    for line in history.lines() {
+        let Ok(entry) = serde_json::from_str::<Line>(line) else {
+            continue;
+        };
+        match entry.source.as_str() {
+            "code" | "dismissed" => queue.clear(),
+            "desktop" => queue.push(Intent { id: entry.id, date: entry.date, title: entry.title, next_action: entry.next_action }),
+            _ => {}
+        }
+    }
+    if ttl_days > 0 {
+        let cutoff = now - Duration::days(i64::from(ttl_days));
+        queue.retain(|intent| entry_time(&intent.date).is_none_or(|t| t >= cutoff));
+    }
+    queue.sort_by(|a, b| a.date.cmp(&b.date));
+    queue
+}
+
+/// An entry's `date`: RFC 3339, or a bare day taken as its midnight UTC.
+fn entry_time(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date).map(|t| t.with_timezone(&Utc)).ok()
+        .or_else(|| NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0).map(|t| t.and_utc()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_resets_on_code_or_dismissed_and_drops_lapsed_intents() {
+        let line = |date: &str, title: &str, source: &str| format!("{{\"id\":\"{title}\",\"date\":\"{date}\",\"title\":\"{title}\",\"next_action\":\"do {title}\",\"source\":\"{source}\"}}");
+        let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z").unwrap().with_timezone(&Utc);
+        let history = [
+            "{\"_schema\": \"history\", \"_version\": \"1.0\"}".to_string(),
+            line("2026-01-02T10:00:00Z", "ancient", "desktop"),
+            line("2026-03-01T10:00:00Z", "old", "desktop"),
+            line("2026-03-30T10:00:00Z", "new", "desktop"),
+            line("2026-03-29", "day-only", "desktop"),
+        ].join("\n");
+
+        let titles = |q: Vec<Intent>| q.into_iter().map(|i| i.title).collect::<Vec<_>>();
+        assert_eq!(titles(pending(&history, 0, now)), ["ancient", "old", "day-only", "new"]);
+        assert_eq!(titles(pending(&history, 14, now)), ["day-only", "new"]);
+
+        let dismissed = format!("{history}\n{}", line("2026-03-30T11:00:00Z", "ack", "dismissed"));
+        assert!(pending(&dismissed, 0, now).is_empty());
+        let after_code = format!("{dismissed}\n{}\n{}", line("2026-03-30T12:00:00Z", "shipped", "code"), line("2026-03-31T09:00:00Z", "next", "desktop"));
+        let queue = pending(&after_code, 14, now);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].next_action, "do next");
+    }
+}
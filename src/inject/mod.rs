@@ -1,3 +1,7 @@
 pub mod claude_md;
+pub mod context;
+pub mod heartbeat;
+pub mod hook;
+pub mod intents;
 
 pub use claude_md::*;
@@ -0,0 +1,305 @@
+use crate::index::builder::IndexBuilder;
+use crate::index::store::IndexStore;
+use crate::install::output::Output;
+use crate::mcp::server::{SearchParams, WardwellServer};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Files in the synthetic vault unless `--files` says otherwise.
+pub const DEFAULT_FILES: usize = 10_000;
+
+/// File under the config dir where `wardwell bench --save` keeps the snapshot later runs
+/// are compared against.
+pub const SNAPSHOT_FILE: &str = "bench.json";
+
+/// Every project holds current_state.md, INDEX.md, decisions.md, history.jsonl, and notes
+/// up to this many files.
+const FILES_PER_PROJECT: usize = 20;
+const PROJECTS_PER_DOMAIN: usize = 50;
+const HISTORY_ENTRIES: usize = 40;
+
+/// Timed repetitions per measurement; the median (and for search the p95) is reported.
+const SEARCH_RUNS: usize = 50;
+const SCAN_RUNS: usize = 5;
+
+const WORDS: &[&str] = &[
+    "retry", "backoff", "queue", "worker", "deploy", "schema", "migration", "cache", "latency",
+    "auth", "token", "session", "webhook", "invoice", "payments", "ledger", "export", "import",
+    "index", "search", "ranking", "snippet", "budget", "timeout", "circuit", "breaker", "replica",
+    "shard", "vacuum", "billing", "dashboard", "alert", "incident", "rollback", "feature", "flag",
+];
+
+/// What each measurement may take, in milliseconds, at `DEFAULT_FILES` or fewer. Larger
+/// vaults scale the index build and scan budgets with their size.
+const BUDGETS: &[(&str, f64)] = &[
+    ("index_build", 120_000.0),
+    ("search_median", 50.0),
+    ("search_p95", 200.0),
+    ("orchestrate", 5_000.0),
+    ("history_scan", 5_000.0),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub name: String,
+    pub millis: f64,
+}
+
+/// One run of the suite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub files: usize,
+    pub measured_at: String,
+    pub measurements: Vec<Measurement>,
+}
+
+/// A measurement over its budget, or slower than the snapshot by more than the tolerance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Regression {
+    pub name: String,
+    pub millis: f64,
+    pub limit_millis: f64,
+    /// `budget` or `snapshot`.
+    pub against: &'static str,
+}
+
+/// Write a synthetic vault of `files` files under `root`: domains of projects, each with the
+/// files a real project has and notes of generated text. Deterministic, so runs compare.
+pub fn generate_vault(root: &Path, files: usize) -> std::io::Result<usize> {
+    let mut rng = Rng(0x5eed_0f3a_11ce);
+    let mut written = 0;
+    let day = chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap_or_default();
+    let mut project_index = 0;
+    while written < files {
+        let domain = format!("domain-{:02}", project_index / PROJECTS_PER_DOMAIN);
+        let project = format!("project-{project_index:03}");
+        let dir = root.join(&domain).join(&project);
+        std::fs::create_dir_all(&dir)?;
+        let updated = day + chrono::Duration::days((project_index % 90) as i64);
+        let status = ["active", "active", "blocked", "paused", "completed"][project_index % 5];
+
+        let mut project_files: Vec<(String, String)> = vec![
+            ("current_state.md".to_string(), format!(
+                "---\ntype: project\nstatus: {status}\nupdated: {updated}\nsummary: {}\n---\n# {project}\n\n## Focus\n{}\n\n## Next Action\n{}\n",
+                rng.sentence(8), rng.sentence(20), rng.sentence(10),
+            )),
+            ("INDEX.md".to_string(), format!("# {project}\n\n## What\n{}\n\n## Why\n{}\n", rng.sentence(15), rng.sentence(15))),
+            ("decisions.md".to_string(), format!("# Decisions\n\n## {}\n{}\n", rng.sentence(5), rng.paragraph(3))),
+        ];
+        let history: Vec<String> = (0..HISTORY_ENTRIES).map(|i| {
+            let date = updated - chrono::Duration::days((HISTORY_ENTRIES - i) as i64);
+            let source = ["code", "code", "desktop"][i % 3];
+            serde_json::json!({
+                "id": format!("{project}-{i}"),
+                "date": format!("{date}T10:00:00Z"),
+                "title": rng.sentence(5),
+                "status": status,
+                "focus": rng.sentence(8),
+                "next_action": rng.sentence(6),
+                "commit": "",
+                "body": rng.paragraph(2),
+                "source": source,
+            }).to_string()
+        }).collect();
+        project_files.push(("history.jsonl".to_string(), format!("{{\"_schema\": \"history\", \"_version\": \"1.0\"}}\n{}\n", history.join("\n"))));
+        for n in 0..FILES_PER_PROJECT - project_files.len() {
+            let kind = ["reference", "insight", "decision"][n % 3];
+            project_files.push((format!("note-{n:02}.md"), format!(
+                "---\ntype: {kind}\nsummary: {}\ntags: [{}, {}]\n---\n# {}\n\n{}\n\n## Details\n{}\n",
+                rng.sentence(8), rng.word(), rng.word(), rng.sentence(4), rng.paragraph(4), rng.paragraph(6),
+            )));
+        }
+
+        for (name, content) in project_files.into_iter().take(files - written) {
+            std::fs::write(dir.join(name), content)?;
+            written += 1;
+        }
+        project_index += 1;
+    }
+    Ok(written)
+}
+
+/// Generate a vault of `files` files in a temporary directory, time the suite against it,
+/// and remove it again.
+pub fn run_suite(files: usize) -> Result<Report, Box<dyn std::error::Error>> {
+    let tmp = std::env::temp_dir().join(format!("wardwell-bench-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&tmp);
+    let report = measure(&tmp, files);
+    let _ = std::fs::remove_dir_all(&tmp);
+    report
+}
+
+fn measure(tmp: &Path, files: usize) -> Result<Report, Box<dyn std::error::Error>> {
+    let vault = tmp.join("vault");
+    let files = generate_vault(&vault, files)?;
+    let config_path = tmp.join("config.yml");
+    std::fs::write(&config_path, format!("vault_path: {}\nmetrics: false\n", vault.display()))?;
+    let config = crate::config::loader::load(Some(&config_path))?;
+
+    let index = Arc::new(IndexStore::open(&tmp.join("index.db"))?);
+    let started = Instant::now();
+    IndexBuilder::full_build(&index, &vault, None)?;
+    let mut measurements = vec![Measurement { name: "index_build".to_string(), millis: elapsed_ms(started) }];
+
+    let server = WardwellServer::new(config, index, Arc::new(Mutex::new(None)), None, None);
+    let params = |action: &str, query: Option<&str>| -> Result<SearchParams, serde_json::Error> {
+        serde_json::from_value(serde_json::json!({ "action": action, "query": query }))
+    };
+
+    let mut search_times = Vec::with_capacity(SEARCH_RUNS);
+    for i in 0..SEARCH_RUNS {
+        let query = format!("{} {}", WORDS[i % WORDS.len()], WORDS[(i * 7 + 3) % WORDS.len()]);
+        let p = params("search", Some(&query))?;
+        let started = Instant::now();
+        let _ = server.action_search(&p);
+        search_times.push(elapsed_ms(started));
+    }
+    measurements.push(Measurement { name: "search_median".to_string(), millis: percentile(&mut search_times, 0.5) });
+    measurements.push(Measurement { name: "search_p95".to_string(), millis: percentile(&mut search_times, 0.95) });
+
+    for (name, p) in [("orchestrate", params("orchestrate", None)?), ("history_scan", params("history", Some("retry backoff"))?)] {
+        let mut times: Vec<f64> = (0..SCAN_RUNS).map(|_| {
+            let started = Instant::now();
+            let _ = match name {
+                "orchestrate" => server.action_orchestrate(&p),
+                _ => server.action_history(&p),
+            };
+            elapsed_ms(started)
+        }).collect();
+        measurements.push(Measurement { name: name.to_string(), millis: percentile(&mut times, 0.5) });
+    }
+
+    Ok(Report { files, measured_at: chrono::Utc::now().to_rfc3339(), measurements })
+}
+
+/// Measurements over their budget, and, given a snapshot of the same vault size, those more
+/// than `tolerance` (0.25 = 25%) slower than it.
+pub fn check(report: &Report, snapshot: Option<&Report>, tolerance: f64) -> Vec<Regression> {
+    let scale = (report.files as f64 / DEFAULT_FILES as f64).max(1.0);
+    let mut regressions = Vec::new();
+    for m in &report.measurements {
+        if let Some(&(_, budget)) = BUDGETS.iter().find(|(name, _)| *name == m.name) {
+            let budget = if m.name.starts_with("search") { budget } else { budget * scale };
+            if m.millis > budget {
+                regressions.push(Regression { name: m.name.clone(), millis: m.millis, limit_millis: budget, against: "budget" });
+                continue;
+            }
+        }
+        let before = snapshot.filter(|s| s.files == report.files)
+            .and_then(|s| s.measurements.iter().find(|b| b.name == m.name));
+        if let Some(before) = before {
+            let limit = before.millis * (1.0 + tolerance);
+            if m.millis > limit {
+                regressions.push(Regression { name: m.name.clone(), millis: m.millis, limit_millis: limit, against: "snapshot" });
+            }
+        }
+    }
+    regressions
+}
+
+/// `wardwell bench`: run the suite, report it against the budgets and the saved snapshot,
+/// and with `save` make this run the new snapshot. Ok(false) when something regressed.
+pub fn run(files: usize, save: bool, tolerance_percent: f64, out: Output) -> Result<bool, Box<dyn std::error::Error>> {
+    out.line(format!("wardwell bench — {files} files\n"));
+    let report = run_suite(files)?;
+    let snapshot_path = crate::config::loader::config_dir().join(SNAPSHOT_FILE);
+    let snapshot: Option<Report> = std::fs::read_to_string(&snapshot_path).ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+    let regressions = check(&report, snapshot.as_ref(), tolerance_percent / 100.0);
+
+    for m in &report.measurements {
+        let before = snapshot.as_ref().filter(|s| s.files == report.files)
+            .and_then(|s| s.measurements.iter().find(|b| b.name == m.name))
+            .map(|b| format!("  (snapshot {:.1} ms)", b.millis))
+            .unwrap_or_default();
+        let flag = if regressions.iter().any(|r| r.name == m.name) { "  \u{2717}" } else { "" };
+        out.line(format!("  {:<16} {:>10.1} ms{before}{flag}", m.name, m.millis));
+    }
+    if snapshot.as_ref().is_some_and(|s| s.files != report.files) {
+        out.note(format!("\n  Snapshot is for {} files; only the budgets were checked.", snapshot.as_ref().map_or(0, |s| s.files)));
+    }
+    for r in &regressions {
+        out.warn(format!("wardwell: {} took {:.1} ms, over the {} limit of {:.1} ms", r.name, r.millis, r.against, r.limit_millis));
+    }
+    if save {
+        std::fs::create_dir_all(crate::config::loader::config_dir())?;
+        std::fs::write(&snapshot_path, serde_json::to_string_pretty(&report)?)?;
+        out.line(format!("\n  Saved as the snapshot in {}", snapshot_path.display()));
+    }
+
+    out.result(serde_json::json!({
+        "report": report,
+        "snapshot": snapshot.map(|s| s.measured_at),
+        "regressions": regressions,
+        "saved": save,
+    }));
+    Ok(regressions.is_empty())
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+/// The value at fraction `p` of the way through `times` once sorted.
+fn percentile(times: &mut [f64], p: f64) -> f64 {
+    times.sort_by(|a, b| a.total_cmp(b));
+    let i = ((times.len() as f64 - 1.0) * p).round() as usize;
+    times.get(i).copied().unwrap_or_default()
+}
+
+/// xorshift64: plenty for filler text, and the same vault on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn word(&mut self) -> &'static str {
+        WORDS[(self.next() % WORDS.len() as u64) as usize]
+    }
+
+    fn sentence(&mut self, words: usize) -> String {
+        (0..words).map(|_| self.word()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn paragraph(&mut self, sentences: usize) -> String {
+        (0..sentences).map(|_| format!("{}.", self.sentence(12))).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suite_measures_a_small_vault_and_flags_regressions() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(generate_vault(tmp.path(), 45).unwrap(), 45);
+        assert!(tmp.path().join("domain-00/project-002/note-00.md").exists());
+        assert!(!tmp.path().join("domain-00/project-002/note-01.md").exists());
+
+        let report = run_suite(60).unwrap();
+        let names: Vec<&str> = report.measurements.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["index_build", "search_median", "search_p95", "orchestrate", "history_scan"]);
+        assert!(check(&report, None, 0.25).is_empty(), "{report:?}");
+
+        let faster = Report {
+            measurements: report.measurements.iter().map(|m| Measurement { name: m.name.clone(), millis: m.millis / 10.0 - 1.0 }).collect(),
+            ..report.clone()
+        };
+        let slower = check(&report, Some(&faster), 0.25);
+        assert!(!slower.is_empty() && slower.iter().all(|r| r.against == "snapshot"), "{slower:?}");
+        let other_size = Report { files: 10, ..faster };
+        assert!(check(&report, Some(&other_size), 0.25).is_empty());
+
+        let over = Report { measurements: vec![Measurement { name: "search_p95".to_string(), millis: 1_000.0 }], ..report };
+        assert_eq!(check(&over, None, 0.25)[0].against, "budget");
+    }
+}
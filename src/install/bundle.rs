@@ -0,0 +1,429 @@
+use crate::vault::compress;
+use crate::vault::symlinks::SymlinkPolicy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Component, Path};
+
+/// Bundle layout version, bumped when the format changes incompatibly.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// File types bundled when `--types` isn't given.
+pub const DEFAULT_TYPES: &[&str] = &["md", "jsonl"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("not a wardwell bundle: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("bundle version {0} is newer than this wardwell understands ({BUNDLE_VERSION})")]
+    Version(u32),
+
+    #[error("invalid scope '{0}': use <domain> or <domain>/<project>")]
+    Scope(String),
+
+    #[error("'{0}' does not exist in the vault")]
+    NotFound(String),
+
+    #[error("bundle holds an unsafe path: {0}")]
+    UnsafePath(String),
+}
+
+/// A portable copy of a domain or project: every selected file, uncompressed, by
+/// vault-relative path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub wardwell_bundle: u32,
+    pub created_at: String,
+    /// `domain` or `domain/project`.
+    pub scope: String,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// What goes into a bundle.
+#[derive(Debug, Clone, Default)]
+pub struct BundleFilter {
+    /// Only JSONL entries dated on or after this day, and other files modified since.
+    pub since: Option<chrono::NaiveDate>,
+    /// Only JSONL entries dated on or before this day, and other files modified by then.
+    pub until: Option<chrono::NaiveDate>,
+    /// Extensions to include. Empty means `DEFAULT_TYPES`.
+    pub types: Vec<String>,
+}
+
+impl BundleFilter {
+    fn wants_type(&self, ext: &str) -> bool {
+        if self.types.is_empty() {
+            DEFAULT_TYPES.contains(&ext)
+        } else {
+            self.types.iter().any(|t| t.trim_start_matches('.').eq_ignore_ascii_case(ext))
+        }
+    }
+
+    fn in_range(&self, day: chrono::NaiveDate) -> bool {
+        self.since.is_none_or(|s| day >= s) && self.until.is_none_or(|u| day <= u)
+    }
+
+    fn is_dated(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+}
+
+/// Collect `scope` (a domain, or `domain/project`) from the vault. JSONL files keep their
+/// header and the entries whose `date` is in range; entries without a date are kept. Other
+/// files are kept whole if their modification day is in range. Compressed archives are
+/// stored uncompressed under their plain name.
+pub fn collect(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy, scope: &str, filter: &BundleFilter) -> Result<Bundle, BundleError> {
+    let scope = scope.trim().trim_matches('/');
+    let (domain, project) = match scope.split_once('/') {
+        Some((d, p)) if !p.contains('/') => (d, Some(p)),
+        Some(_) => return Err(BundleError::Scope(scope.to_string())),
+        None => (scope, None),
+    };
+    if domain.is_empty() || project.is_some_and(str::is_empty) || !is_safe(Path::new(scope)) {
+        return Err(BundleError::Scope(scope.to_string()));
+    }
+    if !vault_path.join(scope).is_dir() {
+        return Err(BundleError::NotFound(scope.to_string()));
+    }
+
+    let mut files = Vec::new();
+    for (d, p, dir) in crate::install::export::project_dirs(vault_path, exclude, symlinks) {
+        if d != domain || project.is_some_and(|want| want != p) {
+            continue;
+        }
+        walk(&dir, &mut |path| {
+            let logical = compress::logical_path(path);
+            let ext = logical.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+            if !filter.wants_type(&ext) {
+                return Ok(());
+            }
+            let Ok(rel) = logical.strip_prefix(vault_path) else {
+                return Ok(());
+            };
+            let content = compress::read_to_string(path)?;
+            let content = if ext == "jsonl" {
+                filter_entries(&content, filter)
+            } else if filter.is_dated() && !modified_in_range(path, filter) {
+                return Ok(());
+            } else {
+                content
+            };
+            files.push(BundleFile { path: rel.to_string_lossy().replace('\\', "/"), content });
+            Ok(())
+        })?;
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(Bundle { wardwell_bundle: BUNDLE_VERSION, created_at: chrono::Utc::now().to_rfc3339(), scope: scope.to_string(), files })
+}
+
+/// Every file under `dir`, skipping hidden entries.
+fn walk(dir: &Path, visit: &mut dyn FnMut(&Path) -> std::io::Result<()>) -> std::io::Result<()> {
+    let mut entries: Vec<std::path::PathBuf> = std::fs::read_dir(dir)?.flatten()
+        .map(|e| e.path())
+        .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            walk(&path, visit)?;
+        } else if path.is_file() {
+            visit(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The day in an entry's `date` (RFC 3339 or `YYYY-MM-DD...`).
+fn entry_day(entry: &Value) -> Option<chrono::NaiveDate> {
+    let date = entry.get("date")?.as_str()?;
+    chrono::NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn filter_entries(content: &str, filter: &BundleFilter) -> String {
+    if !filter.is_dated() {
+        return content.to_string();
+    }
+    let mut out = String::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let keep = match serde_json::from_str::<Value>(line) {
+            Ok(entry) => entry_day(&entry).is_none_or(|day| filter.in_range(day)),
+            Err(_) => true,
+        };
+        if keep {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn modified_in_range(path: &Path, filter: &BundleFilter) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| filter.in_range(chrono::DateTime::<chrono::Local>::from(t).date_naive()))
+        .unwrap_or(true)
+}
+
+/// Write `bundle` as JSON, zstd-compressed when `path` ends in `.zst`.
+pub fn save(path: &Path, bundle: &Bundle) -> Result<(), BundleError> {
+    let json = serde_json::to_string_pretty(bundle)?;
+    if compress::is_compressed(path) {
+        compress::write_compressed(path, &json)?;
+    } else {
+        std::fs::write(path, json)?;
+    }
+    Ok(())
+}
+
+/// Read a bundle written by `save` (compressed or not).
+pub fn load(path: &Path) -> Result<Bundle, BundleError> {
+    let bundle: Bundle = serde_json::from_str(&compress::read_to_string(path)?)?;
+    if bundle.wardwell_bundle > BUNDLE_VERSION {
+        return Err(BundleError::Version(bundle.wardwell_bundle));
+    }
+    Ok(bundle)
+}
+
+/// One markdown document for reading or sharing: each markdown file under its path, and each
+/// JSONL entry as a dated heading with its fields.
+pub fn render_markdown(bundle: &Bundle) -> String {
+    let mut out = format!("# {}\n\nExported {} from wardwell.\n", bundle.scope, bundle.created_at.get(..10).unwrap_or(&bundle.created_at));
+    for file in &bundle.files {
+        out.push_str(&format!("\n---\n\n## {}\n\n", file.path));
+        if !file.path.ends_with(".jsonl") {
+            let body = crate::vault::frontmatter::parse_frontmatter(&file.content).map_or_else(|_| file.content.clone(), |(_, body)| body);
+            out.push_str(body.trim());
+            out.push('\n');
+            continue;
+        }
+        for entry in file.content.lines().filter_map(|l| serde_json::from_str::<Value>(l).ok()) {
+            let Some(fields) = entry.as_object().filter(|o| !o.contains_key("_schema")) else {
+                continue;
+            };
+            let text = |key: &str| fields.get(key).and_then(Value::as_str).unwrap_or_default();
+            let date = text("date").get(..10).unwrap_or(text("date"));
+            out.push_str(&format!("### {date} — {}\n\n", text("title")));
+            for (key, value) in fields {
+                if matches!(key.as_str(), "id" | "date" | "title") {
+                    continue;
+                }
+                let value = match value {
+                    Value::String(s) if s.is_empty() => continue,
+                    Value::String(s) => s.clone(),
+                    Value::Null => continue,
+                    other => other.to_string(),
+                };
+                out.push_str(&format!("- **{key}:** {value}\n"));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// What merging a bundle did, or would do in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MergeReport {
+    /// Files that didn't exist and were written whole.
+    pub created: Vec<String>,
+    /// Files already identical to the bundle's copy.
+    pub unchanged: usize,
+    /// JSONL entries appended to existing files.
+    pub entries_added: usize,
+    /// JSONL entries the vault already had.
+    pub entries_skipped: usize,
+    /// Local files left as they are because they differ from the bundle, with why.
+    pub conflicts: Vec<(String, String)>,
+}
+
+/// Merge `bundle` into the vault. Missing files are created. An existing JSONL file gets the
+/// bundle's entries it lacks appended, in date order, so it stays append-only. Entries match
+/// on `id`, or on `date` and `title` when either side has no `id`. A match with different
+/// content is a conflict: the local entry wins and the clash is reported. Any other file that
+/// differs is a conflict too and left alone.
+pub fn merge(vault_path: &Path, bundle: &Bundle, dry_run: bool) -> Result<MergeReport, BundleError> {
+    if let Some(bad) = bundle.files.iter().find(|f| !is_safe(Path::new(&f.path))) {
+        return Err(BundleError::UnsafePath(bad.path.clone()));
+    }
+
+    let mut report = MergeReport::default();
+    for file in &bundle.files {
+        let target = vault_path.join(&file.path);
+        let Some(existing) = compress::existing(&target) else {
+            if !dry_run {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&target, &file.content)?;
+            }
+            report.created.push(file.path.clone());
+            continue;
+        };
+        let local = compress::read_to_string(&existing)?;
+        if local == file.content {
+            report.unchanged += 1;
+            continue;
+        }
+        if !file.path.ends_with(".jsonl") {
+            report.conflicts.push((file.path.clone(), "differs from the local copy".to_string()));
+            continue;
+        }
+        if compress::is_compressed(&existing) {
+            report.conflicts.push((file.path.clone(), "local copy is compressed; decompress it to merge".to_string()));
+            continue;
+        }
+
+        let local_entries: Vec<Value> = entries(&local).collect();
+        let mut incoming: Vec<Value> = Vec::new();
+        for entry in entries(&file.content) {
+            match local_entries.iter().find(|l| same_entry(l, &entry)) {
+                Some(l) if *l == entry => report.entries_skipped += 1,
+                Some(_) => report.conflicts.push((file.path.clone(), format!(
+                    "entry {} differs from the local one",
+                    entry_label(&entry),
+                ))),
+                None if incoming.iter().any(|i| same_entry(i, &entry)) => report.entries_skipped += 1,
+                None => incoming.push(entry),
+            }
+        }
+        // Stable, so same-day entries keep the bundle's order
+        incoming.sort_by_key(entry_day);
+        report.entries_added += incoming.len();
+        if !dry_run && !incoming.is_empty() {
+            use std::io::Write;
+            let mut out = std::fs::OpenOptions::new().append(true).open(&existing)?;
+            if !local.is_empty() && !local.ends_with('\n') {
+                writeln!(out)?;
+            }
+            for entry in incoming {
+                writeln!(out, "{}", serde_json::to_string(&entry)?)?;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// JSONL entries, without the `_schema` header.
+fn entries(content: &str) -> impl Iterator<Item = Value> + '_ {
+    content.lines()
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+        .filter(|v| v.is_object() && v.get("_schema").is_none())
+}
+
+fn same_entry(a: &Value, b: &Value) -> bool {
+    match (a.get("id").and_then(Value::as_str), b.get("id").and_then(Value::as_str)) {
+        (Some(x), Some(y)) => x == y,
+        _ => a.get("date") == b.get("date") && a.get("title") == b.get("title"),
+    }
+}
+
+fn entry_label(entry: &Value) -> String {
+    let text = |key: &str| entry.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+    let id = text("id");
+    if id.is_empty() { format!("{} '{}'", text("date"), text("title")) } else { id }
+}
+
+/// A relative path that stays inside the vault.
+fn is_safe(path: &Path) -> bool {
+    path.components().next().is_some() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const HEADER: &str = "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n";
+
+    #[test]
+    fn collect_filters_by_scope_type_and_date() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("work/api");
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("work/billing")).unwrap();
+        std::fs::write(dir.join("history.jsonl"), format!(
+            "{HEADER}{{\"date\":\"2026-01-05T10:00:00Z\",\"title\":\"Old\"}}\n{{\"date\":\"2026-03-01\",\"title\":\"New\"}}\n"
+        )).unwrap();
+        std::fs::write(dir.join("docs/audit.md"), "# Audit\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "scratch\n").unwrap();
+        std::fs::write(tmp.path().join("work/billing/decisions.md"), "# billing Decisions\n").unwrap();
+
+        let filter = BundleFilter { since: chrono::NaiveDate::from_ymd_opt(2026, 2, 1), ..Default::default() };
+        let bundle = collect(tmp.path(), &[], SymlinkPolicy::Follow, "work/api", &filter).unwrap();
+        let paths: Vec<&str> = bundle.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["work/api/docs/audit.md", "work/api/history.jsonl"]);
+        assert_eq!(bundle.files[1].content, format!("{HEADER}{{\"date\":\"2026-03-01\",\"title\":\"New\"}}\n"));
+
+        let txt = BundleFilter { types: vec!["txt".to_string()], ..Default::default() };
+        assert_eq!(collect(tmp.path(), &[], SymlinkPolicy::Follow, "work", &txt).unwrap().files.len(), 1);
+        assert_eq!(collect(tmp.path(), &[], SymlinkPolicy::Follow, "work", &BundleFilter::default()).unwrap().files.len(), 3);
+        assert!(matches!(collect(tmp.path(), &[], SymlinkPolicy::Follow, "../etc", &txt), Err(BundleError::Scope(_))));
+        assert!(matches!(collect(tmp.path(), &[], SymlinkPolicy::Follow, "personal", &txt), Err(BundleError::NotFound(_))));
+
+        let md = render_markdown(&bundle);
+        assert!(md.starts_with("# work/api\n"));
+        assert!(md.contains("## work/api/history.jsonl\n\n### 2026-03-01 — New\n"));
+    }
+
+    #[test]
+    fn merge_appends_missing_entries_and_reports_conflicts() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("history.jsonl"), format!(
+            "{HEADER}{{\"id\":\"a\",\"date\":\"2026-03-01\",\"title\":\"Start\"}}\n{{\"date\":\"2026-03-02\",\"title\":\"Ship\",\"body\":\"local\"}}\n"
+        )).unwrap();
+        std::fs::write(dir.join("current_state.md"), "# api local\n").unwrap();
+
+        let file = |path: &str, content: String| BundleFile { path: path.to_string(), content };
+        let bundle = Bundle {
+            wardwell_bundle: BUNDLE_VERSION,
+            created_at: "2026-03-10T00:00:00Z".to_string(),
+            scope: "work/api".to_string(),
+            files: vec![
+                file("work/api/current_state.md", "# api remote\n".to_string()),
+                file("work/api/decisions.md", "# api Decisions\n".to_string()),
+                file("work/api/history.jsonl", format!(concat!(
+                    "{}{{\"id\":\"a\",\"date\":\"2026-03-01\",\"title\":\"Start\"}}\n",
+                    "{{\"date\":\"2026-03-02\",\"title\":\"Ship\",\"body\":\"remote\"}}\n",
+                    "{{\"id\":\"c\",\"date\":\"2026-03-04\",\"title\":\"Later\"}}\n",
+                    "{{\"id\":\"b\",\"date\":\"2026-03-03\",\"title\":\"Fix\"}}\n",
+                ), HEADER)),
+            ],
+        };
+
+        let preview = merge(tmp.path(), &bundle, true).unwrap();
+        assert_eq!(preview.entries_added, 2);
+        assert!(!dir.join("decisions.md").exists());
+
+        let report = merge(tmp.path(), &bundle, false).unwrap();
+        assert_eq!(report, preview);
+        assert_eq!(report.created, vec!["work/api/decisions.md"]);
+        assert_eq!((report.entries_added, report.entries_skipped), (2, 1));
+        assert_eq!(report.conflicts.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(), vec![
+            "work/api/current_state.md", "work/api/history.jsonl",
+        ]);
+        assert!(report.conflicts[1].1.contains("2026-03-02 'Ship'"));
+        let history = std::fs::read_to_string(dir.join("history.jsonl")).unwrap();
+        let titles: Vec<String> = entries(&history).map(|e| e["title"].as_str().unwrap().to_string()).collect();
+        assert_eq!(titles, vec!["Start", "Ship", "Fix", "Later"]);
+        assert_eq!(std::fs::read_to_string(dir.join("current_state.md")).unwrap(), "# api local\n");
+
+        // Merging again changes nothing
+        let again = merge(tmp.path(), &bundle, false).unwrap();
+        assert_eq!((again.entries_added, again.created.len()), (0, 0));
+
+        let evil = Bundle { files: vec![file("../outside.md", String::new())], ..bundle };
+        assert!(matches!(merge(tmp.path(), &evil, false), Err(BundleError::UnsafePath(_))));
+    }
+}
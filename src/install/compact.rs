@@ -0,0 +1,346 @@
+use crate::daemon::summarizer::SummaryProvider;
+use crate::vault::compress::{self, HISTORY_ARCHIVE, ROLLUP_SOURCE};
+use crate::vault::symlinks::SymlinkPolicy;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// What `wardwell compact` does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompactOptions {
+    /// History entries and session summaries older than this many days are archived.
+    pub older_than_days: u32,
+    /// zstd-compress history archives and aged summaries.
+    pub compress: bool,
+    /// Leave a summary entry per archived month in `history.jsonl`.
+    pub rollup: Rollup,
+}
+
+/// How archived months are summarized in `history.jsonl`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rollup {
+    /// No summary entries.
+    #[default]
+    Off,
+    /// List the month's entry titles.
+    Titles,
+    /// Ask the summarizer model for a paragraph, falling back to titles if the call fails.
+    Ai,
+}
+
+/// What a compact run changed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct CompactReport {
+    /// History entries moved from `history.jsonl` into `history.archive.jsonl`.
+    pub entries_archived: usize,
+    /// Projects whose history was rewritten.
+    pub projects: usize,
+    /// Monthly summary entries added to `history.jsonl`.
+    pub rollups: usize,
+    /// AI summaries that failed and were written as title lists instead.
+    pub rollup_fallbacks: usize,
+    /// Files written compressed, archives and summaries together.
+    pub files_compressed: usize,
+    /// Size of those files before compression, in bytes.
+    pub bytes_before: u64,
+    /// Size of those files after compression, in bytes.
+    pub bytes_after: u64,
+}
+
+impl CompactReport {
+    pub fn bytes_saved(&self) -> u64 {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+
+    fn compressed(&mut self, before: u64, after: u64) {
+        self.files_compressed += 1;
+        self.bytes_before += before;
+        self.bytes_after += after;
+    }
+}
+
+/// Move old history into each project's archive and, with `compress`, compress the archives
+/// and the session summaries in `summaries_dir` last written before the cutoff. With a
+/// `rollup`, each archived month without a summary entry gets one; `model` writes AI ones.
+pub async fn run(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy, summaries_dir: &Path, opts: CompactOptions, provider: &dyn SummaryProvider) -> std::io::Result<CompactReport> {
+    let cutoff = chrono::Local::now().date_naive() - chrono::Duration::days(i64::from(opts.older_than_days));
+    let mut report = CompactReport::default();
+    for (_, _, dir) in crate::install::export::project_dirs(vault_path, exclude, symlinks) {
+        compact_history(&dir, cutoff, opts, provider, &mut report).await?;
+    }
+    if opts.compress {
+        compress_summaries(summaries_dir, cutoff, &mut report)?;
+    }
+    Ok(report)
+}
+
+/// Split `history.jsonl` at `cutoff`, appending the older entries to the archive. Summary
+/// entries stay in `history.jsonl` whatever their date.
+async fn compact_history(dir: &Path, cutoff: chrono::NaiveDate, opts: CompactOptions, provider: &dyn SummaryProvider, report: &mut CompactReport) -> std::io::Result<()> {
+    let compress = opts.compress;
+    let history_path = dir.join("history.jsonl");
+    let archive = compress::history_archive(dir);
+    let content = match std::fs::read_to_string(&history_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    let mut header = String::new();
+    let mut keep = String::new();
+    let mut old = String::new();
+    let mut rollups: Vec<String> = Vec::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let target = if line.starts_with("{\"_schema\"") {
+            &mut header
+        } else if is_rollup(line) {
+            rollups.push(line.to_string());
+            continue;
+        } else if entry_date(line).is_some_and(|d| d < cutoff) {
+            &mut old
+        } else {
+            &mut keep
+        };
+        target.push_str(line);
+        target.push('\n');
+    }
+
+    let plain_archive = archive.as_deref().filter(|a| !compress::is_compressed(a));
+    let archive_changed = !old.is_empty() || (compress && plain_archive.is_some());
+    let summarize = opts.rollup != Rollup::Off && (archive.is_some() || !old.is_empty());
+    if !archive_changed && !summarize {
+        return Ok(());
+    }
+
+    let mut archived = match &archive {
+        Some(path) => compress::read_to_string(path)?,
+        None => header.clone(),
+    };
+    archived.push_str(&old);
+
+    let added = if summarize {
+        let done: HashSet<String> = rollups.iter()
+            .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+            .filter_map(|v| v.get("date")?.as_str()?.get(..7).map(String::from))
+            .collect();
+        month_rollups(&archived, &done, opts.rollup, provider, report).await
+    } else {
+        Vec::new()
+    };
+    if !archive_changed && added.is_empty() {
+        return Ok(());
+    }
+
+    let write_compressed = compress || archive.as_deref().is_some_and(compress::is_compressed);
+    let plain_path = dir.join(HISTORY_ARCHIVE);
+    if archive_changed && write_compressed {
+        let after = compress::write_compressed(&compress::compressed_path(&plain_path), &archived)?;
+        report.compressed(archived.len() as u64, after);
+        if plain_path.exists() {
+            std::fs::remove_file(&plain_path)?;
+        }
+    } else if archive_changed {
+        write_atomic(&plain_path, &archived)?;
+    }
+
+    if !old.is_empty() || !added.is_empty() {
+        report.rollups += added.len();
+        rollups.extend(added);
+        rollups.sort_by_cached_key(|l| entry_date(l));
+        let summaries: String = rollups.iter().map(|l| format!("{l}\n")).collect();
+        crate::vault::jsonl::replace(&history_path, &content, &(header + &summaries + &keep))?;
+        report.entries_archived += old.lines().count();
+        report.projects += 1;
+    }
+    Ok(())
+}
+
+/// Whether a history line is a monthly summary written by compact.
+fn is_rollup(line: &str) -> bool {
+    line.contains(ROLLUP_SOURCE)
+        && serde_json::from_str::<serde_json::Value>(line)
+            .is_ok_and(|v| v.get("source").and_then(|s| s.as_str()) == Some(ROLLUP_SOURCE))
+}
+
+/// One summary entry (a JSON line) per month of `archived` not in `done`, oldest first.
+/// Each carries the month's last status, focus, and next action, dated at its last entry.
+async fn month_rollups(archived: &str, done: &HashSet<String>, rollup: Rollup, provider: &dyn SummaryProvider, report: &mut CompactReport) -> Vec<String> {
+    let mut months: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+    for line in archived.lines().filter(|l| !l.trim().is_empty() && !l.starts_with("{\"_schema\"")) {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if let Some(month) = entry.get("date").and_then(|d| d.as_str()).and_then(|d| d.get(..7))
+            && !done.contains(month)
+        {
+            months.entry(month.to_string()).or_default().push(entry);
+        }
+    }
+
+    let field = |e: &serde_json::Value, name: &str| e.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let mut lines = Vec::new();
+    for (month, mut entries) in months {
+        entries.sort_by_key(|e| field(e, "date"));
+        let Some(last) = entries.last() else {
+            continue;
+        };
+        let titles: String = entries.iter()
+            .map(|e| format!("- {}: {}", field(e, "date").get(..10).unwrap_or(""), field(e, "title")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = if rollup == Rollup::Ai {
+            let listing: String = entries.iter()
+                .map(|e| format!("- {} {}: {}", field(e, "date").get(..10).unwrap_or(""), field(e, "title"), field(e, "body")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let prompt = format!("{ROLLUP_PROMPT}\n\n{month}:\n{listing}");
+            match provider.complete(&prompt).await {
+                Ok(text) if !text.trim().is_empty() => text.trim().to_string(),
+                _ => {
+                    report.rollup_fallbacks += 1;
+                    titles
+                }
+            }
+        } else {
+            titles
+        };
+        let entry = crate::mcp::server::HistoryJsonlEntry {
+            id: crate::vault::entry_id::new_id(),
+            date: field(last, "date"),
+            title: format!("{month}: {} entr{} rolled up", entries.len(), if entries.len() == 1 { "y" } else { "ies" }),
+            status: field(last, "status"),
+            focus: field(last, "focus"),
+            next_action: field(last, "next_action"),
+            commit: String::new(),
+            body,
+            source: ROLLUP_SOURCE.to_string(),
+            blockers: Vec::new(),
+            waiting_on: Vec::new(),
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            lines.push(json);
+        }
+    }
+    lines
+}
+
+const ROLLUP_PROMPT: &str = "Summarize this month of a project's history log in one short paragraph: what got done, \
+decisions made, and where things stood at the end. Plain prose, no headings, no preamble.";
+
+/// Compress `*.md` summaries last modified before `cutoff`.
+fn compress_summaries(summaries_dir: &Path, cutoff: chrono::NaiveDate, report: &mut CompactReport) -> std::io::Result<()> {
+    let Ok(entries) = std::fs::read_dir(summaries_dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|e| e != "md") {
+            continue;
+        }
+        let modified = entry.metadata()
+            .and_then(|m| m.modified())
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).date_naive());
+        if modified.is_ok_and(|d| d < cutoff) {
+            let (before, after) = compress::compress_file(&path)?;
+            report.compressed(before, after);
+        }
+    }
+    Ok(())
+}
+
+fn entry_date(line: &str) -> Option<chrono::NaiveDate> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let date = value.get("date")?.as_str()?;
+    chrono::NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const HEADER: &str = "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n";
+    /// These runs never ask for AI rollups, so the provider is never called.
+    const NO_MODEL: crate::daemon::summarizer::ClaudeCli = crate::daemon::summarizer::ClaudeCli { model: String::new() };
+
+    fn setup() -> tempfile::TempDir {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("vault/work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d");
+        std::fs::write(dir.join("history.jsonl"), format!(
+            "{HEADER}{{\"date\":\"2024-01-05\",\"title\":\"Old one\"}}\n{{\"date\":\"2024-02-01\",\"title\":\"Old two\"}}\n{{\"date\":\"{today}\",\"title\":\"Fresh\"}}\n",
+        )).unwrap();
+        tmp
+    }
+
+    #[tokio::test]
+    async fn archives_old_history_entries() {
+        let tmp = setup();
+        let dir = tmp.path().join("vault/work/api");
+        let opts = CompactOptions { older_than_days: 90, compress: false, rollup: Rollup::Off };
+        let report = run(&tmp.path().join("vault"), &[], SymlinkPolicy::Follow, &tmp.path().join("summaries"), opts, &NO_MODEL).await.unwrap();
+        assert_eq!((report.entries_archived, report.projects, report.files_compressed), (2, 1, 0));
+
+        let history = std::fs::read_to_string(dir.join("history.jsonl")).unwrap();
+        assert!(history.starts_with(HEADER) && history.contains("Fresh") && !history.contains("Old"));
+        let archive = std::fs::read_to_string(dir.join(HISTORY_ARCHIVE)).unwrap();
+        assert!(archive.starts_with(HEADER) && archive.contains("Old one") && archive.contains("Old two"));
+
+        // Nothing left to move
+        assert_eq!(run(&tmp.path().join("vault"), &[], SymlinkPolicy::Follow, &tmp.path().join("summaries"), opts, &NO_MODEL).await.unwrap(), CompactReport::default());
+    }
+
+    #[tokio::test]
+    async fn compress_shrinks_archive_and_aged_summaries() {
+        let tmp = setup();
+        let dir = tmp.path().join("vault/work/api");
+        std::fs::write(dir.join(HISTORY_ARCHIVE), format!("{HEADER}{}", "{\"date\":\"2023-06-01\",\"title\":\"Ancient\"}\n".repeat(40))).unwrap();
+        let summaries = tmp.path().join("summaries");
+        std::fs::create_dir_all(&summaries).unwrap();
+        std::fs::write(summaries.join("fresh.md"), "---\ntype: thread\n---\nToday").unwrap();
+
+        let opts = CompactOptions { older_than_days: 90, compress: true, rollup: Rollup::Off };
+        let report = run(&tmp.path().join("vault"), &[], SymlinkPolicy::Follow, &summaries, opts, &NO_MODEL).await.unwrap();
+        assert_eq!((report.entries_archived, report.files_compressed), (2, 1));
+        assert!(report.bytes_saved() > 0);
+        assert!(!dir.join(HISTORY_ARCHIVE).exists());
+        assert!(summaries.join("fresh.md").exists());
+
+        let archive = compress::read_to_string(&compress::compressed_path(&dir.join(HISTORY_ARCHIVE))).unwrap();
+        assert_eq!(archive.matches("Ancient").count(), 40);
+        assert!(archive.contains("Old one"));
+    }
+
+    #[tokio::test]
+    async fn rollup_leaves_a_summary_per_archived_month() {
+        let tmp = setup();
+        let dir = tmp.path().join("vault/work/api");
+        std::fs::write(dir.join(HISTORY_ARCHIVE), format!("{HEADER}{{\"date\":\"2023-12-20\",\"title\":\"Earlier\",\"status\":\"paused\"}}\n")).unwrap();
+        let opts = CompactOptions { older_than_days: 90, compress: false, rollup: Rollup::Titles };
+        let report = run(&tmp.path().join("vault"), &[], SymlinkPolicy::Follow, &tmp.path().join("summaries"), opts, &NO_MODEL).await.unwrap();
+        assert_eq!((report.entries_archived, report.rollups), (2, 3));
+
+        let history = std::fs::read_to_string(dir.join("history.jsonl")).unwrap();
+        let lines: Vec<serde_json::Value> = history.lines().skip(1).map(|l| serde_json::from_str(l).unwrap()).collect();
+        let titles: Vec<&str> = lines.iter().map(|l| l["title"].as_str().unwrap()).collect();
+        assert_eq!(titles, vec!["2023-12: 1 entry rolled up", "2024-01: 1 entry rolled up", "2024-02: 1 entry rolled up", "Fresh"]);
+        assert_eq!(lines[0]["source"], ROLLUP_SOURCE);
+        assert_eq!(lines[0]["status"], "paused");
+        assert_eq!(lines[1]["body"], "- 2024-01-05: Old one");
+        let archive = std::fs::read_to_string(dir.join(HISTORY_ARCHIVE)).unwrap();
+        assert!(!archive.contains(ROLLUP_SOURCE));
+
+        // Summaries are written once and never archived themselves
+        let again = run(&tmp.path().join("vault"), &[], SymlinkPolicy::Follow, &tmp.path().join("summaries"), opts, &NO_MODEL).await.unwrap();
+        assert_eq!(again, CompactReport::default());
+        assert_eq!(std::fs::read_to_string(dir.join("history.jsonl")).unwrap(), history);
+    }
+}
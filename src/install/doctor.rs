@@ -1,27 +1,91 @@
 use crate::config::loader::{self, config_dir};
 use crate::install::detect;
 use crate::install::mcp_config::{self, McpConfigPaths, McpEntryStatus};
+use crate::install::output::Output;
+use crate::vault::symlinks::SymlinkPolicy;
+
+/// Run diagnostic checks. `--quiet` prints only failing checks; `--json` reports each check
+/// as `{name, ok, detail}`.
+pub fn run(out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    out.line("wardwell doctor\n");
+
+    let (lines, all_ok) = collect_checks();
+    let checks = group_checks(&lines);
+    for (_, text) in &checks {
+        if out.is_human() {
+            println!("{text}");
+        } else if out == Output::Quiet && text.contains('\u{2717}') {
+            out.warn(text);
+        }
+    }
+
+    out.line("");
+    if all_ok {
+        out.line("  All checks passed.");
+    } else {
+        out.line("  Some checks failed. Run `wardwell init` to fix.");
+    }
+    out.verdict(all_ok, serde_json::json!({ "passed": all_ok, "checks": checks_json(&checks) }));
+
+    Ok(())
+}
 
-/// Run diagnostic checks.
-pub fn run() -> Result<(), Box<dyn std::error::Error>> {
-    println!("wardwell doctor\n");
+/// Grouped checks as `{name, ok, detail}`, with detail lines joined by newlines.
+fn checks_json(checks: &[(String, String)]) -> Vec<serde_json::Value> {
+    checks.iter().map(|(label, text)| {
+        let mut lines = text.lines();
+        let head = lines.next().unwrap_or("");
+        // Not every line pads its label to the same column, so split at the mark itself
+        let (name, ok, rest) = match head.find(['\u{2713}', '\u{2717}']) {
+            Some(i) => (head[..i].trim(), !head[i..].starts_with('\u{2717}'), &head[i + '\u{2713}'.len_utf8()..]),
+            None => (label.as_str(), true, ""),
+        };
+        let mut detail: Vec<&str> = vec![rest.trim()];
+        detail.extend(lines.map(str::trim));
+        detail.retain(|d| !d.is_empty());
+        serde_json::json!({ "name": name, "ok": ok, "detail": detail.join("\n") })
+    }).collect()
+}
 
+/// Run every check once. Returns the report lines and whether all checks passed.
+fn collect_checks() -> (Vec<String>, bool) {
+    let mut out = Vec::new();
     let mut all_ok = true;
 
+    if let Some(reason) = crate::install::killswitch::disabled_reason() {
+        out.push(format!("  Hooks                                  \u{2717} disabled ({reason}). Run `wardwell enable`"));
+        all_ok = false;
+    }
+
+    let startup = crate::mcp::safe_mode::read_record(&config_dir());
+    if startup.consecutive_failures > 0 {
+        let error = startup.last_error.as_deref().unwrap_or("no error recorded");
+        let mode = if startup.in_safe_mode() { ", next serve starts in safe mode" } else { "" };
+        out.push(format!("  Startup                                \u{2717} {} failed start(s){mode}: {error}", startup.consecutive_failures));
+        all_ok = false;
+    }
+
     // 1. Config
     let config_path = config_dir().join("config.yml");
     if config_path.exists() {
         match loader::load(Some(&config_path)) {
             Ok(config) => {
-                println!("  Config                                 \u{2713} vault: {}", config.vault_path.display());
+                out.push(format!("  Config                                 \u{2713} vault: {}", config.vault_path.display()));
 
                 // Vault directory + file count
                 if config.vault_path.exists() {
-                    let md_count = count_md_files(&config.vault_path, &config.exclude);
-                    println!("  Vault                                  \u{2713} {} .md files", md_count);
+                    let md_count = count_md_files(&config.vault_path, &config.exclude, config.symlinks);
+                    out.push(format!("  Vault                                  \u{2713} {} .md files", md_count));
+                    let links = crate::vault::symlinks::scan(&config.vault_path, &config.exclude, config.symlinks);
+                    if !links.is_empty() {
+                        out.push(symlinks_line(&links, config.symlinks));
+                        for link in &links {
+                            out.push(format!("    {} \u{2192} {}: {}", link.path, link.target.display(), link.verdict.describe()));
+                        }
+                    }
                 } else {
-                    println!("  Vault                                  \u{2717}");
-                    println!("    {} does not exist", config.vault_path.display());
+                    out.push("  Vault                                  \u{2717}".to_string());
+                    out.push(format!("    {} does not exist", config.vault_path.display()));
                     all_ok = false;
                 }
 
@@ -29,12 +93,23 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                 if config.vault_path.exists() {
                     let domains = list_vault_domains(&config.vault_path);
                     if domains.is_empty() {
-                        println!("  Domains                                \u{2717} no subdirectories in vault");
+                        out.push("  Domains                                \u{2717} no subdirectories in vault".to_string());
                     } else {
-                        println!("  Domains                                \u{2713} {}", domains.join(", "));
+                        out.push(format!("  Domains                                \u{2713} {}", domains.join(", ")));
                     }
                 }
 
+                // Domain files that didn't load as written
+                let domain_errors = config.registry.errors();
+                if !domain_errors.is_empty() {
+                    out.push(format!("  Domain files                           \u{2717} {} with problems", domain_errors.len()));
+                    for error in domain_errors {
+                        let loaded = if error.fallback.is_some() { "loaded from its valid parts" } else { "not loaded" };
+                        out.push(format!("    {} ({loaded}): {}", error.file, error.problems.join("; ")));
+                    }
+                    all_ok = false;
+                }
+
                 // Index
                 let index_path = config_dir().join("index.db");
                 if index_path.exists() {
@@ -47,19 +122,57 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                         let size = std::fs::metadata(&index_path)
                             .map(|m| format_size(m.len()))
                             .unwrap_or_default();
-                        println!("  Index                                  \u{2713} {} entries ({})", count, size);
+                        out.push(format!("  Index                                  \u{2713} {} entries ({})", count, size));
+                        drop(conn);
+                        let threshold = chrono::Duration::minutes(config.stale_index.threshold_minutes as i64);
+                        match crate::index::freshness::check(&index, &config.vault_path, &config.exclude, config.symlinks, threshold) {
+                            Ok(None) => out.push("  Index freshness                        \u{2713} up to date".to_string()),
+                            Ok(Some(stale)) => {
+                                out.push(format!("  Index freshness                        \u{2717} {} changed since last index update", stale.newest_path));
+                                out.push("    run `wardwell reindex`, or restart `wardwell serve` if the watcher stopped".to_string());
+                                all_ok = false;
+                            }
+                            Err(e) => out.push(format!("  Index freshness                        \u{2717} could not check: {e}")),
+                        }
                     } else {
-                        println!("  Index                                  \u{2717} could not open");
+                        out.push("  Index                                  \u{2717} could not open".to_string());
                         all_ok = false;
                     }
                 } else {
-                    println!("  Index                                  \u{2717} not built yet (run `wardwell serve`)");
+                    out.push("  Index                                  \u{2717} not built yet (run `wardwell serve`)".to_string());
                     all_ok = false;
                 }
 
+                // Machine files: malformed lines, and damage since the last `wardwell verify --snapshot`
+                if config.vault_path.exists() {
+                    let manifest = crate::install::verify::load(&config_dir()).ok().flatten();
+                    let report = crate::install::verify::verify(&config.vault_path, &config.exclude, config.symlinks, manifest.as_ref());
+                    if !report.is_ok() {
+                        out.push(format!("  Integrity                              \u{2717} {} problem(s) in machine files (run `wardwell verify`)", report.problems.len()));
+                        for problem in report.problems.iter().take(3) {
+                            out.push(format!("    {}: {}", problem.path, problem.detail));
+                        }
+                        all_ok = false;
+                    } else if report.snapshot {
+                        out.push(format!("  Integrity                              \u{2713} {} machine files match the snapshot", report.checked));
+                    } else {
+                        out.push(format!("  Integrity                              \u{2713} {} machine files parse (no snapshot: `wardwell verify --snapshot`)", report.checked));
+                    }
+                }
+
                 // Excluded patterns
                 if !config.exclude.is_empty() {
-                    println!("  Excluded                               \u{2713} {}", config.exclude.join(", "));
+                    out.push(format!("  Excluded                               \u{2713} {}", config.exclude.join(", ")));
+                }
+
+                // Session folders kept out by session_exclude
+                if !config.session_exclude.is_empty() {
+                    let excluded = crate::daemon::indexer::excluded_project_dirs(&config.session_sources);
+                    let globs: Vec<&str> = config.session_exclude.iter().map(|g| g.as_str()).collect();
+                    out.push(format!("  Session exclude                        \u{2713} {} transcript folder(s) excluded by {}", excluded.len(), globs.join(", ")));
+                    for folder in &excluded {
+                        out.push(format!("    {folder}"));
+                    }
                 }
 
                 // Sessions
@@ -68,7 +181,87 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                     && let Ok(store) = crate::daemon::indexer::SessionStore::open(&sessions_db)
                     && let Ok(count) = store.count()
                 {
-                    println!("  Sessions                               \u{2713} {} indexed", count);
+                    out.push(format!("  Sessions                               \u{2713} {} indexed", count));
+
+                    let backoff = store.backoff_count().unwrap_or(0);
+                    match crate::daemon::summarizer::breaker_state(&store) {
+                        Ok(breaker) if breaker.is_open() => {
+                            let until = breaker.open_until
+                                .map(|t| t.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                                .unwrap_or_default();
+                            out.push(format!("  Summarizer                             \u{2717} paused until {until} after {} consecutive failures", breaker.consecutive_failures));
+                            out.push("    check that `claude` is logged in and not rate-limited".to_string());
+                            all_ok = false;
+                        }
+                        Ok(_) if backoff > 0 => {
+                            out.push(format!("  Summarizer                             \u{2713} healthy ({backoff} sessions waiting to retry)"));
+                        }
+                        Ok(_) => out.push("  Summarizer                             \u{2713} healthy".to_string()),
+                        Err(e) => out.push(format!("  Summarizer                             \u{2717} could not read state: {e}")),
+                    }
+
+                    if let Ok(pending) = store.unsummarized()
+                        && !pending.is_empty()
+                    {
+                        let per_cycle = config.ai.max_summaries_per_cycle;
+                        let cycles = if per_cycle == 0 { 1 } else { pending.len().div_ceil(per_cycle) };
+                        let plural = if cycles == 1 { "" } else { "s" };
+                        out.push(format!("  Summary backlog                        \u{2713} {} sessions (~{cycles} cycle{plural})", pending.len()));
+                    }
+                }
+
+                // Backup
+                if let Some(ref backup) = config.backup {
+                    match crate::daemon::backup::last_success(&config_dir()) {
+                        Some(last) => {
+                            let age = chrono::Utc::now() - last;
+                            let age_str = format_age(age);
+                            if age > chrono::Duration::hours(2 * backup.interval_hours as i64) {
+                                out.push(format!("  Backup                                 \u{2717} last success {age_str} ago (every {}h)", backup.interval_hours));
+                                all_ok = false;
+                            } else {
+                                out.push(format!("  Backup                                 \u{2713} last success {age_str} ago"));
+                            }
+                        }
+                        None => {
+                            out.push("  Backup                                 \u{2717} never succeeded (run `wardwell backup now`)".to_string());
+                            all_ok = false;
+                        }
+                    }
+                }
+
+                // Links
+                if let Some(report) = crate::daemon::links::read_report(&config_dir()) {
+                    use crate::daemon::links::LinkKind;
+                    let related = report.broken_count(LinkKind::Related);
+                    let urls = report.broken_count(LinkKind::Url);
+                    if related + urls > 0 {
+                        out.push(format!("  Links                                  \u{2717} {related} dead related path(s), {urls} broken URL(s) (run `wardwell links check`)"));
+                        all_ok = false;
+                    } else {
+                        out.push(format!("  Links                                  \u{2713} {} related links resolve", report.related_checked));
+                    }
+                }
+
+                // Latency, from the last week of calls recorded in metrics.db
+                let metrics_db = config_dir().join("metrics.db");
+                if config.metrics
+                    && config.slow_action_ms > 0
+                    && metrics_db.exists()
+                    && let Ok(store) = crate::mcp::metrics::MetricsStore::open(&metrics_db)
+                {
+                    let since = (chrono::Local::now().date_naive() - chrono::Days::new(6)).to_string();
+                    let latencies = store.latencies(&since).unwrap_or_default();
+                    let mut slow: Vec<_> = latencies.iter().filter(|(_, l)| l.p95_ms >= config.slow_action_ms).collect();
+                    slow.sort_by_key(|(_, l)| std::cmp::Reverse(l.p95_ms));
+                    if slow.is_empty() {
+                        out.push(format!("  Latency                                \u{2713} p95 under {} ms for {} action(s) this week", config.slow_action_ms, latencies.len()));
+                    } else {
+                        out.push(format!("  Latency                                \u{2717} {} action(s) with p95 over {} ms this week", slow.len(), config.slow_action_ms));
+                        for (action, l) in slow.iter().take(5) {
+                            out.push(format!("    {action}: p50 {} ms, p95 {} ms over {} calls", l.p50_ms, l.p95_ms, l.calls));
+                        }
+                    }
                 }
 
                 // MCP configs
@@ -76,8 +269,8 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                 let binary_path = detect::find_binary_path();
                 let binary_str = binary_path.to_string_lossy().to_string();
 
-                check_mcp("Claude Code MCP", &mcp_paths.claude_code, &binary_str, &mut all_ok);
-                check_mcp("Claude Desktop MCP", &mcp_paths.claude_desktop, &binary_str, &mut all_ok);
+                check_mcp("Claude Code MCP", &mcp_paths.claude_code, &binary_str, &mut out, &mut all_ok);
+                check_mcp("Claude Desktop MCP", &mcp_paths.claude_desktop, &binary_str, &mut out, &mut all_ok);
 
                 // CLAUDE.md pointers
                 let domain_paths: Vec<String> = config.registry
@@ -96,9 +289,9 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                     }
                 }
                 if pointer_count > 0 {
-                    println!("  CLAUDE.md pointer                      \u{2713} markers found");
+                    out.push("  CLAUDE.md pointer                      \u{2713} markers found".to_string());
                 } else {
-                    println!("  CLAUDE.md pointer                      \u{2717} no wardwell markers");
+                    out.push("  CLAUDE.md pointer                      \u{2717} no wardwell markers".to_string());
                     all_ok = false;
                 }
 
@@ -106,44 +299,142 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                 let home = dirs::home_dir().unwrap_or_default();
                 let settings_path = home.join(".claude/settings.json");
                 if check_session_start_hook(&settings_path) {
-                    println!("  SessionStart hook                      \u{2713} wardwell inject");
+                    out.push("  SessionStart hook                      \u{2713} wardwell inject".to_string());
                 } else {
-                    println!("  SessionStart hook                      \u{2717} not registered");
+                    out.push("  SessionStart hook                      \u{2717} not registered".to_string());
                     all_ok = false;
                 }
 
-                // Claude CLI
-                let claude_available = std::process::Command::new("claude")
+                // Summary provider
+                let provider = crate::daemon::summarizer::provider(&config.ai, None);
+                let found = |bin: &str| std::process::Command::new(bin)
                     .arg("--version")
                     .stdout(std::process::Stdio::null())
                     .stderr(std::process::Stdio::null())
                     .status()
                     .is_ok_and(|s| s.success());
-                if claude_available {
-                    println!("  Claude CLI                             \u{2713} {} available", config.ai.summarize_model);
-                } else {
-                    println!("  Claude CLI                             \u{2717} `claude` not found");
-                    all_ok = false;
+                match config.ai.provider {
+                    crate::config::loader::AiProvider::ClaudeCli if found("claude") => {
+                        out.push(format!("  Claude CLI                             \u{2713} {} available", provider.model()));
+                    }
+                    crate::config::loader::AiProvider::ClaudeCli => {
+                        out.push("  Claude CLI                             \u{2717} `claude` not found".to_string());
+                        all_ok = false;
+                    }
+                    _ if !found("curl") => {
+                        out.push(format!("  Summary provider                       \u{2717} {} needs `curl`, not found", provider.name()));
+                        all_ok = false;
+                    }
+                    crate::config::loader::AiProvider::Openai if config.ai.api_key.is_none() && config.ai.base_url.is_none() => {
+                        out.push("  Summary provider                       \u{2717} openai without ai.api_key".to_string());
+                        all_ok = false;
+                    }
+                    _ => out.push(format!("  Summary provider                       \u{2713} {} ({})", provider.name(), provider.model())),
                 }
             }
             Err(e) => {
-                println!("  Config                                 \u{2717} parse error: {e}");
+                out.push(format!("  Config                                 \u{2717} parse error: {e}"));
                 all_ok = false;
             }
         }
     } else {
-        println!("  Config                                 \u{2717} not found. Run `wardwell init`.");
+        out.push("  Config                                 \u{2717} not found. Run `wardwell init`.".to_string());
         all_ok = false;
     }
 
-    println!();
-    if all_ok {
-        println!("  All checks passed.");
-    } else {
-        println!("  Some checks failed. Run `wardwell init` to fix.");
+    (out, all_ok)
+}
+
+/// Re-run checks every `interval`, printing only the checks whose output changed.
+/// Adds live lines for pending summaries and an MCP stdio handshake against a throwaway server.
+pub fn run_watch(interval: std::time::Duration) -> Result<(), Box<dyn std::error::Error>> {
+    println!("wardwell doctor --watch (every {}s, Ctrl-C to stop)\n", interval.as_secs());
+
+    let binary = detect::find_binary_path();
+    let mut previous: Vec<(String, String)> = Vec::new();
+    loop {
+        let (mut lines, _) = collect_checks();
+        lines.extend(live_checks(&binary));
+        let current = group_checks(&lines);
+
+        let stamp = chrono::Local::now().format("%H:%M:%S");
+        if previous.is_empty() {
+            println!("[{stamp}]");
+            for (_, text) in &current {
+                println!("{text}");
+            }
+        } else {
+            for delta in check_deltas(&previous, &current) {
+                println!("[{stamp}] {}", delta.trim_start());
+            }
+        }
+        previous = current;
+        std::thread::sleep(interval);
     }
+}
 
-    Ok(())
+/// Checks that only make sense when watching: queue depth and a real MCP handshake.
+fn live_checks(binary: &std::path::Path) -> Vec<String> {
+    let mut out = Vec::new();
+
+    let sessions_db = config_dir().join("sessions.db");
+    if sessions_db.exists()
+        && let Ok(store) = crate::daemon::indexer::SessionStore::open(&sessions_db)
+        && let Ok(pending) = store.unsummarized()
+    {
+        out.push(format!("  {:<38} \u{2713} {}", "Pending summaries", pending.len()));
+    }
+
+    match probe_mcp_handshake(binary, std::time::Duration::from_secs(15)) {
+        Ok(info) => out.push(format!("  {:<38} \u{2713} {info}", "MCP handshake")),
+        Err(e) => out.push(format!("  {:<38} \u{2717} {e}", "MCP handshake")),
+    }
+    out
+}
+
+/// Group report lines into (label, text) checks. Indented detail lines stay with the check above them.
+fn group_checks(lines: &[String]) -> Vec<(String, String)> {
+    let mut checks: Vec<(String, String)> = Vec::new();
+    for line in lines {
+        if line.starts_with("    ")
+            && let Some(last) = checks.last_mut()
+        {
+            last.1.push('\n');
+            last.1.push_str(line);
+            continue;
+        }
+        let label = line.chars().take(41).collect::<String>().trim().to_string();
+        checks.push((label, line.clone()));
+    }
+    checks
+}
+
+/// Checks that are new, changed, or gone since the previous run.
+fn check_deltas(previous: &[(String, String)], current: &[(String, String)]) -> Vec<String> {
+    let mut deltas = Vec::new();
+    for (label, text) in current {
+        if previous.iter().find(|(l, _)| l == label).map(|(_, t)| t) != Some(text) {
+            deltas.push(text.clone());
+        }
+    }
+    for (label, _) in previous {
+        if !current.iter().any(|(l, _)| l == label) {
+            deltas.push(format!("  {label:<38} - no longer reported"));
+        }
+    }
+    deltas
+}
+
+/// Spawn `<binary> serve --no-background`, send an MCP `initialize` request over stdio,
+/// and wait for the response. Returns a short description of the server that answered.
+fn probe_mcp_handshake(binary: &std::path::Path, timeout: std::time::Duration) -> Result<String, String> {
+    let mut client = crate::install::selftest::StdioClient::spawn(binary, &[])?;
+    let result = client.initialize("wardwell-doctor", timeout)?;
+    let info = result.get("serverInfo")
+        .ok_or_else(|| format!("unexpected response: {}", result.to_string().chars().take(120).collect::<String>()))?;
+    let name = info.get("name").and_then(|n| n.as_str()).unwrap_or("unknown");
+    let version = info.get("version").and_then(|v| v.as_str()).unwrap_or("?");
+    Ok(format!("{name} {version} answered initialize"))
 }
 
 fn check_session_start_hook(settings_path: &std::path::Path) -> bool {
@@ -174,21 +465,21 @@ fn check_session_start_hook(settings_path: &std::path::Path) -> bool {
     })
 }
 
-fn check_mcp(name: &str, config_path: &std::path::Path, expected_binary: &str, all_ok: &mut bool) {
+fn check_mcp(name: &str, config_path: &std::path::Path, expected_binary: &str, out: &mut Vec<String>, all_ok: &mut bool) {
     match mcp_config::check_mcp_entry(config_path) {
         McpEntryStatus::Configured { binary_path } => {
             if binary_path == expected_binary {
-                println!("  {name:<40} \u{2713} wardwell in mcpServers");
+                out.push(format!("  {name:<40} \u{2713} wardwell in mcpServers"));
             } else {
-                println!("  {name:<40} \u{2713} wardwell (binary path differs)");
+                out.push(format!("  {name:<40} \u{2713} wardwell (binary path differs)"));
             }
         }
         McpEntryStatus::NotConfigured => {
-            println!("  {name:<40} \u{2717} not configured");
+            out.push(format!("  {name:<40} \u{2717} not configured"));
             *all_ok = false;
         }
         McpEntryStatus::ConfigMissing => {
-            println!("  {name:<40} \u{2717} config file missing");
+            out.push(format!("  {name:<40} \u{2717} config file missing"));
             *all_ok = false;
         }
     }
@@ -210,12 +501,38 @@ fn list_vault_domains(vault_dir: &std::path::Path) -> Vec<String> {
     domains
 }
 
+/// One line summing up the vault's symlinks by what walks do with them.
+fn symlinks_line(links: &[crate::vault::symlinks::Symlink], policy: SymlinkPolicy) -> String {
+    use crate::vault::symlinks::Verdict;
+    let followed = links.iter().filter(|l| l.verdict == Verdict::Followed).count();
+    let broken = links.iter().filter(|l| l.verdict == Verdict::Broken).count();
+    let mut parts = vec![format!("{followed} followed"), format!("{} skipped", links.len() - followed - broken)];
+    if broken > 0 {
+        parts.push(format!("{broken} broken"));
+    }
+    let policy = match policy {
+        SymlinkPolicy::Follow => "follow",
+        SymlinkPolicy::Skip => "skip",
+    };
+    format!("  Symlinks                               \u{2713} {} (symlinks: {policy})", parts.join(", "))
+}
+
 /// Count .md files in a directory tree, respecting exclude patterns.
-fn count_md_files(root: &std::path::Path, exclude: &[String]) -> usize {
-    let results = crate::vault::reader::walk_vault_filtered(root, exclude);
+fn count_md_files(root: &std::path::Path, exclude: &[String], symlinks: SymlinkPolicy) -> usize {
+    let results = crate::vault::reader::walk_vault_filtered(root, exclude, symlinks);
     results.iter().filter(|r| r.is_ok()).count()
 }
 
+fn format_age(age: chrono::Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    }
+}
+
 fn format_size(bytes: u64) -> String {
     if bytes >= 1_000_000 {
         format!("{}MB", bytes / 1_000_000)
@@ -251,6 +568,13 @@ mod tests {
         assert_eq!(format_size(0), "0B");
     }
 
+    #[test]
+    fn format_age_picks_largest_unit() {
+        assert_eq!(format_age(chrono::Duration::minutes(5)), "5m");
+        assert_eq!(format_age(chrono::Duration::hours(3)), "3h");
+        assert_eq!(format_age(chrono::Duration::hours(50)), "2d");
+    }
+
     #[test]
     fn list_vault_domains_with_subdirs() {
         let dir = tempfile::tempdir().unwrap();
@@ -280,10 +604,61 @@ mod tests {
         let excluded = root.join("node_modules");
         std::fs::create_dir(&excluded).unwrap();
         std::fs::write(excluded.join("pkg.md"), "---\ntype: reference\n---\n# Pkg\n").unwrap();
-        let count = count_md_files(root, &["node_modules".to_string()]);
+        let count = count_md_files(root, &["node_modules".to_string()], SymlinkPolicy::Follow);
         assert_eq!(count, 2);
     }
 
+    #[test]
+    fn group_checks_keeps_detail_lines_with_their_check() {
+        let lines = vec![
+            "  Index                                  \u{2713} 10 entries (4KB)".to_string(),
+            "  Index freshness                        \u{2717} a.md changed since last index update".to_string(),
+            "    run `wardwell reindex`".to_string(),
+        ];
+        let checks = group_checks(&lines);
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].0, "Index");
+        assert_eq!(checks[1].0, "Index freshness");
+        assert!(checks[1].1.ends_with("\n    run `wardwell reindex`"));
+    }
+
+    #[test]
+    fn checks_json_splits_status_and_detail() {
+        let checks = group_checks(&[
+            "  Index                                  \u{2713} 10 entries (4KB)".to_string(),
+            "  Vault                                  \u{2717}".to_string(),
+            "    /tmp/nope does not exist".to_string(),
+        ]);
+        let json = checks_json(&checks);
+        assert_eq!(json[0], serde_json::json!({"name": "Index", "ok": true, "detail": "10 entries (4KB)"}));
+        assert_eq!(json[1], serde_json::json!({"name": "Vault", "ok": false, "detail": "/tmp/nope does not exist"}));
+    }
+
+    #[test]
+    fn check_deltas_reports_changed_new_and_gone() {
+        let before = group_checks(&[
+            "  Index                                  \u{2713} 10 entries".to_string(),
+            "  Sessions                               \u{2713} 3 indexed".to_string(),
+            "  Backup                                 \u{2713} last success 1h ago".to_string(),
+        ]);
+        let after = group_checks(&[
+            "  Index                                  \u{2713} 12 entries".to_string(),
+            "  Sessions                               \u{2713} 3 indexed".to_string(),
+            "  MCP handshake                          \u{2713} wardwell answered".to_string(),
+        ]);
+        let deltas = check_deltas(&before, &after);
+        assert_eq!(deltas.len(), 3);
+        assert!(deltas[0].contains("12 entries"));
+        assert!(deltas[1].contains("MCP handshake"));
+        assert!(deltas[2].contains("Backup") && deltas[2].contains("no longer reported"));
+    }
+
+    #[test]
+    fn probe_mcp_handshake_reports_missing_binary() {
+        let err = probe_mcp_handshake(std::path::Path::new("/nonexistent/wardwell"), std::time::Duration::from_secs(1)).unwrap_err();
+        assert!(err.contains("could not start"));
+    }
+
     #[test]
     fn check_session_start_hook_detects_nested() {
         let dir = tempfile::tempdir().unwrap();
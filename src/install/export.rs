@@ -0,0 +1,297 @@
+use serde::Deserialize;
+use serde_json::Value;
+use crate::vault::symlinks::SymlinkPolicy;
+use std::path::Path;
+
+/// Output format for `wardwell export`.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Which records `wardwell export` flattens.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExportWhat {
+    History,
+    Lessons,
+    Decisions,
+    Sessions,
+}
+
+/// Flattened records with a fixed column set, so every row has every column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub columns: &'static [&'static str],
+    pub rows: Vec<Vec<Value>>,
+}
+
+const HISTORY_COLUMNS: &[&str] = &["domain", "project", "date", "title", "status", "focus", "next_action", "commit", "body", "source"];
+const LESSON_COLUMNS: &[&str] = &["domain", "project", "date", "title", "what_happened", "root_cause", "prevention", "source"];
+const DECISION_COLUMNS: &[&str] = &["domain", "project", "date", "title", "body"];
+const SESSION_COLUMNS: &[&str] = &[
+    "session_id", "domain", "project", "project_path", "started_at", "ended_at", "duration_minutes",
+    "messages", "user_messages", "assistant_messages", "file_size", "summarized",
+];
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct HistoryRow {
+    date: String,
+    title: String,
+    status: String,
+    focus: String,
+    next_action: String,
+    commit: String,
+    body: String,
+    source: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LessonRow {
+    date: String,
+    title: String,
+    what_happened: String,
+    root_cause: String,
+    prevention: String,
+    source: String,
+}
+
+/// Build the table for `what`: vault records from every `<domain>/<project>/`, or sessions
+/// from `sessions.db` (empty if it doesn't exist yet).
+pub fn build(
+    what: ExportWhat,
+    vault_path: &Path,
+    exclude: &[String],
+    symlinks: SymlinkPolicy,
+    sessions_db: &Path,
+) -> Result<Table, crate::daemon::indexer::SessionError> {
+    if what != ExportWhat::Sessions {
+        return Ok(vault_table(vault_path, exclude, symlinks, what));
+    }
+    if !sessions_db.exists() {
+        return Ok(sessions_table(&[]));
+    }
+    let store = crate::daemon::indexer::SessionStore::open(sessions_db)?;
+    Ok(sessions_table(&store.all_sessions()?))
+}
+
+/// History, lessons, or decisions across the vault, sorted by date, oldest first.
+fn vault_table(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy, what: ExportWhat) -> Table {
+    let columns = match what {
+        ExportWhat::History => HISTORY_COLUMNS,
+        ExportWhat::Lessons => LESSON_COLUMNS,
+        ExportWhat::Decisions => DECISION_COLUMNS,
+        ExportWhat::Sessions => SESSION_COLUMNS,
+    };
+    let mut rows: Vec<Vec<Value>> = Vec::new();
+    for (domain, project, dir) in project_dirs(vault_path, exclude, symlinks) {
+        let prefix = [Value::from(domain), Value::from(project)];
+        match what {
+            ExportWhat::History => {
+                let archive = crate::vault::compress::history_archive(&dir);
+                // Compact's monthly summaries repeat the archived entries exported alongside them
+                let recent = crate::vault::jsonl::read_entries::<HistoryRow>(&dir.join("history.jsonl")).into_iter()
+                    .filter(|e| archive.is_none() || e.source != crate::vault::compress::ROLLUP_SOURCE);
+                for e in archive.iter().flat_map(|a| crate::vault::jsonl::read_entries::<HistoryRow>(a)).chain(recent) {
+                    let mut row = prefix.to_vec();
+                    row.extend([e.date, e.title, e.status, e.focus, e.next_action, e.commit, e.body, e.source].map(Value::from));
+                    rows.push(row);
+                }
+            }
+            ExportWhat::Lessons => {
+                for e in crate::vault::jsonl::read_entries::<LessonRow>(&dir.join("lessons.jsonl")) {
+                    let mut row = prefix.to_vec();
+                    row.extend([e.date, e.title, e.what_happened, e.root_cause, e.prevention, e.source].map(Value::from));
+                    rows.push(row);
+                }
+            }
+            ExportWhat::Decisions => {
+                let Ok(content) = std::fs::read_to_string(dir.join("decisions.md")) else {
+                    continue;
+                };
+                for (date, title, body) in crate::mcp::server::parse_decisions(&content) {
+                    let mut row = prefix.to_vec();
+                    row.extend([date, title, body].map(Value::from));
+                    rows.push(row);
+                }
+            }
+            ExportWhat::Sessions => {}
+        }
+    }
+    // Stable, so same-day entries keep their file order
+    rows.sort_by(|a, b| a[2].as_str().cmp(&b[2].as_str()));
+    Table { columns, rows }
+}
+
+/// One row per indexed session, in store order.
+fn sessions_table(sessions: &[(crate::daemon::indexer::SessionMeta, bool)]) -> Table {
+    let rows = sessions.iter().map(|(s, summarized)| {
+        let project = s.project_path.trim_end_matches('/').rsplit('/').next().unwrap_or(&s.project_path);
+        let duration = s.first_message_at.as_deref()
+            .zip(s.last_message_at.as_deref())
+            .and_then(|(a, b)| {
+                let a = chrono::DateTime::parse_from_rfc3339(a).ok()?;
+                let b = chrono::DateTime::parse_from_rfc3339(b).ok()?;
+                Some((b - a).num_minutes().max(0))
+            });
+        vec![
+            Value::from(s.session_id.clone()),
+            s.domain.clone().map_or(Value::Null, Value::from),
+            Value::from(project),
+            Value::from(s.project_path.clone()),
+            s.first_message_at.clone().map_or(Value::Null, Value::from),
+            s.last_message_at.clone().map_or(Value::Null, Value::from),
+            duration.map_or(Value::Null, Value::from),
+            Value::from(s.message_count),
+            Value::from(s.user_message_count),
+            Value::from(s.assistant_message_count),
+            Value::from(s.file_size),
+            Value::from(*summarized),
+        ]
+    }).collect();
+    Table { columns: SESSION_COLUMNS, rows }
+}
+
+/// Render as a JSON array of objects, or as CSV with a header row.
+pub fn render(table: &Table, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Json => {
+            let objects: Vec<serde_json::Map<String, Value>> = table.rows.iter()
+                .map(|row| table.columns.iter().map(|c| c.to_string()).zip(row.iter().cloned()).collect())
+                .collect();
+            let mut out = serde_json::to_string_pretty(&objects).unwrap_or_default();
+            out.push('\n');
+            out
+        }
+        ExportFormat::Csv => {
+            let mut out = table.columns.join(",");
+            out.push('\n');
+            for row in &table.rows {
+                let cells: Vec<String> = row.iter().map(csv_cell).collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            }
+            out
+        }
+    }
+}
+
+/// RFC 4180 quoting: wrap in quotes when the cell holds a comma, quote, or line break.
+fn csv_cell(value: &Value) -> String {
+    let raw = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if raw.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", raw.replace('"', "\"\""))
+    } else {
+        raw
+    }
+}
+
+/// `(domain, project, dir)` for every second-level folder, in path order.
+pub(crate) fn project_dirs(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy) -> Vec<(String, String, std::path::PathBuf)> {
+    let visible = |p: &Path| {
+        let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        !name.starts_with('.') && !exclude.iter().any(|e| e == name)
+    };
+    // Links are entered per `symlinks`, and never into the vault, so no project is listed twice
+    let list = |dir: &Path| -> Vec<std::path::PathBuf> {
+        crate::vault::symlinks::subdirs(vault_path, dir, symlinks)
+            .into_iter()
+            .filter(|p| visible(p))
+            .collect()
+    };
+
+    let mut out = Vec::new();
+    for domain_dir in list(vault_path) {
+        let domain = domain_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        for project_dir in list(&domain_dir) {
+            let project = project_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            out.push((domain.clone(), project, project_dir));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn history_flattens_with_consistent_columns() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("history.jsonl"), concat!(
+            "{\"_schema\": \"history\"}\n",
+            "{\"date\":\"2026-03-02\",\"title\":\"Ship, finally\",\"status\":\"active\",\"body\":\"Said \\\"done\\\"\\nfor real\",\"source\":\"code\"}\n",
+            "{\"date\":\"2026-03-01\",\"title\":\"Start\",\"status\":\"active\",\"focus\":\"auth\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\"}\n",
+        )).unwrap();
+
+        let table = vault_table(tmp.path(), &[], SymlinkPolicy::Follow, ExportWhat::History);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0][2], "2026-03-01");
+        assert!(table.rows.iter().all(|r| r.len() == table.columns.len()));
+
+        let csv = render(&table, ExportFormat::Csv);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), "domain,project,date,title,status,focus,next_action,commit,body,source");
+        assert_eq!(lines.next().unwrap(), "work,api,2026-03-01,Start,active,auth,,,,");
+        assert!(csv.contains("\"Ship, finally\",active,,,,\"Said \"\"done\"\"\nfor real\",code\n"));
+
+        let json: Vec<serde_json::Value> = serde_json::from_str(&render(&table, ExportFormat::Json)).unwrap();
+        assert_eq!(json[1]["title"], "Ship, finally");
+        assert_eq!(json[1]["focus"], "");
+    }
+
+    #[test]
+    fn decisions_and_sessions_export() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("decisions.md"), "# api Decisions\n\n## 2026-02-20 — Use OAuth\n\nSimpler.\n\n---\n").unwrap();
+        let table = vault_table(tmp.path(), &[], SymlinkPolicy::Follow, ExportWhat::Decisions);
+        assert_eq!(table.rows, vec![vec![
+            Value::from("work"), Value::from("api"), Value::from("2026-02-20"), Value::from("Use OAuth"), Value::from("Simpler."),
+        ]]);
+
+        let meta = crate::daemon::indexer::SessionMeta {
+            session_id: "abc".to_string(),
+            project_dir: "-Users-test-Code-api".to_string(),
+            project_path: "/Users/test/Code/api".to_string(),
+            domain: None,
+            message_count: 12,
+            user_message_count: 5,
+            assistant_message_count: 7,
+            first_message_at: Some("2026-03-01T10:00:00Z".to_string()),
+            last_message_at: Some("2026-03-01T11:30:00Z".to_string()),
+            file_size: 4096,
+            file_hash: "4096:abc".to_string(),
+        };
+        let csv = render(&sessions_table(&[(meta, true)]), ExportFormat::Csv);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "abc,,api,/Users/test/Code/api,2026-03-01T10:00:00Z,2026-03-01T11:30:00Z,90,12,5,7,4096,true",
+        );
+    }
+
+    #[test]
+    fn project_dirs_follow_the_symlink_policy() {
+        let tmp = tempdir().unwrap();
+        let (vault, repo) = (tmp.path().join("vault"), tmp.path().join("repo/docs"));
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::create_dir_all(&repo).unwrap();
+        std::os::unix::fs::symlink(&repo, vault.join("work/docs")).unwrap();
+        std::os::unix::fs::symlink(vault.join("work/api"), vault.join("work/api-alias")).unwrap();
+
+        let names = |policy| project_dirs(&vault, &[], policy).into_iter().map(|(_, p, _)| p).collect::<Vec<_>>();
+        assert_eq!(names(SymlinkPolicy::Follow), ["api", "docs"]);
+        assert_eq!(names(SymlinkPolicy::Skip), ["api"]);
+    }
+}
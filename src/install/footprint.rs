@@ -0,0 +1,352 @@
+use crate::install::output::Output;
+use crate::vault::symlinks::SymlinkPolicy;
+use crate::vault::types::Status;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A hand-written domain current_state.md past this many bytes gets a trim suggestion:
+/// inject prints it whole at every session start in that domain.
+const HAND_WRITTEN_LIMIT: usize = 4096;
+
+/// A project focus past this many characters gets a trim suggestion.
+const FOCUS_LIMIT: usize = 280;
+
+/// What wardwell puts in front of the model, and what it costs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Footprint {
+    /// Wardwell blocks in CLAUDE.md files, loaded at the start of every session.
+    pub claude_md: Vec<Artifact>,
+    /// `wardwell inject` output per domain, printed at session start in that domain.
+    pub inject: Vec<Artifact>,
+    pub trims: Vec<Trim>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Artifact {
+    /// A file path for CLAUDE.md blocks, the domain name for inject output.
+    pub name: String,
+    pub bytes: usize,
+    pub tokens: usize,
+    /// Each project's share of a domain's inject output.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<Share>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Share {
+    pub project: String,
+    pub bytes: usize,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trim {
+    /// The file to change.
+    pub path: String,
+    pub suggestion: String,
+    /// Estimated tokens saved per session once trimmed.
+    pub saves_tokens: usize,
+    /// Whether `--optimize` applies it; the rest need a person's judgment.
+    pub automatic: bool,
+    pub applied: bool,
+    #[serde(skip)]
+    fix: Option<Fix>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fix {
+    /// Rewrite the wardwell block with what init injects today.
+    Refresh,
+    /// Remove the wardwell block; the global CLAUDE.md already carries it.
+    Remove,
+}
+
+/// Estimated tokens for `bytes` of text, at the summarizer's estimate of four per token.
+pub fn tokens(bytes: usize) -> usize {
+    bytes.div_ceil(crate::inject::context::BYTES_PER_TOKEN)
+}
+
+/// Measure the wardwell blocks in `claude_mds` (the first being the global CLAUDE.md) and
+/// the inject output of each domain under `vault_root`, and list what could be trimmed.
+pub fn collect(vault_root: &Path, exclude: &[String], symlinks: SymlinkPolicy, statuses: &BTreeMap<String, Status>, claude_mds: &[PathBuf], max_tokens: usize) -> Footprint {
+    let current = crate::install::init::build_injection_content(&[]);
+    let global = claude_mds.first().and_then(|p| crate::inject::injected(p));
+
+    let mut claude_md = Vec::new();
+    let mut trims = Vec::new();
+    for (i, path) in claude_mds.iter().enumerate() {
+        let Some(block) = crate::inject::injected(path) else {
+            continue;
+        };
+        claude_md.push(artifact(path.display().to_string(), &block, Vec::new()));
+        if i > 0 && global.is_some() {
+            trims.push(Trim {
+                path: path.display().to_string(),
+                suggestion: "repeats the wardwell block the global CLAUDE.md already loads in every session; remove it here".to_string(),
+                saves_tokens: tokens(block.len()),
+                automatic: true,
+                applied: false,
+                fix: Some(Fix::Remove),
+            });
+        } else if block != current {
+            trims.push(Trim {
+                path: path.display().to_string(),
+                suggestion: "wardwell block is from an older version; rewrite it with the current one".to_string(),
+                saves_tokens: tokens(block.len().saturating_sub(current.len())),
+                automatic: true,
+                applied: false,
+                fix: Some(Fix::Refresh),
+            });
+        }
+    }
+
+    let mut inject = Vec::new();
+    let domains: Vec<PathBuf> = crate::vault::symlinks::subdirs(vault_root, vault_root, symlinks).into_iter()
+        .filter(|p| {
+            let name = p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            !name.starts_with('.') && !exclude.contains(&name)
+        })
+        .collect();
+    for domain_dir in &domains {
+        let output = crate::inject::context::domain_context(domain_dir, max_tokens, statuses);
+        if output.trim().is_empty() {
+            continue;
+        }
+        let domain = domain_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let state = domain_dir.join("current_state.md");
+        let hand_written = std::fs::read_to_string(&state).ok().filter(|c| !crate::daemon::rollup::is_generated(c) && *c == output);
+        if hand_written.is_some() && output.len() > HAND_WRITTEN_LIMIT {
+            trims.push(Trim {
+                path: state.display().to_string(),
+                suggestion: format!(
+                    "hand-written and printed whole at session start; move detail into INDEX.md, or delete it so the generated rollup takes its place (over {HAND_WRITTEN_LIMIT} bytes)"
+                ),
+                saves_tokens: tokens(output.len() - HAND_WRITTEN_LIMIT),
+                automatic: false,
+                applied: false,
+                fix: None,
+            });
+        }
+
+        let mut projects = Vec::new();
+        for project_dir in project_dirs(vault_root, domain_dir, symlinks) {
+            let project = project_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let share = match hand_written {
+                Some(_) => 0,
+                None => match rollup_share(&output, &project) {
+                    0 => listed_share(&output, &format!("{domain}/{project}")),
+                    share => share,
+                },
+            };
+            if share == 0 {
+                continue;
+            }
+            projects.push(Share { project: format!("{domain}/{project}"), bytes: share, tokens: tokens(share) });
+            trims.extend(project_trims(&project_dir, &format!("{domain}/{project}"), share, statuses));
+        }
+        inject.push(artifact(domain, &output, projects));
+    }
+
+    Footprint { claude_md, inject, trims }
+}
+
+/// Apply the automatic trims. Returns how many were applied.
+pub fn optimize(footprint: &mut Footprint) -> std::io::Result<usize> {
+    let current = crate::install::init::build_injection_content(&[]);
+    let mut applied = 0;
+    for trim in &mut footprint.trims {
+        let path = Path::new(&trim.path);
+        match trim.fix {
+            Some(Fix::Refresh) => crate::inject::inject(path, &current).map_err(std::io::Error::other)?,
+            Some(Fix::Remove) => {
+                crate::install::uninstall::remove_markers(path)?;
+            }
+            None => continue,
+        }
+        trim.applied = true;
+        applied += 1;
+    }
+    Ok(applied)
+}
+
+pub fn run(optimize_now: bool, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    let config = crate::config::loader::load(None)?;
+    let mut claude_mds = vec![dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".claude/CLAUDE.md")];
+    let domain_paths: Vec<String> = config.registry.all().iter()
+        .flat_map(|d| d.paths.iter().map(|p| p.as_str().to_string()))
+        .collect();
+    for path in crate::install::detect::find_claude_md_files(&domain_paths) {
+        if !claude_mds.contains(&path) {
+            claude_mds.push(path);
+        }
+    }
+
+    let mut footprint = collect(&config.vault_path, &config.exclude, config.symlinks, &config.statuses, &claude_mds, config.inject_max_tokens);
+    if optimize_now {
+        optimize(&mut footprint)?;
+    }
+    for line in render(&footprint, optimize_now) {
+        out.line(line);
+    }
+    out.result(serde_json::to_value(&footprint)?);
+    Ok(())
+}
+
+/// The footprint as lines for people.
+fn render(footprint: &Footprint, optimized: bool) -> Vec<String> {
+    let row = |indent: usize, name: &str, bytes: usize, tokens: usize| {
+        format!("{:indent$}{name:<width$} {bytes:>7} B  ~{tokens:>6} tokens", "", width = 50 - indent)
+    };
+    let mut lines = vec!["wardwell footprint (tokens estimated at 4 bytes each)".to_string(), String::new()];
+
+    lines.push("Every session — CLAUDE.md wardwell blocks".to_string());
+    if footprint.claude_md.is_empty() {
+        lines.push("  (none)".to_string());
+    }
+    for a in &footprint.claude_md {
+        lines.push(row(2, &a.name, a.bytes, a.tokens));
+    }
+
+    lines.push(String::new());
+    lines.push("Session start in a domain — wardwell inject".to_string());
+    if footprint.inject.is_empty() {
+        lines.push("  (nothing injected)".to_string());
+    }
+    for a in &footprint.inject {
+        lines.push(row(2, &a.name, a.bytes, a.tokens));
+        for p in &a.projects {
+            lines.push(row(4, &p.project, p.bytes, p.tokens));
+        }
+    }
+
+    lines.push(String::new());
+    if footprint.trims.is_empty() {
+        lines.push("No trims to suggest.".to_string());
+        return lines;
+    }
+    lines.push("Trims".to_string());
+    for t in &footprint.trims {
+        let mark = if t.applied { " [applied]" } else if t.automatic { " [--optimize]" } else { "" };
+        lines.push(format!("  - {}: {} (saves ~{} tokens){mark}", t.path, t.suggestion, t.saves_tokens));
+    }
+    let pending = footprint.trims.iter().filter(|t| t.automatic && !t.applied).count();
+    if !optimized && pending > 0 {
+        lines.push(String::new());
+        lines.push(format!("Run `wardwell footprint --optimize` to apply the {pending} marked [--optimize]."));
+    }
+    lines
+}
+
+fn artifact(name: String, text: &str, projects: Vec<Share>) -> Artifact {
+    Artifact { name, bytes: text.len(), tokens: tokens(text.len()), projects }
+}
+
+fn project_dirs(vault_root: &Path, domain_dir: &Path, symlinks: SymlinkPolicy) -> Vec<PathBuf> {
+    crate::vault::symlinks::subdirs(vault_root, domain_dir, symlinks).into_iter()
+        .filter(|p| p.join("current_state.md").exists())
+        .collect()
+}
+
+/// Bytes of a generated rollup's lines about `project`: its project line, blockers, and
+/// next action.
+fn rollup_share(rollup: &str, project: &str) -> usize {
+    let listed = format!("- **{project}** (");
+    let keyed = format!("- {project}: ");
+    rollup.lines()
+        .filter(|l| l.starts_with(&listed) || l.starts_with(&keyed))
+        .map(|l| l.len() + 1)
+        .sum()
+}
+
+/// Bytes of a project's entry in the listing inject prints when there's no rollup to print.
+fn listed_share(listing: &str, name: &str) -> usize {
+    let head = format!("**{name}** (");
+    let mut lines = listing.lines().skip_while(|l| !l.starts_with(&head));
+    let Some(first) = lines.next() else {
+        return 0;
+    };
+    first.len() + 1 + lines.take_while(|l| l.starts_with("  Next: ")).map(|l| l.len() + 1).sum::<usize>()
+}
+
+/// Suggestions for one project's entry: a focus too long for a one-line summary, or finished
+/// work still listed.
+fn project_trims(project_dir: &Path, name: &str, share: usize, statuses: &BTreeMap<String, Status>) -> Vec<Trim> {
+    let state = project_dir.join("current_state.md");
+    let Ok(vf) = crate::vault::reader::read_file_with(&state, statuses) else {
+        return Vec::new();
+    };
+    let path = state.display().to_string();
+    let mut trims = Vec::new();
+    if let Some(status) = vf.frontmatter.status
+        && !status.is_open()
+        && status != Status::Paused
+    {
+        trims.push(Trim {
+            path: path.clone(),
+            suggestion: format!("{name} is {status} but still listed at session start; archive it (wardwell_write action: archive)"),
+            saves_tokens: tokens(share),
+            automatic: false,
+            applied: false,
+            fix: None,
+        });
+    }
+    let focus = crate::inject::context::extract_section_simple(&vf.body, "Focus");
+    let chars = focus.chars().count();
+    if chars > FOCUS_LIMIT {
+        trims.push(Trim {
+            path,
+            suggestion: format!("{name}'s focus is {chars} characters; cut it to a line and keep the detail in INDEX.md"),
+            saves_tokens: tokens(focus.len().saturating_sub(FOCUS_LIMIT)),
+            automatic: false,
+            applied: false,
+            fix: None,
+        });
+    }
+    trims
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_blocks_and_inject_and_applies_automatic_trims() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        let state = |status: &str, focus: &str| format!("---\ntype: project\nstatus: {status}\n---\n## Focus\n{focus}\n\n## Next Action\nShip it\n");
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::create_dir_all(vault.join("work/old")).unwrap();
+        std::fs::write(vault.join("work/api/current_state.md"), state("active", &"word ".repeat(80))).unwrap();
+        std::fs::write(vault.join("work/old/current_state.md"), state("completed", "Done")).unwrap();
+
+        let global = tmp.path().join("global/CLAUDE.md");
+        let repo = tmp.path().join("repo/CLAUDE.md");
+        std::fs::create_dir_all(global.parent().unwrap()).unwrap();
+        std::fs::create_dir_all(repo.parent().unwrap()).unwrap();
+        crate::inject::inject(&global, "an older, shorter block").unwrap();
+        std::fs::write(&repo, "# Repo notes\n").unwrap();
+        crate::inject::inject(&repo, &crate::install::init::build_injection_content(&[])).unwrap();
+
+        let mut footprint = collect(&vault, &[], SymlinkPolicy::Follow, &BTreeMap::new(), &[global.clone(), repo.clone()], 0);
+        assert_eq!(footprint.claude_md.len(), 2);
+        let work = &footprint.inject[0];
+        assert_eq!(work.name, "work");
+        let shares: Vec<_> = work.projects.iter().map(|p| p.project.as_str()).collect();
+        assert_eq!(shares, ["work/api", "work/old"]);
+        assert_eq!(work.projects.iter().map(|p| p.bytes).sum::<usize>(), work.bytes);
+
+        let suggestions: Vec<_> = footprint.trims.iter().map(|t| (t.automatic, t.fix)).collect();
+        assert_eq!(suggestions, [(true, Some(Fix::Refresh)), (true, Some(Fix::Remove)), (false, None), (false, None)]);
+        assert!(footprint.trims[2].suggestion.contains("focus is 399 characters"), "{:?}", footprint.trims[2]);
+        assert!(footprint.trims[3].suggestion.contains("work/old is completed"));
+
+        assert_eq!(optimize(&mut footprint).unwrap(), 2);
+        assert_eq!(crate::inject::injected(&global), Some(crate::install::init::build_injection_content(&[])));
+        assert_eq!(std::fs::read_to_string(&repo).unwrap(), "# Repo notes\n");
+        let after = collect(&vault, &[], SymlinkPolicy::Follow, &BTreeMap::new(), &[global, repo], 0);
+        assert!(after.trims.iter().all(|t| !t.automatic));
+        assert!(render(&footprint, true).join("\n").contains("[applied]"));
+    }
+}
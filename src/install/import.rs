@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Domain suggested for flat folders that share a prefix with nothing else.
+pub const DEFAULT_DOMAIN: &str = "personal";
+
+/// Top-level folders that are never treated as flat projects.
+const RESERVED_DIRS: &[&str] = &["domains", "archive"];
+
+/// Where one flat folder should go.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub folder: String,
+    /// None leaves the folder where it is.
+    pub domain: Option<String>,
+    pub project: String,
+}
+
+/// One filesystem change in an import plan. Paths are vault-relative.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportStep {
+    CreateDomain(String),
+    /// `domains/<name>.md`, only when the vault already registers domains explicitly.
+    WriteDomainFile(String),
+    Move { from: String, to: String },
+    Seed { domain: String, project: String, file: &'static str },
+    /// Target already exists; the folder is left in place.
+    Conflict { from: String, to: String },
+}
+
+impl std::fmt::Display for ImportStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CreateDomain(d) => write!(f, "CREATE  {d}/"),
+            Self::WriteDomainFile(d) => write!(f, "WRITE   domains/{d}.md"),
+            Self::Move { from, to } => write!(f, "MOVE    {from}/ → {to}/"),
+            Self::Seed { domain, project, file } => write!(f, "WRITE   {domain}/{project}/{file}"),
+            Self::Conflict { from, to } => write!(f, "SKIP    {from}/ → {to}/ (already exists)"),
+        }
+    }
+}
+
+/// Top-level folders that look like projects rather than domains: they hold markdown
+/// directly and none of their subfolders is a project (has INDEX.md or current_state.md).
+/// Registered domains never count: those with a `domains/<name>.md` file and those in
+/// `registered`, the `domains:` of config.yml.
+pub fn detect_flat_projects(vault_path: &Path, registered: &[String]) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(vault_path) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<String> = entries.flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.') && !RESERVED_DIRS.contains(&name.as_str()))
+        .filter(|name| !registered.contains(name) && !vault_path.join("domains").join(format!("{name}.md")).exists())
+        .filter(|name| {
+            let dir = vault_path.join(name);
+            has_markdown(&dir) && !has_project_subdir(&dir)
+        })
+        .collect();
+    folders.sort();
+    folders
+}
+
+/// Group folders by shared prefix (`work-api`, `work-billing` → `work/api`, `work/billing`).
+/// A prefix counts if two or more folders share it, or it names an existing domain or
+/// another flat folder.
+/// Everything else goes to `DEFAULT_DOMAIN` under its own name.
+pub fn propose(vault_path: &Path, folders: &[String]) -> Vec<Assignment> {
+    let mut by_prefix: BTreeMap<String, usize> = BTreeMap::new();
+    for folder in folders {
+        if let Some((prefix, _)) = split_prefix(folder) {
+            *by_prefix.entry(prefix).or_default() += 1;
+        }
+    }
+
+    folders.iter().map(|folder| {
+        let grouped = split_prefix(folder).filter(|(prefix, _)| {
+            by_prefix.get(prefix).is_some_and(|n| *n >= 2)
+                || folders.contains(prefix)
+                || has_project_subdir(&vault_path.join(prefix))
+        });
+        match grouped {
+            Some((prefix, rest)) => Assignment { folder: folder.clone(), domain: Some(prefix), project: rest },
+            None => Assignment {
+                folder: folder.clone(),
+                domain: Some(DEFAULT_DOMAIN.to_string()),
+                project: crate::vault::project::slugify(folder),
+            },
+        }
+    }).collect()
+}
+
+/// Turn assignments into the ordered list of changes `apply` would make.
+pub fn plan(vault_path: &Path, assignments: &[Assignment]) -> Vec<ImportStep> {
+    let explicit_domains = vault_path.join("domains").is_dir();
+    let mut steps = Vec::new();
+    let mut new_domains: Vec<&str> = Vec::new();
+    let target_domains: Vec<&str> = assignments.iter().filter_map(|a| a.domain.as_deref()).collect();
+
+    for a in assignments {
+        let Some(ref domain) = a.domain else {
+            continue;
+        };
+        // A flat folder named like a target domain becomes that domain instead of moving
+        if target_domains.contains(&a.folder.as_str()) {
+            continue;
+        }
+        if !vault_path.join(domain).is_dir() && !new_domains.contains(&domain.as_str()) {
+            new_domains.push(domain);
+            steps.push(ImportStep::CreateDomain(domain.clone()));
+            if explicit_domains && !vault_path.join("domains").join(format!("{domain}.md")).exists() {
+                steps.push(ImportStep::WriteDomainFile(domain.clone()));
+            }
+        }
+
+        let to = format!("{domain}/{}", a.project);
+        if vault_path.join(&to).exists() || steps.iter().any(|s| matches!(s, ImportStep::Move { to: t, .. } if *t == to)) {
+            steps.push(ImportStep::Conflict { from: a.folder.clone(), to });
+            continue;
+        }
+        steps.push(ImportStep::Move { from: a.folder.clone(), to });
+        for file in ["INDEX.md", "current_state.md"] {
+            if !vault_path.join(&a.folder).join(file).exists() {
+                steps.push(ImportStep::Seed { domain: domain.clone(), project: a.project.clone(), file });
+            }
+        }
+    }
+    steps
+}
+
+/// Carry out a plan. Returns the number of folders moved.
+pub fn apply(vault_path: &Path, steps: &[ImportStep]) -> Result<usize, std::io::Error> {
+    let mut moved = 0;
+    for step in steps {
+        match step {
+            ImportStep::CreateDomain(d) => std::fs::create_dir_all(vault_path.join(d))?,
+            ImportStep::WriteDomainFile(d) => std::fs::write(
+                vault_path.join("domains").join(format!("{d}.md")),
+                format!("---\ntype: domain\ndomain: {d}\nconfidence: confirmed\nstatus: active\n---\n\n## Paths\n"),
+            )?,
+            ImportStep::Move { from, to } => {
+                std::fs::rename(vault_path.join(from), vault_path.join(to))?;
+                moved += 1;
+            }
+            ImportStep::Seed { domain, project, .. } => {
+                // Writes both missing files at once; later Seed steps for the same project are no-ops
+                crate::vault::seed::write_project_seed(&vault_path.join(domain).join(project), domain, project)?;
+            }
+            ImportStep::Conflict { .. } => {}
+        }
+    }
+    Ok(moved)
+}
+
+fn split_prefix(folder: &str) -> Option<(String, String)> {
+    let (prefix, rest) = folder.split_once(['-', '_', ' ', '.'])?;
+    let prefix = crate::vault::project::slugify(prefix);
+    let rest = crate::vault::project::slugify(rest);
+    (!prefix.is_empty() && !rest.is_empty()).then_some((prefix, rest))
+}
+
+fn has_markdown(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|e| e.path().extension().and_then(|x| x.to_str()) == Some("md"))
+    })
+}
+
+fn has_project_subdir(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|e| {
+            let p = e.path();
+            p.is_dir() && (p.join("INDEX.md").exists() || p.join("current_state.md").exists())
+        })
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn note(vault: &Path, rel: &str) {
+        let path = vault.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "# note\n").unwrap();
+    }
+
+    #[test]
+    fn detects_flat_folders_and_groups_by_prefix() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "work-api/notes.md");
+        note(tmp.path(), "work-billing/todo.md");
+        note(tmp.path(), "Recipes/pasta.md");
+        note(tmp.path(), "side-thing/idea.md");
+        note(tmp.path(), "lab/existing/current_state.md");
+        std::fs::create_dir_all(tmp.path().join("empty")).unwrap();
+
+        let folders = detect_flat_projects(tmp.path(), &[]);
+        assert_eq!(folders, vec!["Recipes", "side-thing", "work-api", "work-billing"]);
+
+        let proposal = propose(tmp.path(), &folders);
+        let targets: Vec<(String, String)> = proposal.iter()
+            .map(|a| (a.domain.clone().unwrap(), a.project.clone()))
+            .collect();
+        assert_eq!(targets, vec![
+            ("personal".to_string(), "recipes".to_string()),
+            ("personal".to_string(), "side-thing".to_string()),
+            ("work".to_string(), "api".to_string()),
+            ("work".to_string(), "billing".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn plan_and_apply_move_and_seed() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "work-api/notes.md");
+        note(tmp.path(), "work-api/INDEX.md");
+        note(tmp.path(), "blog/post.md");
+        note(tmp.path(), "personal/blog/current_state.md");
+
+        let assignments = vec![
+            Assignment { folder: "work-api".to_string(), domain: Some("work".to_string()), project: "api".to_string() },
+            Assignment { folder: "blog".to_string(), domain: Some("personal".to_string()), project: "blog".to_string() },
+        ];
+        let steps = plan(tmp.path(), &assignments);
+        assert_eq!(steps, vec![
+            ImportStep::CreateDomain("work".to_string()),
+            ImportStep::Move { from: "work-api".to_string(), to: "work/api".to_string() },
+            ImportStep::Seed { domain: "work".to_string(), project: "api".to_string(), file: "current_state.md" },
+            ImportStep::Conflict { from: "blog".to_string(), to: "personal/blog".to_string() },
+        ]);
+        assert!(tmp.path().join("work-api").exists(), "planning changes nothing");
+
+        assert_eq!(apply(tmp.path(), &steps).unwrap(), 1);
+        assert!(tmp.path().join("work/api/notes.md").exists());
+        assert_eq!(std::fs::read_to_string(tmp.path().join("work/api/INDEX.md")).unwrap(), "# note\n");
+        assert!(tmp.path().join("work/api/current_state.md").exists());
+        assert!(tmp.path().join("blog/post.md").exists());
+    }
+
+    #[test]
+    fn folder_named_like_a_domain_stays_put() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "work/loose-notes.md");
+        note(tmp.path(), "work-api/notes.md");
+        let folders = detect_flat_projects(tmp.path(), &[]);
+        let steps = plan(tmp.path(), &propose(tmp.path(), &folders));
+        assert_eq!(steps[0], ImportStep::Move { from: "work-api".to_string(), to: "work/api".to_string() });
+        assert!(!steps.iter().any(|s| matches!(s, ImportStep::Move { from, .. } if from == "work")));
+
+        apply(tmp.path(), &steps).unwrap();
+        assert!(tmp.path().join("work/loose-notes.md").exists());
+        assert!(tmp.path().join("work/api/notes.md").exists());
+    }
+
+    #[test]
+    fn plan_writes_domain_file_when_domains_are_explicit() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "domains/work.md");
+        note(tmp.path(), "taxes/2025.md");
+        let assignments = vec![
+            Assignment { folder: "taxes".to_string(), domain: Some("personal".to_string()), project: "taxes".to_string() },
+        ];
+        let steps = plan(tmp.path(), &assignments);
+        assert!(steps.contains(&ImportStep::WriteDomainFile("personal".to_string())));
+        assert!(!detect_flat_projects(tmp.path(), &[]).contains(&"domains".to_string()));
+    }
+
+    #[test]
+    fn registered_domains_are_not_flat_projects() {
+        let tmp = tempdir().unwrap();
+        note(tmp.path(), "domains/work.md");
+        note(tmp.path(), "work/notes.md");
+        note(tmp.path(), "clients/acme.md");
+        note(tmp.path(), "recipes/pasta.md");
+
+        assert_eq!(detect_flat_projects(tmp.path(), &[]), vec!["clients", "recipes"]);
+        assert_eq!(detect_flat_projects(tmp.path(), &["clients".to_string()]), vec!["recipes"]);
+    }
+}
@@ -19,6 +19,14 @@ fn prompt_pause(label: &str) -> bool {
     !input.eq_ignore_ascii_case("s")
 }
 
+/// Ask a yes/no question defaulting to yes.
+fn prompt_yes(question: &str) -> bool {
+    print!("  {question} [Y/n] ");
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+    let input = prompt_line();
+    !input.eq_ignore_ascii_case("n") && !input.eq_ignore_ascii_case("no")
+}
+
 /// Detect vault path interactively. Returns validated PathBuf.
 fn detect_vault_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
     // Check if config already exists with a vault path
@@ -150,28 +158,162 @@ fn count_files_recursive(dir: &Path) -> usize {
     count
 }
 
-/// Print preview of all mutations, return true if user confirms.
-fn preview_and_confirm(vault_path: &Path, config_path: &Path, binary_path: &Path) -> bool {
-    println!("\n  wardwell will perform the following:");
-    println!();
+/// Whether an init step's work is already on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepStatus {
+    Installed,
+    /// Present, but not what this init would write (another binary path, older content).
+    Outdated,
+    Missing,
+}
+
+impl StepStatus {
+    fn label(self) -> &'static str {
+        match self {
+            StepStatus::Installed => "installed",
+            StepStatus::Outdated => "outdated",
+            StepStatus::Missing => "missing",
+        }
+    }
+}
 
+/// What init finds already set up, checked before anything is written.
+struct InstallState {
+    config: StepStatus,
+    summaries: StepStatus,
+    tools: StepStatus,
+    mcp_code: StepStatus,
+    mcp_desktop: StepStatus,
+    hook: StepStatus,
+    claude_md: StepStatus,
+    manifest: StepStatus,
+    /// None when the vault isn't an Obsidian vault.
+    obsidian: Option<StepStatus>,
+    index: StepStatus,
+}
+
+impl InstallState {
+    fn check(vault_path: &Path, config_path: &Path, binary_path: &Path) -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let mcp_paths = McpConfigPaths::detect();
+        let present = |exists: bool| if exists { StepStatus::Installed } else { StepStatus::Missing };
+        Self {
+            config: config_status(config_path, vault_path),
+            summaries: present(config_dir().join("summaries").is_dir()),
+            tools: present(std::fs::read_to_string(config_path).is_ok_and(|c| c.lines().any(|l| l.starts_with("tools_enabled:")))),
+            mcp_code: mcp_status(&mcp_paths.claude_code, binary_path),
+            mcp_desktop: mcp_status(&mcp_paths.claude_desktop, binary_path),
+            hook: hook_status(&home.join(".claude/settings.json"), binary_path),
+            claude_md: claude_md_status(&home.join(".claude/CLAUDE.md"), &build_injection_content(&[])),
+            manifest: match std::fs::read_to_string(vault_path.join(crate::vault::app_files::MANIFEST)) {
+                Ok(c) if c == crate::vault::app_files::manifest_yaml() => StepStatus::Installed,
+                Ok(_) => StepStatus::Outdated,
+                Err(_) => StepStatus::Missing,
+            },
+            obsidian: vault_path.join(".obsidian").is_dir().then(|| {
+                let filters = crate::vault::app_files::obsidian_filters(vault_path);
+                match crate::vault::app_files::missing_obsidian_filters(vault_path, &filters) {
+                    0 => StepStatus::Installed,
+                    n if n < filters.len() => StepStatus::Outdated,
+                    _ => StepStatus::Missing,
+                }
+            }),
+            index: present(config_dir().join("index.db").exists()),
+        }
+    }
+
+    fn all(&self) -> Vec<StepStatus> {
+        let mut all = vec![self.config, self.summaries, self.tools, self.mcp_code, self.mcp_desktop,
+            self.hook, self.claude_md, self.manifest, self.index];
+        all.extend(self.obsidian);
+        all
+    }
+}
+
+/// Config at `config_path` pointing at `vault_path`: outdated when it points elsewhere or
+/// doesn't load.
+fn config_status(config_path: &Path, vault_path: &Path) -> StepStatus {
     if !config_path.exists() {
-        println!("    CREATE  {}", config_path.display());
-    } else {
-        println!("    UPDATE  {} (vault_path)", config_path.display());
+        return StepStatus::Missing;
     }
+    match crate::config::loader::load(Some(config_path)) {
+        Ok(c) if c.vault_path == vault_path => StepStatus::Installed,
+        _ => StepStatus::Outdated,
+    }
+}
 
-    println!("    CREATE  ~/.wardwell/summaries/");
+/// A wardwell MCP entry in `config_path`, under any key, running `binary_path`.
+fn mcp_status(config_path: &Path, binary_path: &Path) -> StepStatus {
+    match mcp_config::check_mcp_entry(config_path) {
+        mcp_config::McpEntryStatus::Configured { binary_path: found } if Path::new(&found) == binary_path => StepStatus::Installed,
+        mcp_config::McpEntryStatus::Configured { .. } => StepStatus::Outdated,
+        _ => StepStatus::Missing,
+    }
+}
 
-    let mcp_paths = McpConfigPaths::detect();
-    println!("    INJECT  MCP → {}", mcp_paths.claude_code.display());
-    println!("    INJECT  MCP → {}", mcp_paths.claude_desktop.display());
+/// Both wardwell hooks in `settings_path`, exactly as `install_hook` writes them.
+fn hook_status(settings_path: &Path, binary_path: &Path) -> StepStatus {
+    let settings: serde_json::Value = std::fs::read_to_string(settings_path).ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    let mut current = 0;
+    let mut found = 0;
+    for (event, hook) in hook_entries(binary_path) {
+        let entries = settings["hooks"][event].as_array().cloned().unwrap_or_default();
+        if let Some(entry) = entries.iter().find(|e| is_wardwell_hook(e)) {
+            found += 1;
+            if *entry == hook {
+                current += 1;
+            }
+        }
+    }
+    match (current, found) {
+        (2, _) => StepStatus::Installed,
+        (_, 0) => StepStatus::Missing,
+        _ => StepStatus::Outdated,
+    }
+}
 
+/// The wardwell block in a CLAUDE.md, compared with what init would inject.
+fn claude_md_status(path: &Path, content: &str) -> StepStatus {
+    match crate::inject::injected(path) {
+        Some(existing) if existing == content => StepStatus::Installed,
+        Some(_) => StepStatus::Outdated,
+        None => StepStatus::Missing,
+    }
+}
+
+/// Print the steps with what each finds on disk, return true if the user confirms.
+fn preview_and_confirm(vault_path: &Path, config_path: &Path, binary_path: &Path, state: &InstallState) -> bool {
+    println!("\n  wardwell will perform the following:");
+    println!();
+
+    let mcp_paths = McpConfigPaths::detect();
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-    println!("    INJECT  SessionStart hook → {}", home.join(".claude/settings.json").display());
-    println!("    INJECT  CLAUDE.md markers → {}", home.join(".claude/CLAUDE.md").display());
-    println!("    INDEX   {} → ~/.wardwell/index.db", vault_path.display());
-    println!("    BINARY  {}", binary_path.display());
+    let mut rows: Vec<(&str, String, Option<StepStatus>)> = vec![
+        (if config_path.exists() { "UPDATE" } else { "CREATE" }, format!("{} (vault_path)", config_path.display()), Some(state.config)),
+        ("CREATE", "~/.wardwell/summaries/".to_string(), Some(state.summaries)),
+        ("CHOOSE", "MCP tools (tools_enabled)".to_string(), Some(state.tools)),
+        ("INJECT", format!("MCP → {}", mcp_paths.claude_code.display()), Some(state.mcp_code)),
+        ("INJECT", format!("MCP → {}", mcp_paths.claude_desktop.display()), Some(state.mcp_desktop)),
+        ("INJECT", format!("SessionStart hook → {}", home.join(".claude/settings.json").display()), Some(state.hook)),
+        ("INJECT", format!("CLAUDE.md markers → {}", home.join(".claude/CLAUDE.md").display()), Some(state.claude_md)),
+        ("CREATE", vault_path.join(crate::vault::app_files::MANIFEST).display().to_string(), Some(state.manifest)),
+    ];
+    if let Some(status) = state.obsidian {
+        rows.push(("UPDATE", vault_path.join(".obsidian/app.json").display().to_string(), Some(status)));
+    }
+    rows.push(("INDEX", format!("{} → ~/.wardwell/index.db", vault_path.display()), Some(state.index)));
+    rows.push(("BINARY", binary_path.display().to_string(), None));
+
+    let width = rows.iter().map(|(_, target, _)| target.chars().count()).max().unwrap_or(0);
+    for (verb, target, status) in &rows {
+        match status {
+            Some(StepStatus::Installed) => println!("    {:<7} {target:<width$}  installed — skipping", "SKIP"),
+            Some(status) => println!("    {verb:<7} {target:<width$}  {}", status.label()),
+            None => println!("    {verb:<7} {target}"),
+        }
+    }
 
     print!("\n  Proceed? [Y/n] ");
     let _ = std::io::Write::flush(&mut std::io::stdout());
@@ -179,21 +321,57 @@ fn preview_and_confirm(vault_path: &Path, config_path: &Path, binary_path: &Path
     input.is_empty() || input.eq_ignore_ascii_case("y")
 }
 
+/// What to do with one step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    AlreadyInstalled,
+    Run,
+    Declined,
+}
+
+/// Installed steps are skipped without asking; `--repair` runs the rest without asking.
+fn decide(status: StepStatus, repair: bool, name: &str, question: &str) -> Decision {
+    if status == StepStatus::Installed {
+        println!("  \u{2713} {name} already installed — skipping");
+        Decision::AlreadyInstalled
+    } else if repair || prompt_pause(question) {
+        Decision::Run
+    } else {
+        Decision::Declined
+    }
+}
+
 /// Interactive init. Walks user through vault selection, previews mutations,
-/// step-by-step with pauses.
-pub fn run() -> Result<(), Box<dyn std::error::Error>> {
-    println!("wardwell init\n");
+/// step-by-step with pauses. Steps already in place are skipped. With `repair`, the vault
+/// comes from the existing config and every missing or outdated step runs without prompts.
+pub fn run(repair: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}\n", if repair { "wardwell init --repair" } else { "wardwell init" });
 
     // 1. Detect vault path
-    let vault_path = detect_vault_path()?;
     let config_path = config_dir().join("config.yml");
+    let vault_path = if repair {
+        let config = crate::config::loader::load(Some(&config_path))
+            .map_err(|e| format!("nothing to repair from — {e}. Run `wardwell init` first."))?;
+        println!("  Vault: {}", config.vault_path.display());
+        config.vault_path
+    } else {
+        detect_vault_path()?
+    };
     let binary_path = detect::find_binary_path();
+    let state = InstallState::check(&vault_path, &config_path, &binary_path);
+
+    if repair && state.all().iter().all(|s| *s == StepStatus::Installed) {
+        println!("\n  Nothing to repair — every step is installed.");
+        return Ok(());
+    }
 
     // 2. Scan and display vault contents
-    scan_and_display_vault(&vault_path);
+    if !repair {
+        scan_and_display_vault(&vault_path);
+    }
 
     // 3. Preview and confirm
-    if !preview_and_confirm(&vault_path, &config_path, &binary_path) {
+    if !preview_and_confirm(&vault_path, &config_path, &binary_path, &state) {
         println!("\n  Cancelled.");
         return Ok(());
     }
@@ -209,86 +387,144 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         std::fs::create_dir_all(dir)?;
     }
 
-    if config_path.exists() {
-        // Only update vault_path if it actually changed
-        let existing_vault = crate::config::loader::load(Some(&config_path))
-            .ok()
-            .map(|c| c.vault_path);
-        if existing_vault.as_ref() == Some(&vault_path) {
-            println!("  \u{2713} Existing config (vault_path unchanged)");
-        } else {
+    match state.config {
+        StepStatus::Installed => println!("  \u{2713} Existing config (vault_path unchanged)"),
+        StepStatus::Outdated => {
             println!("  \u{2713} Existing config found. Updating vault_path.");
             update_config_vault_path(&config_path, &vault_path)?;
         }
-    } else {
-        write_minimal_config(&config_path, &vault_path)?;
-        println!("  \u{2713} Config written: {}", config_path.display());
+        StepStatus::Missing => {
+            write_minimal_config(&config_path, &vault_path)?;
+            println!("  \u{2713} Config written: {}", config_path.display());
+        }
     }
 
-    // 5. MCP — Claude Code
-    let mcp_paths = McpConfigPaths::detect();
-    if prompt_pause("Inject MCP server into Claude Code config?") {
-        match mcp_config::inject_mcp_entry(&mcp_paths.claude_code, &binary_path) {
-            Ok(_) => println!("  \u{2713} MCP injected into {}", mcp_paths.claude_code.display()),
-            Err(e) => {
-                println!("  \u{2717} MCP inject failed: {e}");
-                skipped.push(format!("MCP Claude Code: manually add wardwell to {}", mcp_paths.claude_code.display()));
-            }
+    // 5. Tool selection
+    match decide(state.tools, repair, "Tool selection", "Choose which MCP tools to register?") {
+        Decision::Run if repair => {
+            // Record the tools the config already implies, so the step counts as done
+            let tools = crate::config::loader::load(Some(&config_path)).map(|c| c.tools_enabled).unwrap_or_default();
+            let content = std::fs::read_to_string(&config_path)?;
+            std::fs::write(&config_path, set_tools_enabled_block(&content, &tools))?;
+            println!("  \u{2713} Tool selection saved (tools_enabled in config.yml)");
         }
-    } else {
-        skipped.push(format!("MCP Claude Code: manually add wardwell to {}", mcp_paths.claude_code.display()));
+        Decision::Run => {
+            println!("  wardwell_search is read-only. wardwell_write changes vault files. wardwell_clipboard copies to your clipboard.");
+            let tools = crate::config::loader::ToolsEnabled {
+                search: prompt_yes("Enable wardwell_search?"),
+                write: prompt_yes("Enable wardwell_write?"),
+                clipboard: prompt_yes("Enable wardwell_clipboard?"),
+            };
+            let content = std::fs::read_to_string(&config_path)?;
+            std::fs::write(&config_path, set_tools_enabled_block(&content, &tools))?;
+            println!("  \u{2713} Tool selection saved (tools_enabled in config.yml)");
+        }
+        Decision::AlreadyInstalled | Decision::Declined => {}
     }
 
-    // 6. MCP — Claude Desktop
-    if prompt_pause("Inject MCP server into Claude Desktop config?") {
-        match mcp_config::inject_mcp_entry(&mcp_paths.claude_desktop, &binary_path) {
-            Ok(_) => println!("  \u{2713} MCP injected into {}", mcp_paths.claude_desktop.display()),
-            Err(e) => {
-                println!("  \u{2717} MCP inject failed: {e}");
-                skipped.push(format!("MCP Claude Desktop: manually add wardwell to {}", mcp_paths.claude_desktop.display()));
-            }
+    // 6–7. MCP — Claude Code and Claude Desktop
+    let mcp_paths = McpConfigPaths::detect();
+    for (client, path, status) in [
+        ("Claude Code", &mcp_paths.claude_code, state.mcp_code),
+        ("Claude Desktop", &mcp_paths.claude_desktop, state.mcp_desktop),
+    ] {
+        let manual = format!("MCP {client}: manually add wardwell to {}", path.display());
+        match decide(status, repair, &format!("MCP server in {client} config"), &format!("Inject MCP server into {client} config?")) {
+            Decision::Run => match mcp_config::inject_mcp_entry(path, &binary_path) {
+                Ok(_) => println!("  \u{2713} MCP injected into {}", path.display()),
+                Err(e) => {
+                    println!("  \u{2717} MCP inject failed: {e}");
+                    skipped.push(manual);
+                }
+            },
+            Decision::Declined => skipped.push(manual),
+            Decision::AlreadyInstalled => {}
         }
-    } else {
-        skipped.push(format!("MCP Claude Desktop: manually add wardwell to {}", mcp_paths.claude_desktop.display()));
     }
 
-    // 7. SessionStart hook
-    if prompt_pause("Install SessionStart hook?") {
-        match install_hook() {
+    // 8. SessionStart hook
+    let manual = "SessionStart hook: manually register wardwell inject in ~/.claude/settings.json".to_string();
+    match decide(state.hook, repair, "SessionStart hook", "Install SessionStart hook?") {
+        Decision::Run => match install_hook() {
             Ok(()) => println!("  \u{2713} SessionStart hook installed"),
             Err(e) => {
                 println!("  \u{2717} Hook install failed: {e}");
-                skipped.push("SessionStart hook: manually register wardwell inject in ~/.claude/settings.json".to_string());
+                skipped.push(manual);
             }
+        },
+        Decision::Declined => skipped.push(manual),
+        Decision::AlreadyInstalled => {}
+    }
+
+    // 9. CLAUDE.md injection
+    match decide(state.claude_md, repair, "CLAUDE.md markers", "Inject wardwell context into CLAUDE.md?") {
+        Decision::Run => {
+            inject_claude_md_pointer();
+            println!("  \u{2713} CLAUDE.md markers injected");
         }
-    } else {
-        skipped.push("SessionStart hook: manually register wardwell inject in ~/.claude/settings.json".to_string());
+        Decision::Declined => skipped.push("CLAUDE.md: manually add wardwell markers to ~/.claude/CLAUDE.md".to_string()),
+        Decision::AlreadyInstalled => {}
     }
 
-    // 8. CLAUDE.md injection
-    if prompt_pause("Inject wardwell context into CLAUDE.md?") {
-        inject_claude_md_pointer();
-        println!("  \u{2713} CLAUDE.md markers injected");
-    } else {
-        skipped.push("CLAUDE.md: manually add wardwell markers to ~/.claude/CLAUDE.md".to_string());
+    // 10. Organize a flat vault into domains (interactive, so never part of a repair)
+    if !repair
+        && !crate::install::import::detect_flat_projects(&vault_path, &config_domain_names()).is_empty()
+        && prompt_pause("Your vault has project folders at the top level. Organize them into domains?")
+        && let Err(e) = run_import(&vault_path, false)
+    {
+        println!("  \u{2717} Import failed: {e}");
+        skipped.push("Domain import: run `wardwell import` to organize top-level folders into domains".to_string());
     }
 
-    // 9. Build index (with exclude list from config)
+    // 11. Mark wardwell's own files, for other tools and for Obsidian
     if vault_path.exists() {
+        if state.manifest == StepStatus::Installed {
+            println!("  \u{2713} Manifest already installed — skipping");
+        } else {
+            match crate::vault::app_files::write_manifest(&vault_path) {
+                Ok(()) => println!("  \u{2713} Manifest written: {}", vault_path.join(crate::vault::app_files::MANIFEST).display()),
+                Err(e) => println!("  \u{2717} Manifest write failed: {e}"),
+            }
+        }
+    }
+    if let Some(status) = state.obsidian {
+        let manual = "Obsidian: add `/\\.jsonl$/` under Settings → Files and links → Excluded files".to_string();
+        match decide(status, repair, "Obsidian excluded-file filters", "Hide wardwell's machine files (JSONL logs, generated rollups) from Obsidian search?") {
+            Decision::Run => {
+                let filters = crate::vault::app_files::obsidian_filters(&vault_path);
+                match crate::vault::app_files::merge_obsidian_ignore(&vault_path, &filters) {
+                    Ok(0) => println!("  \u{2713} Obsidian already ignores wardwell's machine files"),
+                    Ok(n) => println!("  \u{2713} Added {n} excluded-file filters to .obsidian/app.json (reopen the vault in Obsidian)"),
+                    Err(e) => {
+                        println!("  \u{2717} Obsidian settings update failed: {e}");
+                        skipped.push(manual);
+                    }
+                }
+            }
+            Decision::Declined => skipped.push(manual),
+            Decision::AlreadyInstalled => {}
+        }
+    }
+
+    // 12. Build index (with exclude list from config). Builds are incremental, so a normal
+    // init refreshes an existing index; a repair only builds a missing one.
+    if vault_path.exists() && !(repair && state.index == StepStatus::Installed) {
         println!("\n  Building index...");
-        let exclude = crate::config::loader::load(Some(&config_path))
-            .map(|c| c.exclude)
+        let (exclude, symlinks, statuses) = crate::config::loader::load(Some(&config_path))
+            .map(|c| (c.exclude, c.symlinks, c.statuses))
             .unwrap_or_default();
         let index_path = config_dir().join("index.db");
         if let Ok(index) = crate::index::store::IndexStore::open(&index_path) {
-            match crate::index::builder::IndexBuilder::build_filtered(&index, &vault_path, &exclude, None) {
+            match crate::index::builder::IndexBuilder::build_filtered(&index, &vault_path, &exclude, symlinks, &statuses, None) {
                 Ok(stats) => println!("  \u{2713} Indexed {} files ({} skipped, {} errors)", stats.indexed, stats.skipped, stats.errors),
                 Err(e) => println!("  \u{2717} Index build failed: {e}"),
             }
         }
+    } else if vault_path.exists() {
+        println!("  \u{2713} Index already installed — skipping");
     }
 
-    // 10. Migrate config domains if needed
+    // 13. Migrate config domains if needed
     if let Ok(config) = crate::config::loader::load(Some(&config_path))
         && !config.registry.is_empty()
     {
@@ -303,7 +539,7 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    // 11. Summary
+    // 14. Summary
     println!("\n  Done.");
     if !skipped.is_empty() {
         println!("\n  Skipped steps (manual instructions):");
@@ -316,6 +552,125 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Domains a legacy config.yml declares under `domains:`, which import leaves in place.
+fn config_domain_names() -> Vec<String> {
+    std::fs::read_to_string(config_dir().join("config.yml")).ok()
+        .and_then(|contents| crate::config::loader::legacy_domains(&contents).ok())
+        .map(|domains| domains.iter().map(|d| d.name.as_str().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Organize a flat vault (project folders at the top level) into `domain/project/`.
+/// Shows the proposed grouping, lets the user reassign folders, previews every change,
+/// and only touches the filesystem after confirmation. `dry_run` stops after the preview.
+pub fn run_import(vault_path: &Path, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::install::import;
+
+    let folders = import::detect_flat_projects(vault_path, &config_domain_names());
+    if folders.is_empty() {
+        println!("  No flat project folders found in {}.", vault_path.display());
+        return Ok(());
+    }
+
+    let mut assignments = import::propose(vault_path, &folders);
+    println!("\n  Found {} top-level folder(s) that look like projects. Proposed domains:", folders.len());
+    print_assignments(&assignments);
+
+    if !prompt_yes("Use this grouping?") {
+        println!("  For each folder: Enter keeps the suggestion, type a domain (or domain/project), or '-' to leave it in place.");
+        for a in &mut assignments {
+            let suggested = a.domain.as_deref().map(|d| format!("{d}/{}", a.project)).unwrap_or_default();
+            print!("    {}/ [{suggested}]: ", a.folder);
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            let input = prompt_line();
+            match input.as_str() {
+                "" => {}
+                "-" => a.domain = None,
+                other => {
+                    let (domain, project) = other.split_once('/').unwrap_or((other, a.project.as_str()));
+                    a.project = crate::vault::project::slugify(project);
+                    a.domain = Some(crate::vault::project::slugify(domain));
+                }
+            }
+        }
+    }
+
+    let steps = import::plan(vault_path, &assignments);
+    if steps.is_empty() {
+        println!("  Nothing to do.");
+        return Ok(());
+    }
+    println!("\n  wardwell will perform the following in {}:\n", vault_path.display());
+    for step in &steps {
+        println!("    {step}");
+    }
+    if dry_run {
+        println!("\n  Dry run — nothing changed.");
+        return Ok(());
+    }
+    println!();
+    if !prompt_yes("Apply?") {
+        println!("  Cancelled.");
+        return Ok(());
+    }
+
+    let moved = import::apply(vault_path, &steps)?;
+    println!("  \u{2713} Moved {moved} folder(s) into domains");
+    Ok(())
+}
+
+fn print_assignments(assignments: &[crate::install::import::Assignment]) {
+    let width = assignments.iter().map(|a| a.folder.len()).max().unwrap_or(0) + 1;
+    for a in assignments {
+        let target = a.domain.as_deref().map(|d| format!("{d}/{}/", a.project)).unwrap_or_else(|| "(left in place)".to_string());
+        println!("    {:<width$} → {target}", format!("{}/", a.folder));
+    }
+}
+
+/// Project-level init. Registers wardwell in `<dir>/.mcp.json` and injects the wardwell
+/// block into `<dir>/CLAUDE.md`. Global configs, hooks, and the index are left alone.
+pub fn run_project(dir: &Path, domain: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    println!("wardwell init --project\n");
+
+    if !dir.join(".git").exists() {
+        println!("  Note: {} is not a git repository root", dir.display());
+    }
+    if !config_dir().join("config.yml").exists() {
+        println!("  Note: no ~/.wardwell/config.yml yet — run `wardwell init` once so the server can find your vault");
+    }
+
+    let binary_path = detect::find_binary_path();
+    let (mcp_result, claude_md) = register_project(dir, &binary_path, domain)?;
+    let verb = match mcp_result {
+        mcp_config::InjectResult::Created => "added to",
+        mcp_config::InjectResult::Updated => "updated in",
+    };
+    println!("  \u{2713} wardwell {verb} {}", dir.join(".mcp.json").display());
+    if let Some(d) = domain {
+        println!("    scoped to domain '{d}'");
+    }
+    println!("  \u{2713} CLAUDE.md markers injected into {}", claude_md.display());
+    println!("\n  Commit .mcp.json to share the registration, then restart Claude Code in this repo.");
+    Ok(())
+}
+
+/// Write the project `.mcp.json` entry and CLAUDE.md block. Returns the CLAUDE.md path.
+fn register_project(
+    dir: &Path,
+    binary_path: &Path,
+    domain: Option<&str>,
+) -> Result<(mcp_config::InjectResult, PathBuf), Box<dyn std::error::Error>> {
+    let mut args = vec!["serve"];
+    if let Some(d) = domain {
+        args.extend(["--domain", d]);
+    }
+    let result = mcp_config::inject_mcp_entry_with_args(&dir.join(".mcp.json"), binary_path, &args)?;
+
+    let claude_md = dir.join("CLAUDE.md");
+    crate::inject::inject(&claude_md, &build_injection_content(&[]))?;
+    Ok((result, claude_md))
+}
+
 /// Update just the vault_path in an existing config.yml.
 fn update_config_vault_path(config_path: &Path, vault_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let content = std::fs::read_to_string(config_path)?;
@@ -338,6 +693,32 @@ fn update_config_vault_path(config_path: &Path, vault_path: &Path) -> Result<(),
     Ok(())
 }
 
+/// Replace (or append) the `tools_enabled:` block in config.yml content.
+fn set_tools_enabled_block(content: &str, tools: &crate::config::loader::ToolsEnabled) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        if line.starts_with("tools_enabled:") {
+            in_block = true;
+            continue;
+        }
+        if in_block && (line.starts_with(' ') || line.starts_with('\t')) {
+            continue;
+        }
+        in_block = false;
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let mut out = lines.join("\n");
+    out.push_str(&format!(
+        "\n\ntools_enabled:\n  search: {}\n  write: {}\n  clipboard: {}\n",
+        tools.search, tools.write, tools.clipboard,
+    ));
+    out
+}
+
 /// Migrate domains from config to vault files.
 fn migrate_config_domains(config: &crate::config::loader::WardwellConfig, vault_path: &std::path::Path) {
     let domains_dir = vault_path.join("domains");
@@ -351,21 +732,7 @@ fn migrate_config_domains(config: &crate::config::loader::WardwellConfig, vault_
         let name = domain.name.as_str();
         let path = domains_dir.join(format!("{name}.md"));
 
-        let mut content = format!(
-            "---\ntype: domain\ndomain: {name}\nconfidence: confirmed\nstatus: active\n---\n\n## Paths\n"
-        );
-        for p in &domain.paths {
-            content.push_str(&format!("- {}\n", p.as_str()));
-        }
-
-        if !domain.aliases.is_empty() {
-            content.push_str("\n## Aliases\n");
-            let mut sorted_aliases: Vec<_> = domain.aliases.iter().collect();
-            sorted_aliases.sort_by_key(|(k, _)| (*k).clone());
-            for (key, value) in sorted_aliases {
-                content.push_str(&format!("- {key}: {value}\n"));
-            }
-        }
+        let content = crate::install::migrate::domain_file(domain);
 
         if let Err(e) = std::fs::write(&path, content) {
             eprintln!("wardwell: failed to write domain file {}: {e}", path.display());
@@ -408,22 +775,24 @@ exclude:
     Ok(())
 }
 
-fn build_injection_content(_domains: &[String]) -> String {
+pub(crate) fn build_injection_content(_domains: &[String]) -> String {
     "\
 ## Wardwell — Personal Knowledge System
 
 Your vault is indexed. Three tools:
 
 **wardwell_search** — Find things.
-  action: search | read | history | orchestrate | retrospective | patterns | context | resume
-  - \"search\": FTS query across vault (default). Add mode:\"semantic\" for hybrid BM25+vector search — returns chunk-level results with full text. Use limit to control depth (3=surgical, 20=broad).
+  action: search | read | history | orchestrate | retrospective | patterns | sources | context | resume | help
+  - \"search\": FTS query across vault (default). Add mode:\"hybrid\" for BM25+vector search on broad or exploratory queries, or mode:\"semantic\" for vector-only matches that share no words — both return chunk-level results with full text. Use limit to control depth (3=surgical, 20=broad).
   - \"read\": full file by path
   - \"history\": query across history.jsonl files
   - \"orchestrate\": prioritized project queue
   - \"retrospective\": what happened in a time period (requires since date)
   - \"patterns\": recurring blockers, stale threads, hot topics (defaults to 90 days)
+  - \"sources\": where project state comes from (desktop vs code vs manual), and what was planned but never executed
   - \"context\": session summary by ID (lightweight, cached)
   - \"resume\": full session handoff by ID — plan, progress, remaining work (always fresh, uses AI)
+  - \"help\": every action and parameter of the enabled tools, from their live schemas
 
 **wardwell_write** — Change things.
   action: sync | decide | append_history | lesson | append
@@ -454,11 +823,11 @@ All writes accept an optional 'source' param. Always pass it:
 - 'code' — from Claude Code
 - 'manual' — human-edited
 
+**Project inference:**
+If you don't know the project for a write, pass 'cwd' (your working directory) instead; wardwell matches it to a project through the domain's paths.
+
 **Quality bar:**
-- Snapshots: one sentence focus, concrete next action
-- History entries: what changed and why, not what was discussed
-- Decisions: the tradeoff and rejected alternatives, not the implementation
-- Lessons: root cause and prevention, not just what happened
+{quality_bar}
 
 **File roles:**
 - INDEX.md — rich project notes, architecture, context. Human-edited. Never overwritten by wardwell.
@@ -470,7 +839,7 @@ All writes accept an optional 'source' param. Always pass it:
 Other .md files in a project folder are user-managed — indexed and searchable, but never written or overwritten by wardwell.
 
 Domains are folders under the vault root. Projects are subfolders."
-        .to_string()
+        .replace("{quality_bar}", &crate::mcp::server::QUALITY_BAR.iter().map(|q| format!("- {q}")).collect::<Vec<_>>().join("\n"))
 }
 
 fn inject_claude_md_pointer() {
@@ -508,10 +877,27 @@ fn inject_claude_md_pointer() {
 
 fn install_hook() -> Result<(), Box<dyn std::error::Error>> {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-    let settings_path = home.join(".claude/settings.json");
+    install_hook_at(&home.join(".claude/settings.json"), &detect::find_binary_path())
+}
 
+/// The hook entries init registers: SessionStart runs a fast inject (no index rebuild),
+/// Stop lists the project's queued Desktop intents.
+fn hook_entries(binary_path: &Path) -> [(&'static str, serde_json::Value); 2] {
+    let hook = |command: String| serde_json::json!({
+        "hooks": [{
+            "type": "command",
+            "command": command
+        }]
+    });
+    [
+        ("SessionStart", hook(format!("{} inject \"$(pwd)\"", binary_path.display()))),
+        ("Stop", hook(format!("{} resolve", binary_path.display()))),
+    ]
+}
+
+fn install_hook_at(settings_path: &Path, binary_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let mut config: serde_json::Value = if settings_path.exists() {
-        let content = std::fs::read_to_string(&settings_path)?;
+        let content = std::fs::read_to_string(settings_path)?;
         serde_json::from_str(&content).unwrap_or_else(|_| serde_json::json!({}))
     } else {
         serde_json::json!({})
@@ -527,29 +913,9 @@ fn install_hook() -> Result<(), Box<dyn std::error::Error>> {
         .as_object_mut()
         .ok_or_else(|| std::io::Error::other("hooks is not a JSON object"))?;
 
-    let binary_path = detect::find_binary_path();
-
-    // SessionStart: fast inject (no index rebuild)
-    let inject_command = format!("{} inject \"$(pwd)\"", binary_path.display());
-    let start_hook = serde_json::json!({
-        "hooks": [{
-            "type": "command",
-            "command": inject_command
-        }]
-    });
-
-    // Install SessionStart hook
-    install_hook_entry(hooks_obj, "SessionStart", &start_hook)?;
-
-    // Stop: resolve session against last Desktop intent
-    let resolve_command = format!("{} resolve", binary_path.display());
-    let stop_hook = serde_json::json!({
-        "hooks": [{
-            "type": "command",
-            "command": resolve_command
-        }]
-    });
-    install_hook_entry(hooks_obj, "Stop", &stop_hook)?;
+    for (event, hook) in hook_entries(binary_path) {
+        install_hook_entry(hooks_obj, event, &hook)?;
+    }
 
     // Remove SessionEnd hook if present
     hooks_obj.remove("SessionEnd");
@@ -559,7 +925,7 @@ fn install_hook() -> Result<(), Box<dyn std::error::Error>> {
     }
     let json = serde_json::to_string_pretty(&config)
         .map_err(|e| std::io::Error::other(e.to_string()))?;
-    std::fs::write(&settings_path, json)?;
+    std::fs::write(settings_path, json)?;
 
     Ok(())
 }
@@ -667,5 +1033,78 @@ mod tests {
         assert!(content.contains("wardwell_search"), "missing wardwell_search");
         assert!(content.contains("wardwell_write"), "missing wardwell_write");
         assert!(content.contains("wardwell_clipboard"), "missing wardwell_clipboard");
+        assert!(content.contains("**Quality bar:**\n- Snapshots: one sentence focus"));
+    }
+
+    #[test]
+    fn set_tools_enabled_block_appends() {
+        let tools = crate::config::loader::ToolsEnabled { search: true, write: true, clipboard: false };
+        let out = set_tools_enabled_block("vault_path: /tmp/v\n\nexclude:\n  - .git\n", &tools);
+        assert!(out.starts_with("vault_path: /tmp/v\n\nexclude:\n  - .git\n\ntools_enabled:\n"));
+        assert!(out.contains("  clipboard: false\n"));
+    }
+
+    #[test]
+    fn set_tools_enabled_block_replaces_existing() {
+        let tools = crate::config::loader::ToolsEnabled { search: true, write: false, clipboard: true };
+        let existing = "vault_path: /tmp/v\ntools_enabled:\n  write: true\n  clipboard: false\nexclude:\n  - .git\n";
+        let out = set_tools_enabled_block(existing, &tools);
+        assert_eq!(out.matches("tools_enabled:").count(), 1);
+        assert!(out.contains("exclude:\n  - .git"));
+        assert!(out.contains("  write: false\n"));
+        assert!(out.contains("  clipboard: true\n"));
+    }
+
+    #[test]
+    fn hook_status_follows_install_and_binary_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let settings = tmp.path().join("settings.json");
+        let binary = Path::new("/usr/local/bin/wardwell");
+        assert_eq!(hook_status(&settings, binary), StepStatus::Missing);
+
+        install_hook_at(&settings, binary).unwrap();
+        assert_eq!(hook_status(&settings, binary), StepStatus::Installed);
+        assert_eq!(hook_status(&settings, Path::new("/opt/wardwell")), StepStatus::Outdated);
+
+        // Installing again replaces rather than duplicates
+        install_hook_at(&settings, binary).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&settings).unwrap()).unwrap();
+        assert_eq!(json["hooks"]["SessionStart"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn claude_md_and_config_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        let claude_md = tmp.path().join("CLAUDE.md");
+        let content = build_injection_content(&[]);
+        assert_eq!(claude_md_status(&claude_md, &content), StepStatus::Missing);
+        crate::inject::inject(&claude_md, "older instructions").unwrap();
+        assert_eq!(claude_md_status(&claude_md, &content), StepStatus::Outdated);
+        crate::inject::inject(&claude_md, &content).unwrap();
+        assert_eq!(claude_md_status(&claude_md, &content), StepStatus::Installed);
+
+        let config = tmp.path().join("config.yml");
+        let vault = tmp.path().join("vault");
+        assert_eq!(config_status(&config, &vault), StepStatus::Missing);
+        write_minimal_config(&config, &vault).unwrap();
+        assert_eq!(config_status(&config, &vault), StepStatus::Installed);
+        assert_eq!(config_status(&config, &tmp.path().join("elsewhere")), StepStatus::Outdated);
+    }
+
+    #[test]
+    fn register_project_writes_local_configs_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(".mcp.json"), r#"{"mcpServers": {"other": {"command": "other"}}}"#).unwrap();
+        std::fs::write(tmp.path().join("CLAUDE.md"), "# Repo rules\n").unwrap();
+
+        register_project(tmp.path(), Path::new("/usr/local/bin/wardwell"), Some("work")).unwrap();
+
+        let mcp: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(tmp.path().join(".mcp.json")).unwrap()).unwrap();
+        assert!(mcp["mcpServers"]["other"].is_object(), "existing servers preserved");
+        assert_eq!(mcp["mcpServers"]["wardwell"]["args"], serde_json::json!(["serve", "--domain", "work"]));
+
+        let claude_md = std::fs::read_to_string(tmp.path().join("CLAUDE.md")).unwrap();
+        assert!(claude_md.starts_with("# Repo rules"));
+        assert!(claude_md.contains("<!-- wardwell:start -->"));
     }
 }
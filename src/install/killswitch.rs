@@ -0,0 +1,89 @@
+use crate::config::loader::config_dir;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that disables hook entry points when set to `1` or `true`.
+pub const DISABLE_ENV: &str = "WARDWELL_DISABLE";
+
+/// Sentinel file under the config dir. Its presence disables hook entry points.
+const SENTINEL: &str = "disabled";
+
+/// Path of the kill-switch sentinel file.
+pub fn sentinel_path() -> PathBuf {
+    config_dir().join(SENTINEL)
+}
+
+/// Why hooks are disabled, if they are. Checked by `inject` and `resolve` before doing anything.
+pub fn disabled_reason() -> Option<String> {
+    if let Ok(v) = std::env::var(DISABLE_ENV)
+        && env_value_disables(&v)
+    {
+        return Some(format!("{DISABLE_ENV}={v}"));
+    }
+    sentinel_reason(&sentinel_path())
+}
+
+/// Create the sentinel. Returns false if it already existed.
+pub fn disable() -> Result<bool, std::io::Error> {
+    write_sentinel(&sentinel_path())
+}
+
+/// Remove the sentinel. Returns false if it wasn't there.
+pub fn enable() -> Result<bool, std::io::Error> {
+    remove_sentinel(&sentinel_path())
+}
+
+fn env_value_disables(v: &str) -> bool {
+    matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes")
+}
+
+fn sentinel_reason(path: &Path) -> Option<String> {
+    path.exists().then(|| format!("{} exists", path.display()))
+}
+
+fn write_sentinel(path: &Path) -> Result<bool, std::io::Error> {
+    if path.exists() {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format!("disabled {}\n", chrono::Utc::now().to_rfc3339()))?;
+    Ok(true)
+}
+
+fn remove_sentinel(path: &Path) -> Result<bool, std::io::Error> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_values() {
+        assert!(env_value_disables("1"));
+        assert!(env_value_disables("TRUE"));
+        assert!(!env_value_disables("0"));
+        assert!(!env_value_disables(""));
+    }
+
+    #[test]
+    fn sentinel_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("nested").join(SENTINEL);
+        assert!(sentinel_reason(&path).is_none());
+
+        assert!(write_sentinel(&path).unwrap());
+        assert!(!write_sentinel(&path).unwrap());
+        assert!(sentinel_reason(&path).is_some());
+
+        assert!(remove_sentinel(&path).unwrap());
+        assert!(!remove_sentinel(&path).unwrap());
+        assert!(sentinel_reason(&path).is_none());
+    }
+}
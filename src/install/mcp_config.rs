@@ -19,9 +19,14 @@ impl McpConfigPaths {
 /// Inject wardwell MCP server entry into a JSON config file.
 /// Preserves all existing entries. Only adds/updates the wardwell entry.
 pub fn inject_mcp_entry(config_path: &Path, binary_path: &Path) -> Result<InjectResult, std::io::Error> {
+    inject_mcp_entry_with_args(config_path, binary_path, &["serve"])
+}
+
+/// Like `inject_mcp_entry`, with explicit server arguments (e.g. `serve --domain work`).
+pub fn inject_mcp_entry_with_args(config_path: &Path, binary_path: &Path, args: &[&str]) -> Result<InjectResult, std::io::Error> {
     let wardwell_entry = serde_json::json!({
         "command": binary_path.to_string_lossy(),
-        "args": ["serve"]
+        "args": args
     });
 
     let mut config: serde_json::Value = if config_path.exists() {
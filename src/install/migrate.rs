@@ -0,0 +1,221 @@
+use crate::domain::model::Domain;
+use crate::install::output::Output;
+use serde::Serialize;
+use std::path::Path;
+
+/// What migrating one config domain does to `domains/<name>.md`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// No domain file yet; one is written.
+    Create,
+    /// A domain file already says the same; it is left alone.
+    Unchanged,
+    /// A domain file says something else and is kept. The config keys stay until it is resolved.
+    Conflict,
+    /// A domain file said something else and is replaced (`--overwrite`).
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Migrated {
+    pub domain: String,
+    /// Vault-relative, e.g. `domains/work.md`.
+    pub path: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub domains: Vec<Migrated>,
+    pub dry_run: bool,
+    /// Whether the `domains:` block was (or, in a dry run, would be) removed from config.yml.
+    pub config_updated: bool,
+}
+
+impl Report {
+    pub fn conflicts(&self) -> usize {
+        self.domains.iter().filter(|d| d.outcome == Outcome::Conflict).count()
+    }
+}
+
+/// Move the `domains:` in the config at `config_path` into vault domain files, then drop
+/// the block from the config. A domain file that already exists and differs is a conflict:
+/// it is kept, and so are the config keys, unless `overwrite` replaces it. A dry run reports
+/// the same without writing anything.
+pub fn migrate(config_path: &Path, dry_run: bool, overwrite: bool) -> Result<Report, Box<dyn std::error::Error>> {
+    let config = crate::config::loader::load(Some(config_path))?;
+    let contents = std::fs::read_to_string(config_path)?;
+    let domains = crate::config::loader::legacy_domains(&contents)?;
+    let domains_dir = config.vault_path.join("domains");
+
+    let mut migrated = Vec::new();
+    for domain in &domains {
+        let name = domain.name.as_str();
+        let path = domains_dir.join(format!("{name}.md"));
+        let outcome = match existing_domain(&path) {
+            None if path.exists() => Outcome::Conflict,
+            None => Outcome::Create,
+            Some(existing) if same_domain(&existing, domain) => Outcome::Unchanged,
+            Some(_) => Outcome::Conflict,
+        };
+        let outcome = if outcome == Outcome::Conflict && overwrite { Outcome::Overwrite } else { outcome };
+        if !dry_run && matches!(outcome, Outcome::Create | Outcome::Overwrite) {
+            std::fs::create_dir_all(&domains_dir)?;
+            std::fs::write(&path, domain_file(domain))?;
+        }
+        migrated.push(Migrated { domain: name.to_string(), path: format!("domains/{name}.md"), outcome });
+    }
+
+    let mut report = Report { domains: migrated, dry_run, config_updated: false };
+    if !domains.is_empty() && report.conflicts() == 0 {
+        if !dry_run {
+            std::fs::write(config_path, remove_domains_block(&contents))?;
+        }
+        report.config_updated = true;
+    }
+    Ok(report)
+}
+
+pub fn run(dry_run: bool, overwrite: bool, out: Output) -> Result<bool, Box<dyn std::error::Error>> {
+    let config_path = crate::config::loader::config_dir().join("config.yml");
+    let report = migrate(&config_path, dry_run, overwrite)?;
+    if report.domains.is_empty() {
+        out.line("No domains in config.yml to migrate — they already live in the vault.");
+    }
+    for d in &report.domains {
+        let verb = match (d.outcome, dry_run) {
+            (Outcome::Create, false) => "created",
+            (Outcome::Create, true) => "would create",
+            (Outcome::Unchanged, _) => "already up to date",
+            (Outcome::Overwrite, false) => "overwrote",
+            (Outcome::Overwrite, true) => "would overwrite",
+            (Outcome::Conflict, _) => "conflict, kept",
+        };
+        out.line(format!("  {:<24} {verb:<20} {}", d.domain, d.path));
+    }
+    let conflicts = report.conflicts();
+    if conflicts > 0 {
+        out.warn(format!(
+            "{conflicts} domain file(s) differ from config.yml. Reconcile them by hand, or rerun with --overwrite to replace them; config.yml keeps its domains until then."
+        ));
+    } else if report.config_updated {
+        out.line(if dry_run { "Would remove `domains:` from config.yml." } else { "Removed `domains:` from config.yml." });
+    }
+    if dry_run {
+        out.line("Dry run — nothing written.");
+    }
+    out.result(serde_json::to_value(&report)?);
+    Ok(dry_run || conflicts == 0)
+}
+
+/// A vault domain file for `domain`, with `## Paths` and `## Aliases` sections and its
+/// `can_read` in the frontmatter.
+pub fn domain_file(domain: &Domain) -> String {
+    let name = domain.name.as_str();
+    let mut content = format!("---\ntype: domain\ndomain: {name}\nconfidence: confirmed\nstatus: active\n");
+    if !domain.can_read.is_empty() {
+        content.push_str(&format!("can_read: [{}]\n", domain.can_read.join(", ")));
+    }
+    content.push_str("---\n\n## Paths\n");
+    for p in &domain.paths {
+        content.push_str(&format!("- {}\n", p.as_str()));
+    }
+
+    if !domain.aliases.is_empty() {
+        content.push_str("\n## Aliases\n");
+        let mut sorted_aliases: Vec<_> = domain.aliases.iter().collect();
+        sorted_aliases.sort_by_key(|(k, _)| (*k).clone());
+        for (key, value) in sorted_aliases {
+            content.push_str(&format!("- {key}: {value}\n"));
+        }
+    }
+    content
+}
+
+/// `content` with the top-level `domains:` key and everything nested under it removed.
+pub fn remove_domains_block(content: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_block = false;
+    let mut blanks: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        if line.starts_with("domains:") {
+            in_block = true;
+            blanks.clear();
+            continue;
+        }
+        if in_block {
+            if line.trim().is_empty() {
+                blanks.push(line);
+                continue;
+            }
+            if line.starts_with(' ') || line.starts_with('\t') {
+                blanks.clear();
+                continue;
+            }
+            in_block = false;
+            if lines.last().is_none_or(|l| l.trim().is_empty()) {
+                blanks.clear();
+            }
+        }
+        lines.append(&mut blanks);
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n") + "\n"
+}
+
+fn existing_domain(path: &Path) -> Option<Domain> {
+    let vf = crate::vault::reader::read_file(path).ok()?;
+    Domain::from_vault_file(&vf).ok()
+}
+
+fn same_domain(a: &Domain, b: &Domain) -> bool {
+    a.name == b.name && a.paths == b.paths && a.aliases == b.aliases && a.can_read == b.can_read
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_config_domains_and_holds_the_config_back_on_conflict() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        let config_path = tmp.path().join("config.yml");
+        let config = format!(
+            "vault_path: {}\n\ndomains:\n  personal:\n    paths:\n      - /tmp/notes/*\n\n  work:\n    paths:\n      - /tmp/work/*\n    aliases:\n      api: /tmp/work/api\n    can_read: [personal]\n\nexclude:\n  - target\n",
+            vault.display()
+        );
+        std::fs::write(&config_path, &config).unwrap();
+        std::fs::create_dir_all(vault.join("domains")).unwrap();
+        std::fs::write(vault.join("domains/personal.md"), "---\ntype: domain\nconfidence: confirmed\n---\n\n## Paths\n- /elsewhere/*\n").unwrap();
+
+        let dry = migrate(&config_path, true, false).unwrap();
+        let outcomes: Vec<_> = dry.domains.iter().map(|d| (d.domain.as_str(), d.outcome)).collect();
+        assert_eq!(outcomes, [("personal", Outcome::Conflict), ("work", Outcome::Create)]);
+        assert!(!dry.config_updated);
+        assert!(!vault.join("domains/work.md").exists());
+
+        // The conflict holds the config keys back, but the new file is written
+        let report = migrate(&config_path, false, false).unwrap();
+        assert_eq!(report.conflicts(), 1);
+        assert_eq!(std::fs::read_to_string(&config_path).unwrap(), config);
+        let work = existing_domain(&vault.join("domains/work.md")).unwrap();
+        assert_eq!(work.can_read, ["personal"]);
+        assert_eq!(work.aliases.get("api").map(String::as_str), Some("/tmp/work/api"));
+
+        let report = migrate(&config_path, false, true).unwrap();
+        let outcomes: Vec<_> = report.domains.iter().map(|d| d.outcome).collect();
+        assert_eq!(outcomes, [Outcome::Overwrite, Outcome::Unchanged]);
+        assert!(report.config_updated);
+        assert_eq!(
+            std::fs::read_to_string(&config_path).unwrap(),
+            format!("vault_path: {}\n\nexclude:\n  - target\n", vault.display())
+        );
+        assert!(migrate(&config_path, false, false).unwrap().domains.is_empty());
+    }
+}
@@ -1,5 +1,18 @@
 pub mod init;
 pub mod doctor;
+pub mod status;
 pub mod uninstall;
+pub mod killswitch;
+pub mod import;
+pub mod export;
+pub mod bundle;
+pub mod compact;
+pub mod verify;
+pub mod output;
+pub mod selftest;
+pub mod bench;
+pub mod migrate;
+pub mod footprint;
+pub mod vacuum;
 mod mcp_config;
 mod detect;
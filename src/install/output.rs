@@ -0,0 +1,95 @@
+use serde_json::Value;
+use std::fmt::Display;
+
+/// How a CLI command reports: readable text, errors only (`--quiet`), or a single JSON
+/// object on stdout (`--json`) for scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Output {
+    #[default]
+    Human,
+    Quiet,
+    Json,
+}
+
+impl Output {
+    pub fn from_flags(quiet: bool, json: bool) -> Self {
+        if json {
+            Self::Json
+        } else if quiet {
+            Self::Quiet
+        } else {
+            Self::Human
+        }
+    }
+
+    pub fn is_human(self) -> bool {
+        self == Self::Human
+    }
+
+    /// A result line for people, on stdout. Human mode only.
+    pub fn line(self, text: impl Display) {
+        if self.is_human() {
+            println!("{text}");
+        }
+    }
+
+    /// Progress or detail for people, on stderr. Human mode only.
+    pub fn note(self, text: impl Display) {
+        if self.is_human() {
+            eprintln!("{text}");
+        }
+    }
+
+    /// Something the user should act on, on stderr. Shown in every mode; stdout stays parseable.
+    pub fn warn(self, text: impl Display) {
+        eprintln!("{text}");
+    }
+
+    /// The command's structured result. JSON mode only, as `{"ok": true, ...fields}`.
+    pub fn result(self, fields: Value) {
+        self.verdict(true, fields);
+    }
+
+    /// A result for a command that checks something: `ok` is whether everything it checked
+    /// passed, so scripts can key on it without reading the details.
+    pub fn verdict(self, ok: bool, fields: Value) {
+        if self == Self::Json {
+            println!("{}", envelope(ok, fields));
+        }
+    }
+
+    /// Report a failed command: `{"ok": false, "error": ...}` on stdout in JSON mode,
+    /// `wardwell: <error>` on stderr otherwise (also with `--quiet`).
+    pub fn error(self, err: impl Display) {
+        if self == Self::Json {
+            println!("{}", envelope(false, serde_json::json!({ "error": err.to_string() })));
+        } else {
+            eprintln!("wardwell: {err}");
+        }
+    }
+}
+
+/// `{"ok": ok}` plus the fields of `fields`, when it is an object.
+fn envelope(ok: bool, fields: Value) -> Value {
+    let mut object = serde_json::Map::new();
+    object.insert("ok".to_string(), Value::Bool(ok));
+    if let Value::Object(fields) = fields {
+        object.extend(fields);
+    }
+    Value::Object(object)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_merges_fields() {
+        let value = envelope(true, serde_json::json!({"indexed": 3, "errors": 0}));
+        assert_eq!(value, serde_json::json!({"ok": true, "indexed": 3, "errors": 0}));
+        assert_eq!(envelope(false, Value::Null).to_string(), r#"{"ok":false}"#);
+        assert_eq!(Output::from_flags(true, true), Output::Json);
+        assert_eq!(Output::from_flags(true, false), Output::Quiet);
+    }
+}
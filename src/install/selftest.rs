@@ -0,0 +1,275 @@
+use crate::install::output::Output;
+use serde_json::Value;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// MCP protocol version sent in `initialize`.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// One tool call the selftest makes: (tool, label, arguments). Arguments only touch the
+/// throwaway vault, and the clipboard call stops at the confirmation step.
+const CASES: &[(&str, &str, &str)] = &[
+    ("wardwell_search", "search", r#"{"action": "search", "query": "selftest"}"#),
+    ("wardwell_search", "read", r#"{"action": "read", "path": "work/demo/current_state.md"}"#),
+    ("wardwell_search", "history", r#"{"action": "history", "query": "selftest"}"#),
+    ("wardwell_search", "orchestrate", r#"{"action": "orchestrate"}"#),
+    ("wardwell_search", "projects", r#"{"action": "projects"}"#),
+    ("wardwell_write", "sync", r#"{"action": "sync", "domain": "work", "project": "demo", "status": "active", "focus": "Selftest", "next_action": "Nothing", "commit_message": "Selftest sync"}"#),
+    ("wardwell_write", "append_history", r#"{"action": "append_history", "domain": "work", "project": "demo", "title": "Selftest", "body": "Round trip"}"#),
+    ("wardwell_clipboard", "confirm step", r#"{"content": "wardwell selftest"}"#),
+    ("wardwell_kanban", "create", r#"{"action": "create", "domain": "work", "project": "demo", "title": "Selftest ticket"}"#),
+    ("wardwell_kanban", "list", r#"{"action": "list"}"#),
+];
+
+/// A JSON-RPC session with `wardwell serve` over its stdin and stdout.
+pub(crate) struct StdioClient {
+    child: std::process::Child,
+    lines: mpsc::Receiver<String>,
+    next_id: u64,
+}
+
+impl StdioClient {
+    /// Start `<binary> serve --no-background` with extra environment variables.
+    pub(crate) fn spawn(binary: &Path, envs: &[(&str, &Path)]) -> Result<Self, String> {
+        let mut command = std::process::Command::new(binary);
+        command.args(["serve", "--no-background"])
+            .env_remove("WARDWELL_DOMAIN")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null());
+        for (key, value) in envs {
+            command.env(key, value);
+        }
+        let mut child = command.spawn().map_err(|e| format!("could not start {}: {e}", binary.display()))?;
+
+        let (tx, lines) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            std::thread::spawn(move || {
+                for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Ok(Self { child, lines, next_id: 1 })
+    }
+
+    /// Send `initialize` and return its result.
+    pub(crate) fn initialize(&mut self, client_name: &str, timeout: Duration) -> Result<Value, String> {
+        let result = self.request("initialize", serde_json::json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": client_name, "version": env!("CARGO_PKG_VERSION") },
+        }), timeout)?;
+        self.notify("notifications/initialized")?;
+        Ok(result)
+    }
+
+    /// Send a request and wait for the response with its id, skipping notifications.
+    pub(crate) fn request(&mut self, method: &str, params: Value, timeout: Duration) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let line = match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => line,
+                Err(mpsc::RecvTimeoutError::Timeout) => return Err(format!("no {method} response within {}s", timeout.as_secs())),
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Err(format!("server exited before answering {method}")),
+            };
+            let message: Value = serde_json::from_str(line.trim())
+                .map_err(|_| "server sent a non-JSON response".to_string())?;
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                let text = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+                return Err(format!("{method} failed: {text}"));
+            }
+            return message.get("result").cloned()
+                .ok_or_else(|| format!("unexpected response: {}", line.trim().chars().take(120).collect::<String>()));
+        }
+    }
+
+    fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.send(&serde_json::json!({ "jsonrpc": "2.0", "method": method }))
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        let stdin = self.child.stdin.as_mut().ok_or("server stdin is closed")?;
+        writeln!(stdin, "{message}")
+            .and_then(|_| stdin.flush())
+            .map_err(|_| "could not write to server stdin".to_string())
+    }
+}
+
+impl Drop for StdioClient {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// One selftest result.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Check {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: Result<String, String>) -> Self {
+        let (ok, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        Self { name: name.into(), ok, detail }
+    }
+
+    fn line(&self) -> String {
+        let mark = if self.ok { '\u{2713}' } else { '\u{2717}' };
+        format!("  {:<38} {mark} {}", self.name, self.detail)
+    }
+}
+
+/// Start this binary's server against a throwaway vault, then handshake, list tools, and call
+/// each one. Returns whether every check passed.
+pub fn run(timeout: Duration, out: Output) -> Result<bool, Box<dyn std::error::Error>> {
+    out.line("wardwell selftest\n");
+    let binary = std::env::current_exe()?;
+    let tmp = std::env::temp_dir().join(format!("wardwell-selftest-{}", std::process::id()));
+    let checks = prepare_sandbox(&tmp).map(|config_dir| round_trip(&binary, &config_dir, timeout));
+    let _ = std::fs::remove_dir_all(&tmp);
+    let checks = checks?;
+
+    let passed = checks.iter().all(|c| c.ok);
+    for check in &checks {
+        if out.is_human() {
+            println!("{}", check.line());
+        } else if out == Output::Quiet && !check.ok {
+            out.warn(check.line());
+        }
+    }
+    let failed = checks.iter().filter(|c| !c.ok).count();
+    out.line("");
+    if passed {
+        out.line(format!("  All {} checks passed.", checks.len()));
+    } else {
+        out.line(format!("  {failed} of {} checks failed.", checks.len()));
+    }
+    out.result(serde_json::json!({ "passed": passed, "checks": checks }));
+    Ok(passed)
+}
+
+/// A config dir and vault under `tmp` with one seeded project, `work/demo`. Returns the config dir.
+fn prepare_sandbox(tmp: &Path) -> std::io::Result<PathBuf> {
+    let config_dir = tmp.join("config");
+    let vault = tmp.join("vault");
+    std::fs::create_dir_all(&config_dir)?;
+    crate::vault::seed::write_project_seed(&vault.join("work").join("demo"), "work", "demo")?;
+    let config = serde_json::json!({
+        "vault_path": vault,
+        "session_sources": [],
+        "kanban": { "enabled": true },
+    });
+    // JSON is valid YAML
+    std::fs::write(config_dir.join("config.yml"), config.to_string())?;
+    Ok(config_dir)
+}
+
+/// Every check against a running server. A failed handshake ends the run early.
+fn round_trip(binary: &Path, config_dir: &Path, timeout: Duration) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let mut client = match StdioClient::spawn(binary, &[("WARDWELL_CONFIG_DIR", config_dir)]) {
+        Ok(c) => c,
+        Err(e) => return vec![Check::new("Start server", Err(e))],
+    };
+
+    let init = client.initialize("wardwell-selftest", timeout).map(|result| {
+        let info = &result["serverInfo"];
+        format!("{} {}", info["name"].as_str().unwrap_or("unknown"), info["version"].as_str().unwrap_or("?"))
+    });
+    let initialized = init.is_ok();
+    checks.push(Check::new("MCP initialize", init));
+    if !initialized {
+        return checks;
+    }
+
+    let tools = match client.request("tools/list", serde_json::json!({}), timeout) {
+        Ok(result) => result["tools"].as_array().cloned().unwrap_or_default(),
+        Err(e) => {
+            checks.push(Check::new("tools/list", Err(e)));
+            return checks;
+        }
+    };
+    checks.push(Check::new("tools/list", schema_problems(&tools).map(|_| format!("{} tools, schemas valid", tools.len()))));
+
+    let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+    for (tool, label, args) in CASES {
+        let name = format!("{tool} {label}");
+        if !names.contains(tool) {
+            // Disabled in config (e.g. kanban) is not a failure
+            continue;
+        }
+        let args: Value = serde_json::from_str(args).unwrap_or_default();
+        checks.push(Check::new(name, call_tool(&mut client, tool, args, timeout)));
+    }
+    for tool in names.iter().filter(|n| !CASES.iter().any(|(t, _, _)| t == *n)) {
+        let responded = call_tool(&mut client, tool, serde_json::json!({}), timeout)
+            .or_else(|e| if e.starts_with("tools/call failed") { Err(e) } else { Ok(format!("responded ({e})")) });
+        checks.push(Check::new(format!("{tool} (no selftest case)"), responded));
+    }
+    checks
+}
+
+/// `Err` naming tools whose `inputSchema` is not a JSON object schema.
+fn schema_problems(tools: &[Value]) -> Result<(), String> {
+    let bad: Vec<&str> = tools.iter()
+        .filter(|t| t["inputSchema"]["type"] != "object")
+        .map(|t| t["name"].as_str().unwrap_or("?"))
+        .collect();
+    if bad.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("input schema is not an object for {}", bad.join(", ")))
+    }
+}
+
+/// Call a tool. Wardwell tools answer with JSON text; an `"error": "..."` field is a failure.
+fn call_tool(client: &mut StdioClient, tool: &str, args: Value, timeout: Duration) -> Result<String, String> {
+    let started = Instant::now();
+    let result = client.request("tools/call", serde_json::json!({ "name": tool, "arguments": args }), timeout)?;
+    let text = result["content"][0]["text"].as_str().unwrap_or("");
+    if result["isError"] == true {
+        return Err(text.chars().take(120).collect());
+    }
+    if let Ok(body) = serde_json::from_str::<Value>(text)
+        && let Some(error) = body.get("error").and_then(Value::as_str) {
+        return Err(error.chars().take(120).collect());
+    }
+    Ok(format!("{} ms", started.elapsed().as_millis()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_are_valid_json_and_schemas_are_checked() {
+        for (tool, label, args) in CASES {
+            assert!(serde_json::from_str::<Value>(args).is_ok_and(|v| v.is_object()), "{tool} {label}");
+        }
+        let tools = serde_json::json!([
+            { "name": "ok", "inputSchema": { "type": "object" } },
+            { "name": "broken", "inputSchema": {} },
+        ]);
+        assert_eq!(schema_problems(tools.as_array().unwrap()), Err("input schema is not an object for broken".to_string()));
+        assert!(Check::new("x", Err("boom".to_string())).line().contains("\u{2717} boom"));
+    }
+}
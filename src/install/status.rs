@@ -0,0 +1,192 @@
+use crate::config::loader::WardwellConfig;
+use crate::daemon::indexer::{SessionStore, SummaryCounts};
+use crate::index::store::IndexStore;
+use crate::install::output::Output;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How far back desktop plans are looked for, as the `sources` action does by default.
+const INTENT_WINDOW_DAYS: i64 = 90;
+
+/// What the background work has been doing, read from index.db and sessions.db.
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    pub index: IndexStatus,
+    pub sessions: SessionsStatus,
+    /// Projects planned from desktop with no code work since, as `sources` reports them.
+    pub pending_intents: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    pub entries: i64,
+    /// Indexed files per domain; files outside every domain are under "".
+    pub by_domain: BTreeMap<String, i64>,
+    pub last_indexed_at: Option<String>,
+    pub watcher: WatcherStatus,
+}
+
+/// Whether index events are keeping up with vault edits. Only a running `wardwell serve`
+/// watches the vault, so a vault that has moved on without the index means no watcher.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WatcherStatus {
+    KeepingUp,
+    /// A vault file changed longer than `stale_index.threshold_minutes` after the last index event.
+    Behind { newest_path: String, newest_change: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionsStatus {
+    #[serde(flatten)]
+    pub counts: SummaryCounts,
+    pub last_indexed_at: Option<String>,
+    pub last_summarized_at: Option<String>,
+    /// Set while the summarizer's circuit breaker holds it off after repeated failures.
+    pub summarizer_paused_until: Option<String>,
+}
+
+/// Gather the status from the databases under `config_dir`. Missing databases read as empty
+/// rather than being created.
+pub fn collect(config: &WardwellConfig, config_dir: &Path) -> Result<Status, Box<dyn std::error::Error>> {
+    let index_db = config_dir.join("index.db");
+    let index = if index_db.exists() {
+        let store = IndexStore::open(&index_db)?;
+        let by_domain = store.counts_by_domain()?;
+        let threshold = chrono::Duration::minutes(config.stale_index.threshold_minutes as i64);
+        let watcher = match crate::index::freshness::check(&store, &config.vault_path, &config.exclude, config.symlinks, threshold)? {
+            None => WatcherStatus::KeepingUp,
+            Some(stale) => WatcherStatus::Behind { newest_path: stale.newest_path, newest_change: stale.newest_change.to_rfc3339() },
+        };
+        IndexStatus {
+            entries: by_domain.values().sum(),
+            by_domain,
+            last_indexed_at: store.last_indexed_at()?.map(|t| t.to_rfc3339()),
+            watcher,
+        }
+    } else {
+        IndexStatus { entries: 0, by_domain: BTreeMap::new(), last_indexed_at: None, watcher: WatcherStatus::KeepingUp }
+    };
+
+    let sessions_db = config_dir.join("sessions.db");
+    let sessions = if sessions_db.exists() {
+        let store = SessionStore::open(&sessions_db)?;
+        let breaker = crate::daemon::summarizer::breaker_state(&store)?;
+        SessionsStatus {
+            counts: store.summary_counts()?,
+            last_indexed_at: store.get_state(crate::daemon::indexer::STATE_LAST_INDEXED)?,
+            last_summarized_at: store.get_state(crate::daemon::summarizer::STATE_LAST_RUN)?,
+            summarizer_paused_until: breaker.open_until.filter(|_| breaker.is_open()).map(|t| t.to_rfc3339()),
+        }
+    } else {
+        SessionsStatus { counts: SummaryCounts::default(), last_indexed_at: None, last_summarized_at: None, summarizer_paused_until: None }
+    };
+
+    let since = chrono::Local::now().date_naive() - chrono::Duration::days(INTENT_WINDOW_DAYS);
+    let pending_intents = crate::mcp::server::pending_desktop_intents(&config.vault_path, since, config.symlinks, config.remote_vault.batch());
+    Ok(Status { index, sessions, pending_intents })
+}
+
+pub fn run(out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    let config = crate::config::loader::load(None)?;
+    let status = collect(&config, &crate::config::loader::config_dir())?;
+    for line in render(&status) {
+        out.line(line);
+    }
+    out.result(serde_json::to_value(&status)?);
+    Ok(())
+}
+
+/// The status as lines for people.
+fn render(status: &Status) -> Vec<String> {
+    let when = |t: &Option<String>| t.as_deref().map(local_time).unwrap_or_else(|| "never".to_string());
+    let mut lines = vec!["wardwell status".to_string(), String::new()];
+
+    let index = &status.index;
+    lines.push(format!("Index      {} entries, last indexed {}", index.entries, when(&index.last_indexed_at)));
+    for (domain, count) in &index.by_domain {
+        let name = if domain.is_empty() { "(no domain)" } else { domain };
+        lines.push(format!("  {name:<16} {count:>6}"));
+    }
+    lines.push(match &index.watcher {
+        WatcherStatus::KeepingUp => "Watcher    keeping up".to_string(),
+        WatcherStatus::Behind { newest_path, newest_change } => format!(
+            "Watcher    behind — {newest_path} changed {} and isn't indexed. Is `wardwell serve` running?",
+            local_time(newest_change),
+        ),
+    });
+
+    let sessions = &status.sessions;
+    let counts = &sessions.counts;
+    let backing_off = if counts.backing_off > 0 { format!(" ({} backing off)", counts.backing_off) } else { String::new() };
+    lines.push(format!("Sessions   {} indexed: {} summarized, {} skipped, {} unsummarized{backing_off}",
+        counts.total, counts.summarized, counts.skipped, counts.unsummarized));
+    lines.push(format!("  last indexed {}, last summarizer run {}", when(&sessions.last_indexed_at), when(&sessions.last_summarized_at)));
+    if let Some(ref until) = sessions.summarizer_paused_until {
+        lines.push(format!("  summarizer paused after repeated failures until {}", local_time(until)));
+    }
+
+    let intents = status.pending_intents.as_array().map(Vec::as_slice).unwrap_or_default();
+    if intents.is_empty() {
+        lines.push("Intents    no desktop plans waiting on code work".to_string());
+    } else {
+        lines.push(format!("Intents    {} project(s) planned from desktop with no code work since:", intents.len()));
+        for intent in intents {
+            lines.push(format!("  {:<28} {} desktop entries, last {}",
+                intent["project"].as_str().unwrap_or("?"),
+                intent["desktop_entries"],
+                intent["last_desktop_entry"].as_str().unwrap_or("?")));
+        }
+    }
+    lines
+}
+
+/// An RFC 3339 time as local `YYYY-MM-DD HH:MM`, or as stored when it doesn't parse.
+fn local_time(t: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(t)
+        .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| t.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_reads_both_databases() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::write(vault.join("work/api/current_state.md"), "---\ntype: project\n---\n# API\n").unwrap();
+        let line = |date: &str| format!("{{\"date\":\"{date}T10:00:00Z\",\"title\":\"Plan\",\"status\":\"active\",\"focus\":\"f\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\",\"source\":\"desktop\"}}");
+        let today = chrono::Local::now().date_naive();
+        let history: Vec<String> = (1..=3).map(|d| line(&(today - chrono::Duration::days(d)).to_string())).collect();
+        std::fs::write(vault.join("work/api/history.jsonl"), history.join("\n")).unwrap();
+        std::fs::write(tmp.path().join("config.yml"), format!("vault_path: {}\n", vault.display())).unwrap();
+        let config = crate::config::loader::load(Some(&tmp.path().join("config.yml"))).unwrap();
+
+        let empty = collect(&config, tmp.path()).unwrap();
+        assert_eq!(empty.index.entries, 0);
+        assert!(!tmp.path().join("index.db").exists());
+
+        let index = IndexStore::open(&tmp.path().join("index.db")).unwrap();
+        crate::index::builder::IndexBuilder::full_build(&index, &vault, None).unwrap();
+        index.mark_indexed().unwrap();
+        let sessions = SessionStore::open(&tmp.path().join("sessions.db")).unwrap();
+        crate::daemon::indexer::index_sessions(&[], &sessions, &[]).unwrap();
+
+        let status = collect(&config, tmp.path()).unwrap();
+        assert_eq!(status.index.by_domain.get("work"), Some(&2));
+        assert_eq!(status.index.watcher, WatcherStatus::KeepingUp);
+        assert!(status.sessions.last_indexed_at.is_some());
+        assert_eq!(status.sessions.last_summarized_at, None);
+        assert_eq!(status.pending_intents[0]["project"], "work/api");
+        assert_eq!(status.pending_intents[0]["desktop_entries"], 3);
+
+        let text = render(&status).join("\n");
+        assert!(text.contains("Watcher    keeping up"), "{text}");
+        assert!(text.contains("work/api"), "{text}");
+    }
+}
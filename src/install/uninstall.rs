@@ -139,7 +139,7 @@ fn remove_hook(settings_path: &std::path::Path, event: &str) -> Result<bool, std
 
 /// Remove wardwell markers and content between them from a CLAUDE.md file.
 /// Returns true if markers were found and removed.
-fn remove_markers(path: &std::path::Path) -> Result<bool, std::io::Error> {
+pub(crate) fn remove_markers(path: &std::path::Path) -> Result<bool, std::io::Error> {
     let content = std::fs::read_to_string(path)?;
 
     let start_marker = "<!-- wardwell:start -->";
@@ -0,0 +1,169 @@
+use crate::config::loader::SessionSource;
+use crate::daemon::indexer::SessionStore;
+use crate::index::store::IndexStore;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What `wardwell vacuum` did, or would do in a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VacuumReport {
+    /// index.db and its WAL, before and after.
+    pub index_bytes_before: u64,
+    pub index_bytes_after: u64,
+    /// sessions.db and its WAL, before and after.
+    pub sessions_bytes_before: u64,
+    pub sessions_bytes_after: u64,
+    /// Indexed paths whose file no longer exists, dropped from the index.
+    pub orphaned_paths: Vec<String>,
+    /// Summaries and cached resume documents of sessions neither sessions.db nor any
+    /// session source knows any more, deleted.
+    pub orphaned_summaries: Vec<String>,
+    pub summary_bytes: u64,
+    pub dry_run: bool,
+}
+
+impl VacuumReport {
+    /// Bytes freed on disk. A dry run only counts the summaries it would delete.
+    pub fn reclaimed(&self) -> u64 {
+        (self.index_bytes_before + self.sessions_bytes_before).saturating_sub(self.index_bytes_after + self.sessions_bytes_after)
+            + self.summary_bytes
+    }
+}
+
+/// Drop index rows for files that are gone and summaries for sessions that are gone, then
+/// optimize the FTS indexes and `VACUUM` both databases. A dry run finds the orphans and
+/// touches nothing.
+pub fn run(
+    index_path: &Path,
+    sessions_path: &Path,
+    summaries_dir: &Path,
+    vault_root: &Path,
+    session_sources: &[SessionSource],
+    dry_run: bool,
+) -> Result<VacuumReport, Box<dyn std::error::Error>> {
+    let mut report = VacuumReport {
+        index_bytes_before: db_bytes(index_path),
+        sessions_bytes_before: db_bytes(sessions_path),
+        dry_run,
+        ..VacuumReport::default()
+    };
+
+    let index = IndexStore::open(index_path)?;
+    report.orphaned_paths = index.orphaned_paths(vault_root)?;
+
+    let sessions = SessionStore::open(sessions_path)?;
+    let mut live = sessions.session_ids()?;
+    live.extend(transcript_ids(session_sources));
+    for (path, session_id) in summary_files(summaries_dir) {
+        if live.contains(&session_id) {
+            continue;
+        }
+        report.summary_bytes += std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        report.orphaned_summaries.push(path.strip_prefix(summaries_dir).unwrap_or(&path).to_string_lossy().to_string());
+        if !dry_run {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    report.orphaned_summaries.sort();
+
+    if dry_run {
+        report.index_bytes_after = report.index_bytes_before;
+        report.sessions_bytes_after = report.sessions_bytes_before;
+        return Ok(report);
+    }
+    for path in &report.orphaned_paths {
+        index.remove(path)?;
+    }
+    index.optimize()?;
+    sessions.vacuum()?;
+    drop((index, sessions));
+    report.index_bytes_after = db_bytes(index_path);
+    report.sessions_bytes_after = db_bytes(sessions_path);
+    Ok(report)
+}
+
+/// A database's size on disk, WAL included.
+fn db_bytes(path: &Path) -> u64 {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    [path.to_path_buf(), PathBuf::from(wal)].iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Session IDs of the transcripts in every source, whether indexed or not.
+fn transcript_ids(session_sources: &[SessionSource]) -> HashSet<String> {
+    session_sources.iter()
+        .flat_map(|source| std::fs::read_dir(&source.path).into_iter().flatten().flatten())
+        .flat_map(|project| std::fs::read_dir(project.path()).into_iter().flatten().flatten())
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "jsonl"))
+        .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(String::from))
+        .collect()
+}
+
+/// Summaries (`<id>.md`, compressed or not) and cached resume documents (`resume/<id>.md`),
+/// with the session each belongs to.
+fn summary_files(summaries_dir: &Path) -> Vec<(PathBuf, String)> {
+    [summaries_dir.to_path_buf(), summaries_dir.join("resume")].iter()
+        .flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten())
+        .map(|entry| entry.path())
+        .filter(|p| p.is_file())
+        .filter_map(|p| {
+            let name = crate::vault::compress::logical_path(&p);
+            let id = name.file_name()?.to_str()?.strip_suffix(".md")?.to_string();
+            Some((p, id))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vacuum_drops_orphaned_rows_and_summaries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        let (index_path, sessions_path, summaries) = (tmp.path().join("index.db"), tmp.path().join("sessions.db"), tmp.path().join("summaries"));
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::create_dir_all(summaries.join("resume")).unwrap();
+        for (name, body) in [("kept", "Still here"), ("gone", "Deleted later")] {
+            std::fs::write(vault.join(format!("work/api/{name}.md")), format!("---\ntype: reference\n---\n{body}\n")).unwrap();
+        }
+        {
+            let index = IndexStore::open(&index_path).unwrap();
+            for name in ["kept", "gone"] {
+                let vf = crate::vault::reader::read_file(&vault.join(format!("work/api/{name}.md"))).unwrap();
+                index.upsert(&vf, &vault).unwrap();
+            }
+        }
+        std::fs::remove_file(vault.join("work/api/gone.md")).unwrap();
+
+        // "live" has a transcript but was never indexed; "old" has neither
+        let source = tmp.path().join("projects");
+        std::fs::create_dir_all(source.join("-work-api")).unwrap();
+        std::fs::write(source.join("-work-api/live.jsonl"), "").unwrap();
+        drop(SessionStore::open(&sessions_path).unwrap());
+        for name in ["live.md", "old.md", "resume/old.md"] {
+            std::fs::write(summaries.join(name), "summary").unwrap();
+        }
+        let sources = [SessionSource::new(&source)];
+
+        let dry = run(&index_path, &sessions_path, &summaries, &vault, &sources, true).unwrap();
+        assert_eq!(dry.orphaned_paths, ["work/api/gone.md"]);
+        assert_eq!(dry.orphaned_summaries, ["old.md", "resume/old.md"]);
+        assert_eq!(dry.reclaimed(), 14);
+        assert!(summaries.join("old.md").exists());
+
+        let report = run(&index_path, &sessions_path, &summaries, &vault, &sources, false).unwrap();
+        assert_eq!(report.orphaned_summaries.len(), 2);
+        assert!(!summaries.join("resume/old.md").exists() && summaries.join("live.md").exists());
+        let index = IndexStore::open(&index_path).unwrap();
+        assert!(index.orphaned_paths(&vault).unwrap().is_empty());
+        assert!(index.get_body("work/api/kept.md").unwrap().is_some());
+    }
+}
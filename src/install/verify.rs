@@ -0,0 +1,367 @@
+use crate::vault::compress;
+use crate::vault::symlinks::SymlinkPolicy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The snapshot `wardwell verify --snapshot` records, in the config directory rather than the
+/// vault so a sync tool that damages the files can't damage their record too.
+pub const MANIFEST_FILE: &str = "integrity.json";
+
+/// Malformed line numbers listed per file; the rest are counted.
+const MAX_LINES_LISTED: usize = 5;
+
+/// Size and hash of one machine file's (uncompressed) content when the snapshot was taken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileRecord {
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Machine files by vault-relative path, as of `created_at`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub created_at: String,
+    pub files: BTreeMap<String, FileRecord>,
+}
+
+/// What kind of damage a file shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemKind {
+    /// In the snapshot, no longer on disk.
+    Missing,
+    /// Content from the snapshot changed or was cut. The files are append-only, so this is
+    /// damage unless something rewrote them on purpose.
+    Rewritten,
+    /// Lines that aren't JSON.
+    Malformed,
+    /// The last line was cut off mid-write, as a crash during an append leaves it.
+    TornTail,
+    /// Couldn't be read (or decompressed).
+    Unreadable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Problem {
+    pub path: String,
+    pub kind: ProblemKind,
+    pub detail: String,
+}
+
+/// What a verify run found.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerifyReport {
+    /// Machine files on disk that were checked.
+    pub checked: usize,
+    /// Tracked files that only grew since the snapshot.
+    pub appended: usize,
+    /// Files on disk the snapshot doesn't list, checked for malformed lines only.
+    pub untracked: usize,
+    /// Tracked files found intact where delete, archive, or rename moved them.
+    pub moved: usize,
+    /// Whether a snapshot was compared against.
+    pub snapshot: bool,
+    pub problems: Vec<Problem>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Machine-managed files: the JSONL logs and lists in each project, and history archives
+/// (compressed or not, keyed by their uncompressed name). Sorted by path.
+pub fn machine_files(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    for (domain, project, dir) in crate::install::export::project_dirs(vault_path, exclude, symlinks) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let logical = compress::logical_path(&path);
+            if path.is_file() && logical.extension().is_some_and(|e| e == "jsonl") {
+                let name = logical.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                files.push((format!("{domain}/{project}/{name}"), path));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Record every machine file's size and hash.
+pub fn snapshot(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy) -> std::io::Result<Manifest> {
+    let mut manifest = Manifest { created_at: chrono::Utc::now().to_rfc3339(), ..Default::default() };
+    for (rel, path) in machine_files(vault_path, exclude, symlinks) {
+        let content = compress::read_to_string(&path)?;
+        manifest.files.insert(rel, FileRecord { bytes: content.len() as u64, sha256: hash(content.as_bytes()) });
+    }
+    Ok(manifest)
+}
+
+/// Check machine files for malformed lines and, against `manifest`, for anything but appends.
+pub fn verify(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy, manifest: Option<&Manifest>) -> VerifyReport {
+    let mut report = VerifyReport { snapshot: manifest.is_some(), ..Default::default() };
+    let on_disk = machine_files(vault_path, exclude, symlinks);
+    let mut problem = |path: &str, kind: ProblemKind, detail: String| {
+        report.problems.push(Problem { path: path.to_string(), kind, detail });
+    };
+
+    let mut appended = 0;
+    let mut untracked = Vec::new();
+    for (rel, path) in &on_disk {
+        let content = match compress::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                problem(rel, ProblemKind::Unreadable, e.to_string());
+                continue;
+            }
+        };
+
+        let not_json = |l: &str| serde_json::from_str::<serde_json::Value>(l).is_err();
+        let torn = crate::vault::jsonl::unterminated_tail(&content).is_some_and(not_json);
+        // Line number of the torn last line, reported on its own; 0 when there is none
+        let torn_line = if torn { content.lines().count() } else { 0 };
+        if torn {
+            problem(rel, ProblemKind::TornTail, format!("line {torn_line} was cut off mid-write (`wardwell verify --repair` removes it)"));
+        }
+        let bad: Vec<usize> = content.lines().enumerate()
+            .filter(|(i, l)| i + 1 != torn_line && !l.trim().is_empty() && not_json(l))
+            .map(|(i, _)| i + 1)
+            .collect();
+        if !bad.is_empty() {
+            let listed: Vec<String> = bad.iter().take(MAX_LINES_LISTED).map(|n| n.to_string()).collect();
+            let more = bad.len().saturating_sub(MAX_LINES_LISTED);
+            let tail = if more > 0 { format!(" and {more} more") } else { String::new() };
+            problem(rel, ProblemKind::Malformed, format!("line {}{tail} not JSON", listed.join(", ")));
+        }
+
+        match manifest.and_then(|m| m.files.get(rel)) {
+            Some(record) => {
+                let bytes = content.as_bytes();
+                let prefix = usize::try_from(record.bytes).ok().and_then(|n| bytes.get(..n));
+                match prefix {
+                    Some(prefix) if hash(prefix) == record.sha256 => {
+                        if bytes.len() as u64 > record.bytes {
+                            appended += 1;
+                        }
+                    }
+                    _ => problem(rel, ProblemKind::Rewritten, format!(
+                        "content from the snapshot changed ({} bytes then, {} now)", record.bytes, bytes.len()
+                    )),
+                }
+            }
+            None if manifest.is_some() => untracked.push((rel.clone(), content)),
+            None => {}
+        }
+    }
+
+    let mut moved = 0;
+    if let Some(manifest) = manifest {
+        for (rel, record) in &manifest.files {
+            if on_disk.iter().any(|(r, _)| r == rel) {
+                continue;
+            }
+            if relocated(vault_path, rel, record, &mut untracked) {
+                moved += 1;
+            } else {
+                problem(rel, ProblemKind::Missing, "in the snapshot, gone from disk".to_string());
+            }
+        }
+    }
+    report.checked = on_disk.len();
+    report.appended = appended;
+    report.untracked = untracked.len();
+    report.moved = moved;
+    report
+}
+
+/// Whether a tracked file that left its path still starts with its snapshotted content where
+/// the server moves files: renamed to another project (taken out of `untracked`), archived
+/// under `<domain>/archive/`, or deleted into the trash, possibly under a timestamped name.
+fn relocated(vault_path: &Path, rel: &str, record: &FileRecord, untracked: &mut Vec<(String, String)>) -> bool {
+    let intact = |content: &str| {
+        usize::try_from(record.bytes).ok().and_then(|n| content.as_bytes().get(..n)).is_some_and(|p| hash(p) == record.sha256)
+    };
+    let mut parts = rel.splitn(3, '/');
+    let (Some(domain), Some(project), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+
+    let renamed = untracked.iter().position(|(r, content)| r.rsplit('/').next() == Some(name) && intact(content));
+    if let Some(i) = renamed {
+        untracked.remove(i);
+        return true;
+    }
+
+    let readable = |path: PathBuf| {
+        let packed = compress::compressed_path(&path);
+        [path, packed].into_iter().filter(|p| p.is_file()).any(|p| compress::read_to_string(&p).is_ok_and(|c| intact(&c)))
+    };
+    if readable(vault_path.join(domain).join(crate::vault::project::ARCHIVE_DIR).join(project).join(name)) {
+        return true;
+    }
+
+    let trash = vault_path.join(crate::vault::reader::TRASH_DIR).join(domain).join(project);
+    let stem = name.strip_suffix(".jsonl").unwrap_or(name);
+    let Ok(entries) = std::fs::read_dir(&trash) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let trashed = entry.file_name().to_string_lossy().to_string();
+        (trashed == name || (trashed.starts_with(&format!("{stem}.")) && trashed.ends_with(".jsonl"))) && readable(entry.path())
+    })
+}
+
+/// A machine file whose tail `repair` fixed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Repair {
+    pub path: String,
+    /// What was done, for the report.
+    pub detail: String,
+}
+
+/// Fix the tails crashes leave on uncompressed machine files: cut a last line that was torn
+/// off mid-write, and add the missing newline to one that is whole.
+pub fn repair(vault_path: &Path, exclude: &[String], symlinks: SymlinkPolicy) -> std::io::Result<Vec<Repair>> {
+    let mut repairs = Vec::new();
+    for (rel, path) in machine_files(vault_path, exclude, symlinks) {
+        if compress::logical_path(&path) != path {
+            continue;
+        }
+        let detail = match crate::vault::jsonl::repair_tail(&path)? {
+            None => continue,
+            Some(crate::vault::jsonl::TailRepair::Terminated) => "added the missing newline after the last line".to_string(),
+            Some(crate::vault::jsonl::TailRepair::Cut { fragment }) => {
+                let shown: String = fragment.chars().take(60).collect();
+                format!("removed a torn last line ({} bytes): {shown}", fragment.len())
+            }
+        };
+        repairs.push(Repair { path: rel, detail });
+    }
+    Ok(repairs)
+}
+
+/// The snapshot in `config_dir`, if one was recorded.
+pub fn load(config_dir: &Path) -> std::io::Result<Option<Manifest>> {
+    match std::fs::read_to_string(config_dir.join(MANIFEST_FILE)) {
+        Ok(content) => serde_json::from_str(&content).map(Some).map_err(std::io::Error::other),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn save(config_dir: &Path, manifest: &Manifest) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(manifest).map_err(std::io::Error::other)?;
+    let path = config_dir.join(MANIFEST_FILE);
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const HEADER: &str = "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n";
+
+    #[test]
+    fn appends_pass_and_damage_is_reported() {
+        let tmp = tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        let dir = vault.join("work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("history.jsonl"), format!("{HEADER}{{\"title\":\"one\"}}\n")).unwrap();
+        std::fs::write(dir.join("lessons.jsonl"), format!("{HEADER}{{\"title\":\"lesson\"}}\n")).unwrap();
+        std::fs::write(dir.join("todo.jsonl"), format!("{HEADER}{{\"title\":\"item\"}}\n")).unwrap();
+        std::fs::write(dir.join("current_state.md"), "# api\n").unwrap();
+        compress::write_compressed(&compress::compressed_path(&dir.join(compress::HISTORY_ARCHIVE)), HEADER).unwrap();
+
+        let manifest = snapshot(&vault, &[], SymlinkPolicy::Follow).unwrap();
+        assert_eq!(manifest.files.keys().collect::<Vec<_>>(), vec![
+            "work/api/history.archive.jsonl", "work/api/history.jsonl", "work/api/lessons.jsonl", "work/api/todo.jsonl",
+        ]);
+        save(tmp.path(), &manifest).unwrap();
+        assert_eq!(load(tmp.path()).unwrap(), Some(manifest.clone()));
+        assert!(verify(&vault, &[], SymlinkPolicy::Follow, Some(&manifest)).is_ok());
+
+        // Appending is what these files are for
+        std::fs::write(dir.join("history.jsonl"), format!("{HEADER}{{\"title\":\"one\"}}\n{{\"title\":\"two\"}}\n")).unwrap();
+        let report = verify(&vault, &[], SymlinkPolicy::Follow, Some(&manifest));
+        assert!(report.is_ok());
+        assert_eq!((report.checked, report.appended), (4, 1));
+
+        // A sync conflict cut one file, mangled another, and removed a third
+        std::fs::write(dir.join("lessons.jsonl"), HEADER).unwrap();
+        std::fs::write(dir.join("history.jsonl"), format!("{HEADER}{{\"title\":\"one\"}}\n{{\"title\":\"tw")).unwrap();
+        std::fs::remove_file(dir.join("todo.jsonl")).unwrap();
+        let report = verify(&vault, &[], SymlinkPolicy::Follow, Some(&manifest));
+        let kinds: Vec<(&str, ProblemKind)> = report.problems.iter().map(|p| (p.path.as_str(), p.kind)).collect();
+        assert_eq!(kinds, vec![
+            ("work/api/history.jsonl", ProblemKind::TornTail),
+            ("work/api/lessons.jsonl", ProblemKind::Rewritten),
+            ("work/api/todo.jsonl", ProblemKind::Missing),
+        ]);
+        assert_eq!(report.problems[0].detail, "line 3 was cut off mid-write (`wardwell verify --repair` removes it)");
+
+        // Without a snapshot only malformed lines can be caught
+        let report = verify(&vault, &[], SymlinkPolicy::Follow, None);
+        assert_eq!(report.problems.len(), 1);
+        assert!(!report.snapshot);
+
+        // Repair cuts the torn line, after which the file only grew since the snapshot
+        let repairs = repair(&vault, &[], SymlinkPolicy::Follow).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].path, "work/api/history.jsonl");
+        assert!(repairs[0].detail.starts_with("removed a torn last line (12 bytes)"), "{}", repairs[0].detail);
+        assert!(verify(&vault, &[], SymlinkPolicy::Follow, None).is_ok());
+        assert!(repair(&vault, &[], SymlinkPolicy::Follow).unwrap().is_empty());
+    }
+
+    #[test]
+    fn files_the_server_moved_are_not_missing() {
+        let tmp = tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        for project in ["api", "web", "old"] {
+            let dir = vault.join("work").join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("history.jsonl"), format!("{HEADER}{{\"title\":\"{project}\"}}\n")).unwrap();
+        }
+        std::fs::write(vault.join("work/api/ideas.jsonl"), format!("{HEADER}{{\"title\":\"idea\"}}\n")).unwrap();
+        std::fs::write(vault.join("work/api/todo.jsonl"), format!("{HEADER}{{\"title\":\"item\"}}\n")).unwrap();
+        let manifest = snapshot(&vault, &[], SymlinkPolicy::Follow).unwrap();
+
+        // Renamed, archived, deleted (under a timestamp, since the plain name was taken), and grown since
+        std::fs::rename(vault.join("work/web"), vault.join("work/site")).unwrap();
+        std::fs::write(vault.join("work/site/history.jsonl"), format!("{HEADER}{{\"title\":\"web\"}}\n{{\"title\":\"two\"}}\n")).unwrap();
+        std::fs::create_dir_all(vault.join("work/archive")).unwrap();
+        std::fs::rename(vault.join("work/old"), vault.join("work/archive/old")).unwrap();
+        let trash = vault.join(".trash/work/api");
+        std::fs::create_dir_all(&trash).unwrap();
+        std::fs::write(trash.join("ideas.jsonl"), HEADER).unwrap();
+        std::fs::rename(vault.join("work/api/ideas.jsonl"), trash.join("ideas.20261014-120000.jsonl")).unwrap();
+        // Gone for real
+        std::fs::remove_file(vault.join("work/api/todo.jsonl")).unwrap();
+
+        let report = verify(&vault, &[], SymlinkPolicy::Follow, Some(&manifest));
+        let kinds: Vec<(&str, ProblemKind)> = report.problems.iter().map(|p| (p.path.as_str(), p.kind)).collect();
+        assert_eq!(kinds, vec![("work/api/todo.jsonl", ProblemKind::Missing)]);
+        assert_eq!((report.moved, report.untracked), (3, 0));
+
+        // A file that takes the old one's name with other content is new, not the moved one
+        std::fs::write(vault.join("work/site/history.jsonl"), format!("{HEADER}{{\"title\":\"other\"}}\n")).unwrap();
+        let report = verify(&vault, &[], SymlinkPolicy::Follow, Some(&manifest));
+        assert_eq!((report.moved, report.untracked), (2, 1));
+        assert_eq!(report.problems.len(), 2);
+    }
+}
@@ -109,20 +109,20 @@ impl KanbanStore {
         let groups = load_kanban_yml(&vault_root);
 
         // Check schema version — wipe if stale (SQLite is just a cache)
-        if db_path.exists() {
-            if let Ok(c) = Connection::open(db_path) {
-                let version: i64 = c.query_row(
-                    "SELECT COALESCE((SELECT version FROM kanban_schema_version), 0)", [], |r| r.get(0),
-                ).unwrap_or(0);
-                if version != Self::SCHEMA_VERSION {
-                    drop(c);
-                    let _ = std::fs::remove_file(db_path);
-                    let shm = db_path.with_extension("db-shm");
-                    let wal = db_path.with_extension("db-wal");
-                    let _ = std::fs::remove_file(shm);
-                    let _ = std::fs::remove_file(wal);
-                    eprintln!("wardwell: kanban schema v{version} → v{}, rebuilding from JSONL", Self::SCHEMA_VERSION);
-                }
+        if db_path.exists()
+            && let Ok(c) = Connection::open(db_path)
+        {
+            let version: i64 = c.query_row(
+                "SELECT COALESCE((SELECT version FROM kanban_schema_version), 0)", [], |r| r.get(0),
+            ).unwrap_or(0);
+            if version != Self::SCHEMA_VERSION {
+                drop(c);
+                let _ = std::fs::remove_file(db_path);
+                let shm = db_path.with_extension("db-shm");
+                let wal = db_path.with_extension("db-wal");
+                let _ = std::fs::remove_file(shm);
+                let _ = std::fs::remove_file(wal);
+                eprintln!("wardwell: kanban schema v{version} → v{}, rebuilding from JSONL", Self::SCHEMA_VERSION);
             }
         }
 
@@ -511,12 +511,10 @@ impl KanbanStore {
         let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(search_pat)];
         let mut idx = 2;
 
-        if use_domain {
-            if let Some(dl) = domains {
-                let ph: Vec<String> = dl.iter().map(|_| { let s = format!("?{idx}"); idx += 1; s }).collect();
-                conditions.push(format!("p.domain IN ({})", ph.join(",")));
-                for d in dl { params.push(Box::new(d.clone())); }
-            }
+        if use_domain && let Some(dl) = domains {
+            let ph: Vec<String> = dl.iter().map(|_| { let s = format!("?{idx}"); idx += 1; s }).collect();
+            conditions.push(format!("p.domain IN ({})", ph.join(",")));
+            for d in dl { params.push(Box::new(d.clone())); }
         }
         if let Some(proj) = project {
             let group_members = self.resolve_group_members(proj);
@@ -558,6 +556,7 @@ impl KanbanStore {
         Ok(items)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn list(
         &self, project: Option<&str>, status: Option<&str>, priority: Option<&str>,
         assignee: Option<&str>, epic: Option<&str>, tag: Option<&str>, include_done: bool, domains: Option<&[String]>,
@@ -572,12 +571,10 @@ impl KanbanStore {
             "FROM kanban_items INNER JOIN kanban_projects p ON kanban_items.project = p.project"
         } else { "FROM kanban_items" };
 
-        if use_domain {
-            if let Some(dl) = domains {
-                let ph: Vec<String> = dl.iter().map(|_| { let s = format!("?{idx}"); idx += 1; s }).collect();
-                conditions.push(format!("p.domain IN ({})", ph.join(",")));
-                for d in dl { params.push(Box::new(d.clone())); }
-            }
+        if use_domain && let Some(dl) = domains {
+            let ph: Vec<String> = dl.iter().map(|_| { let s = format!("?{idx}"); idx += 1; s }).collect();
+            conditions.push(format!("p.domain IN ({})", ph.join(",")));
+            for d in dl { params.push(Box::new(d.clone())); }
         }
         if !include_done { conditions.push(format!("kanban_items.status != ?{idx}")); params.push(Box::new("done".to_string())); idx += 1; }
         if let Some(v) = project {
@@ -646,11 +643,11 @@ impl KanbanStore {
         let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![];
         let mut idx = 1;
 
-        if use_domain { if let Some(dl) = domains {
+        if use_domain && let Some(dl) = domains {
             let ph: Vec<String> = dl.iter().map(|_| { let s = format!("?{idx}"); idx += 1; s }).collect();
             extra.push(format!("p.domain IN ({})", ph.join(",")));
             for d in dl { params.push(Box::new(d.clone())); }
-        }}
+        }
         if let Some(p) = project { extra.push(format!("kanban_items.project=?{idx}")); params.push(Box::new(p.to_string())); let _ = idx; }
 
         let wh = if extra.is_empty() { format!("WHERE {named_where}") } else { format!("WHERE ({named_where}) AND {}", extra.join(" AND ")) };
@@ -1173,7 +1170,7 @@ mod tests {
 
     #[test]
     fn rebuild_from_jsonl_restores_state() {
-        let (dir, store) = make_store();
+        let (_dir, store) = make_store();
         let p = HashMap::new();
         store.create_item("Task", "shulops", "work", None, None, None, None, None, None, None, None, None, &p).unwrap();
         store.move_item("SH-1", "todo").unwrap();
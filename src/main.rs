@@ -1,25 +1,70 @@
 use clap::{Parser, Subcommand};
-use std::path::Path;
+use wardwell::install::output::Output;
 
 #[derive(Parser)]
 #[command(name = "wardwell", version, about = "Personal AI knowledge vault — MCP server")]
 struct Cli {
+    /// Print only warnings and errors (reindex, seed, doctor, links check, backup now)
+    #[arg(long, global = true, conflicts_with = "json")]
+    quiet: bool,
+    /// Print one JSON object with the command's result (reindex, seed, doctor, links check, backup now)
+    #[arg(long, global = true)]
+    json: bool,
     #[command(subcommand)]
     command: Commands,
 }
 
+/// MCP transport for `wardwell serve`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Transport {
+    Stdio,
+    Http,
+}
+
 #[derive(Subcommand)]
 enum Commands {
-    /// Start the MCP server (stdio transport) with background daemon tasks
+    /// Start the MCP server (stdio, or streamable HTTP/SSE) with background daemon tasks
     Serve {
         /// Scope this server to a specific vault domain (also reads WARDWELL_DOMAIN env var)
         #[arg(long)]
         domain: Option<String>,
+        /// How clients connect: `stdio` for a locally spawned client, `http` for remote and web clients
+        #[arg(long, value_enum, default_value_t = Transport::Stdio)]
+        transport: Transport,
+        /// Port for the HTTP transport (implies `--transport http`)
+        #[arg(long)]
+        port: Option<u16>,
+        /// Address the HTTP transport listens on. Anything but loopback needs `http.token` in config.yml
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+        /// Skip indexing, file watching, summarization, and backups (used by `doctor --watch` probes)
+        #[arg(long, hide = true)]
+        no_background: bool,
+        /// Refuse writes: no wardwell_write or wardwell_clipboard, and kanban answers queries only (also `read_only: true` in config.yml)
+        #[arg(long)]
+        read_only: bool,
     },
     /// First-run setup — generates config, injects MCP entries, installs hooks
-    Init,
+    Init {
+        /// Register wardwell for the current repo only (.mcp.json + CLAUDE.md), leaving global configs alone
+        #[arg(long)]
+        project: bool,
+        /// With --project: scope the project's server to this vault domain
+        #[arg(long, requires = "project")]
+        domain: Option<String>,
+        /// Perform only the steps that are missing or outdated, without prompts, using the existing config's vault
+        #[arg(long, conflicts_with = "project")]
+        repair: bool,
+    },
     /// Check that everything is wired correctly
-    Doctor,
+    Doctor {
+        /// Keep re-running checks and print what changes, including a live MCP handshake
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between checks in --watch mode
+        #[arg(long, default_value_t = 10, requires = "watch")]
+        interval: u64,
+    },
     /// Clean removal — removes MCP entries, hooks, and markers (preserves vault data)
     Uninstall,
     /// Output project context for the given directory (used by hooks)
@@ -27,55 +72,401 @@ enum Commands {
         /// Project directory (defaults to current directory)
         #[arg(default_value = ".")]
         path: String,
+        /// Estimated tokens to fit the output in, shortening the project listing past it
+        /// (default: inject_max_tokens in config.yml; 0 = no limit)
+        #[arg(long, alias = "budget")]
+        max_tokens: Option<usize>,
+    },
+    /// Summarize a directory's recent sessions ahead of context/resume (spawned by inject)
+    #[command(hide = true)]
+    Prewarm {
+        #[arg(default_value = ".")]
+        path: String,
     },
     /// Stop hook — check if session should sync before exit (reads JSON from stdin)
     Resolve,
     /// Rebuild the vault search index from scratch
     Reindex,
+    /// Organize a flat vault (project folders at the top level) into domain/project folders, or merge a bundle back in
+    Import {
+        /// Show the proposed moves and seed files (or the bundle's merge) without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Merge a bundle written by `wardwell export --bundle` into the vault
+        #[arg(long)]
+        bundle: Option<std::path::PathBuf>,
+    },
+    /// Export history, lessons, decisions, or sessions as JSON or CSV, or bundle a domain or project
+    Export {
+        /// Output format
+        #[arg(long, value_enum, required_unless_present = "bundle")]
+        format: Option<wardwell::install::export::ExportFormat>,
+        /// Which records to export
+        #[arg(long, value_enum, required_unless_present = "bundle")]
+        what: Option<wardwell::install::export::ExportWhat>,
+        /// Bundle a domain or project (`work`, `work/api`) into one file `wardwell import --bundle` can merge back
+        #[arg(long, conflicts_with_all = ["format", "what"])]
+        bundle: Option<String>,
+        /// With --bundle: write one merged markdown document instead
+        #[arg(long, requires = "bundle")]
+        markdown: bool,
+        /// With --bundle: only entries dated (and files modified) on or after this day (YYYY-MM-DD)
+        #[arg(long, requires = "bundle")]
+        since: Option<chrono::NaiveDate>,
+        /// With --bundle: only entries dated (and files modified) on or before this day (YYYY-MM-DD)
+        #[arg(long, requires = "bundle")]
+        until: Option<chrono::NaiveDate>,
+        /// With --bundle: file extensions to include, comma-separated (default md,jsonl)
+        #[arg(long, requires = "bundle", value_delimiter = ',')]
+        types: Vec<String>,
+        /// Write to this file instead of stdout (a name ending in .zst is compressed)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Start a server against a throwaway vault and call every MCP tool, reporting pass/fail per tool
+    Selftest {
+        /// Seconds to wait for each response
+        #[arg(long, default_value_t = 15)]
+        timeout: u64,
+    },
+    /// Time index build, search, orchestrate, and history scans on a generated vault, against budgets and the saved snapshot
+    Bench {
+        /// Files in the generated vault
+        #[arg(long, default_value_t = wardwell::install::bench::DEFAULT_FILES)]
+        files: usize,
+        /// Save this run as the snapshot later runs are compared against
+        #[arg(long)]
+        save: bool,
+        /// Percent slower than the snapshot that counts as a regression
+        #[arg(long, default_value_t = 25.0)]
+        tolerance: f64,
+    },
+    /// Measure what wardwell adds to the context window (CLAUDE.md blocks, inject output per domain and project) and suggest trims
+    Footprint {
+        /// Apply the trims that need no judgment: refresh outdated CLAUDE.md blocks, remove ones the global CLAUDE.md repeats
+        #[arg(long)]
+        optimize: bool,
+    },
+    /// Move the `domains:` in config.yml into vault domain files, then remove them from config.yml
+    MigrateDomains {
+        /// Show what would be written and removed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Replace vault domain files that differ from config.yml instead of keeping them
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Move old history into history.archive.jsonl, optionally compressing archives and old summaries
+    Compact {
+        /// Archive history entries and summaries older than this many days
+        #[arg(long, default_value_t = 180)]
+        older_than: u32,
+        /// zstd-compress history archives and aged session summaries
+        #[arg(long)]
+        compress: bool,
+        /// Leave one summary entry per archived month in history.jsonl, listing its entries
+        #[arg(long)]
+        summarize: bool,
+        /// Have the summarizer model write the monthly summaries (implies --summarize)
+        #[arg(long)]
+        ai: bool,
+        /// Compact even if `wardwell verify` finds damaged files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Optimize the search index, VACUUM index.db and sessions.db, and prune index rows and summaries whose files or sessions are gone
+    Vacuum {
+        /// List what would be pruned without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Apply `retention`: delete or archive expired summaries and drop old sessions from sessions.db
+    Prune {
+        /// List what has expired without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Summarize pending sessions now, or re-summarize matching ones with --redo
+    Summarize {
+        /// Re-summarize sessions that already have a summary
+        #[arg(long)]
+        redo: bool,
+        /// Only sessions for this project: `domain/project`, or just `project`
+        #[arg(long)]
+        project: Option<String>,
+        /// Only sessions active on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Model for this run (defaults to ai.summarize_model)
+        #[arg(long)]
+        model: Option<String>,
+        /// List matching sessions and the cost estimate without summarizing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show how a project changed between two dates or history entries, as markdown
+    Diff {
+        /// The project, as domain/project
+        target: String,
+        /// Start: YYYY-MM-DD, "YYYY-MM-DD HH:MM", or a history entry ref (#1 = first, #-1 = latest)
+        #[arg(long)]
+        from: String,
+        /// End, in the same forms as --from
+        #[arg(long, default_value = "#-1")]
+        to: String,
+    },
+    /// List recent sessions and whether each was summarized, skipped (and why), or is pending
+    Sessions {
+        /// How many sessions to show, newest first
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Only sessions the summarizer skipped
+        #[arg(long)]
+        skipped: bool,
+    },
+    /// Show what the background work is doing: index entries per domain, last index and summarizer runs, unsummarized sessions, watcher lag, and desktop plans waiting on code
+    Status,
     /// Create a domain or project folder under the vault (additive only)
     Seed {
         /// Domain or domain/project path (e.g., "work", "work/my-project")
         target: String,
+        /// Template variable for the seed files, e.g. --var client=Acme (repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+        /// Seed from ~/.wardwell/templates/<NAME>/ instead of the domain's default template
+        #[arg(long, value_name = "NAME")]
+        template: Option<String>,
     },
     /// Migrate kanban attachments from ~/.wardwell/attachments/ to vault docs/
     MigrateAttachments,
+    /// Run the configured vault backup command
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
+    /// Check vault notes for dead `related:` paths and broken URLs
+    Links {
+        #[command(subcommand)]
+        action: LinksAction,
+    },
+    /// Disable the inject/resolve hooks without editing settings.json (creates ~/.wardwell/disabled)
+    Disable,
+    /// Re-enable hooks after `wardwell disable`
+    Enable,
+    /// Check machine-managed vault files (history, lessons, lists) against a recorded snapshot
+    Verify {
+        /// Record the current files as the snapshot to verify against later
+        #[arg(long)]
+        snapshot: bool,
+        /// First fix tails left by a crash mid-append: cut torn last lines, terminate whole ones
+        #[arg(long, conflicts_with = "snapshot")]
+        repair: bool,
+    },
+    /// List pairs of near-duplicate notes from the search index, with merge suggestions
+    Dedupe {
+        /// Least similarity (Jaccard over 5-word shingles, 0–1) for a pair to be listed
+        #[arg(long, default_value_t = 0.8)]
+        threshold: f64,
+        /// Only compare notes in this domain
+        #[arg(long)]
+        domain: Option<String>,
+        /// How many pairs to show, most similar first
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Show local usage metrics: tool calls per day, search hit rates, syncs per project
+    Metrics {
+        /// How many days back to report
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+    /// Manage secrets in the OS keychain, referenced from config.yml as {secret:<name>}
+    Secret {
+        #[command(subcommand)]
+        action: SecretAction,
+    },
+}
+
+impl Commands {
+    /// Whether the command honors `--quiet` and `--json`.
+    fn reports_results(&self) -> bool {
+        matches!(
+            self,
+            Commands::Reindex | Commands::Compact { .. } | Commands::Vacuum { .. } | Commands::Prune { .. } | Commands::Diff { .. } | Commands::Sessions { .. } | Commands::Seed { .. } | Commands::Doctor { watch: false, .. } | Commands::Status | Commands::Selftest { .. } | Commands::Bench { .. } | Commands::MigrateDomains { .. } | Commands::Footprint { .. } | Commands::Links { .. } | Commands::Backup { .. } | Commands::Metrics { .. } | Commands::Verify { .. } | Commands::Dedupe { .. }
+        )
+    }
+}
+
+#[derive(Subcommand)]
+enum SecretAction {
+    /// Store a secret. The value is read from stdin (hidden when typed at a terminal)
+    Set {
+        /// Secret name, e.g. anthropic_api_key
+        name: String,
+    },
+    /// Remove a stored secret
+    Delete {
+        /// Secret name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum LinksAction {
+    /// Verify related paths resolve and write ~/.wardwell/links_report.json
+    Check {
+        /// Also HEAD-check http(s) URLs (needs network; uses curl)
+        #[arg(long)]
+        urls: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackupAction {
+    /// Run a backup immediately, regardless of schedule
+    Now,
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    let out = Output::from_flags(cli.quiet, cli.json);
+    if !out.is_human() && !cli.command.reports_results() {
+        out.error("--quiet and --json are supported by reindex, compact, diff, sessions, seed, doctor, selftest, links check, backup now, metrics, and verify");
+        std::process::exit(2);
+    }
     let result: Result<(), Box<dyn std::error::Error>> = match cli.command {
-        Commands::Serve { domain } => {
+        Commands::Serve { domain, transport, port, host, no_background, read_only } => {
             let domain = domain.or_else(|| std::env::var("WARDWELL_DOMAIN").ok());
-            run_serve(domain).await
+            let http = (transport == Transport::Http || port.is_some())
+                .then(|| std::net::SocketAddr::new(host, port.unwrap_or(wardwell::mcp::http::DEFAULT_PORT)));
+            let result = run_serve(domain, !no_background, http, read_only).await;
+            if let Err(ref e) = result
+                && !no_background
+            {
+                wardwell::mcp::safe_mode::record_error(&wardwell::config::loader::config_dir(), &e.to_string());
+            }
+            result
         }
-        Commands::Init => wardwell::install::init::run(),
-        Commands::Doctor => wardwell::install::doctor::run(),
+        Commands::Init { project: false, repair, .. } => wardwell::install::init::run(repair),
+        Commands::Init { project: true, ref domain, .. } => std::env::current_dir()
+            .map_err(|e| e.into())
+            .and_then(|dir| wardwell::install::init::run_project(&dir, domain.as_deref())),
+        Commands::Doctor { watch: false, .. } => wardwell::install::doctor::run(out),
+        Commands::Doctor { watch: true, interval } => {
+            wardwell::install::doctor::run_watch(std::time::Duration::from_secs(interval.max(1)))
+        }
+        Commands::Selftest { timeout } => match wardwell::install::selftest::run(std::time::Duration::from_secs(timeout.max(1)), out) {
+            Ok(false) => std::process::exit(1),
+            other => other.map(|_| ()),
+        },
+        Commands::Bench { files, save, tolerance } => match wardwell::install::bench::run(files.max(1), save, tolerance, out) {
+            Ok(false) => std::process::exit(1),
+            other => other.map(|_| ()),
+        },
+        Commands::MigrateDomains { dry_run, overwrite } => match wardwell::install::migrate::run(dry_run, overwrite, out) {
+            Ok(false) => std::process::exit(1),
+            other => other.map(|_| ()),
+        },
+        Commands::Footprint { optimize } => wardwell::install::footprint::run(optimize, out),
+        Commands::Status => wardwell::install::status::run(out),
         Commands::Uninstall => wardwell::install::uninstall::run(),
-        Commands::Inject { ref path } => run_inject(path),
+        // Kill switch: hooks exit 0 immediately so a misbehaving wardwell never blocks a session
+        Commands::Inject { .. } | Commands::Prewarm { .. } | Commands::Resolve if wardwell::install::killswitch::disabled_reason().is_some() => Ok(()),
+        Commands::Inject { ref path, max_tokens } => run_inject(path, max_tokens),
+        Commands::Prewarm { ref path } => run_prewarm(path).await,
         Commands::Resolve => run_resolve(),
-        Commands::Reindex => run_reindex(),
-        Commands::Seed { ref target } => run_seed(target),
+        Commands::Disable => run_disable(),
+        Commands::Enable => run_enable(),
+        Commands::Reindex => run_reindex(out),
+        Commands::Import { dry_run, bundle: Some(ref bundle) } => run_import_bundle(bundle, dry_run),
+        Commands::Import { dry_run, bundle: None } => wardwell::config::loader::load(None)
+            .map_err(|e| e.into())
+            .and_then(|config| wardwell::install::init::run_import(&config.vault_path, dry_run)),
+        Commands::Export { bundle: Some(ref scope), markdown, since, until, ref types, ref out, .. } => {
+            let filter = wardwell::install::bundle::BundleFilter { since, until, types: types.clone() };
+            run_export_bundle(scope, &filter, markdown, out.as_deref())
+        }
+        Commands::Export { format: Some(format), what: Some(what), ref out, .. } => run_export(format, what, out.as_deref()),
+        Commands::Export { .. } => Err("--format and --what are required unless --bundle is given".into()),
+        Commands::Compact { older_than, compress, summarize, ai, force } => {
+            let rollup = match (summarize, ai) {
+                (_, true) => wardwell::install::compact::Rollup::Ai,
+                (true, false) => wardwell::install::compact::Rollup::Titles,
+                (false, false) => wardwell::install::compact::Rollup::Off,
+            };
+            run_compact(older_than, compress, rollup, force, out).await
+        }
+        Commands::Vacuum { dry_run } => run_vacuum(dry_run, out),
+        Commands::Prune { dry_run } => run_prune(dry_run, out),
+        Commands::Summarize { redo, ref project, ref since, ref model, dry_run } => {
+            run_summarize(redo, project.as_deref(), since.as_deref(), model.as_deref(), dry_run).await
+        }
+        Commands::Diff { ref target, ref from, ref to } => run_diff(target, from, to, out),
+        Commands::Sessions { limit, skipped } => run_sessions(limit, skipped, out),
+        Commands::Seed { ref target, ref vars, ref template } => run_seed(target, vars, template.as_deref(), out),
         Commands::MigrateAttachments => run_migrate_attachments(),
+        Commands::Backup { action: BackupAction::Now } => run_backup_now(out),
+        Commands::Links { action: LinksAction::Check { urls } } => run_links_check(urls, out),
+        Commands::Metrics { days } => run_metrics(days, out),
+        Commands::Dedupe { threshold, ref domain, limit } => run_dedupe(threshold, domain.as_deref(), limit, out),
+        Commands::Verify { snapshot, repair } => match run_verify(snapshot, repair, out) {
+            Ok(false) => std::process::exit(1),
+            other => other.map(|_| ()),
+        },
+        Commands::Secret { action: SecretAction::Set { ref name } } => run_secret_set(name),
+        Commands::Secret { action: SecretAction::Delete { ref name } } => {
+            wardwell::config::secrets::delete(name).map_err(|e| e.into())
+        }
     };
     if let Err(e) = result {
-        eprintln!("wardwell: {e}");
+        out.error(e);
         std::process::exit(1);
     }
 }
 
-async fn run_serve(domain: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+/// Pause before restarting a vault watcher that died.
+const WATCHER_RESTART_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
+async fn run_serve(domain: Option<String>, background: bool, http: Option<std::net::SocketAddr>, read_only: bool) -> Result<(), Box<dyn std::error::Error>> {
     use rmcp::ServiceExt;
     use std::sync::Arc;
     use wardwell::config::loader;
     use wardwell::index::builder::IndexBuilder;
     use wardwell::index::store::IndexStore;
+    use wardwell::mcp::safe_mode;
     use wardwell::mcp::server::WardwellServer;
 
-    eprintln!("wardwell: loading config");
-    let config = loader::load(None)?;
-
     let config_dir = loader::config_dir();
+    let startup = safe_mode::read_record(&config_dir);
+    if startup.in_safe_mode() {
+        eprintln!("wardwell: {} consecutive failed startups, starting in safe mode", startup.consecutive_failures);
+        let safe = safe_mode::SafeModeServer::new(config_dir, startup);
+        if let Some(addr) = http {
+            // The config may be what's broken; without it only loopback access is possible
+            let access = loader::load(None).ok().and_then(|c| wardwell::mcp::http::Access::new(&c.http).ok()).unwrap_or_default();
+            let listener = wardwell::mcp::http::bind(addr, &access).await?;
+            eprintln!("wardwell: safe mode listening on http://{addr}/mcp");
+            wardwell::mcp::http::serve(listener, move || safe.clone(), None, access).await?;
+            return Ok(());
+        }
+        let service = safe.serve(rmcp::transport::stdio()).await?;
+        service.waiting().await?;
+        return Ok(());
+    }
+    // Probes (`doctor --watch`, selftest) start and kill servers of their own; they mustn't
+    // count toward, or clear, the real server's failed startups
+    if background {
+        safe_mode::begin_attempt(&config_dir);
+        install_startup_panic_hook(config_dir.clone());
+    }
+
+    eprintln!("wardwell: loading config");
+    let mut config = loader::load(None)?;
+    config.read_only |= read_only;
+    if config.read_only {
+        eprintln!("wardwell: read-only — wardwell_write and wardwell_clipboard are off, kanban answers queries only");
+    }
 
     // Open kanban BEFORE index — IndexStore registers sqlite-vec globally
     // which causes disk I/O errors on connections opened after it.
@@ -110,141 +501,716 @@ async fn run_serve(domain: Option<String>) -> Result<(), Box<dyn std::error::Err
     let index = Arc::new(index);
 
     // Embedder starts as None — loaded in background so MCP server starts immediately
-    let embedder: Arc<std::sync::Mutex<Option<wardwell::index::embed::Embedder>>> =
-        Arc::new(std::sync::Mutex::new(None));
-
-    // Index + load embedder in background
-    let bg_index = Arc::clone(&index);
-    let bg_roots = all_index_roots.clone();
-    let bg_exclude = config.exclude.clone();
-    let bg_embedder = Arc::clone(&embedder);
-    let models_dir = config_dir.join("models");
-    tokio::spawn(async move {
-        // 1. Index with FTS only (fast, no embedder needed)
-        for root in &bg_roots {
-            match IndexBuilder::build_filtered(&bg_index, root, &bg_exclude, None) {
-                Ok(stats) => {
-                    if stats.indexed > 0 || stats.removed > 0 {
-                        eprintln!("wardwell: indexed {} files from {} ({} skipped, {} removed, {} errors)",
+    let embedder: wardwell::index::embeddings::SharedProvider = Arc::new(std::sync::Mutex::new(None));
+
+    eprintln!("wardwell: starting MCP server");
+    let mut server = WardwellServer::new(config, Arc::clone(&index), Arc::clone(&embedder), domain, kanban);
+    let shared_registry = server.registry.clone();
+
+    if background {
+        // Index + load embedder in background
+        let bg_index = Arc::clone(&index);
+        let bg_roots = all_index_roots.clone();
+        let bg_exclude = server.config.exclude.clone();
+        let bg_symlinks = server.config.symlinks;
+        let bg_statuses = server.config.statuses.clone();
+        let bg_log = server.log.clone();
+        let bg_embedder = Arc::clone(&embedder);
+        let models_dir = config_dir.join("models");
+        tokio::spawn(async move {
+            // 1. Index with FTS only (fast, no embedder needed)
+            for root in &bg_roots {
+                match IndexBuilder::build_filtered(&bg_index, root, &bg_exclude, bg_symlinks, &bg_statuses, None) {
+                    Ok(stats) => {
+                        let line = format!("indexed {} files from {} ({} skipped, {} removed, {} errors)",
                             stats.indexed, root.display(), stats.skipped, stats.removed, stats.errors);
+                        if stats.errors > 0 {
+                            // The first few say what went wrong; `wardwell reindex` lists them all
+                            let shown: Vec<&str> = stats.error_details.iter().take(3).map(String::as_str).collect();
+                            bg_log.warning("index", &format!("{line}: {}", shown.join("; ")));
+                        } else if stats.indexed > 0 || stats.removed > 0 {
+                            bg_log.info("index", &line);
+                        }
                     }
+                    Err(e) => bg_log.error("index", &format!("index error for {}: {e}", root.display())),
                 }
-                Err(e) => eprintln!("wardwell: index error for {}: {e}", root.display()),
             }
-        }
 
-        // 2. Load embedder (may download model ~33MB on first run)
-        match wardwell::index::embed::Embedder::new(&models_dir) {
-            Ok(e) => {
-                eprintln!("wardwell: embedding model loaded");
-                let mut guard = bg_embedder.lock().unwrap_or_else(|e| e.into_inner());
-                *guard = Some(e);
-                drop(guard);
-
-                // 3. Re-index with embeddings for any files that need chunk vectors
-                for root in &bg_roots {
-                    let mut emb_guard = bg_embedder.lock().unwrap_or_else(|e| e.into_inner());
-                    let result = IndexBuilder::build_filtered(&bg_index, root, &bg_exclude, emb_guard.as_mut());
-                    drop(emb_guard);
-                    match result {
-                        Ok(stats) => {
-                            if stats.chunks_embedded > 0 {
-                                eprintln!("wardwell: embedded {} chunks from {}", stats.chunks_embedded, root.display());
+            // 2. Load embedder (may download model ~33MB on first run)
+            match wardwell::index::embed::Embedder::new(&models_dir) {
+                Ok(e) => {
+                    bg_log.info("index", "embedding model loaded");
+                    let mut guard = bg_embedder.lock().unwrap_or_else(|e| e.into_inner());
+                    *guard = Some(Box::new(e));
+                    drop(guard);
+
+                    // 3. Re-index with embeddings for any files that need chunk vectors
+                    for root in &bg_roots {
+                        let mut emb_guard = bg_embedder.lock().unwrap_or_else(|e| e.into_inner());
+                        let result = IndexBuilder::build_filtered(&bg_index, root, &bg_exclude, bg_symlinks, &bg_statuses, emb_guard.as_mut().map(|e| e.as_mut() as &mut dyn wardwell::index::embeddings::EmbeddingProvider));
+                        drop(emb_guard);
+                        match result {
+                            Ok(stats) => {
+                                if stats.chunks_embedded > 0 {
+                                    bg_log.info("index", &format!("embedded {} chunks from {}", stats.chunks_embedded, root.display()));
+                                }
                             }
+                            Err(e) => bg_log.error("index", &format!("embedding index error for {}: {e}", root.display())),
                         }
-                        Err(e) => eprintln!("wardwell: embedding index error for {}: {e}", root.display()),
                     }
                 }
+                Err(e) => {
+                    bg_log.warning("index", &format!("embedding model unavailable (semantic search disabled): {e}"));
+                }
             }
-            Err(e) => {
-                eprintln!("wardwell: embedding model unavailable (semantic search disabled): {e}");
-            }
+        });
+
+        // Spawn vault file watcher for vault + sources
+        // The vault root watcher gets the shared registry for live domain reload
+        let vault_root_for_watcher = server.vault_root.clone();
+        for root in all_index_roots {
+            let watcher_index = Arc::clone(&index);
+            let registry_for_watcher = if root == vault_root_for_watcher {
+                Some(shared_registry.clone())
+            } else {
+                None
+            };
+            let watcher_log = server.log.clone();
+            let durability = server.config.durability;
+            let symlinks = server.config.symlinks;
+            let statuses = server.config.statuses.clone();
+            tokio::spawn(async move {
+                // The watcher only returns when it has died; bring it back after a pause
+                loop {
+                    let reason = match wardwell::daemon::watcher::watch_vault(root.clone(), Arc::clone(&watcher_index), registry_for_watcher.clone(), durability, symlinks, statuses.clone()).await {
+                        Ok(()) => "stopped".to_string(),
+                        Err(e) => e.to_string(),
+                    };
+                    watcher_log.error("watcher", &format!("watcher for {} failed ({reason}), restarting in {}s", root.display(), WATCHER_RESTART_DELAY.as_secs()));
+                    tokio::time::sleep(WATCHER_RESTART_DELAY).await;
+                    watcher_log.info("watcher", &format!("watcher restarted for {}", root.display()));
+                }
+            });
         }
-    });
 
-    eprintln!("wardwell: starting MCP server");
-    let server = WardwellServer::new(config, Arc::clone(&index), embedder, domain, kanban);
-    let shared_registry = server.registry.clone();
+        // Spawn session indexer + summarizer (runs once then periodically)
+        let session_sources = server.config.session_sources.clone();
+        let domains = server.config.registry.all().to_vec();
+        let ai_config = server.config.ai.clone();
+        let vault_root_for_daemon = server.vault_root.clone();
+        let summaries_dir = config_dir.join("summaries");
+        let sessions_db = config_dir.join("sessions.db");
+        let daemon_log = server.log.clone();
+        tokio::spawn(async move {
+            run_daemon_loop(sessions_db, session_sources, domains, summaries_dir, ai_config, vault_root_for_daemon, daemon_log).await;
+        });
 
-    // Spawn vault file watcher for vault + sources
-    // The vault root watcher gets the shared registry for live domain reload
-    let vault_root_for_watcher = server.vault_root.clone();
-    for root in all_index_roots {
-        let watcher_index = Arc::clone(&index);
-        let registry_for_watcher = if root == vault_root_for_watcher {
-            Some(shared_registry.clone())
-        } else {
-            None
-        };
+        // Spawn domain rollup regeneration (<domain>/current_state.md)
+        let rollup_root = server.vault_root.clone();
+        let rollup_exclude = server.config.exclude.clone();
+        let rollup_statuses = server.config.statuses.clone();
         tokio::spawn(async move {
-            if let Err(e) = wardwell::daemon::watcher::watch_vault(root.clone(), watcher_index, registry_for_watcher).await {
-                eprintln!("wardwell: watcher error for {}: {e}", root.display());
-            }
+            wardwell::daemon::rollup::run_rollup_loop(rollup_root, rollup_exclude, rollup_statuses).await;
         });
+
+        // Spawn periodic related-path checks (links_report.json)
+        let links_root = server.vault_root.clone();
+        let links_exclude = server.config.exclude.clone();
+        let links_symlinks = server.config.symlinks;
+        let links_config_dir = config_dir.clone();
+        tokio::spawn(async move {
+            wardwell::daemon::links::run_links_loop(links_root, links_exclude, links_symlinks, links_config_dir).await;
+        });
+
+        // Spawn scheduled backups if configured
+        if let Some(backup) = server.config.backup.clone() {
+            let vault_path = server.vault_root.clone();
+            let backup_config_dir = config_dir.clone();
+            tokio::spawn(async move {
+                wardwell::daemon::backup::run_backup_loop(backup, vault_path, backup_config_dir).await;
+            });
+        }
+
+        // Spawn retention for summaries and sessions.db if configured
+        if let Some(retention) = server.config.retention.clone() {
+            let retention_config_dir = config_dir.clone();
+            tokio::spawn(async move {
+                wardwell::daemon::retention::run_retention_loop(retention, retention_config_dir).await;
+            });
+        }
+
+        // Spawn scheduled digests (<domain>/_digests/) if configured
+        if server.config.digest.is_some() {
+            let (plan_server, digest_server) = (server.clone(), server.clone());
+            let digest_config_dir = config_dir.clone();
+            let subscriptions = move || plan_server.digest_subscriptions().unwrap_or_else(|e| {
+                eprintln!("wardwell: digest domains unavailable: {e}");
+                Vec::new()
+            });
+            tokio::spawn(async move {
+                wardwell::daemon::digest::run_digest_loop(digest_config_dir, subscriptions, move |sub| digest_server.write_digest(sub)).await;
+            });
+        }
     }
 
-    // Spawn session indexer + summarizer (runs once then periodically)
-    let session_sources = server.config.session_sources.clone();
-    let domains = server.config.registry.all().to_vec();
-    let ai_config = server.config.ai.clone();
-    let summaries_dir = config_dir.join("summaries");
-    let sessions_db = config_dir.join("sessions.db");
-    tokio::spawn(async move {
-        run_daemon_loop(sessions_db, session_sources, domains, summaries_dir, ai_config).await;
-    });
+    if let Some(addr) = http {
+        let access = wardwell::mcp::http::Access::new(&server.config.http)?;
+        let listener = wardwell::mcp::http::bind(addr, &access).await?;
+        if background {
+            safe_mode::mark_healthy(&config_dir);
+        }
+        eprintln!("wardwell: listening on http://{addr}/mcp, dashboard at http://{addr}/");
+        let dashboard = wardwell::mcp::dashboard::Dashboard::new(server.clone());
+        wardwell::mcp::http::serve(listener, move || server.clone(), Some(dashboard), access).await?;
+        return Ok(());
+    }
+
+    if background {
+        server.track_startup(config_dir.clone());
+    }
+    let log = server.log.clone();
     let service = server.serve(rmcp::transport::stdio()).await?;
+    log.attach(service.peer().clone());
     service.waiting().await?;
 
     Ok(())
 }
 
+/// Record startup panics (bad domain file, corrupt index) so safe mode can report them.
+/// Once the server is healthy the record is gone and later panics are ignored here.
+fn install_startup_panic_hook(config_dir: std::path::PathBuf) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        wardwell::mcp::safe_mode::record_error(&config_dir, &format!("panic: {info}"));
+        default_hook(info);
+    }));
+}
+
+/// How long a session transcript must go unwritten before a change to it is indexed, and the
+/// most a run of writes can hold indexing off.
+const SESSION_SETTLE: std::time::Duration = std::time::Duration::from_secs(3);
+const SESSION_SETTLE_LIMIT: std::time::Duration = std::time::Duration::from_secs(30);
+
 async fn run_daemon_loop(
     sessions_db: std::path::PathBuf,
-    session_sources: Vec<std::path::PathBuf>,
+    session_sources: Vec<wardwell::config::loader::SessionSource>,
     domains: Vec<wardwell::domain::model::Domain>,
     summaries_dir: std::path::PathBuf,
     ai_config: wardwell::config::loader::AiConfig,
+    vault_root: std::path::PathBuf,
+    log: wardwell::mcp::logging::McpLog,
 ) {
     use wardwell::daemon::indexer;
-    use wardwell::daemon::summarizer;
 
     let session_store = match indexer::SessionStore::open(&sessions_db) {
         Ok(s) => s,
         Err(e) => {
-            eprintln!("wardwell: failed to open sessions.db: {e}");
+            log.error("sessions", &format!("failed to open sessions.db: {e}"));
             return;
         }
     };
 
+    let mut changes = wardwell::daemon::watcher::watch_sessions(&session_sources);
     loop {
         // 1. Index sessions
-        match indexer::index_sessions(&session_sources, &session_store, &domains) {
-            Ok(stats) => {
-                if stats.indexed > 0 {
-                    eprintln!("wardwell: indexed {} sessions ({} skipped, {} errors)",
-                        stats.indexed, stats.skipped, stats.errors);
+        index_sessions_logged(None, &session_sources, &session_store, &domains, &log);
+
+        // 2. Summarize via claude CLI
+        summarize_logged(&session_store, &session_sources, &summaries_dir, &ai_config, &vault_root, &log).await;
+
+        // Wait 5 minutes before next run; a changed transcript is indexed as soon as it settles
+        let next_cycle = tokio::time::sleep(std::time::Duration::from_secs(300));
+        tokio::pin!(next_cycle);
+        loop {
+            tokio::select! {
+                _ = &mut next_cycle => break,
+                Some(first) = changes.recv() => {
+                    let changed = wardwell::daemon::watcher::settle(first, &mut changes, SESSION_SETTLE, SESSION_SETTLE_LIMIT).await;
+                    index_sessions_logged(Some(&changed), &session_sources, &session_store, &domains, &log);
+                    if ai_config.summarize_on_change {
+                        summarize_logged(&session_store, &session_sources, &summaries_dir, &ai_config, &vault_root, &log).await;
+                    }
                 }
             }
-            Err(e) => eprintln!("wardwell: session indexing error: {e}"),
         }
+    }
+}
 
-        // 2. Summarize via claude CLI
-        match summarizer::summarize_pending(&session_store, &session_sources, &summaries_dir, &ai_config.summarize_model, false).await {
-            Ok(stats) => {
-                if stats.summarized > 0 {
-                    eprintln!("wardwell: summarized {} sessions ({} skipped, {} errors)",
-                        stats.summarized, stats.skipped, stats.errors);
-                }
+/// Index every session, or only the `changed` transcripts when the watcher reported them.
+fn index_sessions_logged(
+    changed: Option<&[std::path::PathBuf]>,
+    session_sources: &[wardwell::config::loader::SessionSource],
+    session_store: &wardwell::daemon::indexer::SessionStore,
+    domains: &[wardwell::domain::model::Domain],
+    log: &wardwell::mcp::logging::McpLog,
+) {
+    let result = match changed {
+        Some(paths) => wardwell::daemon::indexer::index_session_paths(paths, session_sources, session_store, domains),
+        None => wardwell::daemon::indexer::index_sessions(session_sources, session_store, domains),
+    };
+    match result {
+        Ok(stats) => {
+            if stats.indexed > 0 {
+                log.info("sessions", &format!("indexed {} sessions ({} skipped, {} errors)",
+                    stats.indexed, stats.skipped, stats.errors));
+            }
+            if stats.excluded > 0 {
+                log.info("sessions", &format!("dropped {} indexed sessions now under session_exclude", stats.excluded));
             }
-            Err(e) => eprintln!("wardwell: summarization error: {e}"),
         }
+        Err(e) => log.error("sessions", &format!("session indexing error: {e}")),
+    }
+}
+
+async fn summarize_logged(
+    session_store: &wardwell::daemon::indexer::SessionStore,
+    session_sources: &[wardwell::config::loader::SessionSource],
+    summaries_dir: &std::path::Path,
+    ai_config: &wardwell::config::loader::AiConfig,
+    vault_root: &std::path::Path,
+    log: &wardwell::mcp::logging::McpLog,
+) {
+    use wardwell::daemon::summarizer;
 
-        // Wait 5 minutes before next run
-        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+    let active_domains = summarizer::active_domains(vault_root);
+    match summarizer::summarize_pending(session_store, session_sources, summaries_dir, vault_root, ai_config, &active_domains, false).await {
+        Ok(stats) => {
+            let deferred = if stats.deferred > 0 { format!(", {} left for later cycles", stats.deferred) } else { String::new() };
+            let proposed = if stats.proposed > 0 { format!(", {} decisions queued for approval", stats.proposed) } else { String::new() };
+            let message = format!("summarized {} sessions ({} skipped, {} errors{deferred}){proposed}",
+                stats.summarized, stats.skipped, stats.errors);
+            if stats.errors > 0 {
+                log.warning("summarizer", &message);
+            } else if stats.summarized > 0 {
+                log.info("summarizer", &message);
+            }
+        }
+        Err(e) => log.error("summarizer", &format!("summarization error: {e}")),
     }
 }
 
-fn run_inject(cwd: &str) -> Result<(), Box<dyn std::error::Error>> {
+fn run_export(
+    format: wardwell::install::export::ExportFormat,
+    what: wardwell::install::export::ExportWhat,
+    out: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::install::export;
+
+    let config = loader::load(None)?;
+    let table = export::build(what, &config.vault_path, &config.exclude, config.symlinks, &loader::config_dir().join("sessions.db"))?;
+    let rendered = export::render(&table, format);
+    match out {
+        Some(path) => {
+            std::fs::write(path, rendered)?;
+            eprintln!("wardwell: exported {} rows to {}", table.rows.len(), path.display());
+        }
+        None => {
+            use std::io::Write;
+            std::io::stdout().write_all(rendered.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+fn run_export_bundle(
+    scope: &str,
+    filter: &wardwell::install::bundle::BundleFilter,
+    markdown: bool,
+    out: Option<&std::path::Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::install::bundle;
+
+    let config = loader::load(None)?;
+    let collected = bundle::collect(&config.vault_path, &config.exclude, config.symlinks, scope, filter)?;
+    match (out, markdown) {
+        (Some(path), false) => bundle::save(path, &collected)?,
+        (Some(path), true) => std::fs::write(path, bundle::render_markdown(&collected))?,
+        (None, _) => {
+            use std::io::Write;
+            let rendered = if markdown { bundle::render_markdown(&collected) } else { serde_json::to_string_pretty(&collected)? };
+            std::io::stdout().write_all(rendered.as_bytes())?;
+        }
+    }
+    if let Some(path) = out {
+        eprintln!("wardwell: exported {} file(s) from {scope} to {}", collected.files.len(), path.display());
+    }
+    Ok(())
+}
+
+fn run_import_bundle(path: &std::path::Path, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::install::bundle;
+
+    let config = loader::load(None)?;
+    let loaded = bundle::load(path)?;
+    let report = bundle::merge(&config.vault_path, &loaded, dry_run)?;
+
+    println!("  Bundle of {} ({} file(s), exported {}):", loaded.scope, loaded.files.len(), loaded.created_at);
+    for created in &report.created {
+        println!("    CREATE  {created}");
+    }
+    println!("    {} entr{} appended, {} already present, {} file(s) unchanged.",
+        report.entries_added, if report.entries_added == 1 { "y" } else { "ies" }, report.entries_skipped, report.unchanged);
+    for (file, why) in &report.conflicts {
+        println!("    KEEP    {file}: {why}");
+    }
+    if dry_run {
+        println!("\n  Dry run — nothing changed.");
+    } else if !report.created.is_empty() || report.entries_added > 0 {
+        println!("\n  Run `wardwell reindex` to search the imported notes.");
+    }
+    Ok(())
+}
+
+async fn run_compact(older_than: u32, compress: bool, rollup: wardwell::install::compact::Rollup, force: bool, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::install::{compact, verify};
+
+    let config = loader::load(None)?;
+    // Compacting rewrites history, so damage found now would be baked into the archive
+    let manifest = verify::load(&loader::config_dir())?;
+    let check = verify::verify(&config.vault_path, &config.exclude, config.symlinks, manifest.as_ref());
+    if !check.is_ok() && !force {
+        return Err(format!("{} machine file problem(s) found; run `wardwell verify` for details, or pass --force to compact anyway", check.problems.len()).into());
+    }
+
+    let opts = compact::CompactOptions { older_than_days: older_than, compress, rollup };
+    let report = compact::run(&config.vault_path, &config.exclude, config.symlinks, &loader::config_dir().join("summaries"), opts, wardwell::daemon::summarizer::provider(&config.ai, None).as_ref()).await?;
+    // The rewrite is deliberate: move the snapshot past it
+    if manifest.is_some() && report.projects > 0 {
+        verify::save(&loader::config_dir(), &verify::snapshot(&config.vault_path, &config.exclude, config.symlinks)?)?;
+    }
+
+    out.line(format!("Archived {} history entr{} from {} project(s) older than {older_than} days.",
+        report.entries_archived, if report.entries_archived == 1 { "y" } else { "ies" }, report.projects));
+    if rollup != compact::Rollup::Off {
+        out.line(format!("Added {} monthly summar{} to history.jsonl.", report.rollups, if report.rollups == 1 { "y" } else { "ies" }));
+    }
+    if report.rollup_fallbacks > 0 {
+        out.note(format!("{} AI summar{} failed and listed entry titles instead.", report.rollup_fallbacks, if report.rollup_fallbacks == 1 { "y" } else { "ies" }));
+    }
+    if compress {
+        out.line(format!("Compressed {} file(s): {} → {} ({} saved).",
+            report.files_compressed, human_bytes(report.bytes_before), human_bytes(report.bytes_after), human_bytes(report.bytes_saved())));
+    }
+    if report.projects > 0 {
+        out.note("Run `wardwell reindex` to refresh search over the rewritten history files.");
+    }
+    let mut fields = serde_json::to_value(&report)?;
+    fields["bytes_saved"] = report.bytes_saved().into();
+    out.result(fields);
+    Ok(())
+}
+
+fn run_vacuum(dry_run: bool, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+
+    let config = loader::load(None)?;
+    let dir = loader::config_dir();
+    let report = wardwell::install::vacuum::run(
+        &dir.join("index.db"), &dir.join("sessions.db"), &dir.join("summaries"),
+        &config.vault_path, &config.session_sources, dry_run,
+    )?;
+
+    let verb = if dry_run { "Would prune" } else { "Pruned" };
+    out.line(format!("{verb} {} index entr{} for deleted files.",
+        report.orphaned_paths.len(), if report.orphaned_paths.len() == 1 { "y" } else { "ies" }));
+    out.line(format!("{verb} {} summar{} of sessions that no longer exist ({}).",
+        report.orphaned_summaries.len(), if report.orphaned_summaries.len() == 1 { "y" } else { "ies" }, human_bytes(report.summary_bytes)));
+    if dry_run {
+        out.note("Dry run — nothing changed.");
+    } else {
+        out.line(format!("index.db: {} → {}; sessions.db: {} → {}.",
+            human_bytes(report.index_bytes_before), human_bytes(report.index_bytes_after),
+            human_bytes(report.sessions_bytes_before), human_bytes(report.sessions_bytes_after)));
+        out.line(format!("Reclaimed {}.", human_bytes(report.reclaimed())));
+    }
+    let mut fields = serde_json::to_value(&report)?;
+    fields["bytes_reclaimed"] = report.reclaimed().into();
+    out.result(fields);
+    Ok(())
+}
+
+fn run_prune(dry_run: bool, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+
+    let config = loader::load(None)?;
+    let Some(ref retention) = config.retention else {
+        return Err("no retention configured; set retention.summaries_days or retention.sessions_days in config.yml".into());
+    };
+    let dir = loader::config_dir();
+    let sessions = wardwell::daemon::indexer::SessionStore::open(&dir.join("sessions.db"))?;
+    let report = wardwell::daemon::retention::prune(retention, &dir.join("summaries"), &sessions, chrono::Utc::now(), dry_run)?;
+
+    let (summaries, sessions) = (report.summaries.len(), report.sessions.len());
+    let (verb, fate) = match (dry_run, report.archived) {
+        (true, true) => ("Would archive", "Would drop"),
+        (true, false) => ("Would delete", "Would drop"),
+        (false, true) => ("Archived", "Dropped"),
+        (false, false) => ("Deleted", "Dropped"),
+    };
+    if retention.summaries_days.is_some() {
+        out.line(format!("{verb} {summaries} expired summar{} ({}).", if summaries == 1 { "y" } else { "ies" }, human_bytes(report.summary_bytes)));
+    }
+    if retention.sessions_days.is_some() {
+        out.line(format!("{fate} {sessions} expired session{} from sessions.db.", if sessions == 1 { "" } else { "s" }));
+    }
+    if dry_run {
+        out.note("Dry run — nothing changed.");
+    }
+    out.result(serde_json::to_value(&report)?);
+    Ok(())
+}
+
+/// `1536` → `1.5 KB`.
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{bytes} B") } else { format!("{value:.1} {}", UNITS[unit]) }
+}
+
+fn run_backup_now(out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+
+    let config = loader::load(None)?;
+    let Some(ref backup) = config.backup else {
+        return Err("no backup configured — add a `backup:` block with `command:` to ~/.wardwell/config.yml".into());
+    };
+
+    out.note("wardwell: running backup");
+    let started = std::time::Instant::now();
+    wardwell::daemon::backup::run(backup, &config.vault_path, &loader::config_dir())?;
+    out.note("wardwell: backup completed");
+    out.result(serde_json::json!({ "seconds": started.elapsed().as_secs_f64() }));
+    Ok(())
+}
+
+fn run_links_check(urls: bool, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::daemon::links::{self, LinkKind};
+
+    let config = loader::load(None)?;
+    let check_url: Option<links::UrlCheck> = if urls { Some(&links::curl_head) } else { None };
+    let report = links::check_vault(&config.vault_path, &config.exclude, config.symlinks, check_url);
+    links::write_report(&loader::config_dir(), &report)?;
+
+    for b in &report.broken {
+        let kind = match b.kind {
+            LinkKind::Related => "related",
+            LinkKind::Url => "url",
+        };
+        out.warn(format!("  {:<7} {} → {} ({})", kind, b.file, b.target, b.reason));
+    }
+    let url_summary = if report.urls_skipped {
+        "URLs not checked (pass --urls)".to_string()
+    } else {
+        format!("{} URLs checked, {} broken", report.urls_checked, report.broken_count(LinkKind::Url))
+    };
+    out.note(format!(
+        "wardwell: {} files, {} related links checked, {} broken; {url_summary}",
+        report.files,
+        report.related_checked,
+        report.broken_count(LinkKind::Related),
+    ));
+    let report_path = links::report_path(&loader::config_dir());
+    out.note(format!("wardwell: report written to {}", report_path.display()));
+    let mut result = serde_json::to_value(&report)?;
+    result["report_path"] = serde_json::Value::from(report_path.to_string_lossy());
+    out.result(result);
+    Ok(())
+}
+
+fn run_dedupe(threshold: f64, domain: Option<&str>, limit: usize, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::index::store::IndexStore;
+
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!("--threshold must be between 0 and 1, got {threshold}").into());
+    }
+    let index = IndexStore::open(&loader::config_dir().join("index.db"))?;
+    let domains: Vec<String> = domain.into_iter().map(str::to_string).collect();
+    let mut pairs = index.duplicates(threshold, &domains)?;
+    let total = pairs.len();
+    pairs.truncate(limit);
+
+    for pair in &pairs {
+        out.line(format!("{:.0}%  {}  ↔  {}", pair.similarity * 100.0, pair.a, pair.b));
+        out.line(format!("      {}", pair.suggestion));
+    }
+    let shown = if total > pairs.len() { format!(", showing {}", pairs.len()) } else { String::new() };
+    out.note(format!("wardwell: {total} near-duplicate pair(s) at {:.0}% or more{shown}. Run `wardwell reindex` first if notes changed while nothing was watching.", threshold * 100.0));
+    out.result(serde_json::json!({ "threshold": threshold, "total": total, "pairs": pairs }));
+    Ok(())
+}
+
+fn run_verify(snapshot: bool, repair: bool, out: Output) -> Result<bool, Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::install::verify;
+
+    let config = loader::load(None)?;
+    if snapshot {
+        let manifest = verify::snapshot(&config.vault_path, &config.exclude, config.symlinks)?;
+        verify::save(&loader::config_dir(), &manifest)?;
+        out.line(format!("Recorded {} machine file(s) in ~/.wardwell/{}.", manifest.files.len(), verify::MANIFEST_FILE));
+        out.result(serde_json::json!({ "snapshot": true, "files": manifest.files.len() }));
+        return Ok(true);
+    }
+
+    let repairs = if repair { verify::repair(&config.vault_path, &config.exclude, config.symlinks)? } else { Vec::new() };
+    for fixed in &repairs {
+        out.line(format!("  repaired {}: {}", fixed.path, fixed.detail));
+    }
+    let manifest = verify::load(&loader::config_dir())?;
+    let report = verify::verify(&config.vault_path, &config.exclude, config.symlinks, manifest.as_ref());
+    match &manifest {
+        Some(m) => out.line(format!("Checked {} machine file(s) against the snapshot from {}: {} grew, {} new, {} moved.",
+            report.checked, m.created_at.get(..10).unwrap_or(&m.created_at), report.appended, report.untracked, report.moved)),
+        None => {
+            out.line(format!("Checked {} machine file(s) for malformed lines.", report.checked));
+            out.note("No snapshot yet: run `wardwell verify --snapshot` to also catch rewritten or missing files.");
+        }
+    }
+    for problem in &report.problems {
+        out.warn(format!("  \u{2717} {}: {}", problem.path, problem.detail));
+    }
+    if report.is_ok() {
+        out.line("  \u{2713} no problems found");
+    }
+    let mut result = serde_json::to_value(&report)?;
+    if repair {
+        result["repaired"] = serde_json::to_value(&repairs)?;
+    }
+    out.verdict(report.is_ok(), result);
+    Ok(report.is_ok())
+}
+
+fn run_metrics(days: u32, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::mcp::metrics::MetricsStore;
+
+    let config = loader::load(None)?;
+    let db = loader::config_dir().join("metrics.db");
+    if !config.metrics {
+        out.warn("wardwell: metrics are off. Set `metrics: true` in ~/.wardwell/config.yml to start counting (kept locally, never uploaded).");
+        if !db.exists() {
+            out.result(serde_json::json!({ "enabled": false }));
+            return Ok(());
+        }
+    }
+    let since = (chrono::Local::now().date_naive() - chrono::Days::new(u64::from(days.saturating_sub(1)))).to_string();
+    let report = MetricsStore::open(&db)?.report(&since)?;
+
+    out.line(format!("Usage since {since} ({days} days)"));
+    out.line(format!("  Tool calls: {} ({:.1}/day)", report.tool_calls, report.tool_calls as f64 / f64::from(days.max(1))));
+    let busiest = report.calls_per_day.values().copied().max().unwrap_or(0);
+    for (day, count) in &report.calls_per_day {
+        let bar = "█".repeat(((count * 30) / busiest.max(1)) as usize);
+        out.line(format!("    {day}  {count:>4}  {bar}"));
+    }
+    let mut actions: Vec<(&String, &i64)> = report.calls_by_action.iter().collect();
+    actions.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    if !actions.is_empty() {
+        out.line("  Top actions:");
+        for (action, count) in actions.iter().take(10) {
+            out.line(format!("    {action:<28} {count:>5}"));
+        }
+    }
+    if !report.searches.is_empty() {
+        out.line("  Search hit rate:");
+        for (action, counts) in &report.searches {
+            let rate = counts.hit_rate().map(|r| format!("{:.0}%", r * 100.0)).unwrap_or_else(|| "-".to_string());
+            out.line(format!("    {action:<28} {rate:>5}  ({} of {})", counts.hits, counts.hits + counts.misses));
+        }
+    }
+    if !report.syncs_by_project.is_empty() {
+        let mut syncs: Vec<(&String, &i64)> = report.syncs_by_project.iter().collect();
+        syncs.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        out.line("  Syncs per project:");
+        for (project, count) in syncs {
+            out.line(format!("    {project:<28} {count:>5}"));
+        }
+    }
+    if !report.latency.is_empty() {
+        let mut latency: Vec<_> = report.latency.iter().collect();
+        latency.sort_by(|a, b| b.1.p95_ms.cmp(&a.1.p95_ms).then(a.0.cmp(b.0)));
+        out.line("  Latency (p50 / p95):");
+        for (action, l) in latency.iter().take(10) {
+            let slow = if config.slow_action_ms > 0 && l.p95_ms >= config.slow_action_ms { "  slow" } else { "" };
+            out.line(format!("    {action:<28} {:>6} ms / {:>6} ms  ({} calls){slow}", l.p50_ms, l.p95_ms, l.calls));
+        }
+    }
+    let mut result = serde_json::to_value(&report)?;
+    result["enabled"] = serde_json::json!(config.metrics);
+    out.result(result);
+    Ok(())
+}
+
+fn run_disable() -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::install::killswitch;
+
+    if killswitch::disable()? {
+        eprintln!("wardwell: hooks disabled ({}). Run `wardwell enable` to turn them back on.", killswitch::sentinel_path().display());
+    } else {
+        eprintln!("wardwell: hooks already disabled");
+    }
+    Ok(())
+}
+
+fn run_enable() -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::install::killswitch;
+
+    if killswitch::enable()? {
+        eprintln!("wardwell: hooks enabled");
+    } else {
+        eprintln!("wardwell: hooks were not disabled");
+    }
+    if let Ok(v) = std::env::var(killswitch::DISABLE_ENV) {
+        eprintln!("wardwell: note: {}={v} is set in this environment", killswitch::DISABLE_ENV);
+    }
+    Ok(())
+}
+
+fn run_secret_set(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::IsTerminal;
+
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        eprint!("Value for {name}: ");
+        set_terminal_echo(false);
+    }
+    let mut value = String::new();
+    let read = std::io::stdin().read_line(&mut value);
+    if interactive {
+        set_terminal_echo(true);
+        eprintln!();
+    }
+    read?;
+
+    let value = value.trim_end_matches(['\n', '\r']);
+    if value.is_empty() {
+        return Err("empty value — nothing stored".into());
+    }
+    wardwell::config::secrets::set(name, value)?;
+    eprintln!("wardwell: stored secret '{name}'. Reference it in config.yml as {{secret:{name}}}");
+    Ok(())
+}
+
+/// Toggle terminal echo via `stty` so typed secrets aren't shown.
+fn set_terminal_echo(on: bool) {
+    let _ = std::process::Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(std::process::Stdio::inherit())
+        .status();
+}
+
+fn run_inject(cwd: &str, max_tokens: Option<usize>) -> Result<(), Box<dyn std::error::Error>> {
     use wardwell::config::loader;
 
     let config = loader::load(None)?;
@@ -254,6 +1220,29 @@ fn run_inject(cwd: &str) -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    let payload = wardwell::inject::hook::HookPayload::from_stdin().unwrap_or_default();
+    if payload.is_subagent() {
+        return Ok(());
+    }
+    if let Some(ref session_id) = payload.session_id {
+        let linked_cwd = payload.cwd.clone().unwrap_or_else(|| cwd.to_string());
+        if let Ok(store) = wardwell::daemon::indexer::SessionStore::open(&loader::config_dir().join("sessions.db")) {
+            let _ = store.link_session(session_id, &linked_cwd, payload.source.as_deref(), payload.model());
+        }
+    }
+
+    // Summarize this directory's recent sessions in the background, off the hook's clock
+    if config.ai.prewarm_sessions > 0
+        && let Ok(exe) = std::env::current_exe()
+    {
+        let _ = std::process::Command::new(exe)
+            .args(["prewarm", cwd])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+    }
+
     // Try to match cwd to a vault domain by checking if cwd directory name
     // matches a subdirectory of the vault
     let cwd_path = std::path::Path::new(cwd);
@@ -273,77 +1262,79 @@ fn run_inject(cwd: &str) -> Result<(), Box<dyn std::error::Error>> {
 
     if let Some(domain_dir) = matched_domain {
         // Found a matching domain — output its project summaries
-        inject_domain_context(&domain_dir);
+        let max_tokens = max_tokens.unwrap_or(config.inject_max_tokens);
+        print!("{}", wardwell::inject::context::domain_context(&domain_dir, max_tokens, &config.statuses));
+        let domain = domain_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if let Some(ref digest) = config.digest
+            && digest.covers(&domain)
+            && let Some(line) = wardwell::inject::context::digest_line(&domain_dir, &digest.subscription(&domain).dir, chrono::Local::now().date_naive())
+        {
+            print!("{line}");
+        }
+        if let Some(ref heartbeat) = config.heartbeat {
+            let today = chrono::Local::now().date_naive();
+            if let Some(block) = wardwell::inject::heartbeat::take(heartbeat, &loader::config_dir(), &domain_dir, today) {
+                print!("{block}");
+            }
+        }
     }
     // No match = no output. Don't pollute non-project sessions.
 
     Ok(())
 }
 
-/// Output context for a specific domain's projects.
-fn inject_domain_context(domain_dir: &Path) {
-    let domain = domain_dir.file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown");
-
-    // Check domain-level current_state.md
-    let state = domain_dir.join("current_state.md");
-    if state.exists()
-        && let Ok(content) = std::fs::read_to_string(&state)
-    {
-        print!("{content}");
-        return;
-    }
-
-    // Check subdirectory projects
-    if let Ok(entries) = std::fs::read_dir(domain_dir) {
-        for entry in entries.flatten() {
-            let p = entry.path();
-            if p.is_dir() {
-                let state = p.join("current_state.md");
-                if state.exists()
-                    && let Ok(vf) = wardwell::vault::reader::read_file(&state)
-                {
-                    let project = p.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown");
-                    let status = vf.frontmatter.status.as_ref()
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| "active".to_string());
-                    let focus = extract_section_simple(&vf.body, "Focus");
-                    let next = extract_section_simple(&vf.body, "Next Action");
-                    println!("**{domain}/{project}** ({status}): {focus}");
-                    if !next.is_empty() {
-                        println!("  Next: {next}");
-                    }
-                }
-            }
-        }
-    }
-}
+async fn run_prewarm(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
 
-/// Simple section extractor for inject (no dependency on server module).
-fn extract_section_simple(body: &str, heading: &str) -> String {
-    let marker = format!("## {heading}");
-    let start = match body.find(&marker) {
-        Some(pos) => pos + marker.len(),
-        None => return String::new(),
-    };
-    let rest = body[start..].trim_start();
-    let end = rest.find("\n## ").unwrap_or(rest.len());
-    rest[..end].trim().to_string()
+    let config = loader::load(None)?;
+    let cwd = std::fs::canonicalize(path).unwrap_or_else(|_| std::path::PathBuf::from(path));
+    let stats = wardwell::daemon::prewarm::run(&config, &loader::config_dir(), &cwd).await?;
+    eprintln!("wardwell: pre-warmed {} summaries ({} skipped){}", stats.summarized, stats.skipped,
+        if stats.resume_cached { ", resume cached" } else { "" });
+    Ok(())
 }
 
 
 fn run_resolve() -> Result<(), Box<dyn std::error::Error>> {
-    // No-op. Session logging is handled by CLAUDE.md behavioral rules.
-    // The hook entry is kept so wardwell can re-enable blocking if
-    // Claude Code adds a silent block mechanism.
+    use wardwell::config::loader;
+
+    // Never blocks: queued desktop intents are surfaced as a notice on the way out.
+    // Session logging itself is handled by CLAUDE.md behavioral rules.
+    let payload = wardwell::inject::hook::HookPayload::from_stdin().unwrap_or_default();
+    if payload.is_subagent() {
+        return Ok(());
+    }
+    let Ok(config) = loader::load(None) else {
+        return Ok(());
+    };
+    let cwd = payload.cwd.map(std::path::PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let Some(domain) = config.registry.resolve(&cwd) else {
+        return Ok(());
+    };
+    let domain = domain.name.as_str().to_string();
+    let Some(project) = wardwell::vault::project::project_from_cwd(&config.registry, &config.vault_path, &domain, &cwd) else {
+        return Ok(());
+    };
+    let history = std::fs::read_to_string(config.vault_path.join(&domain).join(&project).join("history.jsonl")).unwrap_or_default();
+    let queue = wardwell::inject::intents::pending(&history, config.intent_ttl_days, chrono::Utc::now());
+    if queue.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!("wardwell: {} desktop intent(s) for {domain}/{project} have no code session since:", queue.len());
+    for intent in &queue {
+        let detail = if intent.next_action.is_empty() { String::new() } else { format!(" — {}", intent.next_action) };
+        message.push_str(&format!("\n  {} {}{detail}", intent.date.get(..10).unwrap_or(&intent.date), intent.title));
+    }
+    message.push_str("\nSync what was done, or append_history with source: dismissed to clear the queue without syncing.");
+    println!("{}", serde_json::json!({ "systemMessage": message }));
     Ok(())
 }
 
 
-fn run_reindex() -> Result<(), Box<dyn std::error::Error>> {
+fn run_reindex(out: Output) -> Result<(), Box<dyn std::error::Error>> {
     use wardwell::config::loader;
     use wardwell::index::builder::IndexBuilder;
     use wardwell::index::store::IndexStore;
@@ -358,7 +1349,8 @@ fn run_reindex() -> Result<(), Box<dyn std::error::Error>> {
     index.clear()?;
 
     if !config.vault_path.exists() {
-        println!("Vault directory does not exist: {}", config.vault_path.display());
+        out.warn(format!("Vault directory does not exist: {}", config.vault_path.display()));
+        out.result(serde_json::json!({ "vault_exists": false, "indexed": 0, "skipped": 0, "errors": 0, "chunks_embedded": 0 }));
         return Ok(());
     }
 
@@ -366,29 +1358,175 @@ fn run_reindex() -> Result<(), Box<dyn std::error::Error>> {
     let models_dir = config_dir.join("models");
     let mut embedder = match wardwell::index::embed::Embedder::new(&models_dir) {
         Ok(e) => {
-            println!("Embedding model loaded.");
+            out.line("Embedding model loaded.");
             Some(e)
         }
         Err(e) => {
-            eprintln!("Embedding model unavailable (skipping vector index): {e}");
+            out.warn(format!("Embedding model unavailable (skipping vector index): {e}"));
             None
         }
     };
 
-    let stats = IndexBuilder::build_filtered(&index, &config.vault_path, &config.exclude, embedder.as_mut())?;
-    println!("Reindexed {} file(s) ({} skipped, {} error(s)).", stats.indexed, stats.skipped, stats.errors);
+    let stats = IndexBuilder::build_filtered(&index, &config.vault_path, &config.exclude, config.symlinks, &config.statuses, embedder.as_mut().map(|e| e as &mut dyn wardwell::index::embeddings::EmbeddingProvider))?;
+    out.line(format!("Reindexed {} file(s) ({} skipped, {} error(s)).", stats.indexed, stats.skipped, stats.errors));
     if stats.chunks_embedded > 0 {
-        println!("Embedded {} chunks.", stats.chunks_embedded);
+        out.line(format!("Embedded {} chunks.", stats.chunks_embedded));
     }
     for detail in &stats.error_details {
-        eprintln!("  error: {detail}");
+        out.warn(format!("  error: {detail}"));
     }
+    out.result(serde_json::json!({
+        "vault_exists": true,
+        "indexed": stats.indexed,
+        "skipped": stats.skipped,
+        "errors": stats.errors,
+        "chunks_embedded": stats.chunks_embedded,
+        "error_details": stats.error_details,
+    }));
     Ok(())
 }
 
-fn run_seed(target: &str) -> Result<(), Box<dyn std::error::Error>> {
+async fn run_summarize(
+    redo: bool,
+    project: Option<&str>,
+    since: Option<&str>,
+    model: Option<&str>,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     use wardwell::config::loader;
+    use wardwell::daemon::{indexer, summarizer};
 
+    if let Some(since) = since
+        && chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d").is_err()
+    {
+        return Err(format!("--since must be YYYY-MM-DD, got '{since}'").into());
+    }
+    let config = loader::load(None)?;
+    let config_dir = loader::config_dir();
+    let provider: std::sync::Arc<dyn summarizer::SummaryProvider> = summarizer::provider(&config.ai, model).into();
+    let model = provider.model();
+    let (domain, project) = match project.and_then(|p| p.split_once('/')) {
+        Some((d, p)) => (Some(d.to_string()), Some(p.to_string())),
+        None => (None, project.map(str::to_string)),
+    };
+
+    // Pick up sessions that finished since the daemon last ran
+    let store = indexer::SessionStore::open(&config_dir.join("sessions.db"))?;
+    indexer::index_sessions(&config.session_sources, &store, config.registry.all())?;
+
+    let filter = indexer::SessionFilter {
+        domain,
+        project,
+        since: since.map(str::to_string),
+        include_summarized: redo,
+    };
+    let sessions = store.sessions_matching(&filter)?;
+    if sessions.is_empty() {
+        println!("No matching sessions.");
+        return Ok(());
+    }
+
+    let estimate = summarizer::estimate_cost(&sessions, &config.session_sources, model, config.ai.summarize_min_signal);
+    let cost = estimate.usd.map(|usd| format!(", ~${usd:.2} at API list prices")).unwrap_or_default();
+    println!(
+        "{} matching session(s), {} to summarize with {model}: ~{}k input + ~{}k output tokens{cost}.",
+        sessions.len(),
+        estimate.sessions,
+        estimate.input_tokens.div_ceil(1000),
+        estimate.output_tokens.div_ceil(1000),
+    );
+    if dry_run {
+        for session in &sessions {
+            println!("  {}  {}", session.session_id, session.project_path);
+        }
+        return Ok(());
+    }
+
+    if redo {
+        let ids: Vec<String> = sessions.iter().map(|s| s.session_id.clone()).collect();
+        store.reset_sessions(&ids)?;
+    }
+    let stats = summarizer::summarize_sessions(
+        &store,
+        &sessions,
+        &config.session_sources,
+        &config_dir.join("summaries"),
+        &config.vault_path,
+        provider.clone(),
+        &config.ai,
+        true,
+        redo,
+    ).await?;
+    println!("Summarized {} session(s) ({} skipped, {} error(s)).", stats.summarized, stats.skipped, stats.errors);
+    if stats.proposed > 0 {
+        println!("Queued {} decision(s) for approval — approve them with `wardwell_write action: approve`.", stats.proposed);
+    }
+    if stats.breaker_open {
+        eprintln!("Stopped early after repeated {} failures. Remaining sessions are retried by `wardwell serve`.", provider.name());
+    }
+    Ok(())
+}
+
+fn run_diff(target: &str, from: &str, to: &str, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::vault::project::{resolve_project, ProjectMatch};
+    use wardwell::vault::timeline::{self, Point};
+
+    let Some((domain, project)) = target.split_once('/') else {
+        return Err(format!("expected domain/project, got '{target}'").into());
+    };
+    let (from, to) = (Point::parse(from)?, Point::parse(to)?);
+    let config = wardwell::config::loader::load(None)?;
+    let domain_dir = config.vault_path.join(domain);
+    let name = match resolve_project(&domain_dir, project) {
+        ProjectMatch::Exact(n) | ProjectMatch::Slug(n) | ProjectMatch::Alias(n) => n,
+        _ => return Err(format!("no project '{project}' in {}", domain_dir.display()).into()),
+    };
+    let diff = timeline::diff(&domain_dir.join(&name), &format!("{domain}/{name}"), &from, &to)?;
+    out.line(timeline::render_markdown(&diff).trim_end());
+    out.result(serde_json::to_value(&diff)?);
+    Ok(())
+}
+
+fn run_sessions(limit: usize, skipped_only: bool, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use wardwell::config::loader;
+    use wardwell::daemon::indexer;
+
+    let config = loader::load(None)?;
+    let store = indexer::SessionStore::open(&loader::config_dir().join("sessions.db"))?;
+    indexer::index_sessions(&config.session_sources, &store, config.registry.all())?;
+    let sessions = store.session_statuses(limit, skipped_only)?;
+
+    if sessions.is_empty() {
+        out.line(if skipped_only { "No skipped sessions." } else { "No sessions indexed yet." });
+    }
+    let now = chrono::Utc::now().to_rfc3339();
+    for session in &sessions {
+        let date = session.last_message_at.as_deref().and_then(|t| t.get(..10)).unwrap_or("?");
+        let project = session.project_path.trim_end_matches('/').rsplit('/').next().unwrap_or(&session.project_path);
+        let state = match (&session.skip_reason, session.summarized, &session.retry_after) {
+            (Some(reason), _, _) => format!("skipped — {reason}"),
+            (None, true, _) => "summarized".to_string(),
+            (None, false, Some(after)) if after.as_str() > now.as_str() => {
+                format!("retrying after {} ({} failed attempt(s))", after.get(..16).unwrap_or(after).replace('T', " "), session.summary_attempts)
+            }
+            (None, false, _) => "pending".to_string(),
+        };
+        out.line(format!("  {date}  {:<10} {:<24} {:>3} msg  {state}",
+            session.domain.as_deref().unwrap_or("-"), project, session.user_message_count));
+    }
+    out.result(serde_json::json!({ "sessions": sessions }));
+    Ok(())
+}
+
+fn run_seed(target: &str, var_args: &[String], template_name: Option<&str>, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::IsTerminal;
+    use wardwell::config::loader;
+    use wardwell::vault::project::ProjectMatch;
+    use wardwell::vault::{seed, template};
+
+    let user_vars = var_args.iter()
+        .map(|pair| template::parse_var(pair))
+        .collect::<Result<Vec<_>, _>>()?;
     let config = loader::load(None)?;
     let vault_path = &config.vault_path;
 
@@ -397,97 +1535,95 @@ fn run_seed(target: &str) -> Result<(), Box<dyn std::error::Error>> {
 
     if parts.len() == 1 {
         // Bare domain — just create the directory
+        if template_name.is_some() {
+            out.warn("--template only applies when seeding a project (domain/project); ignored");
+        }
         let domain_dir = vault_path.join(domain);
         std::fs::create_dir_all(&domain_dir)?;
-        println!("{domain}/: domain directory ready");
+        out.line(format!("{domain}/: domain directory ready"));
+        let mut projects = Vec::new();
         if let Ok(entries) = std::fs::read_dir(&domain_dir) {
             for entry in entries.flatten() {
                 if entry.path().is_dir() {
                     let name = entry.file_name().to_string_lossy().to_string();
                     let has_state = entry.path().join("current_state.md").exists();
                     let marker = if has_state { "\u{2713}" } else { " " };
-                    println!("  [{marker}] {name}");
+                    out.line(format!("  [{marker}] {name}"));
+                    projects.push(serde_json::json!({ "name": name, "has_state": has_state }));
                 }
             }
         }
+        out.result(serde_json::json!({ "path": domain, "kind": "domain", "created": true, "projects": projects }));
         return Ok(());
     }
 
-    let project = parts[1];
-    let project_dir = vault_path.join(domain).join(project);
-
-    if project_dir.exists() {
-        eprintln!("Project already exists at {domain}/{project}/");
+    // "Café Menü" → "cafe-menu", matching an existing folder the way sync would
+    let requested = parts[1].trim();
+    let existing = match wardwell::vault::project::resolve_project(&vault_path.join(domain), requested) {
+        ProjectMatch::Exact(name) | ProjectMatch::Slug(name) | ProjectMatch::Alias(name) => Some(name),
+        ProjectMatch::Ambiguous(names) => return Err(format!("'{requested}' matches several project folders in {domain}: {}", names.join(", ")).into()),
+        ProjectMatch::Fuzzy { .. } | ProjectMatch::New => None,
+    };
+    if let Some(project) = existing {
+        out.warn(format!("Project already exists at {domain}/{project}/"));
+        out.result(serde_json::json!({ "path": format!("{domain}/{project}"), "kind": "project", "created": false, "reason": "already exists" }));
         return Ok(());
     }
 
-    let title = slug_to_title(project);
-    let now = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let slug = wardwell::vault::project::slugify_with(requested, config.slug_transliteration);
+    if slug.is_empty() {
+        return Err(format!("'{requested}' has no letters or digits to name a project folder").into());
+    }
+    let project = if requested.chars().all(|c| c.is_ascii_graphic()) { requested } else { slug.as_str() };
+    let project_dir = vault_path.join(domain).join(project);
     let rel = format!("{domain}/{project}");
+    if project != requested {
+        out.note(format!("  Using folder name '{project}' for '{requested}'"));
+    }
 
-    std::fs::create_dir_all(&project_dir)?;
-    println!("  Creating  {rel}/                {:>width$}", "\u{2713}", width = 40_usize.saturating_sub(rel.len() + 12));
-
-    // INDEX.md
-    let index_path = project_dir.join("INDEX.md");
-    std::fs::write(&index_path, format!("\
-# {title}
-
-## What
-(one sentence — what is this)
-
-## Why
-(one sentence — why does this matter)
-
-## Links
-(related vault files, external URLs)
-"))?;
-    println!("  Writing   {rel}/INDEX.md         \u{2713}");
-
-    // current_state.md
-    let state_path = project_dir.join("current_state.md");
-    std::fs::write(&state_path, format!("\
----
-chat_name: {project}
-updated: {now}
-status: active
-type: project
-context: {domain}
----
-
-# {title}
-
-## Focus
-(what are you working on right now)
-
-## Next Action
-(single concrete next step)
+    // --template, else the domain file's `## Seed Template`, else the top-level templates
+    let template_name = template_name.map(String::from).or_else(|| {
+        config.registry.all().iter().find(|d| d.name.as_str() == domain).and_then(|d| d.seed_template.clone())
+    });
+    let templates = match template_name {
+        Some(ref name) => seed::SeedTemplates::named(&loader::config_dir(), name)?,
+        None => seed::SeedTemplates::load(&loader::config_dir()),
+    };
+    let mut vars = seed::builtin_vars(domain, project);
+    if project != requested {
+        vars.insert("title".to_string(), wardwell::vault::project::title_from_name(requested));
+    }
+    if let Some(remote) = std::env::current_dir().ok().and_then(|dir| seed::git_remote(&dir)) {
+        vars.insert("git_remote".to_string(), remote);
+    }
+    vars.extend(user_vars);
+
+    let mut missing: Vec<String> = templates.variables().into_iter().filter(|name| !vars.contains_key(name)).collect();
+    if !missing.is_empty() {
+        if out.is_human() && std::io::stdin().is_terminal() {
+            for name in missing.drain(..) {
+                eprint!("  {name}: ");
+                let mut value = String::new();
+                std::io::stdin().read_line(&mut value)?;
+                vars.insert(name, value.trim().to_string());
+            }
+        } else {
+            let names: Vec<String> = missing.iter().map(|n| format!("{{{{{n}}}}}")).collect();
+            out.warn(format!("wardwell: no value for {} — left in place (pass --var NAME=VALUE)", names.join(", ")));
+        }
+    }
 
-## Commit Message
-Seeded by wardwell
-"))?;
-    println!("  Writing   {rel}/current_state.md \u{2713}");
+    let written = seed::write_seed_files(&project_dir, &templates, &vars)?;
+    out.line(format!("  Creating  {rel}/                {:>width$}", "\u{2713}", width = 40_usize.saturating_sub(rel.len() + 12)));
+    for file in &written {
+        out.line(format!("  Writing   {rel}/{file:<16} \u{2713}"));
+    }
 
-    println!("\n  Done. Fill in the placeholders in INDEX.md and current_state.md.");
+    out.line("\n  Done. Fill in the placeholders in INDEX.md and current_state.md.");
+    out.result(serde_json::json!({ "path": rel, "kind": "project", "created": true, "template": template_name, "files": written, "unfilled": missing }));
     Ok(())
 }
 
-fn slug_to_title(slug: &str) -> String {
-    slug.split('-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                Some(c) => {
-                    let mut s = c.to_uppercase().to_string();
-                    s.extend(chars);
-                    s
-                }
-                None => String::new(),
-            }
-        })
-        .collect::<Vec<_>>()
-        .join(" ")
-}
 
 fn run_migrate_attachments() -> Result<(), Box<dyn std::error::Error>> {
     use wardwell::config::loader;
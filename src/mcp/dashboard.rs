@@ -0,0 +1,168 @@
+use crate::mcp::server::WardwellServer;
+use http_body_util::{BodyExt, Full, combinators::BoxBody};
+use hyper::body::Bytes;
+use hyper::{Method, Request, Response};
+use std::convert::Infallible;
+
+/// The page at `/`. Static: it fetches `/api/dashboard` on load and `/api/search` from the
+/// search box, and renders both with textContent, so vault text is never parsed as HTML.
+const PAGE: &str = r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>wardwell</title>
+<style>
+  body { font: 15px/1.45 system-ui, sans-serif; max-width: 60rem; margin: 0 auto; padding: 1rem; color: #222; }
+  h1 { font-size: 1.3rem; } h2 { font-size: 1.05rem; margin-top: 1.6rem; border-bottom: 1px solid #ddd; }
+  ul { list-style: none; padding: 0; } li { padding: .3rem 0; border-bottom: 1px solid #f0f0f0; }
+  .meta { color: #777; font-size: .85rem; } .quiet { color: #b45309; } .empty { color: #999; }
+  form { display: flex; gap: .5rem; } input { flex: 1; padding: .4rem; font: inherit; }
+</style>
+</head>
+<body>
+<h1>wardwell <span class="meta" id="now"></span></h1>
+<form id="search"><input name="q" placeholder="Search the vault" autocomplete="off"><button>Search</button></form>
+<ul id="results"></ul>
+<h2>Up next</h2><ul id="queue"></ul>
+<h2>Blocked</h2><ul id="blocked"></ul>
+<h2>Stale threads</h2><ul id="stale"></ul>
+<h2>Recent history</h2><ul id="recent"></ul>
+<script>
+function fill(id, items, render) {
+  const list = document.getElementById(id);
+  list.replaceChildren();
+  if (!items || !items.length) {
+    const li = document.createElement("li"); li.className = "empty"; li.textContent = "Nothing here."; list.append(li); return;
+  }
+  for (const item of items) {
+    const [title, detail, meta, cls] = render(item);
+    const li = document.createElement("li");
+    const b = document.createElement("b"); b.textContent = title; li.append(b);
+    if (detail) li.append(": " + detail);
+    if (meta) { const s = document.createElement("div"); s.className = "meta" + (cls ? " " + cls : ""); s.textContent = meta; li.append(s); }
+    list.append(li);
+  }
+}
+const key = e => e.domain + "/" + e.project;
+// Behind a token, open the page as /?token=<token>; the JSON requests send it as a header
+const token = new URLSearchParams(location.search).get("token");
+const get = url => fetch(url, token ? {headers: {Authorization: "Bearer " + token}} : {});
+get("api/dashboard").then(r => r.json()).then(d => {
+  document.getElementById("now").textContent = d.now || "";
+  fill("queue", d.queue, e => [key(e), e.next_action || e.focus, e.status + " · updated " + e.updated + (e.effort && e.effort.neglected ? " · gone quiet" : ""), e.effort && e.effort.neglected ? "quiet" : ""]);
+  fill("blocked", d.blocked, e => [key(e), e.focus, "updated " + e.updated]);
+  fill("stale", d.stale, e => [e.project, null, "last entry " + e.last_entry + " (" + e.days_stale + " days)"]);
+  fill("recent", d.recent, e => [key(e), e.title, e.date]);
+});
+document.getElementById("search").addEventListener("submit", ev => {
+  ev.preventDefault();
+  const q = new FormData(ev.target).get("q").trim();
+  if (!q) { document.getElementById("results").replaceChildren(); return; }
+  get("api/search?q=" + encodeURIComponent(q)).then(r => r.json()).then(d => {
+    if (d.error) { fill("results", [{path: d.message}], e => [e.path]); return; }
+    fill("results", d.results, e => [e.path, e.frontmatter && e.frontmatter.summary, e.snippet]);
+  });
+});
+</script>
+</body>
+</html>
+"#;
+
+/// Read-only pages served next to MCP on the HTTP transport, for people and devices without
+/// an AI client: the page at `/`, and the JSON it reads from `/api/dashboard` and
+/// `/api/search?q=`. Everything comes from the same search actions MCP clients use.
+#[derive(Clone)]
+pub struct Dashboard {
+    server: WardwellServer,
+}
+
+impl Dashboard {
+    pub fn new(server: WardwellServer) -> Self {
+        Dashboard { server }
+    }
+
+    /// The response to a dashboard route, or None for any other request (left to MCP). The
+    /// JSON routes read the vault on a blocking thread, off the connection's task.
+    pub async fn respond<B>(&self, request: &Request<B>) -> Option<Response<BoxBody<Bytes, Infallible>>> {
+        if request.method() != Method::GET {
+            return None;
+        }
+        let uri = request.uri();
+        let server = self.server.clone();
+        let read = match uri.path() {
+            "/" | "/index.html" => return Some(reply("text/html; charset=utf-8", PAGE.to_string())),
+            "/api/dashboard" => tokio::task::spawn_blocking(move || server.dashboard()),
+            "/api/search" => {
+                let query = uri.query().and_then(|q| param(q, "q")).unwrap_or_default();
+                tokio::task::spawn_blocking(move || server.dashboard_search(&query))
+            }
+            _ => return None,
+        };
+        Some(match read.await {
+            Ok(value) => json(&value),
+            Err(e) => {
+                let mut response = json(&serde_json::json!({ "error": format!("dashboard read failed: {e}") }));
+                *response.status_mut() = hyper::StatusCode::INTERNAL_SERVER_ERROR;
+                response
+            }
+        })
+    }
+}
+
+/// The `token` query parameter, which the page's own URL carries: a browser opening it
+/// can't send a header.
+pub(crate) fn query_token<B>(request: &Request<B>) -> Option<String> {
+    request.uri().query().and_then(|q| param(q, "token"))
+}
+
+fn json(value: &serde_json::Value) -> Response<BoxBody<Bytes, Infallible>> {
+    reply("application/json", value.to_string())
+}
+
+fn reply(content_type: &str, body: String) -> Response<BoxBody<Bytes, Infallible>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)).boxed());
+    if let Ok(value) = content_type.parse() {
+        response.headers_mut().insert(hyper::header::CONTENT_TYPE, value);
+    }
+    response
+}
+
+/// The decoded value of `name` in a query string: `+` is a space, `%XX` a byte.
+fn param(query: &str, name: &str) -> Option<String> {
+    let raw = query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))?;
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    Some(String::from_utf8_lossy(&out).into_owned())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn param_decodes_plus_and_percent_escapes() {
+        assert_eq!(param("x=1&q=sentry+bot%2Fauth", "q").as_deref(), Some("sentry bot/auth"));
+        assert_eq!(param("q=caf%C3%A9", "q").as_deref(), Some("café"));
+        assert_eq!(param("q=100%", "q").as_deref(), Some("100%"));
+        assert_eq!(param("query=x", "q"), None);
+    }
+}
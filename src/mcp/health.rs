@@ -0,0 +1,150 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// History entries newer than this many days count toward a project's velocity.
+pub const VELOCITY_WINDOW_DAYS: i64 = 14;
+/// Days without a current_state.md update before a project counts as stale.
+const STALE_AFTER_DAYS: i64 = 7;
+/// Days at which staleness and blocked time stop adding to the score.
+const CAP_DAYS: i64 = 30;
+
+/// What orchestrate knows about a project's health.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Signals {
+    /// Days since current_state.md was last updated.
+    pub days_since_update: Option<i64>,
+    /// Days the project has been blocked, from the run of blocked history entries it ends
+    /// with (or its last update); None when it isn't blocked.
+    pub blocked_days: Option<i64>,
+    /// Bullets under `## Open Questions` in current_state.md.
+    pub open_questions: usize,
+    /// History entries in the last `VELOCITY_WINDOW_DAYS` days.
+    pub recent_entries: usize,
+}
+
+/// How much a project needs attention, 0 (none) to 100, and why.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Health {
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+/// Staleness and blocked time count up to 30 points each, open questions 4 apiece up to 20,
+/// and a quiet history up to 20: none in the window scores 20, one entry 10.
+pub fn score(s: &Signals) -> Health {
+    let mut points = 0.0;
+    let mut reasons = Vec::new();
+    if let Some(days) = s.days_since_update
+        && days > STALE_AFTER_DAYS
+    {
+        points += 30.0 * ((days - STALE_AFTER_DAYS).min(CAP_DAYS - STALE_AFTER_DAYS) as f64 / (CAP_DAYS - STALE_AFTER_DAYS) as f64);
+        reasons.push(format!("not updated in {days} days"));
+    }
+    if let Some(days) = s.blocked_days {
+        points += 10.0 + 20.0 * (days.clamp(0, CAP_DAYS) as f64 / CAP_DAYS as f64);
+        reasons.push(match days {
+            0 => "blocked since today".to_string(),
+            1 => "blocked for 1 day".to_string(),
+            d => format!("blocked for {d} days"),
+        });
+    }
+    if s.open_questions > 0 {
+        points += 4.0 * s.open_questions.min(5) as f64;
+        reasons.push(format!("{} open question{}", s.open_questions, if s.open_questions == 1 { "" } else { "s" }));
+    }
+    match s.recent_entries {
+        0 => {
+            points += 20.0;
+            reasons.push(format!("no history in the last {VELOCITY_WINDOW_DAYS} days"));
+        }
+        1 => {
+            points += 10.0;
+            reasons.push(format!("1 history entry in the last {VELOCITY_WINDOW_DAYS} days"));
+        }
+        _ => {}
+    }
+    Health { score: points.round() as u32, reasons }
+}
+
+/// How orchestrate's `sort` parameter orders projects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sort {
+    /// Highest score first.
+    Score,
+    /// Least recently updated first.
+    Updated,
+    /// Blocked, then active, then the rest, highest score first within each.
+    Status,
+}
+
+impl Sort {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "score" => Some(Self::Score),
+            "updated" => Some(Self::Updated),
+            "status" => Some(Self::Status),
+            _ => None,
+        }
+    }
+
+    /// Reorder orchestrate entries carrying `score`, `updated` and `status`. Stable, so
+    /// ties keep their current order.
+    pub fn apply(self, entries: &mut [Value]) {
+        let score = |e: &Value| e["score"].as_u64().unwrap_or(0);
+        match self {
+            Self::Score => entries.sort_by_key(|e| std::cmp::Reverse(score(e))),
+            // Undated entries sort as oldest
+            Self::Updated => entries.sort_by(|a, b| a["updated"].as_str().unwrap_or("").cmp(b["updated"].as_str().unwrap_or(""))),
+            Self::Status => entries.sort_by_key(|e| {
+                let rank = match e["status"].as_str() {
+                    Some("blocked") => 0,
+                    Some("active") => 1,
+                    _ => 2,
+                };
+                (rank, std::cmp::Reverse(score(e)))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_adds_up_signals_and_says_why() {
+        let healthy = score(&Signals { days_since_update: Some(2), recent_entries: 4, ..Default::default() });
+        assert_eq!(healthy, Health::default());
+
+        let stuck = score(&Signals { days_since_update: Some(40), blocked_days: Some(15), open_questions: 7, recent_entries: 0 });
+        assert_eq!(stuck.score, 30 + 20 + 20 + 20);
+        assert_eq!(stuck.reasons, [
+            "not updated in 40 days", "blocked for 15 days", "7 open questions", "no history in the last 14 days",
+        ]);
+
+        let quiet = score(&Signals { days_since_update: Some(0), open_questions: 1, recent_entries: 1, ..Default::default() });
+        assert_eq!((quiet.score, quiet.reasons.len()), (14, 2));
+    }
+
+    #[test]
+    fn sorts_by_score_updated_or_status() {
+        let entry = |project: &str, status: &str, updated: &str, score: u32| {
+            serde_json::json!({ "project": project, "status": status, "updated": updated, "score": score })
+        };
+        let order = |sort: Sort| {
+            let mut entries = vec![
+                entry("api", "active", "2026-01-15", 40),
+                entry("site", "blocked", "2026-03-05", 20),
+                entry("docs", "active", "2026-02-01", 60),
+            ];
+            sort.apply(&mut entries);
+            entries.iter().map(|e| e["project"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(order(Sort::Score), ["docs", "api", "site"]);
+        assert_eq!(order(Sort::Updated), ["api", "docs", "site"]);
+        assert_eq!(order(Sort::Status), ["site", "docs", "api"]);
+        assert_eq!(Sort::parse(" Updated"), Some(Sort::Updated));
+        assert_eq!(Sort::parse("priority"), None);
+    }
+}
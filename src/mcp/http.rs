@@ -0,0 +1,245 @@
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use crate::config::loader::HttpConfig;
+use crate::config::secrets::SecretError;
+use crate::mcp::dashboard::Dashboard;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{AUTHORIZATION, HOST, ORIGIN, WWW_AUTHENTICATE};
+use hyper::{Method, Request, Response, StatusCode};
+use rmcp::{RoleServer, Service};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Default port for `wardwell serve --transport http`.
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Which requests the HTTP transport answers. A browser sends a page's requests wherever
+/// the page's own name resolves, so a site that rebinds its name to 127.0.0.1 could drive
+/// the server from any tab; such requests carry that site's `Host` and `Origin`, and are
+/// turned away. With a token, every request must carry it, the dashboard's included.
+#[derive(Debug, Clone, Default)]
+pub struct Access {
+    token: Option<String>,
+    allowed_origins: Vec<String>,
+    allowed_hosts: Vec<String>,
+}
+
+impl Access {
+    /// Fails if the token is a `{secret:NAME}` reference that can't be resolved.
+    pub fn new(config: &HttpConfig) -> Result<Self, SecretError> {
+        Ok(Self {
+            token: config.token.as_deref().map(crate::config::secrets::resolve).transpose()?,
+            allowed_origins: config.allowed_origins.iter().map(|o| o.trim_end_matches('/').to_lowercase()).collect(),
+            allowed_hosts: config.allowed_hosts.iter().map(|h| h.to_lowercase()).collect(),
+        })
+    }
+
+    /// Why `request` is refused, or None to let it through.
+    fn refusal<B>(&self, request: &Request<B>) -> Option<(StatusCode, &'static str)> {
+        let header = |name| request.headers().get(name).and_then(|v| v.to_str().ok());
+        if let Some(origin) = header(ORIGIN)
+            && !self.origin_allowed(origin)
+        {
+            return Some((StatusCode::FORBIDDEN, "origin not allowed; add it to http.allowed_origins"));
+        }
+        let Some(ref token) = self.token else {
+            return match header(HOST) {
+                Some(host) if self.host_allowed(host) => None,
+                _ => Some((StatusCode::FORBIDDEN, "host not allowed; add it to http.allowed_hosts")),
+            };
+        };
+        // A browser opening the page can only pass the token in its URL
+        let page = request.method() == Method::GET && matches!(request.uri().path(), "/" | "/index.html");
+        let given = header(AUTHORIZATION).and_then(|v| v.strip_prefix("Bearer ")).map(str::to_string)
+            .or_else(|| page.then(|| crate::mcp::dashboard::query_token(request)).flatten());
+        match given {
+            Some(given) if same_token(&given, token) => None,
+            _ => Some((StatusCode::UNAUTHORIZED, "missing or wrong bearer token")),
+        }
+    }
+
+    /// Loopback origins, and the configured ones.
+    fn origin_allowed(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/').to_lowercase();
+        let host = origin.strip_prefix("http://").or_else(|| origin.strip_prefix("https://"));
+        host.is_some_and(|h| is_loopback(strip_port(h))) || self.allowed_origins.contains(&origin)
+    }
+
+    /// Loopback names, IP addresses (which no other site's name can stand for), and the
+    /// configured names, with or without a port.
+    fn host_allowed(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        let name = strip_port(&host);
+        is_loopback(name) || name.parse::<IpAddr>().is_ok() || self.allowed_hosts.iter().any(|h| *h == host || h == name)
+    }
+}
+
+fn strip_port(host: &str) -> &str {
+    match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => host.split(':').next().unwrap_or(host),
+    }
+}
+
+fn is_loopback(name: &str) -> bool {
+    name == "localhost" || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Compare tokens without stopping at the first differing byte.
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn refuse(status: StatusCode, message: &str) -> Response<http_body_util::combinators::BoxBody<Bytes, Infallible>> {
+    let mut response = Response::new(Full::new(Bytes::from(format!("{{\"error\":\"{message}\"}}"))).boxed());
+    *response.status_mut() = status;
+    if status == StatusCode::UNAUTHORIZED {
+        response.headers_mut().insert(WWW_AUTHENTICATE, hyper::header::HeaderValue::from_static("Bearer"));
+    }
+    response
+}
+
+/// Serve MCP over streamable HTTP (JSON responses and SSE streams) on `listener`. Each client
+/// session gets its own handler from `make`; they all share whatever state `make` clones.
+/// With a `dashboard`, its pages answer the GET requests they cover and MCP gets the rest.
+/// Requests `access` refuses get a 403 or 401. Runs until accepting a connection fails.
+pub async fn serve<S>(
+    listener: TcpListener,
+    make: impl Fn() -> S + Send + Sync + 'static,
+    dashboard: Option<Dashboard>,
+    access: Access,
+) -> std::io::Result<()>
+where
+    S: Service<RoleServer> + Send + 'static,
+{
+    let service = StreamableHttpService::new(
+        move || Ok(make()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+    let access = Arc::new(access);
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let (service, dashboard, access) = (service.clone(), dashboard.clone(), access.clone());
+        let connection = hyper::service::service_fn(move |request: Request<Incoming>| {
+            let (service, dashboard, access) = (service.clone(), dashboard.clone(), access.clone());
+            async move {
+                if let Some((status, message)) = access.refusal(&request) {
+                    eprintln!("[WARDWELL ACL] DENIED: http {} {} from {peer}: {message}", request.method(), request.uri().path());
+                    return Ok::<_, Infallible>(refuse(status, message));
+                }
+                let page = match dashboard.as_ref() {
+                    Some(d) => d.respond(&request).await,
+                    None => None,
+                };
+                Ok(match page {
+                    Some(page) => page,
+                    None => service.handle(request).await,
+                })
+            }
+        });
+        tokio::spawn(async move {
+            let io = hyper_util::rt::TokioIo::new(stream);
+            if let Err(e) = hyper::server::conn::http1::Builder::new().serve_connection(io, connection).await {
+                eprintln!("wardwell: http connection from {peer} ended with an error: {e}");
+            }
+        });
+    }
+}
+
+/// Bind the HTTP listener. An address other machines can reach is refused unless `access`
+/// has a token, since anyone who reaches it could otherwise read and write the vault.
+pub async fn bind(addr: SocketAddr, access: &Access) -> std::io::Result<TcpListener> {
+    if !addr.ip().is_loopback() && access.token.is_none() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("refusing to serve {addr} without a token; set http.token in config.yml or bind 127.0.0.1"),
+        ));
+    }
+    TcpListener::bind(addr).await
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::mcp::safe_mode::{SafeModeServer, StartupRecord};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn initialize_over_http() {
+        let listener = bind("127.0.0.1:0".parse().unwrap(), &Access::default()).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handler = SafeModeServer::new(std::env::temp_dir(), StartupRecord::default());
+        tokio::spawn(serve(listener, move || handler.clone(), None, Access::default()));
+
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#;
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nAccept: application/json, text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = vec![0u8; 8192];
+        let mut read = 0;
+        // SSE keeps the stream open; stop once the initialize result has arrived
+        while read < response.len() {
+            let n = tokio::time::timeout(std::time::Duration::from_secs(5), stream.read(&mut response[read..])).await.unwrap().unwrap();
+            read += n;
+            if n == 0 || String::from_utf8_lossy(&response[..read]).contains("serverInfo") {
+                break;
+            }
+        }
+        let response = String::from_utf8_lossy(&response[..read]);
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.to_lowercase().contains("mcp-session-id"), "{response}");
+        assert!(response.contains("serverInfo"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn foreign_hosts_and_origins_are_refused() {
+        let request = |path: &str, headers: &[(&str, &str)]| {
+            let mut builder = Request::builder().method(Method::GET).uri(path);
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            builder.body(()).unwrap()
+        };
+        let status = |access: &Access, req: Request<()>| access.refusal(&req).map(|(s, _)| s.as_u16());
+
+        let local = Access::default();
+        for host in ["127.0.0.1:8765", "localhost:8765", "[::1]:8765", "192.168.1.20:8765"] {
+            assert_eq!(status(&local, request("/mcp", &[("host", host)])), None, "{host}");
+        }
+        // A page that rebound its own name to 127.0.0.1
+        assert_eq!(status(&local, request("/mcp", &[("host", "evil.example:8765")])), Some(403));
+        assert_eq!(status(&local, request("/mcp", &[("host", "127.0.0.1:8765"), ("origin", "http://evil.example")])), Some(403));
+        assert_eq!(status(&local, request("/mcp", &[("host", "127.0.0.1:8765"), ("origin", "http://localhost:3000")])), None);
+        assert_eq!(status(&local, request("/mcp", &[])), Some(403));
+
+        let configured = Access::new(&HttpConfig {
+            allowed_origins: vec!["https://claude.ai/".to_string()],
+            allowed_hosts: vec!["studio.lan".to_string()],
+            ..HttpConfig::default()
+        }).unwrap();
+        assert_eq!(status(&configured, request("/mcp", &[("host", "studio.lan:8765"), ("origin", "https://claude.ai")])), None);
+
+        let remote = Access::new(&HttpConfig { token: Some("s3cret".to_string()), ..HttpConfig::default() }).unwrap();
+        assert_eq!(status(&remote, request("/mcp", &[("host", "studio.lan:8765")])), Some(401));
+        assert_eq!(status(&remote, request("/mcp", &[("host", "studio.lan:8765"), ("authorization", "Bearer nope")])), Some(401));
+        assert_eq!(status(&remote, request("/api/search", &[("host", "studio.lan:8765"), ("authorization", "Bearer s3cret")])), None);
+        assert_eq!(status(&remote, request("/", &[("host", "studio.lan:8765")])), Some(401));
+        assert_eq!(status(&remote, request("/?token=nope", &[("host", "studio.lan:8765")])), Some(401));
+        assert_eq!(status(&remote, request("/?token=s3cret", &[("host", "studio.lan:8765")])), None);
+        // Only the page takes the token from its URL
+        assert_eq!(status(&remote, request("/api/dashboard?token=s3cret", &[("host", "studio.lan:8765")])), Some(401));
+        assert_eq!(status(&local, request("/", &[("host", "evil.example:8765")])), Some(403));
+
+        assert!(bind("0.0.0.0:0".parse().unwrap(), &local).await.is_err());
+        assert!(bind("0.0.0.0:0".parse().unwrap(), &remote).await.is_ok());
+    }
+}
@@ -0,0 +1,153 @@
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+use rmcp::service::Peer;
+use rmcp::RoleServer;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Messages kept from before the client connects, flushed once it does.
+const MAX_PENDING: usize = 50;
+
+/// Log sink for background tasks. Every message goes to stderr as before; once a client
+/// is attached, messages at or above the current level are also pushed to it as MCP
+/// `notifications/message`. The level starts at `log_level` from config and follows
+/// the client's `logging/setLevel`.
+#[derive(Clone)]
+pub struct McpLog {
+    inner: Arc<Mutex<LogState>>,
+}
+
+struct LogState {
+    peer: Option<Peer<RoleServer>>,
+    min_level: LoggingLevel,
+    pending: VecDeque<LoggingMessageNotificationParam>,
+}
+
+impl McpLog {
+    pub fn new(min_level: LoggingLevel) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LogState { peer: None, min_level, pending: VecDeque::new() })),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, LogState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn level(&self) -> LoggingLevel {
+        self.state().min_level
+    }
+
+    pub fn set_level(&self, level: LoggingLevel) {
+        self.state().min_level = level;
+    }
+
+    /// Start forwarding to a connected client, flushing messages logged before the handshake.
+    pub fn attach(&self, peer: Peer<RoleServer>) {
+        let mut state = self.state();
+        let min = state.min_level;
+        let backlog: Vec<LoggingMessageNotificationParam> = state.pending.drain(..).filter(|p| enabled(p.level, min)).collect();
+        if !backlog.is_empty()
+            && let Ok(handle) = tokio::runtime::Handle::try_current()
+        {
+            // One task, so the backlog arrives in the order it was logged
+            let backlog_peer = peer.clone();
+            handle.spawn(async move {
+                for param in backlog {
+                    let _ = backlog_peer.notify_logging_message(param).await;
+                }
+            });
+        }
+        state.peer = Some(peer);
+    }
+
+    pub fn log(&self, level: LoggingLevel, logger: &str, message: &str) {
+        eprintln!("wardwell: {message}");
+        let param = LoggingMessageNotificationParam {
+            level,
+            logger: Some(logger.to_string()),
+            data: serde_json::Value::String(message.to_string()),
+        };
+        let mut state = self.state();
+        match state.peer {
+            Some(ref peer) if enabled(level, state.min_level) => send(peer, param),
+            Some(_) => {}
+            None => {
+                if state.pending.len() >= MAX_PENDING {
+                    state.pending.pop_front();
+                }
+                state.pending.push_back(param);
+            }
+        }
+    }
+
+    pub fn info(&self, logger: &str, message: &str) {
+        self.log(LoggingLevel::Info, logger, message);
+    }
+
+    pub fn warning(&self, logger: &str, message: &str) {
+        self.log(LoggingLevel::Warning, logger, message);
+    }
+
+    pub fn error(&self, logger: &str, message: &str) {
+        self.log(LoggingLevel::Error, logger, message);
+    }
+
+    #[cfg(test)]
+    fn pending_len(&self) -> usize {
+        self.state().pending.len()
+    }
+}
+
+/// Fire-and-forget: a slow or gone client must never stall the task that logged.
+fn send(peer: &Peer<RoleServer>, param: LoggingMessageNotificationParam) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let peer = peer.clone();
+    handle.spawn(async move {
+        let _ = peer.notify_logging_message(param).await;
+    });
+}
+
+/// Syslog severity order, as used by the MCP spec.
+fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+fn enabled(level: LoggingLevel, min: LoggingLevel) -> bool {
+    severity(level) >= severity(min)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_filter_by_severity() {
+        assert!(enabled(LoggingLevel::Error, LoggingLevel::Warning));
+        assert!(enabled(LoggingLevel::Warning, LoggingLevel::Warning));
+        assert!(!enabled(LoggingLevel::Info, LoggingLevel::Warning));
+        assert!(enabled(LoggingLevel::Debug, LoggingLevel::Debug));
+    }
+
+    #[test]
+    fn buffers_until_a_client_attaches() {
+        let log = McpLog::new(LoggingLevel::Info);
+        for i in 0..MAX_PENDING + 5 {
+            log.info("index", &format!("indexed {i} files"));
+        }
+        assert_eq!(log.pending_len(), MAX_PENDING);
+        log.set_level(LoggingLevel::Error);
+        assert_eq!(log.level(), LoggingLevel::Error);
+    }
+}
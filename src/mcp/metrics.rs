@@ -0,0 +1,305 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// Errors from the local metrics store.
+#[derive(Debug, thiserror::Error)]
+pub enum MetricsError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("lock poisoned")]
+    LockPoisoned,
+}
+
+/// A counter name in the metrics table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// One tool call; key is `tool.action`, e.g. `search.history`.
+    ToolCall,
+    /// A search-style call that returned something; key is the action.
+    SearchHit,
+    /// A search-style call that returned nothing; key is the action.
+    SearchMiss,
+    /// A sync; key is `domain/project`.
+    Sync,
+}
+
+impl Metric {
+    fn as_str(self) -> &'static str {
+        match self {
+            Metric::ToolCall => "tool_call",
+            Metric::SearchHit => "search_hit",
+            Metric::SearchMiss => "search_miss",
+            Metric::Sync => "sync",
+        }
+    }
+}
+
+/// Days of per-call latencies kept in metrics.db; older samples are dropped on open.
+const LATENCY_KEEP_DAYS: u64 = 30;
+
+/// Longest string parameter logged as is with a slow call; longer ones become `<N chars>`.
+const LOGGED_STRING_MAX: usize = 80;
+
+/// Parameters that say what a call did rather than what it wrote or asked, logged as is.
+/// Every other string is logged only by size.
+const STRUCTURAL_PARAMS: &[&str] = &[
+    "action", "domain", "project", "list", "path", "session_id", "staging_id", "ticket_id",
+    "status", "limit", "start", "end", "part", "mode", "sort", "fields", "since", "from", "to",
+    "threshold", "include_archived", "enrich", "dry_run", "finalize", "confirmed",
+    "confirm_new_domain", "exclude_domains",
+];
+
+/// Search actions whose hit rate is tracked.
+pub const SEARCH_ACTIONS: &[&str] = &["search", "history", "recall", "sessions_around"];
+
+/// Whether a search-style response found anything: a non-empty `results` or `entries` list.
+/// None for responses that are neither (errors, other actions).
+pub fn found_results(response: &str) -> Option<bool> {
+    let value: serde_json::Value = serde_json::from_str(response).ok()?;
+    ["results", "entries"].iter()
+        .find_map(|field| value.get(field).and_then(|v| v.as_array()))
+        .map(|list| !list.is_empty())
+}
+
+/// A call's parameters as they may be logged: unset ones dropped, and strings outside
+/// `STRUCTURAL_PARAMS` or over `LOGGED_STRING_MAX` replaced by their length, so a slow-call
+/// log line carries the shape of the call and not what was written or searched for.
+pub fn sanitize(params: serde_json::Value) -> serde_json::Value {
+    fn clip(value: serde_json::Value, content: bool) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) if content || s.chars().count() > LOGGED_STRING_MAX => {
+                serde_json::Value::String(format!("<{} chars>", s.chars().count()))
+            }
+            serde_json::Value::Array(items) => items.into_iter().map(|v| clip(v, content)).collect(),
+            other => other,
+        }
+    }
+    match params {
+        serde_json::Value::Object(fields) => fields.into_iter()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| {
+                let content = !STRUCTURAL_PARAMS.contains(&k.as_str());
+                (k, clip(v, content))
+            })
+            .collect(),
+        other => other,
+    }
+}
+
+/// How long one action's calls took.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Latency {
+    pub calls: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Latency {
+    /// Percentiles over `samples` (milliseconds), nearest-rank.
+    pub fn of(samples: &mut [u64]) -> Self {
+        samples.sort_unstable();
+        let rank = |p: usize| samples.get((samples.len() * p).div_ceil(100).saturating_sub(1)).copied().unwrap_or(0);
+        Self { calls: samples.len(), p50_ms: rank(50), p95_ms: rank(95), max_ms: samples.last().copied().unwrap_or(0) }
+    }
+}
+
+/// Daily counters of how the vault is used, and how long calls take, kept in
+/// `~/.wardwell/metrics.db` and never sent anywhere. Only action names and `domain/project`
+/// keys are stored: no queries, content, paths, or session IDs.
+pub struct MetricsStore {
+    conn: Mutex<Connection>,
+}
+
+impl MetricsStore {
+    pub fn open(path: &Path) -> Result<Self, MetricsError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, MetricsError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, MetricsError> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS metrics (
+                day TEXT NOT NULL,
+                name TEXT NOT NULL,
+                key TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, name, key)
+            );
+            CREATE TABLE IF NOT EXISTS latency (
+                day TEXT NOT NULL,
+                key TEXT NOT NULL,
+                ms INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS latency_day ON latency(day);"
+        )?;
+        let cutoff = (chrono::Local::now().date_naive() - chrono::Days::new(LATENCY_KEEP_DAYS)).to_string();
+        conn.execute("DELETE FROM latency WHERE day < ?1", rusqlite::params![cutoff])?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>, MetricsError> {
+        self.conn.lock().map_err(|_| MetricsError::LockPoisoned)
+    }
+
+    /// Add one to today's counter for `metric` and `key`.
+    pub fn record(&self, metric: Metric, key: &str) -> Result<(), MetricsError> {
+        let day = chrono::Local::now().format("%Y-%m-%d").to_string();
+        self.record_on(&day, metric, key)
+    }
+
+    fn record_on(&self, day: &str, metric: Metric, key: &str) -> Result<(), MetricsError> {
+        self.lock()?.execute(
+            "INSERT INTO metrics (day, name, key, count) VALUES (?1, ?2, ?3, 1)
+             ON CONFLICT(day, name, key) DO UPDATE SET count = count + 1",
+            rusqlite::params![day, metric.as_str(), key],
+        )?;
+        Ok(())
+    }
+
+    /// Record how long one call to `key` (`tool.action`) took.
+    pub fn record_latency(&self, key: &str, ms: u64) -> Result<(), MetricsError> {
+        let day = chrono::Local::now().format("%Y-%m-%d").to_string();
+        self.record_latency_on(&day, key, ms)
+    }
+
+    fn record_latency_on(&self, day: &str, key: &str, ms: u64) -> Result<(), MetricsError> {
+        self.lock()?.execute(
+            "INSERT INTO latency (day, key, ms) VALUES (?1, ?2, ?3)",
+            rusqlite::params![day, key, ms as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Latency per `tool.action` from `since` (YYYY-MM-DD) through today.
+    pub fn latencies(&self, since: &str) -> Result<BTreeMap<String, Latency>, MetricsError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare("SELECT key, ms FROM latency WHERE day >= ?1")?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        let mut samples: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+        for (key, ms) in rows.flatten() {
+            samples.entry(key).or_default().push(ms.max(0) as u64);
+        }
+        Ok(samples.into_iter().map(|(key, mut ms)| (key, Latency::of(&mut ms))).collect())
+    }
+
+    /// Totals from `since` (YYYY-MM-DD) through today.
+    pub fn report(&self, since: &str) -> Result<MetricsReport, MetricsError> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT day, name, key, count FROM metrics WHERE day >= ?1 ORDER BY day"
+        )?;
+        let rows = stmt.query_map(rusqlite::params![since], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?))
+        })?;
+
+        let mut report = MetricsReport { since: since.to_string(), ..Default::default() };
+        for (day, name, key, count) in rows.flatten() {
+            match name.as_str() {
+                "tool_call" => {
+                    *report.calls_per_day.entry(day).or_default() += count;
+                    *report.calls_by_action.entry(key).or_default() += count;
+                    report.tool_calls += count;
+                }
+                "search_hit" => report.searches.entry(key).or_default().hits += count,
+                "search_miss" => report.searches.entry(key).or_default().misses += count,
+                "sync" => *report.syncs_by_project.entry(key).or_default() += count,
+                _ => {}
+            }
+        }
+        drop(stmt);
+        drop(conn);
+        report.latency = self.latencies(since)?;
+        Ok(report)
+    }
+}
+
+/// Hits and misses for one search action.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SearchCounts {
+    pub hits: i64,
+    pub misses: i64,
+}
+
+impl SearchCounts {
+    /// Share of calls that found something, 0.0–1.0. None before any calls.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Usage over a period, for `wardwell metrics`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetricsReport {
+    pub since: String,
+    pub tool_calls: i64,
+    pub calls_per_day: BTreeMap<String, i64>,
+    pub calls_by_action: BTreeMap<String, i64>,
+    pub searches: BTreeMap<String, SearchCounts>,
+    pub syncs_by_project: BTreeMap<String, i64>,
+    /// Call latency per `tool.action`.
+    pub latency: BTreeMap<String, Latency>,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_roll_up_into_a_report() {
+        let store = MetricsStore::open_in_memory().unwrap();
+        store.record_on("2026-03-01", Metric::ToolCall, "search.search").unwrap();
+        store.record_on("2026-03-02", Metric::ToolCall, "search.search").unwrap();
+        store.record_on("2026-03-02", Metric::ToolCall, "write.sync").unwrap();
+        store.record_on("2026-03-02", Metric::SearchHit, "search").unwrap();
+        store.record_on("2026-03-02", Metric::SearchMiss, "search").unwrap();
+        store.record_on("2026-03-02", Metric::SearchHit, "search").unwrap();
+        store.record_on("2026-03-02", Metric::Sync, "work/api").unwrap();
+
+        let report = store.report("2026-03-02").unwrap();
+        assert_eq!(report.tool_calls, 2);
+        assert_eq!(report.calls_per_day.get("2026-03-02"), Some(&2));
+        assert!(!report.calls_per_day.contains_key("2026-03-01"));
+        assert_eq!(report.searches["search"], SearchCounts { hits: 2, misses: 1 });
+        assert!((report.searches["search"].hit_rate().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.syncs_by_project.get("work/api"), Some(&1));
+    }
+
+    #[test]
+    fn latencies_give_percentiles_and_logs_drop_content() {
+        let store = MetricsStore::open_in_memory().unwrap();
+        for ms in 1..=20 {
+            store.record_latency_on("2026-03-02", "search.search", ms * 10).unwrap();
+        }
+        store.record_latency_on("2026-03-01", "search.search", 9000).unwrap();
+        let latency = &store.latencies("2026-03-02").unwrap()["search.search"];
+        assert_eq!(*latency, Latency { calls: 20, p50_ms: 100, p95_ms: 190, max_ms: 200 });
+        assert_eq!(Latency::of(&mut []), Latency::default());
+
+        let params = serde_json::json!({
+            "action": "sync", "domain": "work", "body": "private notes", "query": null, "focus": "secret plan",
+            "blockers": ["legal"], "limit": 5, "fields": ["summary", "x".repeat(100)],
+        });
+        assert_eq!(sanitize(params), serde_json::json!({
+            "action": "sync", "domain": "work", "body": "<13 chars>", "focus": "<11 chars>",
+            "blockers": ["<5 chars>"], "limit": 5, "fields": ["summary", "<100 chars>"],
+        }));
+    }
+
+    #[test]
+    fn found_results_reads_result_lists() {
+        assert_eq!(found_results(r#"{"results": [{"path": "a"}]}"#), Some(true));
+        assert_eq!(found_results(r#"{"entries": [], "total": 0}"#), Some(false));
+        assert_eq!(found_results(r#"{"error": "nope"}"#), None);
+    }
+}
@@ -1 +1,9 @@
 pub mod server;
+pub mod safe_mode;
+pub mod logging;
+pub mod ranking;
+pub mod health;
+pub mod response;
+pub mod http;
+pub mod metrics;
+pub mod dashboard;
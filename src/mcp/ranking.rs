@@ -0,0 +1,219 @@
+use crate::config::loader::{RankingConfig, RankingWeights};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+/// What orchestrate knows about a queued project when ordering the queue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Features {
+    /// Gone quiet relative to its usual cadence.
+    pub neglected: bool,
+    /// Days since last activity over the usual gap between sessions; 0 without a cadence.
+    pub overdue_ratio: f64,
+    /// Days since current_state.md was last updated.
+    pub days_since_update: Option<i64>,
+    /// Days since the last session or history entry.
+    pub days_since_last: Option<i64>,
+    /// 0 (none) to 4 (critical), from `priority:` in INDEX.md.
+    pub priority: u8,
+    /// Days until `due:` in INDEX.md; negative once overdue.
+    pub due_in_days: Option<i64>,
+}
+
+impl Features {
+    /// 1 when due today or overdue, halving as the deadline moves out (tomorrow 0.5,
+    /// in a week 0.125); 0 without a due date.
+    pub fn due_urgency(&self) -> f64 {
+        self.due_in_days.map_or(0.0, |d| 1.0 / (1.0 + d.max(0) as f64))
+    }
+
+    /// Linear score: each weight times its feature, missing features counting as 0.
+    pub fn score(&self, w: &RankingWeights) -> f64 {
+        w.neglected * f64::from(u8::from(self.neglected))
+            + w.overdue_ratio * self.overdue_ratio
+            + w.days_since_update * self.days_since_update.unwrap_or(0) as f64
+            + w.days_since_last * self.days_since_last.unwrap_or(0) as f64
+            + w.priority * f64::from(self.priority)
+            + w.due_urgency * self.due_urgency()
+    }
+}
+
+/// `priority:` frontmatter to a rank: critical/urgent/p0 = 4, high/p1 = 3, medium/normal/p2 = 2,
+/// low/p3 = 1. Bare numbers 1-4 are taken as the rank itself.
+pub fn priority_rank(raw: &str) -> u8 {
+    match raw.trim().to_lowercase().as_str() {
+        "critical" | "urgent" | "highest" | "p0" => 4,
+        "high" | "p1" => 3,
+        "medium" | "normal" | "p2" => 2,
+        "low" | "lowest" | "p3" => 1,
+        other => other.parse::<u8>().map_or(0, |n| n.min(4)),
+    }
+}
+
+/// Reorder `entries` (orchestrate queue items, each with a `features` object) per `config`.
+/// A ranking command wins; if it fails, weights apply, then the built-in neglected-first
+/// order. Returns the command's error, if any, so the caller can surface it.
+pub fn rank(entries: &mut Vec<Value>, features: &[Features], config: &RankingConfig) -> Option<String> {
+    let mut error = None;
+    if let Some(command) = &config.command {
+        match run_command(command, entries, Duration::from_millis(config.timeout_ms)) {
+            Ok(order) => {
+                apply_order(entries, &order);
+                return None;
+            }
+            Err(e) => error = Some(e),
+        }
+    }
+    match &config.weights {
+        Some(weights) => {
+            let mut scored: Vec<(f64, Value)> = features.iter().map(|f| f.score(weights)).zip(entries.drain(..)).collect();
+            // Stable, so equal scores keep their scan order
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            for (score, mut entry) in scored {
+                entry["rank_score"] = serde_json::json!((score * 1000.0).round() / 1000.0);
+                entries.push(entry);
+            }
+        }
+        None => neglected_first(entries),
+    }
+    error
+}
+
+/// Neglected projects first, most overdue at the top; everything else keeps its order.
+pub fn neglected_first(entries: &mut [Value]) {
+    entries.sort_by(|a, b| {
+        let score = |e: &Value| {
+            if e["effort"]["neglected"].as_bool() == Some(true) {
+                e["effort"]["overdue_ratio"].as_f64().unwrap_or(0.0)
+            } else {
+                f64::NEG_INFINITY
+            }
+        };
+        score(b).total_cmp(&score(a))
+    });
+}
+
+/// Move the `domain/project` keys in `order` to the front, in that order. Projects the
+/// command left out follow in their existing order; unknown keys are ignored.
+fn apply_order(entries: &mut Vec<Value>, order: &[String]) {
+    let key = |e: &Value| format!("{}/{}", e["domain"].as_str().unwrap_or(""), e["project"].as_str().unwrap_or(""));
+    let mut rest = std::mem::take(entries);
+    for wanted in order {
+        if let Some(i) = rest.iter().position(|e| key(e) == *wanted) {
+            entries.push(rest.remove(i));
+        }
+    }
+    entries.extend(rest);
+}
+
+/// Run `sh -c command` with the queue as a JSON array on stdin. It must print a JSON array
+/// of `"domain/project"` keys, best first, before `timeout`.
+fn run_command(command: &str, entries: &[Value], timeout: Duration) -> Result<Vec<String>, String> {
+    let command = crate::config::secrets::resolve(command).map_err(|e| format!("ranking command: {e}"))?;
+    let mut child = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("ranking command failed to start: {e}"))?;
+
+    let input = serde_json::to_string(entries).unwrap_or_default();
+    if let Some(mut stdin) = child.stdin.take() {
+        // Write on a thread so a command that doesn't read stdin can't block us
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+    // Drain both pipes as the command runs, so a chatty command can't fill one and stall
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("ranking command timed out after {}ms", timeout.as_millis()));
+            }
+            Err(e) => return Err(format!("ranking command: {e}")),
+        }
+    };
+    if !status.success() {
+        let stderr = stderr.join().unwrap_or_default();
+        return Err(format!("ranking command exited with {status}: {}", stderr.trim().chars().take(200).collect::<String>()));
+    }
+    let output = stdout.join().unwrap_or_default();
+    serde_json::from_str(output.trim())
+        .map_err(|_| format!("ranking command must print a JSON array of \"domain/project\" strings, got: {}", output.trim().chars().take(120).collect::<String>()))
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut out = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut out);
+        }
+        out
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<Value> {
+        ["api", "site", "docs"].iter()
+            .map(|p| serde_json::json!({ "domain": "work", "project": p }))
+            .collect()
+    }
+
+    #[test]
+    fn weights_order_by_score() {
+        let features = vec![
+            Features { priority: priority_rank("low"), ..Default::default() },
+            Features { priority: priority_rank("P1"), due_in_days: Some(-2), ..Default::default() },
+            Features { priority: priority_rank("medium"), due_in_days: Some(1), ..Default::default() },
+        ];
+        let config = RankingConfig {
+            weights: Some(RankingWeights { priority: 1.0, due_urgency: 10.0, ..Default::default() }),
+            ..Default::default()
+        };
+        let mut queue = entries();
+        assert_eq!(rank(&mut queue, &features, &config), None);
+        let order: Vec<&str> = queue.iter().map(|e| e["project"].as_str().unwrap()).collect();
+        assert_eq!(order, vec!["site", "docs", "api"]);
+        assert_eq!(queue[0]["rank_score"], 13.0);
+        assert_eq!(priority_rank("7"), 4);
+        assert_eq!(priority_rank("someday"), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_orders_and_falls_back_on_failure() {
+        let features = vec![Features::default(); 3];
+        let config = RankingConfig {
+            command: Some("cat >/dev/null; echo '[\"work/docs\", \"work/nope\", \"work/api\"]'".to_string()),
+            ..Default::default()
+        };
+        let mut queue = entries();
+        assert_eq!(rank(&mut queue, &features, &config), None);
+        let order: Vec<&str> = queue.iter().map(|e| e["project"].as_str().unwrap()).collect();
+        assert_eq!(order, vec!["docs", "api", "site"]);
+
+        let config = RankingConfig { command: Some("echo nope".to_string()), ..Default::default() };
+        let mut queue = entries();
+        let error = rank(&mut queue, &features, &config).unwrap();
+        assert!(error.contains("JSON array"), "{error}");
+        assert_eq!(queue, entries());
+
+        let config = RankingConfig { command: Some("sleep 5".to_string()), timeout_ms: 100, ..Default::default() };
+        assert!(rank(&mut entries(), &features, &config).unwrap().contains("timed out"));
+    }
+}
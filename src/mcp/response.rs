@@ -0,0 +1,285 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Room kept free under the limit for the `truncated` note itself.
+const NOTE_ROOM: usize = 2048;
+
+/// Most cuts made before giving up on a pathological payload.
+const MAX_CUTS: usize = 64;
+
+/// Top-level keys a field selection never drops.
+const ALWAYS_KEPT: [&str; 2] = ["warning", "stale_warning"];
+/// Keys kept in trimmed entries whatever was selected, so each still says what it is.
+const IDENTITY_KEYS: [&str; 6] = ["domain", "project", "path", "id", "session_id", "title"];
+
+/// One field the guard shortened, as a JSON pointer (`/results`, `/queue/0/notes`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cut {
+    pub field: String,
+    pub kept: usize,
+    pub total: usize,
+    /// `items` for lists, `lines` for multi-line text, `chars` otherwise.
+    pub unit: &'static str,
+}
+
+/// Fit a JSON response under `max_bytes` (0 = no limit). Oversized responses have their
+/// largest lists and texts cut from the end until they fit, and gain a `truncated` object
+/// listing the cuts with `hint(&cuts)` saying how to fetch the rest. Clients with output
+/// limits then get valid, smaller JSON instead of a payload sliced mid-object.
+pub fn fit(json: String, max_bytes: usize, hint: impl FnOnce(&[Cut]) -> String) -> String {
+    if max_bytes == 0 || json.len() <= max_bytes {
+        return json;
+    }
+    let Ok(parsed) = serde_json::from_str::<Value>(&json) else {
+        return json;
+    };
+    let original = json.len();
+    let mut value = match parsed {
+        Value::Object(_) => parsed,
+        // Wrap bare lists so the note has somewhere to go
+        other => serde_json::json!({ "items": other }),
+    };
+
+    let budget = max_bytes.saturating_sub(NOTE_ROOM);
+    let mut cuts: Vec<Cut> = Vec::new();
+    for _ in 0..MAX_CUTS {
+        let size = pretty_len(&value);
+        if size <= budget {
+            break;
+        }
+        let Some(pointer) = largest_cuttable(&value, String::new()) else {
+            break;
+        };
+        let Some(node) = value.pointer_mut(&pointer) else {
+            break;
+        };
+        let Some((kept, total, unit)) = shrink(node, size - budget) else {
+            break;
+        };
+        // A field cut twice keeps its original total
+        match cuts.iter_mut().find(|c| c.field == pointer) {
+            Some(cut) => cut.kept = kept,
+            None => cuts.push(Cut { field: pointer, kept, total, unit }),
+        }
+    }
+
+    let note = serde_json::json!({
+        "bytes": original,
+        "limit": max_bytes,
+        "cuts": cuts,
+        "hint": hint(&cuts),
+    });
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("truncated".to_string(), note);
+    }
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+/// Keep only the requested `fields` of a JSON response. A top-level key is kept whole;
+/// `parent.child` keeps `parent` with its entries (an object, or a list of them) cut down to
+/// `child`; a name that isn't a top-level key selects that key in the entries of every
+/// top-level list or object holding one, so `focus,next_action` trims a `projects` list.
+/// Warnings and the entries' identifying keys stay. Names matching nothing are listed
+/// under `unknown_fields`, beside `available_fields`. Errors pass through untouched.
+pub fn select(json: String, fields: &[String]) -> String {
+    let names: Vec<&str> = fields.iter()
+        .flat_map(|f| f.split(','))
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .collect();
+    let Ok(Value::Object(map)) = serde_json::from_str::<Value>(&json) else {
+        return json;
+    };
+    if names.is_empty() || map.get("error").is_some_and(|e| !e.is_null() && e != &Value::Bool(false)) {
+        return json;
+    }
+
+    let holds = |value: &Value, key: &str| match value {
+        Value::Object(o) => o.contains_key(key),
+        Value::Array(items) => items.iter().any(|i| i.get(key).is_some()),
+        _ => false,
+    };
+    let mut whole: Vec<&str> = Vec::new();
+    let mut nested: Vec<(&str, &str)> = Vec::new();
+    let mut unknown: Vec<&str> = Vec::new();
+    for name in names {
+        if map.contains_key(name) {
+            whole.push(name);
+        } else if let Some((parent, child)) = name.split_once('.')
+            && map.contains_key(parent)
+        {
+            nested.push((parent, child));
+        } else {
+            let holders: Vec<&str> = map.iter().filter(|(_, v)| holds(v, name)).map(|(k, _)| k.as_str()).collect();
+            if holders.is_empty() {
+                unknown.push(name);
+            }
+            nested.extend(holders.into_iter().map(|k| (k, name)));
+        }
+    }
+
+    let mut out = serde_json::Map::new();
+    for (key, value) in &map {
+        let children: Vec<&str> = nested.iter().filter(|(k, _)| k == key).map(|(_, c)| *c).collect();
+        if whole.contains(&key.as_str()) || ALWAYS_KEPT.contains(&key.as_str()) {
+            out.insert(key.clone(), value.clone());
+        } else if !children.is_empty() {
+            out.insert(key.clone(), trimmed(value, &children));
+        }
+    }
+    if !unknown.is_empty() {
+        out.insert("unknown_fields".to_string(), serde_json::json!(unknown));
+        out.insert("available_fields".to_string(), serde_json::json!(map.keys().collect::<Vec<_>>()));
+    }
+    serde_json::to_string_pretty(&Value::Object(out)).unwrap_or_default()
+}
+
+/// An entry, or each entry of a list, with only `keys` and its identifying keys.
+fn trimmed(value: &Value, keys: &[&str]) -> Value {
+    match value {
+        Value::Object(o) => Value::Object(o.iter()
+            .filter(|(k, _)| keys.contains(&k.as_str()) || IDENTITY_KEYS.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()),
+        Value::Array(items) => Value::Array(items.iter().map(|i| trimmed(i, keys)).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Pointer to the node to cut next: follow the largest child down through objects and
+/// single-item lists, stopping at a longer list or a string.
+fn largest_cuttable(value: &Value, pointer: String) -> Option<String> {
+    let (key, child) = match value {
+        Value::String(s) if !s.is_empty() => return Some(pointer),
+        Value::Array(items) if items.len() > 1 => return Some(pointer),
+        Value::Array(items) => ("0".to_string(), items.first()?),
+        Value::Object(map) => map.iter()
+            .max_by_key(|(_, v)| pretty_len(v))
+            .map(|(k, v)| (k.replace('~', "~0").replace('/', "~1"), v))?,
+        _ => return None,
+    };
+    largest_cuttable(child, format!("{pointer}/{key}"))
+}
+
+/// Cut at least `excess` bytes from the end of a list or string. Returns (kept, total, unit)
+/// as counted before this cut.
+fn shrink(node: &mut Value, excess: usize) -> Option<(usize, usize, &'static str)> {
+    match node {
+        Value::Array(items) => {
+            let total = items.len();
+            let mut freed = 0;
+            while items.len() > 1 && freed < excess {
+                // The separator and indentation are a few bytes more per item
+                freed += items.pop().map_or(0, |v| pretty_len(&v) + 8);
+            }
+            Some((items.len(), total, "items"))
+        }
+        Value::String(s) => {
+            let mut keep = s.len().saturating_sub(excess + 16);
+            while !s.is_char_boundary(keep) {
+                keep -= 1;
+            }
+            let multi_line = s.contains('\n');
+            // Prefer whole lines, as long as at least one survives
+            if multi_line && let Some(nl) = s[..keep].rfind('\n') {
+                let total = s.lines().count();
+                s.truncate(nl + 1);
+                return Some((s.lines().count(), total, "lines"));
+            }
+            let total = s.chars().count();
+            s.truncate(keep);
+            Some((s.chars().count(), total, "chars"))
+        }
+        _ => None,
+    }
+}
+
+/// Serialized size as the server sends it (pretty-printed).
+fn pretty_len(value: &Value) -> usize {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    let _ = serde_json::to_writer_pretty(&mut counter, value);
+    counter.0
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_responses_pass_through() {
+        let json = r#"{"results": [1, 2, 3]}"#.to_string();
+        assert_eq!(fit(json.clone(), 10_000, |_| unreachable!()), json);
+        assert_eq!(fit(json.clone(), 0, |_| unreachable!()), json);
+    }
+
+    #[test]
+    fn select_keeps_requested_fields_at_either_level() {
+        let json = serde_json::json!({
+            "count": 2,
+            "stale_warning": "index is behind",
+            "projects": [
+                { "domain": "work", "project": "api", "focus": "Retry", "next_action": "Ship", "blockers": ["CI"] },
+                { "domain": "work", "project": "site", "focus": "Launch", "next_action": "Copy", "blockers": [] },
+            ],
+            "now": { "project": "api", "focus": "Retry", "updated": "2026-03-01" },
+        }).to_string();
+        let pick = |fields: &[&str]| -> Value {
+            serde_json::from_str(&select(json.clone(), &fields.iter().map(|f| f.to_string()).collect::<Vec<_>>())).unwrap()
+        };
+
+        assert_eq!(pick(&["count"]), serde_json::json!({ "count": 2, "stale_warning": "index is behind" }));
+        let v = pick(&["focus,next_action"]);
+        assert_eq!(v["projects"][1], serde_json::json!({ "domain": "work", "project": "site", "focus": "Launch", "next_action": "Copy" }));
+        assert_eq!(v["now"], serde_json::json!({ "project": "api", "focus": "Retry" }));
+        assert!(v.get("count").is_none());
+        let v = pick(&["now.updated", "nope"]);
+        assert_eq!(v["now"], serde_json::json!({ "project": "api", "updated": "2026-03-01" }));
+        assert_eq!(v["unknown_fields"], serde_json::json!(["nope"]));
+        assert_eq!(v["available_fields"].as_array().unwrap().len(), 4);
+
+        let error = r#"{"error": true, "message": "nope"}"#.to_string();
+        assert_eq!(select(error.clone(), &["count".to_string()]), error);
+        let error = crate::mcp::server::json_error("Unknown action: 'nope'.");
+        assert_eq!(select(error.clone(), &["count".to_string()]), error);
+        let ok = r#"{"error": false, "count": 1, "extra": 2}"#.to_string();
+        assert_eq!(serde_json::from_str::<Value>(&select(ok, &["count".to_string()])).unwrap(), serde_json::json!({"count": 1}));
+        assert_eq!(select(json.clone(), &[" ".to_string()]), json);
+    }
+
+    #[test]
+    fn oversized_lists_and_text_are_cut_to_valid_json() {
+        let results: Vec<Value> = (0..500).map(|i| serde_json::json!({ "path": format!("work/p{i}/INDEX.md"), "snippet": "x".repeat(100) })).collect();
+        let json = serde_json::to_string_pretty(&serde_json::json!({ "query": "x", "results": results })).unwrap();
+        let out = fit(json, 20_000, |cuts| format!("{} cut", cuts.len()));
+        assert!(out.len() <= 20_000, "{}", out.len());
+        let v: Value = serde_json::from_str(&out).unwrap();
+        let kept = v["results"].as_array().unwrap().len();
+        assert!(kept > 10 && kept < 500);
+        assert_eq!(v["truncated"]["cuts"][0]["field"], "/results");
+        assert_eq!(v["truncated"]["cuts"][0]["total"], 500);
+        assert_eq!(v["truncated"]["cuts"][0]["kept"], kept);
+        assert_eq!(v["truncated"]["hint"], "1 cut");
+        assert_eq!(v["query"], "x");
+
+        let content: String = (1..=2000).map(|i| format!("line {i} of the note\n")).collect();
+        let json = serde_json::to_string(&serde_json::json!({ "path": "a.md", "content": content })).unwrap();
+        let out = fit(json, 10_000, |_| String::new());
+        let v: Value = serde_json::from_str(&out).unwrap();
+        let cut = &v["truncated"]["cuts"][0];
+        assert_eq!((cut["field"].as_str(), cut["unit"].as_str(), cut["total"].as_u64()), (Some("/content"), Some("lines"), Some(2000)));
+        let text = v["content"].as_str().unwrap();
+        assert!(text.ends_with('\n'));
+        assert_eq!(text.lines().count() as u64, cut["kept"].as_u64().unwrap());
+    }
+}
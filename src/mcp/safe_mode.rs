@@ -0,0 +1,199 @@
+use crate::config::loader;
+use crate::index::store::IndexStore;
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::model::*;
+use rmcp::{tool, tool_handler, tool_router, ServerHandler};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Consecutive failed startups before `serve` comes up in safe mode.
+pub const SAFE_MODE_THRESHOLD: u32 = 3;
+
+/// Startup bookkeeping file under the config dir.
+const RECORD_FILE: &str = "startup.json";
+
+/// Startup history, persisted across `serve` launches. An attempt counts as failed until
+/// the MCP handshake completes, so panics and killed processes are counted too.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StartupRecord {
+    /// Failed startups in a row, not counting the current attempt.
+    pub consecutive_failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_attempt: Option<String>,
+}
+
+impl StartupRecord {
+    pub fn in_safe_mode(&self) -> bool {
+        self.consecutive_failures >= SAFE_MODE_THRESHOLD
+    }
+}
+
+/// One diagnostic result from `wardwell_diagnose`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub check: &'static str,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn record_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(RECORD_FILE)
+}
+
+/// Read the startup record. Missing or unreadable files count as a clean history.
+pub fn read_record(config_dir: &Path) -> StartupRecord {
+    std::fs::read_to_string(record_path(config_dir))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Note the start of a full `serve` attempt. It counts as a failure until `mark_healthy`.
+/// Safe-mode launches don't call this, so they never push the count further.
+pub fn begin_attempt(config_dir: &Path) {
+    let previous = read_record(config_dir);
+    let pending = StartupRecord {
+        consecutive_failures: previous.consecutive_failures.saturating_add(1),
+        last_error: previous.last_error,
+        last_attempt: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    write_record(config_dir, &pending);
+}
+
+/// Attach the error that ended the current attempt. No-op once startup has succeeded.
+pub fn record_error(config_dir: &Path, error: &str) {
+    if !record_path(config_dir).exists() {
+        return;
+    }
+    let mut record = read_record(config_dir);
+    record.last_error = Some(error.to_string());
+    write_record(config_dir, &record);
+}
+
+/// Startup succeeded: forget past failures.
+pub fn mark_healthy(config_dir: &Path) {
+    match std::fs::remove_file(record_path(config_dir)) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => eprintln!("wardwell: failed to clear startup record: {e}"),
+    }
+}
+
+fn write_record(config_dir: &Path, record: &StartupRecord) {
+    let result = std::fs::create_dir_all(config_dir).and_then(|()| {
+        std::fs::write(record_path(config_dir), serde_json::to_string_pretty(record).unwrap_or_default())
+    });
+    if let Err(e) = result {
+        eprintln!("wardwell: failed to write startup record: {e}");
+    }
+}
+
+/// Re-run the startup steps that can fail — config parse (including domain files and
+/// secrets) and index open — and report each error verbatim.
+pub fn diagnose(config_dir: &Path) -> Vec<Check> {
+    let config = loader::load(Some(&config_dir.join("config.yml")));
+    let index = IndexStore::open(&config_dir.join("index.db"));
+    vec![
+        Check { check: "config", ok: config.is_ok(), error: config.err().map(|e| e.to_string()) },
+        Check { check: "index", ok: index.is_ok(), error: index.err().map(|e| e.to_string()) },
+    ]
+}
+
+/// Degraded MCP server exposing only `wardwell_diagnose`, so a broken install can explain
+/// itself from inside the conversation instead of showing up as a dead server.
+#[derive(Clone)]
+pub struct SafeModeServer {
+    tool_router: ToolRouter<Self>,
+    config_dir: PathBuf,
+    record: StartupRecord,
+}
+
+#[tool_router(router = tool_router)]
+impl SafeModeServer {
+    pub fn new(config_dir: PathBuf, record: StartupRecord) -> Self {
+        Self { tool_router: Self::tool_router(), config_dir, record }
+    }
+
+    #[tool(description = "Wardwell is in safe mode after repeated startup failures. Reports the startup error and re-checks config and index so you can tell the user exactly what to fix.")]
+    async fn wardwell_diagnose(&self) -> String {
+        let checks = diagnose(&self.config_dir);
+        let healthy = checks.iter().all(|c| c.ok);
+        let next_step = if healthy {
+            // Fixed since the last crash: let the next launch try full mode again
+            mark_healthy(&self.config_dir);
+            "Config and index load cleanly now. Restart the wardwell MCP server to leave safe mode.".to_string()
+        } else {
+            format!(
+                "Fix the errors above (config: {}), then restart the wardwell MCP server.",
+                self.config_dir.join("config.yml").display(),
+            )
+        };
+        serde_json::to_string_pretty(&serde_json::json!({
+            "safe_mode": true,
+            "consecutive_failures": self.record.consecutive_failures,
+            "last_error": self.record.last_error,
+            "last_attempt": self.record.last_attempt,
+            "checks": checks,
+            "next_step": next_step,
+        })).unwrap_or_default()
+    }
+}
+
+#[tool_handler(router = self.tool_router)]
+impl ServerHandler for SafeModeServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some(format!(
+                "Wardwell is running in SAFE MODE after {} failed startups; vault tools are unavailable. \
+                 Call wardwell_diagnose and tell the user what needs fixing.",
+                self.record.consecutive_failures,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn failures_accumulate_until_healthy() {
+        let tmp = tempdir().unwrap();
+        for expected in 0..SAFE_MODE_THRESHOLD {
+            let record = read_record(tmp.path());
+            assert_eq!(record.consecutive_failures, expected);
+            assert!(!record.in_safe_mode());
+            begin_attempt(tmp.path());
+        }
+        record_error(tmp.path(), "config parse error: bad indentation");
+
+        let record = read_record(tmp.path());
+        assert!(record.in_safe_mode());
+        assert_eq!(record.last_error.as_deref(), Some("config parse error: bad indentation"));
+
+        mark_healthy(tmp.path());
+        assert_eq!(read_record(tmp.path()), StartupRecord::default());
+    }
+
+    #[test]
+    fn diagnose_reports_config_errors() {
+        let tmp = tempdir().unwrap();
+        let checks = diagnose(tmp.path());
+        assert!(!checks[0].ok);
+        assert!(checks[0].error.as_deref().unwrap().contains("config.yml"));
+        assert!(checks[1].ok);
+
+        std::fs::write(tmp.path().join("config.yml"), "vault_path: [unclosed\n").unwrap();
+        let checks = diagnose(tmp.path());
+        assert!(!checks[0].ok);
+        assert!(checks[0].error.is_some());
+    }
+}
@@ -1,15 +1,22 @@
-use crate::config::loader::WardwellConfig;
+use crate::config::loader::{WardwellConfig, WritePolicy};
 use crate::domain::registry::DomainRegistry;
 use crate::index::fts::SearchQuery;
+use crate::index::hybrid::SearchMode;
 use crate::index::store::IndexStore;
+use crate::mcp::metrics::Metric;
+use crate::vault::history_query::{Candidate, HistoryQuery};
+use crate::vault::project::ProjectMatch;
+use crate::vault::symlinks::SymlinkPolicy;
+use crate::vault::types::Status;
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
-use rmcp::{tool, tool_handler, tool_router, ServerHandler};
+use rmcp::{tool, tool_router, ServerHandler};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// The Wardwell MCP server.
@@ -25,52 +32,161 @@ pub struct WardwellServer {
     /// Most recently accessed (domain, project) pair.
     last_project: Arc<Mutex<Option<(String, String)>>>,
     /// Embedder for hybrid semantic search. None if model not available.
-    pub embedder: Arc<Mutex<Option<crate::index::embed::Embedder>>>,
+    pub embedder: crate::index::embeddings::SharedProvider,
     /// Which domain this session belongs to (None = domainless/full access).
     session_domain: Option<String>,
     /// session_domain + its can_read list. Empty = domainless mode (full access).
     allowed_domains: Vec<String>,
     kanban: Option<Arc<crate::kanban::store::KanbanStore>>,
     kanban_queries: std::collections::HashMap<String, String>,
+    /// Outstanding clipboard challenges, keyed by confirm token.
+    clipboard_challenges: Arc<Mutex<HashMap<String, ConfirmChallenge>>>,
+    /// Content hashes of recent appends → when they were written. Used to drop double tool-calls.
+    recent_appends: Arc<Mutex<HashMap<String, (Instant, String)>>>,
+    /// Log notifications for the client; `logging/setLevel` adjusts its level.
+    pub log: crate::mcp::logging::McpLog,
+    /// Local usage counters, when `metrics: true`.
+    metrics: Option<Arc<crate::mcp::metrics::MetricsStore>>,
+    /// When the server started, for `server_status`.
+    started: Instant,
+    /// Call latencies in milliseconds since start per `tool.action`, the latest `LATENCY_SAMPLES` of each.
+    latencies: Arc<Mutex<HashMap<String, Vec<u64>>>>,
+    /// Content being sent in parts by `stage`, keyed by staging ID.
+    staged: Arc<Mutex<HashMap<String, StagedContent>>>,
+    /// Outstanding delete challenges, keyed by confirm token.
+    delete_challenges: Arc<Mutex<HashMap<String, ConfirmChallenge>>>,
+    /// Vault-relative paths written by write_file or stage in this session, the only notes `delete` may remove.
+    written_files: Arc<Mutex<HashSet<String>>>,
+    /// Config dir whose startup record the first `initialize` clears, for `serve` attempts
+    /// that count toward safe mode.
+    startup_dir: Option<PathBuf>,
+    /// The newest vault file change and when the vault was walked for it, so search and
+    /// read don't stat the whole vault on every call.
+    newest_change: Arc<Mutex<Option<(Instant, NewestChange)>>>,
 }
 
+/// A clipboard write or a delete awaiting confirmation. The token is bound to the exact content it was issued for.
+struct ConfirmChallenge {
+    content_hash: String,
+    issued_at: Instant,
+}
+
+/// Similarity a pair of notes needs to be listed by `duplicates` when no threshold is given.
+const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// How long a clipboard confirm token stays valid.
+const CLIPBOARD_TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// How long the stale-index check reuses its last walk of the vault.
+const NEWEST_CHANGE_TTL: Duration = Duration::from_secs(30);
+
+/// The newest vault file change and its path, if the vault has any files.
+type NewestChange = Option<(chrono::DateTime<chrono::Utc>, PathBuf)>;
+
+/// How long a delete confirm token stays valid.
+const DELETE_TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// Project logs `delete` never removes, even though they're machine files.
+const UNDELETABLE_LISTS: &[&str] = &["history.jsonl", "lessons.jsonl", crate::vault::compress::HISTORY_ARCHIVE];
+
+/// History entries, and search results, the HTTP dashboard lists.
+const DASHBOARD_HISTORY: usize = 20;
+
+/// A note arriving over several `stage` calls, bound to the project it was started in.
+#[derive(Clone)]
+struct StagedContent {
+    domain: String,
+    project: String,
+    /// Parts by 1-based number. A part sent again replaces the earlier copy.
+    parts: std::collections::BTreeMap<u32, String>,
+    touched_at: Instant,
+}
+
+/// How long staged parts are kept after the last call that touched them.
+const STAGING_TTL: Duration = Duration::from_secs(3600);
+
+/// Most content one staging ID can hold.
+const MAX_STAGED_BYTES: usize = 16 * 1024 * 1024;
+
+/// Most content staged across every staging ID at once.
+const MAX_TOTAL_STAGED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Highest part number `stage` accepts.
+const MAX_STAGE_PARTS: u32 = 1000;
+
+/// Environment variable naming the calling session's working directory, for inferring the
+/// project of a write that omits it. The `cwd` param takes precedence.
+pub const CWD_ENV: &str = "WARDWELL_CWD";
+
+/// What good writes look like. Returned by `help` and written into the CLAUDE.md block.
+pub const QUALITY_BAR: &[&str] = &[
+    "Snapshots: one sentence focus, concrete next action",
+    "History entries: what changed and why, not what was discussed",
+    "Decisions: the tradeoff and rejected alternatives, not the implementation",
+    "Lessons: root cause and prevention, not just what happened",
+];
+
 // -- Tool parameter types --
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct SearchParams {
-    #[schemars(description = "search: FTS query across vault. read: full file content. history: query across history files. orchestrate: prioritized project queue. retrospective: what happened in a time period. patterns: recurring blockers, stale threads, hot topics. context: session summary by ID. resume: full session handoff with plan, progress, remaining work by ID.")]
+    #[schemars(description = "search: FTS query across vault. read: full file content. history: query across history files. entry: one history/lesson/list entry by the ID returned when it was appended (query = ID). orchestrate: prioritized project queue, with open projects that have gone quiet relative to their usual cadence first. Queued and blocked projects carry a health 'score' (0-100, higher needs attention sooner) and the 'reasons' for it — staleness, time blocked, open questions, quiet history; 'sort' (score|updated|status) reorders by them and adds 'ranked', queue and blocked together. effort: per-project work cadence (sessions/week, average gap, bursts) from history and session times; flags neglected projects. retrospective: what happened in a time period. patterns: recurring blockers, stale threads, hot topics. sources: where project state comes from (desktop/code/manual) over time. context: session summary by ID, with transcript citations. resume: full session handoff with plan, progress, remaining work by ID. excerpt: verbatim transcript messages start..end from a session by ID. recall: everything known about a query in one project (or domain) — history, lessons, decisions, and session summaries merged by date. domains: every domain with project counts. projects: every project (optionally in one domain) with status and updated date. diff: how a project changed between two points (domain+project+from required) — status/focus/next_action changes, history entries, decisions, and lessons added in between. domain_errors: domain files that failed validation, each problem with its fix, and whether a last-known-good definition is standing in. sessions_around: sessions and history entries across projects in a window around a date (from required), oldest first — for picking up after a break. journal: timestamped bullets from the daily notes (Obsidian daily-notes settings, else daily/YYYY-MM-DD.md) between 'from' and 'to' (YYYY-MM-DD; default the last 7 days), oldest first; 'domain' keeps entries tagged #domain, 'query' filters their text. tags: every tag with document counts per domain; filter search with tag:<name> in the query (a query of only tag: filters lists the tagged documents). help: this cheat-sheet for every enabled tool — actions, parameters (required ones marked), and the quality bar for writes. links: notes linking to and from a file (path required), through related: frontmatter or [[wikilinks]], each with the line the link sits on. duplicates: pairs of near-duplicate notes (optionally in one domain) with similarity scores and which to merge into which; 'threshold' sets the cut-off. digest: the latest scheduled digest (optionally for one domain) — what's up next, what happened since the last one, stale threads, and usage. state: just status, focus, next_action, updated, and blockers for a project, a comma-separated list (domain/project entries allowed), or every project in a domain — cheaper than reading current_state.md. server_status: server uptime and p50/p95 latency per action since start (and over the last 7 days when metrics are on), with the actions slower than slow_action_ms. lists: the lists made with write action 'append' in a project (domain+project required), each with its entry count and last update. list_read: a list's entries, newest first (domain+project+list required); 'start' skips that many, 'limit' sets the page size (default 20, max 100), and 'next_start' is set while more remain.")]
     pub action: String,
-    #[schemars(description = "For search: FTS query. For history: what to look for.")]
+    #[schemars(description = "For search: FTS query; tag:<name> words filter to documents with that tag. For history: words or \"phrases\", plus status:, source:, before:/after: (YYYY-MM-DD) filters, OR, (groups), -negation and /regex/, e.g. status:blocked source:code after:2026-01-01. For entry: the entry ID returned when it was appended.")]
     pub query: Option<String>,
-    #[schemars(description = "For read: file path relative to vault root.")]
+    #[schemars(description = "For read/links: file path relative to vault root.")]
     pub path: Option<String>,
     #[schemars(description = "Filter to a domain (vault subdirectory). Optional.")]
     pub domain: Option<String>,
-    #[schemars(description = "Filter to a project within a domain. For history queries.")]
+    #[schemars(description = "Filter to a project within a domain. For history queries. For state: one project or a comma-separated list; domain/project entries name other domains.")]
     pub project: Option<String>,
-    #[schemars(description = "For history: ISO date, only entries after this.")]
+    #[schemars(description = "For history/sources: ISO date, only entries after this.")]
     pub since: Option<String>,
     #[schemars(description = "Max results.")]
     pub limit: Option<usize>,
-    #[schemars(description = "For context: Claude Code session ID.")]
+    #[schemars(description = "For context/resume/excerpt: Claude Code session ID.")]
     pub session_id: Option<String>,
+    #[schemars(description = "For excerpt: first message index (0-based, as in citation message_index). Default 0. For read: first line of content to return (0-based). For list_read: entries to skip, newest first. Default 0.")]
+    pub start: Option<usize>,
+    #[schemars(description = "For excerpt: last message index, inclusive. Default start + 9. For read: last line, inclusive. Default the end of the file.")]
+    pub end: Option<usize>,
     #[schemars(description = "Include archived projects in retrospective/patterns. Default false.")]
     pub include_archived: Option<bool>,
-    #[schemars(description = "Search mode: 'keyword' (FTS5 only, default) or 'semantic' (hybrid BM25 + vector + RRF). Use 'semantic' for broad/conceptual queries. Use default 'keyword' for exact terms or file names.")]
+    #[schemars(description = "Search mode: 'keyword' (FTS5 only, default), 'hybrid' (BM25 + vector, fused with RRF; 'semantic' is the same), or 'vector' (vector similarity only). Use 'hybrid' for broad queries, 'vector' when the words may differ entirely (\"auth flow\" vs \"login pipeline\"), and the default 'keyword' for exact terms or file names.")]
     pub mode: Option<String>,
+    #[schemars(description = "For diff: start point — a date (YYYY-MM-DD, or YYYY-MM-DD HH:MM) or history entry ref (#1 = first entry, #-1 = latest). For sessions_around: the date (YYYY-MM-DD) to look around. For journal: first day (YYYY-MM-DD), default 6 days before 'to'.")]
+    pub from: Option<String>,
+    #[schemars(description = "For diff: end point, same forms as 'from'. Default #-1 (latest entry). For journal: last day (YYYY-MM-DD), default today.")]
+    pub to: Option<String>,
+    #[schemars(description = "For duplicates: least similarity (0-1) for a pair of notes to be listed. Default 0.8.")]
+    pub threshold: Option<f64>,
+    #[schemars(description = "For retrospective: set to true to also sort the period into wins, grinds, and dropped threads with the summary model. Cached per period, so repeating the call is free.")]
+    pub enrich: Option<bool>,
+    #[schemars(description = "For search (keyword mode): leave out documents containing any of these words or phrases, e.g. [\"payments\"] for 'retry logic but not payments'.")]
+    pub exclude_terms: Option<Vec<String>>,
+    #[schemars(description = "For search (keyword mode): leave out documents in these domains.")]
+    pub exclude_domains: Option<Vec<String>>,
+    #[schemars(description = "For orchestrate: 'score' (highest health score first), 'updated' (least recently updated first), or 'status' (blocked, then active, highest score first within each). Default: the configured ranking.")]
+    pub sort: Option<String>,
+    #[schemars(description = "Any action: return only these keys, e.g. [\"summary\"] or [\"focus\", \"next_action\"]. A top-level key is kept whole; 'parent.child' (e.g. 'queue.next_action') trims parent's entries to child; other names trim the entries of every list holding them. Entries keep domain/project/path/id/title so they stay identifiable. Default: everything.")]
+    pub fields: Option<Vec<String>>,
+    #[schemars(description = "For list_read: the list's name, as given to append (e.g. 'future-ideas').")]
+    pub list: Option<String>,
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct WriteParams {
-    #[schemars(description = "sync: replace current_state.md and optionally append history. decide: append to decisions.md. append_history: append to history.jsonl. lesson: append to lessons.jsonl. append: append to a named JSONL list (requires 'list' param). write_file: write content to a file in the project directory (requires 'path' for relative path within project, e.g. 'docs/my-audit.md', and 'body' for content). IMPORTANT for append: check existing lists first (they're returned if list doesn't exist). ASK the user before creating a new list — do not create lists speculatively.")]
+    #[schemars(description = "sync: replace current_state.md and optionally append history. decide: append to decisions.md. append_history: append to history.jsonl. lesson: append to lessons.jsonl. append: append to a named JSONL list (requires 'list' param). write_file: write content to a file in the project directory (requires 'path' for relative path within project, e.g. 'docs/my-audit.md', and 'body' for content). tag: add ('tags') or remove ('remove_tags') tags on the project's current_state.md. stage: send a large note in parts — each call carries one part in 'body' (omit 'staging_id' on the first call to get one), and a call with 'finalize': true and 'path' writes the assembled note like write_file. delete: move a named list ('list') or a file written by write_file/stage in this session ('path') to the vault's .trash folder — the first call returns a confirm_token and deletes nothing; ask the user, then repeat with it. archive: move the project to '<domain>/archive/<project>', where history, orchestrate, and effort skip it; `related:` references elsewhere are updated and the move is recorded in its history. unarchive: move an archived project back. rename: move the project to 'new_name' (or 'domain/name' for another domain), with the same index, `related:` and history updates as archive; the old name is kept in the project's aliases. journal: append 'body' as a timestamped bullet to today's daily note, tagged #domain (or #domain/project when 'project' is given), creating the note from the Obsidian daily template if there is one. approve: write decisions the summarizer found in this project's sessions (queued in pending-decisions.jsonl) to decisions.md — omit 'ids' to list them first. IMPORTANT for append: check existing lists first (they're returned if list doesn't exist). ASK the user before creating a new list — do not create lists speculatively.")]
     pub action: String,
     #[schemars(description = "Domain folder under vault root (e.g., 'work', 'personal')")]
     pub domain: String,
-    #[schemars(description = "Project folder within the domain. If omitted, inferred from last-accessed project in this session.")]
+    #[schemars(description = "Project folder within the domain. If omitted, inferred from last-accessed project in this session, else from 'cwd'.")]
     pub project: Option<String>,
+    #[schemars(description = "Working directory of the calling session (e.g. the Claude Code project root). When 'project' is omitted and none was accessed in this domain, it is matched to a project through the domain's paths. Defaults to the WARDWELL_CWD environment variable, else the server's working directory.")]
+    pub cwd: Option<String>,
 
     // -- sync fields --
-    #[schemars(description = "REQUIRED for sync: project status (active, blocked, completed)")]
+    #[schemars(description = "REQUIRED for sync: project status (active, blocked, paused, completed, resolved, abandoned, superseded, or a state defined under `statuses:` in config.yml)")]
     pub status: Option<String>,
     #[schemars(description = "REQUIRED for sync: what you're working on right now")]
     pub focus: Option<String>,
@@ -96,17 +212,40 @@ pub struct WriteParams {
     // -- append (generic list) fields --
     #[schemars(description = "For append: list name without extension (e.g., 'future-ideas'). Writes to {list}.jsonl in the project dir.")]
     pub list: Option<String>,
-    #[schemars(description = "For append: set to true to confirm creating a NEW list. Required when the list doesn't exist yet.")]
+    #[schemars(description = "For append: set to true to confirm creating a NEW list. Required when the list doesn't exist yet. Also confirms writing to a new project whose name is close to an existing one.")]
     pub confirmed: Option<bool>,
+    #[schemars(description = "Any action: set to true to create a domain that isn't in the registry or the vault yet. Ask the user first; a new domain is usually a typo.")]
+    pub confirm_new_domain: Option<bool>,
 
     // -- write_file fields --
     #[schemars(description = "For write_file: path relative to project directory (e.g., 'docs/my-audit.md'). Directories created automatically.")]
     pub path: Option<String>,
 
+    // -- stage fields --
+    #[schemars(description = "For stage: the ID returned by the first stage call. Omit it to start staging a new note.")]
+    pub staging_id: Option<String>,
+    #[schemars(description = "For stage: 1-based number of the part in 'body'. Defaults to the part after the highest one staged. Resending a number replaces that part.")]
+    pub part: Option<u32>,
+    #[schemars(description = "For stage: set to true to write the staged parts, in order, to 'path' in the project and discard them. May come with the last part in 'body'.")]
+    pub finalize: Option<bool>,
+
+    // -- delete fields --
+    #[schemars(description = "For delete: the token returned by the first delete call. Omit it to see what would be deleted and get one.")]
+    pub confirm_token: Option<String>,
+
+    #[schemars(description = "Any action: set to true to write nothing and get back each change the call would make, with the full new content (or the text to append) and a diff against the file as it is now.")]
+    pub dry_run: Option<bool>,
+
     // -- source tagging --
-    #[schemars(description = "Where this write originates: 'desktop' (Claude Desktop / claude.ai), 'code' (Claude Code), or 'manual'. Used to track intent vs execution.")]
+    #[schemars(description = "Where this write originates: 'desktop' (Claude Desktop / claude.ai), 'code' (Claude Code), or 'manual'. Used to track intent vs execution. 'dismissed' on append_history acknowledges the project's queued desktop intents without syncing; on approve it drops the given pending decisions instead of recording them.")]
     pub source: Option<String>,
 
+    // -- tag fields --
+    #[schemars(description = "For tag: tags to add to the project's current_state.md frontmatter.")]
+    pub tags: Option<Vec<String>>,
+    #[schemars(description = "For tag: tags to remove from the project's current_state.md frontmatter.")]
+    pub remove_tags: Option<Vec<String>>,
+
     // -- lesson fields --
     #[schemars(description = "REQUIRED for lesson: what went wrong")]
     pub what_happened: Option<String>,
@@ -114,15 +253,21 @@ pub struct WriteParams {
     pub root_cause: Option<String>,
     #[schemars(description = "REQUIRED for lesson: how to prevent it")]
     pub prevention: Option<String>,
+    #[schemars(description = "REQUIRED for rename: the project's new folder name, or 'domain/name' to move it to another domain.")]
+    pub new_name: Option<String>,
+    #[schemars(description = "For approve: IDs of the pending decisions to write to decisions.md. Omit to list the project's pending decisions.")]
+    pub ids: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct ClipboardParams {
     #[schemars(description = "Content to place on clipboard")]
     pub content: String,
+    #[schemars(description = "Token returned by the first call. Omit it to request confirmation; repeat the call with the same content and this token after the user approves.")]
+    pub confirm_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct KanbanParams {
     #[schemars(description = "get: fetch a single ticket by ID (ticket_id required). search: find tickets by text (query required). list: filter and return items. create: new item (title+project required). update: modify fields (ticket_id required). move: status transition (ticket_id+status required). note: append note (ticket_id+text required). query: run a named query (question required). attach: write content to a ticket as a doc (ticket_id+title+text) — or link an existing vault file (ticket_id+file_path). detach: unlink attachment (ticket_id+attachment_id required). sequence: set ticket position — single (ticket_id+position) or bulk (project+order array). export_roadmap: generate PDF roadmap (project required).")]
     pub action: String,
@@ -176,9 +321,12 @@ pub struct KanbanParams {
 
 #[tool_router(router = tool_router)]
 impl WardwellServer {
-    pub fn new(config: WardwellConfig, index: Arc<IndexStore>, embedder: Arc<Mutex<Option<crate::index::embed::Embedder>>>, domain: Option<String>, kanban: Option<crate::kanban::store::KanbanStore>) -> Self {
+    pub fn new(config: WardwellConfig, index: Arc<IndexStore>, embedder: crate::index::embeddings::SharedProvider, domain: Option<String>, kanban: Option<crate::kanban::store::KanbanStore>) -> Self {
         let vault_root = config.vault_path.clone();
-        let raw_registry = DomainRegistry::from_domains(config.registry.all().to_vec());
+        let raw_registry = config.registry.clone();
+        for error in raw_registry.errors() {
+            eprintln!("[WARDWELL] WARNING: {}: {}", error.file, error.problems.join("; "));
+        }
 
         // Log registry state for debugging
         if raw_registry.is_empty() {
@@ -227,7 +375,28 @@ impl WardwellServer {
         if kanban.is_none() {
             tool_router.remove_route("wardwell_kanban");
         }
+        if !config.tools_enabled.search {
+            tool_router.remove_route("wardwell_search");
+        }
+        if !config.tools_enabled.write || config.read_only {
+            tool_router.remove_route("wardwell_write");
+        }
+        if !config.tools_enabled.clipboard || config.read_only {
+            tool_router.remove_route("wardwell_clipboard");
+        }
         let kanban = kanban.map(Arc::new);
+        let log = crate::mcp::logging::McpLog::new(config.log_level);
+        let metrics = if config.metrics {
+            match crate::mcp::metrics::MetricsStore::open(&crate::config::loader::config_dir().join("metrics.db")) {
+                Ok(store) => Some(Arc::new(store)),
+                Err(e) => {
+                    eprintln!("wardwell: metrics disabled, could not open metrics.db: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
         Self {
             tool_router,
@@ -242,27 +411,80 @@ impl WardwellServer {
             allowed_domains,
             kanban,
             kanban_queries,
+            clipboard_challenges: Arc::new(Mutex::new(HashMap::new())),
+            recent_appends: Arc::new(Mutex::new(HashMap::new())),
+            log,
+            metrics,
+            started: Instant::now(),
+            latencies: Arc::new(Mutex::new(HashMap::new())),
+            staged: Arc::new(Mutex::new(HashMap::new())),
+            delete_challenges: Arc::new(Mutex::new(HashMap::new())),
+            written_files: Arc::new(Mutex::new(HashSet::new())),
+            startup_dir: None,
+            newest_change: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Count this server's startup toward safe mode: the first `initialize` it answers
+    /// clears the failed-startup record in `config_dir`.
+    pub fn track_startup(&mut self, config_dir: PathBuf) {
+        self.startup_dir = Some(config_dir);
+    }
+
     #[tool(description = "Search the vault index, query project history, read files, or get a prioritized work queue. Use `action` to specify what you need.")]
     async fn wardwell_search(&self, params: Parameters<SearchParams>) -> String {
+        let started = Instant::now();
         let p = params.0;
-        match p.action.as_str() {
+        let out = match p.action.as_str() {
             "search" => self.action_search(&p),
             "read" => self.action_read(&p),
             "history" => self.action_history(&p),
             "orchestrate" => self.action_orchestrate(&p),
+            "effort" => self.action_effort(&p),
+            "retrospective" if p.enrich.unwrap_or(false) => self.action_retrospective_enriched(&p).await,
             "retrospective" => self.action_retrospective(&p),
             "patterns" => self.action_patterns(&p),
             "context" => self.action_context(&p).await,
             "resume" => self.action_resume(&p).await,
-            other => json_error(&format!("Unknown action: '{other}'. Use search, read, history, orchestrate, retrospective, patterns, context, or resume.")),
+            "excerpt" => self.action_excerpt(&p),
+            "recall" => self.action_recall(&p),
+            "domains" => self.action_domains(),
+            "projects" => self.action_projects(&p),
+            "sources" => self.action_sources(&p),
+            "diff" => self.action_diff(&p),
+            "domain_errors" => self.action_domain_errors(),
+            "entry" => self.action_entry(&p),
+            "lists" => self.action_lists(&p),
+            "list_read" => self.action_list_read(&p),
+            "sessions_around" => self.action_sessions_around(&p),
+            "journal" => self.action_journal_entries(&p),
+            "state" => self.action_state(&p),
+            "tags" => self.action_tags(&p),
+            "links" => self.action_links(&p),
+            "duplicates" => self.action_duplicates(&p),
+            "digest" => self.action_digest(&p),
+            "server_status" => self.action_server_status(),
+            "help" => self.action_help(),
+            other => json_error(&format!("Unknown action: '{other}'. Use search, read, history, entry, lists, list_read, orchestrate, retrospective, patterns, sources, context, resume, excerpt, recall, effort, diff, sessions_around, journal, state, tags, links, duplicates, digest, domains, domain_errors, projects, server_status, or help.")),
+        };
+        self.count_call("search", &p.action);
+        self.time_call("search", &p.action, started, &p);
+        if crate::mcp::metrics::SEARCH_ACTIONS.contains(&p.action.as_str())
+            && let Some(found) = crate::mcp::metrics::found_results(&out)
+        {
+            let metric = if found { Metric::SearchHit } else { Metric::SearchMiss };
+            self.count(metric, &p.action);
         }
+        let out = match &p.fields {
+            Some(fields) => crate::mcp::response::select(out, fields),
+            None => out,
+        };
+        self.fit_response(out, |cuts| search_hint(&p, cuts))
     }
 
     #[tool(description = "Write to the vault. Sync project state, record decisions, append history, or record lessons. Use `action` to specify the operation.")]
     async fn wardwell_write(&self, params: Parameters<WriteParams>) -> String {
+        let started = Instant::now();
         let p = params.0;
 
         // ACL: check domain access before any write
@@ -270,49 +492,164 @@ impl WardwellServer {
             return json_error(&e);
         }
 
-        // Resolve project: explicit > inferred from last access
+        // Daily notes sit outside projects, so nothing is resolved or inferred for them
+        if p.action == "journal" {
+            let out = self.action_journal(&p);
+            self.count_call("write", &p.action);
+            self.time_call("write", &p.action, started, &p);
+            return self.fit_response(out, |_| NARROW_HINT.to_string());
+        }
+
+        // Map near-miss names ("Sentry Bot") onto existing folders instead of creating duplicates
+        let mut notes = Vec::new();
+
+        // A domain nobody has seen before is more often a typo than a new area of work
+        if !self.is_known_domain(&p.domain) {
+            if !p.confirm_new_domain.unwrap_or(false) {
+                return self.new_domain_prompt(&p.domain);
+            }
+            if p.dry_run.unwrap_or(false) {
+                notes.push(format!("would create new domain '{}'", p.domain));
+            } else {
+                eprintln!("[WARDWELL AUDIT] NEW DOMAIN: '{}' created by write action={} project={:?}", p.domain, p.action, p.project);
+                notes.push(format!("created new domain '{}' — add domains/{}.md to give it paths and aliases", p.domain, p.domain));
+            }
+        }
+
+        // Resolve project: explicit > inferred from last access > inferred from the session's cwd
         let project = match p.project.clone() {
             Some(proj) => proj,
             None => match self.last_project.lock().ok().and_then(|lp| lp.clone()) {
                 Some((d, proj)) if d == p.domain => proj,
-                Some(_) => return json_error("'project' is required — last accessed project is in a different domain."),
-                None => return json_error("'project' is required — no project accessed in this session to infer from."),
+                last => {
+                    // The client starts the server in the session's project directory, so its own
+                    // working directory is the last resort
+                    let cwd = p.cwd.clone()
+                        .or_else(|| std::env::var(CWD_ENV).ok())
+                        .filter(|c| !c.trim().is_empty())
+                        .or_else(|| std::env::current_dir().ok().map(|d| d.to_string_lossy().to_string()));
+                    match cwd.as_deref().and_then(|c| self.project_from_cwd(&p.domain, std::path::Path::new(c))) {
+                        Some(proj) => {
+                            notes.push(format!("project '{proj}' inferred from working directory {}", cwd.as_deref().unwrap_or_default()));
+                            proj
+                        }
+                        None if last.is_some() => return json_error("'project' is required — last accessed project is in a different domain, and no cwd matched a project in this one."),
+                        None => return json_error("'project' is required — no project accessed in this session to infer from, and no cwd matched a project."),
+                    }
+                }
             },
         };
 
+        // Unarchive names a project under <domain>/archive/
+        let projects_dir = match p.action.as_str() {
+            "unarchive" => self.vault_root.join(&p.domain).join(crate::vault::project::ARCHIVE_DIR),
+            _ => self.vault_root.join(&p.domain),
+        };
+        let project = match crate::vault::project::resolve_project(&projects_dir, &project) {
+            ProjectMatch::Exact(name) => name,
+            ProjectMatch::New if p.action != "unarchive" && !p.confirmed.unwrap_or(false)
+                && let Some(archived) = crate::vault::project::archived_project(&projects_dir, &project) =>
+            {
+                return serde_json::to_string_pretty(&serde_json::json!({
+                    "error": false,
+                    "needs_confirmation": true,
+                    "message": format!("Project '{project}' does not exist in '{}', but it was archived as '{}/{archived}'. Unarchive it with action 'unarchive' and project='{archived}', or set confirmed=true to start a new '{project}'.", p.domain, crate::vault::project::ARCHIVE_DIR),
+                    "archived_project": archived,
+                })).unwrap_or_default();
+            }
+            ProjectMatch::New => self.new_project_name(project, &mut notes),
+            ProjectMatch::Ambiguous(candidates) => {
+                return serde_json::to_string_pretty(&serde_json::json!({
+                    "error": format!("Project '{project}' matches several folders in '{}': {}. Retry with project set to one of them exactly.", p.domain, candidates.join(", ")),
+                    "ambiguous_project": true,
+                    "candidates": candidates,
+                })).unwrap_or_default();
+            }
+            ProjectMatch::Slug(name) | ProjectMatch::Alias(name) => {
+                notes.push(format!("project '{project}' resolved to existing project '{name}'"));
+                name
+            }
+            ProjectMatch::Fuzzy { project: suggested, score } if !p.confirmed.unwrap_or(false) => {
+                return serde_json::to_string_pretty(&serde_json::json!({
+                    "error": false,
+                    "needs_confirmation": true,
+                    "message": format!("Project '{project}' does not exist in '{}', but '{suggested}' is similar. Retry with project='{suggested}', or set confirmed=true to create '{project}'.", p.domain),
+                    "suggested_project": suggested,
+                    "similarity": (score * 100.0).round() / 100.0,
+                })).unwrap_or_default();
+            }
+            ProjectMatch::Fuzzy { .. } => self.new_project_name(project, &mut notes),
+        };
+        if !crate::vault::project::is_folder_name(&project) {
+            return json_error(&format!("'{project}' can't be a project folder name."));
+        }
+
         // Check if this project was accessed (searched/read) in this session
         let key = format!("{}/{}", p.domain, project);
         let was_accessed = self.accessed_projects.lock()
             .map(|set| set.contains(&key))
             .unwrap_or(true);
-        let warning = if was_accessed {
-            None
-        } else {
-            Some(format!("project '{key}' was not read or searched in this session"))
-        };
+        if !was_accessed {
+            let state = format!("{key}/current_state.md");
+            if self.config.write_policy == WritePolicy::RequireRead
+                && matches!(p.action.as_str(), "sync" | "decide")
+                && self.vault_root.join(&state).exists()
+            {
+                return read_required(&key, &p.action, &state);
+            }
+            notes.push(format!("project '{key}' was not read or searched in this session"));
+        }
+        let warning = if notes.is_empty() { None } else { Some(notes.join("; ")) };
         let inferred = p.project.is_none();
 
-        match p.action.as_str() {
-            "sync" => self.action_sync(&p, &project, warning.as_deref(), inferred),
+        let out = match p.action.as_str() {
+            "sync" => {
+                let (out, synced) = self.action_sync(&p, &project, warning.as_deref(), inferred);
+                if synced {
+                    self.count(Metric::Sync, &key);
+                }
+                out
+            }
             "decide" => self.action_decide(&p, &project, warning.as_deref()),
+            "approve" => self.action_approve(&p, &project),
             "append_history" => self.action_append_history(&p, &project, warning.as_deref()),
             "lesson" => self.action_lesson(&p, &project, warning.as_deref()),
             "append" => self.action_append_list(&p, &project, warning.as_deref()),
             "write_file" => self.action_write_file(&p, &project),
-            other => json_error(&format!("Unknown action: '{other}'. Use sync, decide, append_history, lesson, append, or write_file.")),
-        }
+            "tag" => self.action_tag(&p, &project),
+            "stage" => self.action_stage(&p, &project),
+            "delete" => self.action_delete(&p, &project),
+            "archive" => self.action_archive(&p, &project, false),
+            "unarchive" => self.action_archive(&p, &project, true),
+            "rename" => self.action_rename(&p, &project),
+            other => json_error(&format!("Unknown action: '{other}'. Use sync, decide, approve, append_history, lesson, append, write_file, tag, stage, delete, archive, unarchive, rename, or journal.")),
+        };
+        self.count_call("write", &p.action);
+        self.time_call("write", &p.action, started, &p);
+        self.fit_response(out, |_| NARROW_HINT.to_string())
     }
 
-    #[tool(description = "Copy content to the system clipboard via pbcopy. IMPORTANT: Always ask the user for permission before calling this tool. Never overwrite the clipboard silently.")]
+    #[tool(description = "Copy content to the system clipboard via pbcopy. Two-step: the first call returns a confirm_token and copies nothing. Ask the user for permission, then repeat the call with the same content and confirm_token to copy.")]
     async fn wardwell_clipboard(&self, params: Parameters<ClipboardParams>) -> String {
         let p = params.0;
-        match clipboard_copy(&p.content) {
-            Ok(bytes) => serde_json::to_string(&serde_json::json!({
-                "copied": true,
-                "bytes": bytes,
-            })).unwrap_or_default(),
-            Err(e) => json_error(&format!("Clipboard failed: {e}")),
-        }
+        let started = Instant::now();
+        let action = if p.confirm_token.is_some() { "confirm" } else { "challenge" };
+        self.count_call("clipboard", action);
+        let out = match p.confirm_token {
+            None => self.clipboard_challenge(&p.content),
+            Some(ref token) => match self.clipboard_confirm(token, &p.content) {
+                Ok(()) => match clipboard_copy(&p.content) {
+                    Ok(bytes) => serde_json::to_string(&serde_json::json!({
+                        "copied": true,
+                        "bytes": bytes,
+                    })).unwrap_or_default(),
+                    Err(e) => json_error(&format!("Clipboard failed: {e}")),
+                },
+                Err(e) => json_error(&e),
+            },
+        };
+        self.time_call("clipboard", action, started, &p);
+        out
     }
 
     #[tool(description = "Project kanban board. Create, update, move, and query work items across projects. Items have ticket IDs (e.g., SH-3), status (backlog->todo->in_progress->review->done), priority, assignee, deadline, notes, and file attachments.")]
@@ -320,8 +657,14 @@ impl WardwellServer {
         let Some(ref kanban) = self.kanban else {
             return json_error("kanban is disabled — set kanban.enabled: true in ~/.wardwell/config.yml");
         };
+        let started = Instant::now();
         let p = params.0;
-        match p.action.as_str() {
+        self.count_call("kanban", &p.action);
+        if self.config.read_only && !KANBAN_READ_ACTIONS.contains(&p.action.as_str()) {
+            eprintln!("[WARDWELL ACL] DENIED: read-only server, tool=wardwell_kanban action={}", p.action);
+            return read_only_error(&format!("kanban action '{}'", p.action));
+        }
+        let out = match p.action.as_str() {
             "list" => self.kanban_list(kanban, &p),
             "create" => self.kanban_create(kanban, &p),
             "update" => self.kanban_update(kanban, &p),
@@ -335,7 +678,9 @@ impl WardwellServer {
             "sequence" => self.kanban_sequence(kanban, &p),
             "export_roadmap" => self.kanban_export_roadmap(&p),
             other => json_error(&format!("unknown kanban action '{other}'. Use: get, list, search, create, update, move, note, query, attach, detach, sequence, export_roadmap")),
-        }
+        };
+        self.time_call("kanban", &p.action, started, &p);
+        self.fit_response(out, |_| NARROW_HINT.to_string())
     }
 }
 
@@ -357,6 +702,11 @@ impl WardwellServer {
         }
     }
 
+    /// Subdirectories of a vault directory, per the configured symlink policy.
+    fn subdirs(&self, dir: &std::path::Path) -> Vec<PathBuf> {
+        list_subdirs(&self.vault_root, dir, self.config.symlinks)
+    }
+
     /// Filter domains for vault-walking actions. Returns the list of domain dirs to scan.
     fn scoped_domain_dirs(&self, vault_dir: &std::path::Path, client_domain: Option<&str>) -> Vec<PathBuf> {
         if !self.allowed_domains.is_empty() {
@@ -369,9 +719,247 @@ impl WardwellServer {
             // Domainless mode: honor client filter
             match client_domain {
                 Some(d) => vec![vault_dir.join(d)],
-                None => list_subdirs(vault_dir),
+                None => self.subdirs(vault_dir),
+            }
+        }
+    }
+}
+
+// -- Lists --
+
+/// Project logs the write action `append` never writes to, so `lists` leaves them out.
+const BUILTIN_LISTS: &[&str] = &["history.jsonl", "lessons.jsonl", crate::vault::compress::HISTORY_ARCHIVE, crate::daemon::decisions::QUEUE];
+/// Entries `list_read` returns when no `limit` is given.
+const LIST_READ_DEFAULT_LIMIT: usize = 20;
+/// Upper bound on entries per `list_read` call.
+const LIST_READ_MAX_LIMIT: usize = 100;
+
+impl WardwellServer {
+    /// The folder of the project `p` names, after checking access. Err is the response.
+    fn list_project(&self, p: &SearchParams, action: &str) -> Result<(String, PathBuf), String> {
+        let (Some(domain), Some(project)) = (&p.domain, &p.project) else {
+            return Err(json_error(&format!("'domain' and 'project' are required for action '{action}'.")));
+        };
+        self.check_domain_access(domain, action).map_err(|e| json_error(&e))?;
+        let domain_dir = self.vault_root.join(domain);
+        match crate::vault::project::resolve_project(&domain_dir, project) {
+            ProjectMatch::Exact(name) | ProjectMatch::Slug(name) | ProjectMatch::Alias(name) => {
+                self.record_access(domain, &name);
+                Ok((format!("{domain}/{name}"), domain_dir.join(name)))
+            }
+            _ => Err(json_error(&format!("No project '{project}' in domain '{domain}'."))),
+        }
+    }
+
+    fn action_lists(&self, p: &SearchParams) -> String {
+        let (key, project_dir) = match self.list_project(p, "lists") {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+        let lists: Vec<serde_json::Value> = custom_lists(&project_dir).into_iter().map(|name| {
+            let path = project_dir.join(format!("{name}.jsonl"));
+            let entries = list_entries(&path);
+            // The newest entry's date, or the file's own time for a list of undated entries
+            let last_updated = entries.iter().filter_map(|e| e["date"].as_str()).max().map(String::from).or_else(|| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339())
+            });
+            serde_json::json!({
+                "name": name,
+                "entries": entries.len(),
+                "last_updated": last_updated,
+                "path": self.vault_rel(&path),
+            })
+        }).collect();
+        serde_json::to_string_pretty(&serde_json::json!({
+            "project": key,
+            "count": lists.len(),
+            "lists": lists,
+        })).unwrap_or_default()
+    }
+
+    fn action_list_read(&self, p: &SearchParams) -> String {
+        let Some(name) = p.list.as_deref().map(|l| l.trim().trim_end_matches(".jsonl")) else {
+            return json_error("'list' is required for action 'list_read': the list's name, as given to append.");
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return json_error("'list' must contain only alphanumeric characters, hyphens, and underscores.");
+        }
+        let (key, project_dir) = match self.list_project(p, "list_read") {
+            Ok(found) => found,
+            Err(response) => return response,
+        };
+        let file_name = format!("{name}.jsonl");
+        let path = project_dir.join(&file_name);
+        if BUILTIN_LISTS.contains(&file_name.as_str()) {
+            return json_error(&format!("'{name}' is a built-in project log, not a list made with append. Read it with action 'read' (path '{}').", self.vault_rel(&path)));
+        }
+        if !path.is_file() {
+            return serde_json::to_string_pretty(&serde_json::json!({
+                "error": format!("No list '{name}' in {key}."),
+                "existing_lists": custom_lists(&project_dir),
+            })).unwrap_or_default();
+        }
+
+        let mut entries = list_entries(&path);
+        entries.reverse();
+        let total = entries.len();
+        let start = p.start.unwrap_or(0).min(total);
+        let limit = p.limit.unwrap_or(LIST_READ_DEFAULT_LIMIT).clamp(1, LIST_READ_MAX_LIMIT);
+        let page: Vec<serde_json::Value> = entries.into_iter().skip(start).take(limit).collect();
+        let next = start + page.len();
+        let mut resp = serde_json::json!({
+            "list": name,
+            "project": key,
+            "path": self.vault_rel(&path),
+            "total": total,
+            "start": start,
+            "count": page.len(),
+            "entries": page,
+        });
+        if next < total {
+            resp["next_start"] = serde_json::json!(next);
+        }
+        serde_json::to_string_pretty(&resp).unwrap_or_default()
+    }
+}
+
+/// Names of the lists made with `append` in a project folder, sorted.
+fn custom_lists(project_dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(project_dir).into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .filter(|n| !BUILTIN_LISTS.contains(&n.as_str()))
+        .filter_map(|n| n.strip_suffix(".jsonl").map(String::from))
+        .collect();
+    names.sort();
+    names
+}
+
+/// A list's entries in file order, oldest first, without the schema header. Lines that
+/// don't parse are left out.
+fn list_entries(path: &std::path::Path) -> Vec<serde_json::Value> {
+    crate::vault::compress::read_to_string(path).unwrap_or_default()
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !l.starts_with("{\"_schema\""))
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+// -- New domains --
+
+/// Similarity above which an unknown domain is offered the known one as a correction.
+const DOMAIN_SUGGESTION_THRESHOLD: f64 = 0.8;
+
+impl WardwellServer {
+    /// Domains a write may target without confirmation: registry entries and existing
+    /// top-level vault folders.
+    fn known_domains(&self) -> Vec<String> {
+        let mut known = match self.registry.try_read() {
+            Ok(registry) => registry.names(),
+            Err(_) => self.config.registry.names(),
+        };
+        for dir in self.subdirs(&self.vault_root) {
+            if let Some(name) = dir.file_name().and_then(|n| n.to_str())
+                && !name.starts_with('.')
+                && !known.iter().any(|k| k == name)
+            {
+                known.push(name.to_string());
+            }
+        }
+        known.sort();
+        known
+    }
+
+    fn is_known_domain(&self, domain: &str) -> bool {
+        self.vault_root.join(domain).is_dir() || self.known_domains().iter().any(|d| d == domain)
+    }
+
+    /// The confirmation asked for before a write creates `domain`, with the closest known
+    /// domain when one is similar.
+    fn new_domain_prompt(&self, domain: &str) -> String {
+        let known = self.known_domains();
+        let suggested = known.iter()
+            .map(|k| (k, strsim::jaro_winkler(&domain.to_lowercase(), &k.to_lowercase())))
+            .filter(|(_, score)| *score >= DOMAIN_SUGGESTION_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(k, _)| k.clone());
+        eprintln!("[WARDWELL AUDIT] write to unknown domain '{domain}' held for confirmation (suggested: {suggested:?})");
+        let message = match &suggested {
+            Some(s) => format!("Domain '{domain}' doesn't exist yet. Did you mean '{s}'? Retry with domain='{s}', or ask the user and set confirm_new_domain=true to create '{domain}'."),
+            None => format!("Domain '{domain}' doesn't exist yet. Ask the user, then set confirm_new_domain=true to create it. Known domains: {}.", if known.is_empty() { "none".to_string() } else { known.join(", ") }),
+        };
+        serde_json::to_string_pretty(&serde_json::json!({
+            "error": false,
+            "needs_confirmation": true,
+            "new_domain": domain,
+            "suggested_domain": suggested,
+            "known_domains": known,
+            "message": message,
+        })).unwrap_or_default()
+    }
+}
+
+// -- Index freshness --
+
+impl WardwellServer {
+    /// Check whether the index lags behind the vault. With `stale_index.self_heal`,
+    /// rebuilds the FTS index in place. Returns a warning to attach to the response.
+    fn stale_warning(&self) -> Option<String> {
+        let cfg = &self.config.stale_index;
+        let threshold = chrono::Duration::minutes(cfg.threshold_minutes as i64);
+        let stale = match crate::index::freshness::assess(&self.index, &self.vault_root, self.newest_vault_change(), threshold) {
+            Ok(Some(s)) => s,
+            Ok(None) => return None,
+            Err(e) => {
+                eprintln!("wardwell: index freshness check failed: {e}");
+                return None;
+            }
+        };
+        if cfg.self_heal {
+            eprintln!("wardwell: {} — rebuilding", stale.message());
+            match crate::index::builder::IndexBuilder::build_filtered(&self.index, &self.vault_root, &self.config.exclude, self.config.symlinks, &self.config.statuses, None) {
+                Ok(stats) => return Some(format!(
+                    "index was stale ({} changed after the last index update); rebuilt before answering ({} files updated, {} removed)",
+                    stale.newest_path, stats.indexed, stats.removed,
+                )),
+                Err(e) => eprintln!("wardwell: self-heal reindex failed: {e}"),
             }
         }
+        Some(stale.message())
+    }
+
+    /// The newest vault file change, walking the vault at most once per `NEWEST_CHANGE_TTL`.
+    fn newest_vault_change(&self) -> NewestChange {
+        if let Ok(cached) = self.newest_change.lock()
+            && let Some((walked, newest)) = cached.as_ref()
+            && walked.elapsed() < NEWEST_CHANGE_TTL
+        {
+            return newest.clone();
+        }
+        let newest = crate::index::freshness::newest_mtime(&self.vault_root, &self.config.exclude, self.config.symlinks);
+        if let Ok(mut cached) = self.newest_change.lock() {
+            *cached = Some((Instant::now(), newest.clone()));
+        }
+        newest
+    }
+
+    /// Cut a response over `max_response_bytes` down to fit (see `response::fit`).
+    fn fit_response(&self, json: String, hint: impl FnOnce(&[crate::mcp::response::Cut]) -> String) -> String {
+        crate::mcp::response::fit(json, self.config.max_response_bytes, hint)
+    }
+
+    /// Serialize a response, adding `stale_warning` when the index lags behind the vault.
+    fn to_json_with_stale_warning<T: Serialize>(&self, value: &T) -> String {
+        let mut json = serde_json::to_value(value).unwrap_or_default();
+        if let Some(warning) = self.stale_warning()
+            && let Some(obj) = json.as_object_mut()
+        {
+            obj.insert("stale_warning".to_string(), serde_json::Value::String(warning));
+        }
+        serde_json::to_string_pretty(&json).unwrap_or_default()
     }
 }
 
@@ -390,6 +978,264 @@ impl WardwellServer {
     }
 }
 
+// -- Clipboard confirmation --
+
+impl WardwellServer {
+    /// Issue a confirm token for `content` without touching the clipboard.
+    fn clipboard_challenge(&self, content: &str) -> String {
+        let token = uuid::Uuid::new_v4().to_string();
+        let Ok(mut challenges) = self.clipboard_challenges.lock() else {
+            return json_error("Clipboard challenge lock poisoned.");
+        };
+        challenges.retain(|_, c| c.issued_at.elapsed() < CLIPBOARD_TOKEN_TTL);
+        challenges.insert(token.clone(), ConfirmChallenge {
+            content_hash: crate::index::builder::compute_hash(content),
+            issued_at: Instant::now(),
+        });
+        eprintln!("[WARDWELL AUDIT] clipboard challenge issued: token={token} bytes={}", content.len());
+
+        serde_json::to_string(&serde_json::json!({
+            "copied": false,
+            "needs_confirmation": true,
+            "confirm_token": token,
+            "bytes": content.len(),
+            "message": "Nothing was copied. Ask the user for permission, then repeat this call with the same content and confirm_token.",
+        })).unwrap_or_default()
+    }
+
+    /// Consume a confirm token. Tokens are single-use and only valid for the content they were issued for.
+    fn clipboard_confirm(&self, token: &str, content: &str) -> Result<(), String> {
+        let mut challenges = self.clipboard_challenges.lock()
+            .map_err(|_| "Clipboard challenge lock poisoned.".to_string())?;
+        let Some(challenge) = challenges.remove(token) else {
+            eprintln!("[WARDWELL AUDIT] clipboard confirm rejected: token={token} reason=unknown");
+            return Err("Unknown or already-used confirm_token. Call again without confirm_token to get a new one.".to_string());
+        };
+        if challenge.issued_at.elapsed() >= CLIPBOARD_TOKEN_TTL {
+            eprintln!("[WARDWELL AUDIT] clipboard confirm rejected: token={token} reason=expired");
+            return Err("confirm_token expired. Call again without confirm_token to get a new one.".to_string());
+        }
+        if challenge.content_hash != crate::index::builder::compute_hash(content) {
+            eprintln!("[WARDWELL AUDIT] clipboard confirm rejected: token={token} reason=content_mismatch");
+            return Err("confirm_token was issued for different content. Call again without confirm_token to get a new one.".to_string());
+        }
+        eprintln!("[WARDWELL AUDIT] clipboard confirmed: token={token} bytes={}", content.len());
+        Ok(())
+    }
+}
+
+// -- Call metrics --
+
+/// Latencies kept per action for `server_status` since start.
+const LATENCY_SAMPLES: usize = 500;
+
+/// Days of stored latencies `server_status` reports.
+const LATENCY_WEEK_DAYS: u64 = 7;
+
+impl WardwellServer {
+    /// Bump a local usage counter. Failures only cost the count; the call itself goes on.
+    fn count(&self, metric: Metric, key: &str) {
+        if let Some(ref metrics) = self.metrics
+            && let Err(e) = metrics.record(metric, key)
+        {
+            eprintln!("wardwell: failed to record metric: {e}");
+        }
+    }
+
+    fn count_call(&self, tool: &str, action: &str) {
+        self.count(Metric::ToolCall, &format!("{tool}.{action}"));
+    }
+
+    /// Record how long a call took, and log it with its sanitized parameters when it took
+    /// longer than `slow_action_ms`.
+    fn time_call(&self, tool: &str, action: &str, started: Instant, params: &impl Serialize) {
+        let ms = started.elapsed().as_millis() as u64;
+        let key = format!("{tool}.{action}");
+        if let Ok(mut latencies) = self.latencies.lock() {
+            let samples = latencies.entry(key.clone()).or_default();
+            if samples.len() >= LATENCY_SAMPLES {
+                samples.remove(0);
+            }
+            samples.push(ms);
+        }
+        if let Some(ref metrics) = self.metrics
+            && let Err(e) = metrics.record_latency(&key, ms)
+        {
+            eprintln!("wardwell: failed to record latency: {e}");
+        }
+        let slow = self.config.slow_action_ms;
+        if slow > 0 && ms >= slow {
+            let params = serde_json::to_value(params).map(crate::mcp::metrics::sanitize).unwrap_or_default();
+            self.log.warning("latency", &format!("slow call: {key} took {ms} ms (slow_action_ms: {slow}) with {params}"));
+        }
+    }
+
+    /// Uptime and per-action latency: since this server started, and over the last week
+    /// from metrics.db when `metrics: true`.
+    fn action_server_status(&self) -> String {
+        let since_start: std::collections::BTreeMap<String, crate::mcp::metrics::Latency> = self.latencies.lock()
+            .map(|l| l.iter().map(|(k, v)| (k.clone(), crate::mcp::metrics::Latency::of(&mut v.clone()))).collect())
+            .unwrap_or_default();
+        let since = (chrono::Local::now().date_naive() - chrono::Days::new(LATENCY_WEEK_DAYS - 1)).to_string();
+        let last_week = self.metrics.as_ref().and_then(|m| m.latencies(&since).ok());
+        let mut slow: Vec<(&String, u64)> = last_week.as_ref().unwrap_or(&since_start).iter()
+            .filter(|(_, l)| self.config.slow_action_ms > 0 && l.p95_ms >= self.config.slow_action_ms)
+            .map(|(k, l)| (k, l.p95_ms))
+            .collect();
+        slow.sort_by_key(|s| std::cmp::Reverse(s.1));
+        serde_json::to_string_pretty(&serde_json::json!({
+            "uptime_seconds": self.started.elapsed().as_secs(),
+            "slow_action_ms": self.config.slow_action_ms,
+            "metrics_enabled": self.metrics.is_some(),
+            "latency_since_start": since_start,
+            "latency_last_7_days": last_week,
+            "slow_actions": slow.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+        })).unwrap_or_default()
+    }
+}
+
+// -- Append dedup --
+
+impl WardwellServer {
+    /// Folder name for a project that doesn't exist yet. Names with spaces or non-ASCII
+    /// letters ("Café Menü") are slugified per `slug_transliteration`; plain ASCII names are
+    /// kept as given when they're a usable folder name. Callers still check the result with
+    /// `is_folder_name`, since `..` has no slug.
+    fn new_project_name(&self, project: String, notes: &mut Vec<String>) -> String {
+        if project.chars().all(|c| c.is_ascii_graphic()) && crate::vault::project::is_folder_name(&project) {
+            return project;
+        }
+        let slug = crate::vault::project::slugify_with(&project, self.config.slug_transliteration);
+        if slug.is_empty() || slug == project {
+            return project;
+        }
+        notes.push(format!("new project '{project}' stored as '{slug}'"));
+        slug
+    }
+
+    /// The entry ID of an identical append made within the dedup window, if there was one.
+    fn duplicate_append(&self, hash: &str) -> Option<String> {
+        let window = Duration::from_secs(self.config.dedup_window_seconds);
+        if window.is_zero() {
+            return None;
+        }
+        let mut recent = self.recent_appends.lock().ok()?;
+        recent.retain(|_, (at, _)| at.elapsed() < window);
+        recent.get(hash).map(|(_, id)| id.clone())
+    }
+
+    /// `path` relative to the vault root, as dry-run changes name files.
+    fn vault_rel(&self, path: &std::path::Path) -> String {
+        path.strip_prefix(&self.vault_root).unwrap_or(path).to_string_lossy().to_string()
+    }
+
+    /// `path` as returned to clients: vault-relative, or an `{alias:...}`/`{domain:...}`
+    /// reference that resolves on any machine, rather than this machine's absolute path.
+    fn portable(&self, path: &str) -> String {
+        match self.registry.try_read() {
+            Ok(registry) => crate::alias::portable_path(path, &self.vault_root, registry.all()),
+            Err(_) => crate::alias::portable_path(path, &self.vault_root, &[]),
+        }
+    }
+
+    /// A dry run's entry for appending `entry_json` to the JSONL file at `path`.
+    fn planned_jsonl(&self, path: &std::path::Path, schema_name: &str, entry_json: &str) -> serde_json::Value {
+        let previous = std::fs::read_to_string(path).ok();
+        planned_append(&self.vault_rel(path), previous.as_deref(), &crate::vault::jsonl::append_text(path, schema_name, entry_json))
+    }
+
+    /// Remember a successful append so an identical repeat within the window is skipped
+    /// and answered with the same entry ID.
+    fn record_append(&self, hash: String, id: String) {
+        if self.config.dedup_window_seconds == 0 {
+            return;
+        }
+        if let Ok(mut recent) = self.recent_appends.lock() {
+            recent.insert(hash, (Instant::now(), id));
+        }
+    }
+
+    /// Append `entry` (whose ID is `id`) to the JSONL file at `path`, unless an identical
+    /// entry went in within the dedup window. With `planned`, nothing is written and the
+    /// change is pushed there instead. Errs with the message for the client.
+    fn append_once(
+        &self,
+        path: &std::path::Path,
+        schema_name: &str,
+        entry: &impl Serialize,
+        id: &str,
+        planned: Option<&mut Vec<serde_json::Value>>,
+    ) -> Result<Appended, String> {
+        let hash = append_hash(path, entry);
+        if let Some(id) = self.duplicate_append(&hash) {
+            return Ok(Appended { id, deduplicated: true });
+        }
+        let json = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize {schema_name} entry: {e}"))?;
+        match planned {
+            Some(planned) => planned.push(self.planned_jsonl(path, schema_name, &json)),
+            None => {
+                append_jsonl(path, schema_name, &json, &self.config.durability)
+                    .map_err(|e| format!("Failed to write {}: {e}", path.file_name().unwrap_or_default().to_string_lossy()))?;
+                self.record_append(hash, id.to_string());
+            }
+        }
+        Ok(Appended { id: id.to_string(), deduplicated: false })
+    }
+}
+
+/// What `append_once` did: the ID the entry is stored under, which is the earlier entry's
+/// when an identical append was deduplicated.
+struct Appended {
+    id: String,
+    deduplicated: bool,
+}
+
+/// Field-level summary of what a sync changed: status, focus, and next action as `{from, to}`,
+/// plus any other sections that were added, removed, or edited.
+fn sync_changes(previous: &str, current: &str) -> serde_json::Value {
+    let status = |content: &str| {
+        crate::vault::frontmatter::frontmatter_yaml(content)
+            .and_then(|y| serde_yaml::from_str::<serde_yaml::Value>(y).ok())
+            .and_then(|v| v.get("status").and_then(|s| s.as_str()).map(String::from))
+            .unwrap_or_default()
+    };
+    let body = |content: &str| match crate::vault::frontmatter::parse_frontmatter(content) {
+        Ok((_, body)) => body,
+        Err(_) => content.to_string(),
+    };
+    let (old_body, new_body) = (body(previous), body(current));
+
+    let mut changes = serde_json::Map::new();
+    let fields = [
+        ("status", status(previous), status(current)),
+        ("focus", extract_section(&old_body, "Focus"), extract_section(&new_body, "Focus")),
+        ("next_action", extract_section(&old_body, "Next Action"), extract_section(&new_body, "Next Action")),
+    ];
+    for (name, from, to) in fields {
+        if from != to {
+            changes.insert(name.to_string(), serde_json::json!({ "from": from, "to": to }));
+        }
+    }
+    let other: Vec<String> = crate::vault::diff::changed_sections(&old_body, &new_body)
+        .into_iter()
+        .filter(|h| !h.is_empty() && h != "Focus" && h != "Next Action")
+        .collect();
+    if !other.is_empty() {
+        changes.insert("sections".to_string(), serde_json::json!(other));
+    }
+    serde_json::Value::Object(changes)
+}
+
+/// Hash of an append's target file and content, ignoring its `date` so retries match.
+fn append_hash(path: &std::path::Path, entry: &impl Serialize) -> String {
+    let mut value = serde_json::to_value(entry).unwrap_or_default();
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("date");
+        obj.remove("id");
+    }
+    crate::index::builder::compute_hash(&format!("{}\n{value}", path.display()))
+}
+
 /// Extract (domain, project) from a vault-relative path like "work/sentry-bot/current_state.md".
 fn extract_domain_project(path: &str) -> Option<(String, String)> {
     let parts: Vec<&str> = path.split('/').collect();
@@ -403,15 +1249,30 @@ fn extract_domain_project(path: &str) -> Option<(String, String)> {
 // -- Search actions --
 
 impl WardwellServer {
-    fn action_search(&self, p: &SearchParams) -> String {
-        let query_str = match &p.query {
-            Some(q) => q.clone(),
+    pub(crate) fn action_search(&self, p: &SearchParams) -> String {
+        let (query_str, tags) = match &p.query {
+            Some(q) => split_tag_filters(q),
             None => return json_error("'query' is required for action 'search'."),
         };
+        if query_str.is_empty() && tags.is_empty() {
+            return json_error("'query' is required for action 'search'.");
+        }
 
-        // Check if semantic/hybrid mode requested
-        if p.mode.as_deref() == Some("semantic") {
-            return self.action_search_semantic(&query_str, p);
+        let mode = match p.mode.as_deref() {
+            None => SearchMode::Keyword,
+            Some(m) => match SearchMode::parse(m) {
+                Some(mode) => mode,
+                None => return json_error(&format!("Unknown search mode: '{m}'. Use keyword, hybrid (or semantic), or vector.")),
+            },
+        };
+        if mode != SearchMode::Keyword {
+            if !tags.is_empty() {
+                return json_error("tag: filters work in keyword mode only. Drop mode, or search without tag: filters.");
+            }
+            if p.exclude_terms.is_some() || p.exclude_domains.is_some() {
+                return json_error("exclude_terms and exclude_domains work in keyword mode only. Drop mode, or search without exclusions.");
+            }
+            return self.action_search_semantic(&query_str, p, mode);
         }
 
         let search_domains = if self.allowed_domains.is_empty() {
@@ -427,6 +1288,9 @@ impl WardwellServer {
             domains: search_domains,
             types: Vec::new(),
             status: None,
+            tags,
+            exclude_terms: p.exclude_terms.clone().unwrap_or_default(),
+            exclude_domains: p.exclude_domains.clone().unwrap_or_default(),
             limit: p.limit.unwrap_or(5),
         };
 
@@ -438,19 +1302,19 @@ impl WardwellServer {
                         self.record_access(&d, &p);
                     }
                 }
-                serde_json::to_string_pretty(&results).unwrap_or_default()
+                self.to_json_with_stale_warning(&results)
             }
             Err(e) => json_error(&format!("Search failed: {e}")),
         }
     }
 
-    fn action_search_semantic(&self, query: &str, p: &SearchParams) -> String {
+    fn action_search_semantic(&self, query: &str, p: &SearchParams, mode: SearchMode) -> String {
         let mut emb_guard = match self.embedder.lock() {
             Ok(g) => g,
             Err(_) => return json_error("Embedder lock poisoned."),
         };
 
-        let embedder = match emb_guard.as_mut() {
+        let embedder = match emb_guard.as_deref_mut() {
             Some(e) => e,
             None => return json_error(
                 "Semantic search unavailable. The embedding model has not been initialized. \
@@ -471,6 +1335,7 @@ impl WardwellServer {
             query,
             limit,
             domains.as_deref(),
+            mode,
         ) {
             Ok(results) => {
                 // Track accessed projects from chunk results
@@ -479,7 +1344,7 @@ impl WardwellServer {
                         self.record_access(&d, &p);
                     }
                 }
-                serde_json::to_string_pretty(&results).unwrap_or_default()
+                self.to_json_with_stale_warning(&results)
             }
             Err(e) => {
                 eprintln!("wardwell: semantic search failed, falling back to keyword: {e}");
@@ -495,10 +1360,13 @@ impl WardwellServer {
                     domains: fallback_domains,
                     types: Vec::new(),
                     status: None,
+                    tags: Vec::new(),
+                    exclude_terms: Vec::new(),
+                    exclude_domains: Vec::new(),
                     limit,
                 };
                 match self.index.search(&fallback_query) {
-                    Ok(results) => serde_json::to_string_pretty(&results).unwrap_or_default(),
+                    Ok(results) => self.to_json_with_stale_warning(&results),
                     Err(e2) => json_error(&format!("Search failed: {e2}")),
                 }
             }
@@ -521,7 +1389,7 @@ impl WardwellServer {
         }
 
         let full_path = resolve_path(&self.vault_root, &path);
-        let vf = match full_path.and_then(|fp| crate::vault::reader::read_file(&fp).ok()) {
+        let vf = match full_path.and_then(|fp| crate::vault::reader::read_file_with(&fp, &self.config.statuses).ok()) {
             Some(vf) => vf,
             None => return json_error(&format!("File not found: {path}. Use action 'search' to find valid paths.")),
         };
@@ -534,7 +1402,7 @@ impl WardwellServer {
         let mut related_previews = Vec::new();
         for related_path in &vf.frontmatter.related {
             if let Some(related_full) = resolve_path(&self.vault_root, related_path)
-                && let Ok(related_vf) = crate::vault::reader::read_file(&related_full)
+                && let Ok(related_vf) = crate::vault::reader::read_file_with(&related_full, &self.config.statuses)
             {
                 related_previews.push(serde_json::json!({
                     "path": related_path,
@@ -543,23 +1411,32 @@ impl WardwellServer {
             }
         }
 
-        serde_json::to_string_pretty(&serde_json::json!({
+        let mut response = serde_json::json!({
             "path": path,
             "frontmatter": vf.frontmatter,
             "content": vf.body,
             "related_previews": related_previews,
-        })).unwrap_or_default()
+        });
+        if p.start.is_some() || p.end.is_some() {
+            let lines: Vec<&str> = vf.body.split_inclusive('\n').collect();
+            let start = p.start.unwrap_or(0);
+            let end = p.end.unwrap_or(usize::MAX).min(lines.len().saturating_sub(1));
+            response["content"] = serde_json::json!(lines.iter().skip(start).take((end + 1).saturating_sub(start)).copied().collect::<String>());
+            response["lines"] = serde_json::json!({ "start": start, "end": end, "total": lines.len() });
+        }
+        self.to_json_with_stale_warning(&response)
     }
 
-    fn action_history(&self, p: &SearchParams) -> String {
-        let query_str = match &p.query {
-            Some(q) => q.clone(),
+    pub(crate) fn action_history(&self, p: &SearchParams) -> String {
+        let query = match p.query.as_deref().map(|q| HistoryQuery::parse_with(q, &self.config.statuses)) {
+            Some(Ok(q)) => q,
+            Some(Err(e)) => return json_error(&e),
             None => return json_error("'query' is required for action 'history'."),
         };
 
         let vault_dir = self.vault_root.clone();
         if !vault_dir.exists() {
-            return json_error(&format!("No {}/ directory found in vault.", self.vault_root.display()));
+            return json_error("The vault directory doesn't exist.");
         }
 
         // ACL: validate client domain param if scoped
@@ -584,16 +1461,29 @@ impl WardwellServer {
             match (&p.domain, &p.project) {
                 (Some(d), Some(proj)) => vec![vault_dir.join(d).join(proj)],
                 (Some(d), None) => vec![vault_dir.join(d)],
-                _ => list_subdirs(&vault_dir),
+                _ => self.subdirs(&vault_dir),
             }
         };
 
-        for dir in &dirs_to_scan {
-            let vault_name = self.vault_root.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("vault");
-            walk_history_files(dir, &query_str, since_date, p.limit.unwrap_or(5) * 3, vault_name, &mut all_entries);
-        }
+        // On a network mount, find history files through the index instead of walking directories
+        let files = if self.config.remote_vault.enabled {
+            let indexed: Vec<PathBuf> = dirs_to_scan.iter()
+                .filter_map(|dir| dir.strip_prefix(&self.vault_root).ok())
+                .flat_map(|rel| self.index.paths_under(&rel.to_string_lossy()).unwrap_or_default())
+                .map(|rel| self.vault_root.join(rel))
+                .collect();
+            select_history_files(&indexed)
+        } else {
+            let mut files = Vec::new();
+            for dir in &dirs_to_scan {
+                find_history_files(dir, &mut files);
+            }
+            files
+        };
+        let vault_name = self.vault_root.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("vault");
+        read_history_files(&files, &query, since_date, p.limit.unwrap_or(5) * 3, vault_name, self.config.remote_vault.batch(), &mut all_entries);
 
         // Sort by date descending
         all_entries.sort_by(|a, b| b.date.cmp(&a.date));
@@ -606,14 +1496,18 @@ impl WardwellServer {
 
         let total = all_entries.len();
         let entries_json: Vec<serde_json::Value> = all_entries.iter().map(|e| {
-            serde_json::json!({
+            let mut entry = serde_json::json!({
                 "project": e.project,
                 "domain": e.domain,
                 "date": e.date,
                 "title": e.title,
                 "body": e.body,
                 "source": e.source,
-            })
+            });
+            if !e.id.is_empty() {
+                entry["id"] = serde_json::json!(e.id);
+            }
+            entry
         }).collect();
 
         serde_json::to_string_pretty(&serde_json::json!({
@@ -623,10 +1517,10 @@ impl WardwellServer {
         })).unwrap_or_default()
     }
 
-    fn action_orchestrate(&self, p: &SearchParams) -> String {
+    pub(crate) fn action_orchestrate(&self, p: &SearchParams) -> String {
         let vault_dir = self.vault_root.clone();
         if !vault_dir.exists() {
-            return json_error(&format!("No {}/ directory found in vault.", self.vault_root.display()));
+            return json_error("The vault directory doesn't exist.");
         }
 
         // ACL: validate client domain param if scoped
@@ -635,74 +1529,116 @@ impl WardwellServer {
             return json_error(&e);
         }
 
+        let sort = match p.sort.as_deref().map(|s| (s, crate::mcp::health::Sort::parse(s))) {
+            Some((s, None)) => return json_error(&format!("Invalid 'sort' '{s}'. Use score, updated, or status.")),
+            Some((_, sort)) => sort,
+            None => None,
+        };
+
         let dirs_to_scan = self.scoped_domain_dirs(&vault_dir, p.domain.as_deref());
 
         let mut active = Vec::new();
+        // Project folder per `active` and `blocked` entry, for ranking features and health
+        let mut active_dirs: Vec<PathBuf> = Vec::new();
         let mut blocked = Vec::new();
+        let mut blocked_dirs: Vec<PathBuf> = Vec::new();
+        let mut open_questions: HashMap<PathBuf, usize> = HashMap::new();
         let mut completed_recently = Vec::new();
 
+        // Look for current_state.md in immediate subdirs (projects) and at domain level
+        let mut targets: Vec<(&str, PathBuf)> = Vec::new();
         for domain_dir in &dirs_to_scan {
             let domain_name = domain_dir.file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("unknown");
+            targets.push((domain_name, domain_dir.clone()));
+            targets.extend(self.subdirs(domain_dir).into_iter().map(|dir| (domain_name, dir)));
+        }
+        let state_paths: Vec<PathBuf> = targets.iter().map(|(_, dir)| dir.join("current_state.md")).collect();
+        let states = self.read_states(&state_paths);
 
-            // Look for current_state.md in immediate subdirs (projects) and at domain level
-            let mut targets = vec![domain_dir.clone()];
-            targets.extend(list_subdirs(domain_dir));
+        for (((domain_name, project_dir), state_path), state) in targets.iter().zip(&state_paths).zip(states) {
+            if let Some(vf) = state {
+                let project_name = project_dir.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown");
 
-            for project_dir in &targets {
-                let state_path = project_dir.join("current_state.md");
-                if !state_path.exists() {
-                    continue;
-                }
+                let status = vf.frontmatter.status.unwrap_or(Status::Active);
 
-                if let Ok(vf) = crate::vault::reader::read_file(&state_path) {
-                    let project_name = project_dir.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown");
+                let focus = extract_section(&vf.body, "Focus");
+                let next_action = extract_section(&vf.body, "Next Action");
 
-                    let status_str = vf.frontmatter.status.as_ref()
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| "active".to_string());
+                // Skip empty seeds — no focus and no next action
+                if focus.is_empty() && next_action.is_empty() {
+                    continue;
+                }
 
-                    let focus = extract_section(&vf.body, "Focus");
-                    let next_action = extract_section(&vf.body, "Next Action");
+                let updated_str = vf.frontmatter.updated
+                    .map(|d| d.to_string())
+                    .or_else(|| {
+                        // On a network mount, a missing date isn't worth an extra round trip
+                        if self.config.remote_vault.enabled {
+                            return None;
+                        }
+                        std::fs::metadata(state_path).ok()
+                            .and_then(|m| m.modified().ok())
+                            .map(|t| {
+                                let dt: chrono::DateTime<chrono::Local> = t.into();
+                                dt.format("%Y-%m-%d").to_string()
+                            })
+                    })
+                    .unwrap_or_default();
 
-                    // Skip empty seeds — no focus and no next action
-                    if focus.is_empty() && next_action.is_empty() {
-                        continue;
-                    }
+                let entry = serde_json::json!({
+                    "domain": domain_name,
+                    "project": project_name,
+                    "status": status.to_string(),
+                    "updated": updated_str,
+                    "focus": focus,
+                    "next_action": next_action,
+                });
 
-                    let updated_str = vf.frontmatter.updated
-                        .map(|d| d.to_string())
-                        .or_else(|| {
-                            std::fs::metadata(&state_path).ok()
-                                .and_then(|m| m.modified().ok())
-                                .map(|t| {
-                                    let dt: chrono::DateTime<chrono::Local> = t.into();
-                                    dt.format("%Y-%m-%d").to_string()
-                                })
-                        })
-                        .unwrap_or_default();
-
-                    let entry = serde_json::json!({
-                        "domain": domain_name,
-                        "project": project_name,
-                        "status": status_str,
-                        "updated": updated_str,
-                        "focus": focus,
-                        "next_action": next_action,
-                    });
+                open_questions.insert(project_dir.clone(), section_bullets(&vf.body, "Open Questions").len());
+                if status == Status::Blocked {
+                    blocked.push(entry);
+                    blocked_dirs.push(project_dir.clone());
+                } else if status.is_done() {
+                    completed_recently.push(entry);
+                } else if status.is_open() {
+                    active.push(entry);
+                    active_dirs.push(project_dir.clone());
+                } // paused, abandoned, superseded: excluded from queue
+            }
+        }
 
-                    match status_str.as_str() {
-                        "blocked" => blocked.push(entry),
-                        "completed" | "resolved" => completed_recently.push(entry),
-                        "paused" | "abandoned" | "superseded" => {} // excluded from queue
-                        _ => active.push(entry),
-                    }
-                }
+        // Surface open projects that have gone quiet relative to their usual cadence
+        let window_start = chrono::Local::now().date_naive() - chrono::Duration::days(EFFORT_WINDOW_DAYS);
+        let efforts = self.project_efforts(p.domain.as_deref(), None, window_start, true);
+        for entry in active.iter_mut() {
+            if let Some(e) = efforts.iter().find(|e| entry["domain"] == e.domain.as_str() && entry["project"] == e.project.as_str()) {
+                entry["effort"] = serde_json::json!({
+                    "sessions_per_week": e.effort.sessions_per_week,
+                    "avg_gap_days": e.effort.avg_gap_days,
+                    "days_since_last": e.effort.days_since_last,
+                    "pattern": e.effort.pattern,
+                    "overdue_ratio": e.effort.overdue_ratio,
+                    "neglected": e.effort.neglected,
+                });
             }
         }
+        let ranking = &self.config.ranking;
+        let features = if ranking.weights.is_some() || ranking.command.is_some() {
+            self.queue_features(&mut active, &active_dirs)
+        } else {
+            Vec::new()
+        };
+        let ranking_error = crate::mcp::ranking::rank(&mut active, &features, ranking);
+        self.attach_health(&mut active, &active_dirs, &open_questions);
+        self.attach_health(&mut blocked, &blocked_dirs, &open_questions);
+        if let Some(sort) = sort {
+            sort.apply(&mut active);
+            sort.apply(&mut blocked);
+        }
 
         // Track all returned projects
         for entry in active.iter().chain(blocked.iter()).chain(completed_recently.iter()) {
@@ -713,11 +1649,231 @@ impl WardwellServer {
 
         let now = active.first().cloned();
 
-        serde_json::to_string_pretty(&serde_json::json!({
+        let mut result = serde_json::json!({
             "now": now,
             "queue": active,
             "blocked": blocked,
             "completed_recently": completed_recently,
+        });
+        if let Some(sort) = sort {
+            // Queue and blocked together, so blocked projects can outrank queued ones
+            let mut ranked: Vec<serde_json::Value> = active.iter().chain(blocked.iter()).cloned().collect();
+            sort.apply(&mut ranked);
+            result["ranked"] = serde_json::json!(ranked);
+        }
+        if let Some(e) = ranking_error {
+            result["ranking_error"] = serde_json::json!(e);
+        }
+        serde_json::to_string_pretty(&result).unwrap_or_default()
+    }
+
+    /// Health `score` and `reasons` on each entry, from its updated date and status, its
+    /// open questions, and its history.jsonl.
+    fn attach_health(&self, entries: &mut [serde_json::Value], dirs: &[PathBuf], open_questions: &HashMap<PathBuf, usize>) {
+        use crate::mcp::health::{Signals, VELOCITY_WINDOW_DAYS};
+
+        let today = chrono::Local::now().date_naive();
+        let history_paths: Vec<PathBuf> = dirs.iter().map(|d| d.join("history.jsonl")).collect();
+        let histories = crate::vault::batch::read_many(&history_paths, self.config.remote_vault.batch());
+        let date = |s: &str| chrono::NaiveDate::parse_from_str(s.get(..10).unwrap_or(s), "%Y-%m-%d").ok();
+
+        for ((entry, dir), history) in entries.iter_mut().zip(dirs).zip(histories) {
+            let history: Vec<HistoryJsonlEntry> = history.map(|c| crate::vault::jsonl::parse_entries(&c)).unwrap_or_default();
+            let updated = entry["updated"].as_str().and_then(date);
+            // Blocked since the first of the blocked entries the history ends with
+            let blocked_since = history.iter().rev()
+                .take_while(|h| Status::resolve(&h.status, &self.config.statuses) == Some(Status::Blocked))
+                .last()
+                .and_then(|h| date(&h.date))
+                .or(updated);
+            let signals = Signals {
+                days_since_update: updated.map(|d| (today - d).num_days()),
+                blocked_days: (entry["status"] == "blocked").then(|| blocked_since.map_or(0, |d| (today - d).num_days())),
+                open_questions: open_questions.get(dir).copied().unwrap_or(0),
+                recent_entries: history.iter()
+                    .filter_map(|h| date(&h.date))
+                    .filter(|d| (today - *d).num_days() < VELOCITY_WINDOW_DAYS)
+                    .count(),
+            };
+            let health = crate::mcp::health::score(&signals);
+            entry["score"] = health.score.into();
+            entry["reasons"] = serde_json::json!(health.reasons);
+        }
+    }
+
+    /// Ranking features for each queued project, also attached to its entry as `features`.
+    /// Priority and due date come from INDEX.md, which sync leaves alone.
+    fn queue_features(&self, active: &mut [serde_json::Value], dirs: &[PathBuf]) -> Vec<crate::mcp::ranking::Features> {
+        let today = chrono::Local::now().date_naive();
+        let index_paths: Vec<PathBuf> = dirs.iter().map(|d| d.join("INDEX.md")).collect();
+        let indexes = crate::vault::batch::read_many(&index_paths, self.config.remote_vault.batch());
+
+        let mut features = Vec::with_capacity(active.len());
+        for (entry, index) in active.iter_mut().zip(indexes) {
+            let yaml = index.ok().and_then(|content| {
+                crate::vault::frontmatter::frontmatter_yaml(&content)
+                    .and_then(|y| serde_yaml::from_str::<serde_yaml::Value>(y).ok())
+            });
+            let field = |name: &str| match yaml.as_ref().and_then(|y| y.get(name)) {
+                Some(serde_yaml::Value::String(s)) => Some(s.clone()),
+                Some(serde_yaml::Value::Number(n)) => Some(n.to_string()),
+                _ => None,
+            };
+            let date = |s: &str| chrono::NaiveDate::parse_from_str(s.get(..10).unwrap_or(s), "%Y-%m-%d").ok();
+            let f = crate::mcp::ranking::Features {
+                neglected: entry["effort"]["neglected"].as_bool().unwrap_or(false),
+                overdue_ratio: entry["effort"]["overdue_ratio"].as_f64().unwrap_or(0.0),
+                days_since_update: entry["updated"].as_str().and_then(date).map(|d| (today - d).num_days()),
+                days_since_last: entry["effort"]["days_since_last"].as_i64(),
+                priority: field("priority").map_or(0, |p| crate::mcp::ranking::priority_rank(&p)),
+                due_in_days: field("due").as_deref().and_then(date).map(|d| (d - today).num_days()),
+            };
+            let mut value = serde_json::to_value(&f).unwrap_or_default();
+            value["due_urgency"] = serde_json::json!((f.due_urgency() * 1000.0).round() / 1000.0);
+            entry["features"] = value;
+            features.push(f);
+        }
+        features
+    }
+
+    /// Effort metrics for every project in scope that has a current_state.md or history.
+    fn project_efforts(
+        &self,
+        domain: Option<&str>,
+        project: Option<&str>,
+        window_start: chrono::NaiveDate,
+        skip_archive: bool,
+    ) -> Vec<ProjectEffort> {
+        let today = chrono::Local::now().date_naive();
+        let sessions_db = crate::config::loader::config_dir().join("sessions.db");
+        let spans = if sessions_db.exists() {
+            crate::daemon::indexer::SessionStore::open(&sessions_db)
+                .and_then(|store| store.session_spans(&window_start.to_string()))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let mut projects: Vec<(String, String)> = Vec::new();
+        for domain_dir in self.scoped_domain_dirs(&self.vault_root, domain) {
+            let domain_name = domain_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
+            if skip_archive && domain_name == "archive" {
+                continue;
+            }
+            let project_dirs = match project {
+                Some(name) => match crate::vault::project::resolve_project(&domain_dir, name) {
+                    ProjectMatch::Exact(n) | ProjectMatch::Slug(n) | ProjectMatch::Alias(n) => vec![domain_dir.join(n)],
+                    _ => Vec::new(),
+                },
+                None => self.subdirs(&domain_dir),
+            };
+            for project_dir in project_dirs {
+                let project_name = project_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
+                if !(skip_archive && project_name == "archive") {
+                    projects.push((domain_name.clone(), project_name));
+                }
+            }
+        }
+
+        let dir = |(d, p): &(String, String)| self.vault_root.join(d).join(p);
+        let state_paths: Vec<PathBuf> = projects.iter().map(|k| dir(k).join("current_state.md")).collect();
+        let history_paths: Vec<PathBuf> = projects.iter().map(|k| dir(k).join("history.jsonl")).collect();
+        let states = self.read_states(&state_paths);
+        let mut histories = crate::vault::batch::read_many(&history_paths, self.config.remote_vault.batch());
+        // Archived history counts toward cadence too. Only compacted projects have one, so
+        // it's looked up per project rather than read blind.
+        if !self.config.remote_vault.enabled {
+            for (history, key) in histories.iter_mut().zip(&projects) {
+                if let Some(archive) = crate::vault::compress::history_archive(&dir(key))
+                    && let Ok(old) = crate::vault::compress::read_to_string(&archive) {
+                    let recent = std::mem::replace(history, Ok(String::new())).unwrap_or_default();
+                    *history = Ok(old + &recent);
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        for (((domain_name, project_name), state), history) in projects.into_iter().zip(states).zip(histories) {
+            if state.is_none() && history.is_err() {
+                continue;
+            }
+            let status = state.and_then(|vf| vf.frontmatter.status).unwrap_or(Status::Active);
+
+            // Compact's monthly summaries mark no work of their own
+            let history: Vec<chrono::NaiveDate> = crate::vault::jsonl::parse_entries::<DatedEntry>(&history.unwrap_or_default()).iter()
+                .filter(|e| e.source != crate::vault::compress::ROLLUP_SOURCE)
+                .filter_map(|e| chrono::NaiveDate::parse_from_str(e.date.get(..10).unwrap_or(&e.date), "%Y-%m-%d").ok())
+                .filter(|d| *d >= window_start)
+                .collect();
+            let slug = crate::vault::project::slugify(&project_name);
+            let sessions: Vec<(chrono::NaiveDate, f64)> = spans.iter()
+                .filter(|s| s.domain.as_deref().is_none_or(|d| d == domain_name))
+                .filter(|s| {
+                    let last = s.project_path.trim_end_matches('/').rsplit('/').next().unwrap_or(&s.project_path);
+                    crate::vault::project::slugify(last) == slug
+                })
+                .filter_map(span_hours)
+                .filter(|(d, _)| *d >= window_start)
+                .collect();
+
+            out.push(ProjectEffort {
+                effort: compute_effort(&history, &sessions, window_start, today, status.is_open()),
+                domain: domain_name,
+                project: project_name,
+                status: status.to_string(),
+            });
+        }
+        out
+    }
+
+    /// Parsed current_state.md (or any vault file) per path, None where missing. With
+    /// `remote_vault` on, these come from the index, so a network mount sees no stat or
+    /// read at all; otherwise they are read from disk.
+    fn read_states(&self, paths: &[PathBuf]) -> Vec<Option<crate::vault::types::VaultFile>> {
+        if self.config.remote_vault.enabled {
+            return paths.iter().map(|path| {
+                let rel = path.strip_prefix(&self.vault_root).ok()?.to_string_lossy().to_string();
+                let frontmatter = self.index.get_frontmatter(&rel).ok()?;
+                let body = self.index.get_body(&rel).ok().flatten().unwrap_or_default();
+                Some(crate::vault::types::VaultFile { path: path.clone(), frontmatter, body })
+            }).collect();
+        }
+        crate::vault::batch::read_many(paths, self.config.remote_vault.batch()).into_iter().zip(paths)
+            .map(|(content, path)| crate::vault::reader::parse_file_with(path, content.ok()?, &self.config.statuses).ok())
+            .collect()
+    }
+
+    fn action_effort(&self, p: &SearchParams) -> String {
+        if p.project.is_some() && p.domain.is_none() {
+            return json_error("'domain' is required with 'project' for action 'effort'.");
+        }
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "effort") {
+            return json_error(&e);
+        }
+        let window_start = match p.since.as_deref() {
+            Some(s) => match chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                Ok(d) => d,
+                Err(_) => return json_error(&format!("Invalid 'since' date '{s}'. Use YYYY-MM-DD.")),
+            },
+            None => chrono::Local::now().date_naive() - chrono::Duration::days(EFFORT_WINDOW_DAYS),
+        };
+
+        let skip_archive = !p.include_archived.unwrap_or(false);
+        let mut projects = self.project_efforts(p.domain.as_deref(), p.project.as_deref(), window_start, skip_archive);
+        projects.sort_by(|a, b| {
+            b.effort.neglected.cmp(&a.effort.neglected)
+                .then(b.effort.overdue_ratio.unwrap_or(0.0).total_cmp(&a.effort.overdue_ratio.unwrap_or(0.0)))
+        });
+        for e in &projects {
+            self.record_access(&e.domain, &e.project);
+        }
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "since": window_start.to_string(),
+            "count": projects.len(),
+            "neglected": projects.iter().filter(|e| e.effort.neglected).count(),
+            "projects": projects,
         })).unwrap_or_default()
     }
 }
@@ -733,6 +1889,10 @@ struct ParsedHistoryEntry {
     status: String,
     focus: String,
     body: String,
+    /// Where the entry came from (desktop, code, manual). Empty if untagged.
+    source: String,
+    blockers: Vec<String>,
+    waiting_on: Vec<String>,
 }
 
 /// Walk the vault and collect all history.jsonl entries, filtered by date and domain.
@@ -743,6 +1903,8 @@ fn collect_history_entries(
     domain_filter: Option<&str>,
     skip_archive: bool,
     allowed_domains: &[String],
+    symlinks: SymlinkPolicy,
+    reads: crate::vault::batch::BatchRead,
 ) -> Vec<ParsedHistoryEntry> {
     let mut entries = Vec::new();
     let dirs_to_scan = if !allowed_domains.is_empty() {
@@ -754,10 +1916,12 @@ fn collect_history_entries(
     } else {
         match domain_filter {
             Some(d) => vec![vault_root.join(d)],
-            None => list_subdirs(vault_root),
+            None => list_subdirs(vault_root, vault_root, symlinks),
         }
     };
 
+    // (domain, project, dir) per candidate history.jsonl, read together below
+    let mut projects: Vec<(String, String, PathBuf)> = Vec::new();
     for domain_dir in &dirs_to_scan {
         if !domain_dir.is_dir() { continue; }
         if skip_archive && domain_dir.file_name().is_some_and(|n| n == "archive") {
@@ -768,7 +1932,7 @@ fn collect_history_entries(
             .unwrap_or("unknown")
             .to_string();
 
-        for project_dir in list_subdirs(domain_dir) {
+        for project_dir in list_subdirs(vault_root, domain_dir, symlinks) {
             if skip_archive && project_dir.file_name().is_some_and(|n| n == "archive") {
                 continue;
             }
@@ -776,40 +1940,73 @@ fn collect_history_entries(
                 .and_then(|n| n.to_str())
                 .unwrap_or("unknown")
                 .to_string();
-
-            let jsonl_path = project_dir.join("history.jsonl");
-            if !jsonl_path.exists() { continue; }
-            let content = match std::fs::read_to_string(&jsonl_path) {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
-
-            for line in content.lines() {
-                if line.trim().is_empty() || line.starts_with("{\"_schema\":") || line.starts_with("{\"_schema\" :") {
-                    continue;
+            // Projects moved there by the archive action are archived work too
+            if project_name == crate::vault::project::ARCHIVE_DIR {
+                for archived in list_subdirs(vault_root, &project_dir, symlinks) {
+                    let name = archived.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
+                    projects.push((domain_name.clone(), format!("{project_name}/{name}"), archived));
                 }
-                let entry: HistoryJsonlEntry = match serde_json::from_str(line) {
-                    Ok(e) => e,
-                    Err(_) => continue,
-                };
+            }
+            projects.push((domain_name.clone(), project_name, project_dir));
+        }
+    }
 
-                // Date filter
-                let date_str = entry.date.get(..10).unwrap_or(&entry.date);
-                if let Some(s) = since
-                    && chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").is_ok_and(|d| d < s) {
-                    continue;
-                }
+    let before_window = |date: &str| since.is_some_and(|s| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|d| d < s));
+    let parse = crate::vault::jsonl::parse_entries::<HistoryJsonlEntry>;
 
-                entries.push(ParsedHistoryEntry {
-                    domain: domain_name.clone(),
-                    project: project_name.clone(),
-                    date: date_str.to_string(),
-                    title: entry.title,
-                    status: entry.status,
-                    focus: entry.focus,
-                    body: entry.body,
-                });
+    // Missing files come back as read errors, so there's no separate stat per project
+    let history_paths: Vec<PathBuf> = projects.iter().map(|(_, _, dir)| dir.join("history.jsonl")).collect();
+    let histories: Vec<Vec<HistoryJsonlEntry>> = crate::vault::batch::read_many(&history_paths, reads).into_iter()
+        .map(|content| content.map(|c| parse(&c)).unwrap_or_default())
+        .collect();
+
+    // An archive is read only when the window reaches back into it. Compact archives only
+    // entries older than the day it runs, so the archive's modified date bounds its newest one.
+    let archived_by = |path: &std::path::Path| {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).date_naive())
+    };
+    let mut archive_paths: Vec<PathBuf> = Vec::new();
+    // Per project: whether it has an archive, and its index in `archive_paths` if that's read
+    let mut archive_of: Vec<(bool, Option<usize>)> = Vec::new();
+    for (_, _, dir) in &projects {
+        let archive = crate::vault::compress::history_archive(dir);
+        let has_archive = archive.is_some();
+        let outside = |path: &PathBuf| archived_by(path).zip(since).is_some_and(|(newest, since)| newest < since);
+        let read = archive.filter(|path| !outside(path)).map(|path| {
+            archive_paths.push(path);
+            archive_paths.len() - 1
+        });
+        archive_of.push((has_archive, read));
+    }
+    let mut archives: Vec<Option<Vec<HistoryJsonlEntry>>> = crate::vault::batch::read_many(&archive_paths, reads).into_iter()
+        .map(|content| content.ok().map(|c| parse(&c)))
+        .collect();
+
+    for (((domain_name, project_name, _), history), (has_archive, read)) in projects.into_iter().zip(histories).zip(archive_of) {
+        let archive = read.and_then(|i| archives.get_mut(i)).and_then(Option::take);
+        for entry in archive.into_iter().flatten().chain(history) {
+            // The raw entries are in the archive, so its summaries would count them twice
+            if has_archive && entry.source == crate::vault::compress::ROLLUP_SOURCE {
+                continue;
+            }
+            let date_str = entry.date.get(..10).unwrap_or(&entry.date).to_string();
+            if before_window(&date_str) {
+                continue;
             }
+
+            entries.push(ParsedHistoryEntry {
+                domain: domain_name.clone(),
+                project: project_name.clone(),
+                date: date_str,
+                title: entry.title,
+                status: entry.status,
+                focus: entry.focus,
+                body: entry.body,
+                source: entry.source,
+                blockers: entry.blockers,
+                waiting_on: entry.waiting_on,
+            });
         }
     }
 
@@ -818,6 +2015,87 @@ fn collect_history_entries(
     entries
 }
 
+/// Desktop entries a project needs, with no code entries, to be flagged as planned but not executed.
+const PLANNED_NOT_EXECUTED_MIN: usize = 3;
+
+/// Projects planned from desktop since `since` that no code session has picked up: the
+/// `planned_not_executed` list of the `sources` report, for `wardwell status`.
+pub fn pending_desktop_intents(vault_root: &std::path::Path, since: chrono::NaiveDate, symlinks: SymlinkPolicy, reads: crate::vault::batch::BatchRead) -> serde_json::Value {
+    let entries = collect_history_entries(vault_root, Some(since), None, true, &[], symlinks, reads);
+    let mut report = source_report(&entries);
+    report["planned_not_executed"].take()
+}
+
+/// Aggregate history entries by source, per project, per domain, and per month.
+fn source_report(entries: &[ParsedHistoryEntry]) -> serde_json::Value {
+    use std::collections::BTreeMap;
+
+    fn source_of(e: &ParsedHistoryEntry) -> &str {
+        if e.source.is_empty() { "unknown" } else { e.source.as_str() }
+    }
+
+    let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut by_domain: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    let mut by_project: BTreeMap<String, BTreeMap<&str, usize>> = BTreeMap::new();
+    let mut by_project_month: BTreeMap<String, BTreeMap<&str, BTreeMap<&str, usize>>> = BTreeMap::new();
+    let mut last_desktop: BTreeMap<String, &str> = BTreeMap::new();
+
+    for e in entries {
+        let source = source_of(e);
+        let key = format!("{}/{}", e.domain, e.project);
+        let month = e.date.get(..7).unwrap_or(&e.date);
+        *totals.entry(source).or_default() += 1;
+        *by_domain.entry(&e.domain).or_default().entry(source).or_default() += 1;
+        *by_project.entry(key.clone()).or_default().entry(source).or_default() += 1;
+        *by_project_month.entry(key.clone()).or_default().entry(month).or_default().entry(source).or_default() += 1;
+        if source == "desktop" {
+            last_desktop.entry(key).and_modify(|d| if e.date.as_str() > *d { *d = &e.date })
+                .or_insert(&e.date);
+        }
+    }
+
+    let projects: Vec<serde_json::Value> = by_project.iter()
+        .map(|(key, counts)| serde_json::json!({
+            "project": key,
+            "total": counts.values().sum::<usize>(),
+            "by_source": counts,
+            "by_month": by_project_month.get(key),
+        }))
+        .collect();
+
+    let domains: Vec<serde_json::Value> = by_domain.iter()
+        .map(|(domain, counts)| serde_json::json!({
+            "domain": domain,
+            "total": counts.values().sum::<usize>(),
+            "by_source": counts,
+        }))
+        .collect();
+
+    let planned_not_executed: Vec<serde_json::Value> = by_project.iter()
+        .filter_map(|(key, counts)| {
+            let desktop = counts.get("desktop").copied().unwrap_or(0);
+            let code = counts.get("code").copied().unwrap_or(0);
+            if desktop >= PLANNED_NOT_EXECUTED_MIN && code == 0 {
+                Some(serde_json::json!({
+                    "project": key,
+                    "desktop_entries": desktop,
+                    "last_desktop_entry": last_desktop.get(key),
+                }))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    serde_json::json!({
+        "entries": entries.len(),
+        "totals": totals,
+        "domains": domains,
+        "projects": projects,
+        "planned_not_executed": planned_not_executed,
+    })
+}
+
 impl WardwellServer {
     fn action_retrospective(&self, p: &SearchParams) -> String {
         let since_str = match &p.since {
@@ -842,6 +2120,8 @@ impl WardwellServer {
             p.domain.as_deref(),
             skip_archive,
             &self.allowed_domains,
+            self.config.symlinks,
+            self.config.remote_vault.batch(),
         );
 
         // Group by domain/project
@@ -879,7 +2159,7 @@ impl WardwellServer {
                 "titles": titles,
             }));
 
-            if last_status == "completed" || last_status == "resolved" {
+            if self.status_is_done(last_status) {
                 completed.push(key.clone());
             } else {
                 still_active.push(key.clone());
@@ -900,6 +2180,74 @@ impl WardwellServer {
         })).unwrap_or_default()
     }
 
+    /// The retrospective plus wins, grinds, and dropped threads from the summary model. A
+    /// failed model call still returns the retrospective, with the error alongside.
+    async fn action_retrospective_enriched(&self, p: &SearchParams) -> String {
+        let out = self.action_retrospective(p);
+        let Ok(mut retro) = serde_json::from_str::<serde_json::Value>(&out) else {
+            return out;
+        };
+        if retro.get("error").is_some() {
+            return out;
+        }
+
+        let mut lines: Vec<String> = retro["per_project"].as_array().into_iter().flatten()
+            .map(|project| {
+                let titles: Vec<&str> = project["titles"].as_array().into_iter().flatten().filter_map(|t| t.as_str()).collect();
+                format!("- {} ({} entries, {}): {}", project["project"].as_str().unwrap_or_default(),
+                    project["entries"], project["status_flow"].as_str().unwrap_or_default(), titles.join("; "))
+            })
+            .collect();
+        if lines.is_empty() {
+            retro["enrichment_error"] = serde_json::json!("No history in this period to sort.");
+            return serde_json::to_string_pretty(&retro).unwrap_or_default();
+        }
+        lines.sort();
+        let history = format!("Period: {}\n\n{}", retro["period"].as_str().unwrap_or_default(), lines.join("\n"));
+
+        let cache_dir = crate::config::loader::config_dir().join("retrospectives");
+        let provider = crate::daemon::summarizer::provider(&self.config.ai, None);
+        match enrich_retrospective(&history, &cache_dir, provider.as_ref()).await {
+            Ok((sections, cached)) => {
+                retro["wins"] = serde_json::json!(sections.wins);
+                retro["grinds"] = serde_json::json!(sections.grinds);
+                retro["dropped_threads"] = serde_json::json!(sections.dropped_threads);
+                retro["enrichment_cached"] = serde_json::json!(cached);
+            }
+            Err(e) => retro["enrichment_error"] = serde_json::json!(e),
+        }
+        serde_json::to_string_pretty(&retro).unwrap_or_default()
+    }
+
+    fn action_sources(&self, p: &SearchParams) -> String {
+        let since = p.since.as_deref()
+            .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            .unwrap_or_else(|| chrono::Local::now().date_naive() - chrono::Duration::days(90));
+
+        // ACL: validate client domain param if scoped
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "sources") {
+            return json_error(&e);
+        }
+
+        let skip_archive = !p.include_archived.unwrap_or(false);
+        let entries = collect_history_entries(
+            &self.vault_root,
+            Some(since),
+            p.domain.as_deref(),
+            skip_archive,
+            &self.allowed_domains,
+            self.config.symlinks,
+            self.config.remote_vault.batch(),
+        );
+
+        let mut report = source_report(&entries);
+        if let Some(obj) = report.as_object_mut() {
+            obj.insert("since".to_string(), serde_json::Value::String(since.format("%Y-%m-%d").to_string()));
+        }
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    }
+
     fn action_patterns(&self, p: &SearchParams) -> String {
         let since = p.since.as_deref()
             .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
@@ -918,24 +2266,40 @@ impl WardwellServer {
             p.domain.as_deref(),
             skip_archive,
             &self.allowed_domains,
+            self.config.symlinks,
+            self.config.remote_vault.batch(),
         );
 
         // -- Recurring blockers --
-        let blocked_terms = ["blocked", "waiting", "stuck", "blocker"];
-        let mut blocker_counts: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+        // An entry is blocked when sync recorded blockers or waiting-on items, or a blocked status.
+        // Entries without any of those fall back to the configured keywords in their prose.
+        let mut blocker_counts: std::collections::HashMap<String, (Vec<String>, Vec<String>)> = std::collections::HashMap::new();
         for e in &entries {
-            let text = format!("{} {} {}", e.status, e.focus, e.body).to_lowercase();
-            if blocked_terms.iter().any(|t| text.contains(t)) {
+            let recorded: Vec<&String> = e.blockers.iter().chain(&e.waiting_on).collect();
+            let blocked = !recorded.is_empty()
+                || Status::resolve(&e.status, &self.config.statuses) == Some(Status::Blocked)
+                || {
+                    let text = format!("{} {} {}", e.status, e.focus, e.body).to_lowercase();
+                    self.config.blocked_terms.iter().any(|t| text.contains(t.as_str()))
+                };
+            if blocked {
                 let key = format!("{}/{}", e.domain, e.project);
-                blocker_counts.entry(key).or_default().push(e.title.clone());
+                let (titles, items) = blocker_counts.entry(key).or_default();
+                titles.push(e.title.clone());
+                for item in recorded {
+                    if !items.contains(item) {
+                        items.push(item.clone());
+                    }
+                }
             }
         }
         let recurring_blockers: Vec<serde_json::Value> = blocker_counts.iter()
-            .filter(|(_, titles)| titles.len() >= 2)
-            .map(|(project, titles)| serde_json::json!({
+            .filter(|(_, (titles, _))| titles.len() >= 2)
+            .map(|(project, (titles, items))| serde_json::json!({
                 "project": project,
                 "count": titles.len(),
                 "titles": titles,
+                "blockers": items,
             }))
             .collect();
 
@@ -955,7 +2319,7 @@ impl WardwellServer {
         let today = chrono::Local::now().date_naive();
         let stale_threads: Vec<serde_json::Value> = latest_by_project.iter()
             .filter_map(|(project, (date, status))| {
-                if *status == "completed" || *status == "resolved" {
+                if self.status_is_done(status) {
                     return None;
                 }
                 let last = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
@@ -1005,7 +2369,7 @@ impl WardwellServer {
                 (term, count, projects)
             })
             .collect();
-        hot_topics.sort_by(|a, b| b.1.cmp(&a.1));
+        hot_topics.sort_by_key(|t| std::cmp::Reverse(t.1));
         hot_topics.truncate(10);
         let hot_topics_json: Vec<serde_json::Value> = hot_topics.into_iter()
             .map(|(term, count, projects)| serde_json::json!({
@@ -1021,8 +2385,10 @@ impl WardwellServer {
         for e in entries.iter().rev() {
             let key = format!("{}/{}", e.domain, e.project);
             let flow = status_flows.entry(key).or_default();
-            if !e.status.is_empty() && flow.last().map(|s| s.as_str()) != Some(&e.status) {
-                flow.push(e.status.clone());
+            // Compare canonical names, so "done" after "completed" isn't a change
+            let status = Status::resolve(&e.status, &self.config.statuses).map_or_else(|| e.status.clone(), |s| s.to_string());
+            if !status.is_empty() && flow.last() != Some(&status) {
+                flow.push(status);
             }
         }
         let oscillations: Vec<serde_json::Value> = status_flows.into_iter()
@@ -1046,6 +2412,180 @@ impl WardwellServer {
     }
 }
 
+// -- Digests --
+
+impl WardwellServer {
+    /// A copy sharing the index, config, and caches, with its own session tracking, so
+    /// background work that runs search actions doesn't count as the session reading projects.
+    fn detached(&self) -> Self {
+        Self {
+            accessed_projects: Arc::new(Mutex::new(HashSet::new())),
+            last_project: Arc::new(Mutex::new(None)),
+            ..self.clone()
+        }
+    }
+
+    /// Each domain that gets digests, with its schedule, sections, and folder: the domains with
+    /// projects that `digest.domains` allows (all of them when it lists none), plus every
+    /// domain with a subscription. Empty when digests aren't configured.
+    pub fn digest_subscriptions(&self) -> Result<Vec<crate::daemon::digest::Subscription>, String> {
+        let Some(ref digest) = self.config.digest else {
+            return Ok(Vec::new());
+        };
+        let mut domains: Vec<String> = self.detached().visible_projects(None)?.into_iter().map(|p| p.domain).collect();
+        domains.extend(digest.subscriptions.keys().filter(|d| self.vault_root.join(d).is_dir()).cloned());
+        domains.sort();
+        domains.dedup();
+        domains.retain(|d| digest.covers(d) && (self.allowed_domains.is_empty() || self.allowed_domains.contains(d)));
+        Ok(domains.iter().map(|d| digest.subscription(d)).collect())
+    }
+
+    /// Write today's digest for every domain in `digest_subscriptions`.
+    pub fn write_digests(&self) -> Vec<Result<PathBuf, String>> {
+        match self.digest_subscriptions() {
+            Ok(subs) => subs.iter().map(|sub| self.write_digest(sub)).collect(),
+            Err(e) => vec![Err(e)],
+        }
+    }
+
+    /// Write today's digest for one domain. It covers the days since the domain's previous
+    /// digest, or one period of its schedule (a week for a weekly digest) when there is none.
+    pub fn write_digest(&self, sub: &crate::daemon::digest::Subscription) -> Result<PathBuf, String> {
+        let server = self.detached();
+        let domain = &sub.domain;
+        let now = chrono::Local::now().naive_local();
+        let today = now.date();
+        let domain_dir = self.vault_root.join(domain);
+        let since = crate::daemon::digest::latest(&domain_dir, &sub.dir)
+            .map(|(date, _)| date)
+            .filter(|date| *date < today)
+            .unwrap_or(today - chrono::Duration::days(sub.schedule.period_days(now)));
+        let usage = self.metrics.as_ref().and_then(|m| {
+            let since = today - chrono::Duration::days(6);
+            m.report(&since.to_string()).inspect_err(|e| eprintln!("wardwell: digest usage unavailable: {e}")).ok()
+        });
+        let run = |action: &str, since: Option<String>| -> serde_json::Value {
+            let p = SearchParams {
+                action: action.to_string(),
+                domain: Some(domain.clone()),
+                since,
+                ..Default::default()
+            };
+            let out = match action {
+                "orchestrate" => server.action_orchestrate(&p),
+                "retrospective" => server.action_retrospective(&p),
+                _ => server.action_patterns(&p),
+            };
+            serde_json::from_str(&out).unwrap_or_default()
+        };
+        let mut orchestrate = run("orchestrate", None);
+        // Scoped sessions list every allowed domain; keep this one's projects
+        for key in ["queue", "blocked"] {
+            if let Some(list) = orchestrate[key].as_array_mut() {
+                list.retain(|e| e["domain"] == domain.as_str());
+            }
+        }
+        let input = crate::daemon::digest::DigestInput {
+            domain,
+            date: today,
+            since,
+            orchestrate: &orchestrate,
+            retrospective: &run("retrospective", Some(since.to_string())),
+            patterns: &run("patterns", None),
+            usage: usage.as_ref(),
+            sections: &sub.sections,
+        };
+        let content = crate::daemon::digest::render(&input);
+        let path = crate::daemon::digest::write(&domain_dir, &sub.dir, today, &content)
+            .map_err(|e| format!("{domain}: {e}"))?;
+        self.reindex_file(&path);
+        Ok(path)
+    }
+
+    /// What the HTTP dashboard shows: the orchestrate queue and blocked projects, stale
+    /// threads, and history entries from the last two weeks, newest first. Read through a
+    /// detached copy, so page views don't count as the session reading projects.
+    pub fn dashboard(&self) -> serde_json::Value {
+        let server = self.detached();
+        let since = (chrono::Local::now().date_naive() - chrono::Duration::days(14)).to_string();
+        let run = |action: &str| -> serde_json::Value {
+            let history = action == "history";
+            let p = SearchParams {
+                action: action.to_string(),
+                query: history.then(String::new),
+                since: history.then(|| since.clone()),
+                limit: history.then_some(DASHBOARD_HISTORY),
+                ..Default::default()
+            };
+            let out = match action {
+                "orchestrate" => server.action_orchestrate(&p),
+                "patterns" => server.action_patterns(&p),
+                _ => server.action_history(&p),
+            };
+            serde_json::from_str(&out).unwrap_or_default()
+        };
+        let (orchestrate, patterns, history) = (run("orchestrate"), run("patterns"), run("history"));
+        serde_json::json!({
+            "now": orchestrate["now"],
+            "queue": orchestrate["queue"],
+            "blocked": orchestrate["blocked"],
+            "stale": patterns["stale_threads"],
+            "recent": history["entries"],
+        })
+    }
+
+    /// A keyword search for the dashboard's search box, answered like `wardwell_search` search.
+    pub fn dashboard_search(&self, query: &str) -> serde_json::Value {
+        let p = SearchParams {
+            action: "search".to_string(),
+            query: Some(query.to_string()),
+            limit: Some(DASHBOARD_HISTORY),
+            ..Default::default()
+        };
+        serde_json::from_str(&self.detached().action_search(&p)).unwrap_or_default()
+    }
+
+    /// The newest digest for one domain, or for each visible domain, newest first.
+    fn action_digest(&self, p: &SearchParams) -> String {
+        let domains: Vec<String> = match &p.domain {
+            Some(d) => match self.check_domain_access(d, "digest") {
+                Ok(()) => vec![d.clone()],
+                Err(e) => return json_error(&e),
+            },
+            None => self.scoped_domain_dirs(&self.vault_root, None).iter()
+                .filter_map(|d| d.file_name().map(|n| n.to_string_lossy().to_string()))
+                .collect(),
+        };
+        let mut digests: Vec<(chrono::NaiveDate, serde_json::Value)> = domains.iter()
+            .filter_map(|domain| {
+                let dir = self.config.digest.as_ref()
+                    .map_or_else(|| crate::daemon::digest::DIGEST_DIR.to_string(), |d| d.subscription(domain).dir);
+                let (date, path) = crate::daemon::digest::latest(&self.vault_root.join(domain), &dir)?;
+                let content = std::fs::read_to_string(&path).ok()?;
+                let rel = path.strip_prefix(&self.vault_root).unwrap_or(&path).to_string_lossy().to_string();
+                Some((date, serde_json::json!({
+                    "domain": domain,
+                    "date": date.to_string(),
+                    "path": rel,
+                    "content": content,
+                })))
+            })
+            .collect();
+        if digests.is_empty() {
+            let hint = if self.config.digest.is_some() {
+                "No digest written yet. The daemon writes one when the `digest.schedule` in config.yml next fires."
+            } else {
+                "No digests. Add a `digest:` block to ~/.wardwell/config.yml to have the daemon write them (07:00 daily by default)."
+            };
+            return json_error(hint);
+        }
+        digests.sort_by_key(|d| std::cmp::Reverse(d.0));
+        serde_json::to_string_pretty(&serde_json::json!({
+            "digests": digests.into_iter().map(|(_, d)| d).collect::<Vec<_>>(),
+        })).unwrap_or_default()
+    }
+}
+
 // -- Context action --
 
 impl WardwellServer {
@@ -1059,6 +2599,7 @@ impl WardwellServer {
         let jsonl_path = match crate::daemon::summarizer::find_session_file_by_id(
             &session_id,
             &self.config.session_sources,
+            self.config.registry.all(),
         ) {
             Some(p) => p,
             None => return json_error(&format!("Session not found: '{session_id}'.")),
@@ -1076,17 +2617,22 @@ impl WardwellServer {
         let (started, message_count) = parse_session_metadata(&jsonl_path);
 
         // Get or generate summary
-        let summaries_dir = self.config.vault_path.parent()
-            .unwrap_or(std::path::Path::new("/tmp"))
-            .join("summaries");
+        // Where the daemon and pre-warm write summaries
+        let summaries_dir = crate::config::loader::config_dir().join("summaries");
         let (summary, summary_error) = get_or_generate_summary(
             &session_id,
             &jsonl_path,
             &project_path,
             &summaries_dir,
-            &self.config.ai.summarize_model,
+            crate::daemon::summarizer::provider(&self.config.ai, None).as_ref(),
         ).await;
 
+        // Ground the summary in verbatim transcript quotes
+        let citations = match (&summary, crate::daemon::indexer::extract_conversation(&jsonl_path)) {
+            (Some(text), Ok(conversation)) => crate::daemon::excerpt::cite(text, &conversation, CITATION_COUNT),
+            _ => Vec::new(),
+        };
+
         // Resolve domain/project from vault directory
         let vault_match = resolve_vault_project(
             std::path::Path::new(&project_path),
@@ -1099,7 +2645,7 @@ impl WardwellServer {
             if !state_path.exists() {
                 return None;
             }
-            let vf = crate::vault::reader::read_file(&state_path).ok()?;
+            let vf = crate::vault::reader::read_file_with(&state_path, &self.config.statuses).ok()?;
             let focus = extract_section(&vf.body, "Focus");
             let next_action = extract_section(&vf.body, "Next Action");
             let updated = vf.frontmatter.updated.map(|d| d.to_string());
@@ -1131,6 +2677,9 @@ impl WardwellServer {
                     domains: vault_match.as_ref().map(|(d, _, _)| vec![d.clone()]),
                     types: Vec::new(),
                     status: None,
+                    tags: Vec::new(),
+                    exclude_terms: Vec::new(),
+                    exclude_domains: Vec::new(),
                     limit: 3,
                 };
                 match self.index.search(&query) {
@@ -1156,11 +2705,12 @@ impl WardwellServer {
 
         serde_json::to_string_pretty(&serde_json::json!({
             "session_id": session_id,
-            "project_path": project_path,
+            "project_path": self.portable(&project_path),
             "started": started,
             "message_count": message_count,
             "summary": summary,
             "summary_error": summary_error,
+            "citations": citations,
             "domain": domain_name,
             "project": project_name,
             "vault_state": vault_state,
@@ -1169,7 +2719,8 @@ impl WardwellServer {
     }
 
     /// Resume a previous session — generates a handoff document with plan, progress,
-    /// remaining work, and current state. Always generates fresh (ignores cache).
+    /// remaining work, and current state. Reuses a pre-warmed document only while the
+    /// transcript is unchanged since it was written.
     async fn action_resume(&self, p: &SearchParams) -> String {
         let session_id = match &p.session_id {
             Some(id) => id.clone(),
@@ -1179,6 +2730,7 @@ impl WardwellServer {
         let jsonl_path = match crate::daemon::summarizer::find_session_file_by_id(
             &session_id,
             &self.config.session_sources,
+            self.config.registry.all(),
         ) {
             Some(p) => p,
             None => return json_error(&format!("Session not found: '{session_id}'.")),
@@ -1193,7 +2745,6 @@ impl WardwellServer {
 
         let (started, message_count) = parse_session_metadata(&jsonl_path);
 
-        // Always generate fresh with RESUME_PROMPT (no cache)
         let conversation = match crate::daemon::indexer::extract_conversation(&jsonl_path) {
             Ok(c) => c,
             Err(e) => return json_error(&format!("Failed to extract conversation: {e}")),
@@ -1203,15 +2754,13 @@ impl WardwellServer {
             return json_error("Empty session — nothing to resume.");
         }
 
-        let payload = crate::daemon::summarizer::build_resume_payload(&conversation);
-        let prompt = format!(
-            "{}\n\n---\n\nThis session was for the project at `{project_path}`.\n\n---\n\n{payload}",
-            crate::daemon::summarizer::RESUME_PROMPT,
-        );
-
-        let resume_doc = match crate::daemon::summarizer::claude_cli_call(
-            &prompt,
-            &self.config.ai.summarize_model,
+        let resume_doc = match crate::daemon::summarizer::resume_document(
+            &session_id,
+            &jsonl_path,
+            &project_path,
+            &conversation,
+            &crate::config::loader::config_dir().join("summaries"),
+            crate::daemon::summarizer::provider(&self.config.ai, None).as_ref(),
         ).await {
             Ok(doc) => doc,
             Err(e) => return json_error(&format!("Failed to generate resume document: {e}")),
@@ -1232,69 +2781,738 @@ impl WardwellServer {
 
         serde_json::to_string_pretty(&serde_json::json!({
             "session_id": session_id,
-            "project_path": project_path,
+            "project_path": self.portable(&project_path),
             "started": started,
             "message_count": message_count,
             "domain": domain_name,
             "project": project_name,
             "resume": resume_doc,
+            "citations": crate::daemon::excerpt::cite(&resume_doc, &conversation, CITATION_COUNT),
         })).unwrap_or_default()
     }
-}
 
-/// Parse first JSONL line for timestamp and count user+assistant messages.
-fn parse_session_metadata(path: &std::path::Path) -> (Option<String>, usize) {
-    let file = match std::fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return (None, 0),
-    };
-    let reader = std::io::BufReader::new(file);
-    let mut started: Option<String> = None;
-    let mut count: usize = 0;
+    /// Verbatim transcript messages from a session, for checking a summary's claims.
+    fn action_excerpt(&self, p: &SearchParams) -> String {
+        let Some(ref session_id) = p.session_id else {
+            return json_error("'session_id' is required for action 'excerpt'.");
+        };
+        let Some(jsonl_path) = crate::daemon::summarizer::find_session_file_by_id(
+            session_id,
+            &self.config.session_sources,
+            self.config.registry.all(),
+        ) else {
+            return json_error(&format!("Session not found: '{session_id}'."));
+        };
+        let conversation = match crate::daemon::indexer::extract_conversation(&jsonl_path) {
+            Ok(c) => c,
+            Err(e) => return json_error(&format!("Failed to extract conversation: {e}")),
+        };
 
-    use std::io::BufRead;
-    for line in reader.lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(_) => continue,
+        let start = p.start.unwrap_or(0);
+        let end = p.end.unwrap_or(start.saturating_add(EXCERPT_DEFAULT_SPAN - 1))
+            .min(start.saturating_add(EXCERPT_MAX_SPAN - 1));
+        let messages = crate::daemon::excerpt::message_range(&conversation, start, end, EXCERPT_MAX_CHARS);
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "session_id": session_id,
+            "message_count": conversation.len(),
+            "messages": messages,
+        })).unwrap_or_default()
+    }
+
+    /// How a project changed between two points: state fields, then the history entries,
+    /// decisions, and lessons in between.
+    fn action_diff(&self, p: &SearchParams) -> String {
+        use crate::vault::timeline::{self, Point};
+
+        let (Some(domain), Some(project)) = (&p.domain, &p.project) else {
+            return json_error("'domain' and 'project' are required for action 'diff'.");
         };
-        if line.trim().is_empty() {
-            continue;
+        if let Err(e) = self.check_domain_access(domain, "diff") {
+            return json_error(&e);
         }
-        let parsed: serde_json::Value = match serde_json::from_str(&line) {
-            Ok(v) => v,
-            Err(_) => continue,
+        let Some(ref from) = p.from else {
+            return json_error("'from' is required for action 'diff': a date (YYYY-MM-DD) or history entry ref (#N).");
         };
-        if started.is_none()
-            && let Some(ts) = parsed.get("timestamp").and_then(|t| t.as_str()) {
-                started = Some(ts.to_string());
-            }
-        let msg_type = parsed.get("type").and_then(|t| t.as_str()).unwrap_or("");
-        if msg_type == "user" || msg_type == "assistant" {
-            count += 1;
+        let points = Point::parse(from).and_then(|f| Ok((f, Point::parse(p.to.as_deref().unwrap_or("#-1"))?)));
+        let (from, to) = match points {
+            Ok(points) => points,
+            Err(e) => return json_error(&e),
+        };
+        let domain_dir = self.vault_root.join(domain);
+        let name = match crate::vault::project::resolve_project(&domain_dir, project) {
+            ProjectMatch::Exact(n) | ProjectMatch::Slug(n) | ProjectMatch::Alias(n) => n,
+            _ => return json_error(&format!("No project '{project}' in domain '{domain}'.")),
+        };
+        self.record_access(domain, &name);
+        match timeline::diff(&domain_dir.join(&name), &format!("{domain}/{name}"), &from, &to) {
+            Ok(diff) => serde_json::to_string(&diff).unwrap_or_default(),
+            Err(e) => json_error(&e),
         }
     }
-    (started, count)
-}
 
-/// Get cached summary or generate on-the-fly via claude CLI.
+    /// One query across history, lessons, decisions, and session summaries, newest first.
+    fn action_recall(&self, p: &SearchParams) -> String {
+        let Some(ref query) = p.query else {
+            return json_error("'query' is required for action 'recall'.");
+        };
+        if p.project.is_some() && p.domain.is_none() {
+            return json_error("'domain' is required with 'project' for action 'recall'.");
+        }
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "recall") {
+            return json_error(&e);
+        }
+
+        let since = p.since.as_deref()
+            .and_then(|s| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+        let query_lower = query.to_lowercase();
+        let domain_dirs = match p.domain {
+            Some(ref d) => vec![self.vault_root.join(d)],
+            None => self.scoped_domain_dirs(&self.vault_root, None),
+        };
+        let summaries_dir = crate::config::loader::config_dir().join("summaries");
+
+        let mut entries: Vec<RecallEntry> = Vec::new();
+        for domain_dir in &domain_dirs {
+            let domain = domain_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
+            let project = p.project.as_deref().map(|name| match crate::vault::project::resolve_project(domain_dir, name) {
+                ProjectMatch::Exact(n) | ProjectMatch::Slug(n) | ProjectMatch::Alias(n) => n,
+                _ => name.to_string(),
+            });
+            let project_dirs = match project {
+                Some(ref proj) => vec![domain_dir.join(proj)],
+                None => self.subdirs(domain_dir),
+            };
+            for project_dir in &project_dirs {
+                let project_name = project_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
+                recall_project_files(project_dir, &domain, project_name, &query_lower, since, &mut entries);
+            }
+            recall_summaries(&summaries_dir, &domain, project.as_deref(), &query_lower, since, &mut entries);
+        }
+
+        entries.sort_by(|a, b| b.date.cmp(&a.date));
+        let total = entries.len();
+        let mut counts: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+        for e in &entries {
+            *counts.entry(e.kind).or_default() += 1;
+        }
+        entries.truncate(p.limit.unwrap_or(RECALL_DEFAULT_LIMIT));
+        for e in &entries {
+            self.record_access(&e.domain, &e.project);
+        }
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "query": query,
+            "entries": entries,
+            "counts": counts,
+            "total": total,
+            "returned": entries.len(),
+        })).unwrap_or_default()
+    }
+
+    /// Indexed projects visible to this session, optionally narrowed to one domain.
+    fn visible_projects(&self, domain: Option<&str>) -> Result<Vec<crate::index::store::ProjectEntry>, String> {
+        let projects = self.index.list_projects(domain).map_err(|e| format!("Failed to list projects: {e}"))?;
+        if self.allowed_domains.is_empty() {
+            return Ok(projects);
+        }
+        Ok(projects.into_iter().filter(|p| self.allowed_domains.contains(&p.domain)).collect())
+    }
+
+    fn action_projects(&self, p: &SearchParams) -> String {
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "projects") {
+            return json_error(&e);
+        }
+        let projects = match self.visible_projects(p.domain.as_deref()) {
+            Ok(projects) => projects,
+            Err(e) => return json_error(&e),
+        };
+        serde_json::to_string_pretty(&serde_json::json!({
+            "count": projects.len(),
+            "projects": projects,
+        })).unwrap_or_default()
+    }
+
+    /// Every tag in the index with how many documents carry it, overall and per domain,
+    /// most used first.
+    fn action_tags(&self, p: &SearchParams) -> String {
+        let domains = if self.allowed_domains.is_empty() {
+            p.domain.as_ref().map(|d| vec![d.clone()])
+        } else {
+            Some(self.allowed_domains.clone())
+        };
+        let counts = match self.index.tag_counts(domains.as_deref()) {
+            Ok(c) => c,
+            Err(e) => return json_error(&format!("Failed to read tags: {e}")),
+        };
+        let mut tags: Vec<(String, usize, std::collections::BTreeMap<String, usize>)> = counts.into_iter()
+            .map(|(tag, per_domain)| (tag, per_domain.values().sum(), per_domain))
+            .collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let limit = p.limit.unwrap_or(tags.len());
+        let total = tags.len();
+        let tags: Vec<serde_json::Value> = tags.into_iter().take(limit)
+            .map(|(tag, count, domains)| serde_json::json!({"tag": tag, "count": count, "domains": domains}))
+            .collect();
+        serde_json::to_string_pretty(&serde_json::json!({
+            "count": total,
+            "tags": tags,
+            "hint": "Filter a search with tag:<name> in the query, e.g. query 'tag:auth login'. A query of only tag: filters lists every document carrying them.",
+        })).unwrap_or_default()
+    }
+
+    /// Notes linking to and from `path`, through `related:` frontmatter or `[[wikilinks]]`.
+    /// Ends in domains this server can't read are left out.
+    fn action_links(&self, p: &SearchParams) -> String {
+        let path = match &p.path {
+            Some(path) => path.strip_prefix('/').unwrap_or(path).to_string(),
+            None => return json_error("'path' is required for action 'links'."),
+        };
+        if let Some(file_domain) = path.split('/').next()
+            && let Err(e) = self.check_domain_access(file_domain, "read")
+        {
+            return json_error(&e);
+        }
+        match self.index.indexed_at(&path) {
+            Ok(Some(_)) => {}
+            Ok(None) => return json_error(&format!("File not indexed: {path}. Use action 'search' to find valid paths.")),
+            Err(e) => return json_error(&format!("Failed to read links: {e}")),
+        }
+        if let Some((d, proj)) = extract_domain_project(&path) {
+            self.record_access(&d, &proj);
+        }
+
+        let visible = |link: &crate::index::links::Link| {
+            !link.resolved
+                || self.allowed_domains.is_empty()
+                || link.path.split('/').next().is_some_and(|d| self.allowed_domains.iter().any(|a| a == d))
+        };
+        let (outbound, inbound) = match (self.index.outbound_links(&path), self.index.inbound_links(&path)) {
+            (Ok(out), Ok(into)) => (
+                out.into_iter().filter(|l| visible(l)).collect::<Vec<_>>(),
+                into.into_iter().filter(|l| visible(l)).collect::<Vec<_>>(),
+            ),
+            (Err(e), _) | (_, Err(e)) => return json_error(&format!("Failed to read links: {e}")),
+        };
+        serde_json::to_string_pretty(&serde_json::json!({
+            "path": path,
+            "outbound_count": outbound.len(),
+            "inbound_count": inbound.len(),
+            "outbound": outbound,
+            "inbound": inbound,
+        })).unwrap_or_default()
+    }
+
+    /// Near-duplicate note pairs among the domains this session can read.
+    fn action_duplicates(&self, p: &SearchParams) -> String {
+        let threshold = p.threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD);
+        if !(0.0..=1.0).contains(&threshold) {
+            return json_error(&format!("'threshold' must be between 0 and 1, got {threshold}."));
+        }
+        let domains = match &p.domain {
+            Some(d) => match self.check_domain_access(d, "read") {
+                Ok(()) => vec![d.clone()],
+                Err(e) => return json_error(&e),
+            },
+            None => self.allowed_domains.clone(),
+        };
+        let mut pairs = match self.index.duplicates(threshold, &domains) {
+            Ok(pairs) => pairs,
+            Err(e) => return json_error(&format!("Failed to compare notes: {e}")),
+        };
+        let total = pairs.len();
+        pairs.truncate(p.limit.unwrap_or(20));
+        serde_json::to_string_pretty(&serde_json::json!({
+            "threshold": threshold,
+            "total": total,
+            "pairs": pairs,
+        })).unwrap_or_default()
+    }
+
+    /// A cheat-sheet built from the schemas of the tools this server has enabled, so a client
+    /// without the CLAUDE.md block can learn wardwell mid-conversation.
+    fn action_help(&self) -> String {
+        let mut tools = self.tool_router.list_all();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        let tools: Vec<serde_json::Value> = tools.iter().map(|tool| {
+            let schema = &tool.input_schema;
+            let required: Vec<&str> = schema.get("required").and_then(|r| r.as_array())
+                .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            let mut actions = Vec::new();
+            let mut params = serde_json::Map::new();
+            for (name, prop) in schema.get("properties").and_then(|p| p.as_object()).into_iter().flatten() {
+                let description = prop.get("description").and_then(|d| d.as_str()).unwrap_or_default();
+                if name == "action" {
+                    actions = action_summaries(description).into_iter()
+                        .map(|(action, summary)| serde_json::json!({"action": action, "summary": summary}))
+                        .collect();
+                } else if required.contains(&name.as_str()) {
+                    params.insert(name.clone(), serde_json::json!(format!("(required) {description}")));
+                } else {
+                    params.insert(name.clone(), serde_json::json!(description));
+                }
+            }
+            serde_json::json!({
+                "tool": tool.name,
+                "description": tool.description,
+                "actions": actions,
+                "params": params,
+            })
+        }).collect();
+        serde_json::to_string_pretty(&serde_json::json!({
+            "tools": tools,
+            "quality_bar": QUALITY_BAR,
+        })).unwrap_or_default()
+    }
+
+    fn action_domains(&self) -> String {
+        let projects = match self.visible_projects(None) {
+            Ok(projects) => projects,
+            Err(e) => return json_error(&e),
+        };
+        let mut domains: Vec<serde_json::Value> = Vec::new();
+        for chunk in projects.chunk_by(|a, b| a.domain == b.domain) {
+            let mut statuses: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+            for project in chunk {
+                *statuses.entry(project.status.as_deref().unwrap_or("unknown")).or_default() += 1;
+            }
+            let updated = chunk.iter().filter_map(|p| p.updated.as_deref()).max();
+            domains.push(serde_json::json!({
+                "domain": chunk[0].domain,
+                "project_count": chunk.len(),
+                "statuses": statuses,
+                "updated": updated,
+            }));
+        }
+        serde_json::to_string_pretty(&serde_json::json!({
+            "count": domains.len(),
+            "domains": domains,
+        })).unwrap_or_default()
+    }
+
+    /// Timestamped bullets from the daily notes between two dates, oldest first, optionally
+    /// narrowed to a domain's tag and a text filter.
+    fn action_journal_entries(&self, p: &SearchParams) -> String {
+        let parse = |name: &str, value: &Option<String>| match value {
+            Some(v) => chrono::NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| format!("'{name}' must be a date (YYYY-MM-DD), got '{v}'.")),
+            None => Ok(None),
+        };
+        let (from, to) = match (parse("from", &p.from), parse("to", &p.to)) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(e), _) | (_, Err(e)) => return json_error(&e),
+        };
+        let to = to.unwrap_or_else(|| chrono::Local::now().date_naive());
+        let from = from.unwrap_or(to - chrono::Days::new(6));
+        if from > to {
+            return json_error("'from' is after 'to'.");
+        }
+        if (to - from).num_days() >= crate::vault::journal::MAX_RANGE_DAYS {
+            return json_error(&format!("A journal search covers at most {} days; narrow 'from' and 'to'.", crate::vault::journal::MAX_RANGE_DAYS));
+        }
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "journal") {
+            return json_error(&e);
+        }
+
+        // A scoped server only shows entries tagged with one of its domains
+        let tag_domain = |e: &crate::vault::journal::Entry| e.tag.as_deref().map(|t| t.split('/').next().unwrap_or(t).to_string());
+        let query = p.query.as_deref().map(str::to_lowercase);
+        let mut entries: Vec<_> = crate::vault::journal::DailyNotes::load(&self.vault_root)
+            .entries(&self.vault_root, from, to)
+            .into_iter()
+            .filter(|e| self.allowed_domains.is_empty() || tag_domain(e).is_some_and(|d| self.allowed_domains.contains(&d)))
+            .filter(|e| p.domain.as_ref().is_none_or(|want| tag_domain(e).as_ref() == Some(want)))
+            .filter(|e| query.as_ref().is_none_or(|q| e.text.to_lowercase().contains(q)))
+            .collect();
+        let total = entries.len();
+        if let Some(limit) = p.limit {
+            entries.truncate(limit);
+        }
+        serde_json::to_string_pretty(&serde_json::json!({
+            "from": from.to_string(),
+            "to": to.to_string(),
+            "total": total,
+            "entries": entries,
+        })).unwrap_or_default()
+    }
+
+    /// Sessions and history entries across projects within `sessions_around_days` of a date,
+    /// merged oldest first.
+    fn action_sessions_around(&self, p: &SearchParams) -> String {
+        let Some(ref date) = p.from else {
+            return json_error("'from' is required for action 'sessions_around': the date (YYYY-MM-DD) to look around.");
+        };
+        let Ok(center) = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") else {
+            return json_error(&format!("'from' must be a date (YYYY-MM-DD), got '{date}'."));
+        };
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "sessions_around") {
+            return json_error(&e);
+        }
+        let days = chrono::Days::new(u64::from(self.config.sessions_around_days));
+        let start = center - days;
+        let end = center + days;
+        let until = end + chrono::Days::new(1);
+
+        let domain_dirs = self.scoped_domain_dirs(&self.vault_root, p.domain.as_deref());
+        let visible = |domain: &str| domain_dirs.iter().any(|d| d.file_name().and_then(|n| n.to_str()) == Some(domain));
+        let mut events: Vec<(String, serde_json::Value)> = Vec::new();
+
+        let sessions_db = crate::config::loader::config_dir().join("sessions.db");
+        let summaries_dir = crate::config::loader::config_dir().join("summaries");
+        if sessions_db.exists() {
+            let sessions = crate::daemon::indexer::SessionStore::open(&sessions_db)
+                .and_then(|store| store.sessions_between(&start.to_string(), &until.to_string()))
+                .unwrap_or_default();
+            for (meta, summarized) in sessions {
+                let domain = meta.domain.clone().unwrap_or_default();
+                if (p.domain.is_some() || !self.allowed_domains.is_empty()) && !visible(&domain) {
+                    continue;
+                }
+                let project = meta.project_path.trim_end_matches('/').rsplit('/').next().unwrap_or(&meta.project_path).to_string();
+                if p.project.as_deref().is_some_and(|want| !crate::vault::project::same_slug(want, &project)) {
+                    continue;
+                }
+                let summary = summarized
+                    .then(|| crate::vault::compress::existing(&summaries_dir.join(format!("{}.md", meta.session_id))))
+                    .flatten()
+                    .map(|path| self.portable(&path.to_string_lossy()));
+                let at = meta.first_message_at.clone().unwrap_or_default();
+                events.push((at.clone(), serde_json::json!({
+                    "type": "session",
+                    "date": at,
+                    "ended": meta.last_message_at,
+                    "domain": domain,
+                    "project": project,
+                    "project_path": self.portable(&meta.project_path),
+                    "session_id": meta.session_id,
+                    "user_messages": meta.user_message_count,
+                    "summary": summary,
+                })));
+            }
+        }
+
+        let dirs: Vec<PathBuf> = match (&p.domain, &p.project) {
+            (Some(d), Some(proj)) => vec![self.vault_root.join(d).join(proj)],
+            _ => domain_dirs.clone(),
+        };
+        let mut files = Vec::new();
+        for dir in &dirs {
+            find_history_files(dir, &mut files);
+        }
+        let vault_name = self.vault_root.file_name().and_then(|n| n.to_str()).unwrap_or("vault");
+        let mut history = Vec::new();
+        read_history_files(&files, &HistoryQuery::default(), Some(start), usize::MAX, vault_name, self.config.remote_vault.batch(), &mut history);
+        for e in history {
+            if chrono::NaiveDate::parse_from_str(&e.date, "%Y-%m-%d").is_ok_and(|d| d > end) {
+                continue;
+            }
+            let mut entry = serde_json::json!({
+                "type": "history",
+                "date": e.date,
+                "domain": e.domain,
+                "project": e.project,
+                "title": e.title,
+                "body": e.body,
+            });
+            if !e.id.is_empty() {
+                entry["id"] = serde_json::json!(e.id);
+            }
+            events.push((e.date, entry));
+        }
+
+        // Dates sort on their first ten characters, so a day's history entry lands before its sessions
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+        let total = events.len();
+        let limit = p.limit.unwrap_or(SESSIONS_AROUND_DEFAULT_LIMIT);
+        let sessions = events.iter().filter(|(_, e)| e["type"] == "session").count();
+        let entries: Vec<serde_json::Value> = events.into_iter().take(limit).map(|(_, e)| e).collect();
+        serde_json::to_string_pretty(&serde_json::json!({
+            "date": center.to_string(),
+            "window": {"start": start.to_string(), "end": end.to_string()},
+            "entries": entries,
+            "sessions": sessions,
+            "history": total - sessions,
+            "total": total,
+            "returned": entries.len(),
+        })).unwrap_or_default()
+    }
+
+    /// Status, focus, next action, updated, and blockers for one or more projects — the fields
+    /// an agent checks before starting work, without the rest of current_state.md. Each file is
+    /// served from the index when the index has it at least as new as the file on disk.
+    fn action_state(&self, p: &SearchParams) -> String {
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "state") {
+            return json_error(&e);
+        }
+        // `project` takes one name or a comma-separated list; `domain/project` names any domain
+        let targets: Vec<(String, String)> = match p.project.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(list) => {
+                let mut targets = Vec::new();
+                for name in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    match (name.split_once('/'), &p.domain) {
+                        (Some((d, proj)), _) => targets.push((d.to_string(), proj.to_string())),
+                        (None, Some(d)) => targets.push((d.clone(), name.to_string())),
+                        (None, None) => return json_error(&format!("'{name}' needs a domain: pass 'domain' or write it as domain/{name}.")),
+                    }
+                }
+                targets
+            }
+            None => match self.visible_projects(p.domain.as_deref()) {
+                Ok(projects) => projects.into_iter().map(|e| (e.domain, e.project)).collect(),
+                Err(e) => return json_error(&e),
+            },
+        };
+
+        let mut states = Vec::new();
+        for (domain, project) in targets {
+            if let Err(e) = self.check_domain_access(&domain, "state") {
+                return json_error(&e);
+            }
+            let rel = format!("{domain}/{project}/current_state.md");
+            match self.read_state(&rel) {
+                Some((source, status, updated, body)) => {
+                    self.record_access(&domain, &project);
+                    states.push(serde_json::json!({
+                        "domain": domain,
+                        "project": project,
+                        "status": status.unwrap_or_else(|| "active".to_string()),
+                        "focus": extract_section(&body, "Focus"),
+                        "next_action": extract_section(&body, "Next Action"),
+                        "updated": updated,
+                        "blockers": section_bullets(&body, "Blockers"),
+                        "source": source,
+                    }));
+                }
+                None => states.push(serde_json::json!({
+                    "domain": domain,
+                    "project": project,
+                    "error": format!("No current_state.md at {rel}."),
+                })),
+            }
+        }
+        serde_json::to_string_pretty(&serde_json::json!({
+            "count": states.len(),
+            "projects": states,
+        })).unwrap_or_default()
+    }
+
+    /// A current_state.md as (source, status, updated, body): from the index when its copy is
+    /// no older than the file, else from disk. None when the file doesn't exist.
+    fn read_state(&self, rel: &str) -> Option<(&'static str, Option<String>, Option<String>, String)> {
+        let path = self.vault_root.join(rel);
+        let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+        let fresh = self.index.indexed_at(rel).ok().flatten()
+            .is_some_and(|at| at >= chrono::DateTime::<chrono::Utc>::from(modified));
+        if fresh
+            && let Ok(Some(body)) = self.index.get_body(rel)
+            && let Ok(fm) = self.index.get_frontmatter(rel)
+        {
+            return Some(("index", fm.status.map(|s| s.to_string()), fm.updated.map(|d| d.to_string()), body));
+        }
+        let vf = crate::vault::reader::read_file_with(&path, &self.config.statuses).ok()?;
+        Some(("file", vf.frontmatter.status.map(|s| s.to_string()), vf.frontmatter.updated.map(|d| d.to_string()), vf.body))
+    }
+
+    /// Look up a history, lesson, or list entry by the ID returned when it was appended.
+    fn action_entry(&self, p: &SearchParams) -> String {
+        let Some(id) = p.query.as_deref().map(str::trim) else {
+            return json_error("'query' is required for action 'entry': the entry ID.");
+        };
+        if !crate::vault::entry_id::is_id(id) {
+            return json_error(&format!("'{id}' is not an entry ID (26 characters, as returned by append_history, lesson, or append)."));
+        }
+        if let Some(ref d) = p.domain
+            && let Err(e) = self.check_domain_access(d, "entry") {
+            return json_error(&e);
+        }
+        let dirs = match (&p.domain, &p.project) {
+            (Some(d), Some(proj)) => vec![self.vault_root.join(d).join(proj)],
+            (Some(d), None) => vec![self.vault_root.join(d)],
+            _ => self.scoped_domain_dirs(&self.vault_root, None),
+        };
+        let Some((path, entry)) = dirs.iter().find_map(|dir| crate::vault::entry_id::find(&self.vault_root, dir, self.config.symlinks, id)) else {
+            return json_error(&format!("No entry with ID '{id}'."));
+        };
+        let rel = path.strip_prefix(&self.vault_root).unwrap_or(&path).to_string_lossy().to_string();
+        if let Some((domain, project)) = extract_domain_project(&rel) {
+            self.record_access(&domain, &project);
+        }
+        serde_json::to_string_pretty(&serde_json::json!({
+            "id": id.to_ascii_uppercase(),
+            "path": rel,
+            "entry": entry,
+        })).unwrap_or_default()
+    }
+
+    /// Domain files that failed validation on the last registry load, with what stood in for each.
+    fn action_domain_errors(&self) -> String {
+        let errors = match self.registry.try_read() {
+            Ok(registry) => registry.errors().to_vec(),
+            Err(_) => self.config.registry.errors().to_vec(),
+        };
+        serde_json::to_string_pretty(&serde_json::json!({
+            "count": errors.len(),
+            "errors": errors,
+        })).unwrap_or_default()
+    }
+}
+
+/// Entries returned by `sessions_around` when no `limit` is given.
+const SESSIONS_AROUND_DEFAULT_LIMIT: usize = 50;
+
+/// Entries returned by `recall` when no `limit` is given.
+const RECALL_DEFAULT_LIMIT: usize = 10;
+
+/// Transcript citations attached to context/resume output.
+const CITATION_COUNT: usize = 5;
+/// Messages returned by `excerpt` when no `end` is given.
+const EXCERPT_DEFAULT_SPAN: usize = 10;
+/// Upper bound on messages per `excerpt` call.
+const EXCERPT_MAX_SPAN: usize = 50;
+/// Per-message character cap in `excerpt` output.
+const EXCERPT_MAX_CHARS: usize = 4000;
+
+/// Parse first JSONL line for timestamp and count user+assistant messages.
+fn parse_session_metadata(path: &std::path::Path) -> (Option<String>, usize) {
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return (None, 0),
+    };
+    let reader = std::io::BufReader::new(file);
+    let mut started: Option<String> = None;
+    let mut count: usize = 0;
+
+    use std::io::BufRead;
+    for line in reader.lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(_) => continue,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if started.is_none()
+            && let Some(ts) = parsed.get("timestamp").and_then(|t| t.as_str()) {
+                started = Some(ts.to_string());
+            }
+        let msg_type = parsed.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        if msg_type == "user" || msg_type == "assistant" {
+            count += 1;
+        }
+    }
+    (started, count)
+}
+
+/// Prompt for sorting a retrospective's grouped history into review-ready sections.
+const WINS_PROMPT: &str = "Below is one period of work history, one line per project: entry count, status at the start and end of the period, and the titles of its history entries, newest first.
+
+Sort it into three sections for a performance review. Reply with exactly these three markdown headings, each followed by bullets, one bullet per item, each naming its project:
+
+## Wins
+Work that shipped, resolved, or clearly moved forward. Lead with the outcome.
+
+## Grinds
+Long or repetitive efforts that took many sessions without a clear finish yet.
+
+## Dropped threads
+Projects that stalled, were paused or abandoned, or went quiet with open work.
+
+Use only what the history says. A section with nothing in it gets a single bullet: none. No other text.";
+
+/// A retrospective sorted for a review.
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct RetroSections {
+    wins: Vec<String>,
+    grinds: Vec<String>,
+    dropped_threads: Vec<String>,
+}
+
+/// The bullets under each of `WINS_PROMPT`'s headings. None when the reply has none of them.
+fn parse_retro_sections(reply: &str) -> Option<RetroSections> {
+    let mut sections = RetroSections::default();
+    let mut current: Option<&mut Vec<String>> = None;
+    let mut found = false;
+    for line in reply.lines().map(str::trim) {
+        if let Some(heading) = line.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim().to_lowercase();
+            current = if heading.starts_with("win") {
+                Some(&mut sections.wins)
+            } else if heading.starts_with("grind") {
+                Some(&mut sections.grinds)
+            } else if heading.starts_with("dropped") {
+                Some(&mut sections.dropped_threads)
+            } else {
+                None
+            };
+            found |= current.is_some();
+            continue;
+        }
+        let item = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).map(str::trim);
+        if let (Some(list), Some(item)) = (current.as_deref_mut(), item)
+            && !item.is_empty()
+            && !item.trim_end_matches('.').eq_ignore_ascii_case("none")
+        {
+            list.push(item.to_string());
+        }
+    }
+    found.then_some(sections)
+}
+
+/// Wins, grinds, and dropped threads for `history`, and whether they came from the cache in
+/// `cache_dir`. Replies are cached by the history they were made from (period included) and
+/// the model, so asking again for the same period is free until its history changes.
+async fn enrich_retrospective(
+    history: &str,
+    cache_dir: &std::path::Path,
+    provider: &dyn crate::daemon::summarizer::SummaryProvider,
+) -> Result<(RetroSections, bool), String> {
+    let key = crate::index::builder::compute_hash(&format!("{}\n{history}", provider.model()));
+    let cache_path = cache_dir.join(format!("{}.md", &key[..16]));
+    if let Ok(cached) = std::fs::read_to_string(&cache_path)
+        && let Some(sections) = parse_retro_sections(&cached)
+    {
+        return Ok((sections, true));
+    }
+
+    let reply = provider.complete(&format!("{WINS_PROMPT}\n\n---\n\n{history}")).await
+        .map_err(|e| format!("{} failed: {e}", provider.name()))?;
+    let sections = parse_retro_sections(&reply)
+        .ok_or_else(|| "The summary model's reply had no Wins, Grinds, or Dropped threads section.".to_string())?;
+    if std::fs::create_dir_all(cache_dir).is_ok() {
+        let _ = std::fs::write(&cache_path, &reply);
+    }
+    Ok((sections, false))
+}
+
+/// Get cached summary or generate on-the-fly via claude CLI.
 async fn get_or_generate_summary(
     session_id: &str,
     jsonl_path: &std::path::Path,
     project_path: &str,
     summaries_dir: &std::path::Path,
-    model: &str,
+    provider: &dyn crate::daemon::summarizer::SummaryProvider,
 ) -> (Option<String>, Option<String>) {
     let summary_path = summaries_dir.join(format!("{session_id}.md"));
 
-    // Check cache first
-    if summary_path.exists()
-        && let Ok(content) = std::fs::read_to_string(&summary_path) {
-            let body = strip_frontmatter(&content);
-            if !body.trim().is_empty() {
-                return (Some(body), None);
-            }
+    // Check cache first (compact may have compressed it)
+    if let Some(cached) = crate::vault::compress::existing(&summary_path)
+        && let Ok(content) = crate::vault::compress::read_to_string(&cached) {
+        let body = strip_frontmatter(&content);
+        if !body.trim().is_empty() {
+            return (Some(body), None);
         }
+    }
 
     // Generate on-the-fly
     let conversation = match crate::daemon::indexer::extract_conversation(jsonl_path) {
@@ -1312,7 +3530,7 @@ async fn get_or_generate_summary(
         crate::daemon::summarizer::SUMMARY_PROMPT,
     );
 
-    match crate::daemon::summarizer::claude_cli_call(&prompt, model).await {
+    match provider.complete(&prompt).await {
         Ok(summary) => {
             // Cache the result
             let _ = std::fs::create_dir_all(summaries_dir);
@@ -1556,109 +3774,156 @@ fn extract_search_terms(summary: &str, max_terms: usize) -> String {
 // -- Write actions --
 
 impl WardwellServer {
-    fn action_sync(&self, p: &WriteParams, project: &str, warning: Option<&str>, inferred: bool) -> String {
-        let status = match &p.status {
-            Some(s) => s.clone(),
-            None => return json_error("'status' is required for action 'sync'."),
+    /// Whether a stored status string (history entries keep vault-defined names) counts as finished.
+    fn status_is_done(&self, s: &str) -> bool {
+        Status::resolve(s, &self.config.statuses).is_some_and(Status::is_done)
+    }
+
+    /// The accepted status names, for sync errors.
+    fn status_hint(&self) -> String {
+        let mut names: Vec<String> = Status::ALL.iter().map(|s| s.to_string()).collect();
+        names.extend(self.config.statuses.keys().cloned());
+        format!("Use one of: {}, or add it under `statuses:` in config.yml.", names.join(", "))
+    }
+
+    fn action_sync(&self, p: &WriteParams, project: &str, warning: Option<&str>, inferred: bool) -> (String, bool) {
+        let (status, recorded_status) = match &p.status {
+            Some(s) => match Status::resolve(s, &self.config.statuses) {
+                // Built-in variants are stored canonically; vault-defined states keep their name in history
+                Some(canonical) if Status::parse(s).is_some() => (canonical, canonical.to_string()),
+                Some(canonical) => (canonical, s.trim().to_lowercase()),
+                None => return (json_error(&format!("Unknown status '{s}'. {}", self.status_hint())), false),
+            },
+            None => return (json_error("'status' is required for action 'sync'."), false),
         };
         let focus = match &p.focus {
             Some(f) => f.clone(),
-            None => return json_error("'focus' is required for action 'sync'."),
+            None => return (json_error("'focus' is required for action 'sync'."), false),
         };
         let next_action = match &p.next_action {
             Some(n) => n.clone(),
-            None => return json_error("'next_action' is required for action 'sync'."),
+            None => return (json_error("'next_action' is required for action 'sync'."), false),
         };
         let commit_message = match &p.commit_message {
             Some(c) => c.clone(),
-            None => return json_error("'commit_message' is required for action 'sync'."),
+            None => return (json_error("'commit_message' is required for action 'sync'."), false),
         };
 
         let project_dir = self.vault_root.clone().join(&p.domain).join(project);
-        if let Err(e) = std::fs::create_dir_all(&project_dir) {
-            return json_error(&format!("Failed to create directory: {e}"));
-        }
+        let dry_run = p.dry_run.unwrap_or(false);
 
         let now = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
 
         // Build current_state.md
         let source = p.source.as_deref().unwrap_or("unknown");
-        let mut content = format!(
-            "---\nchat_name: {project}\nupdated: {now}\nstatus: {status}\ntype: project\ncontext: {domain}\nsource: {source}\n---\n\n# {project}\n\n## Focus\n{focus}\n",
-            domain = p.domain,
-        );
 
-        if let Some(ref why) = p.why_this_matters {
-            content.push_str(&format!("\n## Why This Matters\n{why}\n"));
-        }
+        let bullets = |items: &Option<Vec<String>>| {
+            items.as_ref().filter(|v| !v.is_empty())
+                .map(|v| v.iter().map(|i| format!("- {i}")).collect::<Vec<_>>().join("\n"))
+        };
+        let written: Vec<(&str, String)> = [
+            ("Focus", Some(focus.clone())),
+            ("Why This Matters", p.why_this_matters.clone()),
+            ("Next Action", Some(next_action.clone())),
+            ("Open Questions", bullets(&p.open_questions)),
+            ("Blockers", bullets(&p.blockers)),
+            ("Waiting On", bullets(&p.waiting_on)),
+            ("Commit Message", Some(commit_message.clone())),
+        ].into_iter().filter_map(|(h, c)| c.map(|c| (h, c))).collect();
 
-        content.push_str(&format!("\n## Next Action\n{next_action}\n"));
+        let state_path = project_dir.join("current_state.md");
+        let mut files_written = vec![];
+        let previous = std::fs::read_to_string(&state_path).unwrap_or_default();
+
+        // Frontmatter keys sync doesn't own (priority, Dataview fields, ...) are carried over too
+        let managed = [
+            ("chat_name", project.to_string()),
+            ("updated", now),
+            ("status", status.to_string()),
+            ("type", "project".to_string()),
+            ("context", p.domain.clone()),
+            ("source", source.to_string()),
+        ];
+        let frontmatter = crate::vault::frontmatter::merge_frontmatter(&previous, &managed);
+        let mut content = format!("---\n{frontmatter}---\n\n# {project}\n");
 
-        if let Some(ref qs) = p.open_questions
-            && !qs.is_empty() {
-                content.push_str("\n## Open Questions\n");
-                for q in qs { content.push_str(&format!("- {q}\n")); }
-            }
+        // Sections sync doesn't own (Links, People, ...) are carried over from the previous file
+        let order = crate::vault::state::section_order(&project_dir, &self.config.state_sections);
+        content.push_str(&crate::vault::state::render(&crate::vault::state::arrange(&written, &previous, &order)));
 
-        if let Some(ref bs) = p.blockers
-            && !bs.is_empty() {
-                content.push_str("\n## Blockers\n");
-                for b in bs { content.push_str(&format!("- {b}\n")); }
+        // A dry run collects each change here instead of making it
+        let mut planned = vec![];
+        if dry_run {
+            planned.push(planned_write(&self.vault_rel(&state_path), state_path.exists().then_some(previous.as_str()), &content));
+        } else {
+            if let Err(e) = std::fs::create_dir_all(&project_dir) {
+                return (json_error(&format!("Failed to create directory: {e}")), false);
             }
-
-        if let Some(ref ws) = p.waiting_on
-            && !ws.is_empty() {
-                content.push_str("\n## Waiting On\n");
-                for w in ws { content.push_str(&format!("- {w}\n")); }
+            if let Err(e) = std::fs::write(&state_path, &content) {
+                return (json_error(&format!("Failed to write current_state.md: {e}")), false);
             }
-
-        content.push_str(&format!("\n## Commit Message\n{commit_message}\n"));
-
-        let state_path = project_dir.join("current_state.md");
-        let mut files_written = vec![];
-
-        if let Err(e) = std::fs::write(&state_path, &content) {
-            return json_error(&format!("Failed to write current_state.md: {e}"));
+            files_written.push(self.vault_rel(&state_path));
         }
-        files_written.push(format!("{}/{}/{}/current_state.md", self.vault_root.display(), p.domain, project));
 
         // Always append history entry on sync
         let history_path = project_dir.join("history.jsonl");
         let jsonl_entry = HistoryJsonlEntry {
+            id: crate::vault::entry_id::new_id(),
             date: chrono::Utc::now().to_rfc3339(),
             title: p.title.clone().unwrap_or_else(|| commit_message.clone()),
-            status: status.clone(),
+            status: recorded_status,
             focus: focus.clone(),
             next_action: next_action.clone(),
             commit: commit_message.clone(),
             body: p.body.clone().unwrap_or_else(|| commit_message.clone()),
             source: source.to_string(),
+            blockers: p.blockers.clone().unwrap_or_default(),
+            waiting_on: p.waiting_on.clone().unwrap_or_default(),
         };
-        let json = match serde_json::to_string(&jsonl_entry) {
-            Ok(j) => j,
-            Err(e) => return json_error(&format!("Failed to serialize history entry: {e}")),
+        let deduplicated = match self.append_once(&history_path, "history", &jsonl_entry, &jsonl_entry.id, dry_run.then_some(&mut planned)) {
+            Ok(appended) => appended.deduplicated,
+            Err(e) => return (json_error(&e), false),
         };
-        if let Err(e) = append_jsonl(&history_path, "history", &json) {
-            return json_error(&format!("Failed to write history.jsonl: {e}"));
+        if !dry_run && !deduplicated {
+            files_written.push(self.vault_rel(&history_path));
+        }
+
+        if dry_run {
+            let mut resp = dry_run_response(planned, warning);
+            resp["state_changes"] = sync_changes(&previous, &content);
+            if deduplicated {
+                resp["deduplicated"] = serde_json::json!(true);
+            }
+            return (serde_json::to_string(&resp).unwrap_or_default(), false);
         }
-        files_written.push(format!("{}/{}/{}/history.jsonl", self.vault_root.display(), p.domain, project));
 
         // Update FTS index for written files
         self.reindex_file(&state_path);
 
         let project_key = format!("{}/{}", p.domain, project);
+        let diff = crate::vault::diff::unified_diff(&previous, &content, &format!("{project_key}/current_state.md"));
+        let changes = sync_changes(&previous, &content);
+        if !diff.is_empty() {
+            let fields: Vec<&str> = changes.as_object().into_iter().flat_map(|c| c.keys().map(String::as_str)).collect();
+            eprintln!("[WARDWELL AUDIT] sync {project_key} changed [{}]:\n{diff}", fields.join(", "));
+        }
         let mut resp = serde_json::json!({
             "synced": true,
             "project": project_key,
             "files_written": files_written,
+            "changes": changes,
+            "diff": diff,
         });
+        if deduplicated {
+            resp["deduplicated"] = serde_json::json!(true);
+        }
         if let Some(w) = warning {
             resp["warning"] = serde_json::json!(w);
         }
         if inferred {
             resp["inferred_project"] = serde_json::json!(true);
         }
-        serde_json::to_string(&resp).unwrap_or_default()
+        (serde_json::to_string(&resp).unwrap_or_default(), true)
     }
 
     fn action_decide(&self, p: &WriteParams, project: &str, warning: Option<&str>) -> String {
@@ -1672,15 +3937,24 @@ impl WardwellServer {
         };
 
         let project_dir = self.vault_root.clone().join(&p.domain).join(project);
-        if let Err(e) = std::fs::create_dir_all(&project_dir) {
-            return json_error(&format!("Failed to create directory: {e}"));
-        }
-
         let decisions_path = project_dir.join("decisions.md");
         let now = chrono::Local::now().format("%Y-%m-%d").to_string();
 
         let entry = format!("## {now} — {title}\n\n{body}\n\n---\n\n");
 
+        if p.dry_run.unwrap_or(false) {
+            let content = match prepended(&decisions_path, &format!("# {project} Decisions"), &entry) {
+                Ok(c) => c,
+                Err(e) => return json_error(&format!("Failed to read decisions.md: {e}")),
+            };
+            let previous = std::fs::read_to_string(&decisions_path).ok();
+            let change = planned_write(&self.vault_rel(&decisions_path), previous.as_deref(), &content);
+            return serde_json::to_string(&dry_run_response(vec![change], warning)).unwrap_or_default();
+        }
+
+        if let Err(e) = std::fs::create_dir_all(&project_dir) {
+            return json_error(&format!("Failed to create directory: {e}"));
+        }
         if let Err(e) = prepend_to_file(&decisions_path, &format!("# {project} Decisions"), &entry) {
             return json_error(&format!("Failed to write decisions.md: {e}"));
         }
@@ -1688,7 +3962,7 @@ impl WardwellServer {
         self.reindex_file(&decisions_path);
 
         let project_key = format!("{}/{}", p.domain, project);
-        let rel = format!("{}/{}/decisions.md", self.vault_root.display(), project_key);
+        let rel = self.vault_rel(&decisions_path);
         let mut resp = serde_json::json!({
             "recorded": true,
             "project": project_key,
@@ -1700,6 +3974,114 @@ impl WardwellServer {
         serde_json::to_string(&resp).unwrap_or_default()
     }
 
+    fn action_approve(&self, p: &WriteParams, project: &str) -> String {
+        use crate::daemon::decisions;
+        let project_dir = self.vault_root.join(&p.domain).join(project);
+        let project_key = format!("{}/{}", p.domain, project);
+        let pending = decisions::pending(&project_dir);
+        let Some(ids) = p.ids.as_ref().filter(|ids| !ids.is_empty()) else {
+            return serde_json::to_string_pretty(&serde_json::json!({
+                "project": project_key,
+                "pending": pending,
+                "total": pending.len(),
+                "message": "Repeat with 'ids' to write these to decisions.md, or with source: 'dismissed' as well to drop them.",
+            })).unwrap_or_default();
+        };
+        let unknown: Vec<&String> = ids.iter().filter(|id| !pending.iter().any(|d| d.id.eq_ignore_ascii_case(id))).collect();
+        if !unknown.is_empty() {
+            return json_error(&format!(
+                "No pending decision with ID {} in {project_key}. Call approve without 'ids' to list them.",
+                unknown.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        let chosen: Vec<&decisions::Proposed> = pending.iter().filter(|d| ids.iter().any(|id| d.id.eq_ignore_ascii_case(id))).collect();
+        let dismissed = p.source.as_deref() == Some("dismissed");
+        let queue_path = project_dir.join(decisions::QUEUE);
+        let decisions_path = project_dir.join("decisions.md");
+        // decisions.md puts the newest first, so the oldest proposal goes in last
+        let entries: String = chosen.iter().rev().map(|d| format!("## {} — {}\n\n{}\n\n---\n\n", d.date, d.title, d.body)).collect();
+
+        if p.dry_run.unwrap_or(false) {
+            let mut changes = Vec::new();
+            if !dismissed {
+                let content = match prepended(&decisions_path, &format!("# {project} Decisions"), &entries) {
+                    Ok(c) => c,
+                    Err(e) => return json_error(&format!("Failed to read decisions.md: {e}")),
+                };
+                let previous = std::fs::read_to_string(&decisions_path).ok();
+                changes.push(planned_write(&self.vault_rel(&decisions_path), previous.as_deref(), &content));
+            }
+            let previous = std::fs::read_to_string(&queue_path).ok();
+            let kept: String = previous.as_deref().unwrap_or_default().lines()
+                .filter(|l| !chosen.iter().any(|d| l.contains(&format!("\"id\":\"{}\"", d.id))))
+                .map(|l| format!("{l}\n"))
+                .collect();
+            changes.push(planned_write(&self.vault_rel(&queue_path), previous.as_deref(), &kept));
+            return serde_json::to_string(&dry_run_response(changes, None)).unwrap_or_default();
+        }
+
+        if !dismissed {
+            if let Err(e) = prepend_to_file(&decisions_path, &format!("# {project} Decisions"), &entries) {
+                return json_error(&format!("Failed to write decisions.md: {e}"));
+            }
+            self.reindex_file(&decisions_path);
+        }
+        if let Err(e) = decisions::remove(&project_dir, ids) {
+            return json_error(&format!("Failed to update {}: {e}", decisions::QUEUE));
+        }
+
+        let titles: Vec<&str> = chosen.iter().map(|d| d.title.as_str()).collect();
+        let mut resp = serde_json::json!({
+            "project": project_key,
+            "remaining": pending.len() - chosen.len(),
+        });
+        if dismissed {
+            resp["dismissed"] = serde_json::json!(titles);
+        } else {
+            resp["approved"] = serde_json::json!(titles);
+            resp["path"] = serde_json::json!(self.vault_rel(&decisions_path));
+        }
+        serde_json::to_string(&resp).unwrap_or_default()
+    }
+
+    fn action_journal(&self, p: &WriteParams) -> String {
+        let Some(body) = p.body.as_ref().filter(|b| !b.trim().is_empty()) else {
+            return json_error("'body' is required for action 'journal'.");
+        };
+        let project = p.project.as_deref().map(|name| match crate::vault::project::resolve_project(&self.vault_root.join(&p.domain), name) {
+            ProjectMatch::Exact(n) | ProjectMatch::Slug(n) | ProjectMatch::Alias(n) => n,
+            _ => name.to_string(),
+        });
+        let tag = match project {
+            Some(ref project) => format!("{}/{project}", p.domain),
+            None => p.domain.clone(),
+        };
+        let now = chrono::Local::now().naive_local();
+        let notes = crate::vault::journal::DailyNotes::load(&self.vault_root);
+        let bullet = crate::vault::journal::bullet(now.time(), Some(&tag), body);
+        if p.dry_run.unwrap_or(false) {
+            let planned = match notes.plan(&self.vault_root, now, &bullet) {
+                Ok(planned) => planned,
+                Err(e) => return json_error(&format!("Failed to read today's daily note: {e}")),
+            };
+            let change = planned_write(&self.vault_rel(&planned.path), planned.previous.as_deref(), &planned.content);
+            return serde_json::to_string(&dry_run_response(vec![change], None)).unwrap_or_default();
+        }
+        let planned = match notes.append(&self.vault_root, now, &bullet) {
+            Ok(planned) => planned,
+            Err(e) => return json_error(&format!("Failed to append to today's daily note: {e}")),
+        };
+        let rel = self.vault_rel(&planned.path);
+        self.reindex_file(&planned.path);
+
+        serde_json::to_string(&serde_json::json!({
+            "journaled": true,
+            "path": rel,
+            "created": planned.previous.is_none(),
+            "entry": bullet.trim_end(),
+        })).unwrap_or_default()
+    }
+
     fn action_append_history(&self, p: &WriteParams, project: &str, warning: Option<&str>) -> String {
         let title = match &p.title {
             Some(t) => t.clone(),
@@ -1707,12 +4089,14 @@ impl WardwellServer {
         };
 
         let project_dir = self.vault_root.clone().join(&p.domain).join(project);
-        if let Err(e) = std::fs::create_dir_all(&project_dir) {
+        let dry_run = p.dry_run.unwrap_or(false);
+        if !dry_run && let Err(e) = std::fs::create_dir_all(&project_dir) {
             return json_error(&format!("Failed to create directory: {e}"));
         }
 
         let history_path = project_dir.join("history.jsonl");
-        let jsonl_entry = HistoryJsonlEntry {
+        let mut jsonl_entry = HistoryJsonlEntry {
+            id: crate::vault::entry_id::new_id(),
             date: chrono::Utc::now().to_rfc3339(),
             title,
             status: String::new(),
@@ -1721,22 +4105,35 @@ impl WardwellServer {
             commit: String::new(),
             body: p.body.clone().unwrap_or_default(),
             source: p.source.clone().unwrap_or_default(),
+            blockers: Vec::new(),
+            waiting_on: Vec::new(),
         };
-        let json = match serde_json::to_string(&jsonl_entry) {
-            Ok(j) => j,
-            Err(e) => return json_error(&format!("Failed to serialize history entry: {e}")),
+        let mut changes = vec![];
+        let appended = match self.append_once(&history_path, "history", &jsonl_entry, &jsonl_entry.id, dry_run.then_some(&mut changes)) {
+            Ok(appended) => appended,
+            Err(e) => return json_error(&e),
         };
-        if let Err(e) = append_jsonl(&history_path, "history", &json) {
-            return json_error(&format!("Failed to write history.jsonl: {e}"));
+        let deduplicated = appended.deduplicated;
+        if dry_run {
+            let mut resp = dry_run_response(changes, warning);
+            if deduplicated {
+                resp["deduplicated"] = serde_json::json!(true);
+            }
+            return serde_json::to_string(&resp).unwrap_or_default();
         }
+        jsonl_entry.id = appended.id;
 
         let project_key = format!("{}/{}", p.domain, project);
-        let rel = format!("{}/{}/history.jsonl", self.vault_root.display(), project_key);
+        let rel = self.vault_rel(&history_path);
         let mut resp = serde_json::json!({
             "appended": true,
+            "id": jsonl_entry.id,
             "project": project_key,
             "path": rel,
         });
+        if deduplicated {
+            resp["deduplicated"] = serde_json::json!(true);
+        }
         if let Some(w) = warning {
             resp["warning"] = serde_json::json!(w);
         }
@@ -1762,12 +4159,14 @@ impl WardwellServer {
         };
 
         let project_dir = self.vault_root.clone().join(&p.domain).join(project);
-        if let Err(e) = std::fs::create_dir_all(&project_dir) {
+        let dry_run = p.dry_run.unwrap_or(false);
+        if !dry_run && let Err(e) = std::fs::create_dir_all(&project_dir) {
             return json_error(&format!("Failed to create directory: {e}"));
         }
 
         let lessons_path = project_dir.join("lessons.jsonl");
-        let jsonl_entry = LessonJsonlEntry {
+        let mut jsonl_entry = LessonJsonlEntry {
+            id: crate::vault::entry_id::new_id(),
             date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
             title,
             what_happened,
@@ -1775,21 +4174,32 @@ impl WardwellServer {
             prevention,
             source: p.source.clone().unwrap_or_default(),
         };
-        let json = match serde_json::to_string(&jsonl_entry) {
-            Ok(j) => j,
-            Err(e) => return json_error(&format!("Failed to serialize lesson entry: {e}")),
+        let mut changes = vec![];
+        let appended = match self.append_once(&lessons_path, "lessons", &jsonl_entry, &jsonl_entry.id, dry_run.then_some(&mut changes)) {
+            Ok(appended) => appended,
+            Err(e) => return json_error(&e),
         };
-        if let Err(e) = append_jsonl(&lessons_path, "lessons", &json) {
-            return json_error(&format!("Failed to write lessons.jsonl: {e}"));
+        let deduplicated = appended.deduplicated;
+        if dry_run {
+            let mut resp = dry_run_response(changes, warning);
+            if deduplicated {
+                resp["deduplicated"] = serde_json::json!(true);
+            }
+            return serde_json::to_string(&resp).unwrap_or_default();
         }
+        jsonl_entry.id = appended.id;
 
         let project_key = format!("{}/{}", p.domain, project);
-        let rel = format!("{}/{}/lessons.jsonl", self.vault_root.display(), project_key);
+        let rel = self.vault_rel(&lessons_path);
         let mut resp = serde_json::json!({
             "recorded": true,
+            "id": jsonl_entry.id,
             "project": project_key,
             "path": rel,
         });
+        if deduplicated {
+            resp["deduplicated"] = serde_json::json!(true);
+        }
         if let Some(w) = warning {
             resp["warning"] = serde_json::json!(w);
         }
@@ -1846,30 +4256,43 @@ impl WardwellServer {
             })).unwrap_or_default();
         }
 
-        if let Err(e) = std::fs::create_dir_all(&project_dir) {
+        let dry_run = p.dry_run.unwrap_or(false);
+        if !dry_run && let Err(e) = std::fs::create_dir_all(&project_dir) {
             return json_error(&format!("Failed to create directory: {e}"));
         }
 
-        let entry = serde_json::json!({
+        let mut entry = serde_json::json!({
+            "id": crate::vault::entry_id::new_id(),
             "date": chrono::Utc::now().to_rfc3339(),
             "title": title,
             "body": p.body.clone().unwrap_or_default(),
         });
-        let json = match serde_json::to_string(&entry) {
-            Ok(j) => j,
-            Err(e) => return json_error(&format!("Failed to serialize entry: {e}")),
+        let mut changes = vec![];
+        let appended = match self.append_once(&list_path, &list_name, &entry, entry["id"].as_str().unwrap_or_default(), dry_run.then_some(&mut changes)) {
+            Ok(appended) => appended,
+            Err(e) => return json_error(&e),
         };
-        if let Err(e) = append_jsonl(&list_path, &list_name, &json) {
-            return json_error(&format!("Failed to write {list_name}.jsonl: {e}"));
+        let deduplicated = appended.deduplicated;
+        if dry_run {
+            let mut resp = dry_run_response(changes, warning);
+            if deduplicated {
+                resp["deduplicated"] = serde_json::json!(true);
+            }
+            return serde_json::to_string(&resp).unwrap_or_default();
         }
+        entry["id"] = serde_json::json!(appended.id);
 
         let project_key = format!("{}/{}", p.domain, project);
         let mut resp = serde_json::json!({
             "appended": true,
+            "id": entry["id"],
             "list": list_name,
             "project": project_key,
-            "path": list_path.display().to_string(),
+            "path": self.vault_rel(&list_path),
         });
+        if deduplicated {
+            resp["deduplicated"] = serde_json::json!(true);
+        }
         if let Some(w) = warning {
             resp["warning"] = serde_json::json!(w);
         }
@@ -1883,42 +4306,593 @@ impl WardwellServer {
         let Some(ref content) = p.body else {
             return json_error("'body' is required for write_file — the file content to write");
         };
+        self.write_project_file(&p.domain, project, rel_path, content, p.dry_run.unwrap_or(false)).0
+    }
 
+    /// Write `content` to `rel_path` inside the project and reindex it, with whether the file
+    /// was written. A dry run only reports the write.
+    fn write_project_file(&self, domain: &str, project: &str, rel_path: &str, content: &str, dry_run: bool) -> (String, bool) {
         // Reject path traversal
         if rel_path.contains("..") {
-            return json_error("path cannot contain '..'");
+            return (json_error("path cannot contain '..'"), false);
         }
 
-        let project_dir = self.vault_root.join(&p.domain).join(project);
+        let project_dir = self.vault_root.join(domain).join(project);
         let file_path = project_dir.join(rel_path);
+        if dry_run {
+            let previous = std::fs::read_to_string(&file_path).ok();
+            let change = planned_write(&self.vault_rel(&file_path), previous.as_deref(), content);
+            return (serde_json::to_string(&dry_run_response(vec![change], None)).unwrap_or_default(), false);
+        }
 
         // Create parent directories
-        if let Some(parent) = file_path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                return json_error(&format!("failed to create directory: {e}"));
-            }
+        if let Some(parent) = file_path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent) {
+            return (json_error(&format!("failed to create directory: {e}")), false);
         }
 
         if let Err(e) = std::fs::write(&file_path, content) {
-            return json_error(&format!("failed to write file: {e}"));
+            return (json_error(&format!("failed to write file: {e}")), false);
         }
 
         // Reindex the file so wardwell_search can find it immediately
         self.reindex_file(&file_path);
 
-        let vault_rel = format!("{}/{}/{}", p.domain, project, rel_path);
-        serde_json::to_string(&serde_json::json!({
+        let vault_rel = format!("{domain}/{project}/{rel_path}");
+        if let Ok(mut written) = self.written_files.lock() {
+            written.insert(vault_rel.clone());
+        }
+        let out = serde_json::to_string(&serde_json::json!({
             "written": true,
             "path": vault_rel,
             "size": content.len(),
             "hint": format!("Read with wardwell_search action:read path:{vault_rel}")
+        })).unwrap_or_default();
+        (out, true)
+    }
+
+    /// Collect a note sent in parts, then write it in one piece. Parts live in memory until
+    /// finalized or until `STAGING_TTL` passes without a call for their staging ID.
+    fn action_stage(&self, p: &WriteParams, project: &str) -> String {
+        let finalize = p.finalize.unwrap_or(false);
+        if p.body.is_none() && !finalize {
+            return json_error("'body' is required for stage — the next part of the content. Set 'finalize': true to write what is staged.");
+        }
+        if finalize && p.path.is_none() {
+            return json_error("'path' is required to finalize stage (e.g., 'research/deep-dive.md')");
+        }
+        if p.part == Some(0) {
+            return json_error("'part' numbers start at 1.");
+        }
+        if p.part.is_some_and(|n| n > MAX_STAGE_PARTS) {
+            return json_error(&format!("'part' can be at most {MAX_STAGE_PARTS}."));
+        }
+        let Ok(mut staged) = self.staged.lock() else {
+            return json_error("Staging lock poisoned.");
+        };
+        staged.retain(|_, s| s.touched_at.elapsed() < STAGING_TTL);
+
+        let empty = std::collections::BTreeMap::new();
+        let parts = match p.staging_id.as_ref() {
+            Some(id) => {
+                let Some(entry) = staged.get(id) else {
+                    return json_error(&format!("Unknown or expired staging_id '{id}'. Staged parts are kept for {} minutes after the last call; omit staging_id to start again.", STAGING_TTL.as_secs() / 60));
+                };
+                if entry.domain != p.domain || entry.project != project {
+                    return json_error(&format!("staging_id '{id}' belongs to '{}/{}', not '{}/{project}'.", entry.domain, entry.project, p.domain));
+                }
+                &entry.parts
+            }
+            None if p.body.is_none() => return json_error("Nothing staged to finalize. Send the content in 'body' first."),
+            None => &empty,
+        };
+
+        // The parts as they'd be with this call's part added, borrowed so a dry run copies nothing
+        let mut view: std::collections::BTreeMap<u32, &str> = parts.iter().map(|(n, c)| (*n, c.as_str())).collect();
+        let mut added = None;
+        if let Some(ref body) = p.body {
+            let number = match (p.part, view.keys().next_back()) {
+                (Some(n), _) => n,
+                (None, None) => 1,
+                (None, Some(n)) => match n.checked_add(1).filter(|n| *n <= MAX_STAGE_PARTS) {
+                    Some(n) => n,
+                    None => return json_error(&format!("This staging_id already has part {MAX_STAGE_PARTS}, the highest allowed.")),
+                },
+            };
+            view.insert(number, body);
+            let size: usize = view.values().map(|c| c.len()).sum();
+            if size > MAX_STAGED_BYTES {
+                return json_error(&format!("Staged content would reach {size} bytes; one staging_id holds at most {MAX_STAGED_BYTES}."));
+            }
+            let others: usize = staged.iter()
+                .filter(|(id, _)| p.staging_id.as_ref() != Some(*id))
+                .flat_map(|(_, s)| s.parts.values().map(String::len))
+                .sum();
+            if others + size > MAX_TOTAL_STAGED_BYTES {
+                return json_error(&format!("Staging is full: {others} bytes are staged under other staging_ids, and all staged content is capped at {MAX_TOTAL_STAGED_BYTES}. Finalize or let them expire first."));
+            }
+            added = Some((number, body.clone()));
+        }
+        let count = u32::try_from(view.len()).unwrap_or(u32::MAX);
+        let last = view.keys().next_back().copied().unwrap_or(0);
+        let missing: Vec<u32> = (1..=last).filter(|n| !view.contains_key(n)).collect();
+        let bytes: usize = view.values().map(|c| c.len()).sum();
+        let content: Option<String> = (finalize && missing.is_empty()).then(|| view.values().copied().collect());
+        drop(view);
+
+        let dry_run = p.dry_run.unwrap_or(false);
+        if dry_run && !finalize {
+            let mut resp = dry_run_response(vec![], None);
+            resp["parts"] = serde_json::json!(count);
+            resp["bytes"] = serde_json::json!(bytes);
+            resp["missing_parts"] = serde_json::json!(missing);
+            return serde_json::to_string(&resp).unwrap_or_default();
+        }
+        let id = p.staging_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        if !dry_run {
+            let entry = staged.entry(id.clone()).or_insert_with(|| StagedContent {
+                domain: p.domain.clone(),
+                project: project.to_string(),
+                parts: std::collections::BTreeMap::new(),
+                touched_at: Instant::now(),
+            });
+            entry.touched_at = Instant::now();
+            if let Some((number, body)) = added {
+                entry.parts.insert(number, body);
+            }
+        }
+
+        if !finalize {
+            return serde_json::to_string(&serde_json::json!({
+                "staged": true,
+                "staging_id": id,
+                "parts": count,
+                "bytes": bytes,
+                "missing_parts": missing,
+                "hint": "Send the next part with this staging_id, then call again with finalize: true and path.",
+            })).unwrap_or_default();
+        }
+        let Some(content) = content else {
+            return serde_json::to_string(&serde_json::json!({
+                "error": format!("Cannot finalize: part(s) {} were never staged.", missing.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")),
+                "staging_id": id,
+                "missing_parts": missing,
+            })).unwrap_or_default();
+        };
+
+        let rel_path = p.path.clone().unwrap_or_default();
+        let (out, written) = self.write_project_file(&p.domain, project, &rel_path, &content, dry_run);
+        if written {
+            staged.remove(&id);
+        }
+        out
+    }
+
+    /// Move a named list, or a file this session wrote, into the vault trash and drop it from the
+    /// index. Two-step like the clipboard: the first call returns a confirm token bound to the
+    /// file's current content; repeating the call with it does the move.
+    fn action_delete(&self, p: &WriteParams, project: &str) -> String {
+        let rel_path = match (&p.list, &p.path) {
+            (Some(list), None) => format!("{list}.jsonl"),
+            (None, Some(path)) => path.clone(),
+            _ => return json_error("delete needs exactly one of 'list' (a named list) or 'path' (a file written by write_file or stage in this session)."),
+        };
+        if rel_path.contains("..") || rel_path.starts_with('/') {
+            return json_error("path cannot contain '..' or start with '/'");
+        }
+        let vault_rel = format!("{}/{project}/{rel_path}", p.domain);
+        let file_path = self.vault_root.join(&vault_rel);
+
+        let is_list = !rel_path.contains('/') && rel_path.ends_with(".jsonl");
+        if is_list && UNDELETABLE_LISTS.contains(&rel_path.as_str()) {
+            return json_error(&format!("'{rel_path}' is a project log and can't be deleted."));
+        }
+        let written = self.written_files.lock().map(|w| w.contains(&vault_rel)).unwrap_or(false);
+        if !is_list && !written {
+            return json_error(&format!("'{vault_rel}' wasn't written by write_file or stage in this session. delete only removes named lists and files this session created — ask the user to remove it."));
+        }
+        let content = match std::fs::read_to_string(&file_path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return json_error(&format!("No file at '{vault_rel}'.")),
+            Err(e) => return json_error(&format!("failed to read {vault_rel}: {e}")),
+        };
+        let binding = crate::index::builder::compute_hash(&format!("{vault_rel}\n{content}"));
+
+        if p.dry_run.unwrap_or(false) {
+            let trash_path = self.trash_path(&vault_rel);
+            let change = serde_json::json!({"path": vault_rel, "move_to": self.vault_rel(&trash_path), "bytes": content.len()});
+            return serde_json::to_string(&dry_run_response(vec![change], None)).unwrap_or_default();
+        }
+
+        let Some(ref token) = p.confirm_token else {
+            let token = uuid::Uuid::new_v4().to_string();
+            let Ok(mut challenges) = self.delete_challenges.lock() else {
+                return json_error("Delete challenge lock poisoned.");
+            };
+            challenges.retain(|_, c| c.issued_at.elapsed() < DELETE_TOKEN_TTL);
+            challenges.insert(token.clone(), ConfirmChallenge { content_hash: binding, issued_at: Instant::now() });
+            eprintln!("[WARDWELL AUDIT] delete challenge issued: token={token} path={vault_rel}");
+            return serde_json::to_string(&serde_json::json!({
+                "deleted": false,
+                "needs_confirmation": true,
+                "confirm_token": token,
+                "path": vault_rel,
+                "bytes": content.len(),
+                "message": "Nothing was deleted. Ask the user for permission, then repeat this call with confirm_token.",
+            })).unwrap_or_default();
+        };
+
+        {
+            let Ok(mut challenges) = self.delete_challenges.lock() else {
+                return json_error("Delete challenge lock poisoned.");
+            };
+            let Some(challenge) = challenges.remove(token) else {
+                eprintln!("[WARDWELL AUDIT] delete confirm rejected: token={token} reason=unknown");
+                return json_error("Unknown or already-used confirm_token. Call again without confirm_token to get a new one.");
+            };
+            if challenge.issued_at.elapsed() >= DELETE_TOKEN_TTL {
+                eprintln!("[WARDWELL AUDIT] delete confirm rejected: token={token} reason=expired");
+                return json_error("confirm_token expired. Call again without confirm_token to get a new one.");
+            }
+            if challenge.content_hash != binding {
+                eprintln!("[WARDWELL AUDIT] delete confirm rejected: token={token} reason=content_mismatch");
+                return json_error("confirm_token was issued for a different file, or the file changed since. Call again without confirm_token to get a new one.");
+            }
+        }
+
+        let trash_path = self.trash_path(&vault_rel);
+        if let Some(parent) = trash_path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent) {
+            return json_error(&format!("failed to create trash directory: {e}"));
+        }
+        if let Err(e) = std::fs::rename(&file_path, &trash_path) {
+            return json_error(&format!("failed to move {vault_rel} to the trash: {e}"));
+        }
+        let _ = self.index.remove(&vault_rel);
+        if let Ok(mut written) = self.written_files.lock() {
+            written.remove(&vault_rel);
+        }
+        let trashed = self.vault_rel(&trash_path);
+        eprintln!("[WARDWELL AUDIT] deleted: token={token} path={vault_rel} trash={trashed}");
+
+        serde_json::to_string(&serde_json::json!({
+            "deleted": true,
+            "path": vault_rel,
+            "trash": trashed,
+            "hint": "Moved, not erased — restore it by moving the file back.",
+        })).unwrap_or_default()
+    }
+
+    /// Where `delete` moves `vault_rel`: the same path under the trash folder, timestamped if
+    /// an earlier delete already used it.
+    fn trash_path(&self, vault_rel: &str) -> std::path::PathBuf {
+        let mut trash_path = self.vault_root.join(crate::vault::reader::TRASH_DIR).join(vault_rel);
+        if trash_path.exists() {
+            let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+            let stem = trash_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            let name = match trash_path.extension() {
+                Some(ext) => format!("{stem}.{stamp}.{}", ext.to_string_lossy()),
+                None => format!("{stem}.{stamp}"),
+            };
+            trash_path.set_file_name(name);
+        }
+        trash_path
+    }
+
+    /// Move a project into `<domain>/archive/`, or back out with `unarchive`. Its index entries
+    /// move with it, `related:` entries that would stop resolving are pointed at the new
+    /// location, and the move is recorded in the project's history.jsonl.
+    fn action_archive(&self, p: &WriteParams, project: &str, unarchive: bool) -> String {
+        let archive = crate::vault::project::ARCHIVE_DIR;
+        if project == archive {
+            return json_error(&format!("'{archive}' holds archived projects. Name the project to move."));
+        }
+        let active = format!("{}/{project}", p.domain);
+        let archived = format!("{}/{archive}/{project}", p.domain);
+        let (from, to) = if unarchive { (archived, active) } else { (active, archived) };
+        let title = if unarchive { "Unarchived project" } else { "Archived project" };
+        match self.move_project(p, &from, &to, title, None) {
+            Ok(resp) if p.dry_run.unwrap_or(false) => serde_json::to_string(&resp).unwrap_or_default(),
+            Ok(mut resp) => {
+                resp[if unarchive { "unarchived" } else { "archived" }] = serde_json::json!(true);
+                serde_json::to_string(&resp).unwrap_or_default()
+            }
+            Err(e) => json_error(&e),
+        }
+    }
+
+    /// Give a project a new folder name, or move it to another domain with `domain/name`.
+    /// The old name becomes an alias, so it still finds the project, as do sessions in a
+    /// directory with that name.
+    fn action_rename(&self, p: &WriteParams, project: &str) -> String {
+        let Some(new_name) = p.new_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) else {
+            return json_error("'new_name' is required for action 'rename'.");
+        };
+        let (domain, name) = new_name.split_once('/').unwrap_or((p.domain.as_str(), new_name));
+        if domain != p.domain {
+            if let Err(e) = self.check_domain_access(domain, "write") {
+                return json_error(&e);
+            }
+            // Only an existing domain folder: `..` or a hidden folder would take the project
+            // out of the vault or out of sight
+            let is_domain = crate::vault::project::is_folder_name(domain)
+                && domain != "domains"
+                && self.subdirs(&self.vault_root).iter().any(|d| d.file_name().is_some_and(|n| n == domain));
+            if !is_domain {
+                return json_error(&format!("No domain folder '{domain}' in the vault."));
+            }
+        }
+        let mut notes = Vec::new();
+        let name = self.new_project_name(name.to_string(), &mut notes);
+        if !crate::vault::project::is_folder_name(&name) {
+            return json_error(&format!("'{name}' can't be a project folder name."));
+        }
+        let from = format!("{}/{project}", p.domain);
+        let to = format!("{domain}/{name}");
+        if from == to {
+            return json_error(&format!("The project is already at '{to}'."));
+        }
+        let alias = (name != project).then_some(project);
+        match self.move_project(p, &from, &to, "Renamed project", alias) {
+            Ok(resp) if p.dry_run.unwrap_or(false) => serde_json::to_string(&resp).unwrap_or_default(),
+            Ok(mut resp) => {
+                if let Ok(mut last) = self.last_project.lock()
+                    && last.as_ref().is_some_and(|(d, proj)| *d == p.domain && proj == project)
+                {
+                    *last = Some((domain.to_string(), name.clone()));
+                }
+                if let Ok(mut accessed) = self.accessed_projects.lock()
+                    && accessed.remove(&from)
+                {
+                    accessed.insert(to.clone());
+                }
+                resp["renamed"] = serde_json::json!(true);
+                if !notes.is_empty() {
+                    resp["warning"] = serde_json::json!(notes.join("; "));
+                }
+                serde_json::to_string(&resp).unwrap_or_default()
+            }
+            Err(e) => json_error(&e),
+        }
+    }
+
+    /// Move the project folder `from` to `to` (both vault-relative), carrying the index with
+    /// it, rewriting `related:` entries that would break, and recording the move in its
+    /// history as `title`. `old_alias` is added to the moved current_state.md's `aliases`.
+    /// Returns the planned changes under `dry_run`, else what moved.
+    fn move_project(&self, p: &WriteParams, from: &str, to: &str, title: &str, old_alias: Option<&str>) -> Result<serde_json::Value, String> {
+        let (from_dir, to_dir) = (self.vault_root.join(from), self.vault_root.join(to));
+        if !from_dir.is_dir() {
+            return Err(format!("No project at '{from}'."));
+        }
+        if to_dir.exists() {
+            return Err(format!("'{to}' already exists. Rename one of them first."));
+        }
+        let moved = |path: &str| match path.strip_prefix(from) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{to}{rest}"),
+            _ => path.to_string(),
+        };
+
+        // Notes whose related: entries may break: the project's own (relative paths out of
+        // it) and every note pointing into it. An entry is rewritten when, resolved against
+        // the vault as it will be after the move, it would no longer reach its current target.
+        let indexed = self.index.paths_under(from).unwrap_or_default();
+        let mut notes: Vec<String> = indexed.iter().filter(|path| path.ends_with(".md")).cloned().collect();
+        for path in &indexed {
+            for link in self.index.inbound_links(path).unwrap_or_default() {
+                if link.kind == crate::index::links::LinkKind::Related && !notes.contains(&link.path) {
+                    notes.push(link.path);
+                }
+            }
+        }
+        // Whether a vault path will be a file once the project has moved
+        let exists_after = |path: &std::path::Path| {
+            let path = path.to_string_lossy();
+            match path.strip_prefix(to) {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => self.vault_root.join(format!("{from}{rest}")).is_file(),
+                _ => moved(&path) == path && self.vault_root.join(&*path).is_file(),
+            }
+        };
+        // Each note, at its new path, with its old content and the rewrite it needs
+        let mut rewrites: Vec<(String, String, String)> = notes.iter()
+            .filter_map(|note| {
+                let content = std::fs::read_to_string(self.vault_root.join(note)).ok()?;
+                let (fm, _) = crate::vault::frontmatter::parse_frontmatter(&content).ok()?;
+                let (old_note, new_note) = (std::path::Path::new(note), moved(note));
+                let mut changed = false;
+                let related: Vec<String> = fm.related.into_iter()
+                    .map(|entry| {
+                        let target = crate::vault::links::resolve(&entry, old_note, &self.vault_root).map(|t| moved(&t));
+                        match target {
+                            Some(target) if crate::vault::links::resolve_with(&entry, std::path::Path::new(&new_note), exists_after).as_ref() != Some(&target) => {
+                                changed = true;
+                                relinked(&entry, &target)
+                            }
+                            _ => entry,
+                        }
+                    })
+                    .collect();
+                if !changed {
+                    return None;
+                }
+                let managed = [("related", serde_json::to_string(&related).unwrap_or_default())];
+                let updated = crate::vault::frontmatter::replace_frontmatter(&content, &managed)?;
+                Some((new_note, content, updated))
+            })
+            .collect();
+
+        // The old name, kept as an alias on the moved current_state.md
+        if let Some(alias) = old_alias {
+            let state = format!("{to}/current_state.md");
+            let found = rewrites.iter().position(|(note, _, _)| *note == state);
+            let current = match found {
+                Some(i) => Some(rewrites[i].2.clone()),
+                None => std::fs::read_to_string(from_dir.join("current_state.md")).ok(),
+            };
+            if let Some(current) = current {
+                let mut aliases = crate::vault::project::aliases_in(&current);
+                if !aliases.iter().any(|a| a == alias) {
+                    aliases.push(alias.to_string());
+                    let managed = [("aliases", serde_json::to_string(&aliases).unwrap_or_default())];
+                    if let Some(updated) = crate::vault::frontmatter::replace_frontmatter(&current, &managed) {
+                        match found {
+                            Some(i) => rewrites[i].2 = updated,
+                            None => rewrites.push((state, current, updated)),
+                        }
+                    }
+                }
+            }
+        }
+
+        let entry = HistoryJsonlEntry {
+            id: crate::vault::entry_id::new_id(),
+            date: chrono::Utc::now().to_rfc3339(),
+            title: title.to_string(),
+            status: String::new(),
+            focus: String::new(),
+            next_action: String::new(),
+            commit: String::new(),
+            body: format!("Moved {from} to {to}."),
+            source: p.source.clone().unwrap_or_default(),
+            blockers: Vec::new(),
+            waiting_on: Vec::new(),
+        };
+
+        if p.dry_run.unwrap_or(false) {
+            let mut changes = vec![serde_json::json!({"path": from, "move_to": to})];
+            changes.extend(rewrites.iter().map(|(note, old, new)| planned_write(note, Some(old), new)));
+            if let Ok(json) = serde_json::to_string(&entry) {
+                let history = from_dir.join("history.jsonl");
+                let previous = std::fs::read_to_string(&history).ok();
+                changes.push(planned_append(&format!("{to}/history.jsonl"), previous.as_deref(), &crate::vault::jsonl::append_text(&history, "history", &json)));
+            }
+            return Ok(dry_run_response(changes, None));
+        }
+
+        if let Some(parent) = to_dir.parent()
+            && let Err(e) = std::fs::create_dir_all(parent) {
+            return Err(format!("failed to create {}: {e}", self.vault_rel(parent)));
+        }
+        if let Err(e) = std::fs::rename(&from_dir, &to_dir) {
+            return Err(format!("failed to move {from} to {to}: {e}"));
+        }
+        let verb = title.split(' ').next().unwrap_or(title).to_lowercase();
+        eprintln!("[WARDWELL AUDIT] {verb}: from={from} to={to}");
+
+        let mut related_updated = Vec::new();
+        for (note, _, updated) in &rewrites {
+            if std::fs::write(self.vault_root.join(note), updated).is_ok() {
+                related_updated.push(note.clone());
+            }
+        }
+
+        if let Ok(json) = serde_json::to_string(&entry)
+            && let Err(e) = append_jsonl(&to_dir.join("history.jsonl"), "history", &json, &self.config.durability) {
+            eprintln!("wardwell: failed to record the move in {to}/history.jsonl: {e}");
+        }
+
+        // The old paths leave the index; the project's files, and notes relinked to them,
+        // are read back in from where they are now
+        for path in &indexed {
+            let _ = self.index.remove(path);
+        }
+        for vf in crate::vault::reader::walk_vault(&to_dir).into_iter().flatten() {
+            let rel = vf.path.strip_prefix(&self.vault_root).unwrap_or(&vf.path).to_string_lossy().to_string();
+            if vf.path.extension().is_some_and(|e| e == "jsonl") {
+                let _ = crate::index::builder::index_jsonl_incremental_public(&self.index, &vf, &rel, &self.vault_root);
+            } else {
+                let _ = self.index.upsert(&vf, &self.vault_root);
+            }
+        }
+        for note in related_updated.iter().filter(|n| moved(n) == **n) {
+            self.reindex_file(&self.vault_root.join(note));
+        }
+        let _ = self.index.mark_indexed();
+
+        if let Ok(mut written) = self.written_files.lock() {
+            *written = written.drain().map(|path| moved(&path)).collect();
+        }
+
+        Ok(serde_json::json!({
+            "from": from,
+            "to": to,
+            "related_updated": related_updated,
+        }))
+    }
+
+    /// Add and remove tags in current_state.md's frontmatter, leaving the rest of the file as is.
+    fn action_tag(&self, p: &WriteParams, project: &str) -> String {
+        let add = p.tags.clone().unwrap_or_default();
+        let remove = p.remove_tags.clone().unwrap_or_default();
+        if add.is_empty() && remove.is_empty() {
+            return json_error("'tags' or 'remove_tags' is required for action 'tag'.");
+        }
+        if let Some(bad) = add.iter().find(|t| !is_valid_tag(t)) {
+            return json_error(&format!("Invalid tag '{bad}'. Tags are letters, digits, '-', '_', '/', or '.'."));
+        }
+
+        let project_key = format!("{}/{}", p.domain, project);
+        let state_path = self.vault_root.join(&p.domain).join(project).join("current_state.md");
+        let content = match std::fs::read_to_string(&state_path) {
+            Ok(c) => c,
+            Err(_) => return json_error(&format!("No current_state.md for '{project_key}'. Sync the project first.")),
+        };
+        let current = crate::vault::frontmatter::parse_frontmatter(&content)
+            .map(|(fm, _)| fm.tags)
+            .unwrap_or_default();
+
+        let mut tags = current.clone();
+        for tag in &add {
+            let tag = tag.trim();
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags.retain(|t| !remove.iter().any(|r| r.trim().eq_ignore_ascii_case(t)));
+
+        let added: Vec<&String> = tags.iter().filter(|t| !current.contains(t)).collect();
+        let removed: Vec<&String> = current.iter().filter(|t| !tags.contains(t)).collect();
+        if !added.is_empty() || !removed.is_empty() {
+            let managed = [("tags", format!("[{}]", tags.join(", ")))];
+            let Some(updated) = crate::vault::frontmatter::replace_frontmatter(&content, &managed) else {
+                return json_error(&format!("current_state.md for '{project_key}' has no frontmatter to tag. Sync the project first."));
+            };
+            if p.dry_run.unwrap_or(false) {
+                let change = planned_write(&self.vault_rel(&state_path), Some(&content), &updated);
+                let mut resp = dry_run_response(vec![change], None);
+                resp["tags"] = serde_json::json!(tags);
+                return serde_json::to_string(&resp).unwrap_or_default();
+            }
+            if let Err(e) = std::fs::write(&state_path, updated) {
+                return json_error(&format!("Failed to write current_state.md: {e}"));
+            }
+            self.reindex_file(&state_path);
+        }
+
+        if p.dry_run.unwrap_or(false) {
+            return serde_json::to_string(&dry_run_response(vec![], None)).unwrap_or_default();
+        }
+        serde_json::to_string(&serde_json::json!({
+            "tagged": true,
+            "project": project_key,
+            "tags": tags,
+            "added": added,
+            "removed": removed,
         })).unwrap_or_default()
     }
 
+    /// The project in `domain` a working directory belongs to; see `project::project_from_cwd`.
+    fn project_from_cwd(&self, domain: &str, cwd: &std::path::Path) -> Option<String> {
+        let registry = self.registry.try_read().ok()?;
+        crate::vault::project::project_from_cwd(&registry, &self.vault_root, domain, cwd)
+    }
+
     /// Re-read a file from disk and upsert it into the FTS index.
     fn reindex_file(&self, path: &std::path::Path) {
-        if let Ok(vf) = crate::vault::reader::read_file(path) {
-            let _ = self.index.upsert(&vf, &self.vault_root);
+        if let Ok(vf) = crate::vault::reader::read_file_with(path, &self.config.statuses)
+            && self.index.upsert(&vf, &self.vault_root).is_ok()
+        {
+            let _ = self.index.mark_indexed();
         }
     }
 }
@@ -1983,10 +4957,9 @@ impl WardwellServer {
         let Some(ref ticket_id) = p.ticket_id else {
             return json_error("'ticket_id' is required for get");
         };
-        if let Some((ref dom, _)) = self.lookup_item_domain(kanban, ticket_id) {
-            if let Err(e) = self.check_kanban_domain_access(dom) {
-                return json_error(&e);
-            }
+        if let Some((ref dom, _)) = self.lookup_item_domain(kanban, ticket_id)
+            && let Err(e) = self.check_kanban_domain_access(dom) {
+            return json_error(&e);
         }
         match kanban.get_item(ticket_id) {
             Ok(item) => serde_json::to_string(&serde_json::json!({"item": item})).unwrap_or_default(),
@@ -2194,10 +5167,9 @@ impl WardwellServer {
             return json_error("provide 'text' (content to write and attach) with 'title' (filename), or 'file_path' (vault-relative path to existing file)");
         }
         let filename = p.title.as_deref().or(p.file_path.as_deref()).unwrap_or("attachment.md");
-        if let Some((ref dom, _)) = self.lookup_item_domain(kanban, ticket_id) {
-            if let Err(e) = self.check_kanban_domain_access(dom) {
-                return json_error(&e);
-            }
+        if let Some((ref dom, _)) = self.lookup_item_domain(kanban, ticket_id)
+            && let Err(e) = self.check_kanban_domain_access(dom) {
+            return json_error(&e);
         }
         match kanban.attach_file(ticket_id, filename, p.text.as_deref(), p.file_path.as_deref()) {
             Ok(att) => {
@@ -2226,10 +5198,9 @@ impl WardwellServer {
         let Some(ref attachment_id) = p.attachment_id else {
             return json_error("'attachment_id' is required for detach");
         };
-        if let Some((ref dom, _)) = self.lookup_item_domain(kanban, ticket_id) {
-            if let Err(e) = self.check_kanban_domain_access(dom) {
-                return json_error(&e);
-            }
+        if let Some((ref dom, _)) = self.lookup_item_domain(kanban, ticket_id)
+            && let Err(e) = self.check_kanban_domain_access(dom) {
+            return json_error(&e);
         }
         match kanban.detach_file(ticket_id, attachment_id) {
             Ok(()) => {
@@ -2265,41 +5236,187 @@ impl WardwellServer {
     }
 }
 
-#[tool_handler(router = self.tool_router)]
 impl ServerHandler for WardwellServer {
-    fn get_info(&self) -> ServerInfo {
-        let instructions = if self.kanban.is_some() {
-            "Wardwell: Personal AI knowledge vault. Four tools: \
-             wardwell_search (action: search|read|history|orchestrate|retrospective|patterns|context|resume; \
-             search supports mode:'semantic' for broad/conceptual queries — prefer it over keyword for exploratory searches), \
-             wardwell_write (action: sync|decide|append_history|lesson|append|write_file), \
-             wardwell_clipboard (copy to clipboard, ask first), \
-             wardwell_kanban (action: list|create|update|move|note|query — project kanban board with tickets, statuses, priorities, deadlines)."
-                .to_string()
-        } else {
-            "Wardwell: Personal AI knowledge vault. Three tools: \
-             wardwell_search (action: search|read|history|orchestrate|retrospective|patterns|context|resume; \
-             search supports mode:'semantic' for broad/conceptual queries — prefer it over keyword for exploratory searches), \
-             wardwell_write (action: sync|decide|append_history|lesson|append|write_file), \
-             wardwell_clipboard (copy to clipboard, ask first)."
-                .to_string()
-        };
+    /// Answering the handshake is what makes a startup healthy. Waiting for the client's
+    /// `initialized` notification would count clients that hang up early as crashes.
+    async fn initialize(
+        &self,
+        request: InitializeRequestParams,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request);
+        }
+        if let Some(ref dir) = self.startup_dir {
+            crate::mcp::safe_mode::mark_healthy(dir);
+        }
+        Ok(self.get_info())
+    }
+
+    /// The router's dispatch, except that a read-only server answers calls to the tools it
+    /// dropped with a structured error rather than an unknown-tool one.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if self.config.read_only && READ_ONLY_DISABLED_TOOLS.contains(&request.name.as_ref()) {
+            eprintln!("[WARDWELL ACL] DENIED: read-only server, tool={}", request.name);
+            return Ok(CallToolResult::error(vec![Content::text(read_only_error(&request.name))]));
+        }
+        let tcc = rmcp::handler::server::tool::ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult { tools: self.tool_router.list_all(), meta: None, next_cursor: None })
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned()
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        let mut tools: Vec<&str> = Vec::new();
+        if self.tool_router.has_route("wardwell_search") {
+            tools.push(
+                "wardwell_search (action: search|read|history|orchestrate|retrospective|patterns|sources|context|resume|excerpt|recall|effort|domains|projects|help; \
+                 search supports mode:'hybrid' (keyword + vector) for broad/conceptual queries — prefer it over keyword for exploratory searches; mode:'vector' is vector-only)",
+            );
+        }
+        if self.tool_router.has_route("wardwell_write") {
+            tools.push("wardwell_write (action: sync|decide|append_history|lesson|append|write_file)");
+        }
+        if self.tool_router.has_route("wardwell_clipboard") {
+            tools.push("wardwell_clipboard (copy to clipboard; first call returns a confirm_token, ask the user, then repeat with it)");
+        }
+        if self.tool_router.has_route("wardwell_kanban") {
+            tools.push("wardwell_kanban (action: list|create|update|move|note|query — project kanban board with tickets, statuses, priorities, deadlines)");
+        }
+        let mut instructions = format!(
+            "Wardwell: Personal AI knowledge vault. {} tool{}: {}.",
+            tools.len(),
+            if tools.len() == 1 { "" } else { "s" },
+            tools.join(", "),
+        );
+        if self.config.read_only {
+            instructions.push_str(" This server is read-only: nothing you do here changes the vault.");
+        }
 
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder().enable_tools().enable_logging().build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(instructions),
         }
     }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), ErrorData> {
+        self.log.set_level(request.level);
+        Ok(())
+    }
 }
 
 // -- Helpers --
 
-fn json_error(msg: &str) -> String {
+/// Tools a read-only server drops from its router.
+const READ_ONLY_DISABLED_TOOLS: [&str; 2] = ["wardwell_write", "wardwell_clipboard"];
+/// Kanban actions a read-only server still answers.
+const KANBAN_READ_ACTIONS: [&str; 4] = ["get", "list", "search", "query"];
+
+/// What a read-only server says when asked to change something.
+fn read_only_error(what: &str) -> String {
+    serde_json::to_string_pretty(&serde_json::json!({
+        "error": format!("This wardwell server is read-only, so {what} is disabled. Ask the vault's owner to make the change."),
+        "read_only": true,
+    })).unwrap_or_default()
+}
+
+/// How to see what an oversized response left out, when there's no cursor for it.
+const NARROW_HINT: &str = "The response was too large, so the longest lists and texts were cut from the end (see cuts). Narrow it with domain, project, since, or a smaller limit to see the rest.";
+
+/// Continuation for a cut search response: a line cursor for read, a message cursor for
+/// excerpt, otherwise `NARROW_HINT`.
+fn search_hint(p: &SearchParams, cuts: &[crate::mcp::response::Cut]) -> String {
+    let start = p.start.unwrap_or(0);
+    let cursor = match p.action.as_str() {
+        "read" => cuts.iter().find(|c| c.field == "/content" && c.unit == "lines")
+            .map(|c| format!("content stops after line {}. Continue with action 'read', path '{}', start {}.", start + c.kept, p.path.as_deref().unwrap_or(""), start + c.kept)),
+        "excerpt" => cuts.iter().find(|c| c.field == "/messages")
+            .map(|c| format!("messages stop before index {}. Continue with action 'excerpt', session_id '{}', start {}.", start + c.kept, p.session_id.as_deref().unwrap_or(""), start + c.kept)),
+        _ => None,
+    };
+    cursor.unwrap_or_else(|| NARROW_HINT.to_string())
+}
+
+/// Split an `action` description ("search: FTS query. read: full file. ...") into
+/// (action, summary) pairs. An action starts wherever a sentence opens with a lowercase `name: `.
+fn action_summaries(description: &str) -> Vec<(&str, &str)> {
+    let is_action = |s: &str| s.split_once(": ")
+        .is_some_and(|(name, _)| !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase() || b == b'_'));
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(description.match_indices(". ").map(|(i, _)| i + 2))
+        .filter(|&i| is_action(&description[i..]))
+        .collect();
+    starts.iter().enumerate().filter_map(|(n, &start)| {
+        let end = starts.get(n + 1).copied().unwrap_or(description.len());
+        let (name, summary) = description[start..end].split_once(": ")?;
+        Some((name, summary.trim()))
+    }).collect()
+}
+
+/// Pull `tag:name` tokens out of a search query: (the rest of the query, the tags).
+fn split_tag_filters(query: &str) -> (String, Vec<String>) {
+    let mut tags = Vec::new();
+    let mut rest = Vec::new();
+    for word in query.split_whitespace() {
+        match word.get(..4).filter(|p| p.eq_ignore_ascii_case("tag:")).map(|_| &word[4..]) {
+            Some(tag) if !tag.is_empty() => tags.push(tag.to_string()),
+            _ => rest.push(word),
+        }
+    }
+    (rest.join(" "), tags)
+}
+
+/// `target` written the way `entry` was: as a `[[wikilink]]` if it was one, and without `.md`
+/// if it left the extension off.
+fn relinked(entry: &str, target: &str) -> String {
+    let target = match std::path::Path::new(crate::vault::links::clean_target(entry)).extension() {
+        None => target.strip_suffix(".md").unwrap_or(target),
+        Some(_) => target,
+    };
+    if entry.trim().starts_with("[[") { format!("[[{target}]]") } else { target.to_string() }
+}
+
+/// Tags go into frontmatter as a flow list, so keep them to characters YAML leaves alone.
+fn is_valid_tag(tag: &str) -> bool {
+    let tag = tag.trim();
+    !tag.is_empty() && tag.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/' | '.'))
+}
+
+pub(crate) fn json_error(msg: &str) -> String {
     serde_json::to_string(&serde_json::json!({"error": msg})).unwrap_or_default()
 }
 
+/// The refusal `write_policy: require_read` gives a sync or decide on a project the session
+/// hasn't read: the call that reads it, ready to make before retrying.
+fn read_required(key: &str, action: &str, state: &str) -> String {
+    serde_json::to_string(&serde_json::json!({
+        "error": format!("'{key}' was not read or searched in this session, and write_policy is require_read. Read its current state first, then retry the {action}."),
+        "read_required": true,
+        "project": key,
+        "read_first": {"tool": "wardwell_search", "action": "read", "path": state},
+    })).unwrap_or_default()
+}
+
 /// Resolve a vault path: only allow vault-relative paths.
 fn resolve_path(vault_root: &std::path::Path, path: &str) -> Option<PathBuf> {
     // Strip leading slash from relative paths (common copy-paste error)
@@ -2324,23 +5441,14 @@ fn resolve_path(vault_root: &std::path::Path, path: &str) -> Option<PathBuf> {
     None
 }
 
-/// List immediate subdirectories of a directory.
-fn list_subdirs(dir: &std::path::Path) -> Vec<PathBuf> {
-    let mut dirs = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let p = entry.path();
-            if p.is_dir() {
-                dirs.push(p);
-            }
-        }
-    }
-    dirs.sort();
-    dirs
+/// List immediate subdirectories of a directory in the vault at `vault_root`, entering
+/// symlinked ones per `symlinks`.
+fn list_subdirs(vault_root: &std::path::Path, dir: &std::path::Path, symlinks: SymlinkPolicy) -> Vec<PathBuf> {
+    crate::vault::symlinks::subdirs(vault_root, dir, symlinks)
 }
 
 /// Extract a markdown section body by heading name (e.g. "Focus" → content under "## Focus").
-fn extract_section(body: &str, heading: &str) -> String {
+pub(crate) fn extract_section(body: &str, heading: &str) -> String {
     let marker = format!("\n## {heading}");
     // Find marker at line start (check start-of-body case too)
     let pos = if body.starts_with(&marker[1..]) {
@@ -2362,9 +5470,18 @@ fn extract_section(body: &str, heading: &str) -> String {
     rest[..end].trim().to_string()
 }
 
+/// The `- ` bullet items of a `## heading` section, as sync writes blockers and waiting-on.
+pub(crate) fn section_bullets(body: &str, heading: &str) -> Vec<String> {
+    extract_section(body, heading).lines()
+        .filter_map(|l| l.trim().strip_prefix("- ").map(|b| b.trim().to_string()))
+        .filter(|b| !b.is_empty())
+        .collect()
+}
+
 // -- History parsing --
 
 struct HistoryEntry {
+    id: String,
     project: String,
     domain: String,
     date: String,
@@ -2373,19 +5490,62 @@ struct HistoryEntry {
     source: String,
 }
 
-/// Walk a directory looking for history files (JSONL or legacy .md) and parse matching entries.
-fn walk_history_files(
-    dir: &std::path::Path,
-    query: &str,
+/// Walk a directory for history files: `history.jsonl` (else legacy `history.md`) in each
+/// directory, plus any `*.history.jsonl` or `*.history.md`.
+fn find_history_files(dir: &std::path::Path, files: &mut Vec<PathBuf>) {
+    if !dir.exists() { return; }
+
+    // Prefer JSONL, fall back to .md
+    let jsonl_path = dir.join("history.jsonl");
+    let md_path = dir.join("history.md");
+    if jsonl_path.exists() {
+        files.push(jsonl_path);
+    } else if md_path.exists() {
+        files.push(md_path);
+    }
+    if let Some(archive) = crate::vault::compress::history_archive(dir) {
+        files.push(archive);
+    }
+
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            let name = p.to_string_lossy();
+            if p.is_file() && (name.ends_with(".history.jsonl") || name.ends_with(".history.md")) {
+                files.push(p);
+            } else if p.is_dir() {
+                find_history_files(&p, files);
+            }
+        }
+    }
+}
+
+/// The files `find_history_files` would pick, chosen from paths already known from the index.
+fn select_history_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let known: HashSet<&PathBuf> = paths.iter().collect();
+    paths.iter()
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            match name {
+                "history.jsonl" | crate::vault::compress::HISTORY_ARCHIVE => true,
+                "history.md" => !known.contains(&p.with_file_name("history.jsonl")),
+                _ => name.ends_with(".history.jsonl") || name.ends_with(".history.md"),
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Read history files (JSONL or legacy .md) as one batch and parse entries matching `query`.
+fn read_history_files(
+    files: &[PathBuf],
+    query: &HistoryQuery,
     since: Option<chrono::NaiveDate>,
     max: usize,
     vault_dir_name: &str,
+    reads: crate::vault::batch::BatchRead,
     out: &mut Vec<HistoryEntry>,
 ) {
-    if !dir.exists() { return; }
-
-    let query_lower = query.to_lowercase();
-
     // Infer domain/project from a file path
     let infer_domain_project = |path: &std::path::Path, vault_name: &str| -> (String, String) {
         let path_str = path.to_string_lossy();
@@ -2403,11 +5563,7 @@ fn walk_history_files(
         }
     };
 
-    let process_jsonl = |path: &std::path::Path, vault_name: &str, out: &mut Vec<HistoryEntry>| {
-        let content = match std::fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(_) => return,
-        };
+    let process_jsonl = |path: &std::path::Path, content: &str, vault_name: &str, skip_rollups: bool, out: &mut Vec<HistoryEntry>| {
         let (domain, project) = infer_domain_project(path, vault_name);
         let source = path.to_string_lossy().to_string();
 
@@ -2422,10 +5578,13 @@ fn walk_history_files(
                     continue;
                 }
             };
+            if skip_rollups && entry.source == crate::vault::compress::ROLLUP_SOURCE {
+                continue;
+            }
 
             // Filter by query
-            let searchable = format!("{} {} {}", entry.title, entry.body, entry.focus).to_lowercase();
-            if !searchable.contains(&query_lower) {
+            let text = format!("{} {} {}", entry.title, entry.body, entry.focus);
+            if !query.matches(&Candidate { text: &text, status: &entry.status, source: &entry.source, date: &entry.date }) {
                 continue;
             }
 
@@ -2440,6 +5599,7 @@ fn walk_history_files(
             }
 
             out.push(HistoryEntry {
+                id: entry.id,
                 project: project.clone(),
                 domain: domain.clone(),
                 date: date_str.to_string(),
@@ -2450,11 +5610,7 @@ fn walk_history_files(
         }
     };
 
-    let process_md = |path: &std::path::Path, vault_name: &str, out: &mut Vec<HistoryEntry>| {
-        let content = match std::fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(_) => return,
-        };
+    let process_md = |path: &std::path::Path, content: &str, vault_name: &str, out: &mut Vec<HistoryEntry>| {
         let (domain, project) = infer_domain_project(path, vault_name);
         let source = path.to_string_lossy().to_string();
 
@@ -2466,14 +5622,15 @@ fn walk_history_files(
         for line in content.lines() {
             if line.starts_with("## ") && line.len() > 16 {
                 if in_entry && !current_title.is_empty() {
-                    let entry_text = format!("{current_title} {current_body}").to_lowercase();
-                    if entry_text.contains(&query_lower) {
+                    let text = format!("{current_title} {current_body}");
+                    if query.matches(&Candidate { text: &text, status: "", source: "", date: &current_date }) {
                         let skip = since.is_some_and(|s| {
                             chrono::NaiveDate::parse_from_str(&current_date, "%Y-%m-%d")
                                 .is_ok_and(|d| d < s)
                         });
                         if !skip && out.len() < max {
                             out.push(HistoryEntry {
+                                id: String::new(),
                                 project: project.clone(),
                                 domain: domain.clone(),
                                 date: current_date.clone(),
@@ -2506,14 +5663,15 @@ fn walk_history_files(
         }
 
         if in_entry && !current_title.is_empty() {
-            let entry_text = format!("{current_title} {current_body}").to_lowercase();
-            if entry_text.contains(&query_lower) {
+            let text = format!("{current_title} {current_body}");
+            if query.matches(&Candidate { text: &text, status: "", source: "", date: &current_date }) {
                 let skip = since.is_some_and(|s| {
                     chrono::NaiveDate::parse_from_str(&current_date, "%Y-%m-%d")
                         .is_ok_and(|d| d < s)
                 });
                 if !skip && out.len() < max {
                     out.push(HistoryEntry {
+                        id: String::new(),
                         project: project.clone(),
                         domain: domain.clone(),
                         date: current_date,
@@ -2526,46 +5684,334 @@ fn walk_history_files(
         }
     };
 
-    // Prefer JSONL, fall back to .md
-    let jsonl_path = dir.join("history.jsonl");
-    let md_path = dir.join("history.md");
-    if jsonl_path.exists() {
-        process_jsonl(&jsonl_path, vault_dir_name, out);
-    } else if md_path.exists() {
-        process_md(&md_path, vault_dir_name, out);
+    for (path, content) in files.iter().zip(crate::vault::batch::read_many(files, reads)) {
+        let Ok(content) = content else {
+            continue;
+        };
+        if crate::vault::compress::logical_path(path).extension().is_some_and(|e| e == "jsonl") {
+            // Compact's monthly summaries repeat the archive, when that's being read too
+            let archive_read = files.iter().any(|f| {
+                f.parent() == path.parent()
+                    && crate::vault::compress::logical_path(f).file_name().is_some_and(|n| n == crate::vault::compress::HISTORY_ARCHIVE)
+            });
+            process_jsonl(path, &content, vault_dir_name, archive_read, out);
+        } else {
+            process_md(path, &content, vault_dir_name, out);
+        }
     }
+}
 
-    if let Ok(entries) = std::fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let p = entry.path();
-            if p.is_file() && p.to_string_lossy().ends_with(".history.jsonl") {
-                process_jsonl(&p, vault_dir_name, out);
-            } else if p.is_file() && p.to_string_lossy().ends_with(".history.md") {
-                process_md(&p, vault_dir_name, out);
-            } else if p.is_dir() {
-                walk_history_files(&p, query, since, max, vault_dir_name, out);
-            }
+/// One `recall` hit, labelled with the kind of record it came from.
+#[derive(Debug, Serialize)]
+struct RecallEntry {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    domain: String,
+    project: String,
+    date: String,
+    title: String,
+    body: String,
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+}
+
+fn before_since(date: &str, since: Option<chrono::NaiveDate>) -> bool {
+    since.is_some_and(|s| {
+        chrono::NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d").is_ok_and(|d| d < s)
+    })
+}
+
+/// `## YYYY-MM-DD — Title` sections of a decisions.md, as (date, title, body).
+pub(crate) fn parse_decisions(content: &str) -> Vec<(String, String, String)> {
+    let mut out: Vec<(String, String, String)> = Vec::new();
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            let (date, title) = match heading.split_once('—') {
+                Some((d, t)) => (d.trim().to_string(), t.trim().to_string()),
+                None => (String::new(), heading.trim().to_string()),
+            };
+            out.push((date, title, String::new()));
+        } else if let Some((_, _, body)) = out.last_mut()
+            && line != "---" {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    for (_, _, body) in &mut out {
+        *body = body.trim().to_string();
+    }
+    out
+}
+
+/// Search a project's history.jsonl, lessons.jsonl, and decisions.md. Matching is a
+/// case-insensitive substring, like `history`.
+fn recall_project_files(
+    project_dir: &std::path::Path,
+    domain: &str,
+    project: &str,
+    query_lower: &str,
+    since: Option<chrono::NaiveDate>,
+    out: &mut Vec<RecallEntry>,
+) {
+    let mut push = |kind: &'static str, date: &str, title: String, body: String, file: &str, searchable: &[&str]| {
+        if before_since(date, since) || !searchable.iter().any(|s| s.to_lowercase().contains(query_lower)) {
+            return;
+        }
+        out.push(RecallEntry {
+            kind,
+            domain: domain.to_string(),
+            project: project.to_string(),
+            date: date.get(..10).unwrap_or(date).to_string(),
+            title,
+            body,
+            source: project_dir.join(file).to_string_lossy().to_string(),
+            session_id: None,
+        });
+    };
+
+    // Entries `wardwell compact` moved out of history.jsonl are still history
+    let archive = crate::vault::compress::history_archive(project_dir);
+    let archived = archive.as_deref().map(crate::vault::jsonl::read_entries::<HistoryJsonlEntry>).unwrap_or_default();
+    let archive_name = archive.as_deref().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    for e in archived {
+        let searchable = [e.title.as_str(), e.body.as_str(), e.focus.as_str()];
+        push("history", &e.date, e.title.clone(), e.body.clone(), &archive_name, &searchable);
+    }
+    for e in crate::vault::jsonl::read_entries::<HistoryJsonlEntry>(&project_dir.join("history.jsonl")) {
+        // The raw entries are in the archive, so its summaries would repeat them
+        if archive.is_some() && e.source == crate::vault::compress::ROLLUP_SOURCE {
+            continue;
+        }
+        let searchable = [e.title.as_str(), e.body.as_str(), e.focus.as_str()];
+        push("history", &e.date, e.title.clone(), e.body.clone(), "history.jsonl", &searchable);
+    }
+    for e in crate::vault::jsonl::read_entries::<LessonJsonlEntry>(&project_dir.join("lessons.jsonl")) {
+        let body = format!("What happened: {}\nRoot cause: {}\nPrevention: {}", e.what_happened, e.root_cause, e.prevention);
+        let searchable = [e.title.as_str(), body.as_str()];
+        push("lesson", &e.date, e.title.clone(), body.clone(), "lessons.jsonl", &searchable);
+    }
+    if let Ok(content) = std::fs::read_to_string(project_dir.join("decisions.md")) {
+        for (date, title, body) in parse_decisions(&content) {
+            let searchable = [title.as_str(), body.as_str()];
+            push("decision", &date, title.clone(), body.clone(), "decisions.md", &searchable);
+        }
+    }
+}
+
+/// Lines of a summary mentioning the query, for the `recall` body.
+const RECALL_SUMMARY_LINES: usize = 3;
+
+/// Search session summaries (`~/.wardwell/summaries`) belonging to a domain, and to
+/// `project` when given — matched against the last folder of the session's working directory.
+fn recall_summaries(
+    summaries_dir: &std::path::Path,
+    domain: &str,
+    project: Option<&str>,
+    query_lower: &str,
+    since: Option<chrono::NaiveDate>,
+    out: &mut Vec<RecallEntry>,
+) {
+    let Ok(files) = std::fs::read_dir(summaries_dir) else {
+        return;
+    };
+    let want_project = project.map(crate::vault::project::slugify);
+    for file in files.flatten() {
+        let path = file.path();
+        if crate::vault::compress::logical_path(&path).extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Ok(content) = crate::vault::compress::read_to_string(&path) else {
+            continue;
+        };
+        let Some(fm) = crate::vault::frontmatter::frontmatter_yaml(&content)
+            .and_then(|y| serde_yaml::from_str::<serde_yaml::Value>(y).ok()) else {
+            continue;
+        };
+        if fm.get("domain").and_then(|d| d.as_str()) != Some(domain) {
+            continue;
+        }
+        let project_path = fm.get("project").and_then(|p| p.as_str()).unwrap_or("");
+        let project_name = project_path.trim_end_matches('/').rsplit('/').next().unwrap_or(project_path);
+        if want_project.as_ref().is_some_and(|want| crate::vault::project::slugify(project_name) != *want) {
+            continue;
+        }
+
+        let body = content.splitn(3, "---").nth(2).unwrap_or(&content);
+        let hits: Vec<&str> = body.lines()
+            .map(str::trim)
+            .filter(|l| l.to_lowercase().contains(query_lower))
+            .take(RECALL_SUMMARY_LINES)
+            .collect();
+        if hits.is_empty() {
+            continue;
+        }
+        let date = file.metadata().and_then(|m| m.modified()).ok()
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        if before_since(&date, since) {
+            continue;
+        }
+        let session_id = path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+        out.push(RecallEntry {
+            kind: "summary",
+            domain: domain.to_string(),
+            project: project_name.to_string(),
+            date,
+            title: format!("Session {session_id}"),
+            body: hits.join("\n"),
+            source: path.to_string_lossy().to_string(),
+            session_id: Some(session_id),
+        });
+    }
+}
+
+// -- Effort --
+
+/// Default look-back window for effort metrics.
+const EFFORT_WINDOW_DAYS: i64 = 56;
+
+/// Active days at most this far apart belong to the same burst.
+const BURST_GAP_DAYS: i64 = 2;
+
+/// A project is neglected once its quiet stretch reaches this multiple of its usual gap.
+const NEGLECT_GAP_MULTIPLE: f64 = 2.0;
+
+/// Quiet days below which a project is never neglected, however tight its cadence.
+const NEGLECT_MIN_DAYS: i64 = 3;
+
+/// Active days a project needs in the window before its cadence means anything.
+const EFFORT_MIN_ACTIVE_DAYS: usize = 3;
+
+/// Work cadence for one project over the effort window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Effort {
+    sessions: usize,
+    session_hours: f64,
+    history_entries: usize,
+    /// Distinct days with a session or history entry.
+    active_days: usize,
+    sessions_per_week: f64,
+    avg_gap_days: Option<f64>,
+    max_gap_days: Option<i64>,
+    days_since_last: Option<i64>,
+    /// Runs of active days no more than `BURST_GAP_DAYS` apart.
+    bursts: usize,
+    /// dormant, sporadic, steady, or bursty.
+    pattern: &'static str,
+    /// Days since last activity as a multiple of the average gap.
+    overdue_ratio: Option<f64>,
+    /// Open project whose quiet stretch is well past its usual cadence.
+    neglected: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectEffort {
+    domain: String,
+    project: String,
+    status: String,
+    #[serde(flatten)]
+    effort: Effort,
+}
+
+#[derive(Deserialize)]
+struct DatedEntry {
+    date: String,
+    #[serde(default)]
+    source: String,
+}
+
+fn round1(x: f64) -> f64 {
+    (x * 10.0).round() / 10.0
+}
+
+/// Cadence metrics from history dates and (start date, hours) session spans already
+/// limited to the window. `open` projects can be flagged as neglected.
+fn compute_effort(
+    history: &[chrono::NaiveDate],
+    sessions: &[(chrono::NaiveDate, f64)],
+    window_start: chrono::NaiveDate,
+    today: chrono::NaiveDate,
+    open: bool,
+) -> Effort {
+    let mut days: Vec<chrono::NaiveDate> = history.iter().copied().chain(sessions.iter().map(|(d, _)| *d)).collect();
+    days.sort();
+    days.dedup();
+
+    let gaps: Vec<i64> = days.windows(2).map(|w| (w[1] - w[0]).num_days()).collect();
+    let avg_gap = (!gaps.is_empty()).then(|| gaps.iter().sum::<i64>() as f64 / gaps.len() as f64);
+    let max_gap = gaps.iter().copied().max();
+    let days_since_last = days.last().map(|d| (today - *d).num_days());
+    let bursts = if days.is_empty() { 0 } else { 1 + gaps.iter().filter(|g| **g > BURST_GAP_DAYS).count() };
+
+    let pattern = match (days.len(), avg_gap, max_gap) {
+        (0, _, _) => "dormant",
+        (n, Some(avg), Some(max)) if n >= EFFORT_MIN_ACTIVE_DAYS => {
+            if bursts > 1 && max as f64 > 2.0 * avg { "bursty" } else { "steady" }
         }
+        _ => "sporadic",
+    };
+
+    let overdue_ratio = match (avg_gap, days_since_last) {
+        (Some(avg), Some(since)) if days.len() >= EFFORT_MIN_ACTIVE_DAYS => Some(since as f64 / avg.max(1.0)),
+        _ => None,
+    };
+    let neglected = open
+        && days_since_last.is_some_and(|d| d >= NEGLECT_MIN_DAYS)
+        && overdue_ratio.is_some_and(|r| r >= NEGLECT_GAP_MULTIPLE);
+
+    let weeks = ((today - window_start).num_days().max(1) as f64) / 7.0;
+    Effort {
+        sessions: sessions.len(),
+        session_hours: round1(sessions.iter().map(|(_, h)| h).sum()),
+        history_entries: history.len(),
+        active_days: days.len(),
+        sessions_per_week: round1(sessions.len() as f64 / weeks),
+        avg_gap_days: avg_gap.map(round1),
+        max_gap_days: max_gap,
+        days_since_last,
+        bursts,
+        pattern,
+        overdue_ratio: overdue_ratio.map(round1),
+        neglected,
     }
 }
 
+/// Start date and length in hours of a session span, if its timestamps parse.
+fn span_hours(span: &crate::daemon::indexer::SessionSpan) -> Option<(chrono::NaiveDate, f64)> {
+    let start = chrono::DateTime::parse_from_rfc3339(&span.first_message_at).ok()?;
+    let end = chrono::DateTime::parse_from_rfc3339(&span.last_message_at).ok()?;
+    let hours = (end - start).num_seconds().max(0) as f64 / 3600.0;
+    Some((start.with_timezone(&chrono::Local).date_naive(), hours))
+}
+
 // -- JSONL types --
 
 #[derive(Debug, Serialize, Deserialize)]
-struct HistoryJsonlEntry {
-    date: String,
-    title: String,
-    status: String,
-    focus: String,
-    next_action: String,
-    commit: String,
-    body: String,
+pub(crate) struct HistoryJsonlEntry {
+    /// Stable handle for the entry; entries written before IDs existed have none.
     #[serde(default, skip_serializing_if = "String::is_empty")]
-    source: String,
+    pub(crate) id: String,
+    pub(crate) date: String,
+    pub(crate) title: String,
+    pub(crate) status: String,
+    pub(crate) focus: String,
+    pub(crate) next_action: String,
+    pub(crate) commit: String,
+    pub(crate) body: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) source: String,
+    /// Blockers and waiting-on items as they stood at this entry; empty before sync recorded them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) blockers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) waiting_on: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct LessonJsonlEntry {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    id: String,
     date: String,
     title: String,
     what_happened: String,
@@ -2578,26 +6024,22 @@ struct LessonJsonlEntry {
 // -- Write helpers --
 
 /// Append a JSON line to a JSONL file. Creates file with schema header if missing.
-fn append_jsonl(
+pub(crate) fn append_jsonl(
     path: &std::path::Path,
     schema_name: &str,
     entry_json: &str,
+    durability: &crate::config::loader::DurabilityConfig,
 ) -> Result<(), std::io::Error> {
-    use std::io::Write;
-    let needs_schema = !path.exists() || std::fs::metadata(path).is_ok_and(|m| m.len() == 0);
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)?;
-    if needs_schema {
-        writeln!(file, "{{\"_schema\": \"{schema_name}\", \"_version\": \"1.0\"}}")?;
-    }
-    writeln!(file, "{entry_json}")?;
-    Ok(())
+    crate::vault::jsonl::append(path, schema_name, entry_json, durability)
 }
 
 /// Prepend content to a file, creating it with a header if it doesn't exist.
 fn prepend_to_file(path: &std::path::Path, header: &str, content: &str) -> Result<(), std::io::Error> {
+    std::fs::write(path, prepended(path, header, content)?)
+}
+
+/// The file at `path` with `content` inserted after its header line, as `prepend_to_file` writes it.
+fn prepended(path: &std::path::Path, header: &str, content: &str) -> Result<String, std::io::Error> {
     let existing = if path.exists() {
         std::fs::read_to_string(path)?
     } else {
@@ -2605,15 +6047,47 @@ fn prepend_to_file(path: &std::path::Path, header: &str, content: &str) -> Resul
     };
 
     // Insert after the header line
-    let new_content = if let Some(pos) = existing.find("\n\n") {
+    Ok(if let Some(pos) = existing.find("\n\n") {
         let header_part = &existing[..pos + 2];
         let rest = &existing[pos + 2..];
         format!("{header_part}{content}{rest}")
     } else {
         format!("{existing}\n{content}")
-    };
+    })
+}
+
+/// A dry run's entry for a file `content` would replace. `previous` is None for a new file.
+fn planned_write(rel: &str, previous: Option<&str>, content: &str) -> serde_json::Value {
+    serde_json::json!({
+        "path": rel,
+        "exists": previous.is_some(),
+        "content": content,
+        "diff": crate::vault::diff::unified_diff(previous.unwrap_or_default(), content, rel),
+    })
+}
+
+/// A dry run's entry for `text` appended to a file. `previous` is None for a new file.
+fn planned_append(rel: &str, previous: Option<&str>, text: &str) -> serde_json::Value {
+    let previous = previous.unwrap_or_default();
+    serde_json::json!({
+        "path": rel,
+        "exists": !previous.is_empty(),
+        "append": text,
+        "diff": crate::vault::diff::unified_diff(previous, &format!("{previous}{text}"), rel),
+    })
+}
 
-    std::fs::write(path, new_content)
+/// The response to a write action called with `dry_run`: every file change it would make.
+fn dry_run_response(changes: Vec<serde_json::Value>, warning: Option<&str>) -> serde_json::Value {
+    let mut resp = serde_json::json!({
+        "dry_run": true,
+        "changes": changes,
+        "message": "Nothing was written. Repeat the call without dry_run to apply it.",
+    });
+    if let Some(w) = warning {
+        resp["warning"] = serde_json::json!(w);
+    }
+    resp
 }
 
 /// Copy content to the system clipboard via pbcopy.
@@ -2640,19 +6114,54 @@ mod tests {
     use super::*;
 
     fn make_test_server(vault_root: &std::path::Path) -> WardwellServer {
+        make_test_server_with_tools(vault_root, Default::default())
+    }
+
+    fn make_test_server_with_tools(vault_root: &std::path::Path, tools_enabled: crate::config::loader::ToolsEnabled) -> WardwellServer {
+        make_test_server_with(vault_root, |config| config.tools_enabled = tools_enabled)
+    }
+
+    fn make_test_server_with(vault_root: &std::path::Path, adjust: impl FnOnce(&mut crate::config::loader::WardwellConfig)) -> WardwellServer {
         let db_path = vault_root.join("_test_index.db");
         let index = Arc::new(crate::index::store::IndexStore::open(&db_path).unwrap());
-        let config = crate::config::loader::WardwellConfig {
+        let mut config = crate::config::loader::WardwellConfig {
             vault_path: vault_root.to_path_buf(),
             registry: crate::domain::registry::DomainRegistry::from_domains(vec![]),
             session_sources: vec![],
+            session_exclude: vec![],
             exclude: vec![],
             ai: Default::default(),
             stop_hook: true,
             kanban_enabled: false,
             kanban_queries: std::collections::HashMap::new(),
             kanban_prefixes: std::collections::HashMap::new(),
+            backup: None,
+            retention: None,
+            tools_enabled: Default::default(),
+            stale_index: Default::default(),
+            dedup_window_seconds: 300,
+            log_level: rmcp::model::LoggingLevel::Info,
+            statuses: std::collections::BTreeMap::new(),
+            remote_vault: Default::default(),
+            durability: Default::default(),
+            slug_transliteration: Default::default(),
+            symlinks: Default::default(),
+            ranking: Default::default(),
+            max_response_bytes: 80_000,
+            heartbeat: None,
+            digest: None,
+            state_sections: Vec::new(),
+            sessions_around_days: 3,
+            intent_ttl_days: 14,
+            metrics: false,
+            slow_action_ms: 2000,
+            inject_max_tokens: 0,
+            write_policy: Default::default(),
+            blocked_terms: crate::config::loader::default_blocked_terms(),
+            read_only: false,
+            http: Default::default(),
         };
+        adjust(&mut config);
         WardwellServer::new(config, index, Arc::new(Mutex::new(None)), None, None)
     }
 
@@ -2769,7 +6278,7 @@ mod tests {
 
         let path = tmp.join("history.jsonl");
         let entry = r#"{"date":"2026-02-22T14:30:00Z","title":"Test","status":"active","focus":"f","next_action":"n","commit":"c","body":"b"}"#;
-        append_jsonl(&path, "history", entry).unwrap();
+        append_jsonl(&path, "history", entry, &Default::default()).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         let lines: Vec<&str> = content.lines().collect();
@@ -2789,8 +6298,8 @@ mod tests {
         let path = tmp.join("history.jsonl");
         let entry1 = r#"{"date":"2026-02-22T14:00:00Z","title":"First","status":"","focus":"","next_action":"","commit":"","body":""}"#;
         let entry2 = r#"{"date":"2026-02-22T15:00:00Z","title":"Second","status":"","focus":"","next_action":"","commit":"","body":""}"#;
-        append_jsonl(&path, "history", entry1).unwrap();
-        append_jsonl(&path, "history", entry2).unwrap();
+        append_jsonl(&path, "history", entry1, &Default::default()).unwrap();
+        append_jsonl(&path, "history", entry2, &Default::default()).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         let lines: Vec<&str> = content.lines().collect();
@@ -2810,6 +6319,7 @@ mod tests {
 
         let path = tmp.join("lessons.jsonl");
         let entry = LessonJsonlEntry {
+            id: String::new(),
             date: "2026-02-22".to_string(),
             title: "FTS5 duplicate".to_string(),
             what_happened: "Re-inserted all files".to_string(),
@@ -2818,7 +6328,7 @@ mod tests {
             source: String::new(),
         };
         let json = serde_json::to_string(&entry).unwrap();
-        append_jsonl(&path, "lessons", &json).unwrap();
+        append_jsonl(&path, "lessons", &json, &Default::default()).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         let lines: Vec<&str> = content.lines().collect();
@@ -2989,7 +6499,7 @@ mod tests {
         ]);
 
         let since = chrono::NaiveDate::parse_from_str("2026-02-01", "%Y-%m-%d").unwrap();
-        let entries = collect_history_entries(&tmp, Some(since), None, true, &[]);
+        let entries = collect_history_entries(&tmp, Some(since), None, true, &[], SymlinkPolicy::Follow, Default::default());
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].title, "Recent entry");
         assert_eq!(entries[0].domain, "work");
@@ -3007,15 +6517,74 @@ mod tests {
             ("work", "archive", &content),
         ]);
 
-        let entries = collect_history_entries(&tmp, None, None, true, &[]);
+        let entries = collect_history_entries(&tmp, None, None, true, &[], SymlinkPolicy::Follow, Default::default());
         assert!(entries.is_empty());
 
-        let entries_with_archive = collect_history_entries(&tmp, None, None, false, &[]);
+        let entries_with_archive = collect_history_entries(&tmp, None, None, false, &[], SymlinkPolicy::Follow, Default::default());
         assert_eq!(entries_with_archive.len(), 1);
 
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn history_reads_compressed_archives() {
+        let tmp = setup_test_vault("wardwell_test_history_zst", &[
+            ("work", "api", &make_history_jsonl(&[("2026-02-20", "Recent work", "active", "r")])),
+        ]);
+        let archive = tmp.join("work/api").join(crate::vault::compress::HISTORY_ARCHIVE);
+        let old = make_history_jsonl(&[("2024-05-01", "Archived launch", "completed", "a")]);
+        crate::vault::compress::write_compressed(&crate::vault::compress::compressed_path(&archive), &old).unwrap();
+
+        let entries = collect_history_entries(&tmp, None, None, false, &[], SymlinkPolicy::Follow, Default::default());
+        let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+        assert!(titles.contains(&"Archived launch") && titles.contains(&"Recent work"), "{titles:?}");
+
+        let mut files = Vec::new();
+        find_history_files(&tmp, &mut files);
+        let mut found = Vec::new();
+        read_history_files(&files, &HistoryQuery::parse("launch").unwrap(), None, 10, "wardwell_test_history_zst", Default::default(), &mut found);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].project, "api");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn archives_are_read_when_the_window_reaches_them() {
+        let rollup = r#"{"date":"2024-05-20T10:00:00Z","title":"2024-05: 1 entry rolled up","status":"completed","focus":"a","next_action":"","commit":"","body":"- 2024-05-20: Archived launch","source":"rollup"}"#;
+        let history = make_history_jsonl(&[("2026-02-20", "Recent work", "active", "r")]).replacen('\n', &format!("\n{rollup}\n"), 1);
+        let tmp = setup_test_vault("wardwell_test_rollup_window", &[("work", "api", &history)]);
+        let archive = tmp.join("work/api").join(crate::vault::compress::HISTORY_ARCHIVE);
+        std::fs::write(&archive, make_history_jsonl(&[("2024-05-20", "Archived launch", "completed", "a")])).unwrap();
+
+        let titles = |since: Option<chrono::NaiveDate>| -> Vec<String> {
+            collect_history_entries(&tmp, since, None, true, &[], SymlinkPolicy::Follow, Default::default()).into_iter().map(|e| e.title).collect()
+        };
+        assert_eq!(titles(None), vec!["Recent work", "Archived launch"], "summaries never double-count the archive");
+        let may = chrono::NaiveDate::from_ymd_opt(2024, 5, 1);
+        assert_eq!(titles(may), vec!["Recent work", "Archived launch"]);
+
+        // A month archived without a summary is still read for a window after the newest one
+        let unsummarized = make_history_jsonl(&[("2024-05-20", "Archived launch", "completed", "a"), ("2025-03-02", "Archived fix", "active", "f")]);
+        std::fs::write(&archive, unsummarized).unwrap();
+        assert_eq!(titles(chrono::NaiveDate::from_ymd_opt(2025, 1, 1)), vec!["Recent work", "Archived fix"]);
+
+        // An archive last written before the window starts is never opened. Its entry is dated
+        // after the write, which compact never does, so reading it would show
+        std::fs::write(&archive, make_history_jsonl(&[("2025-08-01", "Never read", "active", "n")])).unwrap();
+        let written = chrono::NaiveDate::from_ymd_opt(2025, 6, 1).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        std::fs::File::options().write(true).open(&archive).unwrap().set_modified(written.into()).unwrap();
+        assert_eq!(titles(chrono::NaiveDate::from_ymd_opt(2025, 7, 1)), vec!["Recent work"]);
+
+        let mut files = Vec::new();
+        find_history_files(&tmp, &mut files);
+        let mut found = Vec::new();
+        read_history_files(&files, &HistoryQuery::parse("launch").unwrap(), None, 10, "wardwell_test_rollup_window", Default::default(), &mut found);
+        assert!(found.is_empty(), "the archive is in the list, so its summary is skipped");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn collect_history_entries_domain_filter() {
         let work_content = make_history_jsonl(&[("2026-02-20", "Work", "active", "w")]);
@@ -3025,13 +6594,56 @@ mod tests {
             ("personal", "proj-b", &personal_content),
         ]);
 
-        let entries = collect_history_entries(&tmp, None, Some("work"), true, &[]);
+        let entries = collect_history_entries(&tmp, None, Some("work"), true, &[], SymlinkPolicy::Follow, Default::default());
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].title, "Work");
 
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
+    fn parsed_entry(project: &str, date: &str, source: &str) -> ParsedHistoryEntry {
+        ParsedHistoryEntry {
+            domain: "work".to_string(),
+            project: project.to_string(),
+            date: date.to_string(),
+            title: "t".to_string(),
+            status: "active".to_string(),
+            focus: String::new(),
+            body: String::new(),
+            source: source.to_string(),
+            blockers: Vec::new(),
+            waiting_on: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn source_report_counts_and_flags_unexecuted_plans() {
+        let entries = vec![
+            parsed_entry("planned", "2026-02-01", "desktop"),
+            parsed_entry("planned", "2026-02-10", "desktop"),
+            parsed_entry("planned", "2026-03-02", "desktop"),
+            parsed_entry("shipped", "2026-02-01", "desktop"),
+            parsed_entry("shipped", "2026-02-03", "desktop"),
+            parsed_entry("shipped", "2026-02-05", "desktop"),
+            parsed_entry("shipped", "2026-02-06", "code"),
+            parsed_entry("shipped", "2026-02-07", ""),
+        ];
+        let report = source_report(&entries);
+        assert_eq!(report["totals"]["desktop"], 6);
+        assert_eq!(report["totals"]["code"], 1);
+        assert_eq!(report["totals"]["unknown"], 1);
+        assert_eq!(report["domains"][0]["total"], 8);
+
+        let planned = report["planned_not_executed"].as_array().unwrap();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0]["project"], "work/planned");
+        assert_eq!(planned[0]["last_desktop_entry"], "2026-03-02");
+
+        let shipped = report["projects"].as_array().unwrap().iter()
+            .find(|p| p["project"] == "work/shipped").unwrap();
+        assert_eq!(shipped["by_month"]["2026-02"]["desktop"], 3);
+    }
+
     #[test]
     fn retrospective_groups_by_project() {
         let content = make_history_jsonl(&[
@@ -3042,7 +6654,7 @@ mod tests {
             ("work", "proj-a", &content),
         ]);
 
-        let entries = collect_history_entries(&tmp, Some(chrono::NaiveDate::parse_from_str("2026-02-01", "%Y-%m-%d").unwrap()), None, true, &[]);
+        let entries = collect_history_entries(&tmp, Some(chrono::NaiveDate::parse_from_str("2026-02-01", "%Y-%m-%d").unwrap()), None, true, &[], SymlinkPolicy::Follow, Default::default());
         let mut groups: std::collections::HashMap<String, Vec<&ParsedHistoryEntry>> = std::collections::HashMap::new();
         for e in &entries {
             groups.entry(format!("{}/{}", e.domain, e.project)).or_default().push(e);
@@ -3062,7 +6674,7 @@ mod tests {
             ("work", "done-proj", &done_content),
         ]);
 
-        let entries = collect_history_entries(&tmp, None, None, true, &[]);
+        let entries = collect_history_entries(&tmp, None, None, true, &[], SymlinkPolicy::Follow, Default::default());
         let mut completed = Vec::new();
         let mut still_active = Vec::new();
         let mut groups: std::collections::HashMap<String, Vec<&ParsedHistoryEntry>> = std::collections::HashMap::new();
@@ -3095,7 +6707,7 @@ mod tests {
             ("work", "fresh-proj", &recent_content),
         ]);
 
-        let entries = collect_history_entries(&tmp, None, None, true, &[]);
+        let entries = collect_history_entries(&tmp, None, None, true, &[], SymlinkPolicy::Follow, Default::default());
         let today_date = chrono::Local::now().date_naive();
         let mut latest: std::collections::HashMap<String, (&str, &str)> = std::collections::HashMap::new();
         for e in &entries {
@@ -3135,7 +6747,7 @@ mod tests {
             ("work", "proj-b", &content_b),
         ]);
 
-        let entries = collect_history_entries(&tmp, None, None, true, &[]);
+        let entries = collect_history_entries(&tmp, None, None, true, &[], SymlinkPolicy::Follow, Default::default());
         let stopwords: &[&str] = &["the", "a", "an", "is", "for", "and"];
         let mut word_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
         for e in &entries {
@@ -3151,6 +6763,44 @@ mod tests {
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn patterns_reads_recorded_blockers_and_configured_terms() {
+        let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+        let entry = |title: &str, focus: &str, blockers: &str| format!(
+            "{{\"date\":\"{today}T10:00:00Z\",\"title\":\"{title}\",\"status\":\"active\",\"focus\":\"{focus}\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\"{blockers}}}"
+        );
+        let recorded = [
+            entry("Sync one", "api", r#","blockers":["API keys"]"#),
+            entry("Sync two", "api", r#","waiting_on":["Legal review"],"blockers":["API keys"]"#),
+        ].join("\n");
+        let spanish = [entry("Uno", "bloqueado por legal", ""), entry("Dos", "sigue bloqueado", "")].join("\n");
+        let tmp = setup_test_vault("wardwell_test_patterns_blockers", &[
+            ("work", "recorded", &recorded),
+            ("work", "spanish", &spanish),
+        ]);
+
+        let mut server = make_test_server(&tmp);
+        let params = SearchParams {
+            action: "patterns".to_string(),
+            domain: Some("work".to_string()),
+            ..Default::default()
+        };
+        let blocked = |server: &WardwellServer| -> Vec<serde_json::Value> {
+            let out: serde_json::Value = serde_json::from_str(&server.action_patterns(&params)).unwrap();
+            out["recurring_blockers"].as_array().unwrap().clone()
+        };
+        let found = blocked(&server);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0]["project"], "work/recorded");
+        assert_eq!(found[0]["blockers"], serde_json::json!(["API keys", "Legal review"]));
+
+        Arc::get_mut(&mut server.config).unwrap().blocked_terms = vec!["bloqueado".to_string()];
+        let projects: Vec<String> = blocked(&server).iter().map(|b| b["project"].as_str().unwrap().to_string()).collect();
+        assert!(projects.contains(&"work/spanish".to_string()), "{projects:?}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn read_recent_history_from_dir_falls_back_to_md() {
         let tmp = std::env::temp_dir().join("wardwell_test_history_fallback_md");
@@ -3175,7 +6825,7 @@ mod tests {
         std::fs::create_dir_all(&project_dir).unwrap();
 
         // Write an existing list so we can verify it appears in existing_lists
-        append_jsonl(&project_dir.join("ideas.jsonl"), "ideas", r#"{"title":"old"}"#).unwrap();
+        append_jsonl(&project_dir.join("ideas.jsonl"), "ideas", r#"{"title":"old"}"#, &Default::default()).unwrap();
 
         let server = make_test_server(&tmp);
         let params = WriteParams {
@@ -3183,13 +6833,9 @@ mod tests {
             domain: "personal".to_string(),
             project: Some("test-proj".to_string()),
             list: Some("future-ideas".to_string()),
-            confirmed: None,
             title: Some("Test idea".to_string()),
             body: Some("Details".to_string()),
-            status: None, focus: None, why_this_matters: None, next_action: None,
-            open_questions: None, blockers: None, waiting_on: None, commit_message: None,
-            what_happened: None, root_cause: None, prevention: None, path: None,
-            source: None,
+            ..Default::default()
         };
         let result = server.action_append_list(&params, "test-proj", None);
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
@@ -3215,10 +6861,7 @@ mod tests {
             confirmed: Some(true),
             title: Some("Build a rocket".to_string()),
             body: Some("Literally".to_string()),
-            status: None, focus: None, why_this_matters: None, next_action: None,
-            open_questions: None, blockers: None, waiting_on: None, commit_message: None,
-            what_happened: None, root_cause: None, prevention: None, path: None,
-            source: None,
+            ..Default::default()
         };
         let result = server.action_append_list(&params, "test-proj", None);
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
@@ -3232,55 +6875,1191 @@ mod tests {
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
-    #[test]
-    fn append_list_rejects_reserved_names() {
-        let tmp = std::env::temp_dir().join("wardwell_test_append_reserved");
+    #[tokio::test]
+    async fn write_to_an_unseen_domain_asks_first() {
+        let tmp = std::env::temp_dir().join("wardwell_test_new_domain");
         let _ = std::fs::remove_dir_all(&tmp);
-        std::fs::create_dir_all(&tmp).unwrap();
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        std::fs::create_dir_all(tmp.join(".trash")).unwrap();
 
         let server = make_test_server(&tmp);
-        let params = WriteParams {
-            action: "append".to_string(),
-            domain: "personal".to_string(),
-            project: Some("test-proj".to_string()),
-            list: Some("history".to_string()),
-            confirmed: None,
-            title: Some("Test".to_string()),
-            body: None,
-            status: None, focus: None, why_this_matters: None, next_action: None,
-            open_questions: None, blockers: None, waiting_on: None, commit_message: None,
-            what_happened: None, root_cause: None, prevention: None, path: None,
-            source: None,
+        let params = |domain: &str, confirm_new_domain: Option<bool>| WriteParams {
+            action: "append_history".to_string(),
+            domain: domain.to_string(),
+            project: Some("api".to_string()),
+            confirm_new_domain,
+            title: Some("Triage".to_string()),
+            body: Some("Looked at alerts".to_string()),
+            ..Default::default()
         };
-        let result = server.action_append_list(&params, "test-proj", None);
-        assert!(result.contains("built-in list"));
+
+        let parsed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("wrok", None))).await).unwrap();
+        assert_eq!(parsed["needs_confirmation"], true);
+        assert_eq!(parsed["suggested_domain"], "work");
+        assert_eq!(parsed["known_domains"], serde_json::json!(["work"]));
+        assert!(!tmp.join("wrok").exists());
+
+        let parsed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("garden", None))).await).unwrap();
+        assert!(parsed["suggested_domain"].is_null());
+        assert!(parsed["message"].as_str().unwrap().contains("Known domains: work."));
+
+        // The list and project confirmation is not enough on its own
+        let parsed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(WriteParams { confirmed: Some(true), ..params("garden", None) })).await).unwrap();
+        assert_eq!(parsed["needs_confirmation"], true);
+        let result = server.wardwell_write(Parameters(WriteParams { dry_run: Some(true), ..params("garden", Some(true)) })).await;
+        assert!(result.contains("would create new domain 'garden'"), "{result}");
+        assert!(!tmp.join("garden").exists());
+
+        let result = server.wardwell_write(Parameters(params("garden", Some(true)))).await;
+        assert!(result.contains("created new domain 'garden'"), "{result}");
+        assert!(tmp.join("garden/api/history.jsonl").exists());
+        // Seen now, so no more asking
+        let result = server.wardwell_write(Parameters(params("garden", None))).await;
+        assert!(!result.contains("needs_confirmation") && !result.contains("created new domain"), "{result}");
 
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
-    #[test]
-    fn append_list_existing_list_no_confirmation_needed() {
-        let tmp = std::env::temp_dir().join("wardwell_test_append_existing");
+    #[tokio::test]
+    async fn write_resolves_near_miss_project_names() {
+        let tmp = std::env::temp_dir().join("wardwell_test_project_alias");
         let _ = std::fs::remove_dir_all(&tmp);
-        let project_dir = tmp.join("personal").join("test-proj");
-        std::fs::create_dir_all(&project_dir).unwrap();
-
-        // Pre-create the list
-        append_jsonl(&project_dir.join("bookmarks.jsonl"), "bookmarks", r#"{"title":"first"}"#).unwrap();
+        std::fs::create_dir_all(tmp.join("work").join("sentry-bot")).unwrap();
 
         let server = make_test_server(&tmp);
-        let params = WriteParams {
-            action: "append".to_string(),
-            domain: "personal".to_string(),
-            project: Some("test-proj".to_string()),
-            list: Some("bookmarks".to_string()),
-            confirmed: None, // not needed — list exists
-            title: Some("Second entry".to_string()),
-            body: None,
-            status: None, focus: None, why_this_matters: None, next_action: None,
-            open_questions: None, blockers: None, waiting_on: None, commit_message: None,
-            what_happened: None, root_cause: None, prevention: None, path: None,
-            source: None,
+        let params = |project: &str, confirmed: Option<bool>| WriteParams {
+            action: "append_history".to_string(),
+            domain: "work".to_string(),
+            project: Some(project.to_string()),
+            confirmed,
+            title: Some("Triage".to_string()),
+            body: Some("Looked at alerts".to_string()),
+            ..Default::default()
+        };
+
+        let result = server.wardwell_write(Parameters(params("Sentry Bot", None))).await;
+        assert!(result.contains("resolved to existing project 'sentry-bot'"), "{result}");
+        assert!(tmp.join("work/sentry-bot/history.jsonl").exists());
+        assert!(!tmp.join("work/Sentry Bot").exists());
+
+        let result = server.wardwell_write(Parameters(params("sentry-bott", None))).await;
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["needs_confirmation"], true);
+        assert_eq!(parsed["suggested_project"], "sentry-bot");
+        assert!(!tmp.join("work/sentry-bott").exists());
+
+        server.wardwell_write(Parameters(params("sentry-bott", Some(true)))).await;
+        assert!(tmp.join("work/sentry-bott/history.jsonl").exists());
+
+        // Folders that already forked are listed, not picked between
+        std::fs::create_dir_all(tmp.join("work").join("Sentry_Bot")).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("Sentry Bot", None))).await).unwrap();
+        assert_eq!(parsed["candidates"], serde_json::json!(["Sentry_Bot", "sentry-bot"]));
+
+        // A new name that an archived project already has asks first
+        std::fs::create_dir_all(tmp.join("work/archive/billing")).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("Billing", None))).await).unwrap();
+        assert_eq!(parsed["archived_project"], "billing");
+        assert!(!tmp.join("work/Billing").exists());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn write_infers_project_from_cwd() {
+        let tmp = std::env::temp_dir().join("wardwell_test_project_cwd");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work").join("sentry-bot")).unwrap();
+
+        let server = make_test_server(&tmp);
+        *server.registry.write().await = DomainRegistry::from_domains(vec![crate::domain::model::Domain {
+            name: crate::config::types::DomainName::new("work").unwrap(),
+            paths: vec![crate::config::types::PathGlob::new("/srv/code/work/*").unwrap()],
+            aliases: HashMap::new(),
+            can_read: Vec::new(),
+            seed_template: None,
+        }]);
+        let params = |cwd: &str| WriteParams {
+            action: "append_history".to_string(),
+            domain: "work".to_string(),
+            title: Some("Triage".to_string()),
+            body: Some("Looked at alerts".to_string()),
+            cwd: Some(cwd.to_string()),
+            ..Default::default()
+        };
+
+        // A subdirectory of the repo still finds the project, via its slugged folder name
+        let result = server.wardwell_write(Parameters(params("/srv/code/work/Sentry Bot/src"))).await;
+        assert!(result.contains("inferred from working directory"), "{result}");
+        assert!(tmp.join("work/sentry-bot/history.jsonl").exists());
+
+        // Outside the domain's paths, or no matching project: the old dead-end, explained
+        let result = server.wardwell_write(Parameters(params("/srv/code/personal/sentry-bot"))).await;
+        assert!(result.contains("no cwd matched a project"), "{result}");
+        let result = server.wardwell_write(Parameters(params("/srv/code/work/unknown"))).await;
+        assert!(result.contains("no cwd matched a project"), "{result}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn require_read_refuses_sync_until_the_project_is_read() {
+        let tmp = std::env::temp_dir().join("wardwell_test_require_read");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        std::fs::write(tmp.join("work/api/current_state.md"), "---\ntype: project\nstatus: active\n---\n\n## Focus\nShip it\n").unwrap();
+
+        let mut server = make_test_server(&tmp);
+        Arc::get_mut(&mut server.config).unwrap().write_policy = WritePolicy::RequireRead;
+        let params = |action: &str, project: &str| WriteParams {
+            action: action.to_string(),
+            domain: "work".to_string(),
+            project: Some(project.to_string()),
+            title: Some("Cut release".to_string()),
+            body: Some("Tagged 1.2".to_string()),
+            status: Some("active".to_string()),
+            focus: Some("Release 1.2".to_string()),
+            next_action: Some("Announce it".to_string()),
+            commit_message: Some("release: 1.2".to_string()),
+            ..Default::default()
+        };
+
+        let refused: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("sync", "api"))).await).unwrap();
+        assert_eq!(refused["read_required"], true);
+        assert!(refused["error"].as_str().unwrap().contains("write_policy is require_read"), "{refused}");
+        assert_eq!(refused["read_first"]["path"], "work/api/current_state.md");
+        assert!(std::fs::read_to_string(tmp.join("work/api/current_state.md")).unwrap().contains("Ship it"));
+
+        // History appends, and projects with no state to read yet, are only warned about
+        let result = server.wardwell_write(Parameters(params("append_history", "api"))).await;
+        assert!(result.contains("was not read or searched"), "{result}");
+        let result = server.wardwell_write(Parameters(params("decide", "fresh"))).await;
+        assert!(!result.contains("read_required"), "{result}");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&result).unwrap()["path"], "work/fresh/decisions.md");
+
+        let read = SearchParams {
+            action: "read".to_string(),
+            path: Some("work/api/current_state.md".to_string()),
+            ..Default::default()
+        };
+        server.wardwell_search(Parameters(read)).await;
+        let result = server.wardwell_write(Parameters(params("sync", "api"))).await;
+        assert!(result.contains("\"synced\":true"), "{result}");
+        let synced: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(synced["files_written"], serde_json::json!(["work/api/current_state.md", "work/api/history.jsonl"]));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn approve_moves_pending_decisions_into_decisions_md() {
+        let tmp = std::env::temp_dir().join("wardwell_test_approve");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let project_dir = tmp.join("work/api");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let summary = "## Decisions\n- Kept retries in the client.\n- Chose SQLite.\n- Dropped the cache.\n";
+        crate::daemon::decisions::propose(&project_dir, "s1", "2026-03-02", summary).unwrap();
+
+        let server = make_test_server(&tmp);
+        let approve = |ids: Option<Vec<String>>, source: Option<&str>| WriteParams {
+            action: "approve".to_string(),
+            domain: "work".to_string(),
+            project: Some("api".to_string()),
+            source: source.map(String::from),
+            ids,
+            ..Default::default()
+        };
+        let listed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(approve(None, None))).await).unwrap();
+        assert_eq!(listed["total"], 3);
+        let ids: Vec<String> = listed["pending"].as_array().unwrap().iter().map(|d| d["id"].as_str().unwrap().to_string()).collect();
+
+        let approved: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(approve(Some(ids[..2].to_vec()), None))).await).unwrap();
+        assert_eq!(approved["approved"], serde_json::json!(["Kept retries in the client", "Chose SQLite"]));
+        assert_eq!(approved["remaining"], 1);
+        let recorded = std::fs::read_to_string(project_dir.join("decisions.md")).unwrap();
+        let sqlite = recorded.find("## 2026-03-02 — Chose SQLite").unwrap();
+        assert!(sqlite < recorded.find("## 2026-03-02 — Kept retries in the client").unwrap(), "{recorded}");
+
+        let unknown = server.wardwell_write(Parameters(approve(Some(vec![ids[0].clone()]), None))).await;
+        assert!(unknown.contains("No pending decision"), "{unknown}");
+
+        let dismissed: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(approve(Some(vec![ids[2].clone()]), Some("dismissed")))).await).unwrap();
+        assert_eq!(dismissed["dismissed"], serde_json::json!(["Dropped the cache"]));
+        assert!(!std::fs::read_to_string(project_dir.join("decisions.md")).unwrap().contains("Dropped the cache"));
+        assert!(!project_dir.join(crate::daemon::decisions::QUEUE).exists());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn journal_appends_to_todays_daily_note_and_searches_it() {
+        let tmp = std::env::temp_dir().join("wardwell_test_journal");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let write = |domain: &str, project: Option<&str>, body: &str| WriteParams {
+            action: "journal".to_string(),
+            domain: domain.to_string(),
+            project: project.map(String::from),
+            body: Some(body.to_string()),
+            ..Default::default()
+        };
+        let first: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(write("work", Some("API"), "Paged on 502s"))).await).unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(first["path"], format!("daily/{today}.md"));
+        assert_eq!(first["created"], true);
+        server.wardwell_write(Parameters(write("personal", None, "Booked flights"))).await;
+        let note = std::fs::read_to_string(tmp.join(format!("daily/{today}.md"))).unwrap();
+        assert_eq!(note.lines().count(), 2);
+        assert!(note.contains(" #work/api Paged on 502s\n"), "{note}");
+
+        let search = |domain: Option<&str>| SearchParams {
+            action: "journal".to_string(),
+            domain: domain.map(String::from),
+            ..Default::default()
+        };
+        let all: serde_json::Value = serde_json::from_str(&server.wardwell_search(Parameters(search(None))).await).unwrap();
+        assert_eq!(all["total"], 2);
+        let work: serde_json::Value = serde_json::from_str(&server.wardwell_search(Parameters(search(Some("work")))).await).unwrap();
+        assert_eq!(work["entries"][0]["tag"], "work/api");
+        assert_eq!(work["entries"].as_array().unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn stage_assembles_parts_into_one_file() {
+        let tmp = std::env::temp_dir().join("wardwell_test_stage");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work").join("api")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |staging_id: Option<&str>, part: Option<u32>, body: Option<&str>, finalize: bool| WriteParams {
+            action: "stage".to_string(),
+            domain: "work".to_string(),
+            project: Some("api".to_string()),
+            body: body.map(str::to_string),
+            path: finalize.then(|| "research/dump.md".to_string()),
+            staging_id: staging_id.map(str::to_string),
+            part,
+            finalize: Some(finalize),
+            ..Default::default()
+        };
+
+        let first: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params(None, None, Some("# Dump\n"), false))).await).unwrap();
+        let id = first["staging_id"].as_str().unwrap().to_string();
+
+        // Parts may arrive out of order; a gap blocks finalizing until it is filled
+        let result = server.wardwell_write(Parameters(params(Some(&id), Some(3), Some("three\n"), false))).await;
+        assert!(result.contains("\"missing_parts\":[2]"), "{result}");
+        let result = server.wardwell_write(Parameters(params(Some(&id), None, None, true))).await;
+        assert!(result.contains("never staged"), "{result}");
+        server.wardwell_write(Parameters(params(Some(&id), Some(2), Some("two\n"), false))).await;
+
+        let result = server.wardwell_write(Parameters(params(Some(&id), None, None, true))).await;
+        assert!(result.contains("\"written\":true"), "{result}");
+        assert_eq!(std::fs::read_to_string(tmp.join("work/api/research/dump.md")).unwrap(), "# Dump\ntwo\nthree\n");
+
+        // Finalizing discards the parts
+        let result = server.wardwell_write(Parameters(params(Some(&id), None, Some("more"), false))).await;
+        assert!(result.contains("Unknown or expired staging_id"), "{result}");
+
+        // Part numbers are capped, including the next one picked automatically
+        let result = server.wardwell_write(Parameters(params(None, Some(u32::MAX), Some("x"), false))).await;
+        assert!(result.contains("at most 1000"), "{result}");
+        let last: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params(None, Some(1000), Some("x"), false))).await).unwrap();
+        assert_eq!(last["missing_parts"].as_array().map(Vec::len), Some(999));
+        let result = server.wardwell_write(Parameters(params(last["staging_id"].as_str(), None, Some("y"), false))).await;
+        assert!(result.contains("highest allowed"), "{result}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn archive_moves_the_project_and_relinks_related_references() {
+        let tmp = std::env::temp_dir().join("wardwell_test_archive_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let note = |rel: &str, content: &str| {
+            let path = tmp.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        note("work/api/INDEX.md", "---\ntype: project\nrelated: [auth.md, ../web/INDEX.md]\n---\n# API\n");
+        note("work/api/auth.md", "---\ntype: reference\n---\n# Auth\n");
+        note("work/api/history.jsonl", &(make_history_jsonl(&[("2026-02-20", "Shipped login", "active", "auth")]) + "\n"));
+        note("work/web/INDEX.md", "---\ntype: project\nrelated:\n  - work/api/auth\n  - \"[[auth]]\"\n---\n# Web\n");
+
+        let server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+        let params = |action: &str| WriteParams {
+            action: action.to_string(),
+            domain: "work".to_string(),
+            project: Some("api".to_string()),
+            ..Default::default()
+        };
+        let related = |rel: &str| {
+            let content = std::fs::read_to_string(tmp.join(rel)).unwrap();
+            crate::vault::frontmatter::parse_frontmatter(&content).unwrap().0.related
+        };
+
+        let result: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("archive"))).await).unwrap();
+        assert_eq!(result["archived"], true, "{result}");
+        assert_eq!(result["to"], "work/archive/api");
+        assert!(!tmp.join("work/api").exists() && tmp.join("work/archive/api/auth.md").exists());
+
+        // Paths into the project follow it; paths out of it are made to still resolve; bare
+        // names and paths relative within the project are left alone
+        assert_eq!(related("work/web/INDEX.md"), vec!["work/archive/api/auth", "[[auth]]"]);
+        assert_eq!(related("work/archive/api/INDEX.md"), vec!["auth.md", "work/web/INDEX.md"]);
+
+        assert!(server.index.paths_under("work/api").unwrap().is_empty());
+        assert!(server.index.get_body("work/archive/api/auth.md").unwrap().is_some());
+        let history = std::fs::read_to_string(tmp.join("work/archive/api/history.jsonl")).unwrap();
+        assert!(history.contains("Moved work/api to work/archive/api."), "{history}");
+
+        // Archived history is skipped by default and included on request
+        let titles = |skip: bool| collect_history_entries(&tmp, None, None, skip, &[], SymlinkPolicy::Follow, Default::default())
+            .into_iter().map(|e| e.title).collect::<Vec<_>>();
+        assert!(!titles(true).contains(&"Shipped login".to_string()));
+        assert!(titles(false).contains(&"Shipped login".to_string()));
+
+        // And back again
+        let result = server.wardwell_write(Parameters(params("unarchive"))).await;
+        assert!(result.contains("\"unarchived\":true"), "{result}");
+        assert!(tmp.join("work/api/auth.md").exists());
+        assert_eq!(related("work/web/INDEX.md"), vec!["work/api/auth", "[[auth]]"]);
+        let result = server.wardwell_write(Parameters(params("unarchive"))).await;
+        assert!(result.contains("No project at 'work/archive/api'"), "{result}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn rename_stays_inside_the_vault() {
+        let tmp = std::env::temp_dir().join("wardwell_test_rename_escape");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        std::fs::create_dir_all(tmp.join(".obsidian")).unwrap();
+        std::fs::write(tmp.join("work/api/current_state.md"), "---\ntype: project\nstatus: active\n---\n# API\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |new_name: &str| WriteParams {
+            action: "rename".to_string(),
+            domain: "work".to_string(),
+            project: Some("api".to_string()),
+            new_name: Some(new_name.to_string()),
+            ..Default::default()
+        };
+        for (new_name, error) in [
+            ("../wardwell_rename_escaped", "No domain folder '..'"),
+            ("./x", "No domain folder '.'"),
+            (".obsidian/x", "No domain folder '.obsidian'"),
+            ("work/..", "'..' can't be a project folder name"),
+            ("..", "'..' can't be a project folder name"),
+        ] {
+            let out = server.wardwell_write(Parameters(params(new_name))).await;
+            assert!(out.contains(error), "{new_name}: {out}");
+        }
+        assert!(tmp.join("work/api/current_state.md").exists());
+        assert!(!tmp.parent().unwrap().join("wardwell_rename_escaped").exists());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn rename_moves_the_project_and_keeps_the_old_name_as_an_alias() {
+        let tmp = std::env::temp_dir().join("wardwell_test_rename_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let note = |rel: &str, content: &str| {
+            let path = tmp.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        note("work/api/current_state.md", "---\ntype: project\nstatus: active\n---\n# API\n");
+        note("work/api/auth.md", "---\ntype: reference\n---\n# Auth\n");
+        note("work/web/INDEX.md", "---\ntype: project\nrelated: [work/api/auth.md]\n---\n# Web\n");
+        std::fs::create_dir_all(tmp.join("personal")).unwrap();
+
+        let server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+        let params = |project: &str, new_name: Option<&str>, dry_run: bool| WriteParams {
+            action: "rename".to_string(),
+            domain: "work".to_string(),
+            project: Some(project.to_string()),
+            dry_run: Some(dry_run),
+            new_name: new_name.map(String::from),
+            ..Default::default()
+        };
+
+        let missing = server.wardwell_write(Parameters(params("api", None, false))).await;
+        assert!(missing.contains("'new_name' is required"), "{missing}");
+        let taken = server.wardwell_write(Parameters(params("api", Some("web"), false))).await;
+        assert!(taken.contains("'work/web' already exists"), "{taken}");
+        let planned = server.wardwell_write(Parameters(params("api", Some("backend"), true))).await;
+        assert!(planned.contains("\"dry_run\":true") && tmp.join("work/api").exists(), "{planned}");
+
+        let result: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("api", Some("backend"), false))).await).unwrap();
+        assert_eq!(result["renamed"], true, "{result}");
+        assert!(!tmp.join("work/api").exists() && tmp.join("work/backend/auth.md").exists());
+        let web = std::fs::read_to_string(tmp.join("work/web/INDEX.md")).unwrap();
+        assert_eq!(crate::vault::frontmatter::parse_frontmatter(&web).unwrap().0.related, vec!["work/backend/auth.md"]);
+        assert!(server.index.paths_under("work/api").unwrap().is_empty());
+        assert!(server.index.get_body("work/backend/auth.md").unwrap().is_some());
+        let history = std::fs::read_to_string(tmp.join("work/backend/history.jsonl")).unwrap();
+        assert!(history.contains("Renamed project") && history.contains("Moved work/api to work/backend."), "{history}");
+
+        // The old name still finds the project
+        assert_eq!(crate::vault::project::resolve_project(&tmp.join("work"), "api"), ProjectMatch::Alias("backend".to_string()));
+
+        let result: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("backend", Some("personal/backend"), false))).await).unwrap();
+        assert_eq!(result["to"], "personal/backend", "{result}");
+        assert!(tmp.join("personal/backend/current_state.md").exists());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn delete_needs_a_token_and_moves_the_file_to_the_trash() {
+        let tmp = std::env::temp_dir().join("wardwell_test_delete");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work").join("api")).unwrap();
+        std::fs::write(tmp.join("work/api/todo.jsonl"), "{\"title\":\"item\"}\n").unwrap();
+        std::fs::write(tmp.join("work/api/history.jsonl"), "{\"title\":\"entry\"}\n").unwrap();
+        std::fs::write(tmp.join("work/api/plan.md"), "# Plan\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |action: &str, list: Option<&str>, path: Option<&str>, body: Option<&str>, token: Option<&str>| WriteParams {
+            action: action.to_string(),
+            domain: "work".to_string(),
+            project: Some("api".to_string()),
+            list: list.map(str::to_string),
+            body: body.map(str::to_string),
+            path: path.map(str::to_string),
+            confirm_token: token.map(str::to_string),
+            ..Default::default()
+        };
+
+        // Notes this session didn't write, and project logs, are off limits
+        let result = server.wardwell_write(Parameters(params("delete", None, Some("plan.md"), None, None))).await;
+        assert!(result.contains("wasn't written by write_file or stage"), "{result}");
+        let result = server.wardwell_write(Parameters(params("delete", Some("history"), None, None, None))).await;
+        assert!(result.contains("project log"), "{result}");
+
+        // The first call only issues a token
+        let first: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("delete", Some("todo"), None, None, None))).await).unwrap();
+        assert_eq!(first["deleted"], false);
+        let token = first["confirm_token"].as_str().unwrap().to_string();
+        assert!(tmp.join("work/api/todo.jsonl").exists());
+
+        let result = server.wardwell_write(Parameters(params("delete", Some("todo"), None, None, Some(&token)))).await;
+        assert!(result.contains("\"deleted\":true"), "{result}");
+        assert!(!tmp.join("work/api/todo.jsonl").exists());
+        assert!(tmp.join(".trash/work/api/todo.jsonl").exists());
+
+        // Tokens are single-use
+        std::fs::write(tmp.join("work/api/todo.jsonl"), "{\"title\":\"again\"}\n").unwrap();
+        let result = server.wardwell_write(Parameters(params("delete", Some("todo"), None, None, Some(&token)))).await;
+        assert!(result.contains("already-used"), "{result}");
+
+        // A note written this session can go, and leaves the index with it
+        server.wardwell_write(Parameters(params("write_file", None, Some("notes/scratch.md"), Some("# Scratch\n"), None))).await;
+        assert!(server.index.get_body("work/api/notes/scratch.md").unwrap().is_some());
+        let first: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("delete", None, Some("notes/scratch.md"), None, None))).await).unwrap();
+        let token = first["confirm_token"].as_str().unwrap().to_string();
+        // Rewritten since the token was issued
+        server.wardwell_write(Parameters(params("write_file", None, Some("notes/scratch.md"), Some("# Scratch v2\n"), None))).await;
+        let result = server.wardwell_write(Parameters(params("delete", None, Some("notes/scratch.md"), None, Some(&token)))).await;
+        assert!(result.contains("changed since"), "{result}");
+        let first: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("delete", None, Some("notes/scratch.md"), None, None))).await).unwrap();
+        let token = first["confirm_token"].as_str().unwrap().to_string();
+        let result = server.wardwell_write(Parameters(params("delete", None, Some("notes/scratch.md"), None, Some(&token)))).await;
+        assert!(result.contains("\"deleted\":true"), "{result}");
+        assert!(tmp.join(".trash/work/api/notes/scratch.md").exists());
+        assert!(server.index.get_body("work/api/notes/scratch.md").unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_each_change_and_writes_nothing() {
+        let tmp = std::env::temp_dir().join("wardwell_test_dry_run");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work").join("api")).unwrap();
+        let state = "---\nstatus: active\ntype: project\n---\n\n# api\n\n## Focus\n\nOld focus\n";
+        std::fs::write(tmp.join("work/api/current_state.md"), state).unwrap();
+        std::fs::write(tmp.join("work/api/todo.jsonl"), "{\"title\":\"item\"}\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |action: &str, list: Option<&str>| WriteParams {
+            action: action.to_string(),
+            domain: "work".to_string(),
+            project: Some("api".to_string()),
+            list: list.map(str::to_string),
+            title: Some("Check".to_string()),
+            status: Some("active".to_string()),
+            focus: Some("New focus".to_string()),
+            next_action: Some("Ship it".to_string()),
+            commit_message: Some("Refocus".to_string()),
+            dry_run: Some(true),
+            ..Default::default()
+        };
+
+        let sync: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("sync", None))).await).unwrap();
+        assert_eq!(sync["dry_run"], true);
+        let changes = sync["changes"].as_array().unwrap();
+        assert_eq!(changes.len(), 2, "{sync}");
+        assert_eq!(changes[0]["path"], "work/api/current_state.md");
+        assert!(changes[0]["content"].as_str().unwrap().contains("New focus"));
+        let diff = changes[0]["diff"].as_str().unwrap();
+        assert!(diff.contains("-Old focus") && diff.contains("+New focus"), "{diff}");
+        assert_eq!(changes[1]["path"], "work/api/history.jsonl");
+        assert_eq!(changes[1]["exists"], false);
+        assert!(changes[1]["append"].as_str().unwrap().starts_with("{\"_schema\": \"history\""));
+        assert_eq!(sync["state_changes"]["focus"]["to"], "New focus");
+        assert_eq!(std::fs::read_to_string(tmp.join("work/api/current_state.md")).unwrap(), state);
+        assert!(!tmp.join("work/api/history.jsonl").exists());
+
+        // Appends describe the new line; deletes where the file would go, without a token
+        let append: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("append", Some("todo")))).await).unwrap();
+        let change = &append["changes"][0];
+        assert_eq!(change["exists"], true);
+        assert!(change["diff"].as_str().unwrap().contains("+{\"body\":\"\",\"date\""), "{change}");
+        assert_eq!(std::fs::read_to_string(tmp.join("work/api/todo.jsonl")).unwrap(), "{\"title\":\"item\"}\n");
+
+        let delete: serde_json::Value = serde_json::from_str(&server.wardwell_write(Parameters(params("delete", Some("todo")))).await).unwrap();
+        assert_eq!(delete["changes"][0]["move_to"], ".trash/work/api/todo.jsonl");
+        assert!(delete.get("confirm_token").is_none());
+        assert!(tmp.join("work/api/todo.jsonl").exists());
+
+        // Nothing was written, so the real call isn't taken for a repeat
+        let mut real = params("append", Some("todo"));
+        real.dry_run = None;
+        let result = server.wardwell_write(Parameters(real)).await;
+        assert!(!result.contains("deduplicated"), "{result}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn write_slugifies_new_unicode_project_names() {
+        let tmp = std::env::temp_dir().join("wardwell_test_project_unicode");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |project: &str| WriteParams {
+            action: "append_history".to_string(),
+            domain: "work".to_string(),
+            project: Some(project.to_string()),
+            title: Some("Kickoff".to_string()),
+            body: Some("Menu draft".to_string()),
+            ..Default::default()
+        };
+
+        let result = server.wardwell_write(Parameters(params("Café Menü"))).await;
+        assert!(result.contains("stored as 'cafe-menu'"), "{result}");
+        assert!(tmp.join("work/cafe-menu/history.jsonl").exists());
+
+        let result = server.wardwell_write(Parameters(params("Cafe\u{301} Menu\u{308}"))).await;
+        assert!(result.contains("resolved to existing project 'cafe-menu'"), "{result}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_reports_diff_and_field_changes() {
+        let tmp = std::env::temp_dir().join("wardwell_test_sync_diff");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("personal").join("test-proj")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |status: &str, next: &str| WriteParams {
+            action: "sync".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            status: Some(status.to_string()),
+            focus: Some("Auth flow".to_string()),
+            next_action: Some(next.to_string()),
+            blockers: Some(vec!["Waiting on keys".to_string()]),
+            commit_message: Some(format!("{status}: {next}")),
+            ..Default::default()
+        };
+
+        server.action_sync(&params("active", "Write tests"), "test-proj", None, false);
+        let (result, synced) = server.action_sync(&params("blocked", "Deploy"), "test-proj", None, false);
+        assert!(synced);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(parsed["changes"]["status"]["from"], "active");
+        assert_eq!(parsed["changes"]["status"]["to"], "blocked");
+        assert_eq!(parsed["changes"]["next_action"]["to"], "Deploy");
+        assert!(parsed["changes"].get("focus").is_none());
+        assert_eq!(parsed["changes"]["sections"], serde_json::json!(["Commit Message"]));
+        let diff = parsed["diff"].as_str().unwrap();
+        assert!(diff.contains("-Write tests\n+Deploy\n"), "{diff}");
+        assert!(diff.contains("+status: blocked"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_keeps_hand_added_sections() {
+        let tmp = std::env::temp_dir().join("wardwell_test_sync_sections");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let project_dir = tmp.join("personal").join("test-proj");
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |blockers: Option<Vec<String>>| WriteParams {
+            action: "sync".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            status: Some("active".to_string()),
+            focus: Some("Auth flow".to_string()),
+            next_action: Some("Write tests".to_string()),
+            blockers,
+            commit_message: Some("Progress".to_string()),
+            ..Default::default()
+        };
+
+        server.action_sync(&params(Some(vec!["Keys".to_string()])), "test-proj", None, false);
+        let state_path = project_dir.join("current_state.md");
+        let state = std::fs::read_to_string(&state_path).unwrap()
+            .replace("\n## Next Action\n", "\n## Links\n- [Spec](https://example.com/spec)\n\n## Next Action\n");
+        std::fs::write(&state_path, format!("{state}\n## People\n- Sam (reviewer)\n").replacen("---\n", "---\npriority: high\n", 1)).unwrap();
+
+        server.action_sync(&params(None), "test-proj", None, false);
+        let state = std::fs::read_to_string(&state_path).unwrap();
+        let at = |h: &str| state.find(&format!("## {h}\n")).expect(h);
+        assert!(at("Focus") < at("Links") && at("Links") < at("Next Action"), "{state}");
+        assert!(at("Commit Message") < at("People"), "{state}");
+        assert!(state.contains("- [Spec](https://example.com/spec)\n") && state.contains("- Sam (reviewer)\n"));
+        assert!(!state.contains("## Blockers"), "sync-owned sections come from the call: {state}");
+        assert!(state.starts_with("---\npriority: high\nchat_name: test-proj\n"), "custom frontmatter is kept: {state}");
+
+        // A per-project order in INDEX.md moves them
+        std::fs::write(project_dir.join("INDEX.md"), "---\nstate_sections: [People, Focus, Next Action, Commit Message]\n---\n").unwrap();
+        server.action_sync(&params(None), "test-proj", None, false);
+        let state = std::fs::read_to_string(&state_path).unwrap();
+        let at = |h: &str| state.find(&format!("## {h}\n")).expect(h);
+        assert!(at("People") < at("Focus") && at("Focus") < at("Links"), "{state}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sync_normalizes_and_validates_status() {
+        let tmp = std::env::temp_dir().join("wardwell_test_sync_status");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("personal").join("test-proj")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |status: &str| WriteParams {
+            action: "sync".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            status: Some(status.to_string()),
+            focus: Some("Launch".to_string()),
+            next_action: Some("Celebrate".to_string()),
+            commit_message: Some("Shipped".to_string()),
+            ..Default::default()
+        };
+
+        let (result, synced) = server.action_sync(&params("sideways"), "test-proj", None, false);
+        assert!(!synced);
+        let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(result["error"].as_str().unwrap().contains("Unknown status 'sideways'"), "{result}");
+        assert!(!tmp.join("personal/test-proj/current_state.md").exists());
+
+        server.action_sync(&params("Done"), "test-proj", None, false);
+        let state = std::fs::read_to_string(tmp.join("personal/test-proj/current_state.md")).unwrap();
+        assert!(state.contains("\nstatus: completed\n"), "{state}");
+        let history = std::fs::read_to_string(tmp.join("personal/test-proj/history.jsonl")).unwrap();
+        assert!(history.contains("\"status\":\"completed\""), "{history}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn identical_appends_within_window_are_deduplicated() {
+        let tmp = std::env::temp_dir().join("wardwell_test_append_dedup");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("personal").join("test-proj")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |title: &str| WriteParams {
+            action: "append_history".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            title: Some(title.to_string()),
+            body: Some("Same body".to_string()),
+            ..Default::default()
+        };
+
+        let first: serde_json::Value = serde_json::from_str(&server.action_append_history(&params("Ship it"), "test-proj", None)).unwrap();
+        assert!(first.get("deduplicated").is_none());
+        let second: serde_json::Value = serde_json::from_str(&server.action_append_history(&params("Ship it"), "test-proj", None)).unwrap();
+        assert_eq!(second["deduplicated"], true);
+        assert_eq!(second["id"], first["id"], "a deduplicated append answers with the original entry's ID");
+        server.action_append_history(&params("Something else"), "test-proj", None);
+
+        let content = std::fs::read_to_string(tmp.join("personal/test-proj/history.jsonl")).unwrap();
+        assert_eq!(content.matches("Ship it").count(), 1);
+        assert_eq!(content.matches("Something else").count(), 1);
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn appended_entries_are_found_by_id() {
+        let tmp = std::env::temp_dir().join("wardwell_test_entry_ids");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("personal").join("test-proj")).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = WriteParams {
+            action: "lesson".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            title: Some("Pin the toolchain".to_string()),
+            what_happened: Some("CI broke on a new release".to_string()),
+            root_cause: Some("Floating toolchain".to_string()),
+            prevention: Some("rust-toolchain.toml".to_string()),
+            ..Default::default()
+        };
+        let recorded: serde_json::Value = serde_json::from_str(&server.action_lesson(&params, "test-proj", None)).unwrap();
+        let id = recorded["id"].as_str().unwrap().to_string();
+        assert!(crate::vault::entry_id::is_id(&id), "{id}");
+        assert_eq!(recorded["path"], "personal/test-proj/lessons.jsonl");
+
+        let lookup = |query: &str| -> serde_json::Value {
+            let p = SearchParams {
+                action: "entry".to_string(),
+                query: Some(query.to_string()),
+                ..Default::default()
+            };
+            serde_json::from_str(&server.action_entry(&p)).unwrap()
+        };
+        let found = lookup(&id.to_lowercase());
+        assert_eq!(found["path"], "personal/test-proj/lessons.jsonl");
+        assert_eq!(found["entry"]["title"], "Pin the toolchain");
+        assert!(lookup("nope")["error"].as_str().unwrap().contains("not an entry ID"));
+        assert!(lookup(&crate::vault::entry_id::new_id())["error"].as_str().unwrap().contains("No entry"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn sessions_around_merges_history_in_the_window() {
+        let tmp = std::env::temp_dir().join("wardwell_test_sessions_around");
+        let _ = std::fs::remove_dir_all(&tmp);
+        for (project, dates) in [("api", ["2026-03-01", "2026-03-09"]), ("site", ["2026-03-06", "2026-03-20"])] {
+            let dir = tmp.join("around-test").join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            let lines: Vec<String> = dates.iter()
+                .map(|d| format!(r#"{{"date":"{d}T12:00:00Z","title":"{project} on {d}","status":"","focus":"","next_action":"","commit":"","body":""}}"#))
+                .collect();
+            std::fs::write(dir.join("history.jsonl"), format!("{{\"_schema\": \"history\", \"_version\": \"1.0\"}}\n{}\n", lines.join("\n"))).unwrap();
+        }
+
+        let server = make_test_server(&tmp);
+        let params = SearchParams {
+            action: "sessions_around".to_string(),
+            domain: Some("around-test".to_string()),
+            from: Some("2026-03-07".to_string()),
+            ..Default::default()
+        };
+        let out: serde_json::Value = serde_json::from_str(&server.action_sessions_around(&params)).unwrap();
+        assert_eq!(out["window"], serde_json::json!({"start": "2026-03-04", "end": "2026-03-10"}));
+        let titles: Vec<&str> = out["entries"].as_array().unwrap().iter()
+            .filter(|e| e["type"] == "history")
+            .map(|e| e["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, vec!["site on 2026-03-06", "api on 2026-03-09"]);
+
+        let bad = SearchParams { from: Some("last tuesday".to_string()), ..params };
+        assert!(server.action_sessions_around(&bad).contains("must be a date"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn lists_are_enumerated_and_read_back_newest_first() {
+        let tmp = std::env::temp_dir().join("wardwell_test_list_read");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let dir = tmp.join("work").join("api");
+        std::fs::create_dir_all(&dir).unwrap();
+        let ideas: Vec<String> = (1..=5)
+            .map(|n| format!(r#"{{"id":"ID{n}","date":"2026-03-0{n}T12:00:00Z","title":"Idea {n}","body":""}}"#))
+            .collect();
+        std::fs::write(dir.join("future-ideas.jsonl"), format!("{{\"_schema\": \"future-ideas\", \"_version\": \"1.0\"}}\n{}\n", ideas.join("\n"))).unwrap();
+        std::fs::write(dir.join("history.jsonl"), "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |action: &str, list: Option<&str>, start: Option<usize>| SearchParams {
+            action: action.to_string(),
+            domain: Some("work".to_string()),
+            project: Some("api".to_string()),
+            limit: Some(2),
+            start,
+            list: list.map(String::from),
+            ..Default::default()
+        };
+        let out: serde_json::Value = serde_json::from_str(&server.action_lists(&params("lists", None, None))).unwrap();
+        assert_eq!(out["count"], 1, "{out}");
+        assert_eq!(out["lists"][0]["name"], "future-ideas");
+        assert_eq!((out["lists"][0]["entries"].as_u64(), out["lists"][0]["last_updated"].as_str()), (Some(5), Some("2026-03-05T12:00:00Z")));
+
+        let read = |list: &str, start: Option<usize>| {
+            serde_json::from_str::<serde_json::Value>(&server.action_list_read(&params("list_read", Some(list), start))).unwrap()
+        };
+        let titles = |page: &serde_json::Value| page["entries"].as_array().unwrap().iter().map(|e| e["title"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+        let first = read("future-ideas", None);
+        assert_eq!(titles(&first), ["Idea 5", "Idea 4"]);
+        assert_eq!((first["total"].as_u64(), first["next_start"].as_u64()), (Some(5), Some(2)));
+        let last = read("future-ideas", Some(4));
+        assert_eq!(titles(&last), ["Idea 1"]);
+        assert!(last.get("next_start").is_none());
+
+        assert_eq!(read("nope", None)["existing_lists"], serde_json::json!(["future-ideas"]));
+        assert!(server.action_list_read(&params("list_read", Some("history"), None)).contains("built-in"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn state_serves_fresh_index_and_falls_back_to_disk() {
+        let tmp = std::env::temp_dir().join("wardwell_test_state_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let state = |focus: &str| format!("---\nstatus: active\nupdated: 2026-03-01\n---\n# x\n\n## Focus\n{focus}\n\n## Next Action\nShip it\n\n## Blockers\n- Waiting on keys\n\n## Commit Message\nmsg\n");
+        for project in ["api", "site"] {
+            std::fs::create_dir_all(tmp.join("state-test").join(project)).unwrap();
+            std::fs::write(tmp.join("state-test").join(project).join("current_state.md"), state(project)).unwrap();
+        }
+
+        let server = make_test_server(&tmp);
+        let api_path = tmp.join("state-test/api/current_state.md");
+        let vf = crate::vault::reader::read_file(&api_path).unwrap();
+        server.index.upsert(&vf, &tmp).unwrap();
+
+        let params = SearchParams {
+            action: "state".to_string(),
+            domain: Some("state-test".to_string()),
+            project: Some("api, site, gone".to_string()),
+            ..Default::default()
+        };
+        let out: serde_json::Value = serde_json::from_str(&server.action_state(&params)).unwrap();
+        let projects = out["projects"].as_array().unwrap();
+        assert_eq!(projects[0]["source"], "index");
+        assert_eq!(projects[0]["focus"], "api");
+        assert_eq!(projects[0]["next_action"], "Ship it");
+        assert_eq!(projects[0]["blockers"], serde_json::json!(["Waiting on keys"]));
+        assert_eq!(projects[0]["updated"], "2026-03-01");
+        assert_eq!(projects[1]["source"], "file");
+        assert!(projects[2]["error"].as_str().unwrap().contains("No current_state.md"));
+
+        // An edit after indexing is read from disk
+        std::fs::write(&api_path, state("api v2")).unwrap();
+        let later = std::time::SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&api_path).unwrap().set_modified(later).unwrap();
+        let one = SearchParams { project: Some("state-test/api".to_string()), domain: None, ..params };
+        let out: serde_json::Value = serde_json::from_str(&server.action_state(&one)).unwrap();
+        assert_eq!(out["projects"][0]["source"], "file");
+        assert_eq!(out["projects"][0]["focus"], "api v2");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn search_rejects_unknown_modes() {
+        let tmp = std::env::temp_dir().join("wardwell_test_search_mode");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let server = make_test_server(&tmp);
+        let params = |mode: &str| SearchParams {
+            action: "search".to_string(),
+            query: Some("login pipeline".to_string()),
+            mode: Some(mode.to_string()),
+            ..Default::default()
+        };
+        assert!(server.action_search(&params("fuzzy")).contains("Unknown search mode"));
+        // No embedder in tests: the vector modes say so rather than silently searching keywords
+        for mode in ["semantic", "hybrid", "vector"] {
+            assert!(server.action_search(&params(mode)).contains("Semantic search unavailable"), "{mode}");
+        }
+        assert!(server.action_search(&params("keyword")).contains("results"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn links_action_returns_both_directions() {
+        let tmp = std::env::temp_dir().join("wardwell_test_links_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        std::fs::write(tmp.join("work/api/INDEX.md"), "---\ntype: project\n---\n# API\n\nSee [[auth]].\n").unwrap();
+        std::fs::write(tmp.join("work/api/auth.md"), "---\ntype: reference\nsummary: Auth notes\n---\n# Auth\n").unwrap();
+        let server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+
+        let params = |path: &str| SearchParams {
+            action: "links".to_string(),
+            path: Some(path.to_string()),
+            ..Default::default()
+        };
+        let out: serde_json::Value = serde_json::from_str(&server.action_links(&params("work/api/INDEX.md"))).unwrap();
+        assert_eq!(out["outbound"][0]["path"], "work/api/auth.md");
+        assert_eq!(out["outbound"][0]["summary"], "Auth notes");
+        assert_eq!(out["inbound_count"], 0);
+        let out: serde_json::Value = serde_json::from_str(&server.action_links(&params("/work/api/auth.md"))).unwrap();
+        assert_eq!(out["inbound"][0]["path"], "work/api/INDEX.md");
+        assert_eq!(out["inbound"][0]["snippet"], "See [[auth]].");
+        assert!(server.action_links(&params("work/api/gone.md")).contains("File not indexed"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn duplicates_action_lists_pairs_and_checks_the_threshold() {
+        let tmp = std::env::temp_dir().join("wardwell_test_duplicates_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let body = "Rotate the signing keys every quarter: generate a new pair, publish the public half, \
+            keep the old key valid for a week, and then revoke it in the key service.";
+        for rel in ["work/api/keys.md", "personal/notes/key-rotation.md"] {
+            std::fs::create_dir_all(tmp.join(rel).parent().unwrap()).unwrap();
+            std::fs::write(tmp.join(rel), format!("---\ntype: reference\n---\n{body}\n")).unwrap();
+        }
+        let server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+
+        let params = |threshold: Option<f64>, domain: Option<&str>| SearchParams {
+            action: "duplicates".to_string(),
+            domain: domain.map(str::to_string),
+            threshold,
+            ..Default::default()
+        };
+        let out: serde_json::Value = serde_json::from_str(&server.action_duplicates(&params(None, None))).unwrap();
+        assert_eq!(out["total"], 1);
+        assert_eq!(out["pairs"][0]["similarity"], 1.0);
+        let out: serde_json::Value = serde_json::from_str(&server.action_duplicates(&params(None, Some("work")))).unwrap();
+        assert_eq!(out["total"], 0);
+        assert!(server.action_duplicates(&params(Some(1.5), None)).contains("between 0 and 1"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn digests_are_written_per_domain_and_served_by_the_digest_action() {
+        let today = chrono::Local::now().date_naive().to_string();
+        let tmp = setup_test_vault("wardwell_test_digest", &[
+            ("work", "api", &make_history_jsonl(&[(&today, "Shipped login", "active", "done with the form")])),
+        ]);
+        std::fs::write(tmp.join("work/api/current_state.md"),
+            format!("---\ntype: project\nstatus: active\nupdated: {today}\n---\n## Focus\nAuth\n\n## Next Action\nAdd session tokens\n")).unwrap();
+        let mut server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+
+        let params = |domain: Option<&str>| SearchParams {
+            action: "digest".to_string(),
+            domain: domain.map(str::to_string),
+            ..Default::default()
+        };
+        assert!(server.action_digest(&params(None)).contains("No digests"));
+        assert!(server.write_digests().is_empty());
+
+        // work subscribes to its own folder; personal has no folder in the vault to write to
+        let sub = crate::config::loader::DigestSubscription { path: Some("_daily".to_string()), ..Default::default() };
+        Arc::get_mut(&mut server.config).unwrap().digest = Some(crate::config::loader::DigestConfig {
+            schedule: Default::default(),
+            domains: Vec::new(),
+            subscriptions: [("work".to_string(), sub.clone()), ("personal".to_string(), sub)].into_iter().collect(),
+        });
+        let written = server.write_digests();
+        assert_eq!(written.len(), 1, "{written:?}");
+        let path = written[0].as_ref().unwrap();
+        assert_eq!(path, &tmp.join(format!("work/_digests/_daily/{today}.md")));
+        assert_eq!(crate::vault::project::resolve_project(&tmp.join("work"), "_daily"), ProjectMatch::New, "digest folders aren't projects");
+        let md = std::fs::read_to_string(path).unwrap();
+        assert!(md.contains("- **api**: Add session tokens\n"), "{md}");
+        assert!(md.contains("Shipped login"), "{md}");
+        // Generating the digest isn't the session reading those projects
+        assert!(server.accessed_projects.lock().unwrap().is_empty());
+
+        let out: serde_json::Value = serde_json::from_str(&server.action_digest(&params(Some("work")))).unwrap();
+        assert_eq!(out["digests"][0]["date"], today.as_str());
+        assert_eq!(out["digests"][0]["content"], md.as_str());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn help_describes_enabled_tools_from_their_schemas() {
+        let tmp = std::env::temp_dir().join("wardwell_test_help_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let server = make_test_server(&tmp);
+        let out: serde_json::Value = serde_json::from_str(&server.action_help()).unwrap();
+        let tool = |name: &str| out["tools"].as_array().unwrap().iter().find(|t| t["tool"] == name).cloned();
+
+        let search = tool("wardwell_search").expect("search tool listed");
+        let actions: Vec<&str> = search["actions"].as_array().unwrap().iter().filter_map(|a| a["action"].as_str()).collect();
+        for action in ["search", "read", "entry", "diff", "state", "help"] {
+            assert!(actions.contains(&action), "{action} missing from {actions:?}");
+        }
+        assert!(search["params"]["query"].as_str().unwrap().starts_with("For search"));
+        let write = tool("wardwell_write").expect("write tool listed");
+        assert!(write["params"]["domain"].as_str().unwrap().starts_with("(required)"));
+        assert_eq!(out["quality_bar"].as_array().unwrap().len(), QUALITY_BAR.len());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn tag_action_edits_frontmatter_and_feeds_tag_search() {
+        let tmp = std::env::temp_dir().join("wardwell_test_tag_action");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let dir = tmp.join("personal/test-proj");
+        std::fs::create_dir_all(&dir).unwrap();
+        let body = "\n# test-proj\n\n## Focus\nLogin flow\n";
+        std::fs::write(dir.join("current_state.md"), format!("---\ntype: project\nstatus: active\ntags: [Backend, old]\n---\n{body}")).unwrap();
+        let server = make_test_server(&tmp);
+        // Indexed before tagging: the frontmatter-only edit has to replace this row
+        let vf = crate::vault::reader::read_file(&dir.join("current_state.md")).unwrap();
+        server.index.upsert(&vf, &tmp).unwrap();
+
+        let params = WriteParams {
+            action: "tag".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            tags: Some(vec!["backend".to_string(), "auth".to_string()]),
+            remove_tags: Some(vec!["OLD".to_string()]),
+            ..Default::default()
+        };
+        let out: serde_json::Value = serde_json::from_str(&server.action_tag(&params, "test-proj")).unwrap();
+        assert_eq!(out["tags"], serde_json::json!(["Backend", "auth"]));
+        assert_eq!(out["added"], serde_json::json!(["auth"]));
+        assert_eq!(out["removed"], serde_json::json!(["old"]));
+        let written = std::fs::read_to_string(dir.join("current_state.md")).unwrap();
+        assert!(written.ends_with(&format!("tags: [Backend, auth]\n---\n{body}")), "{written}");
+
+        let bad = WriteParams { tags: Some(vec!["a: b".to_string()]), remove_tags: None, ..params };
+        assert!(server.action_tag(&bad, "test-proj").contains("Invalid tag"));
+
+        let search = |action: &str, query: Option<&str>| SearchParams {
+            action: action.to_string(),
+            query: query.map(String::from),
+            ..Default::default()
+        };
+        let tags: serde_json::Value = serde_json::from_str(&server.action_tags(&search("tags", None))).unwrap();
+        assert_eq!(tags["tags"][0]["tag"], "auth");
+        assert_eq!(tags["tags"][0]["domains"]["personal"], 1);
+
+        let hits: serde_json::Value = serde_json::from_str(&server.action_search(&search("search", Some("tag:AUTH login")))).unwrap();
+        assert_eq!(hits["total"], 1);
+        let listed: serde_json::Value = serde_json::from_str(&server.action_search(&search("search", Some("tag:backend")))).unwrap();
+        assert_eq!(listed["total"], 1);
+        let none: serde_json::Value = serde_json::from_str(&server.action_search(&search("search", Some("tag:frontend")))).unwrap();
+        assert_eq!(none["total"], 0);
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn split_tag_filters_pulls_out_tag_words() {
+        assert_eq!(split_tag_filters("Tag:auth login tag: flow"), ("login tag: flow".to_string(), vec!["auth".to_string()]));
+        assert_eq!(split_tag_filters("tag:a tag:b"), (String::new(), vec!["a".to_string(), "b".to_string()]));
+    }
+
+    /// Replies with a fixed retrospective and counts the calls it gets.
+    struct CountingProvider(std::sync::atomic::AtomicUsize);
+
+    impl crate::daemon::summarizer::SummaryProvider for CountingProvider {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+        fn model(&self) -> &str {
+            "test-model"
+        }
+        fn complete<'a>(&'a self, _prompt: &'a str) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String, crate::daemon::summarizer::SummaryError>> + Send + 'a>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async { Ok("## Wins\n- work/api: shipped login\n\n## Grinds\n* work/web: eight sessions on the build\n\n## Dropped threads\n- none\n".to_string()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn retrospective_enrichment_is_parsed_and_cached_per_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let provider = CountingProvider(std::sync::atomic::AtomicUsize::new(0));
+        let history = "Period: 2026-03-01 to 2026-03-31\n\n- work/api (3 entries, active → completed): Shipped login";
+
+        let (sections, cached) = enrich_retrospective(history, tmp.path(), &provider).await.unwrap();
+        assert!(!cached);
+        assert_eq!(sections.wins, vec!["work/api: shipped login"]);
+        assert_eq!(sections.grinds, vec!["work/web: eight sessions on the build"]);
+        assert!(sections.dropped_threads.is_empty());
+
+        let (again, cached) = enrich_retrospective(history, tmp.path(), &provider).await.unwrap();
+        assert!(cached);
+        assert_eq!(again, sections);
+        assert_eq!(provider.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // New history in the period is a new cache entry
+        enrich_retrospective(&format!("{history}; Fixed logout"), tmp.path(), &provider).await.unwrap();
+        assert_eq!(provider.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        assert_eq!(parse_retro_sections("Here is a summary of the period."), None);
+    }
+
+    #[test]
+    fn action_summaries_split_on_sentence_openers() {
+        let summaries = action_summaries("get: fetch one (ticket_id required). list: filter items, e.g. by status. IMPORTANT for list: be brief. move_to: go.");
+        assert_eq!(summaries, vec![
+            ("get", "fetch one (ticket_id required)."),
+            ("list", "filter items, e.g. by status. IMPORTANT for list: be brief."),
+            ("move_to", "go."),
+        ]);
+    }
+
+    #[test]
+    fn append_list_rejects_reserved_names() {
+        let tmp = std::env::temp_dir().join("wardwell_test_append_reserved");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = WriteParams {
+            action: "append".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            list: Some("history".to_string()),
+            title: Some("Test".to_string()),
+            ..Default::default()
+        };
+        let result = server.action_append_list(&params, "test-proj", None);
+        assert!(result.contains("built-in list"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn append_list_existing_list_no_confirmation_needed() {
+        let tmp = std::env::temp_dir().join("wardwell_test_append_existing");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let project_dir = tmp.join("personal").join("test-proj");
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        // Pre-create the list
+        append_jsonl(&project_dir.join("bookmarks.jsonl"), "bookmarks", r#"{"title":"first"}"#, &Default::default()).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = WriteParams {
+            action: "append".to_string(),
+            domain: "personal".to_string(),
+            project: Some("test-proj".to_string()),
+            list: Some("bookmarks".to_string()),
+            // not needed — list exists
+            title: Some("Second entry".to_string()),
+            ..Default::default()
         };
         let result = server.action_append_list(&params, "test-proj", None);
         let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
@@ -3292,4 +8071,573 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(&tmp);
     }
+
+    #[test]
+    fn clipboard_challenge_issues_single_use_token() {
+        let tmp = std::env::temp_dir().join("wardwell_test_clipboard_token");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        let server = make_test_server(&tmp);
+        let result = server.clipboard_challenge("hello");
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["copied"], false);
+        assert_eq!(parsed["needs_confirmation"], true);
+        let token = parsed["confirm_token"].as_str().unwrap().to_string();
+
+        assert!(server.clipboard_confirm(&token, "hello").is_ok());
+        // Consumed — a replay is rejected
+        assert!(server.clipboard_confirm(&token, "hello").is_err());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn clipboard_confirm_rejects_different_content() {
+        let tmp = std::env::temp_dir().join("wardwell_test_clipboard_mismatch");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        let server = make_test_server(&tmp);
+        let result = server.clipboard_challenge("approved text");
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let token = parsed["confirm_token"].as_str().unwrap();
+
+        let err = server.clipboard_confirm(token, "something else").unwrap_err();
+        assert!(err.contains("different content"));
+        assert!(server.clipboard_confirm("not-a-token", "approved text").is_err());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn disabled_tools_are_not_registered() {
+        let tmp = std::env::temp_dir().join("wardwell_test_tools_enabled");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        let tools = crate::config::loader::ToolsEnabled { search: true, write: false, clipboard: false };
+        let server = make_test_server_with_tools(&tmp, tools);
+        assert!(server.tool_router.has_route("wardwell_search"));
+        assert!(!server.tool_router.has_route("wardwell_write"));
+        assert!(!server.tool_router.has_route("wardwell_clipboard"));
+
+        let instructions = server.get_info().instructions.unwrap();
+        assert!(instructions.contains("1 tool:"));
+        assert!(!instructions.contains("wardwell_clipboard"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn read_only_servers_drop_write_tools() {
+        let tmp = std::env::temp_dir().join("wardwell_test_read_only");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        let server = make_test_server_with(&tmp, |config| config.read_only = true);
+        assert!(server.tool_router.has_route("wardwell_search"));
+        assert!(!server.tool_router.has_route("wardwell_write"));
+        assert!(!server.tool_router.has_route("wardwell_clipboard"));
+        assert!(server.get_info().instructions.unwrap().contains("read-only"));
+
+        let error: serde_json::Value = serde_json::from_str(&read_only_error("wardwell_write")).unwrap();
+        assert!(error["error"].as_str().unwrap().contains("read-only, so wardwell_write is disabled"), "{error}");
+        assert_eq!(error["read_only"], true);
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn read_includes_stale_warning_until_indexed() {
+        let tmp = std::env::temp_dir().join("wardwell_test_stale_warning");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        std::fs::write(tmp.join("work/api/INDEX.md"), "# api\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = SearchParams {
+            action: "read".to_string(),
+            path: Some("work/api/INDEX.md".to_string()),
+            ..Default::default()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_read(&params)).unwrap();
+        assert!(result["stale_warning"].as_str().unwrap().contains("work/api/INDEX.md"));
+
+        server.index.mark_indexed().unwrap();
+        let result: serde_json::Value = serde_json::from_str(&server.action_read(&params)).unwrap();
+        assert!(result.get("stale_warning").is_none());
+
+        // The vault walk is reused for a while rather than repeated on every call
+        let later = std::time::SystemTime::now() + Duration::from_secs(3600);
+        std::fs::write(tmp.join("work/api/new.md"), "# new\n").unwrap();
+        std::fs::File::options().write(true).open(tmp.join("work/api/new.md")).unwrap().set_modified(later).unwrap();
+        assert!(server.stale_warning().is_none());
+        *server.newest_change.lock().unwrap() = None;
+        assert!(server.stale_warning().unwrap().contains("work/api/new.md"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn oversized_read_is_cut_with_a_line_cursor() {
+        let tmp = std::env::temp_dir().join("wardwell_test_response_guard");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        let body: String = (0..1000).map(|i| format!("Line {i}: notes about the api\n")).collect();
+        std::fs::write(tmp.join("work/api/notes.md"), &body).unwrap();
+
+        let mut server = make_test_server(&tmp);
+        Arc::get_mut(&mut server.config).unwrap().max_response_bytes = 8_000;
+        let params = |start: Option<usize>| SearchParams {
+            action: "read".to_string(),
+            path: Some("work/api/notes.md".to_string()),
+            start,
+            ..Default::default()
+        };
+        let out = server.wardwell_search(Parameters(params(None))).await;
+        assert!(out.len() <= 8_000, "{}", out.len());
+        let result: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let kept = result["truncated"]["cuts"][0]["kept"].as_u64().unwrap() as usize;
+        assert!(kept > 0 && kept < 1000);
+        let hint = result["truncated"]["hint"].as_str().unwrap();
+        assert!(hint.contains(&format!("start {kept}")), "{hint}");
+
+        let out = server.wardwell_search(Parameters(params(Some(kept)))).await;
+        let result: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert!(result["content"].as_str().unwrap().starts_with(&format!("Line {kept}:")));
+        assert_eq!(result["lines"]["total"], 1000);
+
+        Arc::get_mut(&mut server.config).unwrap().max_response_bytes = 0;
+        let result: serde_json::Value = serde_json::from_str(&server.wardwell_search(Parameters(params(None))).await).unwrap();
+        assert!(result.get("truncated").is_none());
+        assert_eq!(result["content"], body.as_str());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn server_status_reports_latency_per_action() {
+        let tmp = std::env::temp_dir().join("wardwell_test_server_status");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("work/api")).unwrap();
+        let server = make_test_server(&tmp);
+        let params = |action: &str| SearchParams {
+            action: action.to_string(),
+            ..Default::default()
+        };
+        server.wardwell_search(Parameters(params("domains"))).await;
+        server.wardwell_search(Parameters(params("domains"))).await;
+        server.latencies.lock().unwrap().insert("write.sync".to_string(), vec![40, 5000]);
+
+        let status: serde_json::Value = serde_json::from_str(&server.wardwell_search(Parameters(params("server_status"))).await).unwrap();
+        assert_eq!(status["latency_since_start"]["search.domains"]["calls"], 2);
+        assert_eq!(status["latency_since_start"]["write.sync"]["p95_ms"], 5000);
+        assert_eq!(status["slow_actions"], serde_json::json!(["write.sync"]));
+        assert_eq!(status["metrics_enabled"], false);
+        assert!(status["latency_last_7_days"].is_null());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn domains_and_projects_list_from_index() {
+        let tmp = std::env::temp_dir().join("wardwell_test_list_projects");
+        let _ = std::fs::remove_dir_all(&tmp);
+        for (dir, status, updated) in [("work/api", "active", "2026-03-02"), ("work/billing", "blocked", "2026-02-01"), ("personal/blog", "paused", "2026-01-05")] {
+            std::fs::create_dir_all(tmp.join(dir)).unwrap();
+            std::fs::write(
+                tmp.join(dir).join("current_state.md"),
+                format!("---\ntype: project\nstatus: {status}\nupdated: {updated}\n---\n\n## Focus\nThings\n"),
+            ).unwrap();
+        }
+
+        let server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+
+        let result: serde_json::Value = serde_json::from_str(&server.action_domains()).unwrap();
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["domains"][1]["domain"], "work");
+        assert_eq!(result["domains"][1]["project_count"], 2);
+        assert_eq!(result["domains"][1]["statuses"]["blocked"], 1);
+        assert_eq!(result["domains"][1]["updated"], "2026-03-02");
+
+        let params = SearchParams {
+            action: "projects".to_string(),
+            domain: Some("work".to_string()),
+            ..Default::default()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_projects(&params)).unwrap();
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["projects"][0]["project"], "api");
+        assert_eq!(result["projects"][0]["status"], "active");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn compute_effort_patterns_and_neglect() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let start = today - chrono::Duration::days(56);
+        let d = |n: i64| today - chrono::Duration::days(n);
+
+        // Every other day, then twelve quiet days
+        let history = [d(20), d(18), d(16), d(14), d(12)];
+        let e = compute_effort(&history, &[(d(14), 1.5)], start, today, true);
+        assert_eq!(e.active_days, 5);
+        assert_eq!(e.avg_gap_days, Some(2.0));
+        assert_eq!(e.days_since_last, Some(12));
+        assert_eq!(e.pattern, "steady");
+        assert_eq!(e.overdue_ratio, Some(6.0));
+        assert!(e.neglected);
+        assert_eq!(e.session_hours, 1.5);
+        assert!(!compute_effort(&history, &[], start, today, false).neglected, "closed projects are never neglected");
+
+        let bursty = compute_effort(&[d(30), d(29), d(28), d(3), d(2)], &[], start, today, true);
+        assert_eq!(bursty.bursts, 2);
+        assert_eq!(bursty.pattern, "bursty");
+        assert!(!bursty.neglected);
+
+        let dormant = compute_effort(&[], &[], start, today, true);
+        assert_eq!(dormant.pattern, "dormant");
+        assert_eq!(dormant.overdue_ratio, None);
+    }
+
+    #[test]
+    fn effort_surfaces_neglected_projects_in_orchestrate() {
+        let tmp = std::env::temp_dir().join("wardwell_test_effort");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let today = chrono::Local::now().date_naive();
+        for (project, days_ago) in [("busy", [4, 3, 2, 1, 0]), ("quiet", [30, 27, 24, 21, 18])] {
+            let dir = tmp.join("work").join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("current_state.md"), "---\ntype: project\nstatus: active\n---\n\n## Focus\nThings\n").unwrap();
+            let lines: String = days_ago.iter().map(|n| {
+                let date = today - chrono::Duration::days(*n);
+                format!("{{\"date\":\"{date}\",\"title\":\"Work\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\"}}\n")
+            }).collect();
+            std::fs::write(dir.join("history.jsonl"), lines).unwrap();
+        }
+
+        let server = make_test_server(&tmp);
+        let mut params = SearchParams {
+            action: "effort".to_string(),
+            domain: Some("work".to_string()),
+            ..Default::default()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_effort(&params)).unwrap();
+        assert_eq!(result["count"], 2);
+        assert_eq!(result["neglected"], 1);
+        assert_eq!(result["projects"][0]["project"], "quiet");
+        assert_eq!(result["projects"][0]["avg_gap_days"], 3.0);
+        assert_eq!(result["projects"][1]["days_since_last"], 0);
+
+        params.action = "orchestrate".to_string();
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(result["now"]["project"], "quiet");
+        assert_eq!(result["now"]["effort"]["neglected"], true);
+        assert_eq!(result["queue"][1]["project"], "busy");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn remote_vault_reads_through_the_index() {
+        let tmp = std::env::temp_dir().join("wardwell_test_remote_vault");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let dir = tmp.join("work/api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("current_state.md"), "---\ntype: project\nstatus: active\n---\n\n## Focus\nWebhooks\n").unwrap();
+        std::fs::write(dir.join("history.jsonl"), "{\"date\":\"2026-03-01\",\"title\":\"Webhook retries\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"Signed\"}\n").unwrap();
+
+        let mut server = make_test_server(&tmp);
+        Arc::get_mut(&mut server.config).unwrap().remote_vault.enabled = true;
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+        // A change the index hasn't seen yet: remote mode answers from the index, not the disk
+        std::fs::write(dir.join("current_state.md"), "---\ntype: project\nstatus: blocked\n---\n\n## Focus\nWebhooks\n").unwrap();
+
+        let mut params = SearchParams {
+            action: "orchestrate".to_string(),
+            domain: Some("work".to_string()),
+            ..Default::default()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(result["now"]["project"], "api");
+        assert_eq!(result["now"]["status"], "active");
+
+        params.action = "history".to_string();
+        params.query = Some("webhook".to_string());
+        let result: serde_json::Value = serde_json::from_str(&server.action_history(&params)).unwrap();
+        assert_eq!(result["total"], 1);
+        assert_eq!(result["entries"][0]["title"], "Webhook retries");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn dashboard_serves_the_queue_recent_history_and_search() {
+        let tmp = std::env::temp_dir().join("wardwell_test_dashboard");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let dir = tmp.join("work").join("api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("current_state.md"), "---\ntype: project\nstatus: active\n---\n\n## Focus\nShip the gateway\n").unwrap();
+        let today = chrono::Local::now().date_naive();
+        std::fs::write(dir.join("history.jsonl"), format!(
+            "{{\"date\":\"{today}\",\"title\":\"Cut the release\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\"}}\n\
+             {{\"date\":\"2020-01-01\",\"title\":\"Ancient\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\"}}\n"
+        )).unwrap();
+        let server = make_test_server(&tmp);
+        crate::index::builder::IndexBuilder::build_filtered(&server.index, &tmp, &[], SymlinkPolicy::Follow, &Default::default(), None).unwrap();
+
+        let dashboard = crate::mcp::dashboard::Dashboard::new(server.clone());
+        let get = |uri: &str| hyper::Request::get(uri).body(()).unwrap();
+        let page = dashboard.respond(&get("/")).await.unwrap();
+        assert_eq!(page.headers()[hyper::header::CONTENT_TYPE], "text/html; charset=utf-8");
+        assert!(dashboard.respond(&get("/mcp")).await.is_none());
+        assert!(dashboard.respond(&hyper::Request::post("/").body(()).unwrap()).await.is_none());
+        let api = dashboard.respond(&get("/api/dashboard")).await.unwrap();
+        assert_eq!(api.status(), hyper::StatusCode::OK);
+        assert_eq!(api.headers()[hyper::header::CONTENT_TYPE], "application/json");
+
+        let data = server.dashboard();
+        assert_eq!(data["queue"][0]["project"], "api");
+        assert_eq!(data["recent"].as_array().unwrap().len(), 1, "{data}");
+        assert_eq!(data["recent"][0]["title"], "Cut the release");
+        let found = server.dashboard_search("gateway");
+        assert_eq!(found["results"][0]["path"], "work/api/current_state.md", "{found}");
+        // Page views don't count as the session reading the project
+        assert!(server.accessed_projects.lock().unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn orchestrate_ranks_with_configured_weights() {
+        let tmp = std::env::temp_dir().join("wardwell_test_orchestrate_ranking");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let due = (chrono::Local::now().date_naive() + chrono::Duration::days(1)).to_string();
+        for (project, index) in [("api", "priority: low"), ("billing", "priority: high"), ("site", &*format!("due: {due}"))] {
+            let dir = tmp.join("work").join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("current_state.md"), "---\ntype: project\nstatus: active\n---\n\n## Focus\nShip\n").unwrap();
+            std::fs::write(dir.join("INDEX.md"), format!("---\ntype: project\n{index}\n---\n\n# {project}\n")).unwrap();
+        }
+
+        let mut server = make_test_server(&tmp);
+        let params = SearchParams {
+            action: "orchestrate".to_string(),
+            domain: Some("work".to_string()),
+            ..Default::default()
+        };
+        let order = |result: &serde_json::Value| -> Vec<String> {
+            result["queue"].as_array().unwrap().iter().map(|e| e["project"].as_str().unwrap().to_string()).collect()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(order(&result), vec!["api", "billing", "site"]);
+        assert!(result["queue"][0].get("features").is_none());
+
+        Arc::get_mut(&mut server.config).unwrap().ranking.weights = Some(crate::config::loader::RankingWeights {
+            priority: 1.0,
+            due_urgency: 8.0,
+            ..Default::default()
+        });
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(order(&result), vec!["site", "billing", "api"]);
+        assert_eq!(result["queue"][1]["features"]["priority"], 3);
+        assert_eq!(result["queue"][0]["features"]["due_in_days"], 1);
+
+        // A broken script falls back to the weights and says why
+        Arc::get_mut(&mut server.config).unwrap().ranking.command = Some("exit 3".to_string());
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(order(&result), vec!["site", "billing", "api"]);
+        assert!(result["ranking_error"].as_str().unwrap().contains("exited"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn orchestrate_scores_health_and_sorts_by_it() {
+        let tmp = std::env::temp_dir().join("wardwell_test_orchestrate_health");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let today = chrono::Local::now().date_naive();
+        let days_ago = |n: i64| (today - chrono::Duration::days(n)).to_string();
+        for (project, status, updated, questions) in [
+            ("api", "active", days_ago(1), ""),
+            ("billing", "active", days_ago(37), "\n## Open Questions\n- Which PSP?\n- Refunds?\n"),
+            ("site", "blocked", days_ago(3), ""),
+        ] {
+            let dir = tmp.join("work").join(project);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("current_state.md"), format!("---\ntype: project\nstatus: {status}\nupdated: {updated}\n---\n\n## Focus\nShip\n{questions}")).unwrap();
+        }
+        let entry = |date: String, status: &str| format!("{{\"date\":\"{date} 10:00\",\"title\":\"Work\",\"status\":\"{status}\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"\"}}\n");
+        std::fs::write(tmp.join("work/api/history.jsonl"), [entry(days_ago(2), "active"), entry(days_ago(1), "active")].concat()).unwrap();
+        std::fs::write(tmp.join("work/site/history.jsonl"), [entry(days_ago(20), "active"), entry(days_ago(10), "waiting"), entry(days_ago(3), "blocked")].concat()).unwrap();
+
+        let mut server = make_test_server(&tmp);
+        // A vault status that counts as blocked starts the blocked run too
+        Arc::get_mut(&mut server.config).unwrap().statuses.insert("waiting".to_string(), Status::Blocked);
+        let mut params = SearchParams {
+            action: "orchestrate".to_string(),
+            domain: Some("work".to_string()),
+            ..Default::default()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(result["queue"][0]["project"], "api");
+        assert_eq!((result["queue"][0]["score"].as_u64(), result["queue"][0]["reasons"].as_array().map(Vec::len)), (Some(0), Some(0)));
+        assert_eq!(result["queue"][1]["reasons"], serde_json::json!(["not updated in 37 days", "2 open questions", "no history in the last 14 days"]));
+        assert_eq!(result["queue"][1]["score"], 30 + 8 + 20);
+        assert_eq!(result["blocked"][0]["reasons"][0], "blocked for 10 days");
+        assert!(result.get("ranked").is_none());
+
+        params.sort = Some("score".to_string());
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        let order = |list: &serde_json::Value| -> Vec<String> {
+            list.as_array().unwrap().iter().map(|e| e["project"].as_str().unwrap().to_string()).collect()
+        };
+        assert_eq!(order(&result["queue"]), ["billing", "api"]);
+        assert_eq!(result["now"]["project"], "billing");
+        assert_eq!(order(&result["ranked"]), ["billing", "site", "api"]);
+        params.sort = Some("status".to_string());
+        let result: serde_json::Value = serde_json::from_str(&server.action_orchestrate(&params)).unwrap();
+        assert_eq!(order(&result["ranked"]), ["site", "billing", "api"]);
+
+        params.sort = Some("priority".to_string());
+        assert!(server.action_orchestrate(&params).contains("Use score, updated, or status"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn diff_reports_state_changes_between_points() {
+        let tmp = std::env::temp_dir().join("wardwell_test_diff");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let proj = tmp.join("work/sentry-bot");
+        std::fs::create_dir_all(&proj).unwrap();
+        std::fs::write(proj.join("history.jsonl"), concat!(
+            "{\"_schema\": \"history\"}\n",
+            "{\"date\":\"2026-01-10 09:00\",\"title\":\"Setup\",\"status\":\"active\",\"focus\":\"Scaffolding\",\"next_action\":\"Webhooks\"}\n",
+            "{\"date\":\"2026-03-01 12:00\",\"title\":\"Webhook retries\",\"status\":\"active\",\"focus\":\"Webhooks\",\"next_action\":\"Ship\"}\n",
+        )).unwrap();
+        std::fs::write(proj.join("decisions.md"), "# sentry-bot Decisions\n\n## 2026-02-20 — Verify webhook signatures\n\nReject unsigned payloads.\n\n---\n\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |from: Option<&str>| SearchParams {
+            action: "diff".to_string(),
+            domain: Some("work".to_string()),
+            project: Some("Sentry Bot".to_string()),
+            from: from.map(str::to_string),
+            ..Default::default()
+        };
+
+        let result: serde_json::Value = serde_json::from_str(&server.action_diff(&params(Some("#1")))).unwrap();
+        assert_eq!(result["project"], "work/sentry-bot");
+        assert_eq!(result["changes"].as_array().unwrap().len(), 2);
+        assert_eq!(result["changes"][0]["field"], "focus");
+        assert_eq!(result["history"][0]["title"], "Webhook retries");
+        assert_eq!(result["decisions"][0]["title"], "Verify webhook signatures");
+
+        assert!(server.action_diff(&params(None)).contains("'from' is required"));
+        assert!(server.action_diff(&params(Some("yesterday"))).contains("not a date"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn recall_merges_history_lessons_and_decisions() {
+        let tmp = std::env::temp_dir().join("wardwell_test_recall");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let proj = tmp.join("work/sentry-bot");
+        std::fs::create_dir_all(&proj).unwrap();
+        std::fs::write(proj.join("history.jsonl"), concat!(
+            "{\"_schema\": \"history\"}\n",
+            "{\"date\":\"2026-03-01\",\"title\":\"Webhook retries\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"Fixed webhook signing\"}\n",
+            "{\"date\":\"2026-01-10\",\"title\":\"Setup\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"Initial scaffolding\"}\n",
+        )).unwrap();
+        std::fs::write(
+            proj.join("lessons.jsonl"),
+            "{\"date\":\"2026-02-15\",\"title\":\"Secret rotation\",\"what_happened\":\"Webhook calls failed\",\"root_cause\":\"Rotated secret\",\"prevention\":\"Alert on 401s\"}\n",
+        ).unwrap();
+        std::fs::write(proj.join("decisions.md"), "# sentry-bot Decisions\n\n## 2026-02-20 — Verify webhook signatures\n\nReject unsigned payloads.\n\n---\n\n## 2026-01-05 — Use Rust\n\nFast.\n\n---\n\n").unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = |project: Option<&str>, since: Option<&str>| SearchParams {
+            action: "recall".to_string(),
+            query: Some("WEBHOOK".to_string()),
+            domain: Some("work".to_string()),
+            project: project.map(str::to_string),
+            since: since.map(str::to_string),
+            ..Default::default()
+        };
+
+        let result: serde_json::Value = serde_json::from_str(&server.action_recall(&params(Some("Sentry Bot"), None))).unwrap();
+        assert_eq!(result["total"], 3);
+        let kinds: Vec<&str> = result["entries"].as_array().unwrap().iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(kinds, vec!["history", "decision", "lesson"]);
+        assert_eq!(result["counts"]["decision"], 1);
+        assert_eq!(result["entries"][1]["body"], "Reject unsigned payloads.");
+
+        let result: serde_json::Value = serde_json::from_str(&server.action_recall(&params(None, Some("2026-02-18")))).unwrap();
+        assert_eq!(result["total"], 2);
+
+        let mut no_domain = params(Some("sentry-bot"), None);
+        no_domain.domain = None;
+        assert!(server.action_recall(&no_domain).contains("'domain' is required"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn recall_reads_the_history_archive() {
+        let tmp = std::env::temp_dir().join("wardwell_test_recall_archive");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let proj = tmp.join("work/sentry-bot");
+        std::fs::create_dir_all(&proj).unwrap();
+        std::fs::write(proj.join("history.jsonl"), concat!(
+            "{\"_schema\": \"history\"}\n",
+            "{\"date\":\"2025-06-30\",\"title\":\"June 2025\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"Webhook work\",\"source\":\"rollup\"}\n",
+        )).unwrap();
+        crate::vault::compress::write_compressed(
+            &crate::vault::compress::compressed_path(&proj.join(crate::vault::compress::HISTORY_ARCHIVE)),
+            "{\"date\":\"2025-06-03\",\"title\":\"Webhook signing\",\"status\":\"active\",\"focus\":\"\",\"next_action\":\"\",\"commit\":\"\",\"body\":\"HMAC\"}\n",
+        ).unwrap();
+
+        let server = make_test_server(&tmp);
+        let params = SearchParams {
+            action: "recall".to_string(),
+            query: Some("webhook".to_string()),
+            domain: Some("work".to_string()),
+            project: Some("sentry-bot".to_string()),
+            ..Default::default()
+        };
+        let result: serde_json::Value = serde_json::from_str(&server.action_recall(&params)).unwrap();
+        assert_eq!(result["total"], 1, "{result}");
+        assert_eq!(result["entries"][0]["title"], "Webhook signing");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn recall_summaries_match_domain_and_project_folder() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("abc123.md"),
+            "---\ntype: thread\ndomain: work\nproject: /Users/test/Code/sentry-bot\nstatus: resolved\n---\n\n## Decisions\n- Chose webhook retries at the client\n- Unrelated line\n",
+        ).unwrap();
+        std::fs::write(
+            tmp.path().join("def456.md"),
+            "---\ntype: thread\ndomain: personal\nproject: /Users/test/Code/sentry-bot\n---\n\nwebhook notes\n",
+        ).unwrap();
+
+        let mut out = Vec::new();
+        recall_summaries(tmp.path(), "work", Some("Sentry Bot"), "webhook", None, &mut out);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].kind, "summary");
+        assert_eq!(out[0].session_id.as_deref(), Some("abc123"));
+        assert_eq!(out[0].body, "- Chose webhook retries at the client");
+
+        let mut out = Vec::new();
+        recall_summaries(tmp.path(), "work", Some("other"), "webhook", None, &mut out);
+        assert!(out.is_empty());
+    }
 }
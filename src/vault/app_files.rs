@@ -0,0 +1,157 @@
+use crate::vault::compress::HISTORY_ARCHIVE;
+use std::path::Path;
+
+/// Vault-root manifest naming the files wardwell maintains, for tools that walk the vault.
+pub const MANIFEST: &str = ".wardwell-manifest.yml";
+
+/// Globs, relative to the vault root, for files wardwell writes for itself, each with an
+/// optional caveat. History logs are listed but keep `type: history` in the index, since
+/// `action: history` searches them.
+pub const APP_FILES: &[(&str, &str)] = &[
+    ("*/*/history.jsonl", ""),
+    ("*/*/history.archive.jsonl", ""),
+    ("*/*/history.archive.jsonl.zst", ""),
+    ("*/*/lessons.jsonl", ""),
+    ("*/*/*.jsonl", "named lists"),
+    ("*/current_state.md", "only with `generated: wardwell` in its frontmatter"),
+];
+
+/// Obsidian `userIgnoreFilters` that hide machine files from search, graph, and quick switcher.
+/// Generated rollups can't be matched by pattern, so `obsidian_filters` adds them by path.
+const OBSIDIAN_FILTERS: &[&str] = &[r"/\.jsonl$/", r"/\.zst$/"];
+
+/// Whether the file at `rel_path` (vault-relative, at `path` on disk) is a machine file:
+/// a JSONL list or lessons log, or a domain rollup wardwell generated. History is not.
+pub fn is_machine(rel_path: &str, path: &Path) -> bool {
+    let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+    if name.ends_with(".jsonl") {
+        return name != "history.jsonl" && name != HISTORY_ARCHIVE;
+    }
+    // Domain rollups live one level down; project current_state.md files are two
+    name == "current_state.md"
+        && rel_path.matches('/').count() == 1
+        && std::fs::read_to_string(path).is_ok_and(|c| crate::daemon::rollup::is_generated(&c))
+}
+
+/// Write `<vault>/.wardwell-manifest.yml` listing `APP_FILES`.
+pub fn write_manifest(vault_root: &Path) -> std::io::Result<()> {
+    std::fs::write(vault_root.join(MANIFEST), manifest_yaml())
+}
+
+/// The manifest `write_manifest` writes.
+pub fn manifest_yaml() -> String {
+    let mut yaml = String::from(
+        "# Files wardwell writes for its own use. Tools walking this vault can skip them.\n\
+         # Written by `wardwell init`; edits are overwritten.\n\
+         app_files:\n",
+    );
+    for (glob, note) in APP_FILES {
+        yaml.push_str(&format!("  - \"{glob}\""));
+        if !note.is_empty() {
+            yaml.push_str(&format!("  # {note}"));
+        }
+        yaml.push('\n');
+    }
+    yaml
+}
+
+/// The ignore filters for this vault: the fixed patterns plus each generated domain rollup.
+pub fn obsidian_filters(vault_root: &Path) -> Vec<String> {
+    let mut filters: Vec<String> = OBSIDIAN_FILTERS.iter().map(|f| f.to_string()).collect();
+    let Ok(entries) = std::fs::read_dir(vault_root) else {
+        return filters;
+    };
+    let mut rollups: Vec<String> = entries.flatten()
+        .filter(|e| e.path().is_dir() && !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| {
+            let rel = format!("{}/current_state.md", e.file_name().to_string_lossy());
+            is_machine(&rel, &e.path().join("current_state.md")).then_some(rel)
+        })
+        .collect();
+    rollups.sort();
+    filters.extend(rollups);
+    filters
+}
+
+/// How many of `filters` `<vault>/.obsidian/app.json` doesn't list under `userIgnoreFilters` yet.
+pub fn missing_obsidian_filters(vault_root: &Path, filters: &[String]) -> usize {
+    let app: serde_json::Value = std::fs::read_to_string(vault_root.join(".obsidian").join("app.json")).ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default();
+    let listed = app["userIgnoreFilters"].as_array().cloned().unwrap_or_default();
+    filters.iter().filter(|f| !listed.iter().any(|l| l.as_str() == Some(f.as_str()))).count()
+}
+
+/// Merge `filters` into `userIgnoreFilters` in `<vault>/.obsidian/app.json`, keeping every
+/// other setting and any filters already there. Returns how many filters were added.
+pub fn merge_obsidian_ignore(vault_root: &Path, filters: &[String]) -> Result<usize, String> {
+    let path = vault_root.join(".obsidian").join("app.json");
+    let mut app: serde_json::Value = match std::fs::read_to_string(&path) {
+        Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content)
+            .map_err(|e| format!("{} is not valid JSON: {e}", path.display()))?,
+        _ => serde_json::json!({}),
+    };
+    let Some(settings) = app.as_object_mut() else {
+        return Err(format!("{} is not a JSON object", path.display()));
+    };
+    let existing = settings.entry("userIgnoreFilters").or_insert_with(|| serde_json::json!([]));
+    let Some(list) = existing.as_array_mut() else {
+        return Err(format!("userIgnoreFilters in {} is not a list", path.display()));
+    };
+
+    let mut added = 0;
+    for filter in filters {
+        if !list.iter().any(|f| f.as_str() == Some(filter)) {
+            list.push(serde_json::json!(filter));
+            added += 1;
+        }
+    }
+    if added > 0 {
+        let content = serde_json::to_string_pretty(&app).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(vault_root.join(".obsidian")).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| format!("could not write {}: {e}", path.display()))?;
+    }
+    Ok(added)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn machine_files_and_obsidian_filters() {
+        let tmp = tempdir().unwrap();
+        let vault = tmp.path();
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::create_dir_all(vault.join("home")).unwrap();
+        std::fs::write(vault.join("work/current_state.md"), "---\ntype: domain\ngenerated: wardwell\n---\n").unwrap();
+        std::fs::write(vault.join("home/current_state.md"), "---\ntype: domain\n---\nHand-written").unwrap();
+
+        assert!(is_machine("work/api/lessons.jsonl", &vault.join("work/api/lessons.jsonl")));
+        assert!(!is_machine("work/api/history.jsonl", &vault.join("work/api/history.jsonl")));
+        assert!(is_machine("work/current_state.md", &vault.join("work/current_state.md")));
+        assert!(!is_machine("home/current_state.md", &vault.join("home/current_state.md")));
+
+        let filters = obsidian_filters(vault);
+        assert_eq!(filters.last().map(String::as_str), Some("work/current_state.md"));
+        assert!(!filters.iter().any(|f| f.starts_with("home/")));
+
+        std::fs::create_dir_all(vault.join(".obsidian")).unwrap();
+        std::fs::write(vault.join(".obsidian/app.json"), r#"{"vimMode": true, "userIgnoreFilters": ["Archive/"]}"#).unwrap();
+        assert_eq!(missing_obsidian_filters(vault, &filters), filters.len());
+        assert_eq!(merge_obsidian_ignore(vault, &filters).unwrap(), filters.len());
+        assert_eq!(merge_obsidian_ignore(vault, &filters).unwrap(), 0);
+        assert_eq!(missing_obsidian_filters(vault, &filters), 0);
+        let app: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(vault.join(".obsidian/app.json")).unwrap()).unwrap();
+        assert_eq!(app["vimMode"], true);
+        assert_eq!(app["userIgnoreFilters"][0], "Archive/");
+        assert_eq!(app["userIgnoreFilters"].as_array().unwrap().len(), filters.len() + 1);
+
+        write_manifest(vault).unwrap();
+        let manifest: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(vault.join(MANIFEST)).unwrap()).unwrap();
+        assert_eq!(manifest["app_files"].as_sequence().unwrap().len(), APP_FILES.len());
+        assert_eq!(manifest["app_files"][5], "*/current_state.md");
+    }
+}
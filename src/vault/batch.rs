@@ -0,0 +1,180 @@
+use crate::vault::compress::read_to_string;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How a batch of vault files is read. The default reads one file at a time and waits
+/// as long as it takes, which is right for a local disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchRead {
+    /// Files read at once.
+    pub concurrency: usize,
+    /// Give up on files not read this long after the batch started.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for BatchRead {
+    fn default() -> Self {
+        Self { concurrency: 1, timeout: None }
+    }
+}
+
+/// Errors from a batched read.
+#[derive(Debug, thiserror::Error)]
+pub enum BatchReadError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("read did not finish within {0:?}")]
+    TimedOut(Duration),
+}
+
+/// Most reader threads the shared pool starts, however many a config asks for.
+const MAX_READERS: usize = 32;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Reader threads shared by every batch. A read stuck on a stalled mount holds one of them
+/// until it returns, so however many batches time out, at most `MAX_READERS` threads exist.
+struct Pool {
+    jobs: mpsc::Sender<Job>,
+    queue: Arc<Mutex<mpsc::Receiver<Job>>>,
+    workers: AtomicUsize,
+}
+
+impl Pool {
+    fn shared() -> &'static Pool {
+        static POOL: OnceLock<Pool> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let (jobs, queue) = mpsc::channel();
+            Pool { jobs, queue: Arc::new(Mutex::new(queue)), workers: AtomicUsize::new(0) }
+        })
+    }
+
+    /// Start workers until there are `wanted`, up to `MAX_READERS`.
+    fn grow(&self, wanted: usize) {
+        let wanted = wanted.min(MAX_READERS);
+        while self.workers.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < wanted).then_some(n + 1)).is_ok() {
+            let queue = Arc::clone(&self.queue);
+            let spawned = std::thread::Builder::new().name("wardwell-read".to_string()).spawn(move || {
+                loop {
+                    let job = match queue.lock() {
+                        Ok(queue) => queue.recv(),
+                        Err(_) => return,
+                    };
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => return,
+                    }
+                }
+            });
+            if spawned.is_err() {
+                self.workers.fetch_sub(1, Ordering::AcqRel);
+                return;
+            }
+        }
+    }
+}
+
+/// Read every path to a string, in input order, decompressing `.zst` files. Network mounts answer one small read slowly
+/// but many in parallel fine, so reads run on up to `concurrency` threads of a pool shared by
+/// all batches. A read still pending at the deadline is reported as `TimedOut`; the rest of
+/// its batch is dropped from the queue, and its thread rejoins the pool once the read returns.
+pub fn read_many(paths: &[PathBuf], opts: BatchRead) -> Vec<Result<String, BatchReadError>> {
+    if opts.concurrency <= 1 && opts.timeout.is_none() {
+        return paths.iter().map(|p| read_to_string(p).map_err(BatchReadError::from)).collect();
+    }
+
+    let pool = Pool::shared();
+    pool.grow(opts.concurrency.clamp(1, paths.len().max(1)));
+    let abandoned = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    for (i, path) in paths.iter().enumerate() {
+        let (path, tx, abandoned) = (path.clone(), tx.clone(), Arc::clone(&abandoned));
+        let job: Job = Box::new(move || {
+            if !abandoned.load(Ordering::Acquire) {
+                let _ = tx.send((i, read_to_string(&path)));
+            }
+        });
+        if pool.jobs.send(job).is_err() {
+            break;
+        }
+    }
+    drop(tx);
+
+    let deadline = opts.timeout.map(|t| Instant::now() + t);
+    let mut done: Vec<Option<std::io::Result<String>>> = paths.iter().map(|_| None).collect();
+    for _ in 0..paths.len() {
+        let received = match deadline {
+            Some(d) => rx.recv_timeout(d.saturating_duration_since(Instant::now())).ok(),
+            None => rx.recv().ok(),
+        };
+        let Some((i, result)) = received else {
+            break;
+        };
+        done[i] = Some(result);
+    }
+    abandoned.store(true, Ordering::Release);
+
+    let timeout = opts.timeout.unwrap_or_default();
+    done.into_iter().map(|r| match r {
+        Some(result) => result.map_err(BatchReadError::from),
+        None => Err(BatchReadError::TimedOut(timeout)),
+    }).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_in_input_order() {
+        let tmp = tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..20).map(|i| tmp.path().join(format!("{i}.md"))).collect();
+        for (i, p) in paths.iter().enumerate().skip(1) {
+            std::fs::write(p, format!("file {i}")).unwrap();
+        }
+
+        let opts = BatchRead { concurrency: 4, timeout: Some(Duration::from_secs(10)) };
+        let results = read_many(&paths, opts);
+        assert!(matches!(results[0], Err(BatchReadError::Io(_))));
+        for (i, r) in results.iter().enumerate().skip(1) {
+            assert_eq!(r.as_ref().unwrap(), &format!("file {i}"));
+        }
+        assert_eq!(read_many(&paths[1..3], BatchRead::default()).len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn gives_up_on_reads_that_hang() {
+        let tmp = tempdir().unwrap();
+        // Opening a FIFO with no writer blocks, like a read from a stalled mount
+        let fifo = tmp.path().join("stuck.md");
+        let made = std::process::Command::new("mkfifo").arg(&fifo).status();
+        if !made.is_ok_and(|s| s.success()) {
+            return;
+        }
+        let ok = tmp.path().join("ok.md");
+        std::fs::write(&ok, "fine").unwrap();
+
+        let opts = BatchRead { concurrency: 2, timeout: Some(Duration::from_millis(200)) };
+        let started = Instant::now();
+        let results = read_many(&[fifo, ok], opts);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(results[0], Err(BatchReadError::TimedOut(_))));
+        assert_eq!(results[1].as_ref().unwrap(), "fine");
+
+        // Batches that time out reuse the pool rather than starting threads of their own
+        for _ in 0..3 {
+            let opts = BatchRead { concurrency: 100, timeout: Some(Duration::from_millis(50)) };
+            read_many(&[tmp.path().join("stuck.md"), tmp.path().join("ok.md")], opts);
+        }
+        assert!(Pool::shared().workers.load(Ordering::Acquire) <= MAX_READERS);
+        // A writer lets the stuck opens return, freeing their threads. It blocks until a
+        // reader opens too, so it runs on its own thread.
+        let fifo = tmp.path().join("stuck.md");
+        std::thread::spawn(move || drop(std::fs::OpenOptions::new().write(true).open(fifo)));
+    }
+}
@@ -0,0 +1,102 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Extension added to a compressed vault file: `history.archive.jsonl.zst`.
+pub const EXTENSION: &str = "zst";
+
+/// Older history moved out of `history.jsonl` by `wardwell compact`, next to it in each project.
+pub const HISTORY_ARCHIVE: &str = "history.archive.jsonl";
+
+/// `source` of the monthly summary entries `wardwell compact --summarize` leaves in
+/// `history.jsonl`. Readers that also read the archive skip them, as the raw entries are there.
+pub const ROLLUP_SOURCE: &str = "rollup";
+
+/// zstd level for archives. They are written rarely and read whole, so favor size.
+const LEVEL: i32 = 19;
+
+/// Whether `path` names a compressed file.
+pub fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == EXTENSION)
+}
+
+/// `path` with `.zst` appended.
+pub fn compressed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(EXTENSION);
+    PathBuf::from(name)
+}
+
+/// The name a file has uncompressed: `history.archive.jsonl.zst` → `history.archive.jsonl`.
+pub fn logical_path(path: &Path) -> PathBuf {
+    if is_compressed(path) {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// `path`, or its compressed twin, whichever exists.
+pub fn existing(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+    let compressed = compressed_path(path);
+    compressed.exists().then_some(compressed)
+}
+
+/// Read a vault file to a string, decompressing it when the name ends in `.zst`.
+pub fn read_to_string(path: &Path) -> std::io::Result<String> {
+    if !is_compressed(path) {
+        return std::fs::read_to_string(path);
+    }
+    let mut content = String::new();
+    zstd::stream::read::Decoder::new(std::fs::File::open(path)?)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Compress `content` into `path` (which should end in `.zst`), replacing it atomically.
+/// Returns the compressed size.
+pub fn write_compressed(path: &Path, content: &str) -> std::io::Result<u64> {
+    let bytes = zstd::stream::encode_all(content.as_bytes(), LEVEL)?;
+    let tmp = path.with_extension("zst.tmp");
+    std::fs::write(&tmp, &bytes)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(bytes.len() as u64)
+}
+
+/// Replace `path` with `path.zst`. Returns (size before, size after).
+pub fn compress_file(path: &Path) -> std::io::Result<(u64, u64)> {
+    let content = std::fs::read_to_string(path)?;
+    let after = write_compressed(&compressed_path(path), &content)?;
+    std::fs::remove_file(path)?;
+    Ok((content.len() as u64, after))
+}
+
+/// `<project_dir>/history.archive.jsonl`, compressed or not, if the project has one.
+pub fn history_archive(project_dir: &Path) -> Option<PathBuf> {
+    existing(&project_dir.join(HISTORY_ARCHIVE))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn compressed_files_read_back() {
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join(HISTORY_ARCHIVE);
+        let content = "{\"date\":\"2025-01-01\",\"title\":\"Old\"}\n".repeat(50);
+        std::fs::write(&path, &content).unwrap();
+
+        let (before, after) = compress_file(&path).unwrap();
+        assert!(after < before);
+        assert!(!path.exists());
+        let archive = history_archive(tmp.path()).unwrap();
+        assert_eq!(archive, tmp.path().join("history.archive.jsonl.zst"));
+        assert_eq!(logical_path(&archive), path);
+        assert_eq!(read_to_string(&archive).unwrap(), content);
+    }
+}
@@ -0,0 +1,182 @@
+/// Lines of unchanged context around each hunk in `unified_diff`.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Unified diff of two texts, labelled `a/{label}` and `b/{label}`. Empty when they are identical.
+pub fn unified_diff(old: &str, new: &str, label: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&a, &b);
+    if ops.iter().all(|op| matches!(op, Op::Equal(..))) {
+        return String::new();
+    }
+
+    let mut out = format!("--- a/{label}\n+++ b/{label}\n");
+    let changed: Vec<usize> = ops.iter().enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Group changed ops into hunks, merging ones whose context would overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + CONTEXT_LINES + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        let (mut a_start, mut b_start) = position_at(&ops, start, a.len(), b.len());
+        let slice = &ops[start..end];
+        let a_len = slice.iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+        let b_len = slice.iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+        // Unified diff numbers lines from 1; an empty side points at the line before
+        if a_len > 0 { a_start += 1; }
+        if b_len > 0 { b_start += 1; }
+        out.push_str(&format!("@@ -{a_start},{a_len} +{b_start},{b_len} @@\n"));
+        for op in slice {
+            match *op {
+                Op::Equal(i, _) => out.push_str(&format!(" {}\n", a[i])),
+                Op::Delete(i) => out.push_str(&format!("-{}\n", a[i])),
+                Op::Insert(j) => out.push_str(&format!("+{}\n", b[j])),
+            }
+        }
+    }
+    out
+}
+
+/// Zero-based (old, new) line positions at which op `idx` starts.
+fn position_at(ops: &[Op], idx: usize, a_len: usize, b_len: usize) -> (usize, usize) {
+    match ops.get(idx) {
+        Some(Op::Equal(i, j)) => (*i, *j),
+        Some(Op::Delete(i)) => (*i, ops[..idx].iter().filter(|op| !matches!(op, Op::Delete(_))).count()),
+        Some(Op::Insert(j)) => (ops[..idx].iter().filter(|op| !matches!(op, Op::Insert(_))).count(), *j),
+        None => (a_len, b_len),
+    }
+}
+
+/// Line-level edit script via longest common subsequence. State files are small, so O(n·m) is fine.
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<Op> {
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            ops.push(Op::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(Op::Delete(i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..n).map(Op::Delete));
+    ops.extend((j..m).map(Op::Insert));
+    ops
+}
+
+/// Headings of `## ` sections whose content differs between two bodies, in order of appearance.
+/// Whitespace-only changes are ignored. Added and removed sections count as changed.
+pub fn changed_sections(old: &str, new: &str) -> Vec<String> {
+    let old_sections = split_sections(old);
+    let new_sections = split_sections(new);
+    let mut changed = Vec::new();
+    for (heading, content) in &new_sections {
+        let before = old_sections.iter().find(|(h, _)| h == heading).map(|(_, c)| c.as_str());
+        if before != Some(content.as_str()) {
+            changed.push(heading.clone());
+        }
+    }
+    for (heading, _) in &old_sections {
+        if !new_sections.iter().any(|(h, _)| h == heading) {
+            changed.push(heading.clone());
+        }
+    }
+    changed
+}
+
+/// Split a markdown body into (heading, normalized content) pairs. Text before the first
+/// `## ` heading is keyed by an empty heading.
+pub fn split_sections(body: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, Vec<&str>)> = vec![(String::new(), Vec::new())];
+    for line in body.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            sections.push((heading.trim().to_string(), Vec::new()));
+        } else if let Some(last) = sections.last_mut() {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                last.1.push(trimmed);
+            }
+        }
+    }
+    sections
+        .into_iter()
+        .filter(|(h, lines)| !h.is_empty() || !lines.is_empty())
+        .map(|(h, lines)| (h, lines.join("\n")))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diff_identical_is_empty() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "x.md"), "");
+    }
+
+    #[test]
+    fn unified_diff_single_change_with_context() {
+        let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n";
+        let new = "one\ntwo\nthree\nfour\nFIVE\nsix\nseven\neight\n";
+        let diff = unified_diff(old, new, "current_state.md");
+        assert_eq!(
+            diff,
+            "--- a/current_state.md\n+++ b/current_state.md\n@@ -2,7 +2,7 @@\n two\n three\n four\n-five\n+FIVE\n six\n seven\n eight\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_from_empty() {
+        let diff = unified_diff("", "new\n", "f.md");
+        assert!(diff.contains("@@ -0,0 +1,1 @@\n+new\n"));
+    }
+
+    #[test]
+    fn changed_sections_detects_edits_additions_and_removals() {
+        let old = "# api\n\n## Focus\nShip auth\n\n## Next Action\nWrite tests\n\n## Blockers\n- infra\n";
+        let new = "# api\n\n## Focus\nShip auth\n\n## Next Action\nDeploy to staging\n\n## Waiting On\n- review\n";
+        assert_eq!(changed_sections(old, new), vec!["Next Action", "Waiting On", "Blockers"]);
+    }
+
+    #[test]
+    fn changed_sections_ignores_whitespace() {
+        let old = "## Focus\nShip auth\n";
+        let new = "## Focus\n\n  Ship auth  \n\n";
+        assert!(changed_sections(old, new).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::vault::compress;
+use crate::vault::symlinks::{SymlinkPolicy, Walk};
+use std::path::{Path, PathBuf};
+
+/// Crockford base32, the ULID alphabet: no I, L, O, or U.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Length of an encoded ULID: 48 bits of milliseconds and 80 random bits, five bits a character.
+pub const ID_LEN: usize = 26;
+
+/// A fresh ULID for an appended entry. IDs sort by creation time, so a file's entries stay in
+/// order by ID as well as by position.
+pub fn new_id() -> String {
+    let millis = chrono::Utc::now().timestamp_millis().max(0) as u128;
+    let random = uuid::Uuid::new_v4().as_u128() & ((1u128 << 80) - 1);
+    encode((millis << 80) | random)
+}
+
+fn encode(mut value: u128) -> String {
+    let mut out = [0u8; ID_LEN];
+    for slot in out.iter_mut().rev() {
+        *slot = ALPHABET[(value & 31) as usize];
+        value >>= 5;
+    }
+    out.iter().map(|&b| b as char).collect()
+}
+
+/// Whether `s` is shaped like an entry ID. Lowercase is accepted, as ULIDs are case-insensitive.
+pub fn is_id(s: &str) -> bool {
+    s.len() == ID_LEN && s.bytes().all(|b| ALPHABET.contains(&b.to_ascii_uppercase()))
+}
+
+/// The entry with `id` in any `.jsonl` file under `dir`, compressed archives included, with
+/// the file it was found in. Links are walked per `policy`.
+pub fn find(vault_root: &Path, dir: &Path, policy: SymlinkPolicy, id: &str) -> Option<(PathBuf, serde_json::Value)> {
+    let id = id.to_ascii_uppercase();
+    let mut walk = Walk::new(vault_root, policy);
+    if dir.is_symlink() && !walk.enters(dir) {
+        return None;
+    }
+    let mut files = Vec::new();
+    collect_jsonl(&mut walk, dir, &mut files);
+    files.sort();
+    files.into_iter().find_map(|path| {
+        let content = compress::read_to_string(&path).ok()?;
+        let entry = content.lines()
+            .filter(|line| line.contains(&id))
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .find(|v| v.get("id").and_then(|i| i.as_str()) == Some(id.as_str()))?;
+        Some((path, entry))
+    })
+}
+
+fn collect_jsonl(walk: &mut Walk, dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if walk.enters(&path) {
+                collect_jsonl(walk, &path, out);
+            }
+        } else if compress::logical_path(&path).extension().is_some_and(|e| e == "jsonl") && walk.reads(&path) {
+            out.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn ids_are_well_formed_unique_and_time_ordered() {
+        let a = new_id();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let b = new_id();
+        assert!(is_id(&a) && is_id(&b), "{a} {b}");
+        assert!(a < b);
+        assert!(is_id(&a.to_lowercase()));
+        assert!(!is_id("not-an-id"));
+        assert_eq!(encode(0), "0".repeat(ID_LEN));
+    }
+
+    #[test]
+    fn find_locates_an_entry_by_id() {
+        let tmp = tempdir().unwrap();
+        let project = tmp.path().join("work").join("api");
+        std::fs::create_dir_all(&project).unwrap();
+        let id = new_id();
+        std::fs::write(project.join("history.jsonl"), format!(
+            "{{\"_schema\":\"history\",\"_version\":\"1.0\"}}\n{{\"id\":\"{}\",\"title\":\"other\"}}\n{{\"id\":\"{id}\",\"title\":\"shipped\"}}\n",
+            new_id()
+        )).unwrap();
+
+        let (path, entry) = find(tmp.path(), tmp.path(), SymlinkPolicy::Follow, &id.to_lowercase()).unwrap();
+        assert_eq!(path, project.join("history.jsonl"));
+        assert_eq!(entry["title"], "shipped");
+        assert!(find(tmp.path(), tmp.path(), SymlinkPolicy::Follow, &new_id()).is_none());
+    }
+
+    #[test]
+    fn find_reads_compressed_archives_and_honors_the_symlink_policy() {
+        let tmp = tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        let project = vault.join("work").join("api");
+        std::fs::create_dir_all(&project).unwrap();
+        let archived = new_id();
+        compress::write_compressed(
+            &compress::compressed_path(&project.join(compress::HISTORY_ARCHIVE)),
+            &format!("{{\"id\":\"{archived}\",\"title\":\"old\"}}\n"),
+        ).unwrap();
+        let (path, entry) = find(&vault, &vault, SymlinkPolicy::Follow, &archived).unwrap();
+        assert_eq!(path, project.join("history.archive.jsonl.zst"));
+        assert_eq!(entry["title"], "old");
+
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        let linked = new_id();
+        std::fs::write(outside.join("history.jsonl"), format!("{{\"id\":\"{linked}\"}}\n")).unwrap();
+        std::os::unix::fs::symlink(&outside, vault.join("work").join("linked")).unwrap();
+        assert!(find(&vault, &vault, SymlinkPolicy::Follow, &linked).is_some());
+        assert!(find(&vault, &vault, SymlinkPolicy::Skip, &linked).is_none());
+    }
+}
@@ -1,10 +1,16 @@
-use crate::vault::types::{Frontmatter, VaultError};
+use crate::vault::types::{Frontmatter, Status, VaultError};
+use std::collections::BTreeMap;
 
 /// Parse frontmatter from a vault file's content.
 /// Expects `---` delimiters. Returns (Frontmatter, body).
 /// `type` is required; all other fields are optional.
 /// Unknown fields are ignored (forward compatible).
 pub fn parse_frontmatter(content: &str) -> Result<(Frontmatter, String), VaultError> {
+    parse_frontmatter_with(content, &BTreeMap::new())
+}
+
+/// Like `parse_frontmatter`, also accepting the extra states from `statuses:` in config.yml.
+pub fn parse_frontmatter_with(content: &str, statuses: &BTreeMap<String, Status>) -> Result<(Frontmatter, String), VaultError> {
     let trimmed = content.trim_start();
 
     if !trimmed.starts_with("---") {
@@ -21,11 +27,76 @@ pub fn parse_frontmatter(content: &str) -> Result<(Frontmatter, String), VaultEr
     let body_start = closing_pos + 4; // skip \n---
     let body = after_opening[body_start..].trim_start_matches('\n').to_string();
 
-    let frontmatter: Frontmatter = serde_yaml::from_str(yaml_str)?;
+    let mut frontmatter: Frontmatter = serde_yaml::from_str(yaml_str)?;
+    // Lenient parsing drops states it doesn't know; the configured ones are looked up here
+    if frontmatter.status.is_none()
+        && !statuses.is_empty()
+        && let Ok(serde_yaml::Value::Mapping(map)) = serde_yaml::from_str::<serde_yaml::Value>(yaml_str)
+        && let Some(raw) = map.get("status").and_then(|v| v.as_str())
+    {
+        frontmatter.status = Status::resolve(raw, statuses);
+    }
 
     Ok((frontmatter, body))
 }
 
+/// Return the raw YAML between the `---` delimiters, if the content has frontmatter.
+/// Used to read fields that `Frontmatter` does not model.
+pub fn frontmatter_yaml(content: &str) -> Option<&str> {
+    let after_opening = content.trim_start().strip_prefix("---")?;
+    let closing_pos = after_opening.find("\n---")?;
+    Some(&after_opening[..closing_pos])
+}
+
+/// Frontmatter YAML for a rewrite of `previous`: each `managed` key takes its new value in the
+/// place it already had, keys missing from `previous` follow in `managed` order, and every
+/// other top-level entry (its value, nested lines, and comments) is kept as written.
+pub fn merge_frontmatter(previous: &str, managed: &[(&str, String)]) -> String {
+    // Top-level entries of the old block: (key, raw lines). Lines before the first key, and
+    // comments or nested lines, travel with the entry they sit in.
+    let mut entries: Vec<(Option<String>, String)> = Vec::new();
+    for line in frontmatter_yaml(previous).unwrap_or("").lines() {
+        let key = (!line.starts_with([' ', '\t', '#', '-']))
+            .then(|| line.split_once(':').map(|(k, _)| k.trim()))
+            .flatten()
+            .filter(|k| !k.is_empty());
+        match key {
+            Some(k) => entries.push((Some(k.to_string()), format!("{line}\n"))),
+            None if line.trim().is_empty() && entries.is_empty() => {}
+            None => match entries.last_mut() {
+                Some((_, raw)) => {
+                    raw.push_str(line);
+                    raw.push('\n');
+                }
+                None => entries.push((None, format!("{line}\n"))),
+            },
+        }
+    }
+
+    let mut out = String::new();
+    for (key, raw) in &entries {
+        match managed.iter().find(|(k, _)| Some(*k) == key.as_deref()) {
+            Some((k, v)) => out.push_str(&format!("{k}: {v}\n")),
+            None => out.push_str(raw),
+        }
+    }
+    for (k, v) in managed {
+        if !entries.iter().any(|(key, _)| key.as_deref() == Some(*k)) {
+            out.push_str(&format!("{k}: {v}\n"));
+        }
+    }
+    out
+}
+
+/// `content` with its frontmatter merged with `managed` (see `merge_frontmatter`) and the body
+/// left byte for byte. None if the content has no frontmatter.
+pub fn replace_frontmatter(content: &str, managed: &[(&str, String)]) -> Option<String> {
+    let yaml = frontmatter_yaml(content)?;
+    let start = content.len() - content.trim_start().len();
+    let rest = &content[start + 3 + yaml.len() + 4..];
+    Some(format!("---\n{}---{rest}", merge_frontmatter(content, managed)))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -164,6 +235,22 @@ mod tests {
         assert_eq!(fm.status, Some(Status::Active));
     }
 
+    #[test]
+    fn status_variants_normalize() {
+        for (raw, expected) in [("done", Status::Completed), ("In Progress", Status::Active), ("on-hold", Status::Paused), ("cancelled", Status::Abandoned)] {
+            let content = format!("---\ntype: project\nstatus: {raw}\n---\nbody\n");
+            let (fm, _) = parse_frontmatter(&content).unwrap();
+            assert_eq!(fm.status, Some(expected), "{raw}");
+        }
+        let extra = std::collections::BTreeMap::from([("review".to_string(), Status::Active)]);
+        assert_eq!(Status::resolve("Review", &extra), Some(Status::Active));
+        assert_eq!(Status::resolve("draft", &extra), None);
+
+        let content = "---\ntype: project\nstatus: Review\n---\nbody\n";
+        assert_eq!(parse_frontmatter_with(content, &extra).unwrap().0.status, Some(Status::Active));
+        assert_eq!(parse_frontmatter(content).unwrap().0.status, None);
+    }
+
     #[test]
     fn unknown_status_becomes_none() {
         let content = "---\ntype: project\nstatus: draft\n---\nbody\n";
@@ -209,4 +296,31 @@ mod tests {
         let (fm, _) = result.unwrap();
         assert!(fm.can_read.is_empty());
     }
+
+    #[test]
+    fn merge_frontmatter_keeps_unknown_fields() {
+        let previous = "---\nchat_name: api\npriority: high # set by hand\nstatus: active\ntags:\n  - backend\n  - q3\nupdated: 2026-01-01 09:00\n---\n# api\n";
+        let managed = vec![
+            ("chat_name", "api".to_string()),
+            ("updated", "2026-02-01 10:00".to_string()),
+            ("status", "blocked".to_string()),
+            ("type", "project".to_string()),
+        ];
+        assert_eq!(
+            merge_frontmatter(previous, &managed),
+            "chat_name: api\npriority: high # set by hand\nstatus: blocked\ntags:\n  - backend\n  - q3\nupdated: 2026-02-01 10:00\ntype: project\n"
+        );
+        assert_eq!(merge_frontmatter("# no frontmatter\n", &managed[..2]), "chat_name: api\nupdated: 2026-02-01 10:00\n");
+    }
+
+    #[test]
+    fn replace_frontmatter_keeps_the_body() {
+        let content = "---\nstatus: active\ntags:\n  - old\n---\n\n# api\n\n## Focus\nShip\n";
+        let tags = [("tags", "[new, q3]".to_string())];
+        assert_eq!(
+            replace_frontmatter(content, &tags).unwrap(),
+            "---\nstatus: active\ntags: [new, q3]\n---\n\n# api\n\n## Focus\nShip\n"
+        );
+        assert!(replace_frontmatter("# api\n", &tags).is_none());
+    }
 }
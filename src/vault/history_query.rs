@@ -0,0 +1,305 @@
+use crate::vault::Status;
+use chrono::NaiveDate;
+use regex::{Regex, RegexBuilder};
+use std::collections::BTreeMap;
+
+/// A parsed history `query`: words and quoted phrases to find in an entry's title, body
+/// and focus, plus `status:`, `source:`, `after:` and `before:` filters. Terms side by side
+/// must all match (`AND` may be written out); `OR` joins alternatives, parentheses group,
+/// and a leading `-` negates a term. `/pattern/` is a case-insensitive regex. `status:`
+/// matches any name for the same status, so `status:done` finds `completed` entries.
+///
+/// `status:blocked source:code after:2026-01-01`, `(retry OR backoff) -"rate limit"`.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    /// None matches every entry.
+    expr: Option<Expr>,
+    /// The vault's extra states from `statuses:` in config.yml.
+    statuses: BTreeMap<String, Status>,
+}
+
+/// The parts of a history entry a query looks at. Legacy markdown entries have no status
+/// or source, so they never match those filters.
+pub struct Candidate<'a> {
+    pub text: &'a str,
+    pub status: &'a str,
+    pub source: &'a str,
+    /// `YYYY-MM-DD`, possibly followed by a time.
+    pub date: &'a str,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Text(String),
+    Pattern(Regex),
+    Status(Vec<String>),
+    Source(Vec<String>),
+    /// Strictly before the day.
+    Before(NaiveDate),
+    /// Strictly after the day.
+    After(NaiveDate),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Term { negate: bool, field: Option<String>, value: String, regex: bool },
+}
+
+const FIELDS: [&str; 4] = ["status", "source", "before", "after"];
+
+/// Deepest nesting of parenthesized groups a query may have.
+const MAX_DEPTH: usize = 32;
+
+impl HistoryQuery {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        Self::parse_with(query, &BTreeMap::new())
+    }
+
+    /// Like `parse`, with `status:` also resolving the vault's extra `statuses`.
+    pub fn parse_with(query: &str, statuses: &BTreeMap<String, Status>) -> Result<Self, String> {
+        let tokens = tokenize(query)?;
+        let statuses = statuses.clone();
+        if tokens.is_empty() {
+            return Ok(Self { expr: None, statuses });
+        }
+        let mut pos = 0;
+        let expr = parse_or(&tokens, &mut pos, 0)?;
+        if pos < tokens.len() {
+            return Err("unbalanced ')' in history query".to_string());
+        }
+        Ok(Self { expr: Some(expr), statuses })
+    }
+
+    pub fn matches(&self, c: &Candidate) -> bool {
+        let Some(expr) = &self.expr else { return true };
+        eval(expr, c, &c.text.to_lowercase(), &self.statuses)
+    }
+}
+
+fn eval(expr: &Expr, c: &Candidate, text_lower: &str, statuses: &BTreeMap<String, Status>) -> bool {
+    let day = || c.date.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    match expr {
+        Expr::And(all) => all.iter().all(|e| eval(e, c, text_lower, statuses)),
+        Expr::Or(any) => any.iter().any(|e| eval(e, c, text_lower, statuses)),
+        Expr::Not(e) => !eval(e, c, text_lower, statuses),
+        Expr::Text(t) => text_lower.contains(t.as_str()),
+        Expr::Pattern(re) => re.is_match(c.text),
+        Expr::Status(values) => {
+            let stored = Status::resolve(c.status, statuses);
+            values.iter().any(|v| c.status.eq_ignore_ascii_case(v) || stored.is_some_and(|s| Status::resolve(v, statuses) == Some(s)))
+        }
+        Expr::Source(values) => values.iter().any(|v| c.source.eq_ignore_ascii_case(v)),
+        Expr::Before(d) => day().is_some_and(|day| day < *d),
+        Expr::After(d) => day().is_some_and(|day| day > *d),
+    }
+}
+
+fn parse_or(tokens: &[Token], pos: &mut usize, depth: usize) -> Result<Expr, String> {
+    let mut alternatives = vec![parse_and(tokens, pos, depth)?];
+    while tokens.get(*pos) == Some(&Token::Or) {
+        *pos += 1;
+        alternatives.push(parse_and(tokens, pos, depth)?);
+    }
+    Ok(if alternatives.len() == 1 { alternatives.remove(0) } else { Expr::Or(alternatives) })
+}
+
+fn parse_and(tokens: &[Token], pos: &mut usize, depth: usize) -> Result<Expr, String> {
+    let mut all = Vec::new();
+    while let Some(token) = tokens.get(*pos) {
+        match token {
+            Token::Close | Token::Or => break,
+            Token::And => *pos += 1,
+            Token::Open => {
+                if depth >= MAX_DEPTH {
+                    return Err(format!("history query nests groups more than {MAX_DEPTH} deep"));
+                }
+                *pos += 1;
+                all.push(parse_or(tokens, pos, depth + 1)?);
+                if tokens.get(*pos) != Some(&Token::Close) {
+                    return Err("unbalanced '(' in history query".to_string());
+                }
+                *pos += 1;
+            }
+            Token::Term { negate, field, value, regex } => {
+                *pos += 1;
+                let term = term(field.as_deref(), value, *regex)?;
+                all.push(if *negate { Expr::Not(Box::new(term)) } else { term });
+            }
+        }
+    }
+    match all.len() {
+        0 => Err("history query has an empty group or a dangling AND/OR".to_string()),
+        1 => Ok(all.remove(0)),
+        _ => Ok(Expr::And(all)),
+    }
+}
+
+fn term(field: Option<&str>, value: &str, regex: bool) -> Result<Expr, String> {
+    if regex {
+        return RegexBuilder::new(value)
+            .case_insensitive(true)
+            .build()
+            .map(Expr::Pattern)
+            .map_err(|e| format!("invalid regex /{value}/: {e}"));
+    }
+    let Some(field) = field else { return Ok(Expr::Text(value.to_lowercase())) };
+    if value.is_empty() {
+        return Err(format!("'{field}:' needs a value"));
+    }
+    let values = || value.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect();
+    let date = || NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("'{field}:{value}' is not a date (YYYY-MM-DD)"));
+    match field {
+        "status" => Ok(Expr::Status(values())),
+        "source" => Ok(Expr::Source(values())),
+        "before" => date().map(Expr::Before),
+        _ => date().map(Expr::After),
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            i += 1;
+            continue;
+        }
+        let negate = c == '-' && chars.get(i + 1).is_some_and(|n| !n.is_whitespace());
+        if negate {
+            i += 1;
+        }
+        match chars.get(i) {
+            Some('"') => {
+                let value = delimited(&chars, &mut i, '"')?;
+                tokens.push(Token::Term { negate, field: None, value, regex: false });
+            }
+            Some('/') => {
+                let value = delimited(&chars, &mut i, '/')?;
+                tokens.push(Token::Term { negate, field: None, value, regex: true });
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if !negate && (word == "AND" || word == "OR") {
+                    tokens.push(if word == "AND" { Token::And } else { Token::Or });
+                    continue;
+                }
+                let field = word.split_once(':')
+                    .map(|(f, _)| f.to_lowercase())
+                    .filter(|f| FIELDS.contains(&f.as_str()));
+                let value = match &field {
+                    Some(f) => {
+                        let rest = word[f.len() + 1..].to_string();
+                        if rest.is_empty() && chars.get(i) == Some(&'"') { delimited(&chars, &mut i, '"')? } else { rest }
+                    }
+                    None if chars.get(i) == Some(&'"') => return Err(format!("unexpected '\"' after '{word}' in history query")),
+                    None => word,
+                };
+                tokens.push(Token::Term { negate, field, value, regex: false });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// The text between `chars[*i]` (the opening `delim`) and the next unescaped `delim`,
+/// leaving `*i` just past the closing one.
+fn delimited(chars: &[char], i: &mut usize, delim: char) -> Result<String, String> {
+    let start = *i + 1;
+    let mut end = start;
+    while end < chars.len() && chars[end] != delim {
+        if chars[end] == '\\' && delim == '/' {
+            end += 1;
+        }
+        end += 1;
+    }
+    if end >= chars.len() {
+        return Err(format!("unclosed {delim} in history query"));
+    }
+    *i = end + 1;
+    Ok(chars[start..end].iter().collect())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn entry<'a>(text: &'a str, status: &'a str, source: &'a str, date: &'a str) -> Candidate<'a> {
+        Candidate { text, status, source, date }
+    }
+
+    #[test]
+    fn filters_combine_with_and_or_and_negation() {
+        let blocked = entry("Waiting on the Rate Limit fix", "blocked", "code", "2026-02-03T10:00:00");
+        let shipped = entry("Shipped retry backoff", "completed", "desktop", "2025-12-30");
+        let legacy = entry("Old retry notes", "", "", "2025-06-01");
+        let hits = |q: &str| {
+            let q = HistoryQuery::parse(q).unwrap();
+            [&blocked, &shipped, &legacy].iter().map(|e| q.matches(e)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(hits(""), [true, true, true]);
+        assert_eq!(hits("status:blocked source:code after:2026-01-01"), [true, false, false]);
+        assert_eq!(hits("retry"), [false, true, true]);
+        assert_eq!(hits("retry -status:completed"), [false, false, true]);
+        assert_eq!(hits("\"rate limit\" OR source:desktop"), [true, true, false]);
+        assert_eq!(hits("(status:blocked OR status:completed) AND before:2026-01-01"), [false, true, false]);
+        assert_eq!(hits("status:blocked,completed"), [true, true, false]);
+        assert_eq!(hits("/ret(ry|ain) back/"), [false, true, false]);
+        assert_eq!(hits("after:2026-02-03"), [false, false, false]);
+        // Unknown prefixes are plain text
+        assert_eq!(hits("fix:"), [false, false, false]);
+        assert_eq!(hits(&format!("{}retry{}", "(".repeat(32), ")".repeat(32))), [false, true, true]);
+    }
+
+    #[test]
+    fn status_filters_match_any_name_for_the_status() {
+        let statuses = BTreeMap::from([("review".to_string(), Status::Active)]);
+        let done = entry("Shipped", "completed", "", "2026-01-01");
+        let review = entry("Up for review", "review", "", "2026-01-02");
+        let hits = |q: &str| {
+            let q = HistoryQuery::parse_with(q, &statuses).unwrap();
+            [&done, &review].iter().map(|e| q.matches(e)).collect::<Vec<_>>()
+        };
+        assert_eq!(hits("status:done"), [true, false]);
+        assert_eq!(hits("status:active"), [false, true]);
+        assert_eq!(hits("status:review"), [false, true]);
+        assert_eq!(hits("-status:finished"), [false, true]);
+        assert!(!HistoryQuery::parse("status:active").unwrap().matches(&review));
+    }
+
+    #[test]
+    fn malformed_queries_say_what_is_wrong() {
+        for (q, err) in [
+            ("after:yesterday", "'after:yesterday' is not a date"),
+            ("/(unclosed/", "invalid regex"),
+            ("(retry OR", "dangling AND/OR"),
+            ("(retry", "unbalanced '('"),
+            ("retry)", "unbalanced ')'"),
+            ("\"rate limit", "unclosed \""),
+            ("status:", "'status:' needs a value"),
+            (&"(".repeat(50_000), "more than 32 deep"),
+        ] {
+            let e = HistoryQuery::parse(q).unwrap_err();
+            assert!(e.contains(err), "{q}: {e}");
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Where Obsidian's Daily notes plugin keeps its settings, relative to the vault.
+pub const OBSIDIAN_SETTINGS: &str = ".obsidian/daily-notes.json";
+
+/// Most days one `journal` search reads.
+pub const MAX_RANGE_DAYS: i64 = 366;
+
+/// How daily notes are laid out: `daily/YYYY-MM-DD.md` with no template, unless the vault's
+/// Obsidian Daily notes settings say otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DailyNotes {
+    /// Vault-relative folder; empty for the vault root.
+    pub folder: String,
+    /// File name as a Moment.js format, e.g. `YYYY-MM-DD` or `YYYY/MM/YYYY-MM-DD`.
+    pub format: String,
+    /// Vault-relative note a new daily note starts from, with or without `.md`.
+    pub template: String,
+}
+
+impl Default for DailyNotes {
+    fn default() -> Self {
+        Self { folder: "daily".to_string(), format: "YYYY-MM-DD".to_string(), template: String::new() }
+    }
+}
+
+/// A timestamped bullet in a daily note.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Entry {
+    pub date: String,
+    pub time: String,
+    /// The leading `#tag`, e.g. `work/api` for `#work/api`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub text: String,
+    /// Vault-relative path of the daily note.
+    pub path: String,
+}
+
+/// A daily note with one more bullet, before it is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Planned {
+    pub path: PathBuf,
+    pub previous: Option<String>,
+    pub content: String,
+}
+
+impl DailyNotes {
+    /// The settings in the vault's `.obsidian/daily-notes.json`, or the defaults when there
+    /// is none or it doesn't parse.
+    pub fn load(vault_root: &Path) -> Self {
+        let mut notes = std::fs::read_to_string(vault_root.join(OBSIDIAN_SETTINGS)).ok()
+            .and_then(|s| serde_json::from_str::<DailyNotes>(&s).ok())
+            .unwrap_or_default();
+        if notes.format.trim().is_empty() {
+            notes.format = DailyNotes::default().format;
+        }
+        notes
+    }
+
+    /// The daily note for `date`.
+    pub fn path(&self, vault_root: &Path, date: NaiveDate) -> PathBuf {
+        let name = date.format(&moment_to_chrono(&self.format)).to_string();
+        vault_root.join(self.folder.trim_matches('/')).join(format!("{name}.md"))
+    }
+
+    /// The daily note for `now` with `bullet` appended, starting from the template when
+    /// the note doesn't exist yet.
+    pub fn plan(&self, vault_root: &Path, now: NaiveDateTime, bullet: &str) -> std::io::Result<Planned> {
+        let path = self.path(vault_root, now.date());
+        let previous = match std::fs::read_to_string(&path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let content = self.with_bullet(vault_root, now, &path, previous.as_deref(), bullet);
+        Ok(Planned { path, previous, content })
+    }
+
+    /// Append `bullet` to the daily note for `now` as `plan` lays it out. The note is locked
+    /// from the read until the bullet is in, so concurrent appends all land.
+    pub fn append(&self, vault_root: &Path, now: NaiveDateTime, bullet: &str) -> std::io::Result<Planned> {
+        let path = self.path(vault_root, now.date());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let existed = path.exists();
+        let mut file = crate::vault::jsonl::open_locked(&path)?;
+        let mut current = String::new();
+        file.read_to_string(&mut current)?;
+        let previous = (existed || !current.is_empty()).then_some(current);
+        let content = self.with_bullet(vault_root, now, &path, previous.as_deref(), bullet);
+        let written = previous.as_ref().map_or(0, String::len);
+        file.write_all(&content.as_bytes()[written..])?;
+        Ok(Planned { path, previous, content })
+    }
+
+    /// `previous`, or a new note from the template, with `bullet` on a line of its own at the end.
+    fn with_bullet(&self, vault_root: &Path, now: NaiveDateTime, path: &Path, previous: Option<&str>, bullet: &str) -> String {
+        let mut content = match previous {
+            Some(content) => content.to_string(),
+            None => self.new_note(vault_root, now, path),
+        };
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(bullet);
+        content
+    }
+
+    /// The timestamped bullets in the daily notes from `from` through `to`, oldest first.
+    pub fn entries(&self, vault_root: &Path, from: NaiveDate, to: NaiveDate) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        for date in from.iter_days().take_while(|d| *d <= to) {
+            let path = self.path(vault_root, date);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let rel = path.strip_prefix(vault_root).unwrap_or(&path).to_string_lossy().to_string();
+            let mut open: Option<Entry> = None;
+            for line in content.lines() {
+                if let Some(ref mut entry) = open
+                    && let Some(more) = line.strip_prefix("  ")
+                    && !more.trim_start().starts_with("- ")
+                {
+                    entry.text.push('\n');
+                    entry.text.push_str(more.trim_end());
+                    continue;
+                }
+                entries.extend(open.take());
+                open = parse_bullet(line).map(|(time, tag, text)| Entry {
+                    date: date.format("%Y-%m-%d").to_string(),
+                    time: time.format("%H:%M").to_string(),
+                    tag,
+                    text,
+                    path: rel.clone(),
+                });
+            }
+            entries.extend(open);
+        }
+        entries
+    }
+
+    fn new_note(&self, vault_root: &Path, now: NaiveDateTime, path: &Path) -> String {
+        let template = self.template.trim();
+        if template.is_empty() {
+            return String::new();
+        }
+        let file = if template.ends_with(".md") { template.to_string() } else { format!("{template}.md") };
+        let Ok(template) = std::fs::read_to_string(vault_root.join(file.trim_start_matches('/'))) else {
+            return String::new();
+        };
+        let title = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        render_template(&template, now, &self.format, &title)
+    }
+}
+
+/// `- HH:MM text`, with `#tag` ahead of the text when there is one. Lines after the first
+/// are indented under the bullet.
+pub fn bullet(time: NaiveTime, tag: Option<&str>, text: &str) -> String {
+    let mut lines = text.trim().lines();
+    let mut out = format!("- {}", time.format("%H:%M"));
+    if let Some(tag) = tag {
+        out.push_str(&format!(" #{tag}"));
+    }
+    out.push_str(&format!(" {}\n", lines.next().unwrap_or("").trim_end()));
+    for line in lines {
+        out.push_str(&format!("  {}\n", line.trim_end()));
+    }
+    out
+}
+
+fn parse_bullet(line: &str) -> Option<(NaiveTime, Option<String>, String)> {
+    let rest = line.strip_prefix("- ")?;
+    let time = NaiveTime::parse_from_str(rest.get(..5)?, "%H:%M").ok()?;
+    let rest = rest.get(5..)?;
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let rest = rest.trim();
+    match rest.strip_prefix('#') {
+        Some(tagged) => {
+            let (tag, text) = tagged.split_once(' ').unwrap_or((tagged, ""));
+            Some((time, Some(tag.to_string()), text.trim().to_string()))
+        }
+        None => Some((time, None, rest.to_string())),
+    }
+}
+
+/// Obsidian's core template variables: `{{title}}`, `{{date}}` and `{{time}}`, the latter
+/// two optionally with a Moment.js format (`{{date:dddd, MMMM D}}`).
+fn render_template(template: &str, now: NaiveDateTime, date_format: &str, title: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let inner = rest[start + 2..start + 2 + len].trim();
+        let (name, format) = inner.split_once(':').map_or((inner, None), |(n, f)| (n.trim(), Some(f)));
+        let value = match (name, format) {
+            ("title", None) => Some(title.to_string()),
+            ("date", format) => Some(now.format(&moment_to_chrono(format.unwrap_or(date_format))).to_string()),
+            ("time", format) => Some(now.format(&moment_to_chrono(format.unwrap_or("HH:mm"))).to_string()),
+            _ => None,
+        };
+        out.push_str(&rest[..start]);
+        match value {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + len + 2]),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A Moment.js date format as a chrono one. Covers the tokens daily note names use; other
+/// letters, and text in `[brackets]`, are kept as written.
+pub fn moment_to_chrono(format: &str) -> String {
+    const TOKENS: &[(&str, &str)] = &[
+        ("YYYY", "%Y"), ("YY", "%y"),
+        ("MMMM", "%B"), ("MMM", "%b"), ("MM", "%m"), ("M", "%-m"),
+        ("dddd", "%A"), ("ddd", "%a"),
+        ("DDDD", "%j"), ("DD", "%d"), ("D", "%-d"),
+        ("HH", "%H"), ("H", "%-H"), ("hh", "%I"), ("h", "%-I"),
+        ("mm", "%M"), ("ss", "%S"), ("A", "%p"), ("a", "%P"),
+        ("ww", "%V"), ("w", "%-V"),
+    ];
+    let mut out = String::new();
+    let mut rest = format;
+    'scan: while let Some(c) = rest.chars().next() {
+        if c == '[' && let Some(end) = rest.find(']') {
+            out.push_str(&rest[1..end].replace('%', "%%"));
+            rest = &rest[end + 1..];
+            continue;
+        }
+        for (token, spec) in TOKENS {
+            if let Some(after) = rest.strip_prefix(token) {
+                out.push_str(spec);
+                rest = after;
+                continue 'scan;
+            }
+        }
+        if c == '%' {
+            out.push_str("%%");
+        } else {
+            out.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn appends_bullets_to_the_obsidian_daily_note_and_reads_them_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path();
+        std::fs::create_dir_all(vault.join(".obsidian")).unwrap();
+        std::fs::write(vault.join(OBSIDIAN_SETTINGS), r#"{"folder": "journal/", "format": "YYYY/MM/YYYY-MM-DD ddd", "template": "templates/daily"}"#).unwrap();
+        std::fs::create_dir_all(vault.join("templates")).unwrap();
+        std::fs::write(vault.join("templates/daily.md"), "# {{title}}\nWritten {{date:dddd, MMMM D}} at {{time}} {{weather}}\n\n## Log").unwrap();
+
+        let notes = DailyNotes::load(vault);
+        let path = notes.path(vault, at("2026-03-02 09:00").date());
+        assert_eq!(path, vault.join("journal/2026/03/2026-03-02 Mon.md"));
+
+        let first = notes.plan(vault, at("2026-03-02 09:15"), &bullet(NaiveTime::from_hms_opt(9, 15, 0).unwrap(), Some("work/api"), "Paged on 502s\nrolled back")).unwrap();
+        assert!(first.previous.is_none());
+        assert_eq!(
+            first.content,
+            "# 2026-03-02 Mon\nWritten Monday, March 2 at 09:15 {{weather}}\n\n## Log\n- 09:15 #work/api Paged on 502s\n  rolled back\n"
+        );
+        let written = notes.append(vault, at("2026-03-02 09:15"), &bullet(NaiveTime::from_hms_opt(9, 15, 0).unwrap(), Some("work/api"), "Paged on 502s\nrolled back")).unwrap();
+        assert_eq!(written, first);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), first.content);
+        let second = notes.append(vault, at("2026-03-02 17:40"), &bullet(NaiveTime::from_hms_opt(17, 40, 0).unwrap(), None, "Wrote the postmortem")).unwrap();
+        assert_eq!(second.previous.as_deref(), Some(first.content.as_str()));
+
+        let entries = notes.entries(vault, at("2026-03-01 00:00").date(), at("2026-03-02 00:00").date());
+        let seen: Vec<_> = entries.iter().map(|e| (e.time.as_str(), e.tag.as_deref(), e.text.as_str())).collect();
+        assert_eq!(seen, [("09:15", Some("work/api"), "Paged on 502s\nrolled back"), ("17:40", None, "Wrote the postmortem")]);
+        assert_eq!(entries[0].path, "journal/2026/03/2026-03-02 Mon.md");
+
+        // Without Obsidian settings, daily/YYYY-MM-DD.md and no template
+        let bare = tempfile::tempdir().unwrap();
+        let plain = DailyNotes::load(bare.path());
+        assert_eq!(plain.path(bare.path(), at("2026-03-02 09:00").date()), bare.path().join("daily/2026-03-02.md"));
+        assert_eq!(plain.plan(bare.path(), at("2026-03-02 09:00"), "- 09:00 hi\n").unwrap().content, "- 09:00 hi\n");
+
+        // Appends made at once all land
+        let writers: Vec<_> = (0..8).map(|i| {
+            let (plain, root) = (plain.clone(), bare.path().to_path_buf());
+            std::thread::spawn(move || plain.append(&root, at("2026-03-02 09:00"), &format!("- 09:00 note {i}\n")).unwrap())
+        }).collect();
+        writers.into_iter().for_each(|w| { w.join().unwrap(); });
+        let note = std::fs::read_to_string(bare.path().join("daily/2026-03-02.md")).unwrap();
+        assert_eq!(note.lines().count(), 8, "{note}");
+    }
+
+    #[test]
+    fn moment_formats_map_to_chrono() {
+        assert_eq!(moment_to_chrono("YYYY-MM-DD"), "%Y-%m-%d");
+        assert_eq!(moment_to_chrono("[Week] ww, gggg"), "Week %V, gggg");
+        assert_eq!(moment_to_chrono("D MMM YY"), "%-d %b %y");
+    }
+}
@@ -0,0 +1,269 @@
+use crate::config::loader::{DurabilityConfig, FsyncPolicy};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Files appended to under `fsync: periodic` since the last sync, and when that sync was.
+/// One sync covers every file, so the record is shared by all writers in the process.
+struct Pending {
+    synced_at: Option<Instant>,
+    files: Vec<PathBuf>,
+}
+
+static PENDING: Mutex<Pending> = Mutex::new(Pending { synced_at: None, files: Vec::new() });
+
+/// What a tail repair did to a file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TailRepair {
+    /// The last line was whole JSON missing only its newline, which was added.
+    Terminated,
+    /// The last line was cut off mid-write and has been removed.
+    Cut { fragment: String },
+}
+
+/// Append `entry` as one line of the JSONL file at `path`, creating it with a `schema_name`
+/// header. The line goes out in a single write. A previous line left unterminated by a crash
+/// is closed first, so the torn fragment can't swallow the new entry, and the file is synced
+/// as `durability` says.
+pub fn append(path: &Path, schema_name: &str, entry: &str, durability: &DurabilityConfig) -> std::io::Result<()> {
+    if entry.contains(['\n', '\r']) {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "a JSONL entry must fit on one line"));
+    }
+    let mut file = open_locked(path)?;
+    let text = line_text(last_byte(&mut file)?, schema_name, entry);
+    file.write_all(text.as_bytes())?;
+    match durability.fsync {
+        FsyncPolicy::Always => file.sync_data(),
+        FsyncPolicy::Periodic => sync_periodically(path, &file, Duration::from_secs(durability.interval_seconds)),
+        FsyncPolicy::Never => Ok(()),
+    }
+}
+
+/// Replace the JSONL file at `path` with `content`, built from `read`, what the file held
+/// earlier. Lines appended since are kept after `content`, and appends wait for the swap, so
+/// none are lost. Fails when the file was rewritten rather than appended to in between.
+pub fn replace(path: &Path, read: &str, content: &str) -> std::io::Result<()> {
+    rewrite(path, |current| {
+        let Some(appended) = current.strip_prefix(read) else {
+            return Err(std::io::Error::other(format!("{} changed while it was being rewritten; run again", path.display())));
+        };
+        Ok(Some(format!("{content}{}", appended.trim_start_matches('\n'))))
+    })
+}
+
+/// Rewrite the JSONL file at `path` as `edit` returns it from the current content, or remove
+/// it when `edit` returns None. The file stays locked throughout, so appends wait, and is
+/// swapped in by a rename, so a crash leaves the old or the new file whole.
+pub fn rewrite(path: &Path, edit: impl FnOnce(&str) -> std::io::Result<Option<String>>) -> std::io::Result<()> {
+    let mut file = open_locked(path)?;
+    let mut current = String::new();
+    file.read_to_string(&mut current)?;
+    let Some(content) = edit(&current)? else {
+        return std::fs::remove_file(path);
+    };
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp = path.with_file_name(name);
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Open `path` for appending, creating it, with an exclusive lock held until the file is
+/// dropped. A file replaced while the lock was awaited is let go and the new one opened.
+pub fn open_locked(path: &Path) -> std::io::Result<File> {
+    loop {
+        let file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+        file.lock()?;
+        let (held, current) = (file.metadata()?, std::fs::metadata(path));
+        if current.is_ok_and(|c| c.dev() == held.dev() && c.ino() == held.ino()) {
+            return Ok(file);
+        }
+    }
+}
+
+/// What `append` would add to `path` right now.
+pub fn append_text(path: &Path, schema_name: &str, entry: &str) -> String {
+    let last = File::open(path).and_then(|mut f| last_byte(&mut f)).unwrap_or(None);
+    line_text(last, schema_name, entry)
+}
+
+/// The last line of `content` when it has no newline after it: cut off mid-write, or whole
+/// and missing only the newline. None when the content ends cleanly.
+pub fn unterminated_tail(content: &str) -> Option<&str> {
+    if content.is_empty() || content.ends_with('\n') {
+        return None;
+    }
+    Some(content.rsplit_once('\n').map_or(content, |(_, tail)| tail))
+}
+
+/// Close or cut off an unterminated last line, as a crash mid-append leaves. None when the
+/// file already ends cleanly.
+pub fn repair_tail(path: &Path) -> std::io::Result<Option<TailRepair>> {
+    let content = std::fs::read(path)?;
+    if content.is_empty() || content.ends_with(b"\n") {
+        return Ok(None);
+    }
+    let start = content.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    let tail = &content[start..];
+    if serde_json::from_slice::<serde_json::Value>(tail).is_ok() {
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        file.write_all(b"\n")?;
+        file.sync_data()?;
+        return Ok(Some(TailRepair::Terminated));
+    }
+    let file = OpenOptions::new().write(true).open(path)?;
+    file.set_len(start as u64)?;
+    file.sync_data()?;
+    Ok(Some(TailRepair::Cut { fragment: String::from_utf8_lossy(tail).into_owned() }))
+}
+
+/// Entries of the JSONL file at `path`, decompressing a `.zst` archive. The schema header,
+/// blank lines, and lines that don't parse are skipped; a missing file has none.
+pub fn read_entries<T: serde::de::DeserializeOwned>(path: &Path) -> Vec<T> {
+    let Ok(content) = crate::vault::compress::read_to_string(path) else {
+        return Vec::new();
+    };
+    parse_entries(&content)
+}
+
+/// Like `read_entries`, for content already read.
+pub fn parse_entries<T: serde::de::DeserializeOwned>(content: &str) -> Vec<T> {
+    content.lines()
+        .filter(|l| !l.trim().is_empty() && !l.starts_with("{\"_schema\""))
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+fn last_byte(file: &mut File) -> std::io::Result<Option<u8>> {
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::Start(len - 1))?;
+    let mut byte = [0u8];
+    file.read_exact(&mut byte)?;
+    Ok(Some(byte[0]))
+}
+
+/// The header for a new file, or a newline closing an unterminated last line, then the entry.
+fn line_text(last: Option<u8>, schema_name: &str, entry: &str) -> String {
+    let lead = match last {
+        None => format!("{{\"_schema\": \"{schema_name}\", \"_version\": \"1.0\"}}\n"),
+        Some(b'\n') => String::new(),
+        Some(_) => "\n".to_string(),
+    };
+    format!("{lead}{entry}\n")
+}
+
+/// Sync `file` and every other file appended to since the last sync, unless that sync was
+/// less than `interval` ago.
+fn sync_periodically(path: &Path, file: &File, interval: Duration) -> std::io::Result<()> {
+    let mut pending = PENDING.lock().map_err(|_| std::io::Error::other("fsync bookkeeping poisoned"))?;
+    if !pending.files.iter().any(|p| p == path) {
+        pending.files.push(path.to_path_buf());
+    }
+    if pending.synced_at.is_some_and(|at| at.elapsed() < interval) {
+        return Ok(());
+    }
+    file.sync_data()?;
+    for other in pending.files.drain(..).filter(|p| p != path) {
+        if let Ok(f) = OpenOptions::new().append(true).open(&other) {
+            let _ = f.sync_data();
+        }
+    }
+    pending.synced_at = Some(Instant::now());
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_entries_from_plain_and_compressed_files() {
+        #[derive(serde::Deserialize)]
+        struct Entry {
+            title: String,
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let content = "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n{\"title\":\"one\"}\n\nnot json\n{\"title\":\"two\"}\n";
+        let plain = tmp.path().join("history.jsonl");
+        std::fs::write(&plain, content).unwrap();
+        let archive = crate::vault::compress::compressed_path(&tmp.path().join(crate::vault::compress::HISTORY_ARCHIVE));
+        crate::vault::compress::write_compressed(&archive, content).unwrap();
+
+        for path in [&plain, &archive] {
+            let titles: Vec<String> = read_entries::<Entry>(path).into_iter().map(|e| e.title).collect();
+            assert_eq!(titles, ["one", "two"]);
+        }
+        assert!(read_entries::<Entry>(&tmp.path().join("missing.jsonl")).is_empty());
+    }
+
+    #[test]
+    fn appends_close_torn_lines_and_repair_cuts_them() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("history.jsonl");
+        let durability = DurabilityConfig::default();
+
+        append(&path, "history", r#"{"title":"one"}"#, &durability).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n{\"title\":\"one\"}\n");
+        assert!(append(&path, "history", "{\"title\":\n\"two\"}", &durability).is_err());
+
+        // A crash cut the next line short; the following append starts on a fresh line
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"title":"tw"#).unwrap();
+        assert_eq!(append_text(&path, "history", "{}"), "\n{}\n");
+        append(&path, "history", r#"{"title":"three"}"#, &durability).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.ends_with("{\"title\":\"one\"}\n{\"title\":\"tw\n{\"title\":\"three\"}\n"), "{content}");
+        assert_eq!(unterminated_tail(&content), None);
+
+        // Repair removes a torn tail and terminates a whole one
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"title":"fo"#).unwrap();
+        assert_eq!(unterminated_tail(&std::fs::read_to_string(&path).unwrap()), Some(r#"{"title":"fo"#));
+        assert_eq!(repair_tail(&path).unwrap(), Some(TailRepair::Cut { fragment: r#"{"title":"fo"#.to_string() }));
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with("{\"title\":\"three\"}\n"));
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"title":"four"}"#).unwrap();
+        assert_eq!(repair_tail(&path).unwrap(), Some(TailRepair::Terminated));
+        assert_eq!(repair_tail(&path).unwrap(), None);
+
+        let periodic = DurabilityConfig { fsync: FsyncPolicy::Periodic, interval_seconds: 60 };
+        append(&path, "history", r#"{"title":"five"}"#, &periodic).unwrap();
+        append(&path, "history", r#"{"title":"six"}"#, &periodic).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with("{\"title\":\"four\"}\n{\"title\":\"five\"}\n{\"title\":\"six\"}\n"));
+    }
+
+    #[test]
+    fn replace_keeps_lines_appended_since_the_read() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("history.jsonl");
+        let durability = DurabilityConfig::default();
+        append(&path, "history", r#"{"title":"old"}"#, &durability).unwrap();
+        let read = std::fs::read_to_string(&path).unwrap();
+
+        // An append lands between the read and the rewrite, and one waits on the lock during it
+        append(&path, "history", r#"{"title":"late"}"#, &durability).unwrap();
+        let held = open_locked(&path).unwrap();
+        let waiting = {
+            let path = path.clone();
+            std::thread::spawn(move || append(&path, "history", r#"{"title":"waiting"}"#, &DurabilityConfig::default()))
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        drop(held);
+        replace(&path, &read, "{\"_schema\": \"history\", \"_version\": \"1.0\"}\n{\"title\":\"summary\"}\n").unwrap();
+        waiting.join().unwrap().unwrap();
+        let lines: Vec<String> = std::fs::read_to_string(&path).unwrap().lines().skip(1).map(String::from).collect();
+        assert_eq!(lines[..2], [r#"{"title":"summary"}"#, r#"{"title":"late"}"#]);
+        assert!(lines.contains(&r#"{"title":"waiting"}"#.to_string()), "{lines:?}");
+
+        // A rewrite in between is refused rather than overwritten
+        std::fs::write(&path, "{}\n").unwrap();
+        assert!(replace(&path, &read, "").is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Longest line kept as a link's context.
+const SNIPPET_CHARS: usize = 200;
+
+/// The note a reference names, without `[[ ]]`, an `|alias`, or a `#heading`.
+pub fn clean_target(entry: &str) -> &str {
+    let target = entry.trim().trim_start_matches("[[").trim_end_matches("]]");
+    let target = target.split('|').next().unwrap_or(target);
+    target.split('#').next().unwrap_or(target).trim()
+}
+
+/// `[[wikilink]]` targets in a body, in order, each with the line it sits on (trimmed and
+/// capped). A target linked twice is listed once, with its first line.
+pub fn wikilinks(body: &str) -> Vec<(String, String)> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    for line in body.lines() {
+        let mut rest = line;
+        while let Some(start) = rest.find("[[") {
+            let tail = &rest[start + 2..];
+            let Some(end) = tail.find("]]") else {
+                break;
+            };
+            let target = clean_target(&tail[..end]);
+            if !target.is_empty() && !found.iter().any(|(t, _)| t == target) {
+                found.push((target.to_string(), snippet(line)));
+            }
+            rest = &tail[end + 2..];
+        }
+    }
+    found
+}
+
+/// A line trimmed to `SNIPPET_CHARS`.
+pub fn snippet(line: &str) -> String {
+    let line = line.trim();
+    match line.char_indices().nth(SNIPPET_CHARS) {
+        Some((cut, _)) => format!("{}...", &line[..cut]),
+        None => line.to_string(),
+    }
+}
+
+/// Vault-relative path of the file `target` names, as written in the note at `note_rel`: a
+/// path relative to the note or the vault root, with or without `.md`. None when no such file
+/// exists; bare names are then matched against file names when links are looked up.
+pub fn resolve(target: &str, note_rel: &Path, vault_root: &Path) -> Option<String> {
+    resolve_with(target, note_rel, |c| vault_root.join(c).is_file())
+}
+
+/// `resolve` against a vault described by `is_file`, which says whether a vault-relative path
+/// is a file. Lets a caller resolve against the vault as it will be after a move.
+pub fn resolve_with(target: &str, note_rel: &Path, is_file: impl Fn(&Path) -> bool) -> Option<String> {
+    let target = clean_target(target);
+    if target.is_empty() {
+        return None;
+    }
+    let note_dir = note_rel.parent().unwrap_or(Path::new(""));
+    let mut candidates = vec![note_dir.join(target), PathBuf::from(target)];
+    if Path::new(target).extension().is_none() {
+        candidates.push(note_dir.join(format!("{target}.md")));
+        candidates.push(PathBuf::from(format!("{target}.md")));
+    }
+    candidates.into_iter()
+        .filter_map(|c| normalize(&c))
+        .find(|c| is_file(c))
+        .map(|c| c.to_string_lossy().to_string())
+}
+
+/// Fold `.` and `..` out of a relative path. None if it climbs out of the vault.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wikilinks_are_cleaned_and_carry_their_line() {
+        let body = "See [[auth|the auth doc]] and [[work/api/plan#Phase 2]].\n\n- again [[auth]]\n- broken [[open\n";
+        assert_eq!(wikilinks(body), vec![
+            ("auth".to_string(), "See [[auth|the auth doc]] and [[work/api/plan#Phase 2]].".to_string()),
+            ("work/api/plan".to_string(), "See [[auth|the auth doc]] and [[work/api/plan#Phase 2]].".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn resolve_tries_the_note_dir_then_the_vault_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("work/api")).unwrap();
+        std::fs::write(tmp.path().join("work/api/auth.md"), "# auth\n").unwrap();
+        std::fs::write(tmp.path().join("index.md"), "# home\n").unwrap();
+        let note = Path::new("work/api/INDEX.md");
+        assert_eq!(resolve("auth", note, tmp.path()).as_deref(), Some("work/api/auth.md"));
+        assert_eq!(resolve("[[work/api/auth.md]]", note, tmp.path()).as_deref(), Some("work/api/auth.md"));
+        assert_eq!(resolve("../../index", note, tmp.path()).as_deref(), Some("index.md"));
+        assert_eq!(resolve("../../../etc/passwd", note, tmp.path()), None);
+        assert_eq!(resolve("plan", note, tmp.path()), None);
+    }
+}
@@ -1,6 +1,21 @@
 pub mod types;
 pub mod frontmatter;
 pub mod reader;
+pub mod batch;
+pub mod compress;
+pub mod app_files;
+pub mod project;
+pub mod diff;
+pub mod timeline;
+pub mod seed;
+pub mod template;
+pub mod entry_id;
+pub mod state;
+pub mod links;
+pub mod jsonl;
+pub mod journal;
+pub mod history_query;
+pub mod symlinks;
 
 pub use types::*;
 pub use frontmatter::*;
@@ -0,0 +1,362 @@
+use std::path::Path;
+
+/// Folder under a domain that archived projects are moved into. History, orchestrate, and
+/// effort leave it out unless asked to include archived work.
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Minimum Jaro-Winkler similarity for a fuzzy project suggestion.
+const FUZZY_THRESHOLD: f64 = 0.88;
+
+/// How a caller-supplied project name maps onto existing project folders in a domain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectMatch {
+    /// A folder with exactly this name exists.
+    Exact(String),
+    /// The slugified name matches an existing folder (e.g. "Sentry Bot" → "sentry-bot").
+    Slug(String),
+    /// The name is listed in an existing project's `aliases` frontmatter.
+    Alias(String),
+    /// The slugified name matches more than one existing folder (e.g. "sentry-bot" and
+    /// "Sentry_Bot"), so none is picked.
+    Ambiguous(Vec<String>),
+    /// No exact match, but an existing folder is close. Needs confirmation before creating a new one.
+    Fuzzy { project: String, score: f64 },
+    /// Nothing similar exists — this is a new project.
+    New,
+}
+
+/// How `slugify_with` treats letters outside ASCII when naming new project folders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transliteration {
+    /// Strip accents and spell out ligatures: "Café Menü" → "cafe-menu", "Straße" → "strasse".
+    #[default]
+    Ascii,
+    /// Like `ascii`, but umlauts become two letters: "Menü" → "menue".
+    German,
+    /// Keep letters as written, lowercased: "Café Menü" → "café-menü".
+    None,
+}
+
+/// Lowercase alphanumeric words joined by single hyphens, folding accented Latin letters to
+/// ASCII: "Sentry Bot v2" → "sentry-bot-v2", "Café Menü" → "cafe-menu".
+pub fn slugify(name: &str) -> String {
+    slugify_with(name, Transliteration::Ascii)
+}
+
+/// `slugify` with a choice of transliteration. Letters with no ASCII spelling (Greek,
+/// Cyrillic, CJK, ...) are kept lowercased in every mode so the slug is never empty for them.
+pub fn slugify_with(name: &str, mode: Transliteration) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if is_combining_mark(c) {
+            // Decomposed input ("e" + U+0301): the accent belongs to the letter before it
+            if mode == Transliteration::None && !slug.is_empty() && !slug.ends_with('-') {
+                slug.push(c);
+            }
+        } else if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if let Some(folded) = fold(c, mode) {
+            slug.push_str(folded);
+        } else if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Whether two names slugify alike under either ASCII or German folding, so "Menü", "menu",
+/// and "menue" all find the same folder whichever mode created it.
+pub fn same_slug(a: &str, b: &str) -> bool {
+    let ascii = slugify(a);
+    !ascii.is_empty() && (ascii == slugify(b) || slugify_with(a, Transliteration::German) == slugify_with(b, Transliteration::German))
+}
+
+/// Whether `name` can be a domain or project folder: one path segment that isn't hidden,
+/// `.`/`..`, or the archive folder.
+pub fn is_folder_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name != ARCHIVE_DIR
+        && !name.chars().any(|c| c == '/' || c == '\\' || c.is_control())
+}
+
+/// "my-project" → "My Project", "café_menü" → "Café Menü". Names already written with
+/// capitals or spaces ("Café Menü", "iOS app") keep their own casing.
+pub fn title_from_name(name: &str) -> String {
+    let name = name.trim();
+    let words = name.split(|c: char| c == '-' || c == '_' || c.is_whitespace()).filter(|w| !w.is_empty());
+    if name.chars().any(|c| c.is_whitespace()) && name.chars().any(char::is_uppercase) {
+        return words.collect::<Vec<_>>().join(" ");
+    }
+    words
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(c) => {
+                    let mut s: String = c.to_uppercase().collect();
+                    s.extend(chars);
+                    s
+                }
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Combining diacritical marks (U+0300–U+036F), as left by NFD-normalized input such as
+/// macOS file names.
+fn is_combining_mark(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+}
+
+/// ASCII spelling of a Latin letter, or `None` to keep it as is.
+fn fold(c: char, mode: Transliteration) -> Option<&'static str> {
+    if mode == Transliteration::None {
+        return None;
+    }
+    if mode == Transliteration::German {
+        let umlaut = match c {
+            'ä' | 'Ä' => Some("ae"),
+            'ö' | 'Ö' => Some("oe"),
+            'ü' | 'Ü' => Some("ue"),
+            _ => None,
+        };
+        if umlaut.is_some() {
+            return umlaut;
+        }
+    }
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' | 'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "a",
+        'æ' | 'Æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' | 'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "c",
+        'ď' | 'đ' | 'Ď' | 'Đ' => "d",
+        'ð' | 'Ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' | 'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' | 'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "g",
+        'ĥ' | 'ħ' | 'Ĥ' | 'Ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' | 'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "i",
+        'ĵ' | 'Ĵ' => "j",
+        'ķ' | 'Ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' | 'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'Ñ' | 'Ń' | 'Ņ' | 'Ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' | 'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "o",
+        'œ' | 'Œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' | 'Ŕ' | 'Ŗ' | 'Ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' | 'Ś' | 'Ŝ' | 'Ş' | 'Š' | 'Ș' => "s",
+        'ß' | 'ẞ' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' | 'Ţ' | 'Ť' | 'Ŧ' | 'Ț' => "t",
+        'þ' | 'Þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' | 'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "u",
+        'ŵ' | 'Ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' | 'Ý' | 'Ÿ' | 'Ŷ' => "y",
+        'ź' | 'ż' | 'ž' | 'Ź' | 'Ż' | 'Ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// Resolve `name` against the project folders under `domain_dir`.
+///
+/// Names are compared against the folder listing, not by stat-ing `domain_dir/name`, so a
+/// case-insensitive filesystem can't pass "Sentry-Bot" off as an exact match for `sentry-bot`.
+pub fn resolve_project(domain_dir: &Path, name: &str) -> ProjectMatch {
+    let projects = list_projects(domain_dir);
+    if projects.iter().any(|p| p == name) {
+        return ProjectMatch::Exact(name.to_string());
+    }
+
+    let slug = slugify(name);
+    let mut same: Vec<String> = projects.iter().filter(|p| same_slug(p, name)).cloned().collect();
+    if same.len() > 1 {
+        return ProjectMatch::Ambiguous(same);
+    }
+    if let Some(p) = same.pop() {
+        return ProjectMatch::Slug(p);
+    }
+    if domain_dir.join(name).is_dir() {
+        return ProjectMatch::Exact(name.to_string());
+    }
+    if slug.is_empty() {
+        return ProjectMatch::New;
+    }
+
+    for project in &projects {
+        let aliases = project_aliases(&domain_dir.join(project));
+        if aliases.iter().any(|a| a == name || same_slug(a, name)) {
+            return ProjectMatch::Alias(project.clone());
+        }
+    }
+
+    let best = projects.iter()
+        .map(|p| (p, strsim::jaro_winkler(&slug, &slugify(p))))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    match best {
+        Some((project, score)) if score >= FUZZY_THRESHOLD => ProjectMatch::Fuzzy {
+            project: project.clone(),
+            score,
+        },
+        _ => ProjectMatch::New,
+    }
+}
+
+/// The archived project `name` would fork if created anew: one under `domain_dir/archive/`
+/// with that name, slug, or alias.
+pub fn archived_project(domain_dir: &Path, name: &str) -> Option<String> {
+    match resolve_project(&domain_dir.join(ARCHIVE_DIR), name) {
+        ProjectMatch::Exact(found) | ProjectMatch::Slug(found) | ProjectMatch::Alias(found) => Some(found),
+        ProjectMatch::Ambiguous(_) | ProjectMatch::Fuzzy { .. } | ProjectMatch::New => None,
+    }
+}
+
+/// The project in `domain` a working directory belongs to. The registry must place the
+/// directory in `domain`; then it and each parent still inside the domain are matched
+/// against the domain's project folders (by name, slug, or alias), nearest first.
+pub fn project_from_cwd(registry: &crate::domain::registry::DomainRegistry, vault_root: &Path, domain: &str, cwd: &Path) -> Option<String> {
+    let in_domain = |dir: &Path| registry.resolve(dir).is_some_and(|d| d.name.as_str() == domain);
+    if !in_domain(cwd) {
+        return None;
+    }
+    let domain_dir = vault_root.join(domain);
+    cwd.ancestors()
+        .take_while(|dir| in_domain(dir))
+        .filter_map(|dir| dir.file_name().and_then(|n| n.to_str()))
+        .find_map(|name| match resolve_project(&domain_dir, name) {
+            ProjectMatch::Exact(found) | ProjectMatch::Slug(found) | ProjectMatch::Alias(found) => Some(found),
+            ProjectMatch::Ambiguous(_) | ProjectMatch::Fuzzy { .. } | ProjectMatch::New => None,
+        })
+}
+
+/// Aliases declared in a project's INDEX.md or current_state.md frontmatter (`aliases: [...]`).
+pub fn project_aliases(project_dir: &Path) -> Vec<String> {
+    let mut aliases = Vec::new();
+    for file in ["INDEX.md", "current_state.md"] {
+        if let Ok(content) = std::fs::read_to_string(project_dir.join(file)) {
+            aliases.extend(aliases_in(&content));
+        }
+    }
+    aliases
+}
+
+/// The `aliases` in one note's frontmatter, as a list or a comma-separated string.
+pub fn aliases_in(content: &str) -> Vec<String> {
+    let Some(yaml) = crate::vault::frontmatter::frontmatter_yaml(content) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else {
+        return Vec::new();
+    };
+    match value.get("aliases") {
+        Some(serde_yaml::Value::Sequence(seq)) => seq.iter().filter_map(|v| v.as_str()).map(String::from).collect(),
+        Some(serde_yaml::Value::String(s)) => s.split(',').map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Project folder names in a domain, skipping hidden directories and the digest folder.
+fn list_projects(domain_dir: &Path) -> Vec<String> {
+    let mut projects: Vec<String> = std::fs::read_dir(domain_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .filter(|n| !n.starts_with('.') && n != crate::daemon::digest::DIGEST_DIR && n != ARCHIVE_DIR)
+        .collect();
+    projects.sort();
+    projects
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn slugify_normalizes_names() {
+        assert_eq!(slugify("Sentry Bot"), "sentry-bot");
+        assert_eq!(slugify("  sentry_bot  v2 "), "sentry-bot-v2");
+        assert_eq!(slugify("already-slugged"), "already-slugged");
+        assert_eq!(slugify("--"), "");
+    }
+
+    #[test]
+    fn slugify_transliterates_unicode() {
+        assert_eq!(slugify("Café Menü"), "cafe-menu");
+        assert_eq!(slugify("Cafe\u{301} Straße"), "cafe-strasse");
+        assert_eq!(slugify_with("Café Menü", Transliteration::German), "cafe-menue");
+        assert_eq!(slugify_with("Café Menü", Transliteration::None), "café-menü");
+        assert_eq!(slugify_with("Cafe\u{301}", Transliteration::None), "cafe\u{301}");
+        assert_eq!(slugify("Привет Мир"), "привет-мир");
+        assert!(same_slug("cafe-menue", "Café Menü"));
+        assert!(same_slug("café-menü", "Cafe Menu"));
+        assert!(!same_slug("--", "__"));
+    }
+
+    #[test]
+    fn folder_names_are_single_visible_segments() {
+        assert!(is_folder_name("api") && is_folder_name("café-menü"));
+        for bad in ["", ".", "..", ".hidden", "a/b", "..\\x", "archive", "tab\t"] {
+            assert!(!is_folder_name(bad), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn title_from_name_handles_unicode_words() {
+        assert_eq!(title_from_name("my-project"), "My Project");
+        assert_eq!(title_from_name("café_menü"), "Café Menü");
+        assert_eq!(title_from_name("ørsted-über"), "Ørsted Über");
+        assert_eq!(title_from_name("Café  Menü"), "Café Menü");
+        assert_eq!(title_from_name("iOS app"), "iOS app");
+    }
+
+    #[test]
+    fn resolve_project_prefers_exact_then_slug() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("sentry-bot")).unwrap();
+
+        assert_eq!(resolve_project(tmp.path(), "sentry-bot"), ProjectMatch::Exact("sentry-bot".to_string()));
+        assert_eq!(resolve_project(tmp.path(), "Sentry Bot"), ProjectMatch::Slug("sentry-bot".to_string()));
+    }
+
+    #[test]
+    fn resolve_project_refuses_to_pick_between_slug_twins() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("sentry-bot")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("Sentry_Bot")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("archive/billing")).unwrap();
+
+        assert_eq!(resolve_project(tmp.path(), "Sentry_Bot"), ProjectMatch::Exact("Sentry_Bot".to_string()));
+        assert_eq!(
+            resolve_project(tmp.path(), "Sentry-Bot"),
+            ProjectMatch::Ambiguous(vec!["Sentry_Bot".to_string(), "sentry-bot".to_string()])
+        );
+        assert_eq!(resolve_project(tmp.path(), "Billing"), ProjectMatch::New);
+        assert_eq!(archived_project(tmp.path(), "Billing"), Some("billing".to_string()));
+        assert_eq!(archived_project(tmp.path(), "sentry-bot"), None);
+    }
+
+    #[test]
+    fn resolve_project_uses_frontmatter_aliases() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("sentry-bot");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("INDEX.md"), "---\ntype: project\naliases: [watchdog, error bot]\n---\n# Sentry Bot\n").unwrap();
+
+        assert_eq!(resolve_project(tmp.path(), "Watchdog"), ProjectMatch::Alias("sentry-bot".to_string()));
+        assert_eq!(resolve_project(tmp.path(), "error bot"), ProjectMatch::Alias("sentry-bot".to_string()));
+    }
+
+    #[test]
+    fn resolve_project_fuzzy_and_new() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("sentry-bot")).unwrap();
+
+        assert!(matches!(resolve_project(tmp.path(), "sentry-bott"), ProjectMatch::Fuzzy { ref project, .. } if project == "sentry-bot"));
+        assert_eq!(resolve_project(tmp.path(), "billing-api"), ProjectMatch::New);
+    }
+}
@@ -1,16 +1,32 @@
-use crate::vault::frontmatter::parse_frontmatter;
-use crate::vault::types::{VaultError, VaultFile};
+use crate::vault::frontmatter::parse_frontmatter_with;
+use crate::vault::symlinks::{SymlinkPolicy, Walk};
+use crate::vault::types::{Status, VaultError, VaultFile};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Read a single vault file, parsing its frontmatter and body.
 /// Files without frontmatter are indexed with default metadata (type: reference).
 /// JSONL files get synthetic frontmatter with type: history.
 pub fn read_file(path: &Path) -> Result<VaultFile, VaultError> {
+    read_file_with(path, &BTreeMap::new())
+}
+
+/// Like `read_file`, also accepting the extra states from `statuses:` in config.yml.
+pub fn read_file_with(path: &Path, statuses: &BTreeMap<String, Status>) -> Result<VaultFile, VaultError> {
     let content = std::fs::read_to_string(path).map_err(|e| VaultError::Io {
         path: path.display().to_string(),
         source: e,
     })?;
+    parse_file_with(path, content, statuses)
+}
+
+/// Parse already-read content as the vault file at `path`, as `read_file` would.
+pub fn parse_file(path: &Path, content: String) -> Result<VaultFile, VaultError> {
+    parse_file_with(path, content, &BTreeMap::new())
+}
 
+/// Like `parse_file`, also accepting the extra states from `statuses:` in config.yml.
+pub fn parse_file_with(path: &Path, content: String, statuses: &BTreeMap<String, Status>) -> Result<VaultFile, VaultError> {
     // JSONL files → synthetic history frontmatter
     if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
         let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("history");
@@ -34,7 +50,7 @@ pub fn read_file(path: &Path) -> Result<VaultFile, VaultError> {
         });
     }
 
-    match parse_frontmatter(&content) {
+    match parse_frontmatter_with(&content, statuses) {
         Ok((frontmatter, body)) => Ok(VaultFile {
             path: path.to_path_buf(),
             frontmatter,
@@ -70,18 +86,38 @@ pub fn read_file(path: &Path) -> Result<VaultFile, VaultError> {
 /// Recursively walk a vault directory and parse all .md files.
 /// Returns a Vec of Results — individual file errors don't stop the walk.
 pub fn walk_vault(root: &Path) -> Vec<Result<VaultFile, VaultError>> {
-    walk_vault_filtered(root, &[])
+    walk_vault_filtered(root, &[], SymlinkPolicy::default())
 }
 
+/// Vault-root folder deleted files are moved into. Obsidian uses the same one. Never indexed.
+pub const TRASH_DIR: &str = ".trash";
+
 /// Walk vault with exclusion patterns. Each pattern is matched against
 /// directory/file names (e.g., "node_modules", ".obsidian", ".git").
-pub fn walk_vault_filtered(root: &Path, exclude: &[String]) -> Vec<Result<VaultFile, VaultError>> {
+/// Symlinks are entered or left out per `symlinks`.
+pub fn walk_vault_filtered(root: &Path, exclude: &[String], symlinks: SymlinkPolicy) -> Vec<Result<VaultFile, VaultError>> {
+    walk_vault_with(root, exclude, symlinks, &BTreeMap::new())
+}
+
+/// Like `walk_vault_filtered`, also accepting the extra states from `statuses:` in config.yml.
+pub fn walk_vault_with(
+    root: &Path,
+    exclude: &[String],
+    symlinks: SymlinkPolicy,
+    statuses: &BTreeMap<String, Status>,
+) -> Vec<Result<VaultFile, VaultError>> {
     let mut results = Vec::new();
-    walk_recursive(root, exclude, &mut results);
+    walk_recursive(root, exclude, &mut Walk::new(root, symlinks), statuses, &mut results);
     results
 }
 
-fn walk_recursive(dir: &Path, exclude: &[String], results: &mut Vec<Result<VaultFile, VaultError>>) {
+fn walk_recursive(
+    dir: &Path,
+    exclude: &[String],
+    walk: &mut Walk,
+    statuses: &BTreeMap<String, Status>,
+    results: &mut Vec<Result<VaultFile, VaultError>>,
+) {
     let entries = match std::fs::read_dir(dir) {
         Ok(e) => e,
         Err(e) => {
@@ -101,13 +137,15 @@ fn walk_recursive(dir: &Path, exclude: &[String], results: &mut Vec<Result<Vault
 
     for path in paths {
         let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        if exclude.iter().any(|e| e == name) {
+        if name == TRASH_DIR || exclude.iter().any(|e| e == name) {
             continue;
         }
         if path.is_dir() {
-            walk_recursive(&path, exclude, results);
-        } else if path.extension().is_some_and(|ext| ext == "md" || ext == "jsonl") {
-            results.push(read_file(&path));
+            if walk.enters(&path) {
+                walk_recursive(&path, exclude, walk, statuses, results);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "md" || ext == "jsonl") && walk.reads(&path) {
+            results.push(read_file_with(&path, statuses));
         }
     }
 }
@@ -0,0 +1,204 @@
+use crate::vault::template::{self, Vars};
+use std::path::Path;
+
+/// Directory under the config dir where `INDEX.md` and `current_state.md` override the
+/// built-in seed templates. Each subdirectory is a named template.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// The two files a seeded project starts with.
+#[derive(Debug, Clone)]
+pub struct SeedTemplates {
+    pub index: String,
+    pub state: String,
+}
+
+impl Default for SeedTemplates {
+    fn default() -> Self {
+        Self { index: INDEX_TEMPLATE.to_string(), state: STATE_TEMPLATE.to_string() }
+    }
+}
+
+impl SeedTemplates {
+    /// User templates from `<config_dir>/templates/`, falling back to the built-ins per file.
+    pub fn load(config_dir: &Path) -> Self {
+        let dir = config_dir.join(TEMPLATES_DIR);
+        let defaults = Self::default();
+        Self {
+            index: std::fs::read_to_string(dir.join("INDEX.md")).unwrap_or(defaults.index),
+            state: std::fs::read_to_string(dir.join("current_state.md")).unwrap_or(defaults.state),
+        }
+    }
+
+    /// The named template in `<config_dir>/templates/<name>/`. A file it doesn't have comes
+    /// from `load`, so a template can override just one of the two.
+    pub fn named(config_dir: &Path, name: &str) -> std::io::Result<Self> {
+        let dir = config_dir.join(TEMPLATES_DIR);
+        let valid = !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\']);
+        if !valid || !dir.join(name).is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no seed template '{name}' (expected a folder at {})", dir.join(name).display()),
+            ));
+        }
+        let fallback = Self::load(config_dir);
+        Ok(Self {
+            index: std::fs::read_to_string(dir.join(name).join("INDEX.md")).unwrap_or(fallback.index),
+            state: std::fs::read_to_string(dir.join(name).join("current_state.md")).unwrap_or(fallback.state),
+        })
+    }
+
+    /// Every variable either template references.
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = template::variables(&self.index);
+        for name in template::variables(&self.state) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+/// Variables every seed gets: `domain`, `project`, `title`, `date`, `datetime`, and an
+/// empty `git_remote` for callers to fill in.
+pub fn builtin_vars(domain: &str, project: &str) -> Vars {
+    let now = chrono::Local::now();
+    Vars::from([
+        ("domain".to_string(), domain.to_string()),
+        ("project".to_string(), project.to_string()),
+        ("title".to_string(), crate::vault::project::title_from_name(project)),
+        ("date".to_string(), now.format("%Y-%m-%d").to_string()),
+        ("datetime".to_string(), now.format("%Y-%m-%d %H:%M").to_string()),
+        ("git_remote".to_string(), String::new()),
+    ])
+}
+
+/// `origin` remote URL of the git repo containing `dir`, if any.
+pub fn git_remote(dir: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C").arg(dir)
+        .args(["remote", "get-url", "origin"])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !url.is_empty()).then_some(url)
+}
+
+/// Write placeholder INDEX.md and current_state.md into a project directory using the
+/// built-in templates. Existing files are left alone. Returns the names of the files written.
+pub fn write_project_seed(project_dir: &Path, domain: &str, project: &str) -> Result<Vec<&'static str>, std::io::Error> {
+    write_seed_files(project_dir, &SeedTemplates::default(), &builtin_vars(domain, project))
+}
+
+/// Render `templates` with `vars` into a project directory, skipping files that exist.
+pub fn write_seed_files(project_dir: &Path, templates: &SeedTemplates, vars: &Vars) -> Result<Vec<&'static str>, std::io::Error> {
+    std::fs::create_dir_all(project_dir)?;
+    let mut written = Vec::new();
+    for (file, source) in [("INDEX.md", &templates.index), ("current_state.md", &templates.state)] {
+        let path = project_dir.join(file);
+        if !path.exists() {
+            std::fs::write(&path, template::render(source, vars))?;
+            written.push(file);
+        }
+    }
+    Ok(written)
+}
+
+const INDEX_TEMPLATE: &str = "\
+# {{title}}
+
+## What
+(one sentence — what is this)
+
+## Why
+(one sentence — why does this matter)
+
+## Links
+- Repo: {{git_remote}}
+(related vault files, external URLs)
+";
+
+const STATE_TEMPLATE: &str = "\
+---
+chat_name: {{project}}
+updated: {{datetime}}
+status: active
+type: project
+context: {{domain}}
+---
+
+# {{title}}
+
+## Focus
+(what are you working on right now)
+
+## Next Action
+(single concrete next step)
+
+## Commit Message
+Seeded by wardwell
+";
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_skips_existing_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("work/my-project");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("INDEX.md"), "# Mine\n").unwrap();
+
+        assert_eq!(write_project_seed(&dir, "work", "my-project").unwrap(), vec!["current_state.md"]);
+        assert_eq!(std::fs::read_to_string(dir.join("INDEX.md")).unwrap(), "# Mine\n");
+        let state = std::fs::read_to_string(dir.join("current_state.md")).unwrap();
+        assert!(state.contains("context: work"));
+        assert!(state.contains("# My Project"));
+        assert!(write_project_seed(&dir, "work", "my-project").unwrap().is_empty());
+        assert!(!std::fs::read_to_string(dir.join("INDEX.md")).unwrap().contains("Repo:"));
+    }
+
+    #[test]
+    fn user_templates_render_custom_vars() {
+        let tmp = tempfile::tempdir().unwrap();
+        let templates_dir = tmp.path().join(TEMPLATES_DIR);
+        std::fs::create_dir_all(&templates_dir).unwrap();
+        std::fs::write(templates_dir.join("INDEX.md"), "# {{title}} for {{client}}\n- Repo: {{git_remote}}\n").unwrap();
+
+        let templates = SeedTemplates::load(tmp.path());
+        assert_eq!(templates.variables(), vec!["title", "client", "git_remote", "project", "datetime", "domain"]);
+
+        let mut vars = builtin_vars("work", "acme-site");
+        vars.insert("client".to_string(), "Acme".to_string());
+        vars.insert("git_remote".to_string(), "git@github.com:acme/site.git".to_string());
+        let dir = tmp.path().join("vault/work/acme-site");
+        write_seed_files(&dir, &templates, &vars).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("INDEX.md")).unwrap(),
+            "# Acme Site for Acme\n- Repo: git@github.com:acme/site.git\n",
+        );
+        assert!(std::fs::read_to_string(dir.join("current_state.md")).unwrap().contains("context: work"));
+    }
+
+    #[test]
+    fn named_templates_fall_back_per_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let templates_dir = tmp.path().join(TEMPLATES_DIR);
+        std::fs::create_dir_all(templates_dir.join("client")).unwrap();
+        std::fs::write(templates_dir.join("INDEX.md"), "# {{title}}\n").unwrap();
+        std::fs::write(templates_dir.join("client/current_state.md"), "---\nstatus: active\n---\n# {{project}} in {{domain}}, {{date}}\n").unwrap();
+
+        let templates = SeedTemplates::named(tmp.path(), "client").unwrap();
+        assert_eq!(templates.index, "# {{title}}\n");
+        let dir = tmp.path().join("vault/work/acme");
+        write_seed_files(&dir, &templates, &builtin_vars("work", "acme")).unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert!(std::fs::read_to_string(dir.join("current_state.md")).unwrap().contains(&format!("# acme in work, {today}")));
+
+        assert!(SeedTemplates::named(tmp.path(), "missing").is_err());
+        assert!(SeedTemplates::named(tmp.path(), "../templates").is_err());
+    }
+}
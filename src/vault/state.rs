@@ -0,0 +1,156 @@
+use std::path::Path;
+
+/// Sections `sync` writes from its parameters, in the default order. Every other `## `
+/// section in current_state.md belongs to the user and survives a sync.
+pub const SYNC_SECTIONS: &[&str] = &[
+    "Focus",
+    "Why This Matters",
+    "Next Action",
+    "Open Questions",
+    "Blockers",
+    "Waiting On",
+    "Commit Message",
+];
+
+fn is_sync_section(heading: &str) -> bool {
+    SYNC_SECTIONS.iter().any(|s| s.eq_ignore_ascii_case(heading))
+}
+
+/// `## ` sections of a markdown body as (heading, content), content trimmed of surrounding
+/// blank lines but otherwise verbatim. Text before the first heading is not included.
+pub fn sections(body: &str) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = Vec::new();
+    for line in body.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            out.push((heading.trim().to_string(), String::new()));
+        } else if let Some((_, content)) = out.last_mut() {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    for (_, content) in &mut out {
+        *content = content.trim_matches('\n').trim_end().to_string();
+    }
+    out
+}
+
+/// Section order for a project: `state_sections` in the project's INDEX.md frontmatter,
+/// else the configured order. Empty means the built-in `SYNC_SECTIONS` order.
+pub fn section_order(project_dir: &Path, configured: &[String]) -> Vec<String> {
+    let from_index = std::fs::read_to_string(project_dir.join("INDEX.md")).ok()
+        .and_then(|content| {
+            let yaml = crate::vault::frontmatter::frontmatter_yaml(&content)?;
+            let value: serde_yaml::Value = serde_yaml::from_str(yaml).ok()?;
+            let seq = value.get("state_sections")?.as_sequence()?.clone();
+            Some(seq.iter().filter_map(|v| v.as_str()).map(|s| s.trim().to_string()).collect::<Vec<_>>())
+        })
+        .filter(|order| !order.is_empty());
+    from_index.unwrap_or_else(|| configured.to_vec())
+}
+
+/// Lay out the sections of current_state.md for a sync.
+///
+/// - `written` holds the sync sections that have content this time; sync sections it lacks
+///   are dropped, even if `previous` had them.
+/// - Every other section in `previous` is kept verbatim. One named in `order` goes there;
+///   one that isn't follows the section it followed before.
+/// - A custom section named in `order` but missing from `previous` is written as an empty
+///   heading, ready to fill in.
+/// - Sync sections left out of `order` go after the listed ones, in the default order.
+pub fn arrange(written: &[(&str, String)], previous: &str, order: &[String]) -> Vec<(String, String)> {
+    let old = sections(previous);
+    let default_order: Vec<String> = SYNC_SECTIONS.iter().map(|s| s.to_string()).collect();
+    let order = if order.is_empty() { &default_order } else { order };
+    let named = |heading: &str| order.iter().any(|o| o.eq_ignore_ascii_case(heading));
+
+    let mut layout: Vec<(String, String)> = Vec::new();
+    for name in order {
+        if let Some((_, content)) = written.iter().find(|(h, _)| h.eq_ignore_ascii_case(name)) {
+            layout.push((name.clone(), content.clone()));
+        } else if !is_sync_section(name) {
+            let content = old.iter().find(|(h, _)| h.eq_ignore_ascii_case(name)).map(|(_, c)| c.clone()).unwrap_or_default();
+            layout.push((name.clone(), content));
+        }
+    }
+    for name in SYNC_SECTIONS {
+        if !named(name)
+            && let Some((heading, content)) = written.iter().find(|(h, _)| h == name)
+        {
+            layout.push((heading.to_string(), content.clone()));
+        }
+    }
+
+    // Unlisted custom sections, each placed after the nearest earlier section still present
+    for (i, (heading, content)) in old.iter().enumerate() {
+        if is_sync_section(heading) || named(heading) || layout.iter().any(|(h, _)| h == heading) {
+            continue;
+        }
+        let anchor = old[..i].iter().rev()
+            .find_map(|(prev, _)| layout.iter().position(|(h, _)| h.eq_ignore_ascii_case(prev)));
+        let at = anchor.map_or(0, |a| a + 1);
+        layout.insert(at, (heading.clone(), content.clone()));
+    }
+    layout
+}
+
+/// Render arranged sections in the `\n## Heading\ncontent\n` shape sync has always written.
+pub fn render(layout: &[(String, String)]) -> String {
+    layout.iter()
+        .map(|(heading, content)| {
+            if content.is_empty() {
+                format!("\n## {heading}\n")
+            } else {
+                format!("\n## {heading}\n{content}\n")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn headings(layout: &[(String, String)]) -> Vec<&str> {
+        layout.iter().map(|(h, _)| h.as_str()).collect()
+    }
+
+    #[test]
+    fn custom_sections_survive_in_place() {
+        let previous = "# api\n\n## Focus\nold\n\n## Links\n- [PR](https://x)\n\n## Next Action\nold\n\n## Blockers\n- gone\n\n## Commit Message\nold\n\n## People\n- Sam\n";
+        let written = vec![
+            ("Focus", "new focus".to_string()),
+            ("Next Action", "new next".to_string()),
+            ("Commit Message", "msg".to_string()),
+        ];
+        let layout = arrange(&written, previous, &[]);
+        assert_eq!(headings(&layout), vec!["Focus", "Links", "Next Action", "Commit Message", "People"]);
+        assert_eq!(layout[1].1, "- [PR](https://x)");
+        assert_eq!(layout[0].1, "new focus");
+    }
+
+    #[test]
+    fn order_places_listed_sections_and_adds_empty_ones() {
+        let previous = "## Focus\nold\n\n## Links\n- a\n";
+        let written = vec![
+            ("Focus", "f".to_string()),
+            ("Why This Matters", "w".to_string()),
+            ("Next Action", "n".to_string()),
+            ("Commit Message", "c".to_string()),
+        ];
+        let order: Vec<String> = ["Links", "Focus", "Next Action", "People", "Commit Message"].iter().map(|s| s.to_string()).collect();
+        let layout = arrange(&written, previous, &order);
+        assert_eq!(headings(&layout), vec!["Links", "Focus", "Next Action", "People", "Commit Message", "Why This Matters"]);
+        assert_eq!(render(&layout[3..4]), "\n## People\n");
+        assert_eq!(render(&layout[..1]), "\n## Links\n- a\n");
+    }
+
+    #[test]
+    fn project_index_overrides_configured_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let configured = vec!["Focus".to_string()];
+        assert_eq!(section_order(tmp.path(), &configured), configured);
+        std::fs::write(tmp.path().join("INDEX.md"), "---\nstate_sections: [Next Action, Focus]\n---\n# api\n").unwrap();
+        assert_eq!(section_order(tmp.path(), &configured), vec!["Next Action", "Focus"]);
+    }
+}
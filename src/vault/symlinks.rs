@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What vault walks do with a symlinked file or folder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkPolicy {
+    /// Enter links that lead out of the vault, each target once. A link to somewhere inside
+    /// the vault is left out, since the walk reaches its target under its own path.
+    #[default]
+    Follow,
+    /// Never enter a link.
+    Skip,
+}
+
+/// What a walk did with one symlink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Followed,
+    /// `symlinks: skip`.
+    Skipped,
+    /// Points inside the vault, which is walked under the target's own path.
+    InsideVault,
+    /// Leads to a folder the walk already entered, such as one of its own parents.
+    AlreadyWalked,
+    /// The target doesn't exist.
+    Broken,
+}
+
+impl Verdict {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Followed => "followed",
+            Self::Skipped => "skipped (symlinks: skip)",
+            Self::InsideVault => "skipped, points inside the vault",
+            Self::AlreadyWalked => "skipped, its target is already walked",
+            Self::Broken => "broken",
+        }
+    }
+}
+
+/// A symlink found under the vault.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Symlink {
+    /// Vault-relative path of the link.
+    pub path: String,
+    /// Where it resolves to, or its raw target when broken.
+    pub target: PathBuf,
+    pub verdict: Verdict,
+}
+
+/// Decides which entries a walk of the vault enters, so every walker treats links alike.
+/// Folders are remembered by their resolved path, so none is walked twice.
+pub struct Walk {
+    root: PathBuf,
+    canonical_root: PathBuf,
+    policy: SymlinkPolicy,
+    entered: HashSet<PathBuf>,
+    links: Vec<Symlink>,
+}
+
+impl Walk {
+    pub fn new(vault_root: &Path, policy: SymlinkPolicy) -> Self {
+        let canonical_root = vault_root.canonicalize().unwrap_or_else(|_| vault_root.to_path_buf());
+        let entered = HashSet::from([canonical_root.clone()]);
+        Self { root: vault_root.to_path_buf(), canonical_root, policy, entered, links: Vec::new() }
+    }
+
+    /// Whether to descend into the folder at `path`.
+    pub fn enters(&mut self, path: &Path) -> bool {
+        if !path.is_symlink() {
+            // A plain folder is only new if no link led here first
+            return path.is_dir() && path.canonicalize().map_or(true, |c| self.entered.insert(c));
+        }
+        let verdict = match self.judge_link(path) {
+            Ok(target) if !target.is_dir() => return false,
+            Ok(target) => if self.entered.insert(target) { Verdict::Followed } else { Verdict::AlreadyWalked },
+            Err(verdict) => verdict,
+        };
+        self.record(path, verdict);
+        verdict == Verdict::Followed
+    }
+
+    /// Whether to read the file at `path`.
+    pub fn reads(&mut self, path: &Path) -> bool {
+        if !path.is_symlink() {
+            return true;
+        }
+        let verdict = self.judge_link(path).map_or_else(|v| v, |_| Verdict::Followed);
+        self.record(path, verdict);
+        verdict == Verdict::Followed
+    }
+
+    /// The resolved target of a link the policy allows, or why it's left out.
+    fn judge_link(&self, path: &Path) -> Result<PathBuf, Verdict> {
+        if self.policy == SymlinkPolicy::Skip {
+            return Err(Verdict::Skipped);
+        }
+        let target = path.canonicalize().map_err(|_| Verdict::Broken)?;
+        if target.starts_with(&self.canonical_root) {
+            return Err(Verdict::InsideVault);
+        }
+        Ok(target)
+    }
+
+    fn record(&mut self, path: &Path, verdict: Verdict) {
+        let target = path.canonicalize().or_else(|_| std::fs::read_link(path)).unwrap_or_default();
+        let rel = path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().to_string();
+        if !self.links.iter().any(|l| l.path == rel) {
+            self.links.push(Symlink { path: rel, target, verdict });
+        }
+    }
+}
+
+/// The immediate subfolders of `dir` a vault walk would enter, sorted. For listings one or
+/// two levels deep (domains, projects) that don't keep a `Walk` of their own.
+pub fn subdirs(vault_root: &Path, dir: &Path, policy: SymlinkPolicy) -> Vec<PathBuf> {
+    let mut walk = Walk::new(vault_root, policy);
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir).into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs.retain(|p| walk.enters(p));
+    dirs
+}
+
+/// Every symlink under the vault and what walks do with it, skipping excluded names.
+pub fn scan(vault_root: &Path, exclude: &[String], policy: SymlinkPolicy) -> Vec<Symlink> {
+    let mut walk = Walk::new(vault_root, policy);
+    let mut stack = vec![vault_root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir).into_iter().flatten().flatten().map(|e| e.path()).collect();
+        paths.sort();
+        let mut entered = Vec::new();
+        for path in paths {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if exclude.iter().any(|e| e == name) {
+                continue;
+            }
+            if walk.enters(&path) {
+                entered.push(path);
+            } else if !path.is_dir() {
+                walk.reads(&path);
+            }
+        }
+        stack.extend(entered.into_iter().rev());
+    }
+    let mut links = walk.links;
+    links.sort_by(|a, b| a.path.cmp(&b.path));
+    links
+}
+
+/// Where a watcher event at `path` belongs in the vault: the path walks reach it by.
+/// Watchers report a linked folder's files under its target, or under whichever of two
+/// paths to the same folder they registered last. `followed` pairs each followed link's
+/// resolved target with the link. None when walks don't reach the file.
+pub fn vault_path(vault_root: &Path, followed: &[Symlink], path: &Path) -> Option<PathBuf> {
+    let canonical_root = vault_root.canonicalize().unwrap_or_else(|_| vault_root.to_path_buf());
+    // The file itself may be gone, so resolve its folder
+    let (parent, name) = (path.parent()?, path.file_name()?);
+    let resolved = parent.canonicalize().map(|p| p.join(name)).unwrap_or_else(|_| path.to_path_buf());
+    if let Ok(rel) = resolved.strip_prefix(&canonical_root) {
+        return Some(vault_root.join(rel));
+    }
+    if let Ok(rel) = resolved.strip_prefix(vault_root) {
+        return Some(vault_root.join(rel));
+    }
+    followed.iter()
+        .filter(|l| l.verdict == Verdict::Followed)
+        .find_map(|l| resolved.strip_prefix(&l.target).ok().map(|rel| vault_root.join(&l.path).join(rel)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn links_out_are_followed_once_and_links_in_are_left_out() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (vault, docs) = (tmp.path().join("vault"), tmp.path().join("repo/docs"));
+        std::fs::create_dir_all(vault.join("work/api")).unwrap();
+        std::fs::create_dir_all(&docs).unwrap();
+        symlink(&docs, vault.join("work/docs")).unwrap();
+        symlink(&docs, vault.join("work/docs-again")).unwrap();
+        symlink(vault.join("work/api"), vault.join("work/api-alias")).unwrap();
+        symlink(&docs, docs.join("loop")).unwrap();
+        symlink(tmp.path().join("missing"), vault.join("work/gone")).unwrap();
+
+        let links = scan(&vault, &[], SymlinkPolicy::Follow);
+        let verdicts: Vec<(&str, Verdict)> = links.iter().map(|l| (l.path.as_str(), l.verdict)).collect();
+        assert_eq!(verdicts, [
+            ("work/api-alias", Verdict::InsideVault),
+            ("work/docs", Verdict::Followed),
+            ("work/docs-again", Verdict::AlreadyWalked),
+            ("work/docs/loop", Verdict::AlreadyWalked),
+            ("work/gone", Verdict::Broken),
+        ]);
+        let names = |policy| subdirs(&vault, &vault.join("work"), policy).iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names(SymlinkPolicy::Follow), ["api", "docs"]);
+        assert_eq!(names(SymlinkPolicy::Skip), ["api"]);
+        assert!(scan(&vault, &[], SymlinkPolicy::Skip).iter().all(|l| l.verdict == Verdict::Skipped));
+
+        // Events under a target or an alias map back to the walked path
+        assert_eq!(vault_path(&vault, &links, &docs.join("guide.md")), Some(vault.join("work/docs/guide.md")));
+        assert_eq!(vault_path(&vault, &links, &vault.join("work/api-alias/notes.md")), Some(vault.join("work/api/notes.md")));
+        assert_eq!(vault_path(&vault, &links, &tmp.path().join("elsewhere.md")), None);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+/// Variable name → value for `render`.
+pub type Vars = BTreeMap<String, String>;
+
+/// Minimal `{{name}}` substitution for generated vault files: seed templates, domain rollups,
+/// and digests.
+///
+/// - `{{ name }}` is replaced with its value; spaces inside the braces are ignored.
+/// - Names without a value are left as written, so gaps stay visible in the note.
+/// - A line whose placeholders all render empty is dropped, so optional lines such as
+///   `- Repo: {{git_remote}}` disappear when there is nothing to show.
+pub fn render(template: &str, vars: &Vars) -> String {
+    let mut out = String::with_capacity(template.len());
+    for line in template.split_inclusive('\n') {
+        let mut rendered = String::with_capacity(line.len());
+        let mut placeholders = 0;
+        let mut all_empty = true;
+        let mut rest = line;
+        while let Some((before, name, after)) = next_placeholder(rest) {
+            rendered.push_str(before);
+            placeholders += 1;
+            match vars.get(name) {
+                Some(value) => {
+                    all_empty &= value.is_empty();
+                    rendered.push_str(value);
+                }
+                None => {
+                    all_empty = false;
+                    rendered.push_str(&rest[before.len()..rest.len() - after.len()]);
+                }
+            }
+            rest = after;
+        }
+        rendered.push_str(rest);
+        if placeholders > 0 && all_empty {
+            continue;
+        }
+        out.push_str(&rendered);
+    }
+    out
+}
+
+/// Names referenced by a template, in first-use order.
+pub fn variables(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some((_, name, after)) = next_placeholder(rest) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = after;
+    }
+    names
+}
+
+/// Parse a `name=value` pair from the command line.
+pub fn parse_var(pair: &str) -> Result<(String, String), String> {
+    let (name, value) = pair.split_once('=').ok_or_else(|| format!("expected name=value, got '{pair}'"))?;
+    let name = name.trim();
+    if !is_name(name) {
+        return Err(format!("invalid variable name '{name}' (use letters, digits, and underscores)"));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Split at the next well-formed `{{name}}`: (text before, name, text after).
+fn next_placeholder(s: &str) -> Option<(&str, &str, &str)> {
+    let mut search_from = 0;
+    loop {
+        let start = search_from + s[search_from..].find("{{")?;
+        let close = s[start + 2..].find("}}")?;
+        let name = s[start + 2..start + 2 + close].trim();
+        if is_name(name) {
+            return Some((&s[..start], name, &s[start + 2 + close + 2..]));
+        }
+        search_from = start + 2;
+    }
+}
+
+fn is_name(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vars {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn substitutes_and_keeps_unknown_names() {
+        let out = render("# {{ title }} for {{client}}\n{{ not a var }}\n", &vars(&[("title", "My Project")]));
+        assert_eq!(out, "# My Project for {{client}}\n{{ not a var }}\n");
+        assert_eq!(variables("{{a}} {{b}} {{a}} {{ c }} {{x y}}"), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn drops_lines_whose_placeholders_are_all_empty() {
+        let template = "## Links\n- Repo: {{git_remote}}\n- Client: {{client}}\ntext\n";
+        let out = render(template, &vars(&[("git_remote", ""), ("client", "Acme")]));
+        assert_eq!(out, "## Links\n- Client: Acme\ntext\n");
+    }
+
+    #[test]
+    fn parses_command_line_vars() {
+        assert_eq!(parse_var("client=Acme Corp").unwrap(), ("client".to_string(), "Acme Corp".to_string()));
+        assert_eq!(parse_var("url=a=b").unwrap().1, "a=b");
+        assert!(parse_var("client").is_err());
+        assert!(parse_var("bad name=x").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// One end of a diff: a day, a moment, or a history entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Point {
+    /// Everything dated up to this key: `YYYY-MM-DD` covers the whole day,
+    /// `YYYY-MM-DD HH:MM` stops at that minute.
+    Date(String),
+    /// The Nth history entry, oldest first from 1; negative counts back from the latest.
+    Entry(i64),
+}
+
+impl Point {
+    /// `2026-03-01`, `2026-03-01 14:30`, `#3` (third entry), or `#-1` (latest entry).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if let Some(n) = s.strip_prefix('#') {
+            return match n.parse::<i64>() {
+                Ok(0) | Err(_) => Err(format!("'{s}' is not a history entry ref: use #1 for the first entry or #-1 for the latest")),
+                Ok(n) => Ok(Self::Entry(n)),
+            };
+        }
+        let day_ok = s.get(..10).is_some_and(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok());
+        let rest = s.get(10..).unwrap_or("");
+        let time_ok = rest.is_empty() || chrono::NaiveTime::parse_from_str(rest.trim_start_matches([' ', 'T']), "%H:%M").is_ok();
+        if day_ok && time_ok {
+            Ok(Self::Date(s.replacen('T', " ", 1)))
+        } else {
+            Err(format!("'{s}' is not a date (YYYY-MM-DD or YYYY-MM-DD HH:MM) or history entry ref (#N)"))
+        }
+    }
+}
+
+/// A project's state as of a point, rebuilt from the syncs recorded in its history.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Snapshot {
+    /// The point as given.
+    pub at: String,
+    /// Date of the last history entry at or before the point; empty before the first.
+    pub as_of: String,
+    /// 1-based number of that entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<usize>,
+    pub status: String,
+    pub focus: String,
+    pub next_action: String,
+}
+
+/// A state field that differs between the two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+/// A dated record added between the two points.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Added {
+    pub date: String,
+    pub title: String,
+}
+
+/// What changed in a project between two points.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectDiff {
+    pub project: String,
+    pub from: Snapshot,
+    pub to: Snapshot,
+    pub changes: Vec<FieldChange>,
+    pub history: Vec<Added>,
+    pub decisions: Vec<Added>,
+    pub lessons: Vec<Added>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Entry {
+    date: String,
+    title: String,
+    status: String,
+    focus: String,
+    next_action: String,
+    source: String,
+}
+
+/// Diff `<domain>/<project>` between `from` and `to` using its history (archive included),
+/// decisions.md, and lessons.jsonl. Decisions are dated by day, so a decision made on the
+/// day of an entry ref counts as before it.
+pub fn diff(project_dir: &Path, project: &str, from: &Point, to: &Point) -> Result<ProjectDiff, String> {
+    let archive = crate::vault::compress::history_archive(project_dir);
+    // With the archive read, compact's monthly summaries would repeat its entries
+    let mut entries: Vec<Entry> = archive.iter()
+        .flat_map(|a| crate::vault::jsonl::read_entries(a))
+        .chain(crate::vault::jsonl::read_entries::<Entry>(&project_dir.join("history.jsonl")).into_iter()
+            .filter(|e| archive.is_none() || e.source != crate::vault::compress::ROLLUP_SOURCE))
+        .collect();
+    // Stable, so same-minute entries keep their file order
+    entries.sort_by(|a, b| a.date.cmp(&b.date));
+    if entries.is_empty() {
+        return Err(format!("{project} has no history entries to rebuild its state from"));
+    }
+
+    let (from_key, from_count) = resolve(&entries, from)?;
+    let (to_key, to_count) = resolve(&entries, to)?;
+    if from_count > to_count {
+        return Err("'from' is after 'to'".to_string());
+    }
+    let between = |date: &str| date > from_key.as_str() && date <= to_key.as_str();
+
+    let from = snapshot(&entries[..from_count], from);
+    let to = snapshot(&entries[..to_count], to);
+    let changes = [("status", &from.status, &to.status), ("focus", &from.focus, &to.focus), ("next_action", &from.next_action, &to.next_action)]
+        .into_iter()
+        .filter(|(_, a, b)| a != b)
+        .map(|(field, a, b)| FieldChange { field, from: a.clone(), to: b.clone() })
+        .collect();
+
+    let history = entries[from_count..to_count].iter()
+        .map(|e| Added { date: e.date.clone(), title: e.title.clone() })
+        .collect();
+    let mut decisions: Vec<Added> = std::fs::read_to_string(project_dir.join("decisions.md")).ok()
+        .map(|c| crate::mcp::server::parse_decisions(&c))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(date, _, _)| between(date))
+        .map(|(date, title, _)| Added { date, title })
+        .collect();
+    decisions.sort_by(|a, b| a.date.cmp(&b.date));
+    let lessons = crate::vault::jsonl::read_entries::<Entry>(&project_dir.join("lessons.jsonl")).into_iter()
+        .filter(|e| between(&e.date))
+        .map(|e| Added { date: e.date, title: e.title })
+        .collect();
+
+    Ok(ProjectDiff { project: project.to_string(), from, to, changes, history, decisions, lessons })
+}
+
+/// The date key a point stands for and how many entries fall at or before it.
+fn resolve(entries: &[Entry], point: &Point) -> Result<(String, usize), String> {
+    match point {
+        Point::Date(date) => {
+            // `~` sorts after the digits and spaces of any time on that day
+            let key = if date.len() == 10 { format!("{date}~") } else { date.clone() };
+            let count = entries.iter().take_while(|e| e.date <= key).count();
+            Ok((key, count))
+        }
+        Point::Entry(n) => {
+            let len = entries.len() as i64;
+            let count = if *n > 0 { *n } else { len + 1 + n };
+            if count < 1 || count > len {
+                return Err(format!("history entry #{n} doesn't exist; this project has {len}"));
+            }
+            Ok((entries[count as usize - 1].date.clone(), count as usize))
+        }
+    }
+}
+
+/// Fold syncs up to a point: each field keeps its last non-empty value.
+fn snapshot(entries: &[Entry], point: &Point) -> Snapshot {
+    let mut snap = Snapshot {
+        at: match point {
+            Point::Date(d) => d.clone(),
+            Point::Entry(n) => format!("#{n}"),
+        },
+        entry: (!entries.is_empty()).then_some(entries.len()),
+        ..Default::default()
+    };
+    for e in entries {
+        snap.as_of = e.date.clone();
+        for (field, value) in [(&mut snap.status, &e.status), (&mut snap.focus, &e.focus), (&mut snap.next_action, &e.next_action)] {
+            if !value.is_empty() {
+                *field = value.clone();
+            }
+        }
+    }
+    snap
+}
+
+/// The diff as markdown, for the CLI.
+pub fn render_markdown(d: &ProjectDiff) -> String {
+    let label = |s: &Snapshot| {
+        if s.as_of.is_empty() {
+            format!("{} (before any history)", s.at)
+        } else {
+            format!("{} (as of {})", s.at, s.as_of)
+        }
+    };
+    let mut out = format!("# {}: {} → {}\n", d.project, label(&d.from), label(&d.to));
+
+    out.push_str("\n## State\n\n");
+    if d.changes.is_empty() {
+        out.push_str("No change to status, focus, or next action.\n");
+    }
+    for c in &d.changes {
+        let show = |v: &str| if v.is_empty() { "(none)".to_string() } else { v.to_string() };
+        out.push_str(&format!("- **{}**: {} → {}\n", c.field, show(&c.from), show(&c.to)));
+    }
+    for (heading, items) in [("History", &d.history), ("Decisions", &d.decisions), ("Lessons", &d.lessons)] {
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n## {heading} ({})\n\n", items.len()));
+        for item in items {
+            out.push_str(&format!("- {} — {}\n", item.date, item.title));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn project() -> tempfile::TempDir {
+        let tmp = tempdir().unwrap();
+        let lines = [
+            r#"{"_schema": "history", "_version": "1.0"}"#,
+            r#"{"date":"2026-03-01 09:00","title":"Kickoff","status":"active","focus":"Schema","next_action":"Write migrations"}"#,
+            r#"{"date":"2026-03-05 10:00","title":"Migrations done","status":"active","focus":"API","next_action":"Auth endpoints"}"#,
+            r#"{"date":"2026-03-09 16:00","title":"Blocked on SSO","status":"blocked","focus":"API","next_action":"Wait for IdP"}"#,
+        ];
+        std::fs::write(tmp.path().join("history.jsonl"), lines.join("\n") + "\n").unwrap();
+        std::fs::write(tmp.path().join("decisions.md"), "# api Decisions\n\n## 2026-03-08 — Use OIDC\n\nStandard.\n\n---\n\n## 2026-03-02 — Postgres\n\nFine.\n\n---\n\n").unwrap();
+        std::fs::write(tmp.path().join("lessons.jsonl"), "{\"_schema\": \"lessons\"}\n{\"date\":\"2026-03-06 12:00\",\"title\":\"Pin the IdP sandbox\"}\n").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn diff_between_dates_and_entries() {
+        let tmp = project();
+        let d = diff(tmp.path(), "work/api", &Point::parse("2026-03-03").unwrap(), &Point::parse("#-1").unwrap()).unwrap();
+        assert_eq!((d.from.as_of.as_str(), d.from.entry, d.to.entry), ("2026-03-01 09:00", Some(1), Some(3)));
+        let fields: Vec<&str> = d.changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, vec!["status", "focus", "next_action"]);
+        assert_eq!(d.changes[0].to, "blocked");
+        let titles = |items: &[Added]| items.iter().map(|a| a.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&d.history), vec!["Migrations done", "Blocked on SSO"]);
+        assert_eq!(titles(&d.decisions), vec!["Use OIDC"]);
+        assert_eq!(titles(&d.lessons), vec!["Pin the IdP sandbox"]);
+
+        let md = render_markdown(&d);
+        assert!(md.starts_with("# work/api: 2026-03-03 (as of 2026-03-01 09:00) → #-1 (as of 2026-03-09 16:00)\n"), "{md}");
+        assert!(md.contains("- **status**: active → blocked\n"));
+        assert!(md.contains("## Decisions (1)\n\n- 2026-03-08 — Use OIDC\n"));
+
+        let d = diff(tmp.path(), "work/api", &Point::parse("2026-02-01").unwrap(), &Point::Entry(1)).unwrap();
+        assert!(render_markdown(&d).contains("2026-02-01 (before any history)"));
+        assert_eq!(d.changes[0].from, "");
+    }
+
+    #[test]
+    fn bad_points_are_rejected() {
+        let tmp = project();
+        assert!(Point::parse("#0").is_err());
+        assert!(Point::parse("last week").is_err());
+        assert_eq!(Point::parse("2026-03-05T10:00").unwrap(), Point::Date("2026-03-05 10:00".to_string()));
+        assert!(diff(tmp.path(), "work/api", &Point::Entry(9), &Point::Entry(-1)).unwrap_err().contains("#9"));
+        assert!(diff(tmp.path(), "work/api", &Point::Entry(3), &Point::Entry(1)).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// The type of a vault file.
@@ -13,6 +14,9 @@ pub enum VaultType {
     Thread,
     Domain,
     History,
+    /// Written by wardwell for its own use (lists, lessons, generated rollups).
+    /// Indexed, but left out of search unless asked for by type.
+    Machine,
     #[default]
     Reference,
 }
@@ -30,6 +34,7 @@ impl<'de> Deserialize<'de> for VaultType {
             "thread" => VaultType::Thread,
             "domain" => VaultType::Domain,
             "history" => VaultType::History,
+            "machine" => VaultType::Machine,
             "reference" => VaultType::Reference,
             _ => VaultType::Reference, // unknown types → Reference
         })
@@ -45,13 +50,14 @@ impl std::fmt::Display for VaultType {
             Self::Thread => write!(f, "thread"),
             Self::Domain => write!(f, "domain"),
             Self::History => write!(f, "history"),
+            Self::Machine => write!(f, "machine"),
             Self::Reference => write!(f, "reference"),
         }
     }
 }
 
 /// Status of a vault entry.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Status {
     Active,
@@ -77,6 +83,43 @@ impl std::fmt::Display for Status {
     }
 }
 
+impl Status {
+    pub const ALL: [Status; 7] = [
+        Self::Active, Self::Completed, Self::Blocked, Self::Paused, Self::Resolved, Self::Abandoned, Self::Superseded,
+    ];
+
+    /// Canonical status for a canonical name or one of its common variants ("done", "wip",
+    /// "on hold", "cancelled", ...). Case, surrounding space, and `-`/`_`/space are ignored.
+    pub fn parse(s: &str) -> Option<Status> {
+        let key = s.trim().to_lowercase().replace(['-', ' '], "_");
+        match key.as_str() {
+            "active" | "in_progress" | "wip" | "ongoing" | "started" | "open" => Some(Self::Active),
+            "completed" | "complete" | "done" | "finished" | "shipped" => Some(Self::Completed),
+            "blocked" | "stuck" | "waiting" => Some(Self::Blocked),
+            "paused" | "on_hold" | "hold" | "parked" => Some(Self::Paused),
+            "resolved" | "fixed" | "closed" => Some(Self::Resolved),
+            "abandoned" | "cancelled" | "canceled" | "dropped" | "wontfix" => Some(Self::Abandoned),
+            "superseded" | "replaced" => Some(Self::Superseded),
+            _ => None,
+        }
+    }
+
+    /// Like `parse`, but also accepts the vault's extra states from `statuses:` in config.yml.
+    pub fn resolve(s: &str, extra: &BTreeMap<String, Status>) -> Option<Status> {
+        Self::parse(s).or_else(|| extra.get(s.trim().to_lowercase().as_str()).copied())
+    }
+
+    /// Finished work: counted as completed by retrospectives, never stale.
+    pub fn is_done(self) -> bool {
+        matches!(self, Self::Completed | Self::Resolved)
+    }
+
+    /// Still expected to move: in the orchestrate queue and tracked for neglect.
+    pub fn is_open(self) -> bool {
+        matches!(self, Self::Active | Self::Blocked)
+    }
+}
+
 /// Confidence level of a vault entry.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -146,22 +189,13 @@ where
     }
 }
 
-/// Lenient status deserializer: unknown values become None instead of erroring.
+/// Lenient status deserializer: common variants are normalized, unknown values become None instead of erroring.
 fn deserialize_lenient_status<'de, D>(deserializer: D) -> Result<Option<Status>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let opt: Option<String> = Option::deserialize(deserializer)?;
-    Ok(opt.and_then(|s| match s.as_str() {
-        "active" => Some(Status::Active),
-        "resolved" => Some(Status::Resolved),
-        "completed" => Some(Status::Completed),
-        "blocked" => Some(Status::Blocked),
-        "paused" => Some(Status::Paused),
-        "abandoned" => Some(Status::Abandoned),
-        "superseded" => Some(Status::Superseded),
-        _ => None,
-    }))
+    Ok(opt.as_deref().and_then(Status::parse))
 }
 
 /// Lenient confidence deserializer: unknown values become None instead of erroring.
@@ -0,0 +1,190 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+//! Index → summarize → `context`, through the real binary, with `tests/support/fake_claude.rs`
+//! standing in for the claude CLI. `wardwell summarize` runs the same index and summarize
+//! steps as the daemon loop; `wardwell serve` answers the context action. The same harness
+//! checks that `serve --read-only` turns writes away, and that probe servers stay out of
+//! safe mode's startup count.
+
+use serde_json::Value;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const WARDWELL: &str = env!("CARGO_BIN_EXE_wardwell");
+const FAKE_CLAUDE: &str = env!("CARGO_BIN_EXE_fake_claude");
+/// Where the fixture sessions claim to have run: `/e2e/work/api`, in the `work` domain.
+const PROJECT_DIR: &str = "-e2e-work-api";
+
+struct Sandbox {
+    _tmp: tempfile::TempDir,
+    config_dir: PathBuf,
+    vault: PathBuf,
+    /// PATH with the fake claude first.
+    path: String,
+}
+
+impl Sandbox {
+    /// A vault with `work/api`, a session source holding `fixtures`, and a `claude` on PATH
+    /// that is the fake.
+    fn new(fixtures: &[&str]) -> Self {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_dir = tmp.path().join("config");
+        let vault = tmp.path().join("vault");
+        let sessions = tmp.path().join("projects").join(PROJECT_DIR);
+        let bin = tmp.path().join("bin");
+        for dir in [&config_dir, &sessions, &bin, &vault.join("domains")] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        wardwell::vault::seed::write_project_seed(&vault.join("work/api"), "work", "api").unwrap();
+        std::fs::write(
+            vault.join("domains/work.md"),
+            "---\ntype: domain\ndomain: work\nconfidence: confirmed\nstatus: active\n---\n\n## Paths\n- /e2e/work/*\n",
+        ).unwrap();
+        for name in fixtures {
+            std::fs::copy(
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sessions").join(format!("{name}.jsonl")),
+                sessions.join(format!("{name}.jsonl")),
+            ).unwrap();
+        }
+        let config = serde_json::json!({
+            "vault_path": vault,
+            "session_sources": [tmp.path().join("projects")],
+            "ai": { "requests_per_minute": 0 },
+        });
+        std::fs::write(config_dir.join("config.yml"), config.to_string()).unwrap();
+
+        std::os::unix::fs::symlink(FAKE_CLAUDE, bin.join("claude")).unwrap();
+        let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default());
+        Self { _tmp: tmp, config_dir, vault, path }
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(WARDWELL);
+        command.args(args)
+            .env("WARDWELL_CONFIG_DIR", &self.config_dir)
+            .env("PATH", &self.path)
+            .env_remove("WARDWELL_DOMAIN");
+        command
+    }
+
+    /// `wardwell summarize`, returning its stdout.
+    fn summarize(&self) -> String {
+        let output = self.command(&["summarize"]).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    /// One `wardwell_search` call against `wardwell serve --no-background`, parsed.
+    fn search(&self, args: Value) -> Value {
+        let (_, result) = self.call(&[], "wardwell_search", args);
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+    }
+
+    /// The `tools/list` result and one `tools/call` result from `wardwell serve --no-background`
+    /// with `serve_args`.
+    fn call(&self, serve_args: &[&str], tool: &str, args: Value) -> (Value, Value) {
+        let mut child = self.command(&[&["serve", "--no-background"], serve_args].concat())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let mut lines = std::io::BufReader::new(child.stdout.take().unwrap()).lines();
+        let mut request = |stdin: &mut std::process::ChildStdin, id: u64, method: &str, params: Value| -> Value {
+            writeln!(stdin, "{}", serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).unwrap();
+            loop {
+                let message: Value = serde_json::from_str(&lines.next().expect("server exited").unwrap()).unwrap();
+                if message["id"] == id {
+                    return message["result"].clone();
+                }
+            }
+        };
+        request(&mut stdin, 1, "initialize", serde_json::json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": { "name": "summarize-e2e", "version": "0" },
+        }));
+        writeln!(stdin, "{}", serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).unwrap();
+        let tools = request(&mut stdin, 2, "tools/list", serde_json::json!({}));
+        let result = request(&mut stdin, 3, "tools/call", serde_json::json!({ "name": tool, "arguments": args }));
+        let _ = child.kill();
+        let _ = child.wait();
+        (tools, result)
+    }
+}
+
+#[test]
+fn summarized_sessions_reach_context_and_queue_their_decisions() {
+    let sandbox = Sandbox::new(&["decisive"]);
+
+    let out = sandbox.summarize();
+    assert!(out.contains("Summarized 1 session(s) (0 skipped, 0 error(s))."), "{out}");
+    assert!(out.contains("Queued 1 decision(s) for approval"), "{out}");
+    let summary = std::fs::read_to_string(sandbox.config_dir.join("summaries/decisive.md")).unwrap();
+    assert!(summary.contains("domain: work\n"), "{summary}");
+    assert!(summary.contains("Fake summary of the session at `/e2e/work/api`."), "{summary}");
+
+    let queued = wardwell::daemon::decisions::pending(&sandbox.vault.join("work/api"));
+    assert_eq!(queued.len(), 1);
+    assert_eq!((queued[0].title.as_str(), queued[0].session_id.as_str()), ("Retry in the HTTP client, never in handlers", "decisive"));
+
+    let context = sandbox.search(serde_json::json!({ "action": "context", "session_id": "decisive" }));
+    assert_eq!((context["domain"].as_str(), context["project"].as_str()), (Some("work"), Some("api")));
+    assert!(context["summary"].as_str().unwrap().contains("## Decisions\n- Retry in the HTTP client"), "{context}");
+    assert_eq!(context["message_count"], 7);
+    assert!(context["vault_state"].is_object(), "{context}");
+
+    // A second run finds nothing new to summarize
+    assert!(sandbox.summarize().contains("No matching sessions."));
+}
+
+#[test]
+fn a_failed_summary_is_reported_and_nothing_is_written() {
+    let sandbox = Sandbox::new(&["failing"]);
+
+    let out = sandbox.summarize();
+    assert!(out.contains("Summarized 0 session(s) (0 skipped, 1 error(s))."), "{out}");
+    assert!(!sandbox.config_dir.join("summaries/failing.md").exists());
+
+    // context asks the provider itself, and reports the failure next to the rest
+    let context = sandbox.search(serde_json::json!({ "action": "context", "session_id": "failing" }));
+    assert!(context["summary"].is_null(), "{context}");
+    assert!(context["summary_error"].as_str().unwrap().contains("simulated failure"), "{context}");
+    assert_eq!(context["project"], "api");
+}
+
+#[test]
+fn a_read_only_server_hides_and_refuses_writes() {
+    let sandbox = Sandbox::new(&[]);
+    let write = serde_json::json!({ "action": "append_history", "domain": "work", "project": "api", "title": "Nope", "body": "Should not land" });
+
+    let (tools, result) = sandbox.call(&["--read-only"], "wardwell_write", write.clone());
+    let names: Vec<&str> = tools["tools"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert!(names.contains(&"wardwell_search") && !names.contains(&"wardwell_write") && !names.contains(&"wardwell_clipboard"), "{names:?}");
+    assert_eq!(result["isError"], true, "{result}");
+    let error: Value = serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("read-only"), "{error}");
+    assert_eq!(error["read_only"], true, "{error}");
+    assert!(!sandbox.vault.join("work/api/history.jsonl").exists());
+
+    // Without the flag the same call writes
+    let (tools, _) = sandbox.call(&[], "wardwell_write", write);
+    assert!(tools["tools"].as_array().unwrap().iter().any(|t| t["name"] == "wardwell_write"));
+    assert!(std::fs::read_to_string(sandbox.vault.join("work/api/history.jsonl")).unwrap().contains("Should not land"));
+}
+
+#[test]
+fn probe_servers_leave_the_startup_record_alone() {
+    let sandbox = Sandbox::new(&[]);
+    let record = sandbox.config_dir.join("startup.json");
+    std::fs::write(&record, r#"{"consecutive_failures": 2}"#).unwrap();
+
+    // `--no-background` is what doctor --watch and selftest launch and then kill
+    for _ in 0..3 {
+        sandbox.search(serde_json::json!({ "action": "domains" }));
+    }
+    let after: Value = serde_json::from_str(&std::fs::read_to_string(&record).unwrap()).unwrap();
+    assert_eq!(after["consecutive_failures"], 2, "{after}");
+}
@@ -0,0 +1,58 @@
+//! A stand-in for `claude -p --output-format json`, for the end-to-end tests in
+//! `tests/summarize_e2e.rs`. They link it into a `bin/claude` put first on PATH.
+//!
+//! It reads the prompt on stdin and answers with a summary built from the conversation: a
+//! `## Summary` line naming the project, and a `## Decisions` bullet for each `Decision:` a
+//! message states. A conversation containing `FAKE_CLAUDE_FAIL` makes it exit 1 instead.
+//!
+//! It only builds with the `e2e` feature, so `cargo install` leaves it out.
+
+use std::io::Read;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.iter().any(|a| a == "-p") {
+        return;
+    }
+    if !args.windows(2).any(|w| w[0] == "--output-format" && w[1] == "json") {
+        eprintln!("fake claude: expected --output-format json, got {args:?}");
+        std::process::exit(2);
+    }
+
+    let mut prompt = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut prompt) {
+        eprintln!("fake claude: could not read the prompt: {e}");
+        std::process::exit(2);
+    }
+    // The conversation comes after the last separator
+    let conversation = prompt.rsplit_once("\n---\n").map_or(prompt.as_str(), |(_, c)| c);
+    if conversation.contains("FAKE_CLAUDE_FAIL") {
+        eprintln!("fake claude: simulated failure");
+        std::process::exit(1);
+    }
+
+    let project = prompt.split_once("the project at `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map_or("an unknown project", |(p, _)| p);
+    let decisions: Vec<&str> = conversation.split("Decision:")
+        .skip(1)
+        .filter_map(|rest| rest.lines().next())
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .collect();
+
+    let mut result = format!("## Summary\nFake summary of the session at `{project}`.\n");
+    if !decisions.is_empty() {
+        result.push_str("\n## Decisions\n");
+        for d in decisions {
+            result.push_str(&format!("- {d}\n"));
+        }
+    }
+    let reply = serde_json::json!({
+        "type": "result",
+        "subtype": "success",
+        "is_error": false,
+        "result": result,
+    });
+    println!("{reply}");
+}